@@ -54,6 +54,48 @@ impl FromStr for ThinkingLevel {
     }
 }
 
+/// Keymap used when editing the prompt.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditingMode {
+    /// Plain insert-mode editing.
+    #[default]
+    Standard,
+    /// Modal Vim bindings.
+    Vim,
+    /// Emacs bindings.
+    Emacs,
+}
+
+impl fmt::Display for EditingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Standard => "standard",
+            Self::Vim => "vim",
+            Self::Emacs => "emacs",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Error returned when parsing an invalid editing mode string.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("invalid editing mode: '{0}' (expected standard, vim, emacs)")]
+pub struct ParseEditingModeError(String);
+
+impl FromStr for EditingMode {
+    type Err = ParseEditingModeError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "standard" | "default" => Ok(Self::Standard),
+            "vim" | "vi" => Ok(Self::Vim),
+            "emacs" => Ok(Self::Emacs),
+            other => Err(ParseEditingModeError(other.to_string())),
+        }
+    }
+}
+
 /// General agent settings that apply across all sessions.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Settings {
@@ -75,6 +117,9 @@ pub struct Settings {
     /// Maximum tokens per LLM response.
     #[serde(default)]
     pub max_tokens: Option<u32>,
+    /// Keymap for the prompt.
+    #[serde(default)]
+    pub editing_mode: EditingMode,
 }
 
 /// Load settings from a JSON file.
@@ -137,6 +182,11 @@ pub fn merge(base: &Settings, overlay: &Settings) -> Settings {
         },
         max_turns: overlay.max_turns.or(base.max_turns),
         max_tokens: overlay.max_tokens.or(base.max_tokens),
+        editing_mode: if overlay.editing_mode != EditingMode::Standard {
+            overlay.editing_mode
+        } else {
+            base.editing_mode
+        },
     }
 }
 
@@ -157,6 +207,7 @@ mod tests {
             enabled_models: vec!["claude-sonnet-4-5-20250929".into(), "gpt-4".into()],
             max_turns: Some(20),
             max_tokens: Some(8192),
+            editing_mode: EditingMode::Vim,
         };
 
         save(&settings, &path).unwrap();
@@ -171,6 +222,7 @@ mod tests {
         assert_eq!(loaded.enabled_models.len(), 2);
         assert_eq!(loaded.max_turns, Some(20));
         assert_eq!(loaded.max_tokens, Some(8192));
+        assert_eq!(loaded.editing_mode, EditingMode::Vim);
     }
 
     #[test]
@@ -195,6 +247,7 @@ mod tests {
             enabled_models: vec!["a".into()],
             max_turns: Some(10),
             max_tokens: Some(4096),
+            editing_mode: EditingMode::Vim,
         };
         let overlay = Settings {
             default_provider: Some("openai".into()),
@@ -203,6 +256,7 @@ mod tests {
             enabled_models: vec!["b".into(), "c".into()],
             max_turns: None,
             max_tokens: Some(8192),
+            editing_mode: EditingMode::Emacs,
         };
 
         let merged = merge(&base, &overlay);
@@ -214,6 +268,7 @@ mod tests {
         // overlay.max_turns is None, so base wins.
         assert_eq!(merged.max_turns, Some(10));
         assert_eq!(merged.max_tokens, Some(8192));
+        assert_eq!(merged.editing_mode, EditingMode::Emacs);
     }
 
     #[test]
@@ -225,6 +280,7 @@ mod tests {
             enabled_models: vec!["x".into()],
             max_turns: Some(5),
             max_tokens: Some(2048),
+            editing_mode: EditingMode::Vim,
         };
         let overlay = Settings::default();
 
@@ -236,6 +292,7 @@ mod tests {
         assert_eq!(merged.enabled_models, vec!["x"]);
         assert_eq!(merged.max_turns, Some(5));
         assert_eq!(merged.max_tokens, Some(2048));
+        assert_eq!(merged.editing_mode, EditingMode::Vim);
     }
 
     #[test]
@@ -295,4 +352,17 @@ mod tests {
         let err = "extreme".parse::<ThinkingLevel>().unwrap_err();
         assert!(err.to_string().contains("extreme"));
     }
+
+    #[test]
+    fn editing_mode_parse_and_serde() {
+        assert_eq!("vim".parse::<EditingMode>().unwrap(), EditingMode::Vim);
+        assert_eq!("Emacs".parse::<EditingMode>().unwrap(), EditingMode::Emacs);
+        assert!("nano".parse::<EditingMode>().is_err());
+        assert_eq!(EditingMode::Vim.to_string(), "vim");
+        let json = serde_json::to_string(&EditingMode::Emacs).unwrap();
+        assert_eq!(json, "\"emacs\"");
+        // Older settings files without the field still load.
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings.editing_mode, EditingMode::Standard);
+    }
 }
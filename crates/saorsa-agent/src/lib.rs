@@ -90,7 +90,9 @@ pub use config::{
     import::{ImportReport, import_all},
     models::{CustomModel, CustomProvider, ModelCost, ModelsConfig},
     paths::{ensure_config_dir, saorsa_config_dir},
-    settings::{ParseThinkingLevelError, Settings, ThinkingLevel},
+    settings::{
+        EditingMode, ParseEditingModeError, ParseThinkingLevelError, Settings, ThinkingLevel,
+    },
 };
 pub use context::{AgentsContext, ContextBuilder, ContextBundle, ContextDiscovery, SystemContext};
 pub use cost::{CostEntry, CostTracker};
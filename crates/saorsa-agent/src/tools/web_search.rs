@@ -206,10 +206,7 @@ fn url_decode(input: &str) -> String {
     let mut result = String::with_capacity(input.len());
     let mut chars = input.bytes();
 
-    loop {
-        let Some(b) = chars.next() else {
-            break;
-        };
+    while let Some(b) = chars.next() {
         if b == b'%' {
             let hi = chars.next();
             let lo = chars.next();
@@ -0,0 +1,103 @@
+//! App frame benchmarks — full repaints versus dirty-region updates on a
+//! large DOM.
+
+#![allow(missing_docs)]
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use saorsa_tui::app::{App, Dom, NodeRef, StyledLeaf};
+use saorsa_tui::terminal::TestBackend;
+use saorsa_tui::widget::{Container, Label};
+
+const COLUMNS: u16 = 20;
+const ROWS: u16 = 100;
+
+const TCSS: &str = "
+#root { display: flex; flex-direction: row; flex-wrap: wrap; }
+Label { width: 10; height: 1; }
+.hot { color: red; }
+";
+
+/// A 200x100 screen tiled with 2000 labels.
+fn dashboard() -> (App, TestBackend, Vec<NodeRef>) {
+    let mut backend = TestBackend::new(COLUMNS * 10, ROWS);
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    let labels: Vec<NodeRef> = (0..usize::from(COLUMNS * ROWS))
+        .map(|i| {
+            let label = dom.create(
+                "Label",
+                Box::new(StyledLeaf::new(Label::new(format!("cell {i}")))),
+            );
+            dom.append_child(root, label);
+            label
+        })
+        .collect();
+    let mut app = match App::from_tcss_string(&backend, dom, TCSS) {
+        Ok(app) => app,
+        Err(e) => unreachable!("invalid benchmark app: {e}"),
+    };
+    assert!(app.render_frame(&mut backend).is_ok());
+    (app, backend, labels)
+}
+
+/// Benchmark repainting every node, as every frame did before dirty tracking.
+fn benchmark_full_frame(c: &mut Criterion) {
+    let (mut app, mut backend, _) = dashboard();
+    c.bench_function("app_full_frame_2000_nodes", |b| {
+        b.iter(|| {
+            app.request_render();
+            assert!(app.render_frame(&mut backend).is_ok());
+            backend.clear_buffer();
+        })
+    });
+}
+
+/// Benchmark a frame where a single label's text changed.
+fn benchmark_single_node_update(c: &mut Criterion) {
+    let (mut app, mut backend, labels) = dashboard();
+    let target = labels[labels.len() / 2];
+    let mut tick = 0u64;
+    c.bench_function("app_single_node_update_2000_nodes", |b| {
+        b.iter(|| {
+            tick += 1;
+            if let Some(label) = app.dom_mut().downcast_widget_mut::<Label>(target) {
+                label.set_text(format!("tick {tick}"));
+            }
+            app.mark_node_dirty(target);
+            assert!(app.render_frame(&mut backend).is_ok());
+            backend.clear_buffer();
+        })
+    });
+}
+
+/// Benchmark a frame where a single label's class toggled.
+fn benchmark_single_node_restyle(c: &mut Criterion) {
+    let (mut app, mut backend, labels) = dashboard();
+    // The last label has no later siblings to restyle.
+    let target = labels[labels.len() - 1];
+    let mut hot = false;
+    c.bench_function("app_single_node_restyle_2000_nodes", |b| {
+        b.iter(|| {
+            hot = !hot;
+            if hot {
+                app.dom_mut().add_class(target, "hot");
+            } else {
+                app.dom_mut().remove_class(target, "hot");
+            }
+            app.mark_style_dirty(target);
+            assert!(app.render_frame(&mut backend).is_ok());
+            backend.clear_buffer();
+            black_box(app.last_frame_stats())
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    benchmark_full_frame,
+    benchmark_single_node_update,
+    benchmark_single_node_restyle
+);
+criterion_main!(benches);
@@ -9,6 +9,7 @@ use saorsa_tui::color::{Color, NamedColor};
 use saorsa_tui::geometry::Size;
 use saorsa_tui::segment::Segment;
 use saorsa_tui::style::Style;
+use saorsa_tui::widget::MarkdownRenderer;
 
 /// Benchmark ScreenBuffer creation and diff for 80x24 grid.
 fn benchmark_buffer_diff_80x24(c: &mut Criterion) {
@@ -106,11 +107,36 @@ fn benchmark_segment_rendering_1000(c: &mut Criterion) {
     });
 }
 
+/// Benchmark streaming a long markdown reply in small chunks, rendering
+/// after each one as the chat does.
+fn benchmark_markdown_streaming(c: &mut Criterion) {
+    let block = "## Section\n\nSome **bold** text with `code` and a [link](https://example.com) \
+        that wraps over a few lines of the terminal.\n\n- first item\n- second item\n\n\
+        ```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\n";
+    let text = block.repeat(50);
+    let chunks: Vec<&str> = text
+        .as_bytes()
+        .chunks(16)
+        .filter_map(|chunk| std::str::from_utf8(chunk).ok())
+        .collect();
+
+    c.bench_function("markdown_streaming", |b| {
+        b.iter(|| {
+            let mut renderer = MarkdownRenderer::new();
+            for chunk in &chunks {
+                renderer.push_str(chunk);
+                black_box(renderer.render_to_lines(80));
+            }
+        })
+    });
+}
+
 criterion_group!(
     benches,
     benchmark_buffer_diff_80x24,
     benchmark_buffer_diff_120x40,
     benchmark_buffer_diff_200x60,
-    benchmark_segment_rendering_1000
+    benchmark_segment_rendering_1000,
+    benchmark_markdown_streaming
 );
 criterion_main!(benches);
@@ -0,0 +1,632 @@
+//! Parsing text with ANSI escape sequences into styled [`Segment`]s.
+//!
+//! Subprocess output colours its text with SGR (`CSI … m`) sequences and
+//! redraws progress bars with carriage returns and cursor movement.
+//! [`AnsiParser`] interprets that output the way a terminal would, on a
+//! grid of cells that grows as text is written, and turns the result into
+//! lines of segments:
+//!
+//! - SGR colours (16, 256 and true colour) and attributes, including
+//!   underline shapes and colours
+//! - OSC 8 hyperlinks, carried in [`Style::link`]
+//! - `\r`, backspace and tabs, with later text overwriting earlier text
+//! - cursor movement (`CSI A`–`H`, `d`, `s`/`u`, `ESC 7`/`8`) and erasing
+//!   (`CSI J`, `CSI K`) relative to the start of the output
+//!
+//! Other escape sequences and control characters are dropped. Input may
+//! arrive in chunks that split escape sequences. Cursor movement cannot
+//! take the cursor below the lowest line written or past [`MAX_COLUMN`],
+//! and an OSC string missing its terminator ends at the next line, so
+//! malformed output cannot balloon the grid or swallow what follows.
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::cell::Cell;
+use crate::color::{Color, NamedColor};
+use crate::segment::Segment;
+use crate::style::{Style, UnderlineStyle};
+
+/// Columns between tab stops.
+const TAB_WIDTH: usize = 8;
+
+/// The furthest column cursor movement can reach; text may run further.
+pub const MAX_COLUMN: usize = 1024;
+
+/// Longest OSC string interpreted; longer ones are dropped.
+pub(crate) const MAX_OSC_LEN: usize = 4096;
+
+/// The 16 ANSI colours by SGR index.
+const ANSI_COLORS: [NamedColor; 16] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+];
+
+/// Parse text containing ANSI escape sequences into lines of styled
+/// segments, one line per `\n`. Styles carry over from line to line, as
+/// they do on a terminal.
+pub fn parse_ansi(text: &str) -> Vec<Vec<Segment>> {
+    let mut parser = AnsiParser::new();
+    parser.feed(text);
+    parser.lines()
+}
+
+/// Remove escape sequences from `text`, keeping what a terminal would
+/// show, one line per `\n`.
+pub fn strip_ansi(text: &str) -> String {
+    parse_ansi(text)
+        .iter()
+        .map(|line| line.iter().map(|s| s.text.as_str()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Where the parser is within an escape sequence.
+#[derive(Clone, Debug, Default)]
+enum State {
+    #[default]
+    Ground,
+    /// After `ESC`.
+    Escape,
+    /// In a CSI sequence, collecting parameters.
+    Csi(String),
+    /// In an OSC string, which stops growing past [`MAX_OSC_LEN`].
+    Osc(String),
+    /// After `ESC` inside an OSC string, expecting `\`.
+    OscEscape(String),
+    /// After `ESC (` and friends, expecting a character set.
+    Charset,
+}
+
+/// An incremental ANSI/VT parser that renders output into lines of cells.
+///
+/// ```
+/// use saorsa_tui::ansi::AnsiParser;
+///
+/// let mut parser = AnsiParser::new();
+/// parser.feed("\x1b[32mdownloading 10%");
+/// parser.feed("\r\x1b[Kdone\x1b[0m\n");
+/// let lines = parser.lines();
+/// assert_eq!(lines.len(), 1);
+/// assert_eq!(lines[0][0].text, "done");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AnsiParser {
+    /// Written lines; lines below the last written one are implied.
+    lines: Vec<Vec<Cell>>,
+    row: usize,
+    col: usize,
+    /// The lowest row output has reached, which bounds cursor movement.
+    bottom: usize,
+    /// Cursor saved by `ESC 7` or `CSI s`.
+    saved: (usize, usize),
+    style: Style,
+    state: State,
+}
+
+impl AnsiParser {
+    /// Create a parser with an empty output and default style.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interpret more output. Escape sequences may be split across calls.
+    pub fn feed(&mut self, text: &str) {
+        for ch in text.chars() {
+            self.advance(ch);
+        }
+    }
+
+    /// The output so far as lines of segments. A trailing newline ends
+    /// the last line without starting another.
+    pub fn lines(&self) -> Vec<Vec<Segment>> {
+        let count = self.lines.len().max(self.row).max(1);
+        (0..count)
+            .map(|row| {
+                self.lines
+                    .get(row)
+                    .map(|cells| segments(cells))
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// The style in effect for the next text.
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Discard the output, keeping the current style, and move the
+    /// cursor back to the start.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.row = 0;
+        self.col = 0;
+        self.bottom = 0;
+    }
+
+    fn advance(&mut self, ch: char) {
+        match std::mem::take(&mut self.state) {
+            State::Ground => self.ground(ch),
+            State::Escape => match ch {
+                '[' => self.state = State::Csi(String::new()),
+                ']' => self.state = State::Osc(String::new()),
+                '(' | ')' | '*' | '+' => self.state = State::Charset,
+                '7' => self.saved = (self.row, self.col),
+                '8' => (self.row, self.col) = self.saved,
+                'c' => {
+                    self.clear();
+                    self.style = Style::default();
+                }
+                _ => {}
+            },
+            State::Csi(mut params) => {
+                if ('@'..='~').contains(&ch) {
+                    self.csi(&params, ch);
+                } else if !ch.is_control() {
+                    params.push(ch);
+                    self.state = State::Csi(params);
+                }
+            }
+            State::Osc(mut data) => match ch {
+                '\x07' | '\u{9c}' => self.osc(&data),
+                '\x1b' => self.state = State::OscEscape(data),
+                // CAN and SUB cancel the string; a newline means its
+                // terminator went missing.
+                '\x18' | '\x1a' => {}
+                '\n' => self.ground(ch),
+                c => {
+                    if data.len() <= MAX_OSC_LEN {
+                        data.push(c);
+                    }
+                    self.state = State::Osc(data);
+                }
+            },
+            State::OscEscape(data) => {
+                self.osc(&data);
+                if ch != '\\' {
+                    self.advance(ch);
+                }
+            }
+            State::Charset => {}
+        }
+    }
+
+    fn ground(&mut self, ch: char) {
+        match ch {
+            '\x1b' => self.state = State::Escape,
+            '\n' => {
+                self.row += 1;
+                self.col = 0;
+                self.bottom = self.bottom.max(self.row);
+            }
+            '\r' => self.col = 0,
+            '\x08' => self.col = self.col.saturating_sub(1),
+            '\t' => {
+                self.col = (self.col / TAB_WIDTH + 1) * TAB_WIDTH;
+                let col = self.col;
+                let line = self.line();
+                if line.len() < col {
+                    line.resize_with(col, Cell::blank);
+                }
+            }
+            c if c.is_control() => {}
+            c => self.put(c),
+        }
+    }
+
+    /// The line under the cursor, created if needed.
+    fn line(&mut self) -> &mut Vec<Cell> {
+        if self.lines.len() <= self.row {
+            self.lines.resize_with(self.row + 1, Vec::new);
+        }
+        &mut self.lines[self.row]
+    }
+
+    /// Write a character at the cursor, overwriting what is there.
+    fn put(&mut self, ch: char) {
+        let width = ch.width().unwrap_or(0);
+        let col = self.col;
+        let style = self.style.clone();
+        let line = self.line();
+        if width == 0 {
+            // Combining marks join the character before the cursor.
+            let end = col.min(line.len());
+            if let Some(cell) = line[..end]
+                .iter_mut()
+                .rev()
+                .find(|cell| !cell.is_continuation())
+            {
+                cell.grapheme.push(ch);
+            }
+            return;
+        }
+        if line.len() < col + width {
+            line.resize_with(col + width, Cell::blank);
+        }
+        // Blank the halves of any wide characters being overwritten.
+        if line[col].is_continuation() && col > 0 {
+            line[col - 1] = Cell::blank();
+        }
+        let end = col + width;
+        if line[end - 1].is_wide() && end < line.len() {
+            line[end] = Cell::blank();
+        }
+        line[col] = Cell::new(ch.to_string(), style);
+        if width == 2 {
+            line[col + 1] = Cell::continuation();
+        }
+        self.col = end;
+    }
+
+    /// Handle a CSI sequence with final byte `action`.
+    fn csi(&mut self, params: &str, action: char) {
+        // Private sequences (`CSI ? 25 l` and the like) are ignored.
+        if params.starts_with(['?', '>', '<', '=']) {
+            return;
+        }
+        let nums: Vec<usize> = params
+            .split(';')
+            .map(|p| p.parse::<u16>().map_or(0, usize::from))
+            .collect();
+        let arg = |i: usize| nums.get(i).copied().unwrap_or(0);
+        let count = arg(0).max(1);
+        let (row, col) = (self.row, self.col);
+        match action {
+            'm' => apply_sgr(params, &mut self.style),
+            'A' => self.move_to(row.saturating_sub(count), col),
+            'B' => self.move_to(row.saturating_add(count), col),
+            'C' => self.move_to(row, col.saturating_add(count)),
+            'D' => self.move_to(row, col.saturating_sub(count)),
+            'E' => self.move_to(row.saturating_add(count), 0),
+            'F' => self.move_to(row.saturating_sub(count), 0),
+            'G' | '`' => self.move_to(row, count - 1),
+            'd' => self.move_to(count - 1, col),
+            'H' | 'f' => self.move_to(count - 1, arg(1).max(1) - 1),
+            'K' => self.erase_line(arg(0)),
+            'J' => match arg(0) {
+                0 => {
+                    self.erase_line(0);
+                    self.lines.truncate(self.row + 1);
+                }
+                1 => {
+                    for line in self.lines.iter_mut().take(self.row) {
+                        line.clear();
+                    }
+                    self.erase_line(1);
+                }
+                _ => self.lines.clear(),
+            },
+            's' => self.saved = (self.row, self.col),
+            'u' => (self.row, self.col) = self.saved,
+            _ => {}
+        }
+    }
+
+    /// Move the cursor, keeping it above the lowest row output has reached
+    /// and within [`MAX_COLUMN`] (or where it already is, if further).
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.row = row.min(self.bottom);
+        self.col = col.min(self.col.max(MAX_COLUMN));
+    }
+
+    /// Erase from the cursor to the end of the line (0), from the start
+    /// to the cursor (1) or the whole line (2).
+    fn erase_line(&mut self, mode: usize) {
+        let col = self.col;
+        let line = self.line();
+        match mode {
+            0 => {
+                if line.get(col).is_some_and(Cell::is_continuation) {
+                    line[col - 1] = Cell::blank();
+                }
+                line.truncate(col);
+            }
+            1 => {
+                let end = (col + 1).min(line.len());
+                if line.get(end).is_some_and(Cell::is_continuation) {
+                    line[end] = Cell::blank();
+                }
+                line[..end].fill_with(Cell::blank);
+            }
+            _ => line.clear(),
+        }
+    }
+
+    /// Handle an OSC string; only hyperlinks (`8;params;uri`) matter.
+    fn osc(&mut self, data: &str) {
+        let mut parts = data.splitn(3, ';');
+        if data.len() <= MAX_OSC_LEN && parts.next() == Some("8") {
+            let uri = parts.nth(1).unwrap_or("");
+            self.style.link = (!uri.is_empty()).then(|| uri.to_string());
+        }
+    }
+}
+
+/// A line of cells as segments, joining cells of the same style.
+fn segments(cells: &[Cell]) -> Vec<Segment> {
+    let mut line: Vec<Segment> = Vec::new();
+    for cell in cells.iter().filter(|cell| !cell.is_continuation()) {
+        match line.last_mut() {
+            Some(last) if last.style == cell.style => last.text.push_str(&cell.grapheme),
+            _ => line.push(Segment::styled(cell.grapheme.clone(), cell.style.clone())),
+        }
+    }
+    line
+}
+
+/// Apply the parameters of an SGR sequence (`CSI params m`) to `style`.
+///
+/// Parameters are separated by `;`; extended colours and underline
+/// shapes may also use `:` sub-parameters (`38:2::r:g:b`, `4:3`).
+pub fn apply_sgr(params: &str, style: &mut Style) {
+    let params: Vec<&str> = params.split(';').collect();
+    let mut i = 0;
+    while i < params.len() {
+        let mut sub = params[i].split(':');
+        let code: u16 = sub.next().and_then(|c| c.parse().ok()).unwrap_or(0);
+        match code {
+            0 => *style = Style::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => {
+                let shape = match sub.next().and_then(|s| s.parse::<u8>().ok()) {
+                    Some(0) => None,
+                    Some(2) => Some(UnderlineStyle::Double),
+                    Some(3) => Some(UnderlineStyle::Curly),
+                    Some(4) => Some(UnderlineStyle::Dotted),
+                    Some(5) => Some(UnderlineStyle::Dashed),
+                    _ => Some(UnderlineStyle::Single),
+                };
+                style.underline = shape.is_some();
+                style.underline_style = shape.unwrap_or_default();
+            }
+            5 | 6 => style.blink = true,
+            7 => style.reverse = true,
+            8 => style.hidden = true,
+            9 => style.strikethrough = true,
+            21 => {
+                style.underline = true;
+                style.underline_style = UnderlineStyle::Double;
+            }
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            23 => style.italic = false,
+            24 => style.underline = false,
+            25 => style.blink = false,
+            27 => style.reverse = false,
+            28 => style.hidden = false,
+            29 => style.strikethrough = false,
+            30..=37 => style.fg = Some(Color::Named(ANSI_COLORS[usize::from(code - 30)])),
+            39 => style.fg = None,
+            40..=47 => style.bg = Some(Color::Named(ANSI_COLORS[usize::from(code - 40)])),
+            49 => style.bg = None,
+            53 => style.overline = true,
+            55 => style.overline = false,
+            59 => style.underline_color = None,
+            90..=97 => style.fg = Some(Color::Named(ANSI_COLORS[usize::from(code - 90 + 8)])),
+            100..=107 => style.bg = Some(Color::Named(ANSI_COLORS[usize::from(code - 100 + 8)])),
+            38 | 48 | 58 => {
+                let sub: Vec<&str> = sub.collect();
+                let color = if sub.is_empty() {
+                    let (color, used) = extended_color(&params[i + 1..]);
+                    i += used;
+                    color
+                } else {
+                    extended_color_sub(&sub)
+                };
+                if let Some(color) = color {
+                    match code {
+                        38 => style.fg = Some(color),
+                        48 => style.bg = Some(color),
+                        _ => style.underline_color = Some(color),
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// An extended colour in `;` form (`5;n` or `2;r;g;b`) and how many
+/// parameters it used.
+fn extended_color(params: &[&str]) -> (Option<Color>, usize) {
+    let num = |i: usize| params.get(i).and_then(|p| p.parse::<u8>().ok());
+    match params.first().copied() {
+        Some("5") => (num(1).map(Color::Indexed), 2),
+        Some("2") => {
+            let color = match (num(1), num(2), num(3)) {
+                (Some(r), Some(g), Some(b)) => Some(Color::Rgb { r, g, b }),
+                _ => None,
+            };
+            (color, 4)
+        }
+        _ => (None, 0),
+    }
+}
+
+/// An extended colour in `:` form: `5:n`, `2:r:g:b` or `2:id:r:g:b`
+/// with a colour space id.
+fn extended_color_sub(sub: &[&str]) -> Option<Color> {
+    let num = |s: &str| s.parse::<u8>().ok();
+    match sub {
+        ["5", n, ..] => num(n).map(Color::Indexed),
+        ["2", _, r, g, b, ..] | ["2", r, g, b] => Some(Color::Rgb {
+            r: num(r)?,
+            g: num(g)?,
+            b: num(b)?,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(lines: &[Vec<Segment>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.iter().map(|s| s.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn plain_text_splits_into_lines() {
+        let lines = parse_ansi("one\ntwo\n");
+        assert_eq!(texts(&lines), vec!["one", "two"]);
+        assert_eq!(texts(&parse_ansi("a\n\nb")), vec!["a", "", "b"]);
+        assert!(parse_ansi("").len() == 1);
+    }
+
+    #[test]
+    fn sgr_colours_and_attributes() {
+        let lines = parse_ansi("\x1b[1;31merror\x1b[0m: \x1b[4:3;38;5;208mwavy\x1b[m");
+        let line = &lines[0];
+        assert_eq!(line.len(), 3);
+        assert_eq!(line[0].text, "error");
+        assert!(line[0].style.bold);
+        assert_eq!(line[0].style.fg, Some(Color::Named(NamedColor::Red)));
+        assert_eq!(line[1].style, Style::default());
+        assert_eq!(line[2].style.fg, Some(Color::Indexed(208)));
+        assert!(line[2].style.underline);
+        assert_eq!(line[2].style.underline_style, UnderlineStyle::Curly);
+    }
+
+    #[test]
+    fn true_colour_in_both_forms() {
+        let lines = parse_ansi("\x1b[38;2;1;2;3;48:2::4:5:6mx\x1b[58:2:7:8:9;92my");
+        let rgb = |r, g, b| Some(Color::Rgb { r, g, b });
+        assert_eq!(lines[0][0].style.fg, rgb(1, 2, 3));
+        assert_eq!(lines[0][0].style.bg, rgb(4, 5, 6));
+        assert_eq!(lines[0][1].style.underline_color, rgb(7, 8, 9));
+        assert_eq!(
+            lines[0][1].style.fg,
+            Some(Color::Named(NamedColor::BrightGreen))
+        );
+    }
+
+    #[test]
+    fn styles_carry_across_lines() {
+        let lines = parse_ansi("\x1b[32mgreen\nstill green\x1b[39m plain");
+        assert_eq!(lines[1][0].style.fg, Some(Color::Named(NamedColor::Green)));
+        assert_eq!(lines[1][1].style.fg, None);
+    }
+
+    #[test]
+    fn other_sequences_and_controls_are_dropped() {
+        let text = "\x1b]0;title\x07\x1b[2K\x1b(Ba\x07b\x1b]8;;http://x\x1b\\c\r\n";
+        assert_eq!(texts(&parse_ansi(text)), vec!["abc"]);
+        assert_eq!(texts(&parse_ansi("ab\tc")), vec!["ab      c"]);
+    }
+
+    #[test]
+    fn carriage_return_overwrites() {
+        assert_eq!(texts(&parse_ansi("50%\r100%\n")), vec!["100%"]);
+        assert_eq!(texts(&parse_ansi("abcdef\rXY")), vec!["XYcdef"]);
+        assert_eq!(texts(&parse_ansi("abc\x08\x08Z")), vec!["aZc"]);
+        assert_eq!(texts(&parse_ansi("long line\r\x1b[Kshort")), vec!["short"]);
+    }
+
+    #[test]
+    fn cursor_movement_rewrites_earlier_lines() {
+        // A multi-line progress display redrawn in place.
+        let text = "a: 1\nb: 1\n\x1b[2A\x1b[4Ga2\x1b[1E\x1b[4Gb2\n";
+        assert_eq!(texts(&parse_ansi(text)), vec!["a: a2", "b: b2"]);
+        assert_eq!(texts(&parse_ansi("x\x1b[3Cy")), vec!["x   y"]);
+        assert_eq!(texts(&parse_ansi("abc\x1b[2D\x1b[1KZ")), vec![" Zc"]);
+        assert_eq!(texts(&parse_ansi("1\n2\x1b[2J\x1b[Hnew")), vec!["new"]);
+        assert_eq!(texts(&parse_ansi("ab\x1b7cd\x1b8XY")), vec!["abXY"]);
+    }
+
+    #[test]
+    fn cursor_movement_is_clamped() {
+        let lines = parse_ansi("top\x1b[65535B\x1b[65535Cx\x1b[65535;65535Hy");
+        assert_eq!(lines.len(), 1);
+        // `x` lands on the last column movement reaches; writing it moves
+        // the cursor past that, and movement does not pull it back.
+        let width: usize = lines[0].iter().map(|s| s.text.len()).sum();
+        assert_eq!(width, MAX_COLUMN + 2);
+
+        // Moving back down is fine within the lines already output.
+        let text = "a\n\n\n\x1b[3A\x1b[3Bb\x1b[9Ec";
+        assert_eq!(texts(&parse_ansi(text)), vec!["a", "", "", "c"]);
+    }
+
+    #[test]
+    fn unterminated_osc_strings_end() {
+        let lines = parse_ansi("\x1b]8;;http://x\nvisible");
+        assert_eq!(texts(&lines), vec!["", "visible"]);
+        assert_eq!(lines[1][0].style.link, None);
+        assert_eq!(
+            texts(&parse_ansi("a\x1b]0;title\x18b\x1b]2;t\x1ac")),
+            vec!["abc"]
+        );
+
+        let long = format!("\x1b]8;;{}\x07z", "u".repeat(MAX_OSC_LEN * 2));
+        let lines = parse_ansi(&long);
+        assert_eq!(texts(&lines), vec!["z"]);
+        assert_eq!(lines[0][0].style.link, None);
+    }
+
+    #[test]
+    fn osc8_links_style_their_text() {
+        let text = "see \x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x07 now";
+        let lines = parse_ansi(text);
+        assert_eq!(texts(&lines), vec!["see docs now"]);
+        assert_eq!(lines[0][1].text, "docs");
+        assert_eq!(
+            lines[0][1].style.link.as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(lines[0][2].style.link, None);
+    }
+
+    #[test]
+    fn wide_characters_overwrite_cleanly() {
+        assert_eq!(texts(&parse_ansi("日本\rx")), vec!["x 本"]);
+        assert_eq!(texts(&parse_ansi("ab\rは")), vec!["は"]);
+        assert_eq!(texts(&parse_ansi("e\u{301}!")), vec!["e\u{301}!"]);
+    }
+
+    #[test]
+    fn sequences_may_span_chunks() {
+        let mut parser = AnsiParser::new();
+        for chunk in ["\x1b", "[3", "1mre", "d\x1b]8;;ht", "tp://x\x1b", "\\l\n"] {
+            parser.feed(chunk);
+        }
+        let lines = parser.lines();
+        assert_eq!(texts(&lines), vec!["redl"]);
+        assert_eq!(lines[0][0].style.fg, Some(Color::Named(NamedColor::Red)));
+        assert_eq!(lines[0][1].style.link.as_deref(), Some("http://x"));
+
+        parser.clear();
+        parser.feed("next");
+        assert_eq!(parser.lines()[0][0].style.link.as_deref(), Some("http://x"));
+    }
+
+    #[test]
+    fn strip_ansi_keeps_visible_text() {
+        assert_eq!(
+            strip_ansi("\x1b[1mbold\x1b[0m\n\x1b[?25lhidden cursor"),
+            "bold\nhidden cursor"
+        );
+    }
+}
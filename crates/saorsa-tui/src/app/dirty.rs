@@ -0,0 +1,147 @@
+//! Per-node invalidation state and frame statistics.
+//!
+//! The runtime tracks why each node must be processed again so a frame
+//! only restyles, relays out and repaints what changed. Changes the runtime
+//! cannot attribute to a node (timers, actions, [`App::request_render`],
+//! scrolling, selections) still repaint the whole screen.
+//!
+//! [`App::request_render`]: super::App::request_render
+
+use crate::buffer::ScreenBuffer;
+use crate::cell::Cell;
+use crate::compositor::blend::normalize_row;
+use crate::geometry::Rect;
+
+/// What must be redone for a node in the next frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DirtyFlags {
+    /// Its selector matches or variables changed; styles are re-resolved.
+    pub style: bool,
+    /// Its layout style changed; its subtree is laid out again.
+    pub layout: bool,
+    /// Its widget must be rendered again.
+    pub paint: bool,
+}
+
+impl DirtyFlags {
+    /// Whether nothing is pending.
+    pub fn is_clean(&self) -> bool {
+        !(self.style || self.layout || self.paint)
+    }
+}
+
+/// What the last frame had to redo.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Nodes whose styles were re-resolved.
+    pub restyled: usize,
+    /// Nodes whose layout style changed.
+    pub relaid_out: usize,
+    /// Whether Taffy layout ran at all.
+    pub layout: bool,
+    /// Nodes whose widgets were rendered.
+    pub painted: usize,
+    /// Whether the whole screen was repainted rather than damaged areas.
+    pub full_repaint: bool,
+}
+
+/// Copy the cells inside `areas` from `from` into `to`, keeping wide
+/// characters that straddle an area's edge whole or blanking them.
+pub(super) fn copy_damaged(from: &ScreenBuffer, to: &mut ScreenBuffer, areas: &[Rect]) {
+    let height = to.height().min(from.height());
+    let width = to.width().min(from.width());
+    for y in 0..height {
+        let spans: Vec<(u16, u16)> = areas
+            .iter()
+            .filter(|a| y >= a.position.y && y < a.bottom())
+            .map(|a| (a.position.x.min(width), a.right().min(width)))
+            .filter(|(start, end)| start < end)
+            .collect();
+        let (Some(src), Some(dst)) = (from.get_row(y), to.get_row(y)) else {
+            continue;
+        };
+        if spans.is_empty() {
+            continue;
+        }
+        let mut row: Vec<Cell> = dst[..usize::from(width)].to_vec();
+        for (start, end) in spans {
+            let range = usize::from(start)..usize::from(end);
+            row[range.clone()].clone_from_slice(&src[range]);
+        }
+        normalize_row(&mut row);
+        for (x, cell) in row.into_iter().enumerate() {
+            if let Some(slot) = to.get_mut(x as u16, y) {
+                slot.clone_from(&cell);
+            }
+        }
+    }
+    for graphic in from.graphics() {
+        to.place_graphic(graphic.clone());
+    }
+}
+
+/// Reset the cells of `areas`, widened by one column on each side so wide
+/// characters written at their edges see blank neighbours, to blank.
+pub(super) fn clear_areas(buf: &mut ScreenBuffer, areas: &[Rect]) {
+    let blank = Cell::blank();
+    for area in areas {
+        let x0 = area.position.x.saturating_sub(1);
+        let x1 = area.right().saturating_add(1).min(buf.width());
+        for y in area.position.y..area.bottom().min(buf.height()) {
+            for x in x0..x1 {
+                if let Some(cell) = buf.get_mut(x, y) {
+                    cell.clone_from(&blank);
+                }
+            }
+        }
+    }
+    buf.clear_graphics();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Size;
+    use crate::style::Style;
+
+    #[test]
+    fn default_flags_are_clean() {
+        assert!(DirtyFlags::default().is_clean());
+        let flags = DirtyFlags {
+            paint: true,
+            ..DirtyFlags::default()
+        };
+        assert!(!flags.is_clean());
+    }
+
+    #[test]
+    fn copy_damaged_only_touches_areas() {
+        let mut from = ScreenBuffer::new(Size::new(4, 2));
+        for y in 0..2 {
+            for x in 0..4 {
+                from.set(x, y, Cell::new("n", Style::default()));
+            }
+        }
+        let mut to = ScreenBuffer::new(Size::new(4, 2));
+        copy_damaged(&from, &mut to, &[Rect::new(1, 1, 2, 1)]);
+        let text: Vec<String> = (0..2)
+            .map(|y| {
+                (0..4)
+                    .filter_map(|x| to.get(x, y).map(|c| c.grapheme.clone()))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(text, vec!["    ", " nn "]);
+    }
+
+    #[test]
+    fn copy_damaged_blanks_split_wide_chars() {
+        let mut from = ScreenBuffer::new(Size::new(4, 1));
+        from.set(0, 0, Cell::new("a", Style::default()));
+        let mut to = ScreenBuffer::new(Size::new(4, 1));
+        to.set(0, 0, Cell::new("世", Style::default()));
+        copy_damaged(&from, &mut to, &[Rect::new(1, 0, 1, 1)]);
+        assert!(matches!(to.get(0, 0), Some(c) if c.grapheme == " "));
+        assert!(matches!(to.get(1, 0), Some(c) if c.grapheme == " " && c.width == 1));
+    }
+}
@@ -32,6 +32,9 @@ pub struct Dom {
     nodes: HashMap<NodeId, DomNode>,
     tree: WidgetTree,
     focus: FocusManager,
+    /// Nodes whose id, classes or attributes changed since the app last
+    /// restyled.
+    restyled: Vec<NodeId>,
 }
 
 impl Dom {
@@ -43,6 +46,7 @@ impl Dom {
             nodes: HashMap::new(),
             tree: WidgetTree::new(),
             focus: FocusManager::new(),
+            restyled: Vec::new(),
         }
     }
 
@@ -187,6 +191,7 @@ impl Dom {
     pub fn set_css_id(&mut self, node: NodeRef, css_id: impl Into<String>) {
         if let Some(n) = self.tree.get_mut(node.0) {
             n.css_id = Some(css_id.into());
+            self.restyled.push(node.0);
         }
     }
 
@@ -194,9 +199,81 @@ impl Dom {
     pub fn add_class(&mut self, node: NodeRef, class: impl Into<String>) {
         if let Some(n) = self.tree.get_mut(node.0) {
             n.classes.push(class.into());
+            self.restyled.push(node.0);
         }
     }
 
+    /// Remove a CSS class from a node.
+    pub fn remove_class(&mut self, node: NodeRef, class: &str) {
+        if let Some(n) = self.tree.get_mut(node.0)
+            && n.classes.iter().any(|c| c == class)
+        {
+            n.classes.retain(|c| c != class);
+            self.restyled.push(node.0);
+        }
+    }
+
+    /// Whether a node has a CSS class.
+    pub fn has_class(&self, node: NodeRef, class: &str) -> bool {
+        self.tree
+            .get(node.0)
+            .is_some_and(|n| n.classes.iter().any(|c| c == class))
+    }
+
+    /// Rearrange some children of `parent` into the order given by `order`.
+    ///
+    /// The listed children are placed, in order, into the slots they
+    /// currently occupy; other children keep their positions. Ids that are
+    /// not children of `parent` are ignored. Returns whether anything moved.
+    pub(crate) fn reorder_children(&mut self, parent: NodeRef, order: &[NodeId]) -> bool {
+        let Some(p) = self.tree.get_mut(parent.0) else {
+            return false;
+        };
+        let order: Vec<NodeId> = order
+            .iter()
+            .copied()
+            .filter(|id| p.children.contains(id))
+            .collect();
+        let mut next = order.iter();
+        let mut changed = false;
+        for slot in p.children.iter_mut() {
+            if order.contains(slot)
+                && let Some(&id) = next.next()
+            {
+                changed |= *slot != id;
+                *slot = id;
+            }
+        }
+        changed
+    }
+
+    /// Set an attribute on a node (matched by `[name=value]` selectors).
+    pub fn set_attribute(
+        &mut self,
+        node: NodeRef,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        let value = value.into();
+        if let Some(n) = self.tree.get_mut(node.0)
+            && n.attributes.insert(name.into(), value.clone()) != Some(value)
+        {
+            self.restyled.push(node.0);
+        }
+    }
+
+    /// Take the nodes whose id, classes or attributes changed since the
+    /// last call.
+    pub(crate) fn take_restyled(&mut self) -> Vec<NodeId> {
+        std::mem::take(&mut self.restyled)
+    }
+
+    /// Whether any node's id, classes or attributes changed since the last
+    /// [`Self::take_restyled`].
+    pub(crate) fn has_restyled(&self) -> bool {
+        !self.restyled.is_empty()
+    }
+
     /// Mutable access to a widget by node id.
     pub fn widget_mut(&mut self, node: NodeRef) -> Option<&mut (dyn NodeWidget + '_)> {
         match self.nodes.get_mut(&node.0) {
@@ -4,10 +4,14 @@
 //! It owns the widget tree, computes styles (TCSS), runs layout (Taffy),
 //! dispatches input events, and renders frames via [`RenderContext`].
 
+mod dirty;
 mod dom;
 mod node_widget;
+mod reactive;
 mod runtime;
+mod scroll;
 
+pub use dirty::{DirtyFlags, FrameStats};
 pub use dom::{Dom, NodeId, NodeRef};
 pub use node_widget::{Interactive, Leaf, NodeWidget, StyledInteractive, StyledLeaf};
-pub use runtime::App;
+pub use runtime::{ActionInfo, App, TimerId};
@@ -11,6 +11,7 @@ use crate::buffer::ScreenBuffer;
 use crate::event::Event;
 use crate::geometry::Rect;
 use crate::tcss::ComputedStyle;
+use crate::tcss::variable::VariableEnvironment;
 use crate::widget::EventResult;
 
 /// Dynamic widget interface stored in the [`Dom`](super::Dom).
@@ -29,9 +30,35 @@ pub trait NodeWidget {
         EventResult::Ignored
     }
 
+    /// Widget-defined pseudo-classes currently in effect. Default is none.
+    fn pseudo_classes(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Widget-defined pseudo-classes stylesheets may select. Default is
+    /// none.
+    fn declared_pseudo_classes(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Whether Tab and Shift+Tab go to this widget while it has focus.
+    /// Default is false.
+    fn captures_tab(&self) -> bool {
+        false
+    }
+
+    /// Whether the widget changed on its own since it was last rendered.
+    /// Default is false.
+    fn needs_repaint(&self) -> bool {
+        false
+    }
+
     /// Apply TCSS computed style. Default is no-op.
     fn apply_computed_style(&mut self, _computed: &ComputedStyle) {}
 
+    /// Apply the app's theme variables. Default is no-op.
+    fn apply_variables(&mut self, _vars: &VariableEnvironment) {}
+
     /// Downcast support.
     fn as_any(&self) -> &dyn Any;
 
@@ -88,6 +115,22 @@ where
         self.0.handle_event(event)
     }
 
+    fn pseudo_classes(&self) -> Vec<&'static str> {
+        self.0.pseudo_classes()
+    }
+
+    fn declared_pseudo_classes(&self) -> &'static [&'static str] {
+        self.0.declared_pseudo_classes()
+    }
+
+    fn captures_tab(&self) -> bool {
+        self.0.captures_tab()
+    }
+
+    fn needs_repaint(&self) -> bool {
+        self.0.needs_repaint()
+    }
+
     fn as_any(&self) -> &dyn Any {
         &self.0
     }
@@ -119,6 +162,10 @@ where
         self.0.apply_computed_style(computed);
     }
 
+    fn apply_variables(&mut self, vars: &VariableEnvironment) {
+        self.0.apply_variables(vars);
+    }
+
     fn as_any(&self) -> &dyn Any {
         &self.0
     }
@@ -153,10 +200,30 @@ where
         self.0.handle_event(event)
     }
 
+    fn pseudo_classes(&self) -> Vec<&'static str> {
+        self.0.pseudo_classes()
+    }
+
+    fn declared_pseudo_classes(&self) -> &'static [&'static str] {
+        self.0.declared_pseudo_classes()
+    }
+
+    fn captures_tab(&self) -> bool {
+        self.0.captures_tab()
+    }
+
+    fn needs_repaint(&self) -> bool {
+        self.0.needs_repaint()
+    }
+
     fn apply_computed_style(&mut self, computed: &ComputedStyle) {
         self.0.apply_computed_style(computed);
     }
 
+    fn apply_variables(&mut self, vars: &VariableEnvironment) {
+        self.0.apply_variables(vars);
+    }
+
     fn as_any(&self) -> &dyn Any {
         &self.0
     }
@@ -0,0 +1,101 @@
+//! Glue between reactive signals and the App DOM.
+//!
+//! Signal changes fire binding effects synchronously, at a point where the
+//! [`App`] may not be borrowable. Bindings therefore only queue a
+//! [`PendingUpdate`] for their node; the app applies the queue before
+//! handling the next event or rendering the next frame.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::rc::Rc;
+
+use crate::error::Result;
+
+use super::dom::{NodeId, NodeRef};
+use super::runtime::App;
+
+/// Applies a binding's value to the app.
+pub(super) type ApplyFn<T> = Rc<dyn Fn(&mut App, &T) -> Result<()>>;
+
+type Deferred = Box<dyn FnOnce(&mut App) -> Result<()>>;
+
+/// A deferred change to a node, produced by a binding.
+pub(super) struct PendingUpdate {
+    pub node: NodeId,
+    pub apply: Deferred,
+}
+
+/// Updates queued by bindings, shared between the app and binding sinks.
+pub(super) type UpdateQueue = Rc<RefCell<Vec<PendingUpdate>>>;
+
+/// Queue `apply` for `node` with a copy of `value`.
+pub(super) fn enqueue<T: Clone + 'static>(
+    queue: &UpdateQueue,
+    node: NodeId,
+    value: &T,
+    apply: &ApplyFn<T>,
+) {
+    let value = value.clone();
+    let apply = Rc::clone(apply);
+    queue.borrow_mut().push(PendingUpdate {
+        node,
+        apply: Box::new(move |app| apply(app, &value)),
+    });
+}
+
+/// Creates the node for a new list item.
+pub(super) type CreateItem<T> = Box<dyn FnMut(&mut App, &T) -> Result<NodeRef>>;
+
+/// Keyed children of a reactive list container.
+pub(super) struct KeyedList<T, K> {
+    key: Box<dyn Fn(&T) -> K>,
+    create: CreateItem<T>,
+    nodes: Vec<(K, NodeRef)>,
+}
+
+impl<T, K: Eq + Hash + Clone> KeyedList<T, K> {
+    pub fn new(key: Box<dyn Fn(&T) -> K>, create: CreateItem<T>) -> Self {
+        Self {
+            key,
+            create,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Make the children of `parent` match `items`.
+    ///
+    /// Nodes whose key disappeared are removed, new keys get fresh nodes,
+    /// and surviving nodes are kept (with their state) and reordered.
+    pub fn reconcile(&mut self, app: &mut App, parent: NodeRef, items: &[T]) -> Result<()> {
+        let keys: Vec<K> = items.iter().map(|item| (self.key)(item)).collect();
+        let wanted: HashSet<&K> = keys.iter().collect();
+
+        let mut existing: HashMap<K, NodeRef> = HashMap::new();
+        for (key, node) in self.nodes.drain(..) {
+            if wanted.contains(&key) && !existing.contains_key(&key) {
+                existing.insert(key, node);
+            } else {
+                app.remove_subtree(node)?;
+            }
+        }
+
+        let mut nodes = Vec::with_capacity(items.len());
+        for (key, item) in keys.into_iter().zip(items) {
+            let node = match existing.remove(&key) {
+                Some(node) => node,
+                None => {
+                    let node = (self.create)(app, item)?;
+                    app.mount(parent, node)?;
+                    node
+                }
+            };
+            nodes.push((key, node));
+        }
+
+        let order: Vec<NodeId> = nodes.iter().map(|(_, node)| node.0).collect();
+        app.set_child_order(parent, &order)?;
+        self.nodes = nodes;
+        Ok(())
+    }
+}
@@ -1,25 +1,49 @@
 //! App runtime integrating DOM, TCSS, layout, and rendering.
 
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 use std::path::Path;
+use std::rc::Rc;
 use std::sync::mpsc;
+use std::time::Duration;
 
 use crate::RenderContext;
 use crate::buffer::ScreenBuffer;
+use crate::clipboard::Clipboard;
 use crate::error::{Result, SaorsaTuiError};
 use crate::event::{Event, KeyCode, KeyEvent, Modifiers, MouseEventKind};
-use crate::geometry::{Rect, Size};
-use crate::layout::{LayoutEngine, computed_to_taffy};
+use crate::geometry::{Position, Rect, Size};
+use crate::layout::scroll::extract_overflow;
+use crate::layout::{LayoutEngine, ScrollManager, ScrollState, computed_to_taffy};
+use crate::reactive::{BindingId, BindingScope, Signal};
+use crate::selection::ScreenSelection;
+use crate::style::Style;
 use crate::tcss::{
     CascadeResolver, ComputedStyle, MatchCache, StyleMatcher, StylesheetEvent, StylesheetLoader,
-    ThemeManager, VariableEnvironment, WidgetTree,
+    ThemeManager, ThemeVariant, VariableEnvironment, WidgetTree, builtin_for_variant,
+    builtin_theme, theme_variant,
 };
-use crate::widget::EventResult;
+use crate::terminal::{TerminalQuerier, detect_theme_variant};
+use crate::widget::{EventResult, Label};
 
-use super::dom::{Dom, NodeId};
+use super::dirty::{DirtyFlags, FrameStats, clear_areas, copy_damaged};
+use super::dom::{Dom, NodeId, NodeRef};
+use super::reactive::{ApplyFn, KeyedList, UpdateQueue, enqueue};
+use super::scroll::{Damage, Frame, ScrollRegion, WHEEL_LINES, clips, render_node, visible_rects};
 
 type AppAction = Box<dyn FnMut(&mut App) -> Result<EventResult>>;
 
+/// Identifies a timer registered with [`App::set_timer`] or [`App::set_interval`].
+pub type TimerId = u64;
+
+struct Timer {
+    id: TimerId,
+    due: Duration,
+    period: Option<Duration>,
+    action: AppAction,
+}
+
 #[derive(Clone, Debug)]
 struct KeyBinding {
     code: KeyCode,
@@ -27,18 +51,43 @@ struct KeyBinding {
     action: String,
 }
 
+/// A registered action as listed by [`App::actions`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActionInfo {
+    /// The name the action was registered under.
+    pub name: String,
+    /// The description set with [`App::describe_action`], if any.
+    pub description: Option<String>,
+    /// The key chords bound to the action, in binding order.
+    pub keys: Vec<KeyEvent>,
+}
+
+/// Automatic light/dark theme selection from the terminal's colors.
+struct AutoTheme {
+    querier: Box<dyn TerminalQuerier>,
+    family: Option<String>,
+    variant: Option<ThemeVariant>,
+    /// Set when the terminal regained focus; the colors are queried again
+    /// before the next frame.
+    recheck: bool,
+}
+
 /// Retained-mode application runtime.
 pub struct App {
     dom: Dom,
     matcher: StyleMatcher,
     match_cache: MatchCache,
     vars: VariableEnvironment,
+    /// Whether mounted widgets have yet to see the current variables.
+    vars_changed: bool,
     theme_mgr: ThemeManager,
     active_theme: Option<String>,
+    auto_theme: Option<AutoTheme>,
     stylesheet_loader: StylesheetLoader,
     stylesheet_watcher: Option<notify::RecommendedWatcher>,
     stylesheet_rx: Option<mpsc::Receiver<StylesheetEvent>>,
     actions: HashMap<String, AppAction>,
+    action_descriptions: HashMap<String, String>,
     bindings: Vec<KeyBinding>,
 
     layout: LayoutEngine,
@@ -46,9 +95,34 @@ pub struct App {
     render_order: Vec<NodeId>,
     computed: HashMap<NodeId, ComputedStyle>,
 
+    scroll: ScrollManager,
+    scroll_regions: HashMap<NodeId, ScrollRegion>,
+    visible: HashMap<NodeId, Rect>,
+    pending_reveal: Option<NodeId>,
+
+    binding_scopes: HashMap<NodeId, BindingScope>,
+    updates: UpdateQueue,
+    applying_updates: bool,
+    dirty_nodes: HashMap<NodeId, DirtyFlags>,
+    layout_dirty: bool,
+    full_repaint: bool,
+    damage: Vec<Rect>,
+    frame_stats: FrameStats,
+    scratch: ScreenBuffer,
+
+    clock: Duration,
+    timers: Vec<Timer>,
+    next_timer_id: TimerId,
+    running_timer: Option<(TimerId, bool)>,
+
     render: RenderContext,
     dirty: bool,
     last_focused: Option<NodeId>,
+
+    clipboard: Clipboard,
+    mouse_selection: bool,
+    selection: Option<ScreenSelection>,
+    selection_style: Style,
 }
 
 impl App {
@@ -77,20 +151,44 @@ impl App {
             matcher,
             match_cache: MatchCache::new(),
             vars,
+            vars_changed: true,
             theme_mgr,
             active_theme: None,
+            auto_theme: None,
             stylesheet_loader: loader,
             stylesheet_watcher: None,
             stylesheet_rx: None,
             actions: HashMap::new(),
+            action_descriptions: HashMap::new(),
             bindings: Vec::new(),
             layout: LayoutEngine::new(),
             rects: HashMap::new(),
             render_order: Vec::new(),
             computed: HashMap::new(),
+            scroll: ScrollManager::new(),
+            scroll_regions: HashMap::new(),
+            visible: HashMap::new(),
+            pending_reveal: None,
+            binding_scopes: HashMap::new(),
+            updates: Rc::new(RefCell::new(Vec::new())),
+            applying_updates: false,
+            dirty_nodes: HashMap::new(),
+            layout_dirty: true,
+            full_repaint: true,
+            damage: Vec::new(),
+            frame_stats: FrameStats::default(),
+            scratch: ScreenBuffer::new(render.size()),
+            clock: Duration::ZERO,
+            timers: Vec::new(),
+            next_timer_id: 1,
+            running_timer: None,
             render,
             dirty: true,
             last_focused: None,
+            clipboard: Clipboard::new(),
+            mouse_selection: true,
+            selection: None,
+            selection_style: Style::new().reverse(true),
         };
 
         app.build_layout_tree()?;
@@ -98,26 +196,31 @@ impl App {
     }
 
     /// Convenience: build from a TCSS string.
+    ///
+    /// The stylesheet may select the
+    /// [declared pseudo-classes](crate::widget::InteractiveWidget::declared_pseudo_classes)
+    /// of the widgets in `dom`.
     pub fn from_tcss_string(
         terminal: &dyn crate::terminal::Terminal,
         dom: Dom,
         tcss: &str,
     ) -> Result<Self> {
-        let loader = StylesheetLoader::load_string(tcss)
+        let loader = StylesheetLoader::load_string_with_states(tcss, &declared_states(&dom))
             .map_err(|e| SaorsaTuiError::Style(e.to_string()))?;
         Self::new(terminal, dom, loader)
     }
 
     /// Convenience: build from a TCSS file and enable hot reload.
     ///
-    /// To apply updates, call [`Self::poll_stylesheet_reload`] regularly
+    /// As with [`Self::from_tcss_string`], the stylesheet may select the
+    /// declared pseudo-classes of the widgets in `dom`. To apply updates, call [`Self::poll_stylesheet_reload`] regularly
     /// (e.g. once per event loop tick). Successful reloads mark the app dirty.
     pub fn from_tcss_file(
         terminal: &dyn crate::terminal::Terminal,
         dom: Dom,
         path: impl AsRef<Path>,
     ) -> Result<Self> {
-        let loader = StylesheetLoader::load_file(path.as_ref())
+        let loader = StylesheetLoader::load_file_with_states(path.as_ref(), &declared_states(&dom))
             .map_err(|e| SaorsaTuiError::Style(e.to_string()))?;
         let mut app = Self::new(terminal, dom, loader)?;
 
@@ -134,15 +237,50 @@ impl App {
     }
 
     /// Access the DOM mutably.
+    ///
+    /// Id, class and attribute changes restyle the affected nodes on the
+    /// next frame. The app cannot tell what else changed through this;
+    /// follow widget changes with [`Self::mark_node_dirty`] for the
+    /// affected nodes, or [`Self::request_render`] to repaint everything.
     pub fn dom_mut(&mut self) -> &mut Dom {
         &mut self.dom
     }
 
     /// Get the last computed layout rectangle for a node (if layout ran).
+    ///
+    /// The rect is in absolute screen coordinates before scroll offsets are
+    /// applied; inside a scrolled container it may lie outside the screen.
     pub fn rect_of(&self, node: super::dom::NodeRef) -> Option<Rect> {
         self.rects.get(&node.0).copied()
     }
 
+    /// The scroll state of a container with `overflow` other than
+    /// `visible` (available once layout has run).
+    pub fn scroll_state(&self, node: NodeRef) -> Option<&ScrollState> {
+        self.scroll.get(node.0)
+    }
+
+    /// Scroll a container to an absolute offset, clamped to its content.
+    pub fn scroll_to(&mut self, node: NodeRef, x: u16, y: u16) {
+        self.scroll.scroll_to(node.0, x, y);
+        self.after_scroll();
+    }
+
+    /// Scroll a container by a relative offset, clamped to its content.
+    pub fn scroll_by(&mut self, node: NodeRef, dx: i16, dy: i16) {
+        self.scroll.scroll_by(node.0, dx, dy);
+        self.after_scroll();
+    }
+
+    /// Scroll every container around `node` so that it becomes visible.
+    ///
+    /// Takes effect on the next frame, after layout. Focus changes request
+    /// this automatically for the newly focused node.
+    pub fn scroll_into_view(&mut self, node: NodeRef) {
+        self.pending_reveal = Some(node.0);
+        self.dirty = true;
+    }
+
     /// Register an application-level action handler.
     ///
     /// Actions can be invoked via key bindings (see [`Self::bind_key`]).
@@ -162,6 +300,55 @@ impl App {
         });
     }
 
+    /// Set the human-readable description of an action, shown by command
+    /// palettes and help screens.
+    pub fn describe_action(&mut self, name: impl Into<String>, description: impl Into<String>) {
+        self.action_descriptions
+            .insert(name.into(), description.into());
+    }
+
+    /// The registered actions with their descriptions and key bindings,
+    /// sorted by name.
+    pub fn actions(&self) -> Vec<ActionInfo> {
+        let mut actions: Vec<ActionInfo> = self
+            .actions
+            .keys()
+            .map(|name| ActionInfo {
+                name: name.clone(),
+                description: self.action_descriptions.get(name).cloned(),
+                keys: self
+                    .bindings
+                    .iter()
+                    .filter(|b| &b.action == name)
+                    .map(|b| KeyEvent::new(b.code.clone(), b.modifiers))
+                    .collect(),
+            })
+            .collect();
+        actions.sort_by(|a, b| a.name.cmp(&b.name));
+        actions
+    }
+
+    /// Run a registered action as if its key binding had been pressed.
+    ///
+    /// Returns [`EventResult::Ignored`] if no action has that name.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the action fails with.
+    pub fn run_action(&mut self, name: &str) -> Result<EventResult> {
+        // Temporarily remove to avoid borrowing self.actions while calling action(&mut self).
+        let Some(mut action) = self.actions.remove(name) else {
+            return Ok(EventResult::Ignored);
+        };
+        let res = action(self);
+        self.actions.insert(name.to_string(), action);
+        let res = res?;
+        if matches!(res, EventResult::Consumed) {
+            self.repaint_all();
+        }
+        Ok(res)
+    }
+
     /// Query nodes using a TCSS selector string, in DOM pre-order.
     pub fn query(&self, selector: &str) -> Result<Vec<super::dom::NodeRef>> {
         let selectors = crate::tcss::SelectorList::parse(selector)
@@ -223,7 +410,9 @@ impl App {
         self.sync_layout_edges(parent)?;
 
         let tree = self.dom.widget_tree();
-        self.match_cache.invalidate_subtree(tree, parent.0);
+        self.match_cache
+            .invalidate_for_change(tree, parent.0, self.matcher.uses_has());
+        self.layout_dirty = true;
         self.dirty = true;
         Ok(())
     }
@@ -252,9 +441,13 @@ impl App {
             }
         }
 
-        // Remove from DOM, then remove from layout.
+        // Remove from DOM, then remove from layout. Dropping a node's
+        // binding scope disposes its bindings.
         let removed_ids = self.dom.remove_subtree(node);
         for id in removed_ids {
+            self.binding_scopes.remove(&id);
+            self.dirty_nodes.remove(&id);
+            self.computed.remove(&id);
             if self.layout.has_node(id) {
                 self.layout
                     .remove_node(id)
@@ -268,19 +461,373 @@ impl App {
         {
             self.sync_layout_edges(p)?;
             let tree = self.dom.widget_tree();
-            self.match_cache.invalidate_subtree(tree, p.0);
+            self.match_cache
+                .invalidate_for_change(tree, p.0, self.matcher.uses_has());
         } else {
             self.match_cache.invalidate_all();
         }
 
         self.rects.remove(&node.0);
+        self.layout_dirty = true;
         self.dirty = true;
         Ok(())
     }
 
-    /// Mark the app as needing a re-render.
+    /// Bind a signal to a widget property.
+    ///
+    /// `apply` receives the node's widget, downcast to `W` (the widget
+    /// inside its DOM wrapper), with the current value now and whenever the
+    /// signal changes. Each change marks only this node dirty. The binding
+    /// lives as long as the node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node does not exist or its widget is not a `W`.
+    pub fn bind_widget<W: 'static, T: Clone + 'static>(
+        &mut self,
+        node: NodeRef,
+        source: &Signal<T>,
+        apply: impl Fn(&mut W, &T) + 'static,
+    ) -> Result<BindingId> {
+        if self.dom.downcast_widget_mut::<W>(node).is_none() {
+            return Err(SaorsaTuiError::Widget(
+                "bind_widget: unknown node or widget type mismatch".into(),
+            ));
+        }
+        self.bind_node(
+            node,
+            source,
+            Rc::new(move |app: &mut App, value: &T| {
+                if let Some(widget) = app.dom.downcast_widget_mut::<W>(node) {
+                    apply(widget, value);
+                }
+                Ok(())
+            }),
+        )
+    }
+
+    /// Bind a signal to the text of a [`Label`] node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node does not exist or is not a `Label`.
+    pub fn bind_text(&mut self, node: NodeRef, source: &Signal<String>) -> Result<BindingId> {
+        self.bind_widget(node, source, |label: &mut Label, text: &String| {
+            label.set_text(text.clone());
+        })
+    }
+
+    /// Toggle a CSS class on a node from a boolean signal.
+    ///
+    /// Changes restyle the node (and the siblings and descendants whose
+    /// selectors may depend on it).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node does not exist.
+    pub fn bind_class(
+        &mut self,
+        node: NodeRef,
+        class: impl Into<String>,
+        source: &Signal<bool>,
+    ) -> Result<BindingId> {
+        let class = class.into();
+        self.bind_node(
+            node,
+            source,
+            Rc::new(move |app: &mut App, &enabled: &bool| {
+                if app.dom.has_class(node, &class) == enabled {
+                    return Ok(());
+                }
+                if enabled {
+                    app.dom.add_class(node, class.clone());
+                } else {
+                    app.dom.remove_class(node, &class);
+                }
+                let tree = app.dom.widget_tree();
+                app.match_cache
+                    .invalidate_for_change(tree, node.0, app.matcher.uses_has());
+                Ok(())
+            }),
+        )
+    }
+
+    /// Keep the children of `parent` in sync with a list signal.
+    ///
+    /// Items are identified by `key`: when the list changes, children for
+    /// removed keys are removed (disposing their bindings), `create` builds
+    /// and the app mounts a node for each new key, and children for
+    /// surviving keys keep their node and state and are moved into list
+    /// order. Other children of `parent` keep their positions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `parent` does not exist or creating the initial
+    /// children fails.
+    pub fn bind_list<T, K>(
+        &mut self,
+        parent: NodeRef,
+        items: &Signal<Vec<T>>,
+        key: impl Fn(&T) -> K + 'static,
+        create: impl FnMut(&mut App, &T) -> Result<NodeRef> + 'static,
+    ) -> Result<BindingId>
+    where
+        T: Clone + 'static,
+        K: Eq + Hash + Clone + 'static,
+    {
+        let list = RefCell::new(KeyedList::new(Box::new(key), Box::new(create)));
+        self.bind_node(
+            parent,
+            items,
+            Rc::new(move |app: &mut App, items: &Vec<T>| {
+                list.borrow_mut().reconcile(app, parent, items)
+            }),
+        )
+    }
+
+    /// Number of live bindings scoped to a node.
+    pub fn binding_count(&self, node: NodeRef) -> usize {
+        self.binding_scopes
+            .get(&node.0)
+            .map_or(0, BindingScope::binding_count)
+    }
+
+    /// Apply updates queued by bindings since the last call.
+    ///
+    /// Called automatically before handling events and rendering. Returns
+    /// whether any update was applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a list binding fails to mount or remove children.
+    pub fn apply_bindings(&mut self) -> Result<bool> {
+        // Bindings created while applying (e.g. by a list's `create`) are
+        // picked up by the outer loop.
+        if self.applying_updates {
+            return Ok(false);
+        }
+        self.applying_updates = true;
+        let mut applied = false;
+        let result = loop {
+            let batch = std::mem::take(&mut *self.updates.borrow_mut());
+            if batch.is_empty() {
+                break Ok(applied);
+            }
+            let mut failed = None;
+            for update in batch {
+                if !self.dom.contains(update.node) {
+                    continue;
+                }
+                if let Err(e) = (update.apply)(self) {
+                    failed = Some(e);
+                    break;
+                }
+                self.dirty_nodes.entry(update.node).or_default().paint = true;
+                self.dirty = true;
+                applied = true;
+            }
+            if let Some(e) = failed {
+                break Err(e);
+            }
+        };
+        self.applying_updates = false;
+        result
+    }
+
+    /// Whether a node must be repainted in the next frame, e.g. because
+    /// a binding changed it.
+    pub fn is_node_dirty(&self, node: NodeRef) -> bool {
+        self.dirty_nodes.get(&node.0).is_some_and(|f| f.paint)
+    }
+
+    /// What the next frame must redo for a node.
+    pub fn dirty_flags(&self, node: NodeRef) -> DirtyFlags {
+        let mut flags = self.dirty_nodes.get(&node.0).copied().unwrap_or_default();
+        flags.style |= self.dom.contains(node.0) && self.match_cache.get(node.0).is_none();
+        flags
+    }
+
+    /// Repaint a node in the next frame, after changing its widget through
+    /// [`Self::dom_mut`]. Unlike [`Self::request_render`], the rest of the
+    /// screen is kept.
+    pub fn mark_node_dirty(&mut self, node: NodeRef) {
+        if self.dom.contains(node.0) {
+            self.dirty_nodes.entry(node.0).or_default().paint = true;
+            self.dirty = true;
+        }
+    }
+
+    /// Re-resolve a node's styles in the next frame, after changing
+    /// something its selectors depend on through [`Self::dom_mut`]. Id,
+    /// class and attribute changes are picked up without this.
+    pub fn mark_style_dirty(&mut self, node: NodeRef) {
+        if self.dom.contains(node.0) {
+            let tree = self.dom.widget_tree();
+            self.match_cache
+                .invalidate_for_change(tree, node.0, self.matcher.uses_has());
+            self.dirty = true;
+        }
+    }
+
+    /// What the last frame had to redo.
+    pub fn last_frame_stats(&self) -> FrameStats {
+        self.frame_stats
+    }
+
+    /// Run `action` once, `delay` after the current app clock.
+    ///
+    /// The app clock only moves when [`Self::advance_clock`] is called, so
+    /// event loops drive it with real elapsed time and tests with virtual
+    /// time.
+    pub fn set_timer(&mut self, delay: Duration, action: AppAction) -> TimerId {
+        self.add_timer(delay, None, action)
+    }
+
+    /// Run `action` every `period` (at least one millisecond).
+    pub fn set_interval(&mut self, period: Duration, action: AppAction) -> TimerId {
+        let period = period.max(Duration::from_millis(1));
+        self.add_timer(period, Some(period), action)
+    }
+
+    /// Cancel a timer. Returns whether it was pending.
+    pub fn cancel_timer(&mut self, id: TimerId) -> bool {
+        if let Some((running, cancelled)) = self.running_timer.as_mut()
+            && *running == id
+        {
+            *cancelled = true;
+            return true;
+        }
+        let before = self.timers.len();
+        self.timers.retain(|t| t.id != id);
+        self.timers.len() != before
+    }
+
+    /// Time elapsed on the app clock.
+    pub fn clock(&self) -> Duration {
+        self.clock
+    }
+
+    /// Time until the next timer is due, if any (zero when overdue).
+    ///
+    /// Useful as the poll timeout of an event loop.
+    pub fn next_timer_in(&self) -> Option<Duration> {
+        self.timers
+            .iter()
+            .map(|t| t.due.saturating_sub(self.clock))
+            .min()
+    }
+
+    /// Advance the app clock by `elapsed`, firing due timers in order.
+    ///
+    /// Intervals that fall due several times within `elapsed` fire once
+    /// per period. Returns the number of timer callbacks run.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error raised by a timer callback; later timers
+    /// stay pending.
+    pub fn advance_clock(&mut self, elapsed: Duration) -> Result<usize> {
+        let target = self.clock.saturating_add(elapsed);
+        let mut fired = 0;
+        while let Some(idx) = self
+            .timers
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.due <= target)
+            .min_by_key(|(_, t)| (t.due, t.id))
+            .map(|(i, _)| i)
+        {
+            let mut timer = self.timers.remove(idx);
+            self.clock = timer.due;
+            self.running_timer = Some((timer.id, false));
+            let res = (timer.action)(self);
+            let cancelled = self.running_timer.take().is_some_and(|(_, c)| c);
+            fired += 1;
+            if let Some(period) = timer.period
+                && !cancelled
+            {
+                timer.due = timer.due.saturating_add(period);
+                self.timers.push(timer);
+            }
+            if matches!(res?, EventResult::Consumed) {
+                self.repaint_all();
+            }
+        }
+        self.clock = target;
+        Ok(fired)
+    }
+
+    /// The most recently rendered frame.
+    pub fn screen(&self) -> &ScreenBuffer {
+        self.render.buffer()
+    }
+
+    /// The most recently rendered frame as an SVG screenshot.
+    pub fn export_svg(&self, options: &crate::export::ExportOptions) -> String {
+        self.screen().to_svg(options)
+    }
+
+    /// The most recently rendered frame as an HTML `<pre>` snippet.
+    pub fn export_html(&self, options: &crate::export::ExportOptions) -> String {
+        self.screen().to_html(options)
+    }
+
+    /// The on-screen rect of a node after scrolling and clipping, or `None`
+    /// if it is not visible (available once layout has run).
+    pub fn visible_rect_of(&self, node: NodeRef) -> Option<Rect> {
+        self.visible.get(&node.0).copied()
+    }
+
+    /// Whether a render is pending.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+            || self.dom.has_restyled()
+            || !self.updates.borrow().is_empty()
+            || self.repaint_requests().next().is_some()
+    }
+
+    /// The app's clipboard.
+    ///
+    /// Copies made through it (including mouse selections) are sent to the
+    /// system clipboard via OSC 52 on the next render. Share it with widgets
+    /// such as [`crate::widget::TextArea::with_clipboard`].
+    pub fn clipboard(&self) -> Clipboard {
+        self.clipboard.clone()
+    }
+
+    /// Enable or disable mouse drag selection over the screen (default on).
+    ///
+    /// When enabled, dragging the mouse highlights cells and releasing the
+    /// button copies their text. Drag events are then not delivered to
+    /// the focused widget.
+    pub fn set_mouse_selection(&mut self, enabled: bool) {
+        self.mouse_selection = enabled;
+        if !enabled {
+            self.clear_selection();
+        }
+    }
+
+    /// Set the style merged into selected cells (default: reverse video).
+    pub fn set_selection_style(&mut self, style: Style) {
+        self.selection_style = style;
+        self.repaint_all();
+    }
+
+    /// The current mouse selection, if any.
+    pub fn selection(&self) -> Option<&ScreenSelection> {
+        self.selection.as_ref()
+    }
+
+    /// Remove the mouse selection highlight.
+    pub fn clear_selection(&mut self) {
+        if self.selection.take().is_some() {
+            self.repaint_all();
+        }
+    }
+
+    /// Mark the app as needing a re-render of the whole screen.
     pub fn request_render(&mut self) {
-        self.dirty = true;
+        self.repaint_all();
     }
 
     /// Poll for TCSS hot reload events (if enabled).
@@ -305,6 +852,7 @@ impl App {
 
         match ev {
             StylesheetEvent::Reloaded { .. } => {
+                self.update_widget_states();
                 let reloaded = self
                     .stylesheet_loader
                     .reload()
@@ -330,6 +878,7 @@ impl App {
     /// This updates the matcher, globals/themes, invalidates the match cache,
     /// and marks the app dirty.
     pub fn reload_stylesheet_string(&mut self, css: &str) -> Result<StylesheetEvent> {
+        self.update_widget_states();
         let ev = self
             .stylesheet_loader
             .reload_string(css)
@@ -339,39 +888,136 @@ impl App {
     }
 
     /// Set the active theme by name (a theme is a class selector with variables).
+    ///
+    /// Stylesheet themes take precedence over built-in themes of the same
+    /// name (see [`crate::tcss::builtin_theme`]).
     pub fn set_active_theme(&mut self, name: Option<&str>) {
         self.active_theme = name.map(str::to_string);
         let mut layer = crate::tcss::VariableMap::new();
         if let Some(name) = self.active_theme.as_deref() {
             // Ignore invalid theme names; they simply result in an empty theme layer.
-            let _ = self.theme_mgr.set_active(name);
-            if let Some(theme) = self.theme_mgr.active_theme() {
+            if self.theme_mgr.set_active(name).is_ok()
+                && let Some(theme) = self.theme_mgr.active_theme()
+            {
+                layer = theme.variables().clone();
+            } else if let Some(theme) = builtin_theme(name) {
                 layer = theme.variables().clone();
             }
         }
         self.vars.set_theme_layer(layer);
+        self.vars_changed = true;
         self.match_cache.invalidate_all();
-        self.dirty = true;
+        // Widgets may restyle from the variables without their computed
+        // style changing, so repaint everything.
+        self.repaint_all();
+    }
+
+    /// The active theme name, if one is set.
+    pub fn active_theme(&self) -> Option<&str> {
+        self.active_theme.as_deref()
+    }
+
+    /// Pick the light or dark variant of a theme family to match the
+    /// terminal's background color, and check again before the first frame
+    /// after the terminal regains focus (its theme may have changed
+    /// meanwhile).
+    ///
+    /// `family` is a stylesheet theme family with `{family}-dark` and
+    /// `{family}-light` themes, or a built-in family such as `"catppuccin"`
+    /// (see [`crate::tcss::theme_variant`]); `None` or a family without the
+    /// detected variant uses the built-in `dark`/`light` themes. Returns the
+    /// detected variant; if the terminal does not report its colors, the
+    /// active theme is left unchanged.
+    pub fn enable_auto_theme(
+        &mut self,
+        querier: Box<dyn TerminalQuerier>,
+        family: Option<&str>,
+    ) -> Option<ThemeVariant> {
+        self.auto_theme = Some(AutoTheme {
+            querier,
+            family: family.map(str::to_string),
+            variant: None,
+            recheck: false,
+        });
+        self.detect_theme()
+    }
+
+    /// Stop following the terminal's theme. The active theme is kept.
+    pub fn disable_auto_theme(&mut self) {
+        self.auto_theme = None;
+    }
+
+    /// Query the terminal's colors now and switch themes if the light/dark
+    /// variant changed. Returns the detected variant, or `None` if auto
+    /// theming is off or the terminal did not answer.
+    pub fn detect_theme(&mut self) -> Option<ThemeVariant> {
+        let auto = self.auto_theme.as_mut()?;
+        let variant = detect_theme_variant(auto.querier.as_mut())?;
+        if auto.variant != Some(variant) {
+            auto.variant = Some(variant);
+            self.apply_theme_variant(variant);
+        }
+        Some(variant)
+    }
+
+    /// The light/dark variant last detected by auto theming.
+    pub fn theme_variant(&self) -> Option<ThemeVariant> {
+        self.auto_theme.as_ref()?.variant
+    }
+
+    fn apply_theme_variant(&mut self, variant: ThemeVariant) {
+        let suffix = match variant {
+            ThemeVariant::Dark => "dark",
+            ThemeVariant::Light => "light",
+        };
+        let family = self.auto_theme.as_ref().and_then(|a| a.family.clone());
+        let name = family
+            .as_deref()
+            .and_then(|family| {
+                let own = format!("{family}-{suffix}");
+                if self.theme_mgr.has_theme(&own) {
+                    Some(own)
+                } else {
+                    theme_variant(family, variant).map(str::to_string)
+                }
+            })
+            .unwrap_or_else(|| builtin_for_variant(variant).name().to_string());
+        self.set_active_theme(Some(&name));
     }
 
     /// Handle an input event (focus management + dispatch).
     ///
-    /// Returns whether the event was consumed.
+    /// Returns whether the event was consumed. Key releases (reported by
+    /// terminals using the Kitty keyboard protocol) are ignored so that
+    /// bindings and widgets fire once per press.
     pub fn handle_event(&mut self, event: &Event) -> Result<EventResult> {
+        self.apply_bindings()?;
+        if matches!(event, Event::FocusGained)
+            && let Some(auto) = self.auto_theme.as_mut()
+        {
+            // Querying reads the terminal, which must not happen while its
+            // input is being dispatched; do it before the next frame.
+            auto.recheck = true;
+            self.dirty = true;
+        }
+        if let Event::Key(key) = event
+            && key.is_release()
+        {
+            return Ok(EventResult::Ignored);
+        }
+
         // Key bindings to application actions.
         if let Event::Key(key) = event
             && let Some(action_name) = self.lookup_binding(key)
+            && self.actions.contains_key(&action_name)
         {
-            // Temporarily remove to avoid borrowing self.actions while calling action(&mut self).
-            if let Some(mut action) = self.actions.remove(&action_name) {
-                let res = action(self);
-                self.actions.insert(action_name.clone(), action);
-                let res = res?;
-                if matches!(res, EventResult::Consumed) {
-                    self.dirty = true;
-                }
-                return Ok(res);
-            }
+            return self.run_action(&action_name);
+        }
+
+        if self.mouse_selection
+            && let Some(res) = self.handle_selection(event)
+        {
+            return Ok(res);
         }
 
         match event {
@@ -382,7 +1028,8 @@ impl App {
             Event::Key(KeyEvent {
                 code: KeyCode::Tab,
                 modifiers,
-            }) => {
+                ..
+            }) if !self.focused_captures_tab() => {
                 // Shift+Tab = previous, Tab = next.
                 if modifiers.contains(Modifiers::SHIFT) {
                     self.dom.focus_mut().focus_previous();
@@ -393,6 +1040,23 @@ impl App {
                 self.dirty = true;
                 return Ok(EventResult::Consumed);
             }
+            Event::Mouse(me)
+                if matches!(
+                    me.kind,
+                    MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                ) =>
+            {
+                let dy = if me.kind == MouseEventKind::ScrollUp {
+                    -WHEEL_LINES
+                } else {
+                    WHEEL_LINES
+                };
+                if let Some(target) = self.hit_test(me.x, me.y)
+                    && self.scroll_nearest(target, dy)
+                {
+                    return Ok(EventResult::Consumed);
+                }
+            }
             Event::Mouse(me) if matches!(me.kind, MouseEventKind::Press) => {
                 // Click-to-focus based on hit test.
                 if let Some(id) = self.hit_test(me.x, me.y) {
@@ -410,10 +1074,39 @@ impl App {
             && let Some(node) = self.dom.node_mut(id)
         {
             let res = node.widget.handle_event(event);
+            self.sync_widget_state(id);
             if matches!(res, EventResult::Consumed) {
+                // A widget using the press (e.g. to start its own drag
+                // selection) takes the drag from screen selection.
+                if let Event::Mouse(me) = event
+                    && me.kind == MouseEventKind::Press
+                {
+                    self.selection = None;
+                }
+                self.dirty_nodes.entry(id).or_default().paint = true;
                 self.dirty = true;
+                return Ok(res);
+            }
+        }
+
+        // Unhandled paging keys scroll the container around the focus.
+        if let Event::Key(key) = event
+            && matches!(key.code, KeyCode::PageUp | KeyCode::PageDown)
+            && let Some(start) = focused.or(self.dom.root())
+        {
+            let page = self
+                .nearest_scrollable(start, |_| true)
+                .and_then(|id| self.scroll.get(id))
+                .map_or(1, |s| s.viewport_height.saturating_sub(1).max(1));
+            let page = i16::try_from(page).unwrap_or(i16::MAX);
+            let dy = if key.code == KeyCode::PageUp {
+                -page
+            } else {
+                page
+            };
+            if self.scroll_nearest(start, dy) {
+                return Ok(EventResult::Consumed);
             }
-            return Ok(res);
         }
         Ok(EventResult::Ignored)
     }
@@ -423,6 +1116,9 @@ impl App {
         &mut self,
         terminal: &mut dyn crate::terminal::Terminal,
     ) -> Result<bool> {
+        self.apply_bindings()?;
+        self.apply_dom_restyles();
+        self.apply_repaint_requests();
         if !self.dirty {
             return Ok(false);
         }
@@ -431,24 +1127,89 @@ impl App {
     }
 
     /// Force a render.
+    ///
+    /// Only nodes whose styles were invalidated are restyled, layout only
+    /// runs when a layout style or the tree changed, and unless something
+    /// requested a full repaint only the damaged areas (dirty nodes and
+    /// nodes that moved) are rendered over a copy of the previous frame.
     pub fn render_frame(&mut self, terminal: &mut dyn crate::terminal::Terminal) -> Result<()> {
+        if let Some(auto) = self.auto_theme.as_mut()
+            && std::mem::take(&mut auto.recheck)
+        {
+            self.detect_theme();
+        }
+        self.apply_bindings()?;
+        self.apply_dom_restyles();
+        self.apply_repaint_requests();
         self.sync_focus_state();
+        self.frame_stats = FrameStats::default();
         self.compute_styles()?;
         self.compute_layout()?;
 
-        self.render.begin_frame();
-        let buf: &mut ScreenBuffer = self.render.buffer_mut();
-        for &id in &self.render_order {
-            let area = self
-                .rects
-                .get(&id)
-                .copied()
-                .unwrap_or(Rect::new(0, 0, 0, 0));
-            if let Some(node) = self.dom.node_mut(id) {
-                node.widget.render(area, buf);
+        for (id, flags) in &self.dirty_nodes {
+            if flags.paint
+                && let Some(rect) = self.visible.get(id)
+            {
+                self.damage.push(*rect);
+            }
+        }
+        // Graphics and the selection highlight are not tracked per area.
+        let full = self.full_repaint
+            || !self.render.buffer().graphics().is_empty()
+            || self.selection.is_some_and(|s| !s.is_empty());
+
+        let mut frame = Frame {
+            rects: &self.rects,
+            regions: &self.scroll_regions,
+            scroll: &self.scroll,
+            computed: &self.computed,
+            damage: None,
+            painted: std::cell::Cell::new(0),
+        };
+        if full {
+            self.render.begin_frame();
+            if let Some(root) = self.dom.root() {
+                let buf = self.render.buffer_mut();
+                render_node(&frame, &mut self.dom, root, buf, Position::new(0, 0));
+            }
+        } else {
+            self.render.begin_frame_retained();
+            if let Some(root) = self.dom.root()
+                && !self.damage.is_empty()
+            {
+                frame.damage = Some(Damage {
+                    areas: &self.damage,
+                    visible: &self.visible,
+                });
+                // Damaged areas are rendered over blanks, as in a full
+                // repaint, then copied over the retained frame.
+                let size = self.render.size();
+                if self.scratch.size() != size {
+                    self.scratch = ScreenBuffer::new(size);
+                }
+                clear_areas(&mut self.scratch, &self.damage);
+                render_node(
+                    &frame,
+                    &mut self.dom,
+                    root,
+                    &mut self.scratch,
+                    Position::new(0, 0),
+                );
+                copy_damaged(&self.scratch, self.render.buffer_mut(), &self.damage);
             }
         }
+        self.frame_stats.painted = frame.painted.get();
+        self.frame_stats.full_repaint = full;
+
+        let buf: &mut ScreenBuffer = self.render.buffer_mut();
+        if let Some(selection) = self.selection.filter(|s| !s.is_empty()) {
+            selection.highlight(buf, &self.selection_style);
+        }
         self.render.end_frame(terminal)?;
+        self.clipboard.flush(terminal)?;
+        self.dirty_nodes.clear();
+        self.damage.clear();
+        self.full_repaint = false;
         self.dirty = false;
         Ok(())
     }
@@ -457,13 +1218,144 @@ impl App {
     pub fn handle_resize(&mut self, new_size: Size) {
         self.render.handle_resize(new_size);
         self.rects.clear();
+        self.visible.clear();
+        self.selection = None;
+        self.layout_dirty = true;
+        self.repaint_all();
+    }
+
+    /// Repaint the whole screen in the next frame.
+    fn repaint_all(&mut self) {
         self.dirty = true;
+        self.full_repaint = true;
+    }
+
+    fn add_timer(
+        &mut self,
+        delay: Duration,
+        period: Option<Duration>,
+        action: AppAction,
+    ) -> TimerId {
+        let id = self.next_timer_id;
+        self.next_timer_id += 1;
+        self.timers.push(Timer {
+            id,
+            due: self.clock.saturating_add(delay),
+            period,
+            action,
+        });
+        id
+    }
+
+    /// Move the listed children of `parent` into the given order.
+    pub(super) fn set_child_order(&mut self, parent: NodeRef, order: &[NodeId]) -> Result<()> {
+        if self.dom.reorder_children(parent, order) {
+            self.sync_layout_edges(parent)?;
+            let tree = self.dom.widget_tree();
+            self.match_cache
+                .invalidate_for_change(tree, parent.0, self.matcher.uses_has());
+            self.layout_dirty = true;
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Create a binding scoped to `node` that queues `apply` on changes,
+    /// and apply the initial value.
+    fn bind_node<T: Clone + 'static>(
+        &mut self,
+        node: NodeRef,
+        source: &Signal<T>,
+        apply: ApplyFn<T>,
+    ) -> Result<BindingId> {
+        if !self.dom.contains(node.0) {
+            return Err(SaorsaTuiError::Widget("bind: unknown node".into()));
+        }
+        let queue = Rc::clone(&self.updates);
+        let id = self
+            .binding_scopes
+            .entry(node.0)
+            .or_default()
+            .bind(source, move |value: &T| {
+                enqueue(&queue, node.0, value, &apply);
+            });
+        self.apply_bindings()?;
+        Ok(id)
+    }
+
+    /// Track mouse drag selection.
+    ///
+    /// Returns `Some` when the event was used by the selection and must not
+    /// be dispatched further. A press starts a new selection (and is still
+    /// dispatched); a release after a drag copies the selected text.
+    fn handle_selection(&mut self, event: &Event) -> Option<EventResult> {
+        match event {
+            Event::Mouse(me) => match me.kind {
+                MouseEventKind::Press => {
+                    self.clear_selection();
+                    self.selection = Some(ScreenSelection::new(Position::new(me.x, me.y)));
+                    None
+                }
+                MouseEventKind::Drag => {
+                    let selection = self.selection.as_mut()?;
+                    selection.extend_to(Position::new(me.x, me.y));
+                    self.repaint_all();
+                    Some(EventResult::Consumed)
+                }
+                MouseEventKind::Release => {
+                    let selection = self.selection?;
+                    if selection.is_empty() {
+                        self.selection = None;
+                        return None;
+                    }
+                    let text = selection.text(self.render.buffer());
+                    if !text.is_empty() {
+                        self.clipboard.set_text(text);
+                    }
+                    self.repaint_all();
+                    Some(EventResult::Consumed)
+                }
+                _ => None,
+            },
+            Event::Key(_) => {
+                self.clear_selection();
+                None
+            }
+            _ => None,
+        }
     }
 
     // ---------------------------
     // Internal: style + layout
     // ---------------------------
 
+    /// Nodes whose widgets changed on their own since their last render.
+    fn repaint_requests(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.dom.node_ids().filter(|&id| {
+            self.dom
+                .widget(NodeRef(id))
+                .is_some_and(|w| w.needs_repaint())
+        })
+    }
+
+    /// Mark nodes whose widgets changed on their own for repainting.
+    fn apply_repaint_requests(&mut self) {
+        let ids: Vec<NodeId> = self.repaint_requests().collect();
+        for id in ids {
+            self.dirty_nodes.entry(id).or_default().paint = true;
+            self.dirty = true;
+        }
+    }
+
+    /// Whether the focused widget takes Tab itself rather than cycling focus.
+    fn focused_captures_tab(&self) -> bool {
+        self.dom
+            .focus()
+            .focused()
+            .and_then(|id| self.dom.widget(NodeRef(id)))
+            .is_some_and(|w| w.captures_tab())
+    }
+
     fn sync_focus_state(&mut self) {
         let focused = self.dom.focus().focused();
         if focused == self.last_focused {
@@ -472,47 +1364,130 @@ impl App {
 
         // Clear previous focus.
         if let Some(prev) = self.last_focused {
+            self.dirty_nodes.entry(prev).or_default().paint = true;
             if let Some(n) = self.dom.widget_tree_mut().get_mut(prev) {
                 n.state.focused = false;
             }
             let tree = self.dom.widget_tree();
-            self.match_cache.invalidate_subtree(tree, prev);
+            self.match_cache
+                .invalidate_for_change(tree, prev, self.matcher.uses_has());
         }
 
         // Set new focus.
         if let Some(now) = focused {
+            self.dirty_nodes.entry(now).or_default().paint = true;
+            self.pending_reveal = Some(now);
             if let Some(n) = self.dom.widget_tree_mut().get_mut(now) {
                 n.state.focused = true;
             }
             let tree = self.dom.widget_tree();
-            self.match_cache.invalidate_subtree(tree, now);
+            self.match_cache
+                .invalidate_for_change(tree, now, self.matcher.uses_has());
         }
 
         self.last_focused = focused;
     }
 
-    fn compute_styles(&mut self) -> Result<()> {
-        self.computed.clear();
-        for id in self.dom.node_ids() {
-            let matches = if let Some(cached) = self.match_cache.get(id) {
-                cached.clone()
-            } else {
-                let m = self.matcher.match_widget(self.dom.widget_tree(), id);
-                self.match_cache.insert(id, m.clone());
-                m
-            };
+    /// Copy a widget's own pseudo-classes (such as `:vim-insert`) into
+    /// the style tree, invalidating its styles when they changed.
+    /// Let the stylesheet loader accept the declared pseudo-classes of
+    /// widgets added since it was created.
+    fn update_widget_states(&mut self) {
+        let mut states = self.stylesheet_loader.widget_states().to_vec();
+        states.extend(declared_states(&self.dom));
+        states.sort_unstable();
+        states.dedup();
+        self.stylesheet_loader.set_widget_states(states);
+    }
 
-            let computed = CascadeResolver::resolve_with_variables(&matches, &self.vars);
-            self.computed.insert(id, computed);
+    fn sync_widget_state(&mut self, id: NodeId) {
+        let Some(classes) = self.dom.widget(NodeRef(id)).map(|w| w.pseudo_classes()) else {
+            return;
+        };
+        let Some(node) = self.dom.widget_tree_mut().get_mut(id) else {
+            return;
+        };
+        if node.state.custom == classes {
+            return;
         }
+        node.state.custom = classes.into_iter().map(String::from).collect();
+        self.dirty_nodes.entry(id).or_default().paint = true;
+        let tree = self.dom.widget_tree();
+        self.match_cache
+            .invalidate_for_change(tree, id, self.matcher.uses_has());
+    }
 
-        // Apply computed style into widgets.
-        for (id, style) in self.computed.iter() {
-            if let Some(node) = self.dom.node_mut(*id) {
-                node.widget.apply_computed_style(style);
+    /// Invalidate the matches that may depend on ids, classes or attributes
+    /// changed through [`Self::dom_mut`].
+    fn apply_dom_restyles(&mut self) {
+        let changed = self.dom.take_restyled();
+        if changed.is_empty() {
+            return;
+        }
+        let uses_has = self.matcher.uses_has();
+        for id in changed {
+            if self.dom.contains(id) {
+                self.match_cache
+                    .invalidate_for_change(self.dom.widget_tree(), id, uses_has);
             }
         }
+        self.dirty = true;
+    }
 
+    /// Re-resolve the styles of nodes whose cached matches were
+    /// invalidated, applying and flagging those whose style changed.
+    fn compute_styles(&mut self) -> Result<()> {
+        // Between tree changes every node has a cached style, so only the
+        // invalidated ones need a look; otherwise scan the whole DOM.
+        let stale: Vec<NodeId> = if self.layout_dirty || self.full_repaint {
+            let dom = &self.dom;
+            self.computed.retain(|id, _| dom.contains(*id));
+            self.dom
+                .node_ids()
+                .filter(|id| self.match_cache.get(*id).is_none() || !self.computed.contains_key(id))
+                .collect()
+        } else {
+            self.match_cache
+                .dirty_ids()
+                .filter(|id| self.dom.contains(*id))
+                .collect()
+        };
+        self.frame_stats.restyled = stale.len();
+        let vars_changed = std::mem::take(&mut self.vars_changed);
+        if vars_changed {
+            let ids: Vec<NodeId> = self.dom.node_ids().collect();
+            for id in ids {
+                if let Some(node) = self.dom.node_mut(id) {
+                    node.widget.apply_variables(&self.vars);
+                }
+            }
+        }
+        for id in stale {
+            if !self.computed.contains_key(&id) {
+                // Newly mounted: pick up the widget's own state first.
+                self.sync_widget_state(id);
+            }
+            let matches = self.matcher.match_widget(self.dom.widget_tree(), id);
+            let computed = CascadeResolver::resolve_with_variables(&matches, &self.vars);
+            self.match_cache.insert(id, matches);
+            if !vars_changed
+                && !self.computed.contains_key(&id)
+                && let Some(node) = self.dom.node_mut(id)
+            {
+                // Newly mounted: catch up with the current variables.
+                node.widget.apply_variables(&self.vars);
+            }
+            if self.computed.get(&id) == Some(&computed) {
+                continue;
+            }
+            if let Some(node) = self.dom.node_mut(id) {
+                node.widget.apply_computed_style(&computed);
+            }
+            let flags = self.dirty_nodes.entry(id).or_default();
+            flags.style = true;
+            flags.paint = true;
+            self.computed.insert(id, computed);
+        }
         Ok(())
     }
 
@@ -614,10 +1589,18 @@ impl App {
             .root()
             .ok_or_else(|| SaorsaTuiError::Widget("DOM has no root".into()))?;
 
-        // Update per-node styles in layout engine.
-        for id in self.dom.node_ids() {
-            let computed = self.computed.get(&id).cloned().unwrap_or_default();
-            let mut taffy_style = computed_to_taffy(&computed);
+        // Update the layout styles of restyled nodes (and the root, which
+        // tracks the terminal size). Taffy only recomputes the subtrees
+        // whose styles or children changed.
+        let restyled = self
+            .dirty_nodes
+            .iter()
+            .filter(|(_, f)| f.style)
+            .map(|(id, _)| *id);
+        let candidates: Vec<NodeId> = std::iter::once(root).chain(restyled).collect();
+        let empty = ComputedStyle::default();
+        for id in candidates {
+            let mut taffy_style = computed_to_taffy(self.computed.get(&id).unwrap_or(&empty));
 
             // Root is always the full terminal viewport.
             if id == root {
@@ -625,35 +1608,226 @@ impl App {
                 taffy_style.size.height = taffy::Dimension::Length(f32::from(size.height));
             }
 
+            let current = self
+                .layout
+                .style(id)
+                .map_err(|e| SaorsaTuiError::Layout(e.to_string()))?;
+            if *current == taffy_style {
+                continue;
+            }
             self.layout
                 .update_style(id, taffy_style)
                 .map_err(|e| SaorsaTuiError::Layout(e.to_string()))?;
+            self.dirty_nodes.entry(id).or_default().layout = true;
+            self.frame_stats.relaid_out += 1;
+        }
+
+        let relayout = self.layout_dirty || self.frame_stats.relaid_out > 0;
+        self.layout_dirty = false;
+        self.frame_stats.layout = relayout;
+        if !relayout {
+            // Restyled nodes may have changed their scrollbars.
+            if self.frame_stats.restyled > 0 {
+                self.update_scroll_regions()?;
+            }
+            self.finish_layout(false);
+            return Ok(());
         }
 
         self.layout
             .compute(size.width, size.height)
             .map_err(|e| SaorsaTuiError::Layout(e.to_string()))?;
 
+        self.render_order.clear();
+        pre_order(self.dom.widget_tree(), root, &mut self.render_order);
+
+        // Taffy locations are relative to the parent; parents come first in
+        // pre-order, so accumulate them into absolute rects.
         self.rects.clear();
-        for id in self.dom.node_ids() {
-            let rect = self
+        for &id in &self.render_order {
+            let mut rect = self
                 .layout
                 .layout_rect(id)
                 .map_err(|e| SaorsaTuiError::Layout(e.to_string()))?;
+            let parent = self.dom.widget_tree().ancestors(id).first().copied();
+            if let Some(origin) = parent.and_then(|p| self.rects.get(&p)) {
+                rect.position.x = rect.position.x.saturating_add(origin.position.x);
+                rect.position.y = rect.position.y.saturating_add(origin.position.y);
+            }
             self.rects.insert(id, rect);
         }
 
-        self.render_order.clear();
-        pre_order(self.dom.widget_tree(), root, &mut self.render_order);
+        self.update_scroll_regions()?;
+        // Content sizes may have changed and clamped scroll offsets.
+        if !self.scroll_regions.is_empty() {
+            self.full_repaint = true;
+        }
+        self.finish_layout(true);
+        Ok(())
+    }
 
+    /// Reveal the pending node and refresh the on-screen rects, damaging
+    /// the old and new areas of nodes that moved.
+    fn finish_layout(&mut self, relaid_out: bool) {
+        let scrolled = self
+            .pending_reveal
+            .take()
+            .is_some_and(|target| self.reveal(target));
+        if scrolled {
+            self.full_repaint = true;
+        }
+        if !relaid_out && !scrolled && !self.visible.is_empty() {
+            return;
+        }
+        let old = std::mem::take(&mut self.visible);
+        self.update_visible_rects();
+        for (id, rect) in &self.visible {
+            match old.get(id) {
+                Some(before) if before == rect => {}
+                Some(before) => self.damage.extend([*before, *rect]),
+                None => self.damage.push(*rect),
+            }
+        }
+        for (id, rect) in &old {
+            if !self.visible.contains_key(id) {
+                self.damage.push(*rect);
+            }
+        }
+    }
+
+    /// Register every clipping container with the scroll manager, keeping
+    /// existing offsets where possible.
+    fn update_scroll_regions(&mut self) -> Result<()> {
+        self.scroll_regions.clear();
+        for &id in &self.render_order {
+            let Some(style) = self.computed.get(&id) else {
+                continue;
+            };
+            let overflow = extract_overflow(style);
+            if !clips(overflow) {
+                continue;
+            }
+            let rect = self.rects.get(&id).copied().unwrap_or_default();
+            let layout = self
+                .layout
+                .box_layout(id)
+                .map_err(|e| SaorsaTuiError::Layout(e.to_string()))?;
+            let region = ScrollRegion::new(rect, &layout, overflow, style);
+            let children = self.dom.widget_tree().children(id);
+            let (width, height) =
+                region.content_size(children.iter().filter_map(|c| self.rects.get(c).copied()));
+            self.scroll.update(
+                id,
+                width,
+                height,
+                region.viewport.size.width,
+                region.viewport.size.height,
+            );
+            self.scroll_regions.insert(id, region);
+        }
+        let live: HashSet<NodeId> = self.scroll_regions.keys().copied().collect();
+        self.scroll.retain(|id| live.contains(&id));
         Ok(())
     }
 
+    fn update_visible_rects(&mut self) {
+        self.visible.clear();
+        let Some(root) = self.dom.root() else {
+            return;
+        };
+        let size = self.render.size();
+        let frame = Frame {
+            rects: &self.rects,
+            regions: &self.scroll_regions,
+            scroll: &self.scroll,
+            computed: &self.computed,
+            damage: None,
+            painted: std::cell::Cell::new(0),
+        };
+        visible_rects(
+            &frame,
+            &self.dom,
+            root,
+            (0, 0),
+            Rect::new(0, 0, size.width, size.height),
+            &mut self.visible,
+        );
+    }
+
+    fn after_scroll(&mut self) {
+        self.update_visible_rects();
+        self.repaint_all();
+    }
+
+    /// Scroll the containers around `node`, innermost first, so that it is
+    /// visible on screen. Returns whether any container scrolled.
+    fn reveal(&mut self, node: NodeId) -> bool {
+        let Some(mut target) = self.rects.get(&node).copied() else {
+            return false;
+        };
+        let mut scrolled = false;
+        for id in self.dom.widget_tree().ancestors(node) {
+            let Some(region) = self.scroll_regions.get(&id) else {
+                continue;
+            };
+            let origin = region.viewport.position;
+            let local = Rect::new(
+                target.position.x.saturating_sub(origin.x),
+                target.position.y.saturating_sub(origin.y),
+                target.size.width,
+                target.size.height,
+            );
+            let before = self.scroll.get(id).map(|s| (s.offset_x, s.offset_y));
+            self.scroll.scroll_to_reveal(id, local);
+            // Where the node now appears, in this container's parent space.
+            if let Some(state) = self.scroll.get(id) {
+                scrolled |= before != Some((state.offset_x, state.offset_y));
+                target.position.x = target.position.x.saturating_sub(state.offset_x);
+                target.position.y = target.position.y.saturating_sub(state.offset_y);
+            }
+        }
+        scrolled
+    }
+
+    /// The nearest container at or above `node` that the user may scroll
+    /// vertically and that satisfies `accept`.
+    fn nearest_scrollable(
+        &self,
+        node: NodeId,
+        accept: impl Fn(&ScrollState) -> bool,
+    ) -> Option<NodeId> {
+        std::iter::once(node)
+            .chain(self.dom.widget_tree().ancestors(node))
+            .find(|id| {
+                self.scroll_regions.get(id).is_some_and(|r| r.scrolls_y())
+                    && self.scroll.get(*id).is_some_and(&accept)
+            })
+    }
+
+    /// Scroll the nearest container around `node` that can move by `dy`.
+    fn scroll_nearest(&mut self, node: NodeId, dy: i16) -> bool {
+        let can_move = |s: &ScrollState| {
+            if dy < 0 {
+                s.offset_y > 0
+            } else {
+                s.offset_y < s.max_offset_y()
+            }
+        };
+        match self.nearest_scrollable(node, can_move) {
+            Some(id) => {
+                self.scroll.scroll_by(id, 0, dy);
+                self.after_scroll();
+                true
+            }
+            None => false,
+        }
+    }
+
     fn hit_test(&self, x: u16, y: u16) -> Option<NodeId> {
-        // Prefer the deepest node whose rect contains the point.
-        // This is a simple approximation without z-index or clipping.
+        // Prefer the deepest node whose visible (scrolled and clipped) rect
+        // contains the point. There is no z-index.
         let mut best: Option<(usize, NodeId)> = None;
-        for (&id, rect) in &self.rects {
+        for (&id, rect) in &self.visible {
             if contains(*rect, x, y) {
                 let depth = self.dom.widget_tree().ancestors(id).len();
                 best = Some(match best {
@@ -680,6 +1854,18 @@ fn pre_order(tree: &WidgetTree, id: NodeId, out: &mut Vec<NodeId>) {
     }
 }
 
+/// The declared pseudo-classes of every widget in `dom`, deduplicated.
+fn declared_states(dom: &Dom) -> Vec<&'static str> {
+    let mut states: Vec<&'static str> = dom
+        .node_ids()
+        .filter_map(|id| dom.widget(NodeRef(id)))
+        .flat_map(|w| w.declared_pseudo_classes().iter().copied())
+        .collect();
+    states.sort_unstable();
+    states.dedup();
+    states
+}
+
 fn contains(rect: Rect, x: u16, y: u16) -> bool {
     let x2 = rect.position.x.saturating_add(rect.size.width);
     let y2 = rect.position.y.saturating_add(rect.size.height);
@@ -0,0 +1,421 @@
+//! Scrollable containers for the App runtime.
+//!
+//! Nodes with `overflow` other than `visible` clip their children to a
+//! viewport (the border box minus borders and the scrollbar gutter). Their
+//! children are rendered into an off-screen buffer in content coordinates
+//! and the window at the current scroll offset is copied into the frame.
+
+use std::collections::HashMap;
+
+use crate::buffer::ScreenBuffer;
+use crate::cell::Cell;
+use crate::compositor::blend;
+use crate::geometry::{Position, Rect, Size};
+use crate::layout::engine::{round_position, round_size};
+use crate::layout::scroll::{OverflowBehavior, ScrollManager, ScrollState};
+use crate::style::Style;
+use crate::tcss::{ComputedStyle, CssValue, PropertyName};
+
+use super::dom::{Dom, NodeId};
+
+/// Wheel notches scroll this many lines.
+pub(super) const WHEEL_LINES: i16 = 3;
+
+const THUMB: &str = "█";
+
+/// Geometry and styling of a clipping container, in absolute layout
+/// coordinates (before any scroll offsets are applied).
+#[derive(Clone, Debug)]
+pub(super) struct ScrollRegion {
+    pub viewport: Rect,
+    overflow_x: OverflowBehavior,
+    overflow_y: OverflowBehavior,
+    bar_width: u16,
+    bar_height: u16,
+    thumb: Style,
+    track: Style,
+}
+
+impl ScrollRegion {
+    /// Build the region for a container from its rect and Taffy box.
+    pub fn new(
+        rect: Rect,
+        layout: &taffy::Layout,
+        (overflow_x, overflow_y): (OverflowBehavior, OverflowBehavior),
+        style: &ComputedStyle,
+    ) -> Self {
+        let left = round_position(layout.border.left);
+        let top = round_position(layout.border.top);
+        let right = round_position(layout.border.right);
+        let bottom = round_position(layout.border.bottom);
+        let bar_width = round_size(layout.scrollbar_size.width);
+        let bar_height = round_size(layout.scrollbar_size.height);
+        let viewport = Rect::new(
+            rect.position.x.saturating_add(left),
+            rect.position.y.saturating_add(top),
+            rect.size
+                .width
+                .saturating_sub(left + right)
+                .saturating_sub(bar_width),
+            rect.size
+                .height
+                .saturating_sub(top + bottom)
+                .saturating_sub(bar_height),
+        );
+        let color = |prop| match style.get(&prop) {
+            Some(CssValue::Color(c)) => Some(c.clone()),
+            _ => None,
+        };
+        let mut thumb = Style::new();
+        if let Some(c) = color(PropertyName::ScrollbarColor) {
+            thumb = thumb.fg(c);
+        }
+        let mut track = Style::new();
+        if let Some(c) = color(PropertyName::ScrollbarBackground) {
+            thumb = thumb.bg(c.clone());
+            track = track.bg(c);
+        }
+        Self {
+            viewport,
+            overflow_x,
+            overflow_y,
+            bar_width,
+            bar_height,
+            thumb,
+            track,
+        }
+    }
+
+    /// Whether the user may scroll the region vertically (`overflow: hidden`
+    /// clips but only scrolls programmatically).
+    pub fn scrolls_y(&self) -> bool {
+        matches!(
+            self.overflow_y,
+            OverflowBehavior::Scroll | OverflowBehavior::Auto
+        )
+    }
+
+    /// Content extent `(width, height)` covering `children`, at least the
+    /// size of the viewport.
+    pub fn content_size(&self, children: impl Iterator<Item = Rect>) -> (u16, u16) {
+        let (mut right, mut bottom) = (self.viewport.right(), self.viewport.bottom());
+        for child in children {
+            right = right.max(child.right());
+            bottom = bottom.max(child.bottom());
+        }
+        (
+            right - self.viewport.position.x,
+            bottom - self.viewport.position.y,
+        )
+    }
+}
+
+/// Whether a computed overflow pair makes a node a clipping container.
+pub(super) fn clips(overflow: (OverflowBehavior, OverflowBehavior)) -> bool {
+    overflow != (OverflowBehavior::Visible, OverflowBehavior::Visible)
+}
+
+/// Read-only layout state shared by the render and hit-test passes.
+pub(super) struct Frame<'a> {
+    pub rects: &'a HashMap<NodeId, Rect>,
+    pub regions: &'a HashMap<NodeId, ScrollRegion>,
+    pub scroll: &'a ScrollManager,
+    pub computed: &'a HashMap<NodeId, ComputedStyle>,
+    /// Only repaint nodes whose visible rect meets the damaged areas;
+    /// `None` repaints everything.
+    pub damage: Option<Damage<'a>>,
+    /// Number of widgets rendered.
+    pub painted: std::cell::Cell<usize>,
+}
+
+/// Screen areas to repaint, with the on-screen rect of every visible node.
+#[derive(Clone, Copy)]
+pub(super) struct Damage<'a> {
+    pub areas: &'a [Rect],
+    pub visible: &'a HashMap<NodeId, Rect>,
+}
+
+impl Frame<'_> {
+    /// Whether `id` must be rendered in this frame.
+    fn paints(&self, id: NodeId) -> bool {
+        let Some(damage) = self.damage else {
+            return true;
+        };
+        damage
+            .visible
+            .get(&id)
+            .is_some_and(|r| damage.areas.iter().any(|a| a.intersects(r)))
+    }
+
+    fn region(&self, id: NodeId) -> Option<(&ScrollRegion, &ScrollState)> {
+        Some((self.regions.get(&id)?, self.scroll.get(id)?))
+    }
+
+    /// Opacity and `background: transparent` of `id`, if either means the
+    /// node must be blended rather than painted over what is beneath it.
+    fn blend(&self, id: NodeId) -> Option<(f32, bool)> {
+        let computed = self.computed.get(&id)?;
+        let opacity = match computed.get(&PropertyName::Opacity) {
+            Some(CssValue::Float(f)) => f.clamp(0.0, 1.0),
+            Some(CssValue::Integer(n)) => (*n).clamp(0, 1) as f32,
+            _ => 1.0,
+        };
+        let transparent = matches!(
+            computed.get(&PropertyName::Background),
+            Some(CssValue::Keyword(k)) if k.eq_ignore_ascii_case("transparent")
+        );
+        (opacity < 1.0 || transparent).then_some((opacity, transparent))
+    }
+}
+
+/// Render `id` and its subtree into `buf`, whose origin is at absolute
+/// layout position `origin`.
+///
+/// Translucent nodes and nodes with `background: transparent` are rendered
+/// off-screen and alpha-blended over what is already in `buf`.
+pub(super) fn render_node(
+    frame: &Frame<'_>,
+    dom: &mut Dom,
+    id: NodeId,
+    buf: &mut ScreenBuffer,
+    origin: Position,
+) {
+    let rect = frame.rects.get(&id).copied().unwrap_or_default();
+    match frame.blend(id) {
+        // The subtree is clipped to the node.
+        Some(_) if !frame.paints(id) => {}
+        Some((opacity, transparent)) => {
+            let mut layer = ScreenBuffer::new(rect.size);
+            paint_node(frame, dom, id, &mut layer, rect.position);
+            blend_onto(&layer, buf, relative_to(rect, origin), opacity, transparent);
+        }
+        None => paint_node(frame, dom, id, buf, origin),
+    }
+}
+
+/// Blend `layer` over the `target` area of `buf`.
+fn blend_onto(
+    layer: &ScreenBuffer,
+    buf: &mut ScreenBuffer,
+    target: Rect,
+    opacity: f32,
+    transparent: bool,
+) {
+    let x0 = usize::from(target.position.x);
+    let width = usize::from(layer.width()).min(usize::from(buf.width()).saturating_sub(x0));
+    for y in 0..layer.height() {
+        let ty = target.position.y.saturating_add(y);
+        let (Some(top), Some(below)) = (layer.get_row(y), buf.get_row(ty)) else {
+            continue;
+        };
+        let mut cells: Vec<Cell> = top[..width]
+            .iter()
+            .zip(&below[x0..x0 + width])
+            .map(|(top, below)| blend::blend_cell(top, below, opacity, transparent))
+            .collect();
+        blend::normalize_row(&mut cells);
+        for (x, cell) in cells.into_iter().enumerate() {
+            if !cell.is_continuation() {
+                buf.set(target.position.x + x as u16, ty, cell);
+            }
+        }
+    }
+}
+
+/// Paint `id` and its subtree directly into `buf`.
+fn paint_node(
+    frame: &Frame<'_>,
+    dom: &mut Dom,
+    id: NodeId,
+    buf: &mut ScreenBuffer,
+    origin: Position,
+) {
+    let rect = frame.rects.get(&id).copied().unwrap_or_default();
+    let paints = frame.paints(id);
+    if paints && let Some(node) = dom.node_mut(id) {
+        node.widget.render(relative_to(rect, origin), buf);
+        frame.painted.set(frame.painted.get() + 1);
+    }
+    let children = dom.widget_tree().children(id).to_vec();
+
+    let Some((region, state)) = frame.region(id) else {
+        for child in children {
+            render_node(frame, dom, child, buf, origin);
+        }
+        return;
+    };
+    // Children are clipped to the container.
+    if !paints {
+        return;
+    }
+
+    let viewport = region.viewport;
+    let mut content = ScreenBuffer::new(Size::new(state.content_width, state.content_height));
+    let window = Rect::new(
+        viewport.position.x.saturating_add(state.offset_x),
+        viewport.position.y.saturating_add(state.offset_y),
+        viewport.size.width,
+        viewport.size.height,
+    );
+    for child in children {
+        let visible = frame
+            .rects
+            .get(&child)
+            .is_some_and(|r| r.intersects(&window));
+        if visible {
+            render_node(frame, dom, child, &mut content, viewport.position);
+        }
+    }
+
+    let target = relative_to(viewport, origin);
+    for y in 0..viewport.size.height {
+        for x in 0..viewport.size.width {
+            let Some(cell) = content.get(state.offset_x + x, state.offset_y + y) else {
+                continue;
+            };
+            if cell.is_blank() || cell.is_continuation() {
+                continue;
+            }
+            buf.set(target.position.x + x, target.position.y + y, cell.clone());
+        }
+    }
+    draw_scrollbars(region, state, target, buf);
+}
+
+/// Compute the on-screen rect of every visible node, after scroll offsets
+/// and clipping.
+pub(super) fn visible_rects(
+    frame: &Frame<'_>,
+    dom: &Dom,
+    id: NodeId,
+    shift: (i32, i32),
+    clip: Rect,
+    out: &mut HashMap<NodeId, Rect>,
+) {
+    let rect = frame.rects.get(&id).copied().unwrap_or_default();
+    if let Some(visible) = translate(rect, shift).and_then(|r| r.intersection(&clip)) {
+        out.insert(id, visible);
+    }
+
+    let (shift, clip) = match frame.region(id) {
+        Some((region, state)) => {
+            let viewport = translate(region.viewport, shift).and_then(|v| v.intersection(&clip));
+            let Some(viewport) = viewport else {
+                return;
+            };
+            (
+                (
+                    shift.0 - i32::from(state.offset_x),
+                    shift.1 - i32::from(state.offset_y),
+                ),
+                viewport,
+            )
+        }
+        None => (shift, clip),
+    };
+    for &child in dom.widget_tree().children(id) {
+        visible_rects(frame, dom, child, shift, clip, out);
+    }
+}
+
+fn relative_to(rect: Rect, origin: Position) -> Rect {
+    Rect::new(
+        rect.position.x.saturating_sub(origin.x),
+        rect.position.y.saturating_sub(origin.y),
+        rect.size.width,
+        rect.size.height,
+    )
+}
+
+/// Move `rect` by `shift`, cropping whatever ends up left of or above the
+/// screen origin.
+fn translate(rect: Rect, (dx, dy): (i32, i32)) -> Option<Rect> {
+    let x = i32::from(rect.position.x) + dx;
+    let y = i32::from(rect.position.y) + dy;
+    let right = x + i32::from(rect.size.width);
+    let bottom = y + i32::from(rect.size.height);
+    let clamp = |v: i32| v.clamp(0, i32::from(u16::MAX)) as u16;
+    let (x, y, right, bottom) = (clamp(x), clamp(y), clamp(right), clamp(bottom));
+    (right > x && bottom > y).then(|| Rect::new(x, y, right - x, bottom - y))
+}
+
+/// Thumb `(start, length)` within a track of `track` cells.
+fn thumb(track: u16, content: u16, offset: u16) -> (u16, u16) {
+    if content <= track || track == 0 {
+        return (0, track);
+    }
+    let track32 = u32::from(track);
+    let len = (track32 * track32 / u32::from(content)).max(1);
+    let max_offset = u32::from(content - track);
+    let start =
+        ((track32 - len) * u32::from(offset.min(content - track)) + max_offset / 2) / max_offset;
+    (start as u16, len as u16)
+}
+
+fn draw_scrollbars(
+    region: &ScrollRegion,
+    state: &ScrollState,
+    viewport: Rect,
+    buf: &mut ScreenBuffer,
+) {
+    let show = |behavior, overflows| {
+        behavior == OverflowBehavior::Scroll || (behavior == OverflowBehavior::Auto && overflows)
+    };
+    let cell = |on_thumb: bool| {
+        if on_thumb {
+            Cell::new(THUMB, region.thumb.clone())
+        } else {
+            Cell::new(" ", region.track.clone())
+        }
+    };
+
+    if region.bar_width > 0 && show(region.overflow_y, state.can_scroll_y()) {
+        let (start, len) = thumb(viewport.size.height, state.content_height, state.offset_y);
+        for dy in 0..viewport.size.height {
+            let on_thumb = dy >= start && dy < start + len;
+            for dx in 0..region.bar_width {
+                buf.set(
+                    viewport.right() + dx,
+                    viewport.position.y + dy,
+                    cell(on_thumb),
+                );
+            }
+        }
+    }
+    if region.bar_height > 0 && show(region.overflow_x, state.can_scroll_x()) {
+        let (start, len) = thumb(viewport.size.width, state.content_width, state.offset_x);
+        for dx in 0..viewport.size.width {
+            let on_thumb = dx >= start && dx < start + len;
+            for dy in 0..region.bar_height {
+                buf.set(
+                    viewport.position.x + dx,
+                    viewport.bottom() + dy,
+                    cell(on_thumb),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumb_is_proportional() {
+        assert_eq!(thumb(10, 10, 0), (0, 10));
+        assert_eq!(thumb(10, 40, 0), (0, 2));
+        assert_eq!(thumb(10, 40, 30), (8, 2));
+        assert_eq!(thumb(10, 40, 15), (4, 2));
+        // Never thinner than one cell.
+        assert_eq!(thumb(4, 1000, 0), (0, 1));
+    }
+
+    #[test]
+    fn translate_crops_at_origin() {
+        let r = Rect::new(2, 2, 4, 4);
+        assert_eq!(translate(r, (0, -3)), Some(Rect::new(2, 0, 4, 3)));
+        assert_eq!(translate(r, (0, -6)), None);
+        assert_eq!(translate(r, (1, 1)), Some(Rect::new(3, 3, 4, 4)));
+    }
+}
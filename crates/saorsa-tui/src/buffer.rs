@@ -1,14 +1,39 @@
 //! Screen buffer — a 2D grid of terminal cells.
 
 use crate::cell::Cell;
-use crate::geometry::Size;
+use crate::export::{ExportOptions, export_html, export_svg};
+use crate::geometry::{Position, Size};
+use crate::graphics::GraphicPlacement;
 
 /// A 2D grid of terminal cells representing one frame of terminal content.
-#[derive(Clone, Debug)]
+///
+/// Besides cells, a frame carries the [`GraphicPlacement`]s that widgets
+/// registered for inline images.
+#[derive(Debug)]
 pub struct ScreenBuffer {
     cells: Vec<Cell>,
     width: u16,
     height: u16,
+    graphics: Vec<GraphicPlacement>,
+}
+
+impl Clone for ScreenBuffer {
+    fn clone(&self) -> Self {
+        Self {
+            cells: self.cells.clone(),
+            width: self.width,
+            height: self.height,
+            graphics: self.graphics.clone(),
+        }
+    }
+
+    /// Reuses the existing cells' allocations.
+    fn clone_from(&mut self, source: &Self) {
+        self.cells.clone_from(&source.cells);
+        self.width = source.width;
+        self.height = source.height;
+        self.graphics.clone_from(&source.graphics);
+    }
 }
 
 impl ScreenBuffer {
@@ -19,6 +44,7 @@ impl ScreenBuffer {
             cells: vec![Cell::blank(); len],
             width: size.width,
             height: size.height,
+            graphics: Vec::new(),
         }
     }
 
@@ -37,11 +63,32 @@ impl ScreenBuffer {
         self.height
     }
 
-    /// Clear the buffer, resetting all cells to blank.
+    /// Clear the buffer, resetting all cells to blank and dropping
+    /// graphic placements.
     pub fn clear(&mut self) {
         for cell in &mut self.cells {
             *cell = Cell::blank();
         }
+        self.graphics.clear();
+    }
+
+    /// Drop all graphic placements, keeping the cells.
+    pub(crate) fn clear_graphics(&mut self) {
+        self.graphics.clear();
+    }
+
+    /// Register an image placement for this frame.
+    ///
+    /// The caller is expected to have filled the placement's cells already.
+    /// Any cell written inside the area afterwards marks the placement as
+    /// occluded, hiding the image.
+    pub fn place_graphic(&mut self, placement: GraphicPlacement) {
+        self.graphics.push(placement);
+    }
+
+    /// Image placements registered for this frame, in drawing order.
+    pub fn graphics(&self) -> &[GraphicPlacement] {
+        &self.graphics
     }
 
     /// Resize the buffer. Contents are lost (filled with blanks).
@@ -51,6 +98,7 @@ impl ScreenBuffer {
         let len = usize::from(size.width) * usize::from(size.height);
         self.cells.clear();
         self.cells.resize(len, Cell::blank());
+        self.graphics.clear();
     }
 
     /// Get a reference to the cell at (x, y), or `None` if out of bounds.
@@ -88,6 +136,15 @@ impl ScreenBuffer {
             return;
         }
 
+        if !self.graphics.is_empty() {
+            let pos = Position::new(x, y);
+            for g in &mut self.graphics {
+                if g.area.contains(pos) {
+                    g.occluded = true;
+                }
+            }
+        }
+
         let is_wide = cell.is_wide();
 
         // If this is a wide character at the last column (continuation would be out of bounds),
@@ -168,6 +225,16 @@ impl ScreenBuffer {
         }
     }
 
+    /// Export the buffer as an SVG screenshot. See [`export_svg`].
+    pub fn to_svg(&self, options: &ExportOptions) -> String {
+        export_svg(self, options)
+    }
+
+    /// Export the buffer as an HTML `<pre>` snippet. See [`export_html`].
+    pub fn to_html(&self, options: &ExportOptions) -> String {
+        export_html(self, options)
+    }
+
     /// Compute the differences between this buffer and a previous buffer.
     /// Returns a list of cell changes needed to update the terminal.
     pub fn diff(&self, previous: &ScreenBuffer) -> Vec<CellChange> {
@@ -663,4 +730,33 @@ mod tests {
             }
         }
     }
+
+    fn placement(area: crate::geometry::Rect) -> GraphicPlacement {
+        use crate::graphics::{ImageData, ImageProtocol};
+        let img = match ImageData::from_rgba(1, 1, vec![0, 0, 0, 255]) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        };
+        GraphicPlacement::new(1, area, ImageProtocol::Kitty, std::sync::Arc::new(img))
+    }
+
+    #[test]
+    fn writes_inside_placement_occlude_it() {
+        use crate::geometry::Rect;
+        let mut buf = ScreenBuffer::new(Size::new(10, 5));
+        buf.place_graphic(placement(Rect::new(2, 1, 3, 2)));
+        buf.set(0, 0, Cell::new("x", Style::default()));
+        assert!(!buf.graphics()[0].occluded);
+        buf.set(3, 2, Cell::new("x", Style::default()));
+        assert!(buf.graphics()[0].occluded);
+    }
+
+    #[test]
+    fn clear_drops_placements() {
+        use crate::geometry::Rect;
+        let mut buf = ScreenBuffer::new(Size::new(10, 5));
+        buf.place_graphic(placement(Rect::new(0, 0, 1, 1)));
+        buf.clear();
+        assert!(buf.graphics().is_empty());
+    }
 }
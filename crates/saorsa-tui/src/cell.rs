@@ -4,7 +4,7 @@ use crate::style::Style;
 use unicode_width::UnicodeWidthStr;
 
 /// A single cell in the terminal screen buffer.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Cell {
     /// The grapheme cluster displayed in this cell.
     pub grapheme: String,
@@ -14,6 +14,24 @@ pub struct Cell {
     pub width: u8,
 }
 
+impl Clone for Cell {
+    fn clone(&self) -> Self {
+        Self {
+            grapheme: self.grapheme.clone(),
+            style: self.style.clone(),
+            width: self.width,
+        }
+    }
+
+    /// Reuses the grapheme's allocation, which makes copying whole frames
+    /// cheap.
+    fn clone_from(&mut self, source: &Self) {
+        self.grapheme.clone_from(&source.grapheme);
+        self.style.clone_from(&source.style);
+        self.width = source.width;
+    }
+}
+
 impl Cell {
     /// Create a new cell, auto-detecting width from the grapheme.
     pub fn new(grapheme: impl Into<String>, style: Style) -> Self {
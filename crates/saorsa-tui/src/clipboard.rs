@@ -0,0 +1,130 @@
+//! System clipboard access via OSC 52.
+//!
+//! Terminals that support OSC 52 place base64-encoded text on the system
+//! clipboard, which works over SSH and inside multiplexers (the sequence is
+//! wrapped for pass-through). Because the framework never reads the system
+//! clipboard back, [`Clipboard`] also keeps the last copied text so that
+//! in-app paste works without a round trip through the terminal.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::error::Result;
+use crate::terminal::Terminal;
+use crate::terminal::multiplexer::wrap_sequence;
+
+/// Build the OSC 52 sequence that copies `text` to the system clipboard.
+pub fn osc52_copy(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[derive(Debug, Default)]
+struct ClipboardState {
+    contents: Option<String>,
+    pending: Vec<String>,
+}
+
+/// A shared clipboard register.
+///
+/// Cloning a `Clipboard` yields a handle to the same register, so widgets
+/// and the app runtime can share one. Copies are queued and written to the
+/// terminal by [`Clipboard::flush`].
+#[derive(Clone, Debug, Default)]
+pub struct Clipboard {
+    state: Rc<RefCell<ClipboardState>>,
+}
+
+impl Clipboard {
+    /// Create an empty clipboard.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy `text`, queueing it for the system clipboard.
+    pub fn set_text(&self, text: impl Into<String>) {
+        let text = text.into();
+        let mut state = self.state.borrow_mut();
+        state.pending.push(text.clone());
+        state.contents = Some(text);
+    }
+
+    /// The most recently copied text, if any.
+    pub fn text(&self) -> Option<String> {
+        self.state.borrow().contents.clone()
+    }
+
+    /// Whether copies are waiting to be written to the terminal.
+    pub fn has_pending(&self) -> bool {
+        !self.state.borrow().pending.is_empty()
+    }
+
+    /// Write queued copies to the terminal as OSC 52 sequences.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the terminal fails.
+    pub fn flush(&self, terminal: &mut dyn Terminal) -> Result<()> {
+        let pending = std::mem::take(&mut self.state.borrow_mut().pending);
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let kind = terminal.multiplexer();
+        let output: String = pending
+            .iter()
+            .map(|text| wrap_sequence(&osc52_copy(text), kind))
+            .collect();
+        terminal.write_raw(output.as_bytes())?;
+        terminal.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::{MultiplexerKind, TestBackend};
+
+    #[test]
+    fn osc52_encodes_base64() {
+        assert_eq!(osc52_copy("hello"), "\x1b]52;c;aGVsbG8=\x07");
+    }
+
+    #[test]
+    fn clones_share_contents() {
+        let a = Clipboard::new();
+        let b = a.clone();
+        assert!(a.text().is_none());
+        b.set_text("shared");
+        assert_eq!(a.text().as_deref(), Some("shared"));
+        assert!(a.has_pending());
+    }
+
+    #[test]
+    fn flush_writes_and_drains() {
+        let clipboard = Clipboard::new();
+        clipboard.set_text("hi");
+        let mut backend = TestBackend::new(10, 2);
+        assert!(clipboard.flush(&mut backend).is_ok());
+        assert_eq!(backend.buffer(), b"\x1b]52;c;aGk=\x07");
+        assert!(!clipboard.has_pending());
+
+        backend.clear_buffer();
+        assert!(clipboard.flush(&mut backend).is_ok());
+        assert!(backend.buffer().is_empty());
+        assert_eq!(clipboard.text().as_deref(), Some("hi"));
+    }
+
+    #[test]
+    fn flush_wraps_for_tmux() {
+        let clipboard = Clipboard::new();
+        clipboard.set_text("hi");
+        let mut backend = TestBackend::new(10, 2).with_multiplexer(MultiplexerKind::Tmux);
+        assert!(clipboard.flush(&mut backend).is_ok());
+        assert_eq!(
+            backend.buffer(),
+            b"\x1bPtmux;\x1b\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+    }
+}
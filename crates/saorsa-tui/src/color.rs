@@ -61,6 +61,27 @@ pub enum NamedColor {
     BrightWhite,
 }
 
+/// The xterm default values of the 16 ANSI colors, indexed like
+/// [`NamedColor`].
+pub(crate) const XTERM_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
 impl Color {
     /// Parse a hex color string like `"#rrggbb"` or `"#rgb"`.
     pub fn from_hex(hex: &str) -> Result<Self> {
@@ -110,6 +131,47 @@ impl Color {
             _ => None,
         }
     }
+
+    /// Approximate RGB value of this color.
+    ///
+    /// Named and low palette colors use the xterm defaults (terminals may
+    /// differ); indices 16-255 follow the standard 6x6x6 cube and grey ramp.
+    /// Returns `None` for [`Color::Reset`].
+    pub fn to_rgb(&self) -> Option<(u8, u8, u8)> {
+        match self {
+            Self::Rgb { r, g, b } => Some((*r, *g, *b)),
+            Self::Named(named) => {
+                Some(XTERM_PALETTE[usize::from(crate::renderer::named_to_index(named))])
+            }
+            Self::Indexed(i @ 0..=15) => Some(XTERM_PALETTE[usize::from(*i)]),
+            Self::Indexed(i @ 16..=231) => {
+                let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+                let i = i - 16;
+                Some((level(i / 36), level(i / 6 % 6), level(i % 6)))
+            }
+            Self::Indexed(i) => {
+                let grey = 8 + (i - 232) * 10;
+                Some((grey, grey, grey))
+            }
+            Self::Reset => None,
+        }
+    }
+
+    /// WCAG relative luminance (0.0 black to 1.0 white) of an RGB color.
+    ///
+    /// Returns `None` for palette and reset colors, whose RGB value is
+    /// defined by the terminal.
+    pub fn relative_luminance(&self) -> Option<f32> {
+        use crate::renderer::srgb_to_linear;
+        match self {
+            Self::Rgb { r, g, b } => Some(
+                0.2126 * srgb_to_linear(*r)
+                    + 0.7152 * srgb_to_linear(*g)
+                    + 0.0722 * srgb_to_linear(*b),
+            ),
+            _ => None,
+        }
+    }
 }
 
 impl From<Color> for crossterm::style::Color {
@@ -150,6 +212,28 @@ impl From<&Color> for crossterm::style::Color {
 mod tests {
     use super::*;
 
+    #[test]
+    fn to_rgb_resolves_palette_colors() {
+        assert_eq!(Color::Named(NamedColor::Red).to_rgb(), Some((205, 0, 0)));
+        assert_eq!(Color::Indexed(9).to_rgb(), Some((255, 0, 0)));
+        assert_eq!(Color::Indexed(16).to_rgb(), Some((0, 0, 0)));
+        assert_eq!(Color::Indexed(196).to_rgb(), Some((255, 0, 0)));
+        assert_eq!(Color::Indexed(231).to_rgb(), Some((255, 255, 255)));
+        assert_eq!(Color::Indexed(244).to_rgb(), Some((128, 128, 128)));
+        assert_eq!(Color::Reset.to_rgb(), None);
+    }
+
+    #[test]
+    fn relative_luminance_of_rgb() {
+        let lum = |r, g, b| Color::Rgb { r, g, b }.relative_luminance();
+        assert_eq!(lum(0, 0, 0), Some(0.0));
+        assert!(lum(255, 255, 255).is_some_and(|l| (l - 1.0).abs() < 1e-4));
+        // Green contributes most, blue least.
+        assert!(lum(0, 255, 0) > lum(255, 0, 0));
+        assert!(lum(255, 0, 0) > lum(0, 0, 255));
+        assert_eq!(Color::Named(NamedColor::White).relative_luminance(), None);
+    }
+
     #[test]
     fn hex_6_digit() {
         let c = Color::from_hex("#1e1e2e").ok();
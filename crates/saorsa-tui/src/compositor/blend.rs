@@ -0,0 +1,231 @@
+//! Cell-level alpha blending for translucent layers.
+//!
+//! Colors are mixed in RGB space. Palette colors resolve through
+//! [`Color::to_rgb`]; the renderer later downgrades the mixed RGB value to
+//! whatever the terminal supports, so 256- and 16-color terminals get the
+//! nearest palette entry. Colors that cannot be resolved (the terminal's
+//! default foreground/background) cannot be mixed, so the blend falls back
+//! to picking one side and dimming the text.
+
+use crate::cell::Cell;
+use crate::color::Color;
+
+/// Mix `top` over `below` with `alpha` in `0.0..=1.0`.
+///
+/// Returns `None` when either color has no RGB value ([`Color::Reset`]).
+pub fn mix_colors(top: &Color, below: &Color, alpha: f32) -> Option<Color> {
+    let (tr, tg, tb) = top.to_rgb()?;
+    let (br, bg, bb) = below.to_rgb()?;
+    let alpha = alpha.clamp(0.0, 1.0);
+    let mix = |t: u8, b: u8| (f32::from(t) * alpha + f32::from(b) * (1.0 - alpha)).round() as u8;
+    Some(Color::Rgb {
+        r: mix(tr, br),
+        g: mix(tg, bg),
+        b: mix(tb, bb),
+    })
+}
+
+/// Blend two optional colors, where `None` is the terminal default.
+///
+/// Falls back to whichever side dominates when the colors cannot be mixed.
+pub fn blend_colors(top: Option<&Color>, below: Option<&Color>, alpha: f32) -> Option<Color> {
+    match (top, below) {
+        (Some(t), Some(b)) => {
+            mix_colors(t, b, alpha).or_else(|| Some(if alpha >= 0.5 { t } else { b }.clone()))
+        }
+        _ if alpha >= 0.5 => top.cloned(),
+        _ => below.cloned(),
+    }
+}
+
+/// [`mix_colors`] for optional colors; `None` if either is missing.
+fn mix_optional(top: Option<&Color>, below: Option<&Color>, alpha: f32) -> Option<Color> {
+    mix_colors(top?, below?, alpha)
+}
+
+/// Blend a cell from a layer with `alpha` opacity over the cell below it.
+///
+/// A blank top cell lets the glyph below show through, tinted toward the top
+/// background. A visible top glyph is drawn with its foreground mixed into
+/// the background below. With `transparent` set, top cells without a
+/// background keep the background below, and blank ones are fully
+/// see-through.
+pub fn blend_cell(top: &Cell, below: &Cell, alpha: f32, transparent: bool) -> Cell {
+    let alpha = alpha.clamp(0.0, 1.0);
+    if alpha <= 0.0 || top.is_continuation() {
+        return below.clone();
+    }
+    let blank = top.grapheme == " ";
+    let see_through = transparent && top.style.bg.is_none();
+    if see_through && blank {
+        return below.clone();
+    }
+    if alpha >= 1.0 && !see_through {
+        return top.clone();
+    }
+
+    if blank {
+        let mut cell = below.clone();
+        cell.style.bg = blend_colors(top.style.bg.as_ref(), below.style.bg.as_ref(), alpha);
+        match mix_optional(top.style.bg.as_ref(), below.style.fg.as_ref(), alpha) {
+            Some(fg) => cell.style.fg = Some(fg),
+            None => cell.style.dim = true,
+        }
+        return cell;
+    }
+
+    let mut cell = top.clone();
+    cell.style.bg = if see_through {
+        below.style.bg.clone()
+    } else {
+        blend_colors(top.style.bg.as_ref(), below.style.bg.as_ref(), alpha)
+    };
+    if alpha < 1.0 {
+        match mix_optional(top.style.fg.as_ref(), below.style.bg.as_ref(), alpha) {
+            Some(fg) => cell.style.fg = Some(fg),
+            None => cell.style.dim = true,
+        }
+    }
+    cell
+}
+
+/// Repair wide characters split by blending, in place.
+///
+/// A wide cell whose continuation was replaced becomes a blank, and a
+/// continuation cell without its wide cell becomes a blank too; both keep
+/// their background.
+pub(crate) fn normalize_row(cells: &mut [Cell]) {
+    for x in 0..cells.len() {
+        let orphan = if cells[x].is_continuation() {
+            x == 0 || !cells[x - 1].is_wide()
+        } else if cells[x].is_wide() {
+            !cells.get(x + 1).is_some_and(Cell::is_continuation)
+        } else {
+            false
+        };
+        if orphan {
+            let mut blank = Cell::blank();
+            blank.style.bg = cells[x].style.bg.clone();
+            cells[x] = blank;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::NamedColor;
+    use crate::style::Style;
+
+    fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color::Rgb { r, g, b }
+    }
+
+    fn cell(text: &str, fg: Option<Color>, bg: Option<Color>) -> Cell {
+        let style = Style {
+            fg,
+            bg,
+            ..Style::default()
+        };
+        Cell::new(text, style)
+    }
+
+    #[test]
+    fn mix_colors_interpolates_rgb() {
+        let mixed = mix_colors(&rgb(255, 0, 0), &rgb(0, 0, 255), 0.5);
+        assert_eq!(mixed, Some(rgb(128, 0, 128)));
+        assert_eq!(
+            mix_colors(&rgb(10, 20, 30), &rgb(0, 0, 0), 1.0),
+            Some(rgb(10, 20, 30))
+        );
+        assert_eq!(mix_colors(&Color::Reset, &rgb(0, 0, 0), 0.5), None);
+    }
+
+    #[test]
+    fn mix_colors_resolves_palette_colors() {
+        let mixed = mix_colors(
+            &Color::Named(NamedColor::BrightWhite),
+            &Color::Indexed(16),
+            0.5,
+        );
+        assert_eq!(mixed, Some(rgb(128, 128, 128)));
+    }
+
+    #[test]
+    fn blend_colors_falls_back_to_dominant_side() {
+        let red = rgb(255, 0, 0);
+        assert_eq!(blend_colors(Some(&red), None, 0.7), Some(red.clone()));
+        assert_eq!(blend_colors(Some(&red), None, 0.3), None);
+        assert_eq!(
+            blend_colors(Some(&Color::Reset), Some(&red), 0.3),
+            Some(red)
+        );
+    }
+
+    #[test]
+    fn translucent_blank_tints_glyph_below() {
+        let below = cell("x", Some(rgb(255, 255, 255)), Some(rgb(0, 0, 255)));
+        let top = cell(" ", None, Some(rgb(0, 0, 0)));
+        let out = blend_cell(&top, &below, 0.5, false);
+        assert_eq!(out.grapheme, "x");
+        assert_eq!(out.style.fg, Some(rgb(128, 128, 128)));
+        assert_eq!(out.style.bg, Some(rgb(0, 0, 128)));
+    }
+
+    #[test]
+    fn translucent_glyph_mixes_with_background_below() {
+        let below = cell("x", None, Some(rgb(0, 0, 0)));
+        let top = cell("A", Some(rgb(200, 100, 0)), Some(rgb(100, 100, 100)));
+        let out = blend_cell(&top, &below, 0.5, false);
+        assert_eq!(out.grapheme, "A");
+        assert_eq!(out.style.fg, Some(rgb(100, 50, 0)));
+        assert_eq!(out.style.bg, Some(rgb(50, 50, 50)));
+        assert!(!out.style.dim);
+    }
+
+    #[test]
+    fn unresolvable_colors_dim_instead() {
+        let below = cell("x", None, None);
+        let top = cell(" ", None, Some(rgb(0, 0, 0)));
+        let out = blend_cell(&top, &below, 0.6, false);
+        assert_eq!(out.grapheme, "x");
+        assert!(out.style.dim);
+        assert_eq!(out.style.bg, Some(rgb(0, 0, 0)));
+    }
+
+    #[test]
+    fn opaque_and_invisible_layers_short_circuit() {
+        let below = cell("x", None, Some(rgb(0, 0, 255)));
+        let top = cell("A", None, None);
+        assert_eq!(blend_cell(&top, &below, 1.0, false), top);
+        assert_eq!(blend_cell(&top, &below, 0.0, false), below);
+    }
+
+    #[test]
+    fn transparent_background_shows_through() {
+        let below = cell("x", None, Some(rgb(0, 0, 255)));
+        assert_eq!(blend_cell(&Cell::blank(), &below, 1.0, true), below);
+
+        let top = cell("A", Some(rgb(255, 0, 0)), None);
+        let out = blend_cell(&top, &below, 1.0, true);
+        assert_eq!(out.grapheme, "A");
+        assert_eq!(out.style.fg, Some(rgb(255, 0, 0)));
+        assert_eq!(out.style.bg, Some(rgb(0, 0, 255)));
+    }
+
+    #[test]
+    fn normalize_row_blanks_split_wide_chars() {
+        let mut row = vec![
+            Cell::new("世", Style::default()),
+            Cell::new("a", Style::default()),
+            Cell::continuation(),
+            Cell::new("界", Style::default()),
+            Cell::continuation(),
+        ];
+        normalize_row(&mut row);
+        assert_eq!(row[0], Cell::blank());
+        assert_eq!(row[2], Cell::blank());
+        assert_eq!(row[3].grapheme, "界");
+        assert!(row[4].is_continuation());
+    }
+}
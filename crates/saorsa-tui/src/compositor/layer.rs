@@ -1,6 +1,7 @@
 //! Layer and compositor types for resolving overlapping widgets.
 
 use crate::geometry::Rect;
+use crate::graphics::GraphicPlacement;
 use crate::segment::Segment;
 
 /// A single widget layer in the compositor stack.
@@ -17,6 +18,14 @@ pub struct Layer {
     pub z_index: i32,
     /// Per-line styled segment output.
     pub lines: Vec<Vec<Segment>>,
+    /// Inline images drawn by this layer, in screen coordinates.
+    pub graphics: Vec<GraphicPlacement>,
+    /// Opacity in `0.0..=1.0`; below 1.0 the layer is blended over the
+    /// layers beneath it.
+    pub opacity: f32,
+    /// Whether cells without a background let the layers beneath show
+    /// through (`background: transparent`).
+    pub transparent: bool,
 }
 
 impl Layer {
@@ -27,9 +36,39 @@ impl Layer {
             region,
             z_index,
             lines,
+            graphics: Vec::new(),
+            opacity: 1.0,
+            transparent: false,
         }
     }
 
+    /// Set the layer opacity, clamped to `0.0..=1.0` (builder pattern).
+    #[must_use]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Let the layers beneath show through cells without a background
+    /// (builder pattern).
+    #[must_use]
+    pub fn with_transparent_background(mut self) -> Self {
+        self.transparent = true;
+        self
+    }
+
+    /// Whether the layer fully covers what is beneath it.
+    pub fn is_opaque(&self) -> bool {
+        self.opacity >= 1.0 && !self.transparent
+    }
+
+    /// Attach an inline image placement to this layer (builder pattern).
+    #[must_use]
+    pub fn with_graphic(mut self, placement: GraphicPlacement) -> Self {
+        self.graphics.push(placement);
+        self
+    }
+
     /// Returns true if the given row falls within this layer's region.
     pub fn contains_row(&self, row: u16) -> bool {
         row >= self.region.position.y && row < self.region.position.y + self.region.size.height
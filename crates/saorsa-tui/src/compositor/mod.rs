@@ -3,7 +3,9 @@
 //! The compositor collects styled segment output from each widget,
 //! finds cut boundaries where widget edges meet, selects the topmost
 //! visible widget for each region, and writes the result to a screen buffer.
+//! Translucent layers are alpha-blended over the layers beneath them.
 
+pub mod blend;
 pub mod chop;
 pub mod compose;
 pub mod cuts;
@@ -88,11 +90,78 @@ impl Compositor {
     ///
     /// Processes each row by calling `compose_line` to resolve overlapping
     /// layers, then writes the resulting segments as cells to the buffer.
+    /// Layer graphics are then placed on the buffer; a graphic is marked
+    /// occluded when any layer stacked above its owner overlaps it.
+    ///
+    /// When any layer is translucent or has a transparent background, rows
+    /// are instead painted layer by layer from the bottom up, blending each
+    /// translucent layer's cells over the cells beneath.
     pub fn compose(&self, buf: &mut ScreenBuffer) {
-        for row in 0..self.screen_height {
-            let segments = compose::compose_line(&self.layers, row, self.screen_width);
-            self.write_segments_to_buffer(buf, row, &segments);
+        if self.layers.iter().all(Layer::is_opaque) {
+            for row in 0..self.screen_height {
+                let segments = compose::compose_line(&self.layers, row, self.screen_width);
+                self.write_segments_to_buffer(buf, row, &segments);
+            }
+        } else {
+            let mut order: Vec<usize> = (0..self.layers.len()).collect();
+            order.sort_by_key(|&idx| (self.layers[idx].z_index, idx));
+            for row in 0..self.screen_height {
+                let cells = self.paint_row(&order, row);
+                for (x, cell) in cells.into_iter().enumerate() {
+                    if !cell.is_continuation() {
+                        buf.set(x as u16, row, cell);
+                    }
+                }
+            }
         }
+        for (idx, layer) in self.layers.iter().enumerate() {
+            for graphic in &layer.graphics {
+                let mut placement = graphic.clone();
+                placement.occluded = self.is_covered(idx, &graphic.area);
+                buf.place_graphic(placement);
+            }
+        }
+    }
+
+    /// Whether any layer stacked above `layers[idx]` overlaps `area`.
+    fn is_covered(&self, idx: usize, area: &Rect) -> bool {
+        let Some(owner) = self.layers.get(idx) else {
+            return false;
+        };
+        self.layers.iter().enumerate().any(|(other_idx, other)| {
+            other_idx != idx
+                && (other.z_index > owner.z_index
+                    || (other.z_index == owner.z_index && other_idx > idx))
+                && other.region.intersects(area)
+        })
+    }
+
+    /// Paint one row by drawing `order`ed layers bottom-up, blending
+    /// translucent ones.
+    fn paint_row(&self, order: &[usize], row: u16) -> Vec<Cell> {
+        let width = self.screen_width;
+        let mut cells = vec![Cell::blank(); usize::from(width)];
+        for &idx in order {
+            let layer = &self.layers[idx];
+            let x0 = layer.region.position.x;
+            if !layer.contains_row(row) || x0 >= width {
+                continue;
+            }
+            let span = layer.region.size.width.min(width - x0);
+            let line = layer.line_for_row(row).map(Vec::as_slice).unwrap_or(&[]);
+            let chopped = chop::chop_segments(line, x0, x0, span);
+            let top = segments_to_cells(&chopped);
+            for (offset, cell) in top.into_iter().take(usize::from(span)).enumerate() {
+                let x = usize::from(x0) + offset;
+                cells[x] = if layer.is_opaque() {
+                    cell
+                } else {
+                    blend::blend_cell(&cell, &cells[x], layer.opacity, layer.transparent)
+                };
+            }
+        }
+        blend::normalize_row(&mut cells);
+        cells
     }
 
     /// Write segments to a row of the screen buffer, converting each
@@ -121,6 +190,26 @@ impl Compositor {
     }
 }
 
+/// Expand segments into cells, adding continuation cells after wide
+/// characters.
+fn segments_to_cells(segments: &[Segment]) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    for segment in segments.iter().filter(|s| !s.is_control) {
+        for grapheme in segment.text.graphemes(true) {
+            let cell = Cell::new(grapheme, segment.style.clone());
+            let width = cell.width;
+            if width == 0 {
+                continue;
+            }
+            cells.push(cell);
+            for _ in 1..width {
+                cells.push(Cell::continuation());
+            }
+        }
+    }
+    cells
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1338,4 +1427,131 @@ mod unicode_pipeline_tests {
             None => unreachable!(),
         }
     }
+
+    fn graphic(area: Rect) -> crate::graphics::GraphicPlacement {
+        use crate::graphics::{GraphicPlacement, ImageData, ImageProtocol};
+        let img = match ImageData::from_rgba(1, 1, vec![0, 0, 0, 255]) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        };
+        GraphicPlacement::new(1, area, ImageProtocol::Kitty, std::sync::Arc::new(img))
+    }
+
+    #[test]
+    fn compose_places_uncovered_graphics() {
+        let mut compositor = Compositor::new(20, 10);
+        let area = Rect::new(0, 0, 5, 3);
+        compositor.add_layer(Layer::new(1, area, 0, vec![]).with_graphic(graphic(area)));
+        let mut buf = ScreenBuffer::new(Size::new(20, 10));
+        compositor.compose(&mut buf);
+        assert_eq!(buf.graphics().len(), 1);
+        assert!(!buf.graphics()[0].occluded);
+    }
+
+    #[test]
+    fn compose_occludes_graphics_under_higher_layers() {
+        let mut compositor = Compositor::new(20, 10);
+        let area = Rect::new(0, 0, 5, 3);
+        compositor.add_layer(Layer::new(1, area, 0, vec![]).with_graphic(graphic(area)));
+        compositor.add_layer(Layer::new(2, Rect::new(4, 2, 5, 5), 10, vec![]));
+        let mut buf = ScreenBuffer::new(Size::new(20, 10));
+        compositor.compose(&mut buf);
+        assert!(buf.graphics()[0].occluded);
+    }
+
+    fn rgb(r: u8, g: u8, b: u8) -> crate::Color {
+        crate::Color::Rgb { r, g, b }
+    }
+
+    #[test]
+    fn translucent_layer_blends_over_layer_below() {
+        use crate::style::Style;
+        let mut compositor = Compositor::new(6, 1);
+        let base = Style::new().fg(rgb(255, 255, 255)).bg(rgb(0, 0, 200));
+        compositor.add_layer(Layer::new(
+            1,
+            Rect::new(0, 0, 6, 1),
+            0,
+            vec![vec![Segment::styled("abcdef", base)]],
+        ));
+        let veil = Style::new().fg(rgb(255, 0, 0)).bg(rgb(0, 0, 0));
+        compositor.add_layer(
+            Layer::new(
+                2,
+                Rect::new(2, 0, 3, 1),
+                1,
+                vec![vec![Segment::styled("X  ", veil)]],
+            )
+            .with_opacity(0.5),
+        );
+        let mut buf = ScreenBuffer::new(Size::new(6, 1));
+        compositor.compose(&mut buf);
+
+        let cell = |x| match buf.get(x, 0) {
+            Some(c) => c.clone(),
+            None => unreachable!(),
+        };
+        // Outside the overlay: untouched.
+        assert_eq!(cell(0).grapheme, "a");
+        assert_eq!(cell(0).style.bg, Some(rgb(0, 0, 200)));
+        // Overlay glyph mixed into the blue background.
+        assert_eq!(cell(2).grapheme, "X");
+        assert_eq!(cell(2).style.fg, Some(rgb(128, 0, 100)));
+        assert_eq!(cell(2).style.bg, Some(rgb(0, 0, 100)));
+        // Blank overlay cells tint the text below.
+        assert_eq!(cell(3).grapheme, "d");
+        assert_eq!(cell(3).style.fg, Some(rgb(128, 128, 128)));
+        assert_eq!(cell(5).grapheme, "f");
+        assert_eq!(cell(5).style.bg, Some(rgb(0, 0, 200)));
+    }
+
+    #[test]
+    fn transparent_layer_shows_cells_below() {
+        let mut compositor = Compositor::new(4, 1);
+        compositor.add_widget(
+            1,
+            Rect::new(0, 0, 4, 1),
+            0,
+            vec![vec![Segment::new("wxyz")]],
+        );
+        compositor.add_layer(
+            Layer::new(2, Rect::new(0, 0, 4, 1), 1, vec![vec![Segment::new(" Q")]])
+                .with_transparent_background(),
+        );
+        let mut buf = ScreenBuffer::new(Size::new(4, 1));
+        compositor.compose(&mut buf);
+        let text: String = (0..4)
+            .filter_map(|x| buf.get(x, 0).map(|c| c.grapheme.clone()))
+            .collect();
+        assert_eq!(text, "wQyz");
+    }
+
+    #[test]
+    fn translucent_layer_splitting_wide_char_blanks_it() {
+        let mut compositor = Compositor::new(4, 1);
+        compositor.add_widget(
+            1,
+            Rect::new(0, 0, 4, 1),
+            0,
+            vec![vec![Segment::new("世界")]],
+        );
+        compositor.add_layer(
+            Layer::new(2, Rect::new(1, 0, 1, 1), 1, vec![vec![Segment::new("a")]])
+                .with_opacity(0.5),
+        );
+        let mut buf = ScreenBuffer::new(Size::new(4, 1));
+        compositor.compose(&mut buf);
+        assert!(matches!(buf.get(0, 0), Some(c) if c.grapheme == " "));
+        assert!(matches!(buf.get(1, 0), Some(c) if c.grapheme == "a"));
+        assert!(matches!(buf.get(2, 0), Some(c) if c.grapheme == "界"));
+    }
+
+    #[test]
+    fn layer_opacity_is_clamped() {
+        let layer = Layer::new(1, Rect::new(0, 0, 1, 1), 0, vec![]).with_opacity(3.0);
+        assert!(layer.is_opaque());
+        let layer = layer.with_opacity(-1.0);
+        assert!((layer.opacity - 0.0).abs() < f32::EPSILON);
+        assert!(!layer.is_opaque());
+    }
 }
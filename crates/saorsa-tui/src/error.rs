@@ -29,6 +29,10 @@ pub enum SaorsaTuiError {
     #[error("widget error: {0}")]
     Widget(String),
 
+    /// Image decoding or encoding failed.
+    #[error("image error: {0}")]
+    Image(String),
+
     /// Unicode handling error.
     #[error("unicode error: {0}")]
     Unicode(String),
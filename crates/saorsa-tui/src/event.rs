@@ -14,6 +14,35 @@ pub enum Event {
     Resize(u16, u16),
     /// Text was pasted (bracketed paste mode).
     Paste(String),
+    /// The terminal window gained focus (CSI ?1004h).
+    FocusGained,
+    /// The terminal window lost focus (CSI ?1004h).
+    FocusLost,
+}
+
+impl Event {
+    /// Check if this is a key release.
+    ///
+    /// See [`InteractiveWidget::dispatch_event`](crate::widget::InteractiveWidget::dispatch_event)
+    /// for delivering events to widgets without key releases.
+    pub fn is_key_release(&self) -> bool {
+        matches!(self, Event::Key(key) if key.is_release())
+    }
+}
+
+/// Whether a key event is a press, an auto-repeat or a release.
+///
+/// Repeat and release events are only reported by terminals that speak the
+/// Kitty keyboard protocol; everywhere else every event is a press.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum KeyEventKind {
+    /// The key was pressed.
+    #[default]
+    Press,
+    /// The key is held down and auto-repeating.
+    Repeat,
+    /// The key was released.
+    Release,
 }
 
 /// A keyboard event.
@@ -23,22 +52,42 @@ pub struct KeyEvent {
     pub code: KeyCode,
     /// Active modifiers.
     pub modifiers: Modifiers,
+    /// Press, repeat or release.
+    pub kind: KeyEventKind,
 }
 
 impl KeyEvent {
-    /// Create a new key event.
+    /// Create a new key press event.
     pub fn new(code: KeyCode, modifiers: Modifiers) -> Self {
-        Self { code, modifiers }
-    }
-
-    /// Create a plain key event with no modifiers.
-    pub fn plain(code: KeyCode) -> Self {
         Self {
             code,
-            modifiers: Modifiers::NONE,
+            modifiers,
+            kind: KeyEventKind::Press,
         }
     }
 
+    /// Create a plain key press event with no modifiers.
+    pub fn plain(code: KeyCode) -> Self {
+        Self::new(code, Modifiers::NONE)
+    }
+
+    /// Set the event kind.
+    #[must_use]
+    pub fn with_kind(mut self, kind: KeyEventKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Check if this is a key release.
+    pub fn is_release(&self) -> bool {
+        self.kind == KeyEventKind::Release
+    }
+
+    /// Check if Super (Cmd/Windows) is held.
+    pub fn super_key(&self) -> bool {
+        self.modifiers.contains(Modifiers::SUPER)
+    }
+
     /// Check if Ctrl is held.
     pub fn ctrl(&self) -> bool {
         self.modifiers.contains(Modifiers::CTRL)
@@ -91,6 +140,52 @@ pub enum KeyCode {
     Insert,
     /// Function key (F1-F12).
     F(u8),
+    /// Caps Lock (Kitty keyboard protocol only).
+    CapsLock,
+    /// Scroll Lock (Kitty keyboard protocol only).
+    ScrollLock,
+    /// Num Lock (Kitty keyboard protocol only).
+    NumLock,
+    /// Print Screen (Kitty keyboard protocol only).
+    PrintScreen,
+    /// Pause (Kitty keyboard protocol only).
+    Pause,
+    /// Menu (Kitty keyboard protocol only).
+    Menu,
+    /// A media key (Kitty keyboard protocol only).
+    Media(MediaKey),
+}
+
+/// Media keys reported by the Kitty keyboard protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MediaKey {
+    /// Play.
+    Play,
+    /// Pause.
+    Pause,
+    /// Play/Pause toggle.
+    PlayPause,
+    /// Reverse.
+    Reverse,
+    /// Stop.
+    Stop,
+    /// Fast forward.
+    FastForward,
+    /// Rewind.
+    Rewind,
+    /// Next track.
+    TrackNext,
+    /// Previous track.
+    TrackPrevious,
+    /// Record.
+    Record,
+    /// Lower volume.
+    LowerVolume,
+    /// Raise volume.
+    RaiseVolume,
+    /// Mute volume.
+    MuteVolume,
 }
 
 /// Keyboard modifier flags.
@@ -108,6 +203,10 @@ impl Modifiers {
     pub const ALT: Self = Self(4);
     /// Super/Command modifier.
     pub const SUPER: Self = Self(8);
+    /// Hyper modifier (Kitty keyboard protocol only).
+    pub const HYPER: Self = Self(16);
+    /// Meta modifier (Kitty keyboard protocol only).
+    pub const META: Self = Self(32);
 
     /// Check if this modifier set contains the given modifier.
     pub const fn contains(self, other: Self) -> bool {
@@ -167,16 +266,54 @@ impl From<crossterm::event::Event> for Event {
             crossterm::event::Event::Mouse(mouse) => Event::Mouse(mouse.into()),
             crossterm::event::Event::Resize(w, h) => Event::Resize(w, h),
             crossterm::event::Event::Paste(text) => Event::Paste(text),
-            _ => Event::Key(KeyEvent::plain(KeyCode::Escape)), // fallback for FocusGained/Lost
+            crossterm::event::Event::FocusGained => Event::FocusGained,
+            crossterm::event::Event::FocusLost => Event::FocusLost,
         }
     }
 }
 
 impl From<crossterm::event::KeyEvent> for KeyEvent {
     fn from(ct: crossterm::event::KeyEvent) -> Self {
+        let mut modifiers: Modifiers = ct.modifiers.into();
+        // Shift+Tab arrives as BackTab; report it as Tab with Shift held.
+        if ct.code == crossterm::event::KeyCode::BackTab {
+            modifiers = modifiers | Modifiers::SHIFT;
+        }
         Self {
             code: ct.code.into(),
-            modifiers: ct.modifiers.into(),
+            modifiers,
+            kind: ct.kind.into(),
+        }
+    }
+}
+
+impl From<crossterm::event::KeyEventKind> for KeyEventKind {
+    fn from(ct: crossterm::event::KeyEventKind) -> Self {
+        match ct {
+            crossterm::event::KeyEventKind::Press => KeyEventKind::Press,
+            crossterm::event::KeyEventKind::Repeat => KeyEventKind::Repeat,
+            crossterm::event::KeyEventKind::Release => KeyEventKind::Release,
+        }
+    }
+}
+
+impl From<crossterm::event::MediaKeyCode> for MediaKey {
+    fn from(ct: crossterm::event::MediaKeyCode) -> Self {
+        use crossterm::event::MediaKeyCode as M;
+        match ct {
+            M::Play => MediaKey::Play,
+            M::Pause => MediaKey::Pause,
+            M::PlayPause => MediaKey::PlayPause,
+            M::Reverse => MediaKey::Reverse,
+            M::Stop => MediaKey::Stop,
+            M::FastForward => MediaKey::FastForward,
+            M::Rewind => MediaKey::Rewind,
+            M::TrackNext => MediaKey::TrackNext,
+            M::TrackPrevious => MediaKey::TrackPrevious,
+            M::Record => MediaKey::Record,
+            M::LowerVolume => MediaKey::LowerVolume,
+            M::RaiseVolume => MediaKey::RaiseVolume,
+            M::MuteVolume => MediaKey::MuteVolume,
         }
     }
 }
@@ -186,7 +323,7 @@ impl From<crossterm::event::KeyCode> for KeyCode {
         match ct {
             crossterm::event::KeyCode::Char(c) => KeyCode::Char(c),
             crossterm::event::KeyCode::Enter => KeyCode::Enter,
-            crossterm::event::KeyCode::Tab => KeyCode::Tab,
+            crossterm::event::KeyCode::Tab | crossterm::event::KeyCode::BackTab => KeyCode::Tab,
             crossterm::event::KeyCode::Backspace => KeyCode::Backspace,
             crossterm::event::KeyCode::Delete => KeyCode::Delete,
             crossterm::event::KeyCode::Esc => KeyCode::Escape,
@@ -200,6 +337,13 @@ impl From<crossterm::event::KeyCode> for KeyCode {
             crossterm::event::KeyCode::PageDown => KeyCode::PageDown,
             crossterm::event::KeyCode::Insert => KeyCode::Insert,
             crossterm::event::KeyCode::F(n) => KeyCode::F(n),
+            crossterm::event::KeyCode::CapsLock => KeyCode::CapsLock,
+            crossterm::event::KeyCode::ScrollLock => KeyCode::ScrollLock,
+            crossterm::event::KeyCode::NumLock => KeyCode::NumLock,
+            crossterm::event::KeyCode::PrintScreen => KeyCode::PrintScreen,
+            crossterm::event::KeyCode::Pause => KeyCode::Pause,
+            crossterm::event::KeyCode::Menu => KeyCode::Menu,
+            crossterm::event::KeyCode::Media(media) => KeyCode::Media(media.into()),
             _ => KeyCode::Escape, // fallback
         }
     }
@@ -220,6 +364,12 @@ impl From<crossterm::event::KeyModifiers> for Modifiers {
         if ct.contains(crossterm::event::KeyModifiers::SUPER) {
             m = m | Modifiers::SUPER;
         }
+        if ct.contains(crossterm::event::KeyModifiers::HYPER) {
+            m = m | Modifiers::HYPER;
+        }
+        if ct.contains(crossterm::event::KeyModifiers::META) {
+            m = m | Modifiers::META;
+        }
         m
     }
 }
@@ -268,6 +418,40 @@ impl fmt::Display for KeyCode {
             KeyCode::PageDown => write!(f, "PageDown"),
             KeyCode::Insert => write!(f, "Insert"),
             KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::CapsLock => write!(f, "CapsLock"),
+            KeyCode::ScrollLock => write!(f, "ScrollLock"),
+            KeyCode::NumLock => write!(f, "NumLock"),
+            KeyCode::PrintScreen => write!(f, "PrintScreen"),
+            KeyCode::Pause => write!(f, "Pause"),
+            KeyCode::Menu => write!(f, "Menu"),
+            KeyCode::Media(media) => write!(f, "{media:?}"),
+        }
+    }
+}
+
+/// Formats a key chord the way it is written in key hints, e.g.
+/// `Ctrl+Shift+P`.
+impl fmt::Display for KeyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NAMES: [(Modifiers, &str); 6] = [
+            (Modifiers::CTRL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::SUPER, "Super"),
+            (Modifiers::HYPER, "Hyper"),
+            (Modifiers::META, "Meta"),
+        ];
+        for (modifier, name) in NAMES {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers != Modifiers::NONE => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            ref code => write!(f, "{code}"),
         }
     }
 }
@@ -291,6 +475,26 @@ mod tests {
         assert!(!k.alt());
     }
 
+    #[test]
+    fn key_release_events() {
+        let press = KeyEvent::plain(KeyCode::Enter);
+        assert!(!Event::Key(press.clone()).is_key_release());
+        assert!(Event::Key(press.with_kind(KeyEventKind::Release)).is_key_release());
+        assert!(!Event::FocusGained.is_key_release());
+    }
+
+    #[test]
+    fn key_event_display() {
+        let key = KeyEvent::new(KeyCode::Char('p'), Modifiers::CTRL | Modifiers::SHIFT);
+        assert_eq!(key.to_string(), "Ctrl+Shift+P");
+        assert_eq!(KeyEvent::plain(KeyCode::Char('q')).to_string(), "q");
+        assert_eq!(KeyEvent::plain(KeyCode::Char(' ')).to_string(), "Space");
+        assert_eq!(
+            KeyEvent::new(KeyCode::F(5), Modifiers::ALT).to_string(),
+            "Alt+F5"
+        );
+    }
+
     #[test]
     fn modifier_union() {
         let m = Modifiers::CTRL | Modifiers::SHIFT;
@@ -348,4 +552,65 @@ mod tests {
         let e: Event = ct.into();
         assert!(matches!(e, Event::Resize(120, 40)));
     }
+
+    #[test]
+    fn crossterm_focus_conversion() {
+        let gained: Event = crossterm::event::Event::FocusGained.into();
+        let lost: Event = crossterm::event::Event::FocusLost.into();
+        assert_eq!(gained, Event::FocusGained);
+        assert_eq!(lost, Event::FocusLost);
+    }
+
+    #[test]
+    fn crossterm_key_kind_conversion() {
+        let ct = crossterm::event::KeyEvent::new_with_kind(
+            crossterm::event::KeyCode::Enter,
+            crossterm::event::KeyModifiers::NONE,
+            crossterm::event::KeyEventKind::Release,
+        );
+        let k: KeyEvent = ct.into();
+        assert_eq!(k.kind, KeyEventKind::Release);
+        assert!(k.is_release());
+        assert_eq!(KeyEvent::plain(KeyCode::Enter).kind, KeyEventKind::Press);
+    }
+
+    #[test]
+    fn crossterm_modified_enter_is_distinct() {
+        let shift = crossterm::event::KeyEvent::new(
+            crossterm::event::KeyCode::Enter,
+            crossterm::event::KeyModifiers::SHIFT,
+        );
+        let ctrl = crossterm::event::KeyEvent::new(
+            crossterm::event::KeyCode::Enter,
+            crossterm::event::KeyModifiers::CONTROL,
+        );
+        let shift: KeyEvent = shift.into();
+        let ctrl: KeyEvent = ctrl.into();
+        assert_eq!(shift, KeyEvent::new(KeyCode::Enter, Modifiers::SHIFT));
+        assert_eq!(ctrl, KeyEvent::new(KeyCode::Enter, Modifiers::CTRL));
+    }
+
+    #[test]
+    fn crossterm_back_tab_is_shift_tab() {
+        let ct = crossterm::event::KeyEvent::new(
+            crossterm::event::KeyCode::BackTab,
+            crossterm::event::KeyModifiers::NONE,
+        );
+        let k: KeyEvent = ct.into();
+        assert_eq!(k, KeyEvent::new(KeyCode::Tab, Modifiers::SHIFT));
+    }
+
+    #[test]
+    fn crossterm_extra_modifiers_and_media() {
+        let ct = crossterm::event::KeyEvent::new(
+            crossterm::event::KeyCode::Media(crossterm::event::MediaKeyCode::PlayPause),
+            crossterm::event::KeyModifiers::SUPER | crossterm::event::KeyModifiers::HYPER,
+        );
+        let k: KeyEvent = ct.into();
+        assert_eq!(k.code, KeyCode::Media(MediaKey::PlayPause));
+        assert!(k.super_key());
+        assert!(k.modifiers.contains(Modifiers::HYPER));
+        assert!(!k.modifiers.contains(Modifiers::META));
+        assert_eq!(k.code.to_string(), "PlayPause");
+    }
 }
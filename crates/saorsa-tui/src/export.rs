@@ -0,0 +1,493 @@
+//! Export rendered frames as SVG screenshots and HTML snippets.
+//!
+//! Both exporters walk a [`ScreenBuffer`] row by row, grouping consecutive
+//! cells with the same style into runs. Colors are resolved to RGB through
+//! an [`ExportTheme`] (the 16 ANSI colors and the default foreground and
+//! background are terminal-defined, so the theme supplies them). Wide
+//! characters cover two columns and their continuation cells are skipped;
+//! OSC 8 links become `<a>` elements.
+
+use std::fmt::Write as _;
+
+use crate::buffer::ScreenBuffer;
+use crate::color::{Color, XTERM_PALETTE};
+use crate::renderer::named_to_index;
+use crate::style::Style;
+
+/// An RGB triple.
+pub type Rgb = (u8, u8, u8);
+
+/// Colors used to resolve terminal-defined colors when exporting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportTheme {
+    /// Default foreground color.
+    pub foreground: Rgb,
+    /// Default background color.
+    pub background: Rgb,
+    /// The 16 ANSI colors, indexed like [`crate::color::NamedColor`].
+    pub palette: [Rgb; 16],
+}
+
+impl Default for ExportTheme {
+    /// The xterm default palette on a dark background.
+    fn default() -> Self {
+        Self {
+            foreground: (229, 229, 229),
+            background: (0, 0, 0),
+            palette: XTERM_PALETTE,
+        }
+    }
+}
+
+impl ExportTheme {
+    /// Resolve a color to RGB; `None` and [`Color::Reset`] use `default`.
+    pub fn resolve(&self, color: Option<&Color>, default: Rgb) -> Rgb {
+        match color {
+            Some(Color::Rgb { r, g, b }) => (*r, *g, *b),
+            Some(Color::Named(named)) => self.palette[usize::from(named_to_index(named))],
+            Some(Color::Indexed(i)) => self.indexed(*i),
+            Some(Color::Reset) | None => default,
+        }
+    }
+
+    /// RGB of a 256-color palette index.
+    fn indexed(&self, index: u8) -> Rgb {
+        match self.palette.get(usize::from(index)) {
+            Some(rgb) => *rgb,
+            None => Color::Indexed(index).to_rgb().unwrap_or(self.foreground),
+        }
+    }
+}
+
+/// Options for [`export_svg`] and [`export_html`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Colors for terminal-defined colors.
+    pub theme: ExportTheme,
+    /// CSS font family list.
+    pub font_family: String,
+    /// Font size in pixels (SVG only).
+    pub font_size: u16,
+    /// Width of one cell in pixels (SVG only).
+    pub cell_width: u16,
+    /// Height of one cell in pixels (SVG only).
+    pub cell_height: u16,
+    /// Optional title, emitted as the SVG `<title>`.
+    pub title: Option<String>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            theme: ExportTheme::default(),
+            font_family: "ui-monospace, Menlo, Consolas, \"DejaVu Sans Mono\", monospace"
+                .to_string(),
+            font_size: 15,
+            cell_width: 9,
+            cell_height: 18,
+            title: None,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Create options with the defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the color theme.
+    #[must_use]
+    pub fn theme(mut self, theme: ExportTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set the font family.
+    #[must_use]
+    pub fn font_family(mut self, family: impl Into<String>) -> Self {
+        self.font_family = family.into();
+        self
+    }
+
+    /// Set the font size and cell dimensions in pixels.
+    #[must_use]
+    pub fn cell_size(mut self, font_size: u16, width: u16, height: u16) -> Self {
+        self.font_size = font_size;
+        self.cell_width = width;
+        self.cell_height = height;
+        self
+    }
+
+    /// Set the title.
+    #[must_use]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+}
+
+/// Consecutive cells of one row sharing a style.
+struct Run<'a> {
+    col: u16,
+    width: u16,
+    text: String,
+    style: &'a Style,
+}
+
+impl Run<'_> {
+    fn is_blank(&self) -> bool {
+        self.text.chars().all(|c| c == ' ')
+    }
+}
+
+/// Split row `y` into styled runs, skipping continuation cells.
+fn runs(buf: &ScreenBuffer, y: u16) -> Vec<Run<'_>> {
+    let mut runs: Vec<Run<'_>> = Vec::new();
+    let Some(row) = buf.get_row(y) else {
+        return runs;
+    };
+    let mut col = 0u16;
+    for cell in row {
+        if cell.is_continuation() {
+            continue;
+        }
+        let width = u16::from(cell.width.max(1));
+        match runs.last_mut() {
+            Some(run) if *run.style == cell.style => {
+                run.text.push_str(&cell.grapheme);
+                run.width += width;
+            }
+            _ => runs.push(Run {
+                col,
+                width,
+                text: cell.grapheme.clone(),
+                style: &cell.style,
+            }),
+        }
+        col += width;
+    }
+    runs
+}
+
+/// Foreground and background of a style, after `reverse`.
+fn colors(style: &Style, theme: &ExportTheme) -> (Rgb, Rgb) {
+    let fg = theme.resolve(style.fg.as_ref(), theme.foreground);
+    let bg = theme.resolve(style.bg.as_ref(), theme.background);
+    if style.reverse { (bg, fg) } else { (fg, bg) }
+}
+
+fn hex((r, g, b): Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Escape text for XML/HTML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// CSS `text-decoration-line` values for a style, if any.
+fn decorations(style: &Style) -> Option<String> {
+    let lines: Vec<&str> = [
+        (style.underline, "underline"),
+        (style.strikethrough, "line-through"),
+        (style.overline, "overline"),
+    ]
+    .into_iter()
+    .filter_map(|(on, name)| on.then_some(name))
+    .collect();
+    (!lines.is_empty()).then(|| lines.join(" "))
+}
+
+/// Render a buffer as a standalone SVG image.
+pub fn export_svg(buf: &ScreenBuffer, options: &ExportOptions) -> String {
+    let theme = &options.theme;
+    let (cw, ch) = (
+        u32::from(options.cell_width),
+        u32::from(options.cell_height),
+    );
+    let width = u32::from(buf.width()) * cw;
+    let height = u32::from(buf.height()) * ch;
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"{}\" font-size=\"{}\">",
+        escape(&options.font_family),
+        options.font_size
+    );
+    if let Some(title) = &options.title {
+        let _ = writeln!(out, "<title>{}</title>", escape(title));
+    }
+    let _ = writeln!(
+        out,
+        "<rect width=\"{width}\" height=\"{height}\" fill=\"{}\"/>",
+        hex(theme.background)
+    );
+
+    let mut backgrounds = String::new();
+    let mut texts = String::new();
+    for y in 0..buf.height() {
+        let top = u32::from(y) * ch;
+        let baseline = top + ch * 4 / 5;
+        for run in runs(buf, y) {
+            let (fg, bg) = colors(run.style, theme);
+            let x = u32::from(run.col) * cw;
+            let w = u32::from(run.width) * cw;
+            if bg != theme.background {
+                let _ = writeln!(
+                    backgrounds,
+                    "<rect x=\"{x}\" y=\"{top}\" width=\"{w}\" height=\"{ch}\" fill=\"{}\"/>",
+                    hex(bg)
+                );
+            }
+            let style = run.style;
+            if style.hidden || (run.is_blank() && decorations(style).is_none()) {
+                continue;
+            }
+            let mut attrs = format!(
+                "x=\"{x}\" y=\"{baseline}\" textLength=\"{w}\" \
+                 lengthAdjust=\"spacingAndGlyphs\" fill=\"{}\"",
+                hex(fg)
+            );
+            if style.bold {
+                attrs.push_str(" font-weight=\"bold\"");
+            }
+            if style.italic {
+                attrs.push_str(" font-style=\"italic\"");
+            }
+            if style.dim {
+                attrs.push_str(" opacity=\"0.5\"");
+            }
+            if let Some(lines) = decorations(style) {
+                let _ = write!(attrs, " text-decoration=\"{lines}\"");
+            }
+            let text = format!("<text {attrs}>{}</text>", escape(&run.text));
+            match &style.link {
+                Some(url) => {
+                    let _ = writeln!(texts, "<a href=\"{}\">{text}</a>", escape(url));
+                }
+                None => {
+                    let _ = writeln!(texts, "{text}");
+                }
+            }
+        }
+    }
+    let _ = writeln!(out, "<g shape-rendering=\"crispEdges\">\n{backgrounds}</g>");
+    let _ = writeln!(out, "<g xml:space=\"preserve\">\n{texts}</g>");
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Render a buffer as an HTML `<pre>` snippet with inline styles.
+///
+/// The snippet needs no external CSS. Trailing unstyled blanks on each
+/// row are trimmed.
+pub fn export_html(buf: &ScreenBuffer, options: &ExportOptions) -> String {
+    let theme = &options.theme;
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<pre style=\"font-family: {}; color: {}; background: {}; \
+         line-height: 1.2; padding: 0.5em; margin: 0;\">",
+        escape(&options.font_family),
+        hex(theme.foreground),
+        hex(theme.background)
+    );
+    for y in 0..buf.height() {
+        if y > 0 {
+            out.push('\n');
+        }
+        let mut runs = runs(buf, y);
+        if let Some(last) = runs.last_mut()
+            && last.style.is_empty()
+        {
+            let trimmed = last.text.trim_end_matches(' ').len();
+            last.text.truncate(trimmed);
+        }
+        for run in &runs {
+            write_html_run(&mut out, run, theme);
+        }
+    }
+    out.push_str("</pre>\n");
+    out
+}
+
+fn write_html_run(out: &mut String, run: &Run<'_>, theme: &ExportTheme) {
+    if run.text.is_empty() {
+        return;
+    }
+    let style = run.style;
+    let text = escape(&run.text);
+    if style.is_empty() {
+        out.push_str(&text);
+        return;
+    }
+    let (fg, bg) = colors(style, theme);
+    let mut css = Vec::new();
+    if fg != theme.foreground {
+        css.push(format!("color: {}", hex(fg)));
+    }
+    if bg != theme.background {
+        css.push(format!("background: {}", hex(bg)));
+    }
+    if style.bold {
+        css.push("font-weight: bold".to_string());
+    }
+    if style.italic {
+        css.push("font-style: italic".to_string());
+    }
+    if style.dim {
+        css.push("opacity: 0.5".to_string());
+    }
+    if style.hidden {
+        css.push("visibility: hidden".to_string());
+    }
+    if let Some(lines) = decorations(style) {
+        css.push(format!("text-decoration: {lines}"));
+    }
+    let span = if css.is_empty() {
+        text
+    } else {
+        format!("<span style=\"{}\">{text}</span>", css.join("; "))
+    };
+    match &style.link {
+        Some(url) => {
+            let _ = write!(
+                out,
+                "<a href=\"{}\" style=\"color: inherit\">{span}</a>",
+                escape(url)
+            );
+        }
+        None => out.push_str(&span),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::color::NamedColor;
+    use crate::geometry::Size;
+
+    fn buffer(rows: &[(&str, Style)], width: u16) -> ScreenBuffer {
+        let mut buf = ScreenBuffer::new(Size::new(width, rows.len() as u16));
+        for (y, (text, style)) in rows.iter().enumerate() {
+            let mut x = 0;
+            for ch in text.chars() {
+                let cell = Cell::new(ch.to_string(), style.clone());
+                let w = u16::from(cell.width);
+                buf.set(x, y as u16, cell);
+                x += w;
+            }
+        }
+        buf
+    }
+
+    #[test]
+    fn resolves_named_indexed_and_reset_colors() {
+        let theme = ExportTheme::default();
+        let fallback = (1, 2, 3);
+        assert_eq!(
+            theme.resolve(Some(&Color::Named(NamedColor::BrightRed)), fallback),
+            (255, 0, 0)
+        );
+        assert_eq!(
+            theme.resolve(Some(&Color::Indexed(1)), fallback),
+            (205, 0, 0)
+        );
+        assert_eq!(
+            theme.resolve(Some(&Color::Indexed(196)), fallback),
+            (255, 0, 0)
+        );
+        assert_eq!(
+            theme.resolve(Some(&Color::Indexed(16)), fallback),
+            (0, 0, 0)
+        );
+        assert_eq!(
+            theme.resolve(Some(&Color::Indexed(244)), fallback),
+            (128, 128, 128)
+        );
+        assert_eq!(theme.resolve(Some(&Color::Reset), fallback), fallback);
+        assert_eq!(theme.resolve(None, fallback), fallback);
+    }
+
+    #[test]
+    fn runs_group_styles_and_skip_continuations() {
+        let bold = Style::new().bold(true);
+        let mut buf = buffer(&[("ab", Style::default())], 6);
+        buf.set(2, 0, Cell::new("世", bold.clone()));
+        buf.set(4, 0, Cell::new("x", bold));
+        let runs = runs(&buf, 0);
+        assert_eq!(runs.len(), 3);
+        assert_eq!(
+            (runs[0].col, runs[0].width, runs[0].text.as_str()),
+            (0, 2, "ab")
+        );
+        assert_eq!(
+            (runs[1].col, runs[1].width, runs[1].text.as_str()),
+            (2, 3, "世x")
+        );
+        assert_eq!((runs[2].col, runs[2].width), (5, 1));
+    }
+
+    #[test]
+    fn svg_contains_styled_text_and_backgrounds() {
+        let style = Style::new()
+            .fg(Color::Rgb { r: 255, g: 0, b: 0 })
+            .bg(Color::Named(NamedColor::Blue))
+            .bold(true)
+            .underline(true);
+        let buf = buffer(&[("a<b", style)], 4);
+        let svg = export_svg(&buf, &ExportOptions::new().title("shot"));
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"36\""));
+        assert!(svg.contains("<title>shot</title>"));
+        assert!(
+            svg.contains("<rect x=\"0\" y=\"0\" width=\"27\" height=\"18\" fill=\"#0000ee\"/>")
+        );
+        assert!(svg.contains(
+            "fill=\"#ff0000\" font-weight=\"bold\" text-decoration=\"underline\">a&lt;b</text>"
+        ));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn svg_reverse_swaps_colors_and_links_wrap_text() {
+        let style = Style::new().reverse(true).link("https://example.com/?a&b");
+        let buf = buffer(&[("go", style)], 2);
+        let svg = export_svg(&buf, &ExportOptions::new());
+        assert!(svg.contains("fill=\"#e5e5e5\"/>"));
+        assert!(svg.contains("<a href=\"https://example.com/?a&amp;b\"><text"));
+        assert!(svg.contains("fill=\"#000000\">go</text></a>"));
+    }
+
+    #[test]
+    fn html_trims_trailing_blanks_and_styles_spans() {
+        let red = Style::new().fg(Color::Named(NamedColor::Red)).italic(true);
+        let buf = buffer(&[("plain", Style::default()), ("hot", red)], 8);
+        let html = export_html(&buf, &ExportOptions::new());
+        assert!(html.starts_with("<pre style=\"font-family: "));
+        assert!(html.contains(
+            ">plain\n<span style=\"color: #cd0000; font-style: italic\">hot</span></pre>"
+        ));
+    }
+
+    #[test]
+    fn html_links_and_wide_chars() {
+        let buf = buffer(&[("日本", Style::new().link("https://x.y"))], 4);
+        let html = export_html(&buf, &ExportOptions::new());
+        assert!(html.contains("<a href=\"https://x.y\" style=\"color: inherit\">日本</a>"));
+    }
+}
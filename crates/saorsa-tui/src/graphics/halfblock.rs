@@ -0,0 +1,86 @@
+//! Unicode half-block fallback renderer.
+//!
+//! Each cell shows two vertically stacked pixels: the upper half block
+//! (`▀`) takes the top pixel as its foreground and the bottom pixel as
+//! its background. Transparent pixels fall back to the terminal default.
+
+use crate::buffer::ScreenBuffer;
+use crate::cell::Cell;
+use crate::color::Color;
+use crate::geometry::Rect;
+use crate::style::Style;
+
+use super::ImageData;
+
+/// Render `image` into `area`, scaling it to `area.width` x `2 * area.height` pixels.
+pub fn render(image: &ImageData, area: Rect, buf: &mut ScreenBuffer) {
+    if area.is_empty() || image.width() == 0 || image.height() == 0 {
+        return;
+    }
+    let scaled = image.resized(u32::from(area.size.width), u32::from(area.size.height) * 2);
+    for row in 0..area.size.height {
+        for col in 0..area.size.width {
+            let top = scaled
+                .pixel(u32::from(col), u32::from(row) * 2)
+                .and_then(opaque);
+            let bottom = scaled
+                .pixel(u32::from(col), u32::from(row) * 2 + 1)
+                .and_then(opaque);
+            let cell = match (top, bottom) {
+                (Some(t), Some(b)) => Cell::new("\u{2580}", Style::new().fg(t).bg(b)),
+                (Some(t), None) => Cell::new("\u{2580}", Style::new().fg(t)),
+                (None, Some(b)) => Cell::new("\u{2584}", Style::new().fg(b)),
+                (None, None) => Cell::blank(),
+            };
+            buf.set(area.position.x + col, area.position.y + row, cell);
+        }
+    }
+}
+
+/// Convert a pixel to a color, treating mostly-transparent pixels as absent.
+fn opaque([r, g, b, a]: [u8; 4]) -> Option<Color> {
+    (a >= 128).then_some(Color::Rgb { r, g, b })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Size;
+
+    #[test]
+    fn two_pixels_per_cell() {
+        let mut data = [255, 0, 0, 255].to_vec();
+        data.extend([0, 0, 255, 255]);
+        let img = match ImageData::from_rgba(1, 2, data) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        };
+        let mut buf = ScreenBuffer::new(Size::new(4, 2));
+        render(&img, Rect::new(1, 1, 1, 1), &mut buf);
+        let cell = buf.get(1, 1);
+        assert_eq!(cell.map(|c| c.grapheme.as_str()), Some("\u{2580}"));
+        assert_eq!(
+            cell.and_then(|c| c.style.fg.clone()),
+            Some(Color::Rgb { r: 255, g: 0, b: 0 })
+        );
+        assert_eq!(
+            cell.and_then(|c| c.style.bg.clone()),
+            Some(Color::Rgb { r: 0, g: 0, b: 255 })
+        );
+    }
+
+    #[test]
+    fn transparent_top_uses_lower_block() {
+        let mut data = [0, 0, 0, 0].to_vec();
+        data.extend([0, 255, 0, 255]);
+        let img = match ImageData::from_rgba(1, 2, data) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        };
+        let mut buf = ScreenBuffer::new(Size::new(1, 1));
+        render(&img, Rect::new(0, 0, 1, 1), &mut buf);
+        let cell = buf.get(0, 0);
+        assert_eq!(cell.map(|c| c.grapheme.as_str()), Some("\u{2584}"));
+        assert_eq!(cell.and_then(|c| c.style.bg.clone()), None);
+    }
+}
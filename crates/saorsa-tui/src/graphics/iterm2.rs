@@ -0,0 +1,29 @@
+//! iTerm2 inline image encoder (OSC 1337).
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// Build the OSC 1337 sequence that draws an encoded image file (PNG,
+/// JPEG, ...) at the cursor, stretched to `cols` x `rows` cells.
+///
+/// The cursor does not move.
+pub fn inline_image(file: &[u8], cols: u16, rows: u16) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={cols};height={rows};preserveAspectRatio=0;doNotMoveCursor=1:{}\x07",
+        file.len(),
+        STANDARD.encode(file),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_image_sequence() {
+        assert_eq!(
+            inline_image(b"abc", 4, 2),
+            "\x1b]1337;File=inline=1;size=3;width=4;height=2;preserveAspectRatio=0;doNotMoveCursor=1:YWJj\x07"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Kitty graphics protocol encoder.
+//!
+//! Images are transmitted once as raw RGBA (`f=32`), base64-encoded and
+//! split into chunks of at most 4096 bytes, and stored by the terminal
+//! under an id. Placing a stored image only names the id and the number of
+//! columns and rows the terminal scales it to.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use super::ImageData;
+
+/// Maximum base64 payload per escape sequence, as required by the protocol.
+const CHUNK_SIZE: usize = 4096;
+
+/// Placement id used for the single placement of each image.
+const PLACEMENT: u32 = 1;
+
+/// Build the escape sequences that transmit `image` and store it under `id`
+/// without displaying it.
+///
+/// Each returned string is one complete APC sequence so it can be wrapped
+/// for multiplexer pass-through on its own. Transmitting under an id that
+/// is already in use replaces its data.
+pub fn transmit(image: &ImageData, id: u32) -> Vec<String> {
+    let payload = STANDARD.encode(image.as_rgba());
+    let chunks: Vec<&str> = if payload.is_empty() {
+        vec![""]
+    } else {
+        // Base64 output is ASCII, so byte offsets are char boundaries.
+        payload
+            .as_bytes()
+            .chunks(CHUNK_SIZE)
+            .map(|c| std::str::from_utf8(c).unwrap_or_default())
+            .collect()
+    };
+
+    let last = chunks.len() - 1;
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let more = u8::from(i != last);
+            if i == 0 {
+                format!(
+                    "\x1b_Ga=t,f=32,s={},v={},i={id},q=2,m={more};{chunk}\x1b\\",
+                    image.width(),
+                    image.height(),
+                )
+            } else {
+                format!("\x1b_Gm={more};{chunk}\x1b\\")
+            }
+        })
+        .collect()
+}
+
+/// Build the escape sequence that displays the transmitted image `id` at
+/// the cursor, scaled to `cols` x `rows` cells.
+///
+/// Each image has a single placement: placing it again moves it. The
+/// cursor does not move.
+pub fn place(id: u32, cols: u16, rows: u16) -> String {
+    format!("\x1b_Ga=p,i={id},p={PLACEMENT},c={cols},r={rows},C=1,q=2\x1b\\")
+}
+
+/// Build the escape sequence that removes image `id` from the screen but
+/// keeps its data for a later [`place`].
+pub fn delete_placement(id: u32) -> String {
+    format!("\x1b_Ga=d,d=i,i={id},p={PLACEMENT},q=2\x1b\\")
+}
+
+/// Build the escape sequence that deletes image `id` and frees its data.
+pub fn delete(id: u32) -> String {
+    format!("\x1b_Ga=d,d=I,i={id},q=2\x1b\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: u32, height: u32) -> ImageData {
+        let len = (width * height * 4) as usize;
+        match ImageData::from_rgba(width, height, vec![0x80; len]) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        }
+    }
+
+    #[test]
+    fn single_chunk() {
+        let seqs = transmit(&image(1, 1), 3);
+        assert_eq!(
+            seqs,
+            vec!["\x1b_Ga=t,f=32,s=1,v=1,i=3,q=2,m=0;gICAgA==\x1b\\".to_string()]
+        );
+    }
+
+    #[test]
+    fn large_image_is_chunked() {
+        // 32x32 RGBA = 4096 bytes -> 5464 base64 chars -> 2 chunks.
+        let seqs = transmit(&image(32, 32), 1);
+        assert_eq!(seqs.len(), 2);
+        assert!(seqs[0].contains(",m=1;"));
+        assert!(seqs[1].starts_with("\x1b_Gm=0;"));
+        let payload_len: usize = seqs
+            .iter()
+            .map(|s| {
+                let start = s.find(';').map_or(0, |i| i + 1);
+                s.len() - start - 2
+            })
+            .sum();
+        assert_eq!(payload_len, 5464);
+    }
+
+    #[test]
+    fn place_and_delete_sequences() {
+        assert_eq!(place(7, 4, 2), "\x1b_Ga=p,i=7,p=1,c=4,r=2,C=1,q=2\x1b\\");
+        assert_eq!(delete_placement(7), "\x1b_Ga=d,d=i,i=7,p=1,q=2\x1b\\");
+        assert_eq!(delete(42), "\x1b_Ga=d,d=I,i=42,q=2\x1b\\");
+    }
+}
@@ -0,0 +1,524 @@
+//! Inline terminal graphics: decoded images and protocol encoders.
+//!
+//! Images are drawn outside the cell grid using one of three terminal
+//! protocols — Kitty graphics, iTerm2 inline images, or Sixel — with a
+//! Unicode half-block fallback that renders into ordinary cells.
+//!
+//! Widgets reserve cells for an image and register a [`GraphicPlacement`]
+//! on the [`ScreenBuffer`](crate::buffer::ScreenBuffer). The
+//! [`RenderContext`](crate::render_context::RenderContext) diffs placements
+//! between frames and emits, moves or clears images alongside the cell
+//! diff, wrapping each sequence for tmux/screen pass-through.
+
+pub mod halfblock;
+pub mod iterm2;
+pub mod kitty;
+pub mod sixel;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use image::RgbaImage;
+use image::imageops::FilterType;
+
+use crate::error::{Result, SaorsaTuiError};
+use crate::geometry::Rect;
+use crate::terminal::multiplexer::wrap_sequence;
+use crate::terminal::{
+    MultiplexerKind, TerminalCapabilities, detect, merge_multiplexer_limits, profile_for,
+};
+
+/// A decoded RGBA image.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ImageData {
+    pixels: RgbaImage,
+}
+
+impl ImageData {
+    /// Create an image from raw RGBA8 pixel data (row-major, 4 bytes per pixel).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SaorsaTuiError::Image`] if `rgba` does not hold exactly
+    /// `width * height * 4` bytes.
+    pub fn from_rgba(width: u32, height: u32, rgba: Vec<u8>) -> Result<Self> {
+        RgbaImage::from_raw(width, height, rgba)
+            .map(|pixels| Self { pixels })
+            .ok_or_else(|| {
+                SaorsaTuiError::Image(format!("pixel buffer does not match {width}x{height}"))
+            })
+    }
+
+    /// Decode a PNG or JPEG image from memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SaorsaTuiError::Image`] if the format is unsupported or
+    /// the data is corrupt.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let img =
+            image::load_from_memory(bytes).map_err(|e| SaorsaTuiError::Image(e.to_string()))?;
+        Ok(Self {
+            pixels: img.to_rgba8(),
+        })
+    }
+
+    /// Read and decode a PNG or JPEG file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be read, or
+    /// [`SaorsaTuiError::Image`] if it cannot be decoded.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::decode(&bytes)
+    }
+
+    /// Width in pixels.
+    pub fn width(&self) -> u32 {
+        self.pixels.width()
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> u32 {
+        self.pixels.height()
+    }
+
+    /// Raw RGBA8 pixel data.
+    pub fn as_rgba(&self) -> &[u8] {
+        self.pixels.as_raw()
+    }
+
+    /// The RGBA value of the pixel at `(x, y)`, or `None` if out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x < self.width() && y < self.height() {
+            Some(self.pixels.get_pixel(x, y).0)
+        } else {
+            None
+        }
+    }
+
+    /// Return a copy scaled to exactly `width` x `height` pixels.
+    #[must_use]
+    pub fn resized(&self, width: u32, height: u32) -> Self {
+        if width == self.width() && height == self.height() {
+            return self.clone();
+        }
+        Self {
+            pixels: image::imageops::resize(
+                &self.pixels,
+                width.max(1),
+                height.max(1),
+                FilterType::Triangle,
+            ),
+        }
+    }
+
+    /// Encode the image as PNG.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SaorsaTuiError::Image`] if encoding fails.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        use image::ImageEncoder;
+
+        let mut out = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut out)
+            .write_image(
+                self.pixels.as_raw(),
+                self.width(),
+                self.height(),
+                image::ExtendedColorType::Rgba8,
+            )
+            .map_err(|e| SaorsaTuiError::Image(e.to_string()))?;
+        Ok(out)
+    }
+}
+
+impl std::fmt::Debug for ImageData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImageData")
+            .field("width", &self.width())
+            .field("height", &self.height())
+            .finish_non_exhaustive()
+    }
+}
+
+/// How an image is drawn on the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageProtocol {
+    /// Kitty graphics protocol (APC `_G`).
+    Kitty,
+    /// iTerm2 inline images (OSC 1337).
+    ITerm2,
+    /// DEC Sixel graphics.
+    Sixel,
+    /// Unicode upper/lower half blocks with true-color cells.
+    HalfBlock,
+}
+
+impl ImageProtocol {
+    /// Pick the best protocol the given capabilities support.
+    ///
+    /// Preference order is Kitty, iTerm2, Sixel, then half blocks.
+    pub fn from_capabilities(caps: &TerminalCapabilities) -> Self {
+        if caps.kitty_graphics {
+            Self::Kitty
+        } else if caps.iterm2_images {
+            Self::ITerm2
+        } else if caps.sixel {
+            Self::Sixel
+        } else {
+            Self::HalfBlock
+        }
+    }
+
+    /// Pick the best protocol for the terminal detected from the environment.
+    pub fn detect() -> Self {
+        let info = detect();
+        let caps = merge_multiplexer_limits(profile_for(info.kind), info.multiplexer);
+        Self::from_capabilities(&caps)
+    }
+
+    /// Whether this protocol draws outside the cell grid.
+    pub fn is_graphical(self) -> bool {
+        !matches!(self, Self::HalfBlock)
+    }
+}
+
+/// An image positioned over a rectangle of cells for the current frame.
+#[derive(Clone, Debug)]
+pub struct GraphicPlacement {
+    /// Stable identifier of the image source (reused across frames).
+    pub id: u32,
+    /// Cells covered by the image.
+    pub area: Rect,
+    /// Protocol used to draw the image.
+    pub protocol: ImageProtocol,
+    /// The image to draw, scaled to fill `area`.
+    pub image: Arc<ImageData>,
+    /// Set when later content was drawn over part of `area`; occluded
+    /// placements are not shown.
+    pub occluded: bool,
+}
+
+impl GraphicPlacement {
+    /// Create a visible placement.
+    pub fn new(id: u32, area: Rect, protocol: ImageProtocol, image: Arc<ImageData>) -> Self {
+        Self {
+            id,
+            area,
+            protocol,
+            image,
+            occluded: false,
+        }
+    }
+
+    /// Whether two placements would produce identical terminal output.
+    pub fn same_as(&self, other: &GraphicPlacement) -> bool {
+        self.id == other.id
+            && self.area == other.area
+            && self.protocol == other.protocol
+            && Arc::ptr_eq(&self.image, &other.image)
+    }
+}
+
+/// Encodes placements into escape sequences for a particular terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GraphicsEncoder {
+    multiplexer: MultiplexerKind,
+    cell_pixel_size: (u16, u16),
+}
+
+impl GraphicsEncoder {
+    /// Default cell size in pixels, used to rasterise Sixel images.
+    pub const DEFAULT_CELL_PIXEL_SIZE: (u16, u16) = (10, 20);
+
+    /// Create an encoder for the given multiplexer.
+    pub fn new(multiplexer: MultiplexerKind) -> Self {
+        Self {
+            multiplexer,
+            cell_pixel_size: Self::DEFAULT_CELL_PIXEL_SIZE,
+        }
+    }
+
+    /// Set the size of one cell in pixels.
+    #[must_use]
+    pub fn with_cell_pixel_size(mut self, width: u16, height: u16) -> Self {
+        self.cell_pixel_size = (width.max(1), height.max(1));
+        self
+    }
+
+    /// The multiplexer sequences are wrapped for.
+    pub fn multiplexer(&self) -> MultiplexerKind {
+        self.multiplexer
+    }
+
+    /// Escape sequences that draw `placement` at its area.
+    ///
+    /// Kitty images are only placed; their data must already have been
+    /// sent with [`Self::transmit`]. Returns an empty string for half-block
+    /// placements, which are drawn as cells by the widget itself.
+    pub fn draw(&self, placement: &GraphicPlacement) -> String {
+        let area = placement.area;
+        if area.is_empty() || !placement.protocol.is_graphical() {
+            return String::new();
+        }
+        let mut out = format!("\x1b[{};{}H", area.position.y + 1, area.position.x + 1);
+        match placement.protocol {
+            ImageProtocol::Kitty => {
+                let seq = kitty::place(placement.id, area.size.width, area.size.height);
+                out.push_str(&self.wrap(&seq));
+            }
+            ImageProtocol::ITerm2 => {
+                if let Ok(png) = self.fitted(placement).to_png() {
+                    let seq = iterm2::inline_image(&png, area.size.width, area.size.height);
+                    out.push_str(&self.wrap(&seq));
+                }
+            }
+            ImageProtocol::Sixel => {
+                let (cw, ch) = self.cell_pixel_size;
+                let scaled = placement.image.resized(
+                    u32::from(area.size.width) * u32::from(cw),
+                    u32::from(area.size.height) * u32::from(ch),
+                );
+                out.push_str(&self.wrap(&sixel::encode(&scaled)));
+            }
+            ImageProtocol::HalfBlock => {}
+        }
+        out
+    }
+
+    /// Escape sequences that send the data of a Kitty `placement` to the
+    /// terminal, downscaled to the pixels its area covers, so that
+    /// [`Self::draw`] can place it.
+    ///
+    /// Returns an empty string for other protocols, which send the image
+    /// with every draw.
+    pub fn transmit(&self, placement: &GraphicPlacement) -> String {
+        if placement.protocol != ImageProtocol::Kitty || placement.area.is_empty() {
+            return String::new();
+        }
+        kitty::transmit(&self.fitted(placement), placement.id)
+            .iter()
+            .map(|chunk| self.wrap(chunk))
+            .collect()
+    }
+
+    /// The size in pixels `placement`'s image is sent at: its own size,
+    /// shrunk to at most the pixels its area covers.
+    pub fn transmit_size(&self, placement: &GraphicPlacement) -> (u32, u32) {
+        let (cw, ch) = self.cell_pixel_size;
+        let area = placement.area.size;
+        (
+            placement
+                .image
+                .width()
+                .min(u32::from(area.width) * u32::from(cw))
+                .max(1),
+            placement
+                .image
+                .height()
+                .min(u32::from(area.height) * u32::from(ch))
+                .max(1),
+        )
+    }
+
+    /// Escape sequences that remove a previously drawn placement, if the
+    /// protocol supports explicit deletion.
+    ///
+    /// Kitty images also lose their data; use [`Self::hide`] to keep it.
+    /// Sixel and iTerm2 images live in the cell grid and are cleared by
+    /// repainting the cells underneath instead; this returns `None` for them.
+    pub fn clear(&self, placement: &GraphicPlacement) -> Option<String> {
+        match placement.protocol {
+            ImageProtocol::Kitty => Some(self.release(placement.id)),
+            _ => None,
+        }
+    }
+
+    /// Escape sequences that take a Kitty placement off the screen while
+    /// the terminal keeps its data. Returns `None` for other protocols.
+    pub fn hide(&self, placement: &GraphicPlacement) -> Option<String> {
+        match placement.protocol {
+            ImageProtocol::Kitty => Some(self.wrap(&kitty::delete_placement(placement.id))),
+            _ => None,
+        }
+    }
+
+    /// Escape sequences that free the Kitty image data stored under `id`,
+    /// removing it from the screen.
+    pub fn release(&self, id: u32) -> String {
+        self.wrap(&kitty::delete(id))
+    }
+
+    /// The placement's image scaled down to [`Self::transmit_size`].
+    fn fitted(&self, placement: &GraphicPlacement) -> ImageData {
+        let (width, height) = self.transmit_size(placement);
+        placement.image.resized(width, height)
+    }
+
+    fn wrap(&self, seq: &str) -> String {
+        wrap_sequence(seq, self.multiplexer)
+    }
+}
+
+impl Default for GraphicsEncoder {
+    fn default() -> Self {
+        Self::new(MultiplexerKind::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn red_2x2() -> ImageData {
+        match ImageData::from_rgba(2, 2, [255, 0, 0, 255].repeat(4)) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        }
+    }
+
+    #[test]
+    fn from_rgba_rejects_wrong_length() {
+        assert!(ImageData::from_rgba(2, 2, vec![0; 3]).is_err());
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let img = red_2x2();
+        let png = match img.to_png() {
+            Ok(p) => p,
+            Err(_) => unreachable!("png encode"),
+        };
+        let decoded = match ImageData::decode(&png) {
+            Ok(d) => d,
+            Err(_) => unreachable!("png decode"),
+        };
+        assert_eq!(decoded, img);
+    }
+
+    #[test]
+    fn decode_garbage_fails() {
+        assert!(matches!(
+            ImageData::decode(b"not an image"),
+            Err(SaorsaTuiError::Image(_))
+        ));
+    }
+
+    #[test]
+    fn resize_changes_dimensions() {
+        let img = red_2x2().resized(6, 4);
+        assert_eq!((img.width(), img.height()), (6, 4));
+        assert_eq!(img.pixel(5, 3), Some([255, 0, 0, 255]));
+        assert_eq!(img.pixel(6, 0), None);
+    }
+
+    #[test]
+    fn protocol_preference() {
+        let mut caps = TerminalCapabilities {
+            sixel: true,
+            ..TerminalCapabilities::default()
+        };
+        assert_eq!(
+            ImageProtocol::from_capabilities(&caps),
+            ImageProtocol::Sixel
+        );
+        caps.iterm2_images = true;
+        assert_eq!(
+            ImageProtocol::from_capabilities(&caps),
+            ImageProtocol::ITerm2
+        );
+        caps.kitty_graphics = true;
+        assert_eq!(
+            ImageProtocol::from_capabilities(&caps),
+            ImageProtocol::Kitty
+        );
+        assert_eq!(
+            ImageProtocol::from_capabilities(&TerminalCapabilities::default()),
+            ImageProtocol::HalfBlock
+        );
+    }
+
+    #[test]
+    fn placement_identity() {
+        let img = Arc::new(red_2x2());
+        let a = GraphicPlacement::new(1, Rect::new(0, 0, 2, 1), ImageProtocol::Kitty, img.clone());
+        let b = GraphicPlacement::new(1, Rect::new(0, 0, 2, 1), ImageProtocol::Kitty, img);
+        assert!(a.same_as(&b));
+        let moved = GraphicPlacement {
+            area: Rect::new(0, 1, 2, 1),
+            ..b.clone()
+        };
+        assert!(!a.same_as(&moved));
+        let other_image = GraphicPlacement {
+            image: Arc::new(red_2x2()),
+            ..b
+        };
+        assert!(!a.same_as(&other_image));
+    }
+
+    #[test]
+    fn draw_positions_cursor_and_wraps_for_tmux() {
+        let img = Arc::new(red_2x2());
+        let p = GraphicPlacement::new(7, Rect::new(3, 2, 1, 1), ImageProtocol::Kitty, img);
+        let out = GraphicsEncoder::new(MultiplexerKind::Tmux).draw(&p);
+        assert!(out.starts_with("\x1b[3;4H\x1bPtmux;\x1b\x1b\x1b_Ga=p,i=7,"));
+        assert!(out.ends_with("\x1b\x1b\\\x1b\\"));
+    }
+
+    #[test]
+    fn kitty_transmit_is_downscaled_to_the_area() {
+        let big = match ImageData::from_rgba(400, 300, vec![0; 400 * 300 * 4]) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        };
+        let p = GraphicPlacement::new(
+            2,
+            Rect::new(0, 0, 4, 2),
+            ImageProtocol::Kitty,
+            Arc::new(big),
+        );
+        let encoder = GraphicsEncoder::default();
+        assert_eq!(encoder.transmit_size(&p), (40, 40));
+        let out = encoder.transmit(&p);
+        assert!(out.starts_with("\x1b_Ga=t,f=32,s=40,v=40,i=2,"));
+
+        // Small images are sent as they are.
+        let small = GraphicPlacement::new(
+            3,
+            Rect::new(0, 0, 4, 2),
+            ImageProtocol::Kitty,
+            Arc::new(red_2x2()),
+        );
+        assert_eq!(encoder.transmit_size(&small), (2, 2));
+        let sixel = GraphicPlacement {
+            protocol: ImageProtocol::Sixel,
+            ..small
+        };
+        assert!(encoder.transmit(&sixel).is_empty());
+    }
+
+    #[test]
+    fn half_block_draws_nothing() {
+        let img = Arc::new(red_2x2());
+        let p = GraphicPlacement::new(1, Rect::new(0, 0, 2, 1), ImageProtocol::HalfBlock, img);
+        assert!(GraphicsEncoder::default().draw(&p).is_empty());
+        assert!(GraphicsEncoder::default().clear(&p).is_none());
+    }
+
+    #[test]
+    fn kitty_clear_deletes_by_id() {
+        let img = Arc::new(red_2x2());
+        let p = GraphicPlacement::new(9, Rect::new(0, 0, 2, 1), ImageProtocol::Kitty, img);
+        assert_eq!(
+            GraphicsEncoder::default().clear(&p),
+            Some("\x1b_Ga=d,d=I,i=9,q=2\x1b\\".to_string())
+        );
+        assert_eq!(
+            GraphicsEncoder::default().hide(&p),
+            Some("\x1b_Ga=d,d=i,i=9,p=1,q=2\x1b\\".to_string())
+        );
+    }
+}
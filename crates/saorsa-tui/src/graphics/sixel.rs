@@ -0,0 +1,160 @@
+//! DEC Sixel encoder.
+//!
+//! Pixels are quantised to the 6x6x6 color cube (216 registers) and
+//! emitted in bands of six rows with run-length compression. Pixels with
+//! alpha below 50% are left transparent.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use super::ImageData;
+
+/// Encode an image as a complete Sixel DCS sequence, one pixel per dot.
+pub fn encode(image: &ImageData) -> String {
+    let width = image.width();
+    let height = image.height();
+    let mut out = String::new();
+    // P2=1: transparent pixels keep the existing background.
+    let _ = write!(out, "\x1bP0;1;0q\"1;1;{width};{height}");
+
+    let registers: Vec<Option<u8>> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| image.pixel(x, y).and_then(register_for))
+        .collect();
+
+    let used: BTreeSet<u8> = registers.iter().flatten().copied().collect();
+    for &reg in &used {
+        let (r, g, b) = register_rgb(reg);
+        let _ = write!(out, "#{reg};2;{};{};{}", percent(r), percent(g), percent(b));
+    }
+
+    let width = width as usize;
+    let bands = (height as usize).div_ceil(6);
+    for band in 0..bands {
+        let top = band * 6;
+        let rows = (height as usize - top).min(6);
+        let band_regs: BTreeSet<u8> = (top..top + rows)
+            .flat_map(|y| {
+                registers[y * width..(y + 1) * width]
+                    .iter()
+                    .flatten()
+                    .copied()
+            })
+            .collect();
+
+        let mut first = true;
+        for reg in band_regs {
+            if !first {
+                out.push('$');
+            }
+            first = false;
+            let _ = write!(out, "#{reg}");
+            let mut run: Option<(char, usize)> = None;
+            for x in 0..width {
+                let mut bits = 0u8;
+                for dy in 0..rows {
+                    if registers[(top + dy) * width + x] == Some(reg) {
+                        bits |= 1 << dy;
+                    }
+                }
+                let ch = char::from(63 + bits);
+                run = match run {
+                    Some((c, n)) if c == ch => Some((c, n + 1)),
+                    Some((c, n)) => {
+                        push_run(&mut out, c, n);
+                        Some((ch, 1))
+                    }
+                    None => Some((ch, 1)),
+                };
+            }
+            if let Some((c, n)) = run {
+                push_run(&mut out, c, n);
+            }
+        }
+        if band + 1 < bands {
+            out.push('-');
+        }
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Map a pixel to its color cube register, or `None` if transparent.
+fn register_for([r, g, b, a]: [u8; 4]) -> Option<u8> {
+    if a < 128 {
+        return None;
+    }
+    let level = |c: u8| ((u16::from(c) * 5 + 127) / 255) as u8;
+    Some(level(r) * 36 + level(g) * 6 + level(b))
+}
+
+/// The RGB value of a color cube register.
+fn register_rgb(reg: u8) -> (u8, u8, u8) {
+    let value = |level: u8| ((u16::from(level) * 255) / 5) as u8;
+    (value(reg / 36), value((reg / 6) % 6), value(reg % 6))
+}
+
+/// Convert a channel to Sixel's 0-100 range.
+fn percent(c: u8) -> u8 {
+    ((u16::from(c) * 100 + 127) / 255) as u8
+}
+
+/// Append a run of identical sixels, compressed when it saves space.
+fn push_run(out: &mut String, ch: char, count: usize) {
+    if count > 3 {
+        let _ = write!(out, "!{count}{ch}");
+    } else {
+        for _ in 0..count {
+            out.push(ch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, rgba: [u8; 4]) -> ImageData {
+        let data = rgba.repeat((width * height) as usize);
+        match ImageData::from_rgba(width, height, data) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        }
+    }
+
+    #[test]
+    fn single_red_pixel() {
+        let out = encode(&solid(1, 1, [255, 0, 0, 255]));
+        // Register 180 = level (5,0,0); one dot in the top row = '@'.
+        assert_eq!(out, "\x1bP0;1;0q\"1;1;1;1#180;2;100;0;0#180@\x1b\\");
+    }
+
+    #[test]
+    fn run_length_and_bands() {
+        let out = encode(&solid(5, 7, [0, 0, 255, 255]));
+        // Full band of 6 rows ('~'), then a second band with one row ('@').
+        assert_eq!(out, "\x1bP0;1;0q\"1;1;5;7#5;2;0;0;100#5!5~-#5!5@\x1b\\");
+    }
+
+    #[test]
+    fn transparent_pixels_are_skipped() {
+        let out = encode(&solid(2, 1, [255, 255, 255, 0]));
+        assert_eq!(out, "\x1bP0;1;0q\"1;1;2;1\x1b\\");
+    }
+
+    #[test]
+    fn multiple_colors_use_carriage_return() {
+        let mut data = [255, 0, 0, 255].to_vec();
+        data.extend([0, 255, 0, 255]);
+        let img = match ImageData::from_rgba(2, 1, data) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        };
+        let out = encode(&img);
+        assert_eq!(
+            out,
+            "\x1bP0;1;0q\"1;1;2;1#30;2;0;100;0#180;2;100;0;0#30?@$#180@?\x1b\\"
+        );
+    }
+}
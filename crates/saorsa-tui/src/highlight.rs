@@ -4,7 +4,21 @@
 //! syntax highlighting to [`crate::widget::TextArea`]. Includes a
 //! [`NoHighlighter`] (no-op) and a [`SimpleKeywordHighlighter`] for
 //! testing purposes.
+//!
+//! With the `tree-sitter` feature, `TreeSitterHighlighter` parses the
+//! document with a bundled grammar (Rust, Python, JavaScript, TypeScript,
+//! Go, JSON, TOML, Markdown, Bash) and styles it through a
+//! [`SyntaxTheme`], which can follow the active TCSS theme.
+
+#[cfg(feature = "tree-sitter")]
+mod syntax;
+mod theme;
 
+#[cfg(feature = "tree-sitter")]
+pub use syntax::{SyntaxLanguage, TreeSitterHighlighter};
+pub use theme::SyntaxTheme;
+
+use crate::segment::Segment;
 use crate::style::Style;
 
 /// A styled span within a single line of text.
@@ -21,11 +35,29 @@ pub struct HighlightSpan {
     pub style: Style,
 }
 
+/// A replacement of document text, reported to [`Highlighter::on_edit`].
+///
+/// Offsets are bytes into the document before the edit; the replaced
+/// bytes `start_byte..old_end_byte` become `text`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextEdit<'a> {
+    /// Zero-based line the edit starts on.
+    pub line: usize,
+    /// Byte offset where the replaced text starts.
+    pub start_byte: usize,
+    /// Byte offset where the replaced text ends.
+    pub old_end_byte: usize,
+    /// The text inserted in its place.
+    pub text: &'a str,
+}
+
 /// Trait for providing syntax highlighting to text buffers.
 ///
 /// Implementors return styled spans for each line. The
-/// [`NoHighlighter`] returns no spans (plain text). A tree-sitter
-/// highlighter can be plugged in later without changing the widget API.
+/// [`NoHighlighter`] returns no spans (plain text). Highlighters that
+/// parse the whole document, like the tree-sitter highlighter, receive it
+/// through [`set_text`](Highlighter::set_text) and then follow each
+/// change through [`on_edit`](Highlighter::on_edit).
 pub trait Highlighter {
     /// Return highlight spans for a given line.
     ///
@@ -33,11 +65,71 @@ pub trait Highlighter {
     /// `text` is the content of that line (without trailing newline).
     fn highlight_line(&self, line_idx: usize, text: &str) -> Vec<HighlightSpan>;
 
-    /// Notification that a line has been edited.
+    /// Notification that part of the document was replaced, as each
+    /// edit happens.
+    ///
+    /// Incremental parsers (e.g. tree-sitter) can use this to apply the
+    /// edit to their copy of the document and reparse only the affected
+    /// region.
+    fn on_edit(&mut self, edit: &TextEdit<'_>);
+
+    /// Provide the full document text, replacing whatever came before.
     ///
-    /// Incremental parsers (e.g. tree-sitter) can use this to
-    /// invalidate cached parse results for the affected region.
-    fn on_edit(&mut self, line_idx: usize);
+    /// Called when a highlighter is attached to a document, not for
+    /// individual edits. Line-based highlighters can ignore it.
+    fn set_text(&mut self, _text: &str) {}
+
+    /// Restyle the highlighter with a syntax theme.
+    ///
+    /// Called when the theme changes; highlighters with their own
+    /// styles can ignore it.
+    fn set_theme(&mut self, _theme: &SyntaxTheme) {}
+}
+
+/// A highlighter for a code block language name or file extension
+/// (`"rust"`, `"py"`, …), styled by `theme` and holding `text`.
+///
+/// Returns `None` when the language has no bundled grammar, or without
+/// the `tree-sitter` feature.
+pub fn highlighter_for_language(
+    language: &str,
+    theme: &SyntaxTheme,
+    text: &str,
+) -> Option<Box<dyn Highlighter>> {
+    #[cfg(feature = "tree-sitter")]
+    {
+        let highlighter = TreeSitterHighlighter::for_name(language)?
+            .with_theme(theme)
+            .with_text(text);
+        Some(Box::new(highlighter))
+    }
+    #[cfg(not(feature = "tree-sitter"))]
+    {
+        let _ = (language, theme, text);
+        None
+    }
+}
+
+/// Split `text` into segments, overlaying each span's style on `base`.
+pub fn highlight_segments(text: &str, spans: &[HighlightSpan], base: &Style) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut run = String::new();
+    let mut run_style = base.clone();
+    for (col, ch) in text.chars().enumerate() {
+        let style = spans
+            .iter()
+            .find(|s| col >= s.start_col && col < s.end_col)
+            .map_or_else(|| base.clone(), |s| base.merge(&s.style));
+        if style != run_style && !run.is_empty() {
+            segments.push(Segment::styled(std::mem::take(&mut run), run_style));
+        }
+        run_style = style;
+        run.push(ch);
+    }
+    if !run.is_empty() {
+        segments.push(Segment::styled(run, run_style));
+    }
+    segments
 }
 
 /// A no-op highlighter that returns no spans for any line.
@@ -52,7 +144,7 @@ impl Highlighter for NoHighlighter {
         Vec::new()
     }
 
-    fn on_edit(&mut self, _line_idx: usize) {}
+    fn on_edit(&mut self, _edit: &TextEdit<'_>) {}
 }
 
 /// A simple keyword-based highlighter for testing.
@@ -96,7 +188,7 @@ impl Highlighter for SimpleKeywordHighlighter {
         spans
     }
 
-    fn on_edit(&mut self, _line_idx: usize) {
+    fn on_edit(&mut self, _edit: &TextEdit<'_>) {
         // No caching to invalidate in this simple implementation
     }
 }
@@ -172,10 +264,47 @@ mod tests {
 
     #[test]
     fn on_edit_no_panic() {
+        let edit = TextEdit {
+            line: 5,
+            start_byte: 40,
+            old_end_byte: 42,
+            text: "x",
+        };
         let mut h = NoHighlighter;
-        h.on_edit(0);
+        h.on_edit(&edit);
         let mut kh =
             SimpleKeywordHighlighter::new(vec![("x".to_string(), Style::new().bold(true))]);
-        kh.on_edit(5);
+        kh.on_edit(&edit);
+    }
+
+    #[test]
+    fn highlight_segments_overlay_spans() {
+        let base = Style::new().dim(true);
+        let spans = vec![HighlightSpan {
+            start_col: 4,
+            end_col: 8,
+            style: Style::new().bold(true),
+        }];
+        let segments = highlight_segments("let main = 1", &spans, &base);
+        let texts: Vec<&str> = segments.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["let ", "main", " = 1"]);
+        assert!(segments[1].style.bold && segments[1].style.dim);
+        assert!(!segments[2].style.bold);
+    }
+
+    #[cfg(not(feature = "tree-sitter"))]
+    #[test]
+    fn no_language_highlighters_without_tree_sitter() {
+        let theme = SyntaxTheme::default();
+        assert!(highlighter_for_language("rust", &theme, "fn main() {}").is_none());
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn language_highlighters_with_tree_sitter() {
+        let theme = SyntaxTheme::default();
+        let h = highlighter_for_language("rs", &theme, "fn main() {}\n");
+        assert!(h.is_some_and(|h| !h.highlight_line(0, "fn main() {}").is_empty()));
+        assert!(highlighter_for_language("cobol", &theme, "").is_none());
     }
 }
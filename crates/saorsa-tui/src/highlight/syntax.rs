@@ -0,0 +1,597 @@
+//! Tree-sitter syntax highlighting with bundled grammars.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{InputEdit, Language, Parser, Point, Query, QueryCursor, Tree};
+
+use crate::error::{Result, SaorsaTuiError};
+use crate::style::Style;
+
+use super::{HighlightSpan, Highlighter, SyntaxTheme, TextEdit};
+
+/// A language with a bundled tree-sitter grammar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SyntaxLanguage {
+    /// Rust.
+    Rust,
+    /// Python.
+    Python,
+    /// JavaScript (including JSX).
+    JavaScript,
+    /// TypeScript.
+    TypeScript,
+    /// TypeScript with JSX.
+    Tsx,
+    /// Go.
+    Go,
+    /// JSON.
+    Json,
+    /// TOML.
+    Toml,
+    /// Markdown (block structure).
+    Markdown,
+    /// Bash and POSIX shell.
+    Bash,
+}
+
+impl SyntaxLanguage {
+    /// Every bundled language.
+    pub const ALL: [SyntaxLanguage; 10] = [
+        Self::Rust,
+        Self::Python,
+        Self::JavaScript,
+        Self::TypeScript,
+        Self::Tsx,
+        Self::Go,
+        Self::Json,
+        Self::Toml,
+        Self::Markdown,
+        Self::Bash,
+    ];
+
+    /// Look a language up by name or file extension, ignoring case
+    /// (`"rust"`, `"rs"`, `"py"`, `"sh"`, …), as used in fenced code
+    /// block info strings.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        let lang = match name.as_str() {
+            "rust" | "rs" => Self::Rust,
+            "python" | "py" | "python3" | "pyi" => Self::Python,
+            "javascript" | "js" | "jsx" | "mjs" | "cjs" | "node" => Self::JavaScript,
+            "typescript" | "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            "go" | "golang" => Self::Go,
+            "json" | "jsonc" => Self::Json,
+            "toml" => Self::Toml,
+            "markdown" | "md" => Self::Markdown,
+            "bash" | "sh" | "shell" | "zsh" | "console" => Self::Bash,
+            _ => return None,
+        };
+        Some(lang)
+    }
+
+    /// Look a language up by a file's extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?;
+        Self::from_name(ext)
+    }
+
+    /// The language's canonical name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Tsx => "tsx",
+            Self::Go => "go",
+            Self::Json => "json",
+            Self::Toml => "toml",
+            Self::Markdown => "markdown",
+            Self::Bash => "bash",
+        }
+    }
+
+    fn grammar(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::Json => tree_sitter_json::LANGUAGE.into(),
+            Self::Toml => tree_sitter_toml_ng::LANGUAGE.into(),
+            Self::Markdown => tree_sitter_md::LANGUAGE.into(),
+            Self::Bash => tree_sitter_bash::LANGUAGE.into(),
+        }
+    }
+
+    fn highlights_query(self) -> String {
+        match self {
+            Self::Rust => tree_sitter_rust::HIGHLIGHTS_QUERY.into(),
+            Self::Python => tree_sitter_python::HIGHLIGHTS_QUERY.into(),
+            Self::JavaScript => [
+                tree_sitter_javascript::JSX_HIGHLIGHT_QUERY,
+                tree_sitter_javascript::HIGHLIGHT_QUERY,
+            ]
+            .concat(),
+            // TypeScript's queries only cover what it adds to JavaScript.
+            Self::TypeScript => [
+                tree_sitter_typescript::HIGHLIGHTS_QUERY,
+                tree_sitter_javascript::HIGHLIGHT_QUERY,
+            ]
+            .concat(),
+            Self::Tsx => [
+                tree_sitter_typescript::HIGHLIGHTS_QUERY,
+                tree_sitter_javascript::JSX_HIGHLIGHT_QUERY,
+                tree_sitter_javascript::HIGHLIGHT_QUERY,
+            ]
+            .concat(),
+            Self::Go => tree_sitter_go::HIGHLIGHTS_QUERY.into(),
+            Self::Json => tree_sitter_json::HIGHLIGHTS_QUERY.into(),
+            Self::Toml => tree_sitter_toml_ng::HIGHLIGHTS_QUERY.into(),
+            Self::Markdown => tree_sitter_md::HIGHLIGHT_QUERY_BLOCK.into(),
+            Self::Bash => tree_sitter_bash::HIGHLIGHT_QUERY.into(),
+        }
+    }
+
+    /// The compiled highlights query, shared by every highlighter of the
+    /// language (compiling one takes milliseconds).
+    fn query(self) -> Result<Arc<Query>> {
+        static QUERIES: OnceLock<Mutex<HashMap<SyntaxLanguage, Arc<Query>>>> = OnceLock::new();
+        let cache = QUERIES.get_or_init(Default::default);
+        if let Ok(queries) = cache.lock()
+            && let Some(query) = queries.get(&self)
+        {
+            return Ok(Arc::clone(query));
+        }
+        let query = Query::new(&self.grammar(), &self.highlights_query()).map_err(|e| {
+            SaorsaTuiError::Render(format!("{} highlights query: {e}", self.name()))
+        })?;
+        let query = Arc::new(query);
+        if let Ok(mut queries) = cache.lock() {
+            queries.insert(self, Arc::clone(&query));
+        }
+        Ok(query)
+    }
+}
+
+/// A [`Highlighter`] backed by a tree-sitter parse of the whole document.
+///
+/// The document arrives through [`Highlighter::set_text`] and is reparsed
+/// incrementally after each edit reported through [`Highlighter::on_edit`]:
+/// the old tree is edited and reused. Captures of the language's
+/// highlights query are styled by a [`SyntaxTheme`].
+pub struct TreeSitterHighlighter {
+    language: SyntaxLanguage,
+    parser: Parser,
+    query: Arc<Query>,
+    /// Style per capture index of `query`.
+    styles: Vec<Option<Style>>,
+    tree: Option<Tree>,
+    source: String,
+    /// Byte offset of the start of each line of `source`.
+    line_starts: Vec<usize>,
+}
+
+impl TreeSitterHighlighter {
+    /// Create a highlighter for a language, styled by the default theme.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SaorsaTuiError::Render`] if the grammar cannot be loaded
+    /// or its highlights query does not compile.
+    pub fn new(language: SyntaxLanguage) -> Result<Self> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&language.grammar())
+            .map_err(|e| SaorsaTuiError::Render(format!("{} grammar: {e}", language.name())))?;
+        let mut highlighter = Self {
+            language,
+            parser,
+            query: language.query()?,
+            styles: Vec::new(),
+            tree: None,
+            source: String::new(),
+            line_starts: vec![0],
+        };
+        highlighter.set_theme(&SyntaxTheme::default());
+        Ok(highlighter)
+    }
+
+    /// Create a highlighter for a language name or extension.
+    ///
+    /// Returns `None` for languages without a bundled grammar.
+    pub fn for_name(name: &str) -> Option<Self> {
+        SyntaxLanguage::from_name(name).and_then(|lang| Self::new(lang).ok())
+    }
+
+    /// Set the syntax theme.
+    #[must_use]
+    pub fn with_theme(mut self, theme: &SyntaxTheme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    /// Set the initial document text.
+    #[must_use]
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    /// The highlighted language.
+    pub fn language(&self) -> SyntaxLanguage {
+        self.language
+    }
+
+    /// The current syntax tree, if the document has been parsed.
+    pub fn tree(&self) -> Option<&Tree> {
+        self.tree.as_ref()
+    }
+
+    /// Edit the old tree to match `text`, replacing the bytes between the
+    /// common prefix and suffix of the old and new documents.
+    fn edit_tree(&mut self, text: &str) {
+        let Some(tree) = self.tree.as_mut() else {
+            return;
+        };
+        let old = self.source.as_bytes();
+        let new = text.as_bytes();
+        let mut start = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+        while !(self.source.is_char_boundary(start) && text.is_char_boundary(start)) {
+            start -= 1;
+        }
+        let suffix = old[start..]
+            .iter()
+            .rev()
+            .zip(new[start..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut old_end = old.len() - suffix;
+        let mut new_end = new.len() - suffix;
+        while !(self.source.is_char_boundary(old_end) && text.is_char_boundary(new_end)) {
+            old_end += 1;
+            new_end += 1;
+        }
+        let new_starts = line_starts(text);
+        tree.edit(&InputEdit {
+            start_byte: start,
+            old_end_byte: old_end,
+            new_end_byte: new_end,
+            start_position: point(&self.line_starts, start),
+            old_end_position: point(&self.line_starts, old_end),
+            new_end_position: point(&new_starts, new_end),
+        });
+        self.line_starts = new_starts;
+    }
+}
+
+impl Highlighter for TreeSitterHighlighter {
+    fn highlight_line(&self, line_idx: usize, text: &str) -> Vec<HighlightSpan> {
+        let Some(tree) = &self.tree else {
+            return Vec::new();
+        };
+        let Some(&start) = self.line_starts.get(line_idx) else {
+            return Vec::new();
+        };
+        let end = start + text.len();
+        // A line that does not match the parsed document (an edit was
+        // missed) is left plain rather than mis-styled.
+        if self.source.get(start..end) != Some(text) {
+            return Vec::new();
+        }
+
+        // Paint every styled capture over the line's bytes, outer nodes
+        // first so nested captures (an escape inside a string) win; for
+        // the same node the earliest pattern wins, as in tree-sitter's
+        // own highlighter.
+        let mut painted = Vec::new();
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(start..end);
+        let mut captures = cursor.captures(&self.query, tree.root_node(), self.source.as_bytes());
+        while let Some((m, i)) = captures.next() {
+            let capture = m.captures[*i];
+            if self
+                .styles
+                .get(capture.index as usize)
+                .is_some_and(Option::is_some)
+            {
+                let range = capture.node.byte_range();
+                painted.push((range.start, range.end, m.pattern_index, capture.index));
+            }
+        }
+        painted.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(b.2.cmp(&a.2)));
+        let mut by_byte: Vec<Option<u32>> = vec![None; text.len()];
+        for (from, to, _, capture) in painted {
+            let from = from.max(start) - start;
+            let to = to.min(end).saturating_sub(start);
+            for slot in by_byte.iter_mut().take(to).skip(from) {
+                *slot = Some(capture);
+            }
+        }
+
+        let mut spans: Vec<HighlightSpan> = Vec::new();
+        let mut current: Option<(u32, usize)> = None;
+        let mut col = 0;
+        for (byte, _) in text.char_indices() {
+            let capture = by_byte[byte];
+            if current.map(|(c, _)| c) != capture {
+                if let Some((c, from)) = current.take() {
+                    push_span(&mut spans, &self.styles, c, from, col);
+                }
+                current = capture.map(|c| (c, col));
+            }
+            col += 1;
+        }
+        if let Some((c, from)) = current {
+            push_span(&mut spans, &self.styles, c, from, col);
+        }
+        spans
+    }
+
+    fn on_edit(&mut self, edit: &TextEdit<'_>) {
+        let (start, old_end) = (edit.start_byte, edit.old_end_byte);
+        // An edit that does not fit the document it was given is ignored;
+        // the lines it touched no longer match and are left plain.
+        if start > old_end
+            || !self.source.is_char_boundary(start)
+            || !self.source.is_char_boundary(old_end)
+        {
+            return;
+        }
+        let new_end = start + edit.text.len();
+        let start_position = point(&self.line_starts, start);
+        let old_end_position = point(&self.line_starts, old_end);
+        self.source.replace_range(start..old_end, edit.text);
+
+        // Lines starting inside the replaced bytes go, the inserted text
+        // adds its own, and those after the edit move with it.
+        let kept = self.line_starts.partition_point(|&s| s <= start);
+        let after = self.line_starts.partition_point(|&s| s <= old_end);
+        let inserted = edit
+            .text
+            .match_indices('\n')
+            .map(|(i, _)| start + i + 1)
+            .collect::<Vec<_>>();
+        let moved = self.line_starts[after..]
+            .iter()
+            .map(|&s| s - old_end + new_end)
+            .collect::<Vec<_>>();
+        self.line_starts.truncate(kept);
+        self.line_starts.extend(inserted);
+        self.line_starts.extend(moved);
+
+        if let Some(tree) = self.tree.as_mut() {
+            tree.edit(&InputEdit {
+                start_byte: start,
+                old_end_byte: old_end,
+                new_end_byte: new_end,
+                start_position,
+                old_end_position,
+                new_end_position: point(&self.line_starts, new_end),
+            });
+        }
+        self.tree = self.parser.parse(&self.source, self.tree.as_ref());
+    }
+
+    fn set_text(&mut self, text: &str) {
+        if self.tree.is_some() && text == self.source {
+            return;
+        }
+        if self.tree.is_some() {
+            self.edit_tree(text);
+        } else {
+            self.line_starts = line_starts(text);
+        }
+        self.tree = self.parser.parse(text, self.tree.as_ref());
+        text.clone_into(&mut self.source);
+    }
+
+    fn set_theme(&mut self, theme: &SyntaxTheme) {
+        self.styles = self
+            .query
+            .capture_names()
+            .iter()
+            .map(|name| theme.style_for(name).cloned())
+            .collect();
+    }
+}
+
+fn push_span(
+    spans: &mut Vec<HighlightSpan>,
+    styles: &[Option<Style>],
+    capture: u32,
+    start_col: usize,
+    end_col: usize,
+) {
+    if let Some(Some(style)) = styles.get(capture as usize) {
+        spans.push(HighlightSpan {
+            start_col,
+            end_col,
+            style: style.clone(),
+        });
+    }
+}
+
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// The row and byte column of `byte`.
+fn point(line_starts: &[usize], byte: usize) -> Point {
+    let row = line_starts
+        .partition_point(|&start| start <= byte)
+        .saturating_sub(1);
+    Point::new(row, byte - line_starts.get(row).copied().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{Color, NamedColor};
+
+    fn highlighter(lang: SyntaxLanguage, text: &str) -> TreeSitterHighlighter {
+        match TreeSitterHighlighter::new(lang) {
+            Ok(h) => h.with_text(text),
+            Err(e) => unreachable!("{} should load: {e}", lang.name()),
+        }
+    }
+
+    /// The text covered by each span of a line.
+    fn styled_words(h: &TreeSitterHighlighter, line_idx: usize, line: &str) -> Vec<String> {
+        let chars: Vec<char> = line.chars().collect();
+        h.highlight_line(line_idx, line)
+            .iter()
+            .map(|s| chars[s.start_col..s.end_col].iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn every_bundled_grammar_loads() {
+        for lang in SyntaxLanguage::ALL {
+            let h = TreeSitterHighlighter::new(lang);
+            assert!(h.is_ok(), "{}: {:?}", lang.name(), h.err());
+        }
+    }
+
+    #[test]
+    fn names_and_extensions_resolve() {
+        assert_eq!(SyntaxLanguage::from_name("RS"), Some(SyntaxLanguage::Rust));
+        assert_eq!(SyntaxLanguage::from_name("sh"), Some(SyntaxLanguage::Bash));
+        assert_eq!(
+            SyntaxLanguage::from_path("src/app.tsx"),
+            Some(SyntaxLanguage::Tsx)
+        );
+        assert_eq!(SyntaxLanguage::from_name("cobol"), None);
+    }
+
+    #[test]
+    fn rust_keywords_strings_and_comments() {
+        let src = "fn main() {\n    let s = \"hi\"; // note\n}\n";
+        let h = highlighter(SyntaxLanguage::Rust, src);
+        assert!(styled_words(&h, 0, "fn main() {").contains(&"fn".to_string()));
+        let words = styled_words(&h, 1, "    let s = \"hi\"; // note");
+        assert!(words.contains(&"let".to_string()));
+        assert!(words.contains(&"\"hi\"".to_string()));
+        assert!(words.contains(&"// note".to_string()));
+
+        let spans = h.highlight_line(0, "fn main() {");
+        assert_eq!(
+            spans[0].style.fg,
+            Some(Color::Named(NamedColor::Magenta)),
+            "keyword style from the default theme"
+        );
+    }
+
+    #[test]
+    fn multi_line_nodes_style_each_line() {
+        let src = "x = 1\ns = \"\"\"\ninside\n\"\"\"\n";
+        let h = highlighter(SyntaxLanguage::Python, src);
+        assert_eq!(styled_words(&h, 2, "inside"), vec!["inside".to_string()]);
+    }
+
+    #[test]
+    fn incremental_reparse_follows_edits() {
+        let mut h = highlighter(SyntaxLanguage::Rust, "let a = 1;\nlet b = 2;\n");
+        h.on_edit(&TextEdit {
+            line: 1,
+            start_byte: 11,
+            old_end_byte: 14,
+            text: "//",
+        });
+        assert_eq!(h.source, "let a = 1;\n// b = 2;\n");
+        assert_eq!(
+            styled_words(&h, 1, "// b = 2;"),
+            vec!["// b = 2;".to_string()]
+        );
+        assert_eq!(styled_words(&h, 0, "let a = 1;")[0], "let");
+
+        // Edits that add lines shift what follows.
+        h.on_edit(&TextEdit {
+            line: 0,
+            start_byte: 0,
+            old_end_byte: 0,
+            text: "/* one\ntwo */\n",
+        });
+        assert_eq!(styled_words(&h, 1, "two */"), vec!["two */".to_string()]);
+        assert_eq!(styled_words(&h, 2, "let a = 1;")[0], "let");
+        assert!(h.tree().is_some_and(|t| !t.root_node().has_error()));
+
+        // Edits that join lines shift it back.
+        h.on_edit(&TextEdit {
+            line: 0,
+            start_byte: 6,
+            old_end_byte: 7,
+            text: " ",
+        });
+        assert_eq!(h.line_starts, line_starts(&h.source));
+        assert_eq!(styled_words(&h, 1, "let a = 1;")[0], "let");
+        assert!(h.tree().is_some_and(|t| !t.root_node().has_error()));
+    }
+
+    #[test]
+    fn edits_outside_the_document_are_ignored() {
+        let mut h = highlighter(SyntaxLanguage::Rust, "fn main() {}\n");
+        h.on_edit(&TextEdit {
+            line: 3,
+            start_byte: 40,
+            old_end_byte: 41,
+            text: "x",
+        });
+        assert_eq!(h.source, "fn main() {}\n");
+        assert_eq!(styled_words(&h, 0, "fn main() {}")[0], "fn");
+    }
+
+    #[test]
+    fn unicode_columns_are_characters() {
+        let src = "let é = \"日本\";\n";
+        let h = highlighter(SyntaxLanguage::Rust, src);
+        let line = "let é = \"日本\";";
+        let spans = h.highlight_line(0, line);
+        let string = spans.iter().find(|s| s.start_col == 8);
+        assert!(string.is_some_and(|s| s.end_col == 12), "{spans:?}");
+    }
+
+    #[test]
+    fn stale_lines_are_left_plain() {
+        let h = highlighter(SyntaxLanguage::Rust, "fn main() {}\n");
+        assert!(h.highlight_line(0, "fn mian() {}").is_empty());
+        assert!(h.highlight_line(5, "fn").is_empty());
+    }
+
+    #[test]
+    fn theme_styles_captures() {
+        let theme = SyntaxTheme::empty().with("keyword", Style::new().bold(true));
+        let h = highlighter(SyntaxLanguage::Go, "package main\n").with_theme(&theme);
+        let spans = h.highlight_line(0, "package main");
+        assert_eq!(spans.len(), 1);
+        assert!(spans[0].style.bold);
+    }
+
+    #[test]
+    fn other_grammars_highlight() {
+        let cases = [
+            (SyntaxLanguage::Json, "{\"a\": 1}", "1"),
+            (SyntaxLanguage::Toml, "name = \"x\"", "\"x\""),
+            (SyntaxLanguage::Bash, "echo \"hi\"", "\"hi\""),
+            (SyntaxLanguage::TypeScript, "const x: number = 1;", "const"),
+            (SyntaxLanguage::Markdown, "# Title", "Title"),
+        ];
+        for (lang, line, word) in cases {
+            let h = highlighter(lang, &format!("{line}\n"));
+            let words = styled_words(&h, 0, line);
+            assert!(
+                words.iter().any(|w| w.contains(word)),
+                "{}: {words:?}",
+                lang.name()
+            );
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Capture-name → style mapping for syntax highlighting.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::color::{Color, NamedColor};
+use crate::style::Style;
+use crate::tcss::apply::style_from_value;
+use crate::tcss::variable::VariableEnvironment;
+
+/// Theme variables consulted, in order, for each capture when building a
+/// [`SyntaxTheme`] from TCSS variables. The first defined variable wins;
+/// the explicit `$syntax-*` name always comes first, then palette names
+/// used by the bundled themes, then the semantic slots every theme has.
+const VARIABLE_FALLBACKS: &[(&str, &[&str])] = &[
+    ("keyword", &["syntax-keyword", "mauve", "primary"]),
+    ("function", &["syntax-function", "blue", "secondary"]),
+    ("type", &["syntax-type", "yellow", "warning"]),
+    ("constructor", &["syntax-constructor", "yellow", "warning"]),
+    ("attribute", &["syntax-attribute", "yellow", "warning"]),
+    ("constant", &["syntax-constant", "peach", "warning"]),
+    ("number", &["syntax-number", "peach", "warning"]),
+    (
+        "string",
+        &["syntax-string", "green", "success", "secondary"],
+    ),
+    ("escape", &["syntax-escape", "pink", "error"]),
+    ("comment", &["syntax-comment", "overlay1", "border"]),
+    ("tag", &["syntax-tag", "red", "error"]),
+    (
+        "variable.builtin",
+        &["syntax-variable-builtin", "red", "error"],
+    ),
+    ("label", &["syntax-label", "sapphire", "secondary"]),
+    ("text.title", &["syntax-heading", "blue", "primary"]),
+    (
+        "text.literal",
+        &["syntax-literal", "green", "success", "secondary"],
+    ),
+    ("text.uri", &["syntax-link", "sapphire", "secondary"]),
+];
+
+/// Maps tree-sitter capture names (`keyword`, `function.method`, …) to
+/// styles.
+///
+/// Lookups fall back through the dotted hierarchy: `function.method`
+/// uses the `function.method` style if set, otherwise `function`.
+/// Captures without a style are left unhighlighted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntaxTheme {
+    styles: HashMap<String, Style>,
+}
+
+impl SyntaxTheme {
+    /// Create a theme with no styles.
+    pub fn empty() -> Self {
+        Self {
+            styles: HashMap::new(),
+        }
+    }
+
+    /// Set the style for a capture name.
+    #[must_use]
+    pub fn with(mut self, capture: &str, style: Style) -> Self {
+        self.set(capture, style);
+        self
+    }
+
+    /// Set the style for a capture name.
+    pub fn set(&mut self, capture: &str, style: Style) {
+        self.styles.insert(capture.to_string(), style);
+    }
+
+    /// The style for a capture name, falling back to its parents.
+    pub fn style_for(&self, capture: &str) -> Option<&Style> {
+        let mut name = capture;
+        loop {
+            if let Some(style) = self.styles.get(name) {
+                return Some(style);
+            }
+            name = &name[..name.rfind('.')?];
+        }
+    }
+
+    /// Build a theme from TCSS variables, on top of the default theme.
+    ///
+    /// Each capture family reads its own `$syntax-<name>` variable
+    /// (`$syntax-keyword`, `$syntax-string`, …) and otherwise falls back
+    /// to the active theme's palette (`$primary`, `$warning`, `$border`,
+    /// …). Any other `$syntax-<a>-<b>` variable styles the `a.b` capture,
+    /// so `$syntax-function-macro: red bold;` styles macro invocations.
+    /// Values may be colors or text-style keywords.
+    pub fn from_variables(vars: &VariableEnvironment) -> Self {
+        let mut theme = Self::default();
+        for (capture, names) in VARIABLE_FALLBACKS {
+            let Some(value) = names.iter().find_map(|name| vars.resolve(name)) else {
+                continue;
+            };
+            let base = theme.styles.get(*capture).cloned().unwrap_or_default();
+            if let Some(style) = style_from_value(value, base) {
+                theme.set(capture, style);
+            }
+        }
+
+        let explicit: BTreeSet<&str> = vars
+            .global()
+            .iter()
+            .chain(vars.theme().iter())
+            .map(|(name, _)| name)
+            .filter(|name| name.starts_with("syntax-"))
+            .filter(|name| {
+                !VARIABLE_FALLBACKS
+                    .iter()
+                    .any(|(_, names)| names[0] == *name)
+            })
+            .collect();
+        for name in explicit {
+            let Some(value) = vars.resolve(name) else {
+                continue;
+            };
+            if let Some(style) = style_from_value(value, Style::default()) {
+                theme.set(&name["syntax-".len()..].replace('-', "."), style);
+            }
+        }
+        theme
+    }
+}
+
+impl Default for SyntaxTheme {
+    /// Styles using the 16 ANSI colors, so they follow the terminal's
+    /// palette.
+    fn default() -> Self {
+        let named = |c| Style::new().fg(Color::Named(c));
+        Self::empty()
+            .with("keyword", named(NamedColor::Magenta))
+            .with("function", named(NamedColor::Blue))
+            .with("type", named(NamedColor::Yellow))
+            .with("constructor", named(NamedColor::Yellow))
+            .with("attribute", named(NamedColor::Yellow))
+            .with("constant", named(NamedColor::Cyan))
+            .with("number", named(NamedColor::Cyan))
+            .with("boolean", named(NamedColor::Cyan))
+            .with("string", named(NamedColor::Green))
+            .with("escape", named(NamedColor::Cyan))
+            .with("string.escape", named(NamedColor::Cyan))
+            .with("comment", named(NamedColor::BrightBlack).italic(true))
+            .with("tag", named(NamedColor::Red))
+            .with("variable.builtin", named(NamedColor::Red))
+            .with("label", named(NamedColor::Cyan))
+            .with("text.title", named(NamedColor::Blue).bold(true))
+            .with("text.literal", named(NamedColor::Green))
+            .with("text.uri", named(NamedColor::Cyan).underline(true))
+            .with("text.emphasis", Style::new().italic(true))
+            .with("text.strong", Style::new().bold(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tcss::value::CssValue;
+
+    #[test]
+    fn style_lookup_falls_back_to_parent_captures() {
+        let theme = SyntaxTheme::empty().with("function", Style::new().bold(true));
+        assert!(
+            theme
+                .style_for("function.method.call")
+                .is_some_and(|s| s.bold)
+        );
+        assert!(theme.style_for("keyword").is_none());
+    }
+
+    #[test]
+    fn palette_variables_drive_the_theme() {
+        let mut vars = VariableEnvironment::new();
+        vars.set_theme(
+            "primary",
+            CssValue::Color(Color::Named(NamedColor::BrightRed)),
+        );
+        let theme = SyntaxTheme::from_variables(&vars);
+        assert_eq!(
+            theme.style_for("keyword").and_then(|s| s.fg.clone()),
+            Some(Color::Named(NamedColor::BrightRed))
+        );
+        // Captures without a variable keep their default style.
+        assert_eq!(
+            theme.style_for("string").and_then(|s| s.fg.clone()),
+            Some(Color::Named(NamedColor::Green))
+        );
+    }
+
+    #[test]
+    fn syntax_variables_override_the_palette() {
+        let mut vars = VariableEnvironment::new();
+        vars.set_theme("primary", CssValue::Color(Color::Named(NamedColor::Red)));
+        vars.set_global("syntax-keyword", CssValue::Keyword("blue bold".to_string()));
+        vars.set_theme(
+            "syntax-function-macro",
+            CssValue::Color(Color::Named(NamedColor::Yellow)),
+        );
+        let theme = SyntaxTheme::from_variables(&vars);
+        let keyword = theme.style_for("keyword").cloned().unwrap_or_default();
+        assert_eq!(keyword.fg, Some(Color::Named(NamedColor::Blue)));
+        assert!(keyword.bold);
+        assert_eq!(
+            theme.style_for("function.macro").and_then(|s| s.fg.clone()),
+            Some(Color::Named(NamedColor::Yellow))
+        );
+    }
+}
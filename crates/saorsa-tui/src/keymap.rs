@@ -0,0 +1,220 @@
+//! Modal keymaps for text editing widgets.
+//!
+//! A [`Keymap`] sits in front of a text widget's key handling and
+//! translates keys into edits through the [`TextEditor`] trait. The
+//! standard keymap passes every key straight through; the Vim keymap adds
+//! normal, insert and visual modes with operators, motions, counts, text
+//! objects, registers and `.` repeat; the Emacs keymap adds the kill ring,
+//! the mark and the usual `C-`/`M-` motions.
+//!
+//! The current [`EditMode`] is exposed for status lines
+//! ([`EditMode::label`]) and for TCSS as a pseudo-class
+//! ([`EditMode::pseudo_class`]), so `TextArea:vim-insert` can style a
+//! widget while it is in insert mode.
+
+mod emacs;
+mod vim;
+
+use std::ops::Range;
+
+use crate::clipboard::Clipboard;
+use crate::event::KeyEvent;
+use crate::text_buffer::TextBuffer;
+use crate::widget::EventResult;
+
+/// Which keymap a text widget uses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum KeymapKind {
+    /// The widget's own bindings.
+    #[default]
+    Standard,
+    /// Modal Vim bindings, starting in normal mode.
+    Vim,
+    /// Emacs bindings.
+    Emacs,
+}
+
+/// The editing mode a keymap is in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EditMode {
+    /// The standard keymap.
+    #[default]
+    Standard,
+    /// Vim normal mode.
+    VimNormal,
+    /// Vim insert mode.
+    VimInsert,
+    /// Vim characterwise visual mode.
+    VimVisual,
+    /// Vim linewise visual mode.
+    VimVisualLine,
+    /// The Emacs keymap.
+    Emacs,
+}
+
+impl EditMode {
+    /// The TCSS pseudo-class for this mode (`vim-normal`, `vim-insert`,
+    /// `vim-visual`, `vim-visual-line` or `emacs`), if any.
+    pub fn pseudo_class(self) -> Option<&'static str> {
+        match self {
+            Self::Standard => None,
+            Self::VimNormal => Some("vim-normal"),
+            Self::VimInsert => Some("vim-insert"),
+            Self::VimVisual => Some("vim-visual"),
+            Self::VimVisualLine => Some("vim-visual-line"),
+            Self::Emacs => Some("emacs"),
+        }
+    }
+
+    /// A short label for status lines, as Vim shows it.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Standard => "",
+            Self::VimNormal => "NORMAL",
+            Self::VimInsert => "INSERT",
+            Self::VimVisual => "VISUAL",
+            Self::VimVisualLine => "V-LINE",
+            Self::Emacs => "EMACS",
+        }
+    }
+}
+
+/// The editing surface a [`Keymap`] drives.
+///
+/// Positions are character indices into [`buffer`](Self::buffer).
+pub trait TextEditor {
+    /// The text being edited.
+    fn buffer(&self) -> &TextBuffer;
+
+    /// The cursor as a character index.
+    fn cursor(&self) -> usize;
+
+    /// Move the cursor, clearing any selection.
+    fn set_cursor(&mut self, idx: usize);
+
+    /// Highlight a range as selected without moving the cursor, or clear
+    /// the selection.
+    fn set_selection(&mut self, range: Option<Range<usize>>);
+
+    /// Replace a range of characters, leaving the cursor after the new
+    /// text.
+    fn replace(&mut self, range: Range<usize>, text: &str);
+
+    /// Start recording edits as one undo step. Calls nest.
+    fn begin_change(&mut self);
+
+    /// End an undo step started with [`begin_change`](Self::begin_change).
+    fn end_change(&mut self);
+
+    /// Undo the last change.
+    fn undo(&mut self);
+
+    /// Redo the last undone change.
+    fn redo(&mut self);
+
+    /// Handle a key the keymap does not bind, as the widget normally would
+    /// (typing, arrows, Backspace, …).
+    fn insert_key(&mut self, key: &KeyEvent) -> EventResult;
+
+    /// Lines moved by a page motion.
+    fn page_rows(&self) -> usize {
+        10
+    }
+
+    /// One level of indentation for Vim's `>` and `<`.
+    fn indent_unit(&self) -> &str {
+        "    "
+    }
+
+    /// The system clipboard, for Vim's `+` and `*` registers and the Emacs
+    /// kill ring.
+    fn clipboard(&self) -> Option<&Clipboard> {
+        None
+    }
+}
+
+/// The keymap engine and its state.
+#[derive(Debug, Default)]
+enum Engine {
+    #[default]
+    Standard,
+    Vim(Box<vim::Vim>),
+    Emacs(emacs::Emacs),
+}
+
+/// A keymap for a text widget, with its modal state.
+///
+/// Widgets route key events through [`handle_key`](Self::handle_key);
+/// keys the keymap does not bind reach [`TextEditor::insert_key`].
+#[derive(Debug, Default)]
+pub struct Keymap {
+    engine: Engine,
+}
+
+impl Keymap {
+    /// Create a keymap of the given kind.
+    pub fn new(kind: KeymapKind) -> Self {
+        let engine = match kind {
+            KeymapKind::Standard => Engine::Standard,
+            KeymapKind::Vim => Engine::Vim(Box::default()),
+            KeymapKind::Emacs => Engine::Emacs(emacs::Emacs::default()),
+        };
+        Self { engine }
+    }
+
+    /// Which keymap this is.
+    pub fn kind(&self) -> KeymapKind {
+        match self.engine {
+            Engine::Standard => KeymapKind::Standard,
+            Engine::Vim(_) => KeymapKind::Vim,
+            Engine::Emacs(_) => KeymapKind::Emacs,
+        }
+    }
+
+    /// The current editing mode.
+    pub fn mode(&self) -> EditMode {
+        match &self.engine {
+            Engine::Standard => EditMode::Standard,
+            Engine::Vim(vim) => vim.mode(),
+            Engine::Emacs(_) => EditMode::Emacs,
+        }
+    }
+
+    /// Keys of a command still being typed, such as `2d` or `"a` in Vim or
+    /// `C-x` in Emacs.
+    pub fn pending(&self) -> &str {
+        match &self.engine {
+            Engine::Standard => "",
+            Engine::Vim(vim) => vim.pending(),
+            Engine::Emacs(emacs) => emacs.pending(),
+        }
+    }
+
+    /// The mode label followed by any pending keys, for a status line.
+    pub fn status(&self) -> String {
+        let label = self.mode().label();
+        match self.pending() {
+            "" => label.to_string(),
+            pending => format!("{label} {pending}"),
+        }
+    }
+
+    /// Handle a key, editing through `editor`.
+    pub fn handle_key(&mut self, editor: &mut dyn TextEditor, key: &KeyEvent) -> EventResult {
+        match &mut self.engine {
+            Engine::Standard => editor.insert_key(key),
+            Engine::Vim(vim) => vim.handle_key(editor, key),
+            Engine::Emacs(emacs) => emacs.handle_key(editor, key),
+        }
+    }
+
+    /// Drop any half-typed command and close an open undo step (such as a
+    /// Vim insert session), e.g. before switching keymaps.
+    pub fn finish(&mut self, editor: &mut dyn TextEditor) {
+        match &mut self.engine {
+            Engine::Standard => {}
+            Engine::Vim(vim) => vim.finish(editor),
+            Engine::Emacs(emacs) => emacs.finish(editor),
+        }
+    }
+}
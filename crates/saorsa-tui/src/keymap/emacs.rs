@@ -0,0 +1,354 @@
+//! The Emacs keymap: kill ring, mark and `C-`/`M-` motions.
+
+use std::ops::Range;
+
+use crate::event::{KeyCode, KeyEvent, Modifiers};
+use crate::widget::EventResult;
+
+use super::TextEditor;
+
+/// Entries kept in the kill ring.
+const KILL_RING_MAX: usize = 60;
+
+/// Emacs keymap state.
+#[derive(Debug, Default)]
+pub(crate) struct Emacs {
+    /// The mark, while the region is active.
+    mark: Option<usize>,
+    /// Killed text, most recent last.
+    kill_ring: Vec<String>,
+    /// Whether the last command killed text, so the next kill appends.
+    last_kill: bool,
+    /// The text the last yank inserted and how far back in the ring it
+    /// came from, for `M-y`.
+    last_yank: Option<(Range<usize>, usize)>,
+    /// Column kept across `C-n`/`C-p`.
+    want_col: Option<usize>,
+    /// Whether `C-x` was pressed.
+    prefix: bool,
+}
+
+impl Emacs {
+    pub fn pending(&self) -> &str {
+        if self.prefix { "C-x" } else { "" }
+    }
+
+    pub fn finish(&mut self, ed: &mut dyn TextEditor) {
+        self.prefix = false;
+        self.deactivate_mark(ed);
+    }
+
+    pub fn handle_key(&mut self, ed: &mut dyn TextEditor, key: &KeyEvent) -> EventResult {
+        let ctrl = key.modifiers.contains(Modifiers::CTRL);
+        let meta =
+            key.modifiers.contains(Modifiers::ALT) || key.modifiers.contains(Modifiers::META);
+        let appending = std::mem::take(&mut self.last_kill);
+        let last_yank = self.last_yank.take();
+        let want_col = self.want_col.take();
+
+        if std::mem::take(&mut self.prefix) {
+            match key.code {
+                KeyCode::Char('x') if ctrl => self.exchange_point_and_mark(ed),
+                KeyCode::Char('u') if !ctrl && !meta => self.undo(ed),
+                _ => {}
+            }
+            return EventResult::Consumed;
+        }
+
+        let buf = ed.buffer();
+        let cur = ed.cursor();
+        let (line, _) = buf.position_of(cur);
+        let len = buf.total_chars();
+        let (line_start, line_end) = (buf.char_index(line, 0), buf.char_index(line, usize::MAX));
+        let (word_start, word_end) = (buf.word_start_before(cur), buf.word_end_after(cur));
+        let page = ed.page_rows().max(1);
+        match key.code {
+            KeyCode::Char(c) if ctrl && !meta => match c.to_ascii_lowercase() {
+                'f' => self.move_to(ed, (cur + 1).min(len)),
+                'b' => self.move_to(ed, cur.saturating_sub(1)),
+                'a' => self.move_to(ed, line_start),
+                'e' => self.move_to(ed, line_end),
+                'n' | 'p' => self.vertical(ed, 1, c == 'p', want_col),
+                'v' => self.vertical(ed, page, false, want_col),
+                'd' => {
+                    self.deactivate_mark(ed);
+                    ed.replace(cur..(cur + 1).min(len), "");
+                }
+                'k' => {
+                    let end = if cur < line_end {
+                        line_end
+                    } else {
+                        (cur + 1).min(len)
+                    };
+                    self.kill(ed, cur..end, appending, false);
+                }
+                'w' => match self.mark {
+                    Some(mark) => self.kill(ed, mark.min(cur)..mark.max(cur), appending, false),
+                    None => self.last_kill = appending,
+                },
+                'y' => self.yank(ed),
+                ' ' | '@' => {
+                    self.mark = Some(cur);
+                    ed.set_selection(Some(cur..cur));
+                }
+                'g' => self.deactivate_mark(ed),
+                'x' => self.prefix = true,
+                '/' | '_' | '7' => self.undo(ed),
+                _ => return self.pass_through(ed, key),
+            },
+            KeyCode::Char(c) if meta && !ctrl => match c {
+                'f' => self.move_to(ed, word_end),
+                'b' => self.move_to(ed, word_start),
+                '<' => self.move_to(ed, 0),
+                '>' => self.move_to(ed, len),
+                'v' => self.vertical(ed, page, true, want_col),
+                'd' => self.kill(ed, cur..word_end, appending, false),
+                'w' => {
+                    if let Some(mark) = self.mark {
+                        let text = ed.buffer().slice(mark.min(cur)..mark.max(cur));
+                        self.push_kill(ed, text, appending, false);
+                        self.deactivate_mark(ed);
+                    }
+                }
+                'y' => match last_yank {
+                    Some(yank) => self.yank_pop(ed, yank),
+                    None => return self.pass_through(ed, key),
+                },
+                _ => return self.pass_through(ed, key),
+            },
+            KeyCode::Backspace if meta => self.kill(ed, word_start..cur, appending, true),
+            _ => return self.pass_through(ed, key),
+        }
+        EventResult::Consumed
+    }
+
+    /// Give an unbound key to the widget, dropping the mark.
+    fn pass_through(&mut self, ed: &mut dyn TextEditor, key: &KeyEvent) -> EventResult {
+        self.deactivate_mark(ed);
+        ed.insert_key(key)
+    }
+
+    /// Move point, extending the region while the mark is active.
+    fn move_to(&mut self, ed: &mut dyn TextEditor, idx: usize) {
+        ed.set_cursor(idx);
+        if let Some(mark) = self.mark {
+            ed.set_selection(Some(mark.min(idx)..mark.max(idx)));
+        }
+    }
+
+    fn vertical(&mut self, ed: &mut dyn TextEditor, rows: usize, up: bool, want: Option<usize>) {
+        let buf = ed.buffer();
+        let (line, col) = buf.position_of(ed.cursor());
+        let col = want.unwrap_or(col);
+        let target = if up {
+            line.saturating_sub(rows)
+        } else {
+            (line + rows).min(buf.line_count().saturating_sub(1))
+        };
+        let idx = buf.char_index(target, col);
+        self.move_to(ed, idx);
+        self.want_col = Some(col);
+    }
+
+    fn deactivate_mark(&mut self, ed: &mut dyn TextEditor) {
+        if self.mark.take().is_some() {
+            ed.set_selection(None);
+        }
+    }
+
+    fn exchange_point_and_mark(&mut self, ed: &mut dyn TextEditor) {
+        if let Some(mark) = self.mark {
+            let cur = ed.cursor();
+            self.mark = Some(cur);
+            self.move_to(ed, mark);
+        }
+    }
+
+    fn undo(&mut self, ed: &mut dyn TextEditor) {
+        self.deactivate_mark(ed);
+        ed.undo();
+    }
+
+    /// Remove text into the kill ring. Consecutive kills build up one
+    /// entry, backward kills prepending to it.
+    fn kill(
+        &mut self,
+        ed: &mut dyn TextEditor,
+        range: Range<usize>,
+        appending: bool,
+        backward: bool,
+    ) {
+        self.deactivate_mark(ed);
+        if range.is_empty() {
+            self.last_kill = appending;
+            return;
+        }
+        let text = ed.buffer().slice(range.clone());
+        ed.replace(range.clone(), "");
+        ed.set_cursor(range.start);
+        self.push_kill(ed, text, appending, backward);
+    }
+
+    fn push_kill(&mut self, ed: &dyn TextEditor, text: String, appending: bool, backward: bool) {
+        match self.kill_ring.last_mut() {
+            Some(top) if appending => {
+                if backward {
+                    top.insert_str(0, &text);
+                } else {
+                    top.push_str(&text);
+                }
+            }
+            _ => {
+                self.kill_ring.push(text);
+                if self.kill_ring.len() > KILL_RING_MAX {
+                    self.kill_ring.remove(0);
+                }
+            }
+        }
+        if let (Some(clipboard), Some(top)) = (ed.clipboard(), self.kill_ring.last()) {
+            clipboard.set_text(top.clone());
+        }
+        self.last_kill = true;
+    }
+
+    /// Insert the most recent kill. Text copied elsewhere since then (on
+    /// the shared clipboard) is yanked instead.
+    fn yank(&mut self, ed: &mut dyn TextEditor) {
+        self.deactivate_mark(ed);
+        if let Some(text) = ed.clipboard().and_then(|c| c.text())
+            && self.kill_ring.last() != Some(&text)
+        {
+            self.kill_ring.push(text);
+        }
+        let Some(text) = self.kill_ring.last().cloned() else {
+            return;
+        };
+        let cur = ed.cursor();
+        self.insert_yank(ed, cur..cur, &text, 0);
+    }
+
+    /// Replace the last yank with the kill before it in the ring.
+    fn yank_pop(&mut self, ed: &mut dyn TextEditor, (range, depth): (Range<usize>, usize)) {
+        if self.kill_ring.is_empty() {
+            return;
+        }
+        let depth = (depth + 1) % self.kill_ring.len();
+        let text = self.kill_ring[self.kill_ring.len() - 1 - depth].clone();
+        self.insert_yank(ed, range, &text, depth);
+    }
+
+    fn insert_yank(
+        &mut self,
+        ed: &mut dyn TextEditor,
+        range: Range<usize>,
+        text: &str,
+        depth: usize,
+    ) {
+        let start = range.start;
+        ed.replace(range, text);
+        let end = start + text.chars().count();
+        ed.set_cursor(end);
+        self.last_yank = Some((start..end, depth));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::{Event, KeyCode, KeyEvent, Modifiers};
+    use crate::keymap::{EditMode, KeymapKind, TextEditor};
+    use crate::widget::{InteractiveWidget, TextArea};
+
+    fn emacs(text: &str) -> TextArea {
+        TextArea::from_text(text).with_keymap(KeymapKind::Emacs)
+    }
+
+    fn ctrl(ta: &mut TextArea, ch: char) {
+        ta.handle_event(&Event::Key(KeyEvent::new(
+            KeyCode::Char(ch),
+            Modifiers::CTRL,
+        )));
+    }
+
+    fn meta(ta: &mut TextArea, ch: char) {
+        ta.handle_event(&Event::Key(KeyEvent::new(
+            KeyCode::Char(ch),
+            Modifiers::ALT,
+        )));
+    }
+
+    #[test]
+    fn consecutive_kills_append() {
+        let mut ta = emacs("one\ntwo\nthree");
+        assert_eq!(ta.edit_mode(), EditMode::Emacs);
+        ctrl(&mut ta, 'k');
+        ctrl(&mut ta, 'k');
+        assert_eq!(ta.text(), "two\nthree");
+        ctrl(&mut ta, 'e');
+        ctrl(&mut ta, 'y');
+        assert_eq!(ta.text(), "twoone\n\nthree");
+    }
+
+    #[test]
+    fn yank_pop_cycles_the_ring() {
+        let mut ta = emacs("one two three");
+        meta(&mut ta, 'd');
+        ctrl(&mut ta, 'f');
+        meta(&mut ta, 'd');
+        assert_eq!(ta.text(), "  three");
+        ctrl(&mut ta, 'y');
+        assert_eq!(ta.text(), " two three");
+        meta(&mut ta, 'y');
+        assert_eq!(ta.text(), " one three");
+        meta(&mut ta, 'y');
+        assert_eq!(ta.text(), " two three");
+    }
+
+    #[test]
+    fn region_kill_and_copy() {
+        let mut ta = emacs("hello world");
+        ctrl(&mut ta, ' ');
+        meta(&mut ta, 'f');
+        ctrl(&mut ta, 'w');
+        assert_eq!(ta.text(), " world");
+        ctrl(&mut ta, 'y');
+        assert_eq!(ta.text(), "hello world");
+
+        ctrl(&mut ta, 'a');
+        ctrl(&mut ta, ' ');
+        ctrl(&mut ta, 'e');
+        meta(&mut ta, 'w');
+        assert_eq!(ta.text(), "hello world");
+        assert_eq!(ta.clipboard().text().as_deref(), Some("hello world"));
+    }
+
+    #[test]
+    fn exchange_point_and_mark() {
+        let mut ta = emacs("hello world");
+        ctrl(&mut ta, '@');
+        ctrl(&mut ta, 'e');
+        assert_eq!(TextEditor::cursor(&ta), 11);
+        ctrl(&mut ta, 'x');
+        assert_eq!(ta.keymap().status(), "EMACS C-x");
+        ctrl(&mut ta, 'x');
+        assert_eq!(TextEditor::cursor(&ta), 0);
+        assert_eq!(ta.pseudo_classes(), vec!["emacs"]);
+    }
+
+    #[test]
+    fn motions() {
+        let mut ta = emacs("ab cd\nef");
+        ctrl(&mut ta, 'e');
+        assert_eq!(TextEditor::cursor(&ta), 5);
+        ctrl(&mut ta, 'a');
+        meta(&mut ta, 'f');
+        assert_eq!(TextEditor::cursor(&ta), 2);
+        meta(&mut ta, 'b');
+        assert_eq!(TextEditor::cursor(&ta), 0);
+        ctrl(&mut ta, 'n');
+        assert_eq!(TextEditor::cursor(&ta), 6);
+        meta(&mut ta, '<');
+        assert_eq!(TextEditor::cursor(&ta), 0);
+        meta(&mut ta, '>');
+        assert_eq!(TextEditor::cursor(&ta), 8);
+    }
+}
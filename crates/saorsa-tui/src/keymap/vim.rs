@@ -0,0 +1,1706 @@
+//! The Vim keymap: normal, insert and visual modes.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::event::{KeyCode, KeyEvent, Modifiers};
+use crate::text_buffer::TextBuffer;
+use crate::widget::EventResult;
+
+use super::{EditMode, TextEditor};
+
+/// How far bracket text objects scan for their delimiters.
+const OBJECT_SCAN_LIMIT: usize = 100_000;
+
+/// Largest count accepted before a command.
+const MAX_COUNT: usize = 99_999;
+
+/// Keys that are motions on their own (`g` starts `gg`, `f`/`t` wait for
+/// a character).
+const MOTION_KEYS: &str = "hl jk+-_0^$wWbBeEG%{};,";
+
+/// Registers that can be named with `"`.
+const REGISTER_NAMES: &str = "\"-_+*0123456789";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Mode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+    VisualLine,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+    Indent,
+    Outdent,
+}
+
+impl Operator {
+    fn from_key(c: char) -> Option<Self> {
+        match c {
+            'd' => Some(Self::Delete),
+            'c' => Some(Self::Change),
+            'y' => Some(Self::Yank),
+            '>' => Some(Self::Indent),
+            '<' => Some(Self::Outdent),
+            _ => None,
+        }
+    }
+}
+
+/// The `f`, `F`, `t` and `T` motions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Find {
+    Forward,
+    Backward,
+    Till,
+    TillBackward,
+}
+
+impl Find {
+    fn reversed(self) -> Self {
+        match self {
+            Self::Forward => Self::Backward,
+            Self::Backward => Self::Forward,
+            Self::Till => Self::TillBackward,
+            Self::TillBackward => Self::Till,
+        }
+    }
+}
+
+/// What the next key completes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Pending {
+    #[default]
+    None,
+    Find(Find),
+    Object {
+        inner: bool,
+    },
+    G,
+    Register,
+    Replace,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MotionKind {
+    /// The range stops before the target.
+    Exclusive,
+    /// The range includes the character at the target.
+    Inclusive,
+    /// The range covers whole lines.
+    Linewise,
+}
+
+struct Motion {
+    target: usize,
+    kind: MotionKind,
+}
+
+impl Motion {
+    fn new(target: usize, kind: MotionKind) -> Self {
+        Self { target, kind }
+    }
+}
+
+/// The text an operator acts on.
+enum Span {
+    Chars(Range<usize>),
+    Lines(usize, usize),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Register {
+    text: String,
+    linewise: bool,
+}
+
+/// A key as Vim sees it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Input {
+    Char(char),
+    Ctrl(char),
+    Escape,
+}
+
+/// Vim keymap state.
+#[derive(Debug, Default)]
+pub(crate) struct Vim {
+    mode: Mode,
+    count: Option<usize>,
+    operator: Option<Operator>,
+    /// The count typed before the operator, multiplied into the motion's.
+    op_count: Option<usize>,
+    pending: Pending,
+    register: Option<char>,
+    registers: HashMap<char, Register>,
+    last_find: Option<(Find, char)>,
+    /// Column kept across vertical motions.
+    want_col: Option<usize>,
+    visual_anchor: usize,
+    /// Whether an undo step is open for the change in progress.
+    in_change: bool,
+    /// Whether the keys being typed started in normal mode and may
+    /// become the change `.` repeats.
+    recording: bool,
+    keys: Vec<KeyEvent>,
+    last_change: Vec<KeyEvent>,
+    replaying: bool,
+    /// Keys of the command being typed, for display.
+    typed: String,
+}
+
+impl Vim {
+    pub fn mode(&self) -> EditMode {
+        match self.mode {
+            Mode::Normal => EditMode::VimNormal,
+            Mode::Insert => EditMode::VimInsert,
+            Mode::Visual => EditMode::VimVisual,
+            Mode::VisualLine => EditMode::VimVisualLine,
+        }
+    }
+
+    pub fn pending(&self) -> &str {
+        &self.typed
+    }
+
+    pub fn finish(&mut self, ed: &mut dyn TextEditor) {
+        self.cancel();
+        if self.in_change {
+            ed.end_change();
+            self.in_change = false;
+        }
+        if matches!(self.mode, Mode::Visual | Mode::VisualLine) {
+            ed.set_selection(None);
+        }
+        self.mode = Mode::Normal;
+    }
+
+    pub fn handle_key(&mut self, ed: &mut dyn TextEditor, key: &KeyEvent) -> EventResult {
+        if self.mode == Mode::Insert {
+            return self.insert_key(ed, key);
+        }
+        let Some(input) = input_of(key) else {
+            self.cancel();
+            return ed.insert_key(key);
+        };
+        if !self.replaying {
+            if self.mode == Mode::Normal && self.is_idle() {
+                self.keys.clear();
+                self.recording = true;
+            }
+            self.keys.push(key.clone());
+        }
+        let literal = match key.code {
+            KeyCode::Char(_) => match input {
+                Input::Char(c) => Some(c),
+                _ => None,
+            },
+            _ => None,
+        };
+        match input {
+            Input::Char(c) => self.typed.push(c),
+            Input::Ctrl(c) => {
+                self.typed.push('^');
+                self.typed.push(c.to_ascii_uppercase());
+            }
+            Input::Escape => {}
+        }
+        let keep_col = matches!(
+            input,
+            Input::Char('j' | 'k' | '0'..='9') | Input::Ctrl('d' | 'u' | 'f' | 'b')
+        );
+        let result = self.command(ed, key, input, literal);
+        if !keep_col {
+            self.want_col = None;
+        }
+        if self.is_idle() {
+            self.typed.clear();
+        }
+        result
+    }
+
+    // --- Command parsing ---
+
+    fn is_idle(&self) -> bool {
+        self.count.is_none()
+            && self.operator.is_none()
+            && self.op_count.is_none()
+            && self.pending == Pending::None
+            && self.register.is_none()
+    }
+
+    /// Drop a half-typed command.
+    fn cancel(&mut self) {
+        self.count = None;
+        self.operator = None;
+        self.op_count = None;
+        self.pending = Pending::None;
+        self.register = None;
+        self.typed.clear();
+    }
+
+    /// The count for the command: the counts typed before the operator and
+    /// before the motion multiplied, and whether either was typed.
+    fn take_count(&mut self) -> (usize, bool) {
+        let (a, b) = (self.op_count.take(), self.count.take());
+        let has = a.is_some() || b.is_some();
+        (a.unwrap_or(1).saturating_mul(b.unwrap_or(1)), has)
+    }
+
+    /// Finish a command; a completed change becomes the one `.` repeats.
+    fn complete(&mut self, change: bool) {
+        self.cancel();
+        if change && self.recording && !self.replaying {
+            self.last_change = self.keys.clone();
+        }
+    }
+
+    fn command(
+        &mut self,
+        ed: &mut dyn TextEditor,
+        key: &KeyEvent,
+        input: Input,
+        literal: Option<char>,
+    ) -> EventResult {
+        match std::mem::take(&mut self.pending) {
+            Pending::None => {}
+            Pending::Register => {
+                match literal.filter(|c| c.is_ascii_alphabetic() || REGISTER_NAMES.contains(*c)) {
+                    Some(c) => self.register = Some(c),
+                    None => self.cancel(),
+                }
+                return EventResult::Consumed;
+            }
+            Pending::Replace => {
+                match literal {
+                    Some(c) => self.replace_chars(ed, c),
+                    None => self.cancel(),
+                }
+                return EventResult::Consumed;
+            }
+            Pending::Find(find) => {
+                match literal {
+                    Some(c) => {
+                        self.last_find = Some((find, c));
+                        let (n, _) = self.take_count();
+                        let motion = find_char(ed.buffer(), ed.cursor(), find, c, n, false);
+                        self.apply_motion(ed, motion, 'f');
+                    }
+                    None => self.cancel(),
+                }
+                return EventResult::Consumed;
+            }
+            Pending::G => {
+                match literal {
+                    Some('g') => self.run_motion(ed, 'g'),
+                    _ => self.cancel(),
+                }
+                return EventResult::Consumed;
+            }
+            Pending::Object { inner } => {
+                let range = literal.and_then(|c| text_object(ed.buffer(), ed.cursor(), c, inner));
+                match range {
+                    Some(range) if self.is_visual() => {
+                        self.cancel();
+                        self.mode = Mode::Visual;
+                        self.visual_anchor = range.start;
+                        ed.set_cursor(range.end.saturating_sub(1).max(range.start));
+                        self.update_visual(ed);
+                    }
+                    Some(range) => match self.operator.take() {
+                        Some(op) => {
+                            self.take_count();
+                            self.operate(ed, op, Span::Chars(range));
+                        }
+                        None => self.cancel(),
+                    },
+                    None => self.cancel(),
+                }
+                return EventResult::Consumed;
+            }
+        }
+
+        let c = match input {
+            Input::Escape => {
+                let idle = self.is_idle();
+                self.cancel();
+                if self.is_visual() {
+                    self.exit_visual(ed);
+                    return EventResult::Consumed;
+                }
+                return if idle {
+                    EventResult::Ignored
+                } else {
+                    EventResult::Consumed
+                };
+            }
+            Input::Ctrl(c) => return self.ctrl_command(ed, key, c),
+            Input::Char(c) => c,
+        };
+
+        if let Some(d) = c.to_digit(10)
+            && (d != 0 || self.count.is_some())
+        {
+            let count = self.count.unwrap_or(0).saturating_mul(10) + d as usize;
+            self.count = Some(count.min(MAX_COUNT));
+            return EventResult::Consumed;
+        }
+        if c == '"' && self.operator.is_none() {
+            self.pending = Pending::Register;
+            return EventResult::Consumed;
+        }
+        if let Some(op) = Operator::from_key(c) {
+            if self.is_visual() {
+                let span = self.visual_span(ed);
+                self.exit_visual(ed);
+                self.operate(ed, op, span);
+            } else if self.operator == Some(op) {
+                self.operate_lines(ed, op);
+            } else if self.operator.is_some() {
+                self.cancel();
+            } else {
+                self.operator = Some(op);
+                self.op_count = self.count.take();
+            }
+            return EventResult::Consumed;
+        }
+        if matches!(c, 'i' | 'a') && (self.operator.is_some() || self.is_visual()) {
+            self.pending = Pending::Object { inner: c == 'i' };
+            return EventResult::Consumed;
+        }
+        let find = match c {
+            'f' => Some(Find::Forward),
+            'F' => Some(Find::Backward),
+            't' => Some(Find::Till),
+            'T' => Some(Find::TillBackward),
+            _ => None,
+        };
+        if let Some(find) = find {
+            self.pending = Pending::Find(find);
+            return EventResult::Consumed;
+        }
+        if c == 'g' {
+            self.pending = Pending::G;
+            return EventResult::Consumed;
+        }
+        if MOTION_KEYS.contains(c) {
+            self.run_motion(ed, c);
+            return EventResult::Consumed;
+        }
+        if self.operator.is_some() {
+            self.cancel();
+            return EventResult::Consumed;
+        }
+        if self.is_visual() {
+            self.visual_command(ed, c);
+        } else {
+            self.normal_command(ed, c);
+        }
+        EventResult::Consumed
+    }
+
+    fn ctrl_command(&mut self, ed: &mut dyn TextEditor, key: &KeyEvent, c: char) -> EventResult {
+        let page = ed.page_rows().max(1);
+        let rows = match c {
+            'd' => Some(((page / 2).max(1), false)),
+            'u' => Some(((page / 2).max(1), true)),
+            'f' => Some((page, false)),
+            'b' => Some((page, true)),
+            _ => None,
+        };
+        if let Some((rows, up)) = rows {
+            self.cancel();
+            let motion = self.vertical(ed, rows, up);
+            let target = motion.map_or(ed.cursor(), |m| m.target);
+            self.move_to(ed, target);
+            return EventResult::Consumed;
+        }
+        match c {
+            'r' => {
+                let (n, _) = self.take_count();
+                for _ in 0..n {
+                    ed.redo();
+                }
+                let target = ed.cursor();
+                self.move_to(ed, target);
+                self.complete(false);
+                EventResult::Consumed
+            }
+            _ => {
+                self.cancel();
+                ed.insert_key(key)
+            }
+        }
+    }
+
+    fn normal_command(&mut self, ed: &mut dyn TextEditor, c: char) {
+        let cur = ed.cursor();
+        let buf = ed.buffer();
+        let (line, _) = buf.position_of(cur);
+        match c {
+            'x' | 'X' | 'D' | 'C' | 's' => {
+                let (op, motion) = match c {
+                    'x' => (Operator::Delete, 'l'),
+                    'X' => (Operator::Delete, 'h'),
+                    'D' => (Operator::Delete, '$'),
+                    'C' => (Operator::Change, '$'),
+                    _ => (Operator::Change, 'l'),
+                };
+                self.operator = Some(op);
+                self.run_motion(ed, motion);
+            }
+            'S' => self.operate_lines(ed, Operator::Change),
+            'Y' => self.operate_lines(ed, Operator::Yank),
+            'r' => self.pending = Pending::Replace,
+            'J' => {
+                let (n, _) = self.take_count();
+                self.join(ed, line, n.max(2) - 1);
+            }
+            '~' => {
+                let (n, _) = self.take_count();
+                let end = (cur + n).min(line_end(buf, line));
+                self.toggle_case(ed, cur..end);
+                self.move_to(ed, end);
+                self.complete(true);
+            }
+            'p' | 'P' => self.paste(ed, c == 'P'),
+            'i' => self.enter_insert(ed),
+            'a' => {
+                ed.set_cursor((cur + 1).min(line_end(buf, line)));
+                self.enter_insert(ed);
+            }
+            'I' => {
+                ed.set_cursor(first_non_blank(buf, line));
+                self.enter_insert(ed);
+            }
+            'A' => {
+                ed.set_cursor(line_end(buf, line));
+                self.enter_insert(ed);
+            }
+            'o' | 'O' => self.open_line(ed, line, c == 'O'),
+            'u' => {
+                let (n, _) = self.take_count();
+                for _ in 0..n {
+                    ed.undo();
+                }
+                let target = ed.cursor();
+                self.move_to(ed, target);
+                self.complete(false);
+            }
+            '.' => self.repeat(ed),
+            'v' => self.enter_visual(ed, Mode::Visual),
+            'V' => self.enter_visual(ed, Mode::VisualLine),
+            _ => self.cancel(),
+        }
+    }
+
+    fn visual_command(&mut self, ed: &mut dyn TextEditor, c: char) {
+        match c {
+            'o' => {
+                let cur = ed.cursor();
+                ed.set_cursor(self.visual_anchor);
+                self.visual_anchor = cur;
+                self.update_visual(ed);
+                self.cancel();
+            }
+            'v' | 'V' => {
+                let mode = if c == 'v' {
+                    Mode::Visual
+                } else {
+                    Mode::VisualLine
+                };
+                self.cancel();
+                if self.mode == mode {
+                    self.exit_visual(ed);
+                } else {
+                    self.mode = mode;
+                    self.update_visual(ed);
+                }
+            }
+            'x' | 's' | 'X' | 'S' | 'D' | 'C' | 'Y' => {
+                let op = match c {
+                    'x' | 'X' | 'D' => Operator::Delete,
+                    'Y' => Operator::Yank,
+                    _ => Operator::Change,
+                };
+                let span = match (c, self.visual_span(ed)) {
+                    ('x' | 's', span) => span,
+                    (_, Span::Chars(r)) => {
+                        let buf = ed.buffer();
+                        let lines = (
+                            buf.position_of(r.start).0,
+                            buf.position_of(r.end.saturating_sub(1).max(r.start)).0,
+                        );
+                        Span::Lines(lines.0, lines.1)
+                    }
+                    (_, span) => span,
+                };
+                self.exit_visual(ed);
+                self.operate(ed, op, span);
+            }
+            'p' | 'P' => {
+                let span = self.visual_span(ed);
+                self.exit_visual(ed);
+                self.replace_span(ed, span);
+            }
+            '~' | 'u' | 'U' => {
+                let range = self.span_range(ed.buffer(), &self.visual_span(ed));
+                self.exit_visual(ed);
+                let text = ed.buffer().slice(range.clone());
+                let changed: String = match c {
+                    'u' => text.to_lowercase(),
+                    'U' => text.to_uppercase(),
+                    _ => text.chars().map(swap_case).collect(),
+                };
+                ed.replace(range.clone(), &changed);
+                self.move_to(ed, range.start);
+                self.complete(true);
+            }
+            'J' => {
+                let range = self.span_range(ed.buffer(), &self.visual_span(ed));
+                let buf = ed.buffer();
+                let first = buf.position_of(range.start).0;
+                let last = buf
+                    .position_of(range.end.saturating_sub(1).max(range.start))
+                    .0;
+                self.exit_visual(ed);
+                self.join(ed, first, (last - first).max(1));
+            }
+            _ => self.cancel(),
+        }
+    }
+
+    // --- Motions ---
+
+    fn run_motion(&mut self, ed: &mut dyn TextEditor, c: char) {
+        let (n, has_count) = self.take_count();
+        let motion = if self.operator == Some(Operator::Change) && matches!(c, 'w' | 'W') {
+            change_word(ed.buffer(), ed.cursor(), n, c == 'W')
+        } else {
+            self.motion(ed, c, n, has_count)
+        };
+        self.apply_motion(ed, motion, c);
+    }
+
+    fn motion(
+        &mut self,
+        ed: &dyn TextEditor,
+        c: char,
+        n: usize,
+        has_count: bool,
+    ) -> Option<Motion> {
+        use MotionKind::{Exclusive, Inclusive, Linewise};
+        let buf = ed.buffer();
+        let cur = ed.cursor();
+        let (line, col) = buf.position_of(cur);
+        let last = buf.line_count().saturating_sub(1);
+        let motion = match c {
+            'h' => Motion::new(cur - n.min(col), Exclusive),
+            'l' | ' ' => Motion::new((cur + n).min(line_end(buf, line)), Exclusive),
+            'j' | 'k' => return self.vertical(ed, n, c == 'k'),
+            '+' | '-' | '_' => {
+                let target = match c {
+                    '+' => line.checked_add(n).filter(|l| *l <= last)?,
+                    '-' => line.checked_sub(n)?,
+                    _ => (line + n - 1).min(last),
+                };
+                Motion::new(first_non_blank(buf, target), Linewise)
+            }
+            '0' => Motion::new(line_start(buf, line), Exclusive),
+            '^' => Motion::new(first_non_blank(buf, line), Exclusive),
+            '$' => {
+                let target = (line + n - 1).min(last);
+                let end = line_end(buf, target);
+                Motion::new(
+                    end.saturating_sub(1).max(line_start(buf, target)),
+                    Inclusive,
+                )
+            }
+            'w' | 'W' => Motion::new(
+                repeat(n, cur, |i| next_word_start(buf, i, c == 'W')),
+                Exclusive,
+            ),
+            'b' | 'B' => Motion::new(
+                repeat(n, cur, |i| prev_word_start(buf, i, c == 'B')),
+                Exclusive,
+            ),
+            'e' | 'E' => Motion::new(repeat(n, cur, |i| word_end(buf, i, c == 'E')), Inclusive),
+            'G' | 'g' => {
+                let target = match (has_count, c) {
+                    (true, _) => (n - 1).min(last),
+                    (false, 'G') => last,
+                    (false, _) => 0,
+                };
+                Motion::new(first_non_blank(buf, target), Linewise)
+            }
+            '%' => {
+                let end = line_end(buf, line);
+                let target = (cur..end).find_map(|i| buf.matching_bracket(i))?;
+                Motion::new(target, Inclusive)
+            }
+            '}' | '{' => Motion::new(paragraph(buf, line, n, c == '{'), Exclusive),
+            ';' | ',' => {
+                let (find, ch) = self.last_find?;
+                let find = if c == ',' { find.reversed() } else { find };
+                return find_char(buf, cur, find, ch, n, true);
+            }
+            _ => return None,
+        };
+        Some(motion)
+    }
+
+    /// Move `rows` lines up or down, keeping the column of the first
+    /// vertical move.
+    fn vertical(&mut self, ed: &dyn TextEditor, rows: usize, up: bool) -> Option<Motion> {
+        let buf = ed.buffer();
+        let (line, col) = buf.position_of(ed.cursor());
+        let last = buf.line_count().saturating_sub(1);
+        let target = if up {
+            line.saturating_sub(rows)
+        } else {
+            (line + rows).min(last)
+        };
+        if target == line {
+            return None;
+        }
+        let col = *self.want_col.get_or_insert(col);
+        Some(Motion::new(
+            buf.char_index(target, col),
+            MotionKind::Linewise,
+        ))
+    }
+
+    /// Move the cursor, or apply the pending operator over the motion.
+    fn apply_motion(&mut self, ed: &mut dyn TextEditor, motion: Option<Motion>, key: char) {
+        let Some(motion) = motion else {
+            self.cancel();
+            return;
+        };
+        match self.operator.take() {
+            Some(op) => {
+                let span = motion_span(ed.buffer(), ed.cursor(), &motion, key);
+                self.operate(ed, op, span);
+            }
+            None => {
+                self.move_to(ed, motion.target);
+                self.complete(false);
+            }
+        }
+    }
+
+    /// Move the cursor, keeping it on a character in normal mode and
+    /// updating the selection in visual mode.
+    fn move_to(&mut self, ed: &mut dyn TextEditor, idx: usize) {
+        let idx = clamp_normal(ed.buffer(), idx);
+        ed.set_cursor(idx);
+        if self.is_visual() {
+            self.update_visual(ed);
+        }
+    }
+
+    // --- Operators ---
+
+    /// Apply an operator to `count` lines from the cursor (`dd`, `cc`,
+    /// `yy`, `>>`, `<<`).
+    fn operate_lines(&mut self, ed: &mut dyn TextEditor, op: Operator) {
+        let (n, _) = self.take_count();
+        let buf = ed.buffer();
+        let line = buf.position_of(ed.cursor()).0;
+        let last = (line + n - 1).min(buf.line_count().saturating_sub(1));
+        self.operate(ed, op, Span::Lines(line, last));
+    }
+
+    fn operate(&mut self, ed: &mut dyn TextEditor, op: Operator, span: Span) {
+        let register = self.register.take();
+        let buf = ed.buffer();
+        let (text, linewise) = match &span {
+            Span::Chars(range) => (buf.slice(range.clone()), false),
+            Span::Lines(first, last) => {
+                let mut text = buf.slice(line_start(buf, *first)..line_end(buf, *last));
+                text.push('\n');
+                (text, true)
+            }
+        };
+        match op {
+            Operator::Yank => {
+                self.store(ed, register, text, linewise, true);
+                let target = match span {
+                    Span::Chars(range) => range.start,
+                    Span::Lines(first, _) => {
+                        let (line, col) = buf.position_of(ed.cursor());
+                        buf.char_index(line.min(first), col)
+                    }
+                };
+                self.move_to(ed, target);
+                self.complete(false);
+            }
+            Operator::Delete => {
+                self.store(ed, register, text, linewise, false);
+                ed.begin_change();
+                let target = self.delete_span(ed, &span);
+                ed.end_change();
+                self.move_to(ed, target);
+                self.complete(true);
+            }
+            Operator::Change => {
+                self.store(ed, register, text, linewise, false);
+                self.start_change(ed);
+                match span {
+                    Span::Chars(range) => {
+                        ed.replace(range.clone(), "");
+                        ed.set_cursor(range.start);
+                    }
+                    Span::Lines(first, last) => {
+                        let buf = ed.buffer();
+                        let indent = leading_whitespace(&buf.line(first).unwrap_or_default());
+                        let start = line_start(buf, first);
+                        ed.replace(start..line_end(buf, last), &indent);
+                        ed.set_cursor(start + indent.chars().count());
+                    }
+                }
+                self.enter_insert(ed);
+            }
+            Operator::Indent | Operator::Outdent => {
+                let (first, last) = match span {
+                    Span::Lines(first, last) => (first, last),
+                    Span::Chars(range) => (
+                        buf.position_of(range.start).0,
+                        buf.position_of(range.end.saturating_sub(1).max(range.start))
+                            .0,
+                    ),
+                };
+                let unit = ed.indent_unit().to_string();
+                ed.begin_change();
+                for line in (first..=last).rev() {
+                    let buf = ed.buffer();
+                    let text = buf.line(line).unwrap_or_default();
+                    let start = line_start(buf, line);
+                    if op == Operator::Indent {
+                        if !text.is_empty() {
+                            ed.replace(start..start, &unit);
+                        }
+                    } else {
+                        let width = unit.chars().count().max(1);
+                        let remove = match text.chars().next() {
+                            Some('\t') => 1,
+                            _ => text.chars().take(width).take_while(|c| *c == ' ').count(),
+                        };
+                        ed.replace(start..start + remove, "");
+                    }
+                }
+                ed.end_change();
+                let target = first_non_blank(ed.buffer(), first);
+                self.move_to(ed, target);
+                self.complete(true);
+            }
+        }
+    }
+
+    /// Delete a span and return where the cursor goes.
+    fn delete_span(&mut self, ed: &mut dyn TextEditor, span: &Span) -> usize {
+        match *span {
+            Span::Chars(ref range) => {
+                ed.replace(range.clone(), "");
+                range.start
+            }
+            Span::Lines(first, last) => {
+                let buf = ed.buffer();
+                let total = buf.total_chars();
+                let range = if last + 1 < buf.line_count() {
+                    line_start(buf, first)..line_start(buf, last + 1)
+                } else if first > 0 {
+                    line_end(buf, first - 1)..total
+                } else {
+                    0..total
+                };
+                ed.replace(range, "");
+                let buf = ed.buffer();
+                first_non_blank(buf, first.min(buf.line_count().saturating_sub(1)))
+            }
+        }
+    }
+
+    fn span_range(&self, buf: &TextBuffer, span: &Span) -> Range<usize> {
+        match *span {
+            Span::Chars(ref range) => range.clone(),
+            Span::Lines(first, last) => line_start(buf, first)..line_end(buf, last),
+        }
+    }
+
+    // --- Commands ---
+
+    fn paste(&mut self, ed: &mut dyn TextEditor, before: bool) {
+        let (n, _) = self.take_count();
+        let name = self.register.take();
+        let Some(register) = self.register_contents(ed, name) else {
+            self.complete(false);
+            return;
+        };
+        let cur = ed.cursor();
+        let line = ed.buffer().position_of(cur).0;
+        ed.begin_change();
+        let buf = ed.buffer();
+        if register.linewise {
+            let mut text = register.text.clone();
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            let text = text.repeat(n);
+            let target = if before { line } else { line + 1 };
+            if before || line + 1 < buf.line_count() {
+                let at = line_start(buf, target);
+                ed.replace(at..at, &text);
+            } else {
+                let at = buf.total_chars();
+                let body = text.strip_suffix('\n').unwrap_or(&text);
+                ed.replace(at..at, &format!("\n{body}"));
+            }
+            ed.end_change();
+            let target = first_non_blank(ed.buffer(), target);
+            self.move_to(ed, target);
+        } else {
+            let text = register.text.repeat(n);
+            let at = if before || line_end(buf, line) == line_start(buf, line) {
+                cur
+            } else {
+                (cur + 1).min(line_end(buf, line))
+            };
+            ed.replace(at..at, &text);
+            ed.end_change();
+            self.move_to(ed, (at + text.chars().count()).saturating_sub(1));
+        }
+        self.complete(true);
+    }
+
+    /// Replace a visual selection with a register's text.
+    fn replace_span(&mut self, ed: &mut dyn TextEditor, span: Span) {
+        let name = self.register.take();
+        let Some(register) = self.register_contents(ed, name) else {
+            self.complete(false);
+            return;
+        };
+        let buf = ed.buffer();
+        let (old, old_linewise) = match &span {
+            Span::Chars(range) => (buf.slice(range.clone()), false),
+            Span::Lines(first, last) => {
+                let mut text = buf.slice(line_start(buf, *first)..line_end(buf, *last));
+                text.push('\n');
+                (text, true)
+            }
+        };
+        let range = self.span_range(buf, &span);
+        let text = match (old_linewise, register.linewise) {
+            (true, _) | (false, false) => register.text.trim_end_matches('\n').to_string(),
+            (false, true) => format!("\n{}", register.text),
+        };
+        ed.begin_change();
+        ed.replace(range.clone(), &text);
+        ed.end_change();
+        self.store(ed, None, old, old_linewise, false);
+        self.move_to(ed, range.start);
+        self.complete(true);
+    }
+
+    fn replace_chars(&mut self, ed: &mut dyn TextEditor, c: char) {
+        let (n, _) = self.take_count();
+        let buf = ed.buffer();
+        let cur = ed.cursor();
+        let end = line_end(buf, buf.position_of(cur).0);
+        if cur + n > end {
+            self.cancel();
+            return;
+        }
+        let text: String = std::iter::repeat_n(c, n).collect();
+        ed.replace(cur..cur + n, &text);
+        self.move_to(ed, cur + n - 1);
+        self.complete(true);
+    }
+
+    /// Join `joins` following lines onto `line`.
+    fn join(&mut self, ed: &mut dyn TextEditor, line: usize, joins: usize) {
+        ed.begin_change();
+        let mut at = None;
+        for _ in 0..joins {
+            let buf = ed.buffer();
+            if line + 1 >= buf.line_count() {
+                break;
+            }
+            let text = buf.line(line).unwrap_or_default();
+            let next = buf.line(line + 1).unwrap_or_default();
+            let indent = leading_whitespace(&next).chars().count();
+            let rest = next.trim_start();
+            let separator = if text.is_empty()
+                || text.ends_with(char::is_whitespace)
+                || rest.is_empty()
+                || rest.starts_with(')')
+            {
+                ""
+            } else {
+                " "
+            };
+            let end = line_end(buf, line);
+            ed.replace(end..line_start(buf, line + 1) + indent, separator);
+            at = Some(end);
+        }
+        ed.end_change();
+        match at {
+            Some(at) => {
+                self.move_to(ed, at);
+                self.complete(true);
+            }
+            None => self.cancel(),
+        }
+    }
+
+    fn toggle_case(&mut self, ed: &mut dyn TextEditor, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let text: String = ed
+            .buffer()
+            .slice(range.clone())
+            .chars()
+            .map(swap_case)
+            .collect();
+        ed.replace(range, &text);
+    }
+
+    fn open_line(&mut self, ed: &mut dyn TextEditor, line: usize, above: bool) {
+        self.take_count();
+        let buf = ed.buffer();
+        let indent = leading_whitespace(&buf.line(line).unwrap_or_default());
+        let indent_len = indent.chars().count();
+        self.start_change(ed);
+        if above {
+            let at = line_start(ed.buffer(), line);
+            ed.replace(at..at, &format!("{indent}\n"));
+            ed.set_cursor(at + indent_len);
+        } else {
+            let at = line_end(ed.buffer(), line);
+            ed.replace(at..at, &format!("\n{indent}"));
+            ed.set_cursor(at + 1 + indent_len);
+        }
+        self.enter_insert(ed);
+    }
+
+    /// Replay the last change, `count` times.
+    fn repeat(&mut self, ed: &mut dyn TextEditor) {
+        let (n, _) = self.take_count();
+        self.cancel();
+        if self.replaying || self.last_change.is_empty() {
+            return;
+        }
+        let keys = self.last_change.clone();
+        self.replaying = true;
+        for _ in 0..n {
+            for key in &keys {
+                self.handle_key(ed, key);
+            }
+        }
+        self.replaying = false;
+    }
+
+    // --- Modes ---
+
+    fn is_visual(&self) -> bool {
+        matches!(self.mode, Mode::Visual | Mode::VisualLine)
+    }
+
+    /// Open an undo step that stays open through the insert session.
+    fn start_change(&mut self, ed: &mut dyn TextEditor) {
+        if !self.in_change {
+            ed.begin_change();
+            self.in_change = true;
+        }
+    }
+
+    fn enter_insert(&mut self, ed: &mut dyn TextEditor) {
+        self.start_change(ed);
+        self.cancel();
+        self.mode = Mode::Insert;
+    }
+
+    fn insert_key(&mut self, ed: &mut dyn TextEditor, key: &KeyEvent) -> EventResult {
+        if !self.replaying {
+            self.keys.push(key.clone());
+        }
+        if input_of(key) != Some(Input::Escape) {
+            return ed.insert_key(key);
+        }
+        self.mode = Mode::Normal;
+        if self.in_change {
+            ed.end_change();
+            self.in_change = false;
+        }
+        if self.recording && !self.replaying {
+            self.last_change = self.keys.clone();
+        }
+        // Leaving insert mode steps back onto the last inserted character.
+        let cur = ed.cursor();
+        let buf = ed.buffer();
+        let start = line_start(buf, buf.position_of(cur).0);
+        self.move_to(ed, cur.saturating_sub(1).max(start));
+        EventResult::Consumed
+    }
+
+    fn enter_visual(&mut self, ed: &mut dyn TextEditor, mode: Mode) {
+        self.cancel();
+        self.recording = false;
+        self.mode = mode;
+        self.visual_anchor = ed.cursor();
+        self.update_visual(ed);
+    }
+
+    fn exit_visual(&mut self, ed: &mut dyn TextEditor) {
+        self.mode = Mode::Normal;
+        ed.set_selection(None);
+        let cur = ed.cursor();
+        self.move_to(ed, cur);
+    }
+
+    fn visual_span(&self, ed: &dyn TextEditor) -> Span {
+        let buf = ed.buffer();
+        let cur = ed.cursor();
+        let (start, end) = (cur.min(self.visual_anchor), cur.max(self.visual_anchor));
+        match self.mode {
+            Mode::VisualLine => Span::Lines(buf.position_of(start).0, buf.position_of(end).0),
+            _ => Span::Chars(start..inclusive_end(buf, end)),
+        }
+    }
+
+    fn update_visual(&self, ed: &mut dyn TextEditor) {
+        let range = self.span_range(ed.buffer(), &self.visual_span(ed));
+        ed.set_selection(Some(range));
+    }
+
+    // --- Registers ---
+
+    /// Store yanked or deleted text: in the named register if one was
+    /// given, else in `0` for yanks and `1`–`9` (or `-` for small
+    /// deletes) otherwise. The unnamed register always gets a copy.
+    fn store(
+        &mut self,
+        ed: &dyn TextEditor,
+        name: Option<char>,
+        text: String,
+        linewise: bool,
+        yank: bool,
+    ) {
+        let register = Register { text, linewise };
+        let stored = match name {
+            Some('_') => return,
+            Some(c @ 'A'..='Z') => {
+                let entry = self.registers.entry(c.to_ascii_lowercase()).or_default();
+                if linewise && !entry.text.is_empty() && !entry.text.ends_with('\n') {
+                    entry.text.push('\n');
+                }
+                entry.text.push_str(&register.text);
+                entry.linewise |= linewise;
+                entry.clone()
+            }
+            Some('+' | '*') => {
+                if let Some(clipboard) = ed.clipboard() {
+                    clipboard.set_text(register.text.clone());
+                }
+                register
+            }
+            Some(c) if c != '"' => {
+                self.registers.insert(c, register.clone());
+                register
+            }
+            _ if yank => {
+                self.registers.insert('0', register.clone());
+                register
+            }
+            _ if linewise || register.text.contains('\n') => {
+                for i in (1..9u8).rev() {
+                    if let Some(r) = self.registers.remove(&char::from(b'0' + i)) {
+                        self.registers.insert(char::from(b'1' + i), r);
+                    }
+                }
+                self.registers.insert('1', register.clone());
+                register
+            }
+            _ => {
+                self.registers.insert('-', register.clone());
+                register
+            }
+        };
+        self.registers.insert('"', stored);
+    }
+
+    fn register_contents(&self, ed: &dyn TextEditor, name: Option<char>) -> Option<Register> {
+        match name.unwrap_or('"') {
+            '+' | '*' => ed.clipboard()?.text().map(|text| Register {
+                linewise: text.ends_with('\n'),
+                text,
+            }),
+            c => self.registers.get(&c.to_ascii_lowercase()).cloned(),
+        }
+    }
+}
+
+/// Translate a key event; `None` for keys Vim leaves to the widget.
+fn input_of(key: &KeyEvent) -> Option<Input> {
+    let ctrl = key.modifiers.contains(Modifiers::CTRL);
+    let alt = key.modifiers.contains(Modifiers::ALT);
+    let shift = key.modifiers.contains(Modifiers::SHIFT);
+    let input = match key.code {
+        KeyCode::Escape => Input::Escape,
+        KeyCode::Char('[') if ctrl => Input::Escape,
+        KeyCode::Char(c) if ctrl && !alt => Input::Ctrl(c.to_ascii_lowercase()),
+        KeyCode::Char(_) if ctrl || alt => return None,
+        KeyCode::Char(c) if shift && c.is_ascii_lowercase() => Input::Char(c.to_ascii_uppercase()),
+        KeyCode::Char(c) => Input::Char(c),
+        _ if ctrl || alt => return None,
+        KeyCode::Left | KeyCode::Backspace => Input::Char('h'),
+        KeyCode::Right => Input::Char('l'),
+        KeyCode::Up => Input::Char('k'),
+        KeyCode::Down => Input::Char('j'),
+        KeyCode::Home => Input::Char('0'),
+        KeyCode::End => Input::Char('$'),
+        KeyCode::Delete => Input::Char('x'),
+        KeyCode::Enter => Input::Char('+'),
+        KeyCode::PageUp => Input::Ctrl('b'),
+        KeyCode::PageDown => Input::Ctrl('f'),
+        _ => return None,
+    };
+    Some(input)
+}
+
+// --- Buffer helpers ---
+
+fn line_start(buf: &TextBuffer, line: usize) -> usize {
+    buf.char_index(line, 0)
+}
+
+/// The index just past the last character of a line, before its newline.
+fn line_end(buf: &TextBuffer, line: usize) -> usize {
+    buf.char_index(line, usize::MAX)
+}
+
+fn first_non_blank(buf: &TextBuffer, line: usize) -> usize {
+    let text = buf.line(line).unwrap_or_default();
+    line_start(buf, line) + leading_whitespace(&text).chars().count()
+}
+
+fn leading_whitespace(text: &str) -> String {
+    text.chars().take_while(|c| c.is_whitespace()).collect()
+}
+
+/// Keep the cursor on a character: never past the last one of its line.
+fn clamp_normal(buf: &TextBuffer, idx: usize) -> usize {
+    let (line, _) = buf.position_of(idx);
+    let (start, end) = (line_start(buf, line), line_end(buf, line));
+    idx.min(end.saturating_sub(1)).max(start)
+}
+
+/// The end of an inclusive range ending at `idx`; the newline of an empty
+/// line is not included.
+fn inclusive_end(buf: &TextBuffer, idx: usize) -> usize {
+    match buf.char_at(idx) {
+        Some('\n') | None => idx,
+        Some(_) => idx + 1,
+    }
+}
+
+fn repeat(n: usize, start: usize, step: impl Fn(usize) -> usize) -> usize {
+    (0..n).fold(start, |idx, _| step(idx))
+}
+
+fn swap_case(c: char) -> char {
+    if c.is_lowercase() {
+        c.to_uppercase().next().unwrap_or(c)
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+/// Character classes for word motions: 0 for whitespace, 1 for word
+/// characters, 2 for punctuation. `WORD`s (`big`) lump words and
+/// punctuation together.
+fn class(c: char, big: bool) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if big || c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+fn class_at(buf: &TextBuffer, idx: usize, big: bool) -> u8 {
+    buf.char_at(idx).map_or(0, |c| class(c, big))
+}
+
+/// `w`: the start of the next word, stopping at empty lines.
+fn next_word_start(buf: &TextBuffer, idx: usize, big: bool) -> usize {
+    let len = buf.total_chars();
+    let mut i = idx;
+    let start_class = class_at(buf, i, big);
+    if start_class != 0 {
+        while i < len && class_at(buf, i, big) == start_class {
+            i += 1;
+        }
+    }
+    while i < len && class_at(buf, i, big) == 0 {
+        if buf.char_at(i) == Some('\n') && buf.char_at(i + 1) == Some('\n') {
+            return i + 1;
+        }
+        i += 1;
+    }
+    i
+}
+
+/// `b`: the start of the previous word, stopping at empty lines.
+fn prev_word_start(buf: &TextBuffer, idx: usize, big: bool) -> usize {
+    if idx == 0 {
+        return 0;
+    }
+    let mut i = idx - 1;
+    while i > 0 && class_at(buf, i, big) == 0 {
+        if buf.char_at(i) == Some('\n') && buf.char_at(i - 1) == Some('\n') {
+            return i;
+        }
+        i -= 1;
+    }
+    let c = class_at(buf, i, big);
+    while i > 0 && class_at(buf, i - 1, big) == c {
+        i -= 1;
+    }
+    i
+}
+
+/// `e`: the last character of the next word end.
+fn word_end(buf: &TextBuffer, idx: usize, big: bool) -> usize {
+    let len = buf.total_chars();
+    let mut i = idx + 1;
+    while i < len && class_at(buf, i, big) == 0 {
+        i += 1;
+    }
+    if i >= len {
+        return len.saturating_sub(1).max(idx);
+    }
+    let c = class_at(buf, i, big);
+    while i + 1 < len && class_at(buf, i + 1, big) == c {
+        i += 1;
+    }
+    i
+}
+
+/// `cw` changes to the end of the word rather than to the next one.
+fn change_word(buf: &TextBuffer, idx: usize, n: usize, big: bool) -> Option<Motion> {
+    if class_at(buf, idx, big) == 0 {
+        let target = repeat(n, idx, |i| next_word_start(buf, i, big));
+        return Some(Motion::new(target, MotionKind::Exclusive));
+    }
+    let mut target = idx;
+    if class_at(buf, idx + 1, big) == class_at(buf, idx, big) {
+        target = word_end(buf, target, big);
+    }
+    target = repeat(n - 1, target, |i| word_end(buf, i, big));
+    Some(Motion::new(target, MotionKind::Inclusive))
+}
+
+/// `}` and `{`: the next or previous empty line after a paragraph.
+fn paragraph(buf: &TextBuffer, line: usize, n: usize, backward: bool) -> usize {
+    let last = buf.line_count().saturating_sub(1);
+    let blank = |l: usize| buf.line_len(l).unwrap_or(0) == 0;
+    let mut l = line;
+    for _ in 0..n {
+        if backward {
+            while l > 0 && blank(l) {
+                l -= 1;
+            }
+            while l > 0 && !blank(l) {
+                l -= 1;
+            }
+        } else {
+            while l < last && blank(l) {
+                l += 1;
+            }
+            while l < last && !blank(l) {
+                l += 1;
+            }
+        }
+    }
+    if !backward && l == last && !blank(l) {
+        line_end(buf, l)
+    } else {
+        line_start(buf, l)
+    }
+}
+
+/// `f`, `F`, `t` and `T` within the cursor's line. A repeated `t` skips
+/// the character it stopped before.
+fn find_char(
+    buf: &TextBuffer,
+    idx: usize,
+    find: Find,
+    ch: char,
+    n: usize,
+    repeated: bool,
+) -> Option<Motion> {
+    let (line, col) = buf.position_of(idx);
+    let start = line_start(buf, line);
+    let chars: Vec<char> = buf.line(line).unwrap_or_default().chars().collect();
+    let skip = usize::from(repeated && matches!(find, Find::Till | Find::TillBackward));
+    let hits = |j: &usize| chars[*j] == ch;
+    let found = match find {
+        Find::Forward | Find::Till => (col + 1 + skip..chars.len()).filter(hits).nth(n - 1),
+        Find::Backward | Find::TillBackward => {
+            (0..col.saturating_sub(skip)).rev().filter(hits).nth(n - 1)
+        }
+    }?;
+    let motion = match find {
+        Find::Forward => Motion::new(start + found, MotionKind::Inclusive),
+        Find::Till => Motion::new(start + found - 1, MotionKind::Inclusive),
+        Find::Backward => Motion::new(start + found, MotionKind::Exclusive),
+        Find::TillBackward => Motion::new(start + found + 1, MotionKind::Exclusive),
+    };
+    Some(motion)
+}
+
+/// The text an operator covers for a motion from `cur`.
+fn motion_span(buf: &TextBuffer, cur: usize, motion: &Motion, key: char) -> Span {
+    let (start, mut end) = (cur.min(motion.target), cur.max(motion.target));
+    match motion.kind {
+        MotionKind::Linewise => Span::Lines(buf.position_of(start).0, buf.position_of(end).0),
+        MotionKind::Inclusive => Span::Chars(start..inclusive_end(buf, end)),
+        MotionKind::Exclusive => {
+            // `dw` on the last word of a line stops at the line end.
+            let (start_line, end_line) = (buf.position_of(start).0, buf.position_of(end).0);
+            if matches!(key, 'w' | 'W')
+                && end_line > start_line
+                && end <= first_non_blank(buf, end_line)
+            {
+                end = line_end(buf, end_line - 1);
+            }
+            Span::Chars(start..end)
+        }
+    }
+}
+
+// --- Text objects ---
+
+fn text_object(buf: &TextBuffer, idx: usize, c: char, inner: bool) -> Option<Range<usize>> {
+    match c {
+        'w' | 'W' => word_object(buf, idx, inner, c == 'W'),
+        '(' | ')' | 'b' => bracket_object(buf, idx, '(', ')', inner),
+        '[' | ']' => bracket_object(buf, idx, '[', ']', inner),
+        '{' | '}' | 'B' => bracket_object(buf, idx, '{', '}', inner),
+        '<' | '>' => bracket_object(buf, idx, '<', '>', inner),
+        '"' | '\'' | '`' => quote_object(buf, idx, c, inner),
+        _ => None,
+    }
+}
+
+/// `iw`/`aw`: the word under the cursor, and for `aw` its trailing (or
+/// else leading) whitespace.
+fn word_object(buf: &TextBuffer, idx: usize, inner: bool, big: bool) -> Option<Range<usize>> {
+    let line = buf.position_of(idx).0;
+    let (ls, le) = (line_start(buf, line), line_end(buf, line));
+    if ls == le {
+        return None;
+    }
+    let idx = idx.min(le - 1);
+    let cls = |i: usize| class_at(buf, i, big);
+    let class = cls(idx);
+    let mut start = idx;
+    while start > ls && cls(start - 1) == class {
+        start -= 1;
+    }
+    let mut end = idx + 1;
+    while end < le && cls(end) == class {
+        end += 1;
+    }
+    if inner {
+        return Some(start..end);
+    }
+    if class == 0 {
+        if end < le {
+            let next = cls(end);
+            while end < le && cls(end) == next {
+                end += 1;
+            }
+        }
+        return Some(start..end);
+    }
+    let mut trailing = end;
+    while trailing < le && cls(trailing) == 0 {
+        trailing += 1;
+    }
+    if trailing > end {
+        return Some(start..trailing);
+    }
+    while start > ls && cls(start - 1) == 0 {
+        start -= 1;
+    }
+    Some(start..end)
+}
+
+/// `i(`/`a(` and friends: the innermost pair enclosing the cursor. Inner
+/// objects spanning lines leave the delimiters' lines alone.
+fn bracket_object(
+    buf: &TextBuffer,
+    idx: usize,
+    open: char,
+    close: char,
+    inner: bool,
+) -> Option<Range<usize>> {
+    let start = if buf.char_at(idx) == Some(open) {
+        idx
+    } else {
+        let mut depth = 0usize;
+        let mut i = idx;
+        loop {
+            if i == 0 || idx - i >= OBJECT_SCAN_LIMIT {
+                return None;
+            }
+            i -= 1;
+            match buf.char_at(i) {
+                Some(c) if c == close => depth += 1,
+                Some(c) if c == open => {
+                    if depth == 0 {
+                        break i;
+                    }
+                    depth -= 1;
+                }
+                _ => {}
+            }
+        }
+    };
+    let len = buf.total_chars();
+    let mut depth = 0usize;
+    let mut end = start + 1;
+    loop {
+        if end >= len || end - start >= OBJECT_SCAN_LIMIT {
+            return None;
+        }
+        match buf.char_at(end) {
+            Some(c) if c == open => depth += 1,
+            Some(c) if c == close => {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+        end += 1;
+    }
+    if !inner {
+        return Some(start..end + 1);
+    }
+    let mut inner_start = start + 1;
+    let mut inner_end = end;
+    if buf.char_at(inner_start) == Some('\n') {
+        inner_start += 1;
+        let (close_line, close_col) = buf.position_of(end);
+        let before = buf.line(close_line).unwrap_or_default();
+        if before.chars().take(close_col).all(char::is_whitespace) {
+            inner_end = line_start(buf, close_line);
+        }
+    }
+    Some(inner_start..inner_end.max(inner_start))
+}
+
+/// `i"`/`a"` and friends: the quoted string on the cursor's line that
+/// contains the cursor, or else the next one.
+fn quote_object(buf: &TextBuffer, idx: usize, quote: char, inner: bool) -> Option<Range<usize>> {
+    let (line, col) = buf.position_of(idx);
+    let start = line_start(buf, line);
+    let chars: Vec<char> = buf.line(line).unwrap_or_default().chars().collect();
+    let quotes: Vec<usize> = (0..chars.len())
+        .filter(|&i| chars[i] == quote && (i == 0 || chars[i - 1] != '\\'))
+        .collect();
+    let pairs: Vec<(usize, usize)> = quotes
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+    let &(open, close) = pairs
+        .iter()
+        .find(|(a, b)| *a <= col && col <= *b)
+        .or_else(|| pairs.iter().find(|(a, _)| *a > col))?;
+    if inner {
+        return Some(start + open + 1..start + close);
+    }
+    let mut end = close + 1;
+    while end < chars.len() && chars[end].is_whitespace() {
+        end += 1;
+    }
+    let mut begin = open;
+    if end == close + 1 {
+        while begin > 0 && chars[begin - 1].is_whitespace() {
+            begin -= 1;
+        }
+    }
+    Some(start + begin..start + end)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clipboard::Clipboard;
+    use crate::event::{Event, KeyCode, KeyEvent, Modifiers};
+    use crate::keymap::{EditMode, KeymapKind, TextEditor};
+    use crate::widget::{InteractiveWidget, TextArea};
+
+    fn vim(text: &str) -> TextArea {
+        TextArea::from_text(text).with_keymap(KeymapKind::Vim)
+    }
+
+    /// Type keys; `\x1b` is Escape and `\n` is Enter.
+    fn keys(ta: &mut TextArea, keys: &str) {
+        for ch in keys.chars() {
+            let code = match ch {
+                '\x1b' => KeyCode::Escape,
+                '\n' => KeyCode::Enter,
+                c => KeyCode::Char(c),
+            };
+            ta.handle_event(&Event::Key(KeyEvent::new(code, Modifiers::NONE)));
+        }
+    }
+
+    fn ctrl(ta: &mut TextArea, ch: char) {
+        ta.handle_event(&Event::Key(KeyEvent::new(
+            KeyCode::Char(ch),
+            Modifiers::CTRL,
+        )));
+    }
+
+    #[test]
+    fn counted_operator_motion() {
+        let mut ta = vim("one two three four");
+        keys(&mut ta, "2dw");
+        assert_eq!(ta.text(), "three four");
+        assert_eq!(TextEditor::cursor(&ta), 0);
+    }
+
+    #[test]
+    fn change_word_repeats_with_dot_and_undoes() {
+        let mut ta = vim("foo bar baz");
+        keys(&mut ta, "cwqux\x1b");
+        assert_eq!(ta.text(), "qux bar baz");
+        assert_eq!(TextEditor::cursor(&ta), 2);
+        keys(&mut ta, "w.");
+        assert_eq!(ta.text(), "qux qux baz");
+        keys(&mut ta, "u");
+        assert_eq!(ta.text(), "qux bar baz");
+        ctrl(&mut ta, 'r');
+        assert_eq!(ta.text(), "qux qux baz");
+    }
+
+    #[test]
+    fn linewise_delete_and_put() {
+        let mut ta = vim("a\nb\nc");
+        keys(&mut ta, "ddp");
+        assert_eq!(ta.text(), "b\na\nc");
+        keys(&mut ta, "ggP");
+        assert_eq!(ta.text(), "a\nb\na\nc");
+    }
+
+    #[test]
+    fn named_registers() {
+        let mut ta = vim("x\ny");
+        keys(&mut ta, "\"ayyjdd\"ap");
+        assert_eq!(ta.text(), "x\nx");
+        // The delete went to the unnamed register, not `a`.
+        keys(&mut ta, "p");
+        assert_eq!(ta.text(), "x\nx\ny");
+    }
+
+    #[test]
+    fn text_objects() {
+        let mut ta = vim("f(a, b)");
+        keys(&mut ta, "fadi(");
+        assert_eq!(ta.text(), "f()");
+
+        let mut ta = vim("say \"hi there\" ok");
+        keys(&mut ta, "fhci\"yo\x1b");
+        assert_eq!(ta.text(), "say \"yo\" ok");
+
+        let mut ta = vim("one two three");
+        keys(&mut ta, "wldaw");
+        assert_eq!(ta.text(), "one three");
+    }
+
+    #[test]
+    fn visual_modes() {
+        let mut ta = vim("ab\ncd\nef");
+        keys(&mut ta, "Vj");
+        assert_eq!(ta.edit_mode(), EditMode::VimVisualLine);
+        keys(&mut ta, "d");
+        assert_eq!(ta.text(), "ef");
+        assert_eq!(ta.edit_mode(), EditMode::VimNormal);
+
+        let mut ta = vim("abc");
+        keys(&mut ta, "vl~");
+        assert_eq!(ta.text(), "ABc");
+        assert_eq!(ta.edit_mode(), EditMode::VimNormal);
+    }
+
+    #[test]
+    fn insert_session_is_one_undo_step() {
+        let mut ta = vim("x");
+        keys(&mut ta, "iabc\x1b");
+        assert_eq!(ta.text(), "abcx");
+        assert_eq!(TextEditor::cursor(&ta), 2);
+        keys(&mut ta, "u");
+        assert_eq!(ta.text(), "x");
+    }
+
+    #[test]
+    fn find_and_repeat_find() {
+        let mut ta = vim("a,b,c,d");
+        keys(&mut ta, "f,");
+        assert_eq!(TextEditor::cursor(&ta), 1);
+        keys(&mut ta, ";");
+        assert_eq!(TextEditor::cursor(&ta), 3);
+        keys(&mut ta, ",");
+        assert_eq!(TextEditor::cursor(&ta), 1);
+        keys(&mut ta, "0d2t,");
+        assert_eq!(ta.text(), ",c,d");
+    }
+
+    #[test]
+    fn mode_and_pending_keys() {
+        let mut ta = vim("abc");
+        assert_eq!(ta.keymap().status(), "NORMAL");
+        assert_eq!(ta.pseudo_classes(), vec!["vim-normal"]);
+        keys(&mut ta, "2d");
+        assert_eq!(ta.keymap().pending(), "2d");
+        assert_eq!(ta.keymap().status(), "NORMAL 2d");
+        keys(&mut ta, "\x1bi");
+        assert_eq!(ta.keymap().pending(), "");
+        assert_eq!(ta.edit_mode(), EditMode::VimInsert);
+        assert_eq!(ta.pseudo_classes(), vec!["vim-insert"]);
+        assert_eq!(ta.text(), "abc");
+    }
+
+    #[test]
+    fn open_line_keeps_indent_and_join() {
+        let mut ta = vim("    foo");
+        keys(&mut ta, "obar\x1b");
+        assert_eq!(ta.text(), "    foo\n    bar");
+        keys(&mut ta, "kJ");
+        assert_eq!(ta.text(), "    foo bar");
+    }
+
+    #[test]
+    fn plus_register_uses_the_clipboard() {
+        let clipboard = Clipboard::new();
+        let mut ta = vim("abc").with_clipboard(clipboard.clone());
+        keys(&mut ta, "\"+yy");
+        assert_eq!(clipboard.text().as_deref(), Some("abc\n"));
+        // Clipboard text without a trailing newline is characterwise.
+        clipboard.set_text("xyz");
+        keys(&mut ta, "\"+P");
+        assert_eq!(ta.text(), "xyzabc");
+    }
+}
@@ -147,6 +147,18 @@ impl LayoutEngine {
         Ok(())
     }
 
+    /// Get the current style of a node.
+    pub fn style(&self, widget_id: WidgetId) -> Result<&Style, LayoutError> {
+        let node = self
+            .widget_to_node
+            .get(&widget_id)
+            .copied()
+            .ok_or(LayoutError::WidgetNotFound(widget_id))?;
+        self.taffy
+            .style(node)
+            .map_err(|e| LayoutError::TaffyError(format!("{e}")))
+    }
+
     /// Replace the children list for an existing node.
     pub fn set_children(
         &mut self,
@@ -226,6 +238,23 @@ impl LayoutEngine {
         })
     }
 
+    /// Get the raw Taffy layout for a widget.
+    ///
+    /// Exposes the resolved border, padding and scrollbar gutter, which the
+    /// rounded [`LayoutRect`] does not carry. The location is relative to
+    /// the parent node.
+    pub fn box_layout(&self, widget_id: WidgetId) -> Result<taffy::Layout, LayoutError> {
+        let node = self
+            .widget_to_node
+            .get(&widget_id)
+            .copied()
+            .ok_or(LayoutError::WidgetNotFound(widget_id))?;
+        self.taffy
+            .layout(node)
+            .copied()
+            .map_err(|e| LayoutError::TaffyError(format!("{e}")))
+    }
+
     /// Get the computed layout for a widget as a [`Rect`].
     pub fn layout_rect(&self, widget_id: WidgetId) -> Result<Rect, LayoutError> {
         self.layout(widget_id).map(|lr| lr.to_rect())
@@ -124,6 +124,50 @@ impl ScrollManager {
         );
     }
 
+    /// Update the extents of a region, keeping its scroll offsets.
+    ///
+    /// Offsets are clamped to the new extents. Unknown widgets are
+    /// registered with zero offsets.
+    pub fn update(
+        &mut self,
+        widget_id: WidgetId,
+        content_width: u16,
+        content_height: u16,
+        viewport_width: u16,
+        viewport_height: u16,
+    ) {
+        let state = self.regions.entry(widget_id).or_default();
+        state.content_width = content_width;
+        state.content_height = content_height;
+        state.viewport_width = viewport_width;
+        state.viewport_height = viewport_height;
+        state.offset_x = state.offset_x.min(state.max_offset_x());
+        state.offset_y = state.offset_y.min(state.max_offset_y());
+    }
+
+    /// Scroll the minimum distance needed to make `target` visible.
+    ///
+    /// `target` is in content coordinates. When it is larger than the
+    /// viewport its top-left corner is aligned with the viewport.
+    pub fn scroll_to_reveal(&mut self, widget_id: WidgetId, target: Rect) {
+        if let Some(state) = self.regions.get_mut(&widget_id) {
+            state.offset_x = reveal(
+                state.offset_x,
+                state.viewport_width,
+                target.position.x,
+                target.size.width,
+            )
+            .min(state.max_offset_x());
+            state.offset_y = reveal(
+                state.offset_y,
+                state.viewport_height,
+                target.position.y,
+                target.size.height,
+            )
+            .min(state.max_offset_y());
+        }
+    }
+
     /// Scroll by a relative offset, clamping to valid range.
     pub fn scroll_by(&mut self, widget_id: WidgetId, dx: i16, dy: i16) {
         if let Some(state) = self.regions.get_mut(&widget_id) {
@@ -170,6 +214,11 @@ impl ScrollManager {
     pub fn remove(&mut self, widget_id: WidgetId) {
         self.regions.remove(&widget_id);
     }
+
+    /// Keep only the regions for which `keep` returns true.
+    pub fn retain(&mut self, mut keep: impl FnMut(WidgetId) -> bool) {
+        self.regions.retain(|&id, _| keep(id));
+    }
 }
 
 impl Default for ScrollManager {
@@ -211,6 +260,19 @@ fn keyword_to_overflow(value: &CssValue) -> OverflowBehavior {
     }
 }
 
+/// The offset along one axis that brings `[start, start + len)` into a
+/// viewport of `viewport` cells currently scrolled to `offset`.
+fn reveal(offset: u16, viewport: u16, start: u16, len: u16) -> u16 {
+    let end = start.saturating_add(len);
+    if start < offset || len >= viewport {
+        start
+    } else if end > offset.saturating_add(viewport) {
+        end - viewport
+    } else {
+        offset
+    }
+}
+
 /// Clamp a signed offset to `[0, max]`.
 fn clamp_offset(value: i32, max: u16) -> u16 {
     if value < 0 {
@@ -365,6 +427,45 @@ mod tests {
         assert!(mgr.get(wid(1)).is_none());
     }
 
+    #[test]
+    fn manager_update_keeps_and_clamps_offsets() {
+        let mut mgr = ScrollManager::new();
+        mgr.register(wid(1), 10, 50, 10, 10);
+        mgr.scroll_to(wid(1), 0, 30);
+        mgr.update(wid(1), 10, 60, 10, 10);
+        assert_eq!(mgr.get(wid(1)).map(|s| s.offset_y), Some(30));
+        mgr.update(wid(1), 10, 20, 10, 10);
+        assert_eq!(mgr.get(wid(1)).map(|s| s.offset_y), Some(10));
+
+        mgr.update(wid(2), 5, 5, 10, 10);
+        assert_eq!(mgr.get(wid(2)), Some(&ScrollState::new(5, 5, 10, 10)));
+    }
+
+    #[test]
+    fn manager_scroll_to_reveal() {
+        let mut mgr = ScrollManager::new();
+        mgr.register(wid(1), 10, 100, 10, 10);
+        // Below the viewport: scroll down just enough.
+        mgr.scroll_to_reveal(wid(1), Rect::new(0, 20, 10, 2));
+        assert_eq!(mgr.get(wid(1)).map(|s| s.offset_y), Some(12));
+        // Already visible: no change.
+        mgr.scroll_to_reveal(wid(1), Rect::new(0, 15, 10, 1));
+        assert_eq!(mgr.get(wid(1)).map(|s| s.offset_y), Some(12));
+        // Above the viewport: align to the top.
+        mgr.scroll_to_reveal(wid(1), Rect::new(0, 3, 10, 1));
+        assert_eq!(mgr.get(wid(1)).map(|s| s.offset_y), Some(3));
+    }
+
+    #[test]
+    fn manager_retain() {
+        let mut mgr = ScrollManager::new();
+        mgr.register(wid(1), 10, 10, 5, 5);
+        mgr.register(wid(2), 10, 10, 5, 5);
+        mgr.retain(|id| id == wid(2));
+        assert!(mgr.get(wid(1)).is_none());
+        assert!(mgr.get(wid(2)).is_some());
+    }
+
     #[test]
     fn extract_overflow_default() {
         let style = ComputedStyle::new();
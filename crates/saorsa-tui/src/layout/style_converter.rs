@@ -412,6 +412,13 @@ fn apply_overflow(computed: &ComputedStyle, style: &mut Style) {
     if let Some(v) = computed.get(&PropertyName::OverflowY) {
         style.overflow.y = to_overflow(v);
     }
+    // Scroll containers reserve a gutter for their scrollbars.
+    if style.overflow.x == Overflow::Scroll || style.overflow.y == Overflow::Scroll {
+        style.scrollbar_width = computed
+            .get(&PropertyName::ScrollbarSize)
+            .map_or(1.0, to_f32)
+            .max(0.0);
+    }
 }
 
 #[cfg(test)]
@@ -583,6 +590,19 @@ mod tests {
         assert_eq!(style.overflow.y, Overflow::Hidden);
     }
 
+    #[test]
+    fn scroll_overflow_reserves_scrollbar_gutter() {
+        let mut computed = ComputedStyle::new();
+        computed.set(PropertyName::OverflowY, CssValue::Keyword("auto".into()));
+        assert!((computed_to_taffy(&computed).scrollbar_width - 1.0).abs() < f32::EPSILON);
+
+        computed.set(PropertyName::ScrollbarSize, CssValue::Integer(0));
+        assert!(computed_to_taffy(&computed).scrollbar_width.abs() < f32::EPSILON);
+
+        let hidden = ComputedStyle::new();
+        assert!(computed_to_taffy(&hidden).scrollbar_width.abs() < f32::EPSILON);
+    }
+
     #[test]
     fn convert_overflow_xy_separate() {
         let mut computed = ComputedStyle::new();
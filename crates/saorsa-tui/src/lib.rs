@@ -51,6 +51,10 @@
 //! - **Compositor**: Layer-based rendering with z-ordering, clipping, and overlays
 //! - **Widget Library**: Rich set of data, text, and UI widgets (tables, trees, markdown, etc.)
 //! - **Renderer**: Double-buffered differential rendering with SGR optimization
+//! - **Graphics**: Inline images via Kitty, iTerm2 or Sixel with a half-block fallback
+//! - **Clipboard**: OSC 52 copy and mouse drag selection over the rendered screen
+//! - **Pilot**: Headless end-to-end tests that drive an `App` and inspect the screen
+//! - **Export**: SVG screenshots, HTML snippets and asciicast recordings of rendered output
 //!
 //! ## Key Types
 //!
@@ -61,23 +65,30 @@
 //! - `Signal<T>`: Reactive state container with automatic dependency tracking
 //! - `Compositor`: Manages layers and composition into final screen buffer
 
+pub mod ansi;
 pub mod app;
 pub mod buffer;
 pub mod cell;
+pub mod clipboard;
 pub mod color;
 pub mod compositor;
 pub mod cursor;
 pub mod error;
 pub mod event;
+pub mod export;
 pub mod focus;
 pub mod geometry;
+pub mod graphics;
 pub mod highlight;
+pub mod keymap;
 pub mod layout;
 pub mod overlay;
+pub mod pilot;
 pub mod reactive;
 pub mod render_context;
 pub mod renderer;
 pub mod segment;
+pub mod selection;
 pub mod style;
 pub mod tcss;
 pub mod terminal;
@@ -91,21 +102,31 @@ pub mod wrap;
 #[cfg(test)]
 mod test_env;
 
+pub use ansi::{AnsiParser, parse_ansi, strip_ansi};
 pub use buffer::{CellChange, ScreenBuffer};
 pub use cell::Cell;
+pub use clipboard::{Clipboard, osc52_copy};
 pub use color::Color;
 pub use compositor::{Compositor, CompositorError, CompositorRegion, Layer};
 pub use cursor::{CursorPosition, CursorState, Selection};
 pub use error::{Result, SaorsaTuiError};
-pub use event::{Event, KeyCode, KeyEvent, Modifiers, MouseEvent};
+pub use event::{Event, KeyCode, KeyEvent, KeyEventKind, MediaKey, Modifiers, MouseEvent};
+pub use export::{ExportOptions, ExportTheme, export_html, export_svg};
 pub use focus::{FocusManager, FocusState, WidgetId};
 pub use geometry::{Position, Rect, Size};
-pub use highlight::{HighlightSpan, Highlighter, NoHighlighter, SimpleKeywordHighlighter};
+pub use graphics::{GraphicPlacement, GraphicsEncoder, ImageData, ImageProtocol};
+pub use highlight::{
+    HighlightSpan, Highlighter, NoHighlighter, SimpleKeywordHighlighter, SyntaxTheme, TextEdit,
+};
+#[cfg(feature = "tree-sitter")]
+pub use highlight::{SyntaxLanguage, TreeSitterHighlighter};
+pub use keymap::{EditMode, Keymap, KeymapKind, TextEditor};
 pub use layout::{
     Constraint, Direction, Dock, Layout, LayoutEngine, LayoutError, LayoutRect, OverflowBehavior,
     ScrollManager, ScrollState,
 };
 pub use overlay::{OverlayConfig, OverlayId, OverlayPosition, Placement, ScreenStack};
+pub use pilot::Pilot;
 pub use reactive::{
     Binding, BindingDirection, BindingExpression, BindingId, BindingScope, Computed, Effect,
     OneWayBinding, PropertySink, ReactiveScope, Signal, TwoWayBinding, batch,
@@ -113,7 +134,8 @@ pub use reactive::{
 pub use render_context::RenderContext;
 pub use renderer::{DeltaBatch, Renderer, batch_changes, build_sgr_sequence};
 pub use segment::Segment;
-pub use style::Style;
+pub use selection::ScreenSelection;
+pub use style::{Style, UnderlineStyle};
 pub use terminal::{
     CrosstermBackend, MultiplexerKind, Terminal, TerminalCapabilities, TerminalInfo, TerminalKind,
     TestBackend, detect, detect_multiplexer, detect_terminal, merge_multiplexer_limits,
@@ -127,10 +149,12 @@ pub use text_buffer::TextBuffer;
 pub use undo::{EditOperation, UndoStack};
 pub use viewport::Viewport;
 pub use widget::{
-    Alignment, BorderStyle, Checkbox, Collapsible, Column, Container, DataTable, DiffMode,
-    DiffView, DirectoryTree, EventResult, IndicatorStyle, Label, LoadingIndicator,
-    MarkdownRenderer, Modal, OptionList, ProgressBar, ProgressMode, RadioButton, RichLog,
-    SelectList, Sparkline, StaticWidget, Switch, Tab, TabBarPosition, Tabs, TextArea, Toast,
-    ToastPosition, Tooltip, Tree, TreeNode, Widget,
+    Alignment, BorderStyle, Checkbox, Collapsible, Column, CommandPalette, Container, CsvSource,
+    DataTable, DiffHunk, DiffMode, DiffView, DirectoryTree, EventResult, FileOp, FilterExpr,
+    GitFileStatus, HunkStatus, Image, IndicatorStyle, Label, LoadingIndicator, LogLevel,
+    MarkdownRenderer, MarkdownTheme, Modal, OptionList, PaletteEntry, ProgressBar, ProgressMode,
+    RadioButton, RichLog, SearchOptions, SelectList, SortKey, Sparkline, StaticWidget, Switch, Tab,
+    TabBarPosition, TableCell, TableDate, TableSource, Tabs, TerminalView, TextArea, TextMotion,
+    Toast, ToastPosition, Tooltip, Tree, TreeNode, Widget,
 };
 pub use wrap::{WrapLine, WrapResult, line_number_width, wrap_line, wrap_lines};
@@ -3,6 +3,7 @@
 //! Provides [`ScreenStack`] to manage a stack of overlay layers with
 //! automatic z-indexing, position resolution, and optional background dimming.
 
+use crate::color::Color;
 use crate::compositor::Layer;
 use crate::geometry::{Position, Rect, Size};
 use crate::segment::Segment;
@@ -51,6 +52,9 @@ pub struct OverlayConfig {
     pub z_offset: i32,
     /// Whether to insert a dim layer behind this overlay.
     pub dim_background: bool,
+    /// Opacity of the overlay in `0.0..=1.0`; below 1.0 the content
+    /// beneath shows through.
+    pub opacity: f32,
 }
 
 struct OverlayEntry {
@@ -193,7 +197,10 @@ impl ScreenStack {
                 entry.config.size.width,
                 entry.config.size.height,
             );
-            compositor.add_layer(Layer::new(entry.id, region, z, entry.lines.clone()));
+            compositor.add_layer(
+                Layer::new(entry.id, region, z, entry.lines.clone())
+                    .with_opacity(entry.config.opacity),
+            );
         }
     }
 }
@@ -204,9 +211,15 @@ impl Default for ScreenStack {
     }
 }
 
+/// Opacity of the black veil drawn by [`create_dim_layer`].
+pub const DIM_OPACITY: f32 = 0.5;
+
 /// Creates a full-screen dim layer for background dimming.
+///
+/// The layer is a translucent black veil, so content beneath stays visible
+/// but darkened; where colors cannot be blended it falls back to dim text.
 pub fn create_dim_layer(screen: Size, z_index: i32) -> Layer {
-    let dim_style = Style::new().dim(true);
+    let dim_style = Style::new().dim(true).bg(Color::Rgb { r: 0, g: 0, b: 0 });
     let mut lines = Vec::new();
     for _ in 0..screen.height {
         lines.push(vec![Segment::styled(
@@ -220,6 +233,7 @@ pub fn create_dim_layer(screen: Size, z_index: i32) -> Layer {
         z_index,
         lines,
     )
+    .with_opacity(DIM_OPACITY)
 }
 
 #[cfg(test)]
@@ -241,6 +255,7 @@ mod tests {
             size: Size::new(10, 5),
             z_offset: 0,
             dim_background: false,
+            opacity: 1.0,
         };
         let id = stack.push(config, vec![vec![Segment::new("hi")]]);
         assert!(id == 1);
@@ -255,6 +270,7 @@ mod tests {
             size: Size::new(10, 5),
             z_offset: 0,
             dim_background: false,
+            opacity: 1.0,
         };
         let _id1 = stack.push(config.clone(), vec![]);
         let id2 = stack.push(config, vec![]);
@@ -276,6 +292,7 @@ mod tests {
             size: Size::new(10, 5),
             z_offset: 0,
             dim_background: false,
+            opacity: 1.0,
         };
         let id1 = stack.push(config.clone(), vec![]);
         let _id2 = stack.push(config, vec![]);
@@ -297,6 +314,7 @@ mod tests {
             size: Size::new(10, 5),
             z_offset: 0,
             dim_background: false,
+            opacity: 1.0,
         };
         stack.push(config.clone(), vec![]);
         stack.push(config, vec![]);
@@ -397,6 +415,7 @@ mod tests {
             size: Size::new(10, 3),
             z_offset: 0,
             dim_background: false,
+            opacity: 1.0,
         };
         stack.push(config, vec![vec![Segment::new("test")]]);
 
@@ -421,6 +440,7 @@ mod tests {
             size: Size::new(10, 3),
             z_offset: 0,
             dim_background: true,
+            opacity: 1.0,
         };
         stack.push(config, vec![vec![Segment::new("modal")]]);
 
@@ -559,6 +579,7 @@ mod tests {
             size: Size::new(10, 3),
             z_offset: 0,
             dim_background: false,
+            opacity: 1.0,
         };
         stack.push(config1, vec![vec![Segment::new("first")]]);
 
@@ -568,6 +589,7 @@ mod tests {
             size: Size::new(10, 3),
             z_offset: 0,
             dim_background: false,
+            opacity: 1.0,
         };
         stack.push(config2, vec![vec![Segment::new("second")]]);
 
@@ -624,6 +646,7 @@ mod tests {
             size: Size::new(10, 3),
             z_offset: 0,
             dim_background: true,
+            opacity: 1.0,
         };
         let id = stack.push(config, vec![vec![Segment::new("x")]]);
 
@@ -653,6 +676,7 @@ mod tests {
             size: Size::new(5, 1),
             z_offset: 0,
             dim_background: false,
+            opacity: 1.0,
         };
         stack.push(config.clone(), vec![vec![Segment::new("A")]]);
         stack.push(config, vec![vec![Segment::new("B")]]);
@@ -671,4 +695,84 @@ mod tests {
             None => unreachable!(),
         }
     }
+
+    #[test]
+    fn dim_layer_keeps_content_visible() {
+        let screen = Size::new(10, 1);
+        let mut compositor = crate::compositor::Compositor::new(10, 1);
+        let text = Style::new().fg(Color::Rgb {
+            r: 200,
+            g: 200,
+            b: 200,
+        });
+        compositor.add_widget(
+            1,
+            Rect::new(0, 0, 10, 1),
+            0,
+            vec![vec![Segment::styled("behind", text)]],
+        );
+        compositor.add_layer(create_dim_layer(screen, 5));
+
+        let mut buf = crate::buffer::ScreenBuffer::new(screen);
+        compositor.compose(&mut buf);
+        match buf.get(0, 0) {
+            Some(cell) => {
+                assert_eq!(cell.grapheme, "b");
+                assert_eq!(
+                    cell.style.fg,
+                    Some(Color::Rgb {
+                        r: 100,
+                        g: 100,
+                        b: 100
+                    })
+                );
+            }
+            None => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn translucent_toast_blends_with_content() {
+        use crate::widget::toast::{Toast, ToastPosition};
+
+        let screen = Size::new(20, 2);
+        let blue = Color::Rgb { r: 0, g: 0, b: 200 };
+        let red = Color::Rgb { r: 200, g: 0, b: 0 };
+        let mut compositor = crate::compositor::Compositor::new(20, 2);
+        compositor.add_widget(
+            1,
+            Rect::new(0, 0, 20, 2),
+            0,
+            vec![vec![Segment::styled(
+                "x".repeat(20),
+                Style::new().bg(blue.clone()),
+            )]],
+        );
+        let toast = Toast::new("hi")
+            .with_position(ToastPosition::TopLeft)
+            .with_width(4)
+            .with_style(Style::new().bg(red))
+            .with_opacity(0.5);
+        let mut stack = ScreenStack::new();
+        stack.push(toast.to_overlay_config(screen), toast.render_to_lines());
+        stack.apply_to_compositor(&mut compositor, screen);
+
+        let mut buf = crate::buffer::ScreenBuffer::new(screen);
+        compositor.compose(&mut buf);
+        let purple = Some(Color::Rgb {
+            r: 100,
+            g: 0,
+            b: 100,
+        });
+        match (buf.get(0, 0), buf.get(3, 0), buf.get(5, 0)) {
+            (Some(h), Some(under), Some(outside)) => {
+                assert_eq!(h.grapheme, "h");
+                assert_eq!(h.style.bg, purple);
+                assert_eq!(under.grapheme, "x");
+                assert_eq!(under.style.bg, purple);
+                assert_eq!(outside.style.bg, Some(blue));
+            }
+            _ => unreachable!(),
+        }
+    }
 }
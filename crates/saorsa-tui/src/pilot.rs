@@ -0,0 +1,427 @@
+//! Headless end-to-end testing for [`App`].
+//!
+//! A [`Pilot`] drives an app rendered into a [`TestBackend`]: it sends key,
+//! text, mouse and resize input, advances the app clock, renders after each
+//! step, and exposes the rendered screen for assertions and snapshots.
+//!
+//! ```ignore
+//! let mut pilot = Pilot::from_tcss(40, 10, dom, "Label { height: 1; }")?;
+//! pilot.click("#name")?;
+//! pilot.type_text("Ada")?;
+//! pilot.press(KeyCode::Enter)?;
+//! assert!(pilot.contains_text("Hello, Ada"));
+//! insta::assert_snapshot!(pilot.screen_text());
+//! ```
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{App, Dom, NodeRef};
+use crate::color::Color;
+use crate::error::{Result, SaorsaTuiError};
+use crate::event::{Event, KeyCode, KeyEvent, Modifiers, MouseEvent, MouseEventKind};
+use crate::geometry::Position;
+use crate::style::{Style, UnderlineStyle};
+use crate::terminal::TestBackend;
+use crate::widget::EventResult;
+
+/// Frames rendered by [`Pilot::wait_for_idle`] before giving up.
+const MAX_IDLE_FRAMES: usize = 100;
+
+/// Drives an [`App`] headlessly for tests.
+pub struct Pilot {
+    app: App,
+    backend: TestBackend,
+}
+
+impl Pilot {
+    /// Wrap an app and its backend, rendering the first frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering fails.
+    pub fn new(app: App, backend: TestBackend) -> Result<Self> {
+        let mut pilot = Self { app, backend };
+        pilot.app.render_frame(&mut pilot.backend)?;
+        Ok(pilot)
+    }
+
+    /// Boot an app from a DOM and TCSS on a `width` × `height` test terminal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stylesheet does not parse or rendering fails.
+    pub fn from_tcss(width: u16, height: u16, dom: Dom, tcss: &str) -> Result<Self> {
+        let backend = TestBackend::new(width, height);
+        let app = App::from_tcss_string(&backend, dom, tcss)?;
+        Self::new(app, backend)
+    }
+
+    /// The app under test.
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// The app under test, mutably.
+    ///
+    /// Call [`Self::wait_for_idle`] after changing it to render the result.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// The test terminal, including the raw bytes written so far.
+    pub fn backend(&self) -> &TestBackend {
+        &self.backend
+    }
+
+    // ---------------------------
+    // Input
+    // ---------------------------
+
+    /// Dispatch an event and render until idle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if event handling or rendering fails.
+    pub fn send(&mut self, event: &Event) -> Result<EventResult> {
+        let res = self.app.handle_event(event)?;
+        self.wait_for_idle()?;
+        Ok(res)
+    }
+
+    /// Press a key without modifiers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if event handling or rendering fails.
+    pub fn press(&mut self, code: KeyCode) -> Result<EventResult> {
+        self.send(&Event::Key(KeyEvent::plain(code)))
+    }
+
+    /// Press a key with modifiers, e.g. Ctrl+S.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if event handling or rendering fails.
+    pub fn press_with(&mut self, code: KeyCode, modifiers: Modifiers) -> Result<EventResult> {
+        self.send(&Event::Key(KeyEvent::new(code, modifiers)))
+    }
+
+    /// Press several keys in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from event handling or rendering.
+    pub fn press_keys(&mut self, codes: &[KeyCode]) -> Result<()> {
+        for code in codes {
+            self.press(code.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Type text one key at a time; `\n` presses Enter and `\t` Tab.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from event handling or rendering.
+    pub fn type_text(&mut self, text: &str) -> Result<()> {
+        for ch in text.chars() {
+            let code = match ch {
+                '\n' => KeyCode::Enter,
+                '\t' => KeyCode::Tab,
+                c => KeyCode::Char(c),
+            };
+            self.press(code)?;
+        }
+        Ok(())
+    }
+
+    /// Send a bracketed paste.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if event handling or rendering fails.
+    pub fn paste(&mut self, text: &str) -> Result<EventResult> {
+        self.send(&Event::Paste(text.to_string()))
+    }
+
+    /// Click the centre of the first node matching `selector`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no node matches, the node is not visible, or
+    /// event handling fails.
+    pub fn click(&mut self, selector: &str) -> Result<()> {
+        let pos = self.center_of(selector)?;
+        self.click_at(pos.x, pos.y)
+    }
+
+    /// Press and release the mouse at a screen position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if event handling or rendering fails.
+    pub fn click_at(&mut self, x: u16, y: u16) -> Result<()> {
+        self.send(&mouse(MouseEventKind::Press, x, y))?;
+        self.send(&mouse(MouseEventKind::Release, x, y))?;
+        Ok(())
+    }
+
+    /// Turn the mouse wheel over the first node matching `selector`;
+    /// positive `notches` scroll down.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no node matches, the node is not visible, or
+    /// event handling fails.
+    pub fn scroll(&mut self, selector: &str, notches: i32) -> Result<()> {
+        let pos = self.center_of(selector)?;
+        let kind = if notches < 0 {
+            MouseEventKind::ScrollUp
+        } else {
+            MouseEventKind::ScrollDown
+        };
+        for _ in 0..notches.unsigned_abs() {
+            self.send(&mouse(kind, pos.x, pos.y))?;
+        }
+        Ok(())
+    }
+
+    /// Resize the terminal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering fails.
+    pub fn resize(&mut self, width: u16, height: u16) -> Result<()> {
+        self.backend.set_size(width, height);
+        self.send(&Event::Resize(width, height))?;
+        Ok(())
+    }
+
+    /// Advance the app clock, firing due timers, and render until idle.
+    ///
+    /// Returns the number of timer callbacks run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a timer callback or rendering fails.
+    pub fn advance(&mut self, elapsed: Duration) -> Result<usize> {
+        let fired = self.app.advance_clock(elapsed)?;
+        self.wait_for_idle()?;
+        Ok(fired)
+    }
+
+    /// Render until no render is pending (bindings applied, app clean).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering fails or the app keeps requesting
+    /// frames (e.g. a binding that updates itself).
+    pub fn wait_for_idle(&mut self) -> Result<()> {
+        for _ in 0..MAX_IDLE_FRAMES {
+            if !self.app.is_dirty() {
+                return Ok(());
+            }
+            self.app.render_if_needed(&mut self.backend)?;
+        }
+        Err(SaorsaTuiError::Render(format!(
+            "app not idle after {MAX_IDLE_FRAMES} frames"
+        )))
+    }
+
+    // ---------------------------
+    // Queries and assertions
+    // ---------------------------
+
+    /// The first node matching `selector`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selector is invalid or nothing matches.
+    pub fn node(&self, selector: &str) -> Result<NodeRef> {
+        self.app
+            .query_one(selector)?
+            .ok_or_else(|| SaorsaTuiError::Widget(format!("no node matches `{selector}`")))
+    }
+
+    /// The focused node, if any.
+    pub fn focused(&self) -> Option<NodeRef> {
+        self.app.dom().focus().focused().map(NodeRef)
+    }
+
+    /// Whether the first node matching `selector` has focus.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selector is invalid or nothing matches.
+    pub fn is_focused(&self, selector: &str) -> Result<bool> {
+        Ok(self.focused() == Some(self.node(selector)?))
+    }
+
+    /// Text of screen row `y`, with trailing blanks trimmed.
+    pub fn line(&self, y: u16) -> String {
+        let screen = self.app.screen();
+        let mut line = String::new();
+        for x in 0..screen.width() {
+            match screen.get(x, y) {
+                Some(cell) if !cell.is_continuation() => line.push_str(&cell.grapheme),
+                _ => {}
+            }
+        }
+        line.trim_end().to_string()
+    }
+
+    /// The whole screen as text: one line per row, trailing blanks trimmed.
+    pub fn screen_text(&self) -> String {
+        let rows: Vec<String> = (0..self.app.screen().height())
+            .map(|y| self.line(y))
+            .collect();
+        rows.join("\n")
+    }
+
+    /// Whether `text` appears on a single screen row.
+    pub fn contains_text(&self, text: &str) -> bool {
+        self.find_text(text).is_some()
+    }
+
+    /// The screen position of the first occurrence of `text` on a row.
+    pub fn find_text(&self, text: &str) -> Option<Position> {
+        (0..self.app.screen().height()).find_map(|y| {
+            let line = self.line(y);
+            let idx = line.find(text)?;
+            let x = u16::try_from(UnicodeWidthStr::width(&line[..idx])).ok()?;
+            Some(Position::new(x, y))
+        })
+    }
+
+    /// The style of the cell at a screen position.
+    pub fn style_at(&self, x: u16, y: u16) -> Option<&Style> {
+        self.app.screen().get(x, y).map(|cell| &cell.style)
+    }
+
+    /// A snapshot of the screen text followed by its styled runs.
+    ///
+    /// Each run is listed as `row:start..end attrs`, covering consecutive
+    /// cells with the same non-default style. The output is stable and
+    /// suited to `insta::assert_snapshot!`.
+    pub fn styled_snapshot(&self) -> String {
+        let screen = self.app.screen();
+        let mut out = self.screen_text();
+        out.push_str("\n---");
+        for y in 0..screen.height() {
+            let mut x = 0;
+            while x < screen.width() {
+                let style = match screen.get(x, y) {
+                    Some(cell) => cell.style.clone(),
+                    None => break,
+                };
+                let start = x;
+                while x < screen.width() && screen.get(x, y).is_some_and(|c| c.style == style) {
+                    x += 1;
+                }
+                if style != Style::default() {
+                    let _ = write!(out, "\n{y}:{start}..{x} {}", describe_style(&style));
+                }
+            }
+        }
+        out
+    }
+
+    fn center_of(&self, selector: &str) -> Result<Position> {
+        let node = self.node(selector)?;
+        let rect = self
+            .app
+            .visible_rect_of(node)
+            .ok_or_else(|| SaorsaTuiError::Widget(format!("`{selector}` is not visible")))?;
+        Ok(Position::new(
+            rect.position.x + rect.size.width / 2,
+            rect.position.y + rect.size.height / 2,
+        ))
+    }
+}
+
+fn mouse(kind: MouseEventKind, x: u16, y: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        x,
+        y,
+        modifiers: Modifiers::NONE,
+    })
+}
+
+/// Compact, stable description of a style, e.g. `fg=red bold`.
+fn describe_style(style: &Style) -> String {
+    let mut parts = Vec::new();
+    if let Some(fg) = &style.fg {
+        parts.push(format!("fg={}", describe_color(fg)));
+    }
+    if let Some(bg) = &style.bg {
+        parts.push(format!("bg={}", describe_color(bg)));
+    }
+    let flags = [
+        (style.bold, "bold"),
+        (style.dim, "dim"),
+        (style.italic, "italic"),
+        (style.strikethrough, "strikethrough"),
+        (style.reverse, "reverse"),
+        (style.blink, "blink"),
+        (style.hidden, "hidden"),
+        (style.overline, "overline"),
+    ];
+    parts.extend(
+        flags
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, n)| n.to_string()),
+    );
+    if style.underline {
+        parts.push(match style.underline_style {
+            UnderlineStyle::Single => "underline".to_string(),
+            shape => format!("underline={}", format!("{shape:?}").to_lowercase()),
+        });
+    }
+    if let Some(color) = &style.underline_color {
+        parts.push(format!("underline-color={}", describe_color(color)));
+    }
+    if let Some(link) = &style.link {
+        parts.push(format!("link={link}"));
+    }
+    parts.join(" ")
+}
+
+fn describe_color(color: &Color) -> String {
+    match color {
+        Color::Rgb { r, g, b } => format!("#{r:02x}{g:02x}{b:02x}"),
+        Color::Indexed(i) => format!("{i}"),
+        Color::Named(named) => format!("{named:?}").to_lowercase(),
+        Color::Reset => "reset".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::NamedColor;
+
+    #[test]
+    fn describe_style_lists_attributes() {
+        let style = Style::new()
+            .fg(Color::Named(NamedColor::BrightRed))
+            .bg(Color::Rgb { r: 1, g: 2, b: 255 })
+            .bold(true)
+            .underline(true);
+        assert_eq!(
+            describe_style(&style),
+            "fg=brightred bg=#0102ff bold underline"
+        );
+    }
+
+    #[test]
+    fn describe_underline_shape() {
+        let mut style = Style::new().underline(true);
+        style.underline_style = UnderlineStyle::Curly;
+        assert_eq!(describe_style(&style), "underline=curly");
+    }
+}
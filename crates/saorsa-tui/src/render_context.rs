@@ -3,12 +3,23 @@
 //! The `RenderContext` holds the current and previous frame buffers,
 //! diffs them, and renders the changes to the terminal. It optionally
 //! integrates a [`Compositor`] to resolve overlapping widget layers
-//! before diffing.
+//! before diffing. Inline image placements are diffed alongside the
+//! cells and drawn, moved or cleared after the cell output.
+//!
+//! A context created with [`RenderContext::new_inline`] renders into a
+//! fixed number of rows below the cursor instead of the whole (alternate)
+//! screen, leaving the shell's scrollback intact. Finished output can be
+//! committed above the live region with [`RenderContext::insert_before`].
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::sync::Arc;
 
-use crate::buffer::ScreenBuffer;
+use crate::buffer::{CellChange, ScreenBuffer};
 use crate::compositor::Compositor;
-use crate::error::Result;
-use crate::geometry::Size;
+use crate::error::{Result, SaorsaTuiError};
+use crate::geometry::{Position, Size};
+use crate::graphics::{GraphicPlacement, GraphicsEncoder, ImageData, ImageProtocol};
 use crate::renderer::Renderer;
 use crate::terminal::Terminal;
 
@@ -24,6 +35,27 @@ pub struct RenderContext {
     renderer: Renderer,
     size: Size,
     compositor: Option<Compositor>,
+    graphics: GraphicsEncoder,
+    /// Kitty images stored by the terminal, by id.
+    kitty_images: BTreeMap<u32, KittyUpload>,
+    inline: Option<InlineRegion>,
+    full_redraw: bool,
+}
+
+/// A Kitty image the terminal holds, and what was sent for it.
+#[derive(Clone, Debug)]
+struct KittyUpload {
+    image: Arc<ImageData>,
+    size: (u32, u32),
+}
+
+/// Placement of an inline viewport on the terminal screen.
+#[derive(Clone, Copy, Debug)]
+struct InlineRegion {
+    /// Terminal row of the first viewport row.
+    top: u16,
+    /// Terminal height in rows.
+    screen_height: u16,
 }
 
 impl RenderContext {
@@ -31,13 +63,65 @@ impl RenderContext {
     pub fn new(terminal: &dyn Terminal) -> Result<Self> {
         let size = terminal.size()?;
         let caps = terminal.capabilities();
-        let renderer = Renderer::new(caps.color, caps.synchronized_output);
+        let renderer = Renderer::new(caps.color, caps.synchronized_output)
+            .with_styled_underlines(caps.styled_underlines);
+        Ok(Self {
+            current: ScreenBuffer::new(size),
+            previous: ScreenBuffer::new(size),
+            renderer,
+            size,
+            compositor: None,
+            graphics: GraphicsEncoder::new(terminal.multiplexer()),
+            kitty_images: BTreeMap::new(),
+            inline: None,
+            full_redraw: false,
+        })
+    }
+
+    /// Create a render context for an inline viewport of `height` rows
+    /// starting at the cursor's line.
+    ///
+    /// The terminal should already be in raw mode without the alternate
+    /// screen. If fewer than `height` rows remain below the cursor, the
+    /// screen is scrolled up to make room. When the terminal cannot report
+    /// the cursor position the viewport is reserved at the bottom.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal size or cursor position cannot be
+    /// read, or if writing to the terminal fails.
+    pub fn new_inline(terminal: &mut dyn Terminal, height: u16) -> Result<Self> {
+        let screen = terminal.size()?;
+        let height = height.clamp(1, screen.height.max(1));
+        let row = match terminal.cursor_position()? {
+            Some(pos) => pos.y.min(screen.height.saturating_sub(1)),
+            None => screen.height.saturating_sub(1),
+        };
+        let overflow = (row + height).saturating_sub(screen.height);
+        if overflow > 0 {
+            let mut out = format!("\x1b[{};1H", screen.height);
+            out.push_str(&"\n".repeat(usize::from(overflow)));
+            terminal.write_raw(out.as_bytes())?;
+            terminal.flush()?;
+        }
+
+        let size = Size::new(screen.width, height);
+        let caps = terminal.capabilities();
+        let renderer = Renderer::new(caps.color, caps.synchronized_output)
+            .with_styled_underlines(caps.styled_underlines);
         Ok(Self {
             current: ScreenBuffer::new(size),
             previous: ScreenBuffer::new(size),
             renderer,
             size,
             compositor: None,
+            graphics: GraphicsEncoder::new(terminal.multiplexer()),
+            kitty_images: BTreeMap::new(),
+            inline: Some(InlineRegion {
+                top: row - overflow,
+                screen_height: screen.height,
+            }),
+            full_redraw: false,
         })
     }
 
@@ -49,6 +133,10 @@ impl RenderContext {
             renderer,
             size,
             compositor: None,
+            graphics: GraphicsEncoder::default(),
+            kitty_images: BTreeMap::new(),
+            inline: None,
+            full_redraw: false,
         }
     }
 
@@ -62,6 +150,13 @@ impl RenderContext {
         self
     }
 
+    /// Set the encoder used for inline images (builder pattern).
+    #[must_use]
+    pub fn with_graphics_encoder(mut self, encoder: GraphicsEncoder) -> Self {
+        self.graphics = encoder;
+        self
+    }
+
     /// Get a reference to the compositor, if one is set.
     pub fn compositor(&self) -> Option<&Compositor> {
         self.compositor.as_ref()
@@ -72,7 +167,19 @@ impl RenderContext {
         self.compositor.as_mut()
     }
 
+    /// Whether this context renders an inline viewport.
+    pub fn is_inline(&self) -> bool {
+        self.inline.is_some()
+    }
+
+    /// The terminal row where the inline viewport starts, if inline.
+    pub fn inline_top(&self) -> Option<u16> {
+        self.inline.map(|region| region.top)
+    }
+
     /// Get the current buffer dimensions.
+    ///
+    /// For an inline viewport this is the viewport, not the whole terminal.
     pub fn size(&self) -> Size {
         self.size
     }
@@ -93,6 +200,13 @@ impl RenderContext {
         self.current.clear();
     }
 
+    /// Begin a new frame that starts from a copy of the last one, for
+    /// callers that only repaint the areas that changed.
+    pub fn begin_frame_retained(&mut self) {
+        std::mem::swap(&mut self.current, &mut self.previous);
+        self.current.clone_from(&self.previous);
+    }
+
     /// End the frame: optionally compose layers, diff current vs previous,
     /// render to escape sequences, write to terminal and flush.
     ///
@@ -104,8 +218,27 @@ impl RenderContext {
             compositor.compose(&mut self.current);
         }
 
-        let changes = self.current.diff(&self.previous);
-        let output = self.renderer.render(&changes);
+        let mut output = String::new();
+        let blank;
+        let previous = if self.full_redraw {
+            self.full_redraw = false;
+            output.push_str(&self.clear_region());
+            blank = ScreenBuffer::new(self.current.size());
+            &blank
+        } else {
+            &self.previous
+        };
+        let mut changes = self.current.diff(previous);
+        let mut kitty_images = std::mem::take(&mut self.kitty_images);
+        let graphics = self.graphics_output(previous, &mut changes, &mut kitty_images);
+        self.kitty_images = kitty_images;
+        if let Some(region) = self.inline {
+            for change in &mut changes {
+                change.y += region.top;
+            }
+        }
+        output.push_str(&self.renderer.render(&changes));
+        output.push_str(&graphics);
         if !output.is_empty() {
             terminal.write_raw(output.as_bytes())?;
             terminal.flush()?;
@@ -113,13 +246,256 @@ impl RenderContext {
         Ok(())
     }
 
+    /// Commit `lines` rows of output above the inline viewport.
+    ///
+    /// `draw` renders into a buffer as wide as the viewport and `lines`
+    /// rows tall. Those rows are written just above the viewport and scroll
+    /// into the terminal's normal scrollback; the viewport moves down (or
+    /// the screen scrolls up) to make room and is fully redrawn on the next
+    /// [`end_frame`](Self::end_frame).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this is not an inline context or writing to the
+    /// terminal fails.
+    pub fn insert_before(
+        &mut self,
+        terminal: &mut dyn Terminal,
+        lines: u16,
+        draw: impl FnOnce(&mut ScreenBuffer),
+    ) -> Result<()> {
+        let Some(mut region) = self.inline else {
+            return Err(SaorsaTuiError::Render(
+                "insert_before requires an inline viewport".into(),
+            ));
+        };
+        if lines == 0 {
+            return Ok(());
+        }
+        let mut buf = ScreenBuffer::new(Size::new(self.size.width, lines));
+        draw(&mut buf);
+        let content = buf.diff(&ScreenBuffer::new(buf.size()));
+
+        let height = self.size.height;
+        // Rows available above the viewport once it sits at the bottom.
+        let chunk = region.screen_height.saturating_sub(height).max(1);
+        let mut output = String::new();
+        let mut start = 0;
+        while start < lines {
+            let count = chunk.min(lines - start);
+            let below = region
+                .screen_height
+                .saturating_sub(region.top.saturating_add(height));
+            let scroll = count.saturating_sub(below);
+            if scroll > 0 {
+                let _ = write!(output, "\x1b[{};1H", region.screen_height);
+                output.push_str(&"\n".repeat(usize::from(scroll)));
+            }
+            let new_top = region.top + count - scroll;
+            let first = new_top.saturating_sub(count);
+            for row in 0..count {
+                let _ = write!(output, "\x1b[{};1H\x1b[2K", first + row + 1);
+            }
+            let rows: Vec<CellChange> = content
+                .iter()
+                .filter(|c| (start..start + count).contains(&c.y))
+                .map(|c| CellChange {
+                    x: c.x,
+                    y: c.y - start + first,
+                    cell: c.cell.clone(),
+                })
+                .collect();
+            output.push_str(&self.renderer.render(&rows));
+            region.top = new_top;
+            start += count;
+        }
+
+        self.inline = Some(region);
+        self.full_redraw = true;
+        terminal.write_raw(output.as_bytes())?;
+        terminal.flush()
+    }
+
+    /// Leave an inline viewport, restoring the terminal for the shell.
+    ///
+    /// With `clear` the viewport rows are erased and the cursor returns to
+    /// the viewport's first row; otherwise the last frame stays on screen
+    /// and the cursor moves to the line below it. Does nothing for a
+    /// full-screen context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the terminal fails.
+    pub fn leave_inline(&mut self, terminal: &mut dyn Terminal, clear: bool) -> Result<()> {
+        let Some(region) = self.inline else {
+            return Ok(());
+        };
+        let mut output = String::from("\x1b[0m");
+        if clear {
+            output.push_str(&self.clear_region());
+            let _ = write!(output, "\x1b[{};1H", region.top + 1);
+        } else {
+            let bottom = region.top + self.size.height;
+            if bottom < region.screen_height {
+                let _ = write!(output, "\x1b[{};1H", bottom + 1);
+            } else {
+                // Scroll so the cursor lands on a fresh line below the frame.
+                let _ = write!(output, "\x1b[{};1H", region.screen_height);
+                output.push('\n');
+            }
+        }
+        terminal.write_raw(output.as_bytes())?;
+        terminal.flush()
+    }
+
+    /// Erase every row of the inline viewport (nothing when full-screen).
+    fn clear_region(&self) -> String {
+        let mut out = String::new();
+        if let Some(region) = self.inline {
+            for row in 0..self.size.height {
+                let _ = write!(out, "\x1b[{};1H\x1b[2K", region.top + row + 1);
+            }
+        }
+        out
+    }
+
+    /// Diff image placements between frames.
+    ///
+    /// Kitty images are transmitted once per id and then only placed:
+    /// moving one re-places it, hiding one removes its placement, and its
+    /// data is freed once it is no longer in the buffer. Removed
+    /// Sixel/iTerm2 images are erased by repainting their cells, which are
+    /// appended to `changes`. New or moved placements are drawn, as are
+    /// in-grid images whose cells were repainted this frame.
+    fn graphics_output(
+        &self,
+        previous: &ScreenBuffer,
+        changes: &mut Vec<CellChange>,
+        kitty_images: &mut BTreeMap<u32, KittyUpload>,
+    ) -> String {
+        let visible = |buf: &ScreenBuffer| -> Vec<GraphicPlacement> {
+            buf.graphics()
+                .iter()
+                .filter(|g| !g.occluded && g.protocol.is_graphical())
+                .cloned()
+                .collect()
+        };
+        let previous = visible(previous);
+        let current = visible(&self.current);
+        if previous.is_empty() && current.is_empty() && kitty_images.is_empty() {
+            return String::new();
+        }
+        // Kitty images in the buffer, shown or not.
+        let kept: HashSet<u32> = self
+            .current
+            .graphics()
+            .iter()
+            .filter(|g| g.protocol == ImageProtocol::Kitty)
+            .map(|g| g.id)
+            .collect();
+
+        let mut out = String::new();
+        for old in &previous {
+            if current.iter().any(|g| g.same_as(old)) {
+                continue;
+            }
+            if old.protocol != ImageProtocol::Kitty {
+                self.repaint_area(old, changes);
+            } else if kept.contains(&old.id)
+                && !current.iter().any(|g| g.id == old.id)
+                && let Some(seq) = self.graphics.hide(old)
+            {
+                // Occluded for now: keep the data to show it again later.
+                out.push_str(&seq);
+            }
+        }
+        kitty_images.retain(|id, _| {
+            let keep = kept.contains(id);
+            if !keep {
+                out.push_str(&self.graphics.release(*id));
+            }
+            keep
+        });
+
+        for placement in &current {
+            let unchanged = previous.iter().any(|g| g.same_as(placement));
+            let repainted = placement.protocol != ImageProtocol::Kitty
+                && changes
+                    .iter()
+                    .any(|c| placement.area.contains(Position::new(c.x, c.y)));
+            if placement.protocol == ImageProtocol::Kitty {
+                let size = self.graphics.transmit_size(placement);
+                let sent = kitty_images
+                    .get(&placement.id)
+                    .is_some_and(|u| Arc::ptr_eq(&u.image, &placement.image) && u.size == size);
+                if !sent {
+                    out.push_str(&self.graphics.transmit(placement));
+                    kitty_images.insert(
+                        placement.id,
+                        KittyUpload {
+                            image: Arc::clone(&placement.image),
+                            size,
+                        },
+                    );
+                }
+            }
+            if !unchanged || repainted {
+                out.push_str(&self.graphics.draw(&self.on_screen(placement)));
+            }
+        }
+        out
+    }
+
+    /// Translate a placement from buffer to terminal coordinates.
+    fn on_screen(&self, placement: &GraphicPlacement) -> GraphicPlacement {
+        let mut placement = placement.clone();
+        if let Some(region) = self.inline {
+            placement.area.position.y += region.top;
+        }
+        placement
+    }
+
+    /// Queue every cell under `placement` for redraw, skipping cells that
+    /// already changed this frame.
+    fn repaint_area(&self, placement: &GraphicPlacement, changes: &mut Vec<CellChange>) {
+        let area = placement.area;
+        let dirty: HashSet<(u16, u16)> = changes.iter().map(|c| (c.x, c.y)).collect();
+        for y in area.position.y..area.bottom().min(self.current.height()) {
+            for x in area.position.x..area.right().min(self.current.width()) {
+                if dirty.contains(&(x, y)) {
+                    continue;
+                }
+                if let Some(cell) = self.current.get(x, y) {
+                    changes.push(CellChange {
+                        x,
+                        y,
+                        cell: cell.clone(),
+                    });
+                }
+            }
+        }
+    }
+
     /// Handle a terminal resize: update buffers, size, and compositor dimensions.
+    ///
+    /// An inline viewport keeps its height, stays on screen and is fully
+    /// redrawn on the next frame.
     pub fn handle_resize(&mut self, new_size: Size) {
-        self.size = new_size;
-        self.current.resize(new_size);
-        self.previous.resize(new_size);
+        let size = match self.inline.as_mut() {
+            Some(region) => {
+                let height = self.size.height.min(new_size.height.max(1));
+                region.screen_height = new_size.height;
+                region.top = region.top.min(new_size.height.saturating_sub(height));
+                self.full_redraw = true;
+                Size::new(new_size.width, height)
+            }
+            None => new_size,
+        };
+        self.size = size;
+        self.current.resize(size);
+        self.previous.resize(size);
         if let Some(ref mut compositor) = self.compositor {
-            compositor.resize(new_size.width, new_size.height);
+            compositor.resize(size.width, size.height);
         }
     }
 }
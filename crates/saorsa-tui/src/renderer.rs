@@ -9,24 +9,40 @@ use std::fmt::Write;
 use crate::buffer::CellChange;
 use crate::cell::Cell;
 use crate::color::{Color, NamedColor};
-use crate::style::Style;
+use crate::style::{Style, UnderlineStyle};
 use crate::terminal::ColorSupport;
 
 /// Renders cell changes into ANSI escape sequences.
 pub struct Renderer {
     color_support: ColorSupport,
     synchronized_output: bool,
+    styled_underlines: bool,
 }
 
 impl Renderer {
     /// Create a new renderer with the given color support level.
+    ///
+    /// Extended underline styles and underline colors are disabled; enable
+    /// them with [`Renderer::with_styled_underlines`].
     pub fn new(color_support: ColorSupport, synchronized_output: bool) -> Self {
         Self {
             color_support,
             synchronized_output,
+            styled_underlines: false,
         }
     }
 
+    /// Enable or disable extended underline styles (`CSI 4:x m`) and
+    /// underline colors (`CSI 58`).
+    ///
+    /// When disabled, every underline is emitted as a plain `CSI 4 m` and
+    /// underline colors are dropped.
+    #[must_use]
+    pub fn with_styled_underlines(mut self, enabled: bool) -> Self {
+        self.styled_underlines = enabled;
+        self
+    }
+
     /// Render a set of cell changes into a string of ANSI escape sequences.
     pub fn render(&self, changes: &[CellChange]) -> String {
         if changes.is_empty() {
@@ -188,7 +204,11 @@ impl Renderer {
                 if style_active && !last_style.is_empty() {
                     output.push_str("\x1b[0m");
                 }
-                let sgr = build_sgr_sequence(&change.cell.style, self.color_support);
+                let sgr = sgr_sequence(
+                    &change.cell.style,
+                    self.color_support,
+                    self.styled_underlines,
+                );
                 output.push_str(&sgr);
             }
 
@@ -245,15 +265,31 @@ impl Renderer {
         if !prev.italic && next.italic {
             output.push_str("\x1b[3m");
         }
-        if !prev.underline && next.underline {
-            output.push_str("\x1b[4m");
+        if next.underline && (!prev.underline || prev.underline_style != next.underline_style) {
+            self.write_underline(output, next.underline_style);
+        }
+        if prev.underline_color != next.underline_color {
+            match next.underline_color {
+                Some(_) => self.write_underline_color(output, &next.underline_color),
+                None if self.styled_underlines => output.push_str("\x1b[59m"),
+                None => {}
+            }
+        }
+        if !prev.blink && next.blink {
+            output.push_str("\x1b[5m");
         }
         if !prev.reverse && next.reverse {
             output.push_str("\x1b[7m");
         }
+        if !prev.hidden && next.hidden {
+            output.push_str("\x1b[8m");
+        }
         if !prev.strikethrough && next.strikethrough {
             output.push_str("\x1b[9m");
         }
+        if !prev.overline && next.overline {
+            output.push_str("\x1b[53m");
+        }
     }
 
     /// Write a full style (all attributes from scratch).
@@ -270,14 +306,47 @@ impl Renderer {
             output.push_str("\x1b[3m");
         }
         if style.underline {
-            output.push_str("\x1b[4m");
+            self.write_underline(output, style.underline_style);
+        }
+        self.write_underline_color(output, &style.underline_color);
+        if style.blink {
+            output.push_str("\x1b[5m");
         }
         if style.reverse {
             output.push_str("\x1b[7m");
         }
+        if style.hidden {
+            output.push_str("\x1b[8m");
+        }
         if style.strikethrough {
             output.push_str("\x1b[9m");
         }
+        if style.overline {
+            output.push_str("\x1b[53m");
+        }
+    }
+
+    /// Write an underline SGR sequence, falling back to a single underline
+    /// when styled underlines are unsupported.
+    fn write_underline(&self, output: &mut String, style: UnderlineStyle) {
+        if self.styled_underlines && style != UnderlineStyle::Single {
+            let _ = write!(output, "\x1b[4:{}m", style.sgr_subparam());
+        } else {
+            output.push_str("\x1b[4m");
+        }
+    }
+
+    /// Write an underline color SGR sequence, if supported.
+    fn write_underline_color(&self, output: &mut String, color: &Option<Color>) {
+        if !self.styled_underlines {
+            return;
+        }
+        if let Some(c) = color {
+            let downgraded = self.downgrade_color(c);
+            output.push_str("\x1b[");
+            output.push_str(&underline_color_codes(&downgraded).join(":"));
+            output.push('m');
+        }
     }
 
     /// Write a foreground color SGR sequence.
@@ -338,8 +407,11 @@ fn needs_reset(prev: &Style, next: &Style) -> bool {
         || (prev.dim && !next.dim)
         || (prev.italic && !next.italic)
         || (prev.underline && !next.underline)
+        || (prev.blink && !next.blink)
         || (prev.reverse && !next.reverse)
+        || (prev.hidden && !next.hidden)
         || (prev.strikethrough && !next.strikethrough)
+        || (prev.overline && !next.overline)
 }
 
 /// Build a single combined SGR sequence for all active attributes of a style.
@@ -347,7 +419,16 @@ fn needs_reset(prev: &Style, next: &Style) -> bool {
 /// Instead of emitting separate `\x1b[1m\x1b[3m\x1b[31m` sequences for
 /// bold, italic, and red foreground, this produces a single `\x1b[1;3;31m`.
 /// Returns an empty string if the style has no active attributes.
+///
+/// Underline styles and underline colors are always emitted in their
+/// extended form (`4:3`, `58:2::r:g:b`); use a [`Renderer`] configured with
+/// [`Renderer::with_styled_underlines`] to degrade them for older terminals.
 pub fn build_sgr_sequence(style: &Style, color_support: ColorSupport) -> String {
+    sgr_sequence(style, color_support, true)
+}
+
+/// Build a combined SGR sequence, optionally degrading extended underlines.
+fn sgr_sequence(style: &Style, color_support: ColorSupport, styled_underlines: bool) -> String {
     let mut codes: Vec<String> = Vec::new();
 
     // Text attributes
@@ -361,14 +442,27 @@ pub fn build_sgr_sequence(style: &Style, color_support: ColorSupport) -> String
         codes.push("3".to_string());
     }
     if style.underline {
-        codes.push("4".to_string());
+        if styled_underlines && style.underline_style != UnderlineStyle::Single {
+            codes.push(format!("4:{}", style.underline_style.sgr_subparam()));
+        } else {
+            codes.push("4".to_string());
+        }
+    }
+    if style.blink {
+        codes.push("5".to_string());
     }
     if style.reverse {
         codes.push("7".to_string());
     }
+    if style.hidden {
+        codes.push("8".to_string());
+    }
     if style.strikethrough {
         codes.push("9".to_string());
     }
+    if style.overline {
+        codes.push("53".to_string());
+    }
 
     // Foreground color
     if let Some(ref fg) = style.fg {
@@ -382,6 +476,12 @@ pub fn build_sgr_sequence(style: &Style, color_support: ColorSupport) -> String
         codes.extend(bg_color_codes(&downgraded));
     }
 
+    // Underline color
+    if styled_underlines && let Some(ref ul) = style.underline_color {
+        let downgraded = downgrade_color_standalone(ul, color_support);
+        codes.push(underline_color_codes(&downgraded).join(":"));
+    }
+
     if codes.is_empty() {
         return String::new();
     }
@@ -445,6 +545,36 @@ fn bg_color_codes(color: &Color) -> Vec<String> {
     }
 }
 
+/// Return the colon-separated SGR sub-parameters for an underline color.
+///
+/// Named colors have no dedicated underline code, so they map to their
+/// palette index.
+fn underline_color_codes(color: &Color) -> Vec<String> {
+    match color {
+        Color::Rgb { r, g, b } => vec![
+            "58".to_string(),
+            "2".to_string(),
+            String::new(),
+            r.to_string(),
+            g.to_string(),
+            b.to_string(),
+        ],
+        Color::Indexed(i) => vec!["58".to_string(), "5".to_string(), i.to_string()],
+        Color::Named(n) => vec![
+            "58".to_string(),
+            "5".to_string(),
+            named_to_index(n).to_string(),
+        ],
+        Color::Reset => vec!["59".to_string()],
+    }
+}
+
+/// Palette index (0-15) of a named color.
+pub(crate) fn named_to_index(color: &NamedColor) -> u8 {
+    let code = named_fg_code(color);
+    if code >= 90 { code - 90 + 8 } else { code - 30 }
+}
+
 /// Write an SGR foreground color escape sequence.
 fn write_fg_color(output: &mut String, color: &Color) {
     match color {
@@ -621,7 +751,7 @@ fn rgb_to_lab(r: u8, g: u8, b: u8) -> Lab {
 }
 
 /// Convert sRGB value (0-255) to linear RGB (0.0-1.0).
-fn srgb_to_linear(c: u8) -> f32 {
+pub(crate) fn srgb_to_linear(c: u8) -> f32 {
     let c_norm = f32::from(c) / 255.0;
     if c_norm <= 0.04045 {
         c_norm / 12.92
@@ -1762,4 +1892,163 @@ mod tests {
         };
         assert!(is_greenish, "idx={idx} should be greenish");
     }
+
+    // --- Extended text attributes ---
+
+    fn render_one(renderer: &Renderer, style: Style) -> String {
+        renderer.render(&[CellChange {
+            x: 0,
+            y: 0,
+            cell: Cell::new("X", style),
+        }])
+    }
+
+    #[test]
+    fn styled_underline_emits_subparam() {
+        let renderer = Renderer::new(ColorSupport::TrueColor, false).with_styled_underlines(true);
+        let output = render_one(
+            &renderer,
+            Style::new().underline_style(UnderlineStyle::Curly),
+        );
+        assert!(output.contains("\x1b[4:3m"));
+    }
+
+    #[test]
+    fn styled_underline_falls_back_to_single() {
+        let renderer = Renderer::new(ColorSupport::TrueColor, false);
+        let style = Style::new()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::Rgb { r: 255, g: 0, b: 0 });
+        let output = render_one(&renderer, style);
+        assert!(output.contains("\x1b[4m"));
+        assert!(!output.contains("4:3"));
+        assert!(!output.contains("58"));
+    }
+
+    #[test]
+    fn underline_color_truecolor() {
+        without_no_color(|| {
+            let renderer =
+                Renderer::new(ColorSupport::TrueColor, false).with_styled_underlines(true);
+            let style = Style::new()
+                .underline_style(UnderlineStyle::Dotted)
+                .underline_color(Color::Rgb { r: 1, g: 2, b: 3 });
+            let output = render_one(&renderer, style);
+            assert!(output.contains("\x1b[4:4m"));
+            assert!(output.contains("\x1b[58:2::1:2:3m"));
+        });
+    }
+
+    #[test]
+    fn underline_color_downgraded_and_named() {
+        without_no_color(|| {
+            let renderer =
+                Renderer::new(ColorSupport::Extended256, false).with_styled_underlines(true);
+            let output = render_one(
+                &renderer,
+                Style::new()
+                    .underline(true)
+                    .underline_color(Color::Rgb { r: 255, g: 0, b: 0 }),
+            );
+            assert!(output.contains("\x1b[58:5:"));
+
+            let output = render_one(
+                &renderer,
+                Style::new()
+                    .underline(true)
+                    .underline_color(Color::Named(NamedColor::BrightRed)),
+            );
+            assert!(output.contains("\x1b[58:5:9m"));
+        });
+    }
+
+    #[test]
+    fn underline_color_cleared_incrementally() {
+        without_no_color(|| {
+            let renderer =
+                Renderer::new(ColorSupport::TrueColor, false).with_styled_underlines(true);
+            let colored = Style::new()
+                .underline(true)
+                .underline_color(Color::Named(NamedColor::Red));
+            let plain = Style::new().underline(true);
+            let output = renderer.render(&[
+                CellChange {
+                    x: 0,
+                    y: 0,
+                    cell: Cell::new("A", colored),
+                },
+                CellChange {
+                    x: 1,
+                    y: 0,
+                    cell: Cell::new("B", plain),
+                },
+            ]);
+            assert!(output.contains("\x1b[59m"));
+        });
+    }
+
+    #[test]
+    fn underline_style_change_without_reset() {
+        let renderer = Renderer::new(ColorSupport::TrueColor, false).with_styled_underlines(true);
+        let output = renderer.render(&[
+            CellChange {
+                x: 0,
+                y: 0,
+                cell: Cell::new("A", Style::new().underline_style(UnderlineStyle::Double)),
+            },
+            CellChange {
+                x: 1,
+                y: 0,
+                cell: Cell::new("B", Style::new().underline_style(UnderlineStyle::Dashed)),
+            },
+        ]);
+        assert!(output.contains("\x1b[4:2mA\x1b[4:5mB"));
+    }
+
+    #[test]
+    fn blink_hidden_overline_codes() {
+        let renderer = Renderer::new(ColorSupport::TrueColor, false);
+        let style = Style::new().blink(true).hidden(true).overline(true);
+        let output = render_one(&renderer, style);
+        assert!(output.contains("\x1b[5m"));
+        assert!(output.contains("\x1b[8m"));
+        assert!(output.contains("\x1b[53m"));
+    }
+
+    #[test]
+    fn turning_off_overline_resets() {
+        let prev = Style::new().overline(true);
+        assert!(needs_reset(&prev, &Style::new()));
+        let prev = Style::new().blink(true);
+        assert!(needs_reset(&prev, &Style::new()));
+        let prev = Style::new().hidden(true);
+        assert!(needs_reset(&prev, &Style::new()));
+    }
+
+    #[test]
+    fn build_sgr_sequence_extended() {
+        without_no_color(|| {
+            let style = Style::new()
+                .underline_style(UnderlineStyle::Curly)
+                .underline_color(Color::Rgb { r: 255, g: 0, b: 0 })
+                .blink(true)
+                .overline(true);
+            let sgr = build_sgr_sequence(&style, ColorSupport::TrueColor);
+            assert_eq!(sgr, "\x1b[4:3;5;53;58:2::255:0:0m");
+        });
+    }
+
+    #[test]
+    fn render_optimized_degrades_without_support() {
+        let renderer = Renderer::new(ColorSupport::TrueColor, false);
+        let style = Style::new()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::Named(NamedColor::Red));
+        let output = renderer.render_optimized(&[CellChange {
+            x: 0,
+            y: 0,
+            cell: Cell::new("X", style),
+        }]);
+        assert!(output.contains("\x1b[4mX"));
+    }
 }
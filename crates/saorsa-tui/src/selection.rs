@@ -0,0 +1,174 @@
+//! Mouse text selection over the rendered screen.
+//!
+//! A [`ScreenSelection`] spans cells in reading order, like a terminal's
+//! native selection: from the anchor cell to the end of its row, whole rows
+//! in between, and the start of the last row up to the head cell.
+
+use crate::buffer::ScreenBuffer;
+use crate::geometry::Position;
+use crate::style::Style;
+
+/// A linear selection of screen cells between an anchor and a head.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScreenSelection {
+    /// Where the selection started (mouse press).
+    pub anchor: Position,
+    /// Where the selection currently ends (mouse drag).
+    pub head: Position,
+}
+
+impl ScreenSelection {
+    /// Start a selection at `pos`.
+    pub fn new(pos: Position) -> Self {
+        Self {
+            anchor: pos,
+            head: pos,
+        }
+    }
+
+    /// Move the head of the selection to `pos`.
+    pub fn extend_to(&mut self, pos: Position) {
+        self.head = pos;
+    }
+
+    /// Whether the selection covers no drag (anchor and head coincide).
+    pub fn is_empty(&self) -> bool {
+        self.anchor == self.head
+    }
+
+    /// The (start, end) positions in reading order, both inclusive.
+    pub fn ordered(&self) -> (Position, Position) {
+        if (self.anchor.y, self.anchor.x) <= (self.head.y, self.head.x) {
+            (self.anchor, self.head)
+        } else {
+            (self.head, self.anchor)
+        }
+    }
+
+    /// Whether the cell at (`x`, `y`) is selected.
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        let (start, end) = self.ordered();
+        (start.y, start.x) <= (y, x) && (y, x) <= (end.y, end.x)
+    }
+
+    /// Extract the selected text from `buf`.
+    ///
+    /// Rows are joined with newlines and trailing blanks on each row are
+    /// trimmed. Continuation cells of wide characters are skipped.
+    pub fn text(&self, buf: &ScreenBuffer) -> String {
+        let (start, end) = self.ordered();
+        let last_row = end.y.min(buf.height().saturating_sub(1));
+        let mut lines = Vec::new();
+        for y in start.y..=last_row {
+            let from = if y == start.y { start.x } else { 0 };
+            let to = if y == end.y {
+                end.x
+            } else {
+                buf.width().saturating_sub(1)
+            };
+            let mut line = String::new();
+            for x in from..=to {
+                match buf.get(x, y) {
+                    Some(cell) if !cell.is_continuation() => line.push_str(&cell.grapheme),
+                    _ => {}
+                }
+            }
+            lines.push(line.trim_end().to_string());
+        }
+        lines.join("\n")
+    }
+
+    /// Merge `style` into every selected cell of `buf`.
+    pub fn highlight(&self, buf: &mut ScreenBuffer, style: &Style) {
+        let (start, end) = self.ordered();
+        for y in start.y..=end.y.min(buf.height().saturating_sub(1)) {
+            for x in 0..buf.width() {
+                if self.contains(x, y)
+                    && let Some(cell) = buf.get_mut(x, y)
+                {
+                    cell.style = cell.style.merge(style);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::geometry::Size;
+
+    fn buffer_with(lines: &[&str]) -> ScreenBuffer {
+        let mut buf = ScreenBuffer::new(Size::new(10, lines.len() as u16));
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                buf.set(
+                    x as u16,
+                    y as u16,
+                    Cell::new(ch.to_string(), Style::default()),
+                );
+            }
+        }
+        buf
+    }
+
+    #[test]
+    fn single_row_text() {
+        let buf = buffer_with(&["hello world"]);
+        let mut sel = ScreenSelection::new(Position::new(1, 0));
+        sel.extend_to(Position::new(3, 0));
+        assert_eq!(sel.text(&buf), "ell");
+    }
+
+    #[test]
+    fn multi_row_text_trims_trailing_blanks() {
+        let buf = buffer_with(&["fn main", "  x();", "}"]);
+        let mut sel = ScreenSelection::new(Position::new(3, 0));
+        sel.extend_to(Position::new(0, 2));
+        assert_eq!(sel.text(&buf), "main\n  x();\n}");
+    }
+
+    #[test]
+    fn backwards_drag_is_ordered() {
+        let sel = ScreenSelection {
+            anchor: Position::new(2, 1),
+            head: Position::new(5, 0),
+        };
+        assert_eq!(sel.ordered(), (Position::new(5, 0), Position::new(2, 1)));
+        assert!(sel.contains(9, 0));
+        assert!(sel.contains(0, 1));
+        assert!(!sel.contains(4, 0));
+        assert!(!sel.contains(3, 1));
+    }
+
+    #[test]
+    fn wide_characters_are_not_duplicated() {
+        let mut buf = ScreenBuffer::new(Size::new(4, 1));
+        buf.set(0, 0, Cell::new("世", Style::default()));
+        buf.set(2, 0, Cell::new("a", Style::default()));
+        let mut sel = ScreenSelection::new(Position::new(0, 0));
+        sel.extend_to(Position::new(2, 0));
+        assert_eq!(sel.text(&buf), "世a");
+    }
+
+    #[test]
+    fn highlight_marks_selected_cells() {
+        let mut buf = buffer_with(&["abc", "def"]);
+        let mut sel = ScreenSelection::new(Position::new(2, 0));
+        sel.extend_to(Position::new(0, 1));
+        sel.highlight(&mut buf, &Style::new().reverse(true));
+        let reversed = |x, y| buf.get(x, y).is_some_and(|c| c.style.reverse);
+        assert!(!reversed(1, 0));
+        assert!(reversed(2, 0));
+        assert!(reversed(9, 0));
+        assert!(reversed(0, 1));
+        assert!(!reversed(1, 1));
+    }
+
+    #[test]
+    fn empty_selection() {
+        let sel = ScreenSelection::new(Position::new(4, 4));
+        assert!(sel.is_empty());
+    }
+}
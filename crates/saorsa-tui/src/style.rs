@@ -2,6 +2,41 @@
 
 use crate::color::Color;
 
+/// The shape of an underline.
+///
+/// Styles other than [`UnderlineStyle::Single`] use the extended
+/// `CSI 4:x m` form. Terminals without support fall back to a single
+/// underline (see [`TerminalCapabilities::styled_underlines`]).
+///
+/// [`TerminalCapabilities::styled_underlines`]: crate::terminal::TerminalCapabilities::styled_underlines
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum UnderlineStyle {
+    /// A single straight line (`CSI 4 m`).
+    #[default]
+    Single,
+    /// Two straight lines (`CSI 4:2 m`).
+    Double,
+    /// A wavy line, often called undercurl (`CSI 4:3 m`).
+    Curly,
+    /// A dotted line (`CSI 4:4 m`).
+    Dotted,
+    /// A dashed line (`CSI 4:5 m`).
+    Dashed,
+}
+
+impl UnderlineStyle {
+    /// The `CSI 4:x m` sub-parameter for this style.
+    pub fn sgr_subparam(self) -> u8 {
+        match self {
+            Self::Single => 1,
+            Self::Double => 2,
+            Self::Curly => 3,
+            Self::Dotted => 4,
+            Self::Dashed => 5,
+        }
+    }
+}
+
 /// Style attributes for a piece of text.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Style {
@@ -15,12 +50,22 @@ pub struct Style {
     pub italic: bool,
     /// Underlined text.
     pub underline: bool,
+    /// Shape of the underline (only meaningful when `underline` is set).
+    pub underline_style: UnderlineStyle,
+    /// Underline color, independent of the foreground (`CSI 58`).
+    pub underline_color: Option<Color>,
     /// Strikethrough text.
     pub strikethrough: bool,
     /// Dim/faint text.
     pub dim: bool,
     /// Reverse video.
     pub reverse: bool,
+    /// Blinking text.
+    pub blink: bool,
+    /// Hidden (concealed) text.
+    pub hidden: bool,
+    /// Line above the text.
+    pub overline: bool,
     /// OSC 8 hyperlink URL.
     pub link: Option<String>,
 }
@@ -66,6 +111,21 @@ impl Style {
         self
     }
 
+    /// Set the underline shape. Enables the underline.
+    #[must_use]
+    pub fn underline_style(mut self, val: UnderlineStyle) -> Self {
+        self.underline = true;
+        self.underline_style = val;
+        self
+    }
+
+    /// Set the underline color.
+    #[must_use]
+    pub fn underline_color(mut self, color: Color) -> Self {
+        self.underline_color = Some(color);
+        self
+    }
+
     /// Set strikethrough.
     #[must_use]
     pub fn strikethrough(mut self, val: bool) -> Self {
@@ -87,6 +147,27 @@ impl Style {
         self
     }
 
+    /// Set blink.
+    #[must_use]
+    pub fn blink(mut self, val: bool) -> Self {
+        self.blink = val;
+        self
+    }
+
+    /// Set hidden (concealed).
+    #[must_use]
+    pub fn hidden(mut self, val: bool) -> Self {
+        self.hidden = val;
+        self
+    }
+
+    /// Set overline.
+    #[must_use]
+    pub fn overline(mut self, val: bool) -> Self {
+        self.overline = val;
+        self
+    }
+
     /// Set hyperlink URL.
     #[must_use]
     pub fn link(mut self, url: impl Into<String>) -> Self {
@@ -96,6 +177,9 @@ impl Style {
 
     /// Merge another style on top of this one. The `other` style's
     /// set values take priority.
+    ///
+    /// The underline shape follows whichever style enables the underline,
+    /// preferring `other`.
     #[must_use]
     pub fn merge(&self, other: &Style) -> Style {
         Style {
@@ -108,6 +192,15 @@ impl Style {
             } else {
                 self.underline
             },
+            underline_style: if other.underline {
+                other.underline_style
+            } else {
+                self.underline_style
+            },
+            underline_color: other
+                .underline_color
+                .clone()
+                .or_else(|| self.underline_color.clone()),
             strikethrough: if other.strikethrough {
                 true
             } else {
@@ -115,6 +208,9 @@ impl Style {
             },
             dim: if other.dim { true } else { self.dim },
             reverse: if other.reverse { true } else { self.reverse },
+            blink: if other.blink { true } else { self.blink },
+            hidden: if other.hidden { true } else { self.hidden },
+            overline: if other.overline { true } else { self.overline },
             link: other.link.clone().or_else(|| self.link.clone()),
         }
     }
@@ -143,7 +239,16 @@ impl From<&Style> for crossterm::style::ContentStyle {
             cs.attributes.set(Attribute::Italic);
         }
         if style.underline {
-            cs.attributes.set(Attribute::Underlined);
+            cs.attributes.set(match style.underline_style {
+                UnderlineStyle::Single => Attribute::Underlined,
+                UnderlineStyle::Double => Attribute::DoubleUnderlined,
+                UnderlineStyle::Curly => Attribute::Undercurled,
+                UnderlineStyle::Dotted => Attribute::Underdotted,
+                UnderlineStyle::Dashed => Attribute::Underdashed,
+            });
+        }
+        if let Some(ref ul) = style.underline_color {
+            cs.underline_color = Some(ul.into());
         }
         if style.strikethrough {
             cs.attributes.set(Attribute::CrossedOut);
@@ -154,6 +259,15 @@ impl From<&Style> for crossterm::style::ContentStyle {
         if style.reverse {
             cs.attributes.set(Attribute::Reverse);
         }
+        if style.blink {
+            cs.attributes.set(Attribute::SlowBlink);
+        }
+        if style.hidden {
+            cs.attributes.set(Attribute::Hidden);
+        }
+        if style.overline {
+            cs.attributes.set(Attribute::OverLined);
+        }
         cs
     }
 }
@@ -203,6 +317,55 @@ mod tests {
         assert!(merged.italic);
     }
 
+    #[test]
+    fn underline_style_enables_underline() {
+        let s = Style::new().underline_style(UnderlineStyle::Curly);
+        assert!(s.underline);
+        assert_eq!(s.underline_style, UnderlineStyle::Curly);
+    }
+
+    #[test]
+    fn merge_underline_shape_follows_enabling_style() {
+        let base = Style::new()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::Named(NamedColor::Red));
+        let plain_over = Style::new().bold(true);
+        let merged = base.merge(&plain_over);
+        assert_eq!(merged.underline_style, UnderlineStyle::Curly);
+        assert_eq!(merged.underline_color, Some(Color::Named(NamedColor::Red)));
+
+        let dotted_over = Style::new().underline_style(UnderlineStyle::Dotted);
+        let merged = base.merge(&dotted_over);
+        assert_eq!(merged.underline_style, UnderlineStyle::Dotted);
+        assert_eq!(merged.underline_color, Some(Color::Named(NamedColor::Red)));
+    }
+
+    #[test]
+    fn merge_extended_attributes() {
+        let base = Style::new().blink(true);
+        let over = Style::new().hidden(true).overline(true);
+        let merged = base.merge(&over);
+        assert!(merged.blink);
+        assert!(merged.hidden);
+        assert!(merged.overline);
+    }
+
+    #[test]
+    fn crossterm_conversion_extended() {
+        use crossterm::style::Attribute;
+        let s = Style::new()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::Rgb { r: 255, g: 0, b: 0 })
+            .overline(true);
+        let cs: crossterm::style::ContentStyle = (&s).into();
+        assert!(cs.attributes.has(Attribute::Undercurled));
+        assert!(cs.attributes.has(Attribute::OverLined));
+        assert_eq!(
+            cs.underline_color,
+            Some(crossterm::style::Color::Rgb { r: 255, g: 0, b: 0 })
+        );
+    }
+
     #[test]
     fn crossterm_conversion() {
         let s = Style::new().fg(Color::Rgb { r: 1, g: 2, b: 3 }).bold(true);
@@ -2,19 +2,29 @@
 
 use crate::Color;
 use crate::color::NamedColor;
-use crate::style::Style;
+use crate::highlight::SyntaxTheme;
+use crate::style::{Style, UnderlineStyle};
 use crate::tcss::ComputedStyle;
 use crate::tcss::property::PropertyName;
 use crate::tcss::value::{CssValue, Length};
-use crate::widget::{Alignment, BorderStyle, Container, Label, RichLog};
+use crate::tcss::variable::VariableEnvironment;
+use crate::widget::{
+    Alignment, BorderStyle, CommandPalette, Container, DiffView, Label, RichLog, TextArea,
+};
 
 /// Widgets that can accept a [`ComputedStyle`] at runtime.
 pub trait ApplyComputedStyle {
     /// Apply a computed style to the widget.
     fn apply_computed_style(&mut self, computed: &ComputedStyle);
+
+    /// Apply the app's theme variables, e.g. to derive a [`SyntaxTheme`].
+    ///
+    /// Called when the widget is mounted and whenever the active theme
+    /// changes. Default is no-op.
+    fn apply_variables(&mut self, _vars: &VariableEnvironment) {}
 }
 
-fn css_color(v: &CssValue) -> Option<Color> {
+pub(crate) fn css_color(v: &CssValue) -> Option<Color> {
     match v {
         CssValue::Color(c) => Some(c.clone()),
         CssValue::Keyword(k) => match k.to_ascii_lowercase().as_str() {
@@ -40,30 +50,63 @@ fn css_cells(v: &CssValue) -> Option<u16> {
     }
 }
 
-fn apply_text_style(style: &mut Style, v: &CssValue) {
+pub(crate) fn apply_text_style(style: &mut Style, v: &CssValue) {
     let CssValue::Keyword(k) = v else {
         return;
     };
-    // Allow future multi-token values by splitting on whitespace.
     for token in k.split_whitespace() {
         match token.to_ascii_lowercase().as_str() {
             "bold" => style.bold = true,
             "italic" => style.italic = true,
             "underline" => style.underline = true,
-            "strikethrough" | "strike" => style.strikethrough = true,
+            "single" | "solid" => set_underline(style, UnderlineStyle::Single),
+            "double" => set_underline(style, UnderlineStyle::Double),
+            "curly" | "wavy" | "undercurl" => set_underline(style, UnderlineStyle::Curly),
+            "dotted" => set_underline(style, UnderlineStyle::Dotted),
+            "dashed" => set_underline(style, UnderlineStyle::Dashed),
+            "strikethrough" | "strike" | "line-through" => style.strikethrough = true,
             "dim" => style.dim = true,
             "reverse" => style.reverse = true,
+            "blink" => style.blink = true,
+            "hidden" => style.hidden = true,
+            "overline" => style.overline = true,
             _ => {}
         }
     }
 }
 
-fn apply_opacity(style: &mut Style, v: &CssValue) {
-    let CssValue::Float(f) = v else {
-        return;
-    };
-    if *f < 0.999 {
-        style.dim = true;
+/// Turn a variable value into a style: a color sets the foreground, and
+/// keywords may mix color names with text styles (`red bold`).
+pub(crate) fn style_from_value(value: &CssValue, base: Style) -> Option<Style> {
+    match value {
+        CssValue::Color(_) => css_color(value).map(|c| base.fg(c)),
+        CssValue::Keyword(k) => {
+            let mut style = Style::default();
+            for token in k.split_whitespace() {
+                let word = CssValue::Keyword(token.to_string());
+                match css_color(&word) {
+                    Some(c) => style.fg = Some(c),
+                    None => apply_text_style(&mut style, &word),
+                }
+            }
+            Some(style)
+        }
+        CssValue::List(values) => values.iter().try_fold(Style::default(), |style, v| {
+            style_from_value(v, Style::default()).map(|s| style.merge(&s))
+        }),
+        _ => None,
+    }
+}
+
+/// Underline shape keywords imply `underline`.
+fn set_underline(style: &mut Style, shape: UnderlineStyle) {
+    style.underline = true;
+    style.underline_style = shape;
+}
+
+fn apply_text_decoration_color(style: &mut Style, v: &CssValue) {
+    if let Some(c) = css_color(v) {
+        style.underline_color = Some(c);
     }
 }
 
@@ -109,8 +152,8 @@ impl ApplyComputedStyle for Label {
         if let Some(v) = computed.get(&PropertyName::TextStyle) {
             apply_text_style(&mut style, v);
         }
-        if let Some(v) = computed.get(&PropertyName::Opacity) {
-            apply_opacity(&mut style, v);
+        if let Some(v) = computed.get(&PropertyName::TextDecorationColor) {
+            apply_text_decoration_color(&mut style, v);
         }
 
         self.set_style(style);
@@ -175,8 +218,8 @@ impl ApplyComputedStyle for RichLog {
         if let Some(v) = computed.get(&PropertyName::TextStyle) {
             apply_text_style(&mut style, v);
         }
-        if let Some(v) = computed.get(&PropertyName::Opacity) {
-            apply_opacity(&mut style, v);
+        if let Some(v) = computed.get(&PropertyName::TextDecorationColor) {
+            apply_text_decoration_color(&mut style, v);
         }
         self.set_base_style(style);
 
@@ -187,3 +230,98 @@ impl ApplyComputedStyle for RichLog {
         }
     }
 }
+
+/// Foreground, background and text style from a computed style, on top
+/// of `base`.
+fn apply_text_properties(base: &mut Style, computed: &ComputedStyle) {
+    if let Some(v) = computed.get(&PropertyName::Color)
+        && let Some(c) = css_color(v)
+    {
+        base.fg = Some(c);
+    }
+    if let Some(v) = computed.get(&PropertyName::Background)
+        && let Some(c) = css_color(v)
+    {
+        base.bg = Some(c);
+    }
+    if let Some(v) = computed.get(&PropertyName::TextStyle) {
+        apply_text_style(base, v);
+    }
+}
+
+impl ApplyComputedStyle for TextArea {
+    fn apply_computed_style(&mut self, computed: &ComputedStyle) {
+        apply_text_properties(&mut self.style, computed);
+    }
+
+    fn apply_variables(&mut self, vars: &VariableEnvironment) {
+        self.set_syntax_theme(&SyntaxTheme::from_variables(vars));
+    }
+}
+
+impl ApplyComputedStyle for DiffView {
+    fn apply_computed_style(&mut self, computed: &ComputedStyle) {
+        let mut style = Style::default();
+        apply_text_properties(&mut style, computed);
+        self.set_unchanged_style(style);
+
+        if let Some(v) = computed.get(&PropertyName::Border) {
+            let mut b = self.border_style_kind();
+            apply_border_style(&mut b, v);
+            self.set_border(b);
+        }
+    }
+
+    fn apply_variables(&mut self, vars: &VariableEnvironment) {
+        self.set_syntax_theme(&SyntaxTheme::from_variables(vars));
+    }
+}
+
+impl ApplyComputedStyle for CommandPalette {
+    fn apply_computed_style(&mut self, computed: &ComputedStyle) {
+        let mut style = Style::default();
+        apply_text_properties(&mut style, computed);
+        self.set_style(style);
+
+        if let Some(v) = computed.get(&PropertyName::Border) {
+            let mut b = self.border_style_kind();
+            apply_border_style(&mut b, v);
+            self.set_border(b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_style_keyword_list() {
+        let mut style = Style::default();
+        apply_text_style(
+            &mut style,
+            &CssValue::Keyword("bold underline curly overline blink".into()),
+        );
+        assert!(style.bold);
+        assert!(style.underline);
+        assert_eq!(style.underline_style, UnderlineStyle::Curly);
+        assert!(style.overline);
+        assert!(style.blink);
+        assert!(!style.hidden);
+    }
+
+    #[test]
+    fn underline_shape_implies_underline() {
+        let mut style = Style::default();
+        apply_text_style(&mut style, &CssValue::Keyword("dashed".into()));
+        assert!(style.underline);
+        assert_eq!(style.underline_style, UnderlineStyle::Dashed);
+    }
+
+    #[test]
+    fn text_decoration_color_sets_underline_color() {
+        let mut style = Style::default();
+        apply_text_decoration_color(&mut style, &CssValue::Keyword("red".into()));
+        assert_eq!(style.underline_color, Some(Color::Named(NamedColor::Red)));
+    }
+}
@@ -44,6 +44,11 @@ impl MatchCache {
         self.entries.insert(id, matches);
     }
 
+    /// Widgets marked dirty since they were last inserted.
+    pub fn dirty_ids(&self) -> impl Iterator<Item = WidgetId> + '_ {
+        self.dirty.iter().copied()
+    }
+
     /// Mark a single widget as dirty (needs re-matching).
     pub fn invalidate(&mut self, id: WidgetId) {
         self.dirty.insert(id);
@@ -67,6 +72,41 @@ impl MatchCache {
         }
     }
 
+    /// Mark everything whose selector matches may depend on `id` as dirty.
+    ///
+    /// Call this after `id`'s classes, state, attributes or children change.
+    /// Invalidates:
+    /// - `id` and its descendants (descendant/child combinators),
+    /// - later siblings and their descendants (`+`/`~` combinators and
+    ///   positional pseudo-classes such as `:nth-child`/`:last-of-type`),
+    /// - when `uses_has` is set, every ancestor of `id` and their
+    ///   siblings, with all their descendants: any of them may be a
+    ///   `:has()` subject, or be selected through one (`A:has(.x) B`,
+    ///   `A:has(.x) + B`, `A:has(~ B .x) C`).
+    pub fn invalidate_for_change(&mut self, tree: &WidgetTree, id: WidgetId, uses_has: bool) {
+        self.invalidate_subtree(tree, id);
+        for &sib in tree.following_siblings(id) {
+            self.invalidate_subtree(tree, sib);
+        }
+
+        if uses_has {
+            // The subtrees of the outermost ancestor and its siblings
+            // contain those of every inner ancestor and their siblings.
+            let mut top = id;
+            while let Some(parent) = tree.parent(top) {
+                top = parent.id;
+            }
+            self.invalidate_subtree(tree, top);
+            for &sib in tree
+                .preceding_siblings(top)
+                .iter()
+                .chain(tree.following_siblings(top))
+            {
+                self.invalidate_subtree(tree, sib);
+            }
+        }
+    }
+
     /// Check if a widget is dirty (needs re-matching).
     pub fn is_dirty(&self, id: WidgetId) -> bool {
         self.dirty.contains(&id)
@@ -192,6 +232,54 @@ mod tests {
         assert!(cache.is_dirty(3));
     }
 
+    fn sibling_tree() -> WidgetTree {
+        // 1 -> [2 -> [5], 3, 4]
+        let mut tree = WidgetTree::new();
+        tree.add_node(WidgetNode::new(1, "Root"));
+        for id in 2..=4 {
+            let mut child = WidgetNode::new(id, "Child");
+            child.parent = Some(1);
+            tree.add_node(child);
+        }
+        let mut grandchild = WidgetNode::new(5, "Leaf");
+        grandchild.parent = Some(2);
+        tree.add_node(grandchild);
+        tree
+    }
+
+    #[test]
+    fn invalidate_for_change_marks_following_siblings() {
+        let tree = sibling_tree();
+        let mut cache = MatchCache::new();
+        for id in 1..=5 {
+            cache.insert(id, sample_matches());
+        }
+
+        cache.invalidate_for_change(&tree, 3, false);
+        assert!(cache.is_dirty(3));
+        assert!(cache.is_dirty(4));
+        assert!(!cache.is_dirty(2));
+        assert!(!cache.is_dirty(1));
+    }
+
+    #[test]
+    fn invalidate_for_change_with_has_marks_ancestors() {
+        let tree = sibling_tree();
+        let mut cache = MatchCache::new();
+        for id in 1..=5 {
+            cache.insert(id, sample_matches());
+        }
+
+        cache.invalidate_for_change(&tree, 5, true);
+        assert!(cache.is_dirty(5));
+        assert!(cache.is_dirty(2));
+        assert!(cache.is_dirty(1));
+        // Siblings of an ancestor may be selected through it, as in
+        // `Child:has(Leaf) ~ Child`.
+        assert!(cache.is_dirty(3));
+        assert!(cache.is_dirty(4));
+    }
+
     #[test]
     fn is_dirty_check() {
         let mut cache = MatchCache::new();
@@ -15,7 +15,7 @@ use crate::tcss::variable::VariableEnvironment;
 ///
 /// After cascade resolution, this contains the winning value for each
 /// property from all matching rules.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct ComputedStyle {
     properties: HashMap<PropertyName, CssValue>,
 }
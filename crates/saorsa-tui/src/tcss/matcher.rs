@@ -2,13 +2,15 @@
 //!
 //! Matches selectors from parsed TCSS stylesheets against widget nodes
 //! in a [`WidgetTree`]. Supports simple, compound, and complex selectors
-//! with child and descendant combinators.
+//! with child, descendant and sibling combinators, attribute selectors,
+//! and the relational `:not()` / `:has()` pseudo-classes.
 
 use crate::focus::WidgetId;
 use crate::tcss::ast::Stylesheet;
 use crate::tcss::property::Declaration;
 use crate::tcss::selector::{
-    Combinator, CompoundSelector, PseudoClass, Selector, SelectorList, SimpleSelector,
+    Combinator, CompoundSelector, PseudoClass, RelativeSelector, Selector, SelectorList,
+    SimpleSelector,
 };
 use crate::tcss::tree::{WidgetNode, WidgetTree};
 
@@ -27,6 +29,8 @@ pub struct MatchedRule {
 pub struct StyleMatcher {
     /// (selector list, declarations, source order) for each rule.
     rules: Vec<(SelectorList, Vec<Declaration>, usize)>,
+    /// Whether any rule uses `:has()`, so changes can affect ancestors.
+    uses_has: bool,
 }
 
 impl StyleMatcher {
@@ -39,8 +43,18 @@ impl StyleMatcher {
             .iter()
             .enumerate()
             .map(|(i, rule)| (rule.selectors.clone(), rule.declarations.clone(), i))
-            .collect();
-        Self { rules }
+            .collect::<Vec<_>>();
+        let uses_has = rules.iter().any(|(list, _, _)| list_uses_has(list));
+        Self { rules, uses_has }
+    }
+
+    /// Whether any rule contains a `:has()` pseudo-class.
+    ///
+    /// When true, a change to a widget can alter the styles of its
+    /// ancestors and their earlier siblings; see
+    /// [`MatchCache::invalidate_for_change`](crate::tcss::MatchCache::invalidate_for_change).
+    pub fn uses_has(&self) -> bool {
+        self.uses_has
     }
 
     /// Return all rules that match a given widget in the tree.
@@ -85,6 +99,19 @@ impl StyleMatcher {
     }
 }
 
+fn list_uses_has(list: &SelectorList) -> bool {
+    list.selectors.iter().any(|sel| {
+        std::iter::once(&sel.head)
+            .chain(sel.chain.iter().map(|(_, c)| c))
+            .flat_map(|c| &c.components)
+            .any(|component| match component {
+                SimpleSelector::PseudoClass(PseudoClass::Has(_)) => true,
+                SimpleSelector::PseudoClass(PseudoClass::Not(inner)) => list_uses_has(inner),
+                _ => false,
+            })
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Core matching functions
 // ---------------------------------------------------------------------------
@@ -97,6 +124,7 @@ pub fn matches_simple(tree: &WidgetTree, node: &WidgetNode, selector: &SimpleSel
         SimpleSelector::Id(name) => node.css_id.as_deref() == Some(name.as_str()),
         SimpleSelector::Universal => true,
         SimpleSelector::PseudoClass(pc) => matches_pseudo_class(tree, node, pc),
+        SimpleSelector::Attribute(attr) => attr.matches_value(node.attribute(&attr.name)),
     }
 }
 
@@ -129,17 +157,59 @@ pub fn matches_pseudo_class(tree: &WidgetTree, node: &WidgetNode, pseudo: &Pseud
             let target_usize = target as usize;
             tree.child_index(node.id) == Some(target_usize)
         }
+        PseudoClass::NthChildFormula(a, b) => tree
+            .child_index(node.id)
+            .is_some_and(|i| matches_nth(*a, *b, i.saturating_add(1))),
+        PseudoClass::FirstOfType => {
+            tree.parent(node.id).is_some()
+                && !tree
+                    .preceding_siblings(node.id)
+                    .iter()
+                    .any(|&s| is_same_type(tree, s, node))
+        }
+        PseudoClass::LastOfType => {
+            tree.parent(node.id).is_some()
+                && !tree
+                    .following_siblings(node.id)
+                    .iter()
+                    .any(|&s| is_same_type(tree, s, node))
+        }
+        PseudoClass::Empty => node.children.is_empty(),
         PseudoClass::Even => tree.child_index(node.id).is_some_and(|i| (i + 1) % 2 == 0),
         PseudoClass::Odd => tree.child_index(node.id).is_some_and(|i| (i + 1) % 2 == 1),
         PseudoClass::Root => tree.root() == Some(node.id),
+        PseudoClass::State(name) => node.state.custom.iter().any(|s| s == name),
+        PseudoClass::Not(list) => !list
+            .selectors
+            .iter()
+            .any(|s| matches_selector(tree, node.id, s)),
+        PseudoClass::Has(relative) => relative.iter().any(|r| matches_relative(tree, node.id, r)),
+    }
+}
+
+/// Does the 1-based `index` satisfy `a*k + b` for some integer `k >= 0`?
+fn matches_nth(a: i32, b: i32, index: usize) -> bool {
+    let Ok(index) = i64::try_from(index) else {
+        return false;
+    };
+    let (a, b) = (i64::from(a), i64::from(b));
+    if a == 0 {
+        return index == b;
     }
+    let diff = index - b;
+    diff % a == 0 && diff / a >= 0
+}
+
+fn is_same_type(tree: &WidgetTree, id: WidgetId, node: &WidgetNode) -> bool {
+    tree.get(id).is_some_and(|n| n.type_name == node.type_name)
 }
 
 /// Check if a full selector (with combinators) matches a widget.
 ///
 /// Walks the selector chain from right (subject) to left, matching
 /// each compound selector against the tree using the appropriate
-/// combinator logic.
+/// combinator logic. Descendant and general-sibling steps backtrack,
+/// so `A B C` matches whenever *any* valid assignment of ancestors exists.
 pub fn matches_selector(tree: &WidgetTree, node_id: WidgetId, selector: &Selector) -> bool {
     let node = match tree.get(node_id) {
         Some(n) => n,
@@ -151,102 +221,84 @@ pub fn matches_selector(tree: &WidgetTree, node_id: WidgetId, selector: &Selecto
         return false;
     }
 
-    // No chain means simple match — done.
-    if selector.chain.is_empty() {
-        return true;
-    }
-
-    // Walk the chain from right to left (chain[0] is leftmost ancestor).
-    // We iterate from the end of the chain (closest to subject) backwards.
-    let mut current_id = node_id;
+    matches_chain(tree, node_id, &selector.chain, None)
+}
 
-    for (combinator, compound) in selector.chain.iter().rev() {
-        match combinator {
-            Combinator::Child => {
-                // Must match the immediate parent.
-                let parent = match tree.parent(current_id) {
-                    Some(p) => p,
-                    None => return false,
-                };
-                if !matches_compound(tree, parent, compound) {
-                    return false;
-                }
-                current_id = parent.id;
-            }
-            Combinator::Descendant => {
-                // Walk up all ancestors until one matches.
-                let ancestors = tree.ancestors(current_id);
-                let mut found = false;
-                for ancestor_id in &ancestors {
-                    let ancestor = match tree.get(*ancestor_id) {
-                        Some(a) => a,
-                        None => continue,
-                    };
-                    if matches_compound(tree, ancestor, compound) {
-                        current_id = ancestor.id;
-                        found = true;
-                        break;
-                    }
-                }
-                if !found {
-                    return false;
-                }
-            }
-            Combinator::AdjacentSibling => {
-                let parent = match tree.parent(current_id) {
-                    Some(p) => p,
-                    None => return false,
-                };
-                let idx = match parent.children.iter().position(|&c| c == current_id) {
-                    Some(i) => i,
-                    None => return false,
-                };
-                if idx == 0 {
-                    return false;
-                }
-                let sib_id = parent.children[idx - 1];
-                let sib = match tree.get(sib_id) {
-                    Some(s) => s,
-                    None => return false,
-                };
-                if !matches_compound(tree, sib, compound) {
-                    return false;
-                }
-                current_id = sib.id;
-            }
-            Combinator::GeneralSibling => {
-                let parent = match tree.parent(current_id) {
-                    Some(p) => p,
-                    None => return false,
-                };
-                let idx = match parent.children.iter().position(|&c| c == current_id) {
-                    Some(i) => i,
-                    None => return false,
-                };
-                if idx == 0 {
-                    return false;
-                }
-
-                let mut found = None;
-                for &sib_id in &parent.children[..idx] {
-                    let sib = match tree.get(sib_id) {
-                        Some(s) => s,
-                        None => continue,
-                    };
-                    if matches_compound(tree, sib, compound) {
-                        found = Some(sib.id);
-                    }
-                }
-
-                let Some(sib_id) = found else {
-                    return false;
-                };
-                current_id = sib_id;
+/// Check if a relative selector (from `:has()`) matches relative to `anchor_id`.
+///
+/// Some widget related to the anchor by the relative combinator must match
+/// the whole selector, with the selector's leftmost compound anchored there.
+pub fn matches_relative(
+    tree: &WidgetTree,
+    anchor_id: WidgetId,
+    relative: &RelativeSelector,
+) -> bool {
+    let candidates = match relative.combinator {
+        Combinator::Descendant | Combinator::Child => tree.descendants(anchor_id),
+        Combinator::AdjacentSibling | Combinator::GeneralSibling => {
+            let mut out = Vec::new();
+            for &sib in tree.following_siblings(anchor_id) {
+                out.push(sib);
+                out.extend(tree.descendants(sib));
             }
+            out
         }
-    }
+    };
+
+    candidates.into_iter().any(|id| {
+        tree.get(id)
+            .is_some_and(|n| matches_compound(tree, n, &relative.selector.head))
+            && matches_chain(
+                tree,
+                id,
+                &relative.selector.chain,
+                Some((anchor_id, &relative.combinator)),
+            )
+    })
+}
+
+/// Match the remaining `chain` leftwards from `current_id`.
+///
+/// The last chain entry is the one nearest the already-matched widget.
+/// When `anchor` is set (relative selectors), the leftmost matched widget
+/// must additionally relate to the anchor via the given combinator.
+fn matches_chain(
+    tree: &WidgetTree,
+    current_id: WidgetId,
+    chain: &[(Combinator, CompoundSelector)],
+    anchor: Option<(WidgetId, &Combinator)>,
+) -> bool {
+    let Some(((combinator, compound), rest)) = chain.split_last() else {
+        return match anchor {
+            Some((anchor_id, combinator)) => {
+                leftward_candidates(tree, current_id, combinator).contains(&anchor_id)
+            }
+            None => true,
+        };
+    };
+
+    leftward_candidates(tree, current_id, combinator)
+        .into_iter()
+        .any(|id| {
+            tree.get(id)
+                .is_some_and(|n| matches_compound(tree, n, compound))
+                && matches_chain(tree, id, rest, anchor)
+        })
+}
 
-    true
+/// Widgets that could stand on the left side of `combinator` when `id` is on the right.
+fn leftward_candidates(tree: &WidgetTree, id: WidgetId, combinator: &Combinator) -> Vec<WidgetId> {
+    match combinator {
+        Combinator::Child => tree.parent(id).map(|p| p.id).into_iter().collect(),
+        Combinator::Descendant => tree.ancestors(id),
+        Combinator::AdjacentSibling => tree
+            .preceding_siblings(id)
+            .last()
+            .copied()
+            .into_iter()
+            .collect(),
+        Combinator::GeneralSibling => tree.preceding_siblings(id).iter().rev().copied().collect(),
+    }
 }
 
 #[cfg(test)]
@@ -466,6 +518,21 @@ mod tests {
         assert!(matches_pseudo_class(&tree, node, &PseudoClass::Hover));
     }
 
+    #[test]
+    fn match_widget_state() {
+        let (mut tree, _) = make_tree_with_root("Player");
+        tree.get_mut(1)
+            .iter_mut()
+            .for_each(|n| n.state.custom = vec!["playing".into()]);
+        let node = match tree.get(1) {
+            Some(n) => n,
+            None => unreachable!(),
+        };
+        let state = |name: &str| PseudoClass::State(name.into());
+        assert!(matches_pseudo_class(&tree, node, &state("playing")));
+        assert!(!matches_pseudo_class(&tree, node, &state("paused")));
+    }
+
     #[test]
     fn match_disabled() {
         let (mut tree, _) = make_tree_with_root("Label");
@@ -978,4 +1045,177 @@ mod tests {
         // Should match Label, .error, and #main (not Container).
         assert_eq!(matched.len(), 3);
     }
+
+    // -----------------------------------------------------------------------
+    // Relational, attribute and structural selectors
+    // -----------------------------------------------------------------------
+
+    fn parse_sel(css: &str) -> Selector {
+        match SelectorList::parse(css) {
+            Ok(mut list) => list.selectors.remove(0),
+            Err(e) => unreachable!("parse failed for '{css}': {e:?}"),
+        }
+    }
+
+    #[test]
+    fn match_descendant_backtracks() {
+        // A(1) > B(2) > A(3) > C(4): `A > B C` needs the outer A.
+        let (mut tree, _) = make_tree_with_root("A");
+        add_child(&mut tree, 2, 1, "B");
+        add_child(&mut tree, 3, 2, "A");
+        add_child(&mut tree, 4, 3, "C");
+        assert!(matches_selector(&tree, 4, &parse_sel("A > B C")));
+    }
+
+    #[test]
+    fn match_not_pseudo_class() {
+        let (mut tree, _) = make_tree_with_root("Root");
+        add_child(&mut tree, 2, 1, "Label");
+        add_child(&mut tree, 3, 1, "Label");
+        if let Some(n) = tree.get_mut(3) {
+            n.classes.push("hidden".into());
+        }
+        let sel = parse_sel("Label:not(.hidden, #x)");
+        assert!(matches_selector(&tree, 2, &sel));
+        assert!(!matches_selector(&tree, 3, &sel));
+    }
+
+    #[test]
+    fn match_has_descendant_and_child() {
+        // Root(1) > Panel(2) > Box(3) > Label.error(4); Root > Panel(5)
+        let (mut tree, _) = make_tree_with_root("Root");
+        add_child(&mut tree, 2, 1, "Panel");
+        add_child(&mut tree, 3, 2, "Box");
+        add_child(&mut tree, 4, 3, "Label");
+        add_child(&mut tree, 5, 1, "Panel");
+        if let Some(n) = tree.get_mut(4) {
+            n.classes.push("error".into());
+        }
+
+        let deep = parse_sel("Panel:has(.error)");
+        assert!(matches_selector(&tree, 2, &deep));
+        assert!(!matches_selector(&tree, 5, &deep));
+
+        let child = parse_sel("Panel:has(> .error)");
+        assert!(!matches_selector(&tree, 2, &child));
+        assert!(matches_selector(
+            &tree,
+            2,
+            &parse_sel("Panel:has(> Box > .error)")
+        ));
+    }
+
+    #[test]
+    fn match_has_sibling() {
+        // Root(1): Label(2), Input(3), Label(4)
+        let (mut tree, _) = make_tree_with_root("Root");
+        add_child(&mut tree, 2, 1, "Label");
+        add_child(&mut tree, 3, 1, "Input");
+        add_child(&mut tree, 4, 1, "Label");
+
+        let adjacent = parse_sel("Label:has(+ Input)");
+        assert!(matches_selector(&tree, 2, &adjacent));
+        assert!(!matches_selector(&tree, 4, &adjacent));
+
+        let general = parse_sel("Label:has(~ Label)");
+        assert!(matches_selector(&tree, 2, &general));
+        assert!(!matches_selector(&tree, 4, &general));
+    }
+
+    #[test]
+    fn match_nth_child_formula() {
+        let (mut tree, _) = make_tree_with_root("Root");
+        for id in 2..=8 {
+            add_child(&mut tree, id, 1, "Item");
+        }
+        // 3n+1 → positions 1, 4, 7 → ids 2, 5, 8.
+        let sel = parse_sel("Item:nth-child(3n+1)");
+        let matched: Vec<WidgetId> = (2..=8)
+            .filter(|&id| matches_selector(&tree, id, &sel))
+            .collect();
+        assert_eq!(matched, vec![2, 5, 8]);
+
+        // -n+2 → first two.
+        let sel = parse_sel("Item:nth-child(-n+2)");
+        let matched: Vec<WidgetId> = (2..=8)
+            .filter(|&id| matches_selector(&tree, id, &sel))
+            .collect();
+        assert_eq!(matched, vec![2, 3]);
+    }
+
+    #[test]
+    fn match_of_type_and_empty() {
+        // Root(1): Label(2), Input(3), Label(4) > Span(5)
+        let (mut tree, _) = make_tree_with_root("Root");
+        add_child(&mut tree, 2, 1, "Label");
+        add_child(&mut tree, 3, 1, "Input");
+        add_child(&mut tree, 4, 1, "Label");
+        add_child(&mut tree, 5, 4, "Span");
+
+        let last = parse_sel("Label:last-of-type");
+        assert!(!matches_selector(&tree, 2, &last));
+        assert!(matches_selector(&tree, 4, &last));
+        assert!(matches_selector(&tree, 3, &parse_sel("Input:last-of-type")));
+        assert!(matches_selector(
+            &tree,
+            2,
+            &parse_sel("Label:first-of-type")
+        ));
+
+        let empty = parse_sel(":empty");
+        assert!(matches_selector(&tree, 2, &empty));
+        assert!(!matches_selector(&tree, 4, &empty));
+    }
+
+    #[test]
+    fn match_attribute_selectors() {
+        let mut tree = WidgetTree::new();
+        tree.add_node(
+            WidgetNode::new(1, "Label")
+                .with_attribute("kind", "error-fatal")
+                .with_attribute("tags", "warn diag"),
+        );
+
+        assert!(matches_selector(&tree, 1, &parse_sel("[kind]")));
+        assert!(!matches_selector(&tree, 1, &parse_sel("[missing]")));
+        assert!(matches_selector(
+            &tree,
+            1,
+            &parse_sel("Label[kind=\"error-fatal\"]")
+        ));
+        assert!(matches_selector(&tree, 1, &parse_sel("[kind|=error]")));
+        assert!(matches_selector(&tree, 1, &parse_sel("[kind^=err]")));
+        assert!(matches_selector(&tree, 1, &parse_sel("[kind$=fatal]")));
+        assert!(matches_selector(&tree, 1, &parse_sel("[kind*=or-fa]")));
+        assert!(matches_selector(&tree, 1, &parse_sel("[tags~=diag]")));
+        assert!(!matches_selector(&tree, 1, &parse_sel("[tags~=dia]")));
+        assert!(matches_selector(
+            &tree,
+            1,
+            &parse_sel("[kind=\"ERROR-FATAL\" i]")
+        ));
+        assert!(!matches_selector(
+            &tree,
+            1,
+            &parse_sel("[kind=\"ERROR-FATAL\"]")
+        ));
+    }
+
+    #[test]
+    fn matcher_reports_has_usage() {
+        let mut sheet = Stylesheet::new();
+        sheet.add_rule(make_rule(
+            "Label",
+            PropertyName::Color,
+            CssValue::Keyword("red".into()),
+        ));
+        assert!(!StyleMatcher::new(&sheet).uses_has());
+
+        sheet.add_rule(make_rule(
+            "Panel:not(:has(.error))",
+            PropertyName::Color,
+            CssValue::Keyword("red".into()),
+        ));
+        assert!(StyleMatcher::new(&sheet).uses_has());
+    }
 }
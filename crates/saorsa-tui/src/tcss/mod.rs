@@ -26,13 +26,16 @@ pub use cache::MatchCache;
 pub use cascade::{CascadeResolver, ComputedStyle};
 pub use error::TcssError;
 pub use matcher::{MatchedRule, StyleMatcher};
-pub use parser::{extract_root_variables, parse_declaration, parse_stylesheet};
+pub use parser::{
+    extract_root_variables, parse_declaration, parse_stylesheet, parse_stylesheet_with_states,
+};
 pub use property::{Declaration, PropertyName};
 pub use reload::{StylesheetEvent, StylesheetLoader};
 pub use selector::{
-    Combinator, CompoundSelector, PseudoClass, Selector, SelectorList, SimpleSelector,
+    AttributeOperator, AttributeSelector, Combinator, CompoundSelector, PseudoClass,
+    RelativeSelector, Selector, SelectorList, SimpleSelector, WIDGET_STATES,
 };
-pub use theme::{Theme, ThemeManager};
+pub use theme::{Theme, ThemeManager, builtin_for_variant, builtin_theme, theme_variant};
 pub use themes::{
     ThemeColors, ThemeRegistry, ThemeVariant, catppuccin_frappe, catppuccin_latte,
     catppuccin_macchiato, catppuccin_mocha,
@@ -126,6 +126,15 @@ pub fn parse_keyword(input: &mut Parser<'_, '_>) -> Result<String, TcssError> {
         .map_err(|e| TcssError::Parse(format!("{e:?}")))
 }
 
+/// Parse one or more whitespace-separated keywords, joined by single spaces.
+pub fn parse_keyword_list(input: &mut Parser<'_, '_>) -> Result<String, TcssError> {
+    let mut keywords = vec![parse_keyword(input)?];
+    while let Ok(k) = input.try_parse(|p| p.expect_ident().map(|s| s.to_string())) {
+        keywords.push(k);
+    }
+    Ok(keywords.join(" "))
+}
+
 /// Try to parse a variable reference ($name).
 ///
 /// Returns `Some(CssValue::Variable(name))` if the next tokens are `$ident`,
@@ -242,10 +251,23 @@ pub fn parse_property_value(
 
     match property {
         // Color properties
-        PropertyName::Color | PropertyName::Background | PropertyName::BorderColor => {
-            parse_color(input).map(CssValue::Color)
+        // `background: transparent` lets content beneath show through.
+        PropertyName::Background => {
+            match input.try_parse(|p| p.expect_ident_matching("transparent")) {
+                Ok(()) => Ok(CssValue::Keyword("transparent".into())),
+                Err(_) => parse_color(input).map(CssValue::Color),
+            }
         }
 
+        PropertyName::Color
+        | PropertyName::BorderColor
+        | PropertyName::TextDecorationColor
+        | PropertyName::ScrollbarColor
+        | PropertyName::ScrollbarBackground => parse_color(input).map(CssValue::Color),
+
+        // Text style accepts a list of keywords, e.g. `underline curly`.
+        PropertyName::TextStyle => parse_keyword_list(input).map(CssValue::Keyword),
+
         // Dimension properties
         PropertyName::Width
         | PropertyName::Height
@@ -269,7 +291,7 @@ pub fn parse_property_value(
         | PropertyName::PaddingLeft => parse_length(input).map(CssValue::Length),
 
         // Numeric properties
-        PropertyName::FlexGrow | PropertyName::FlexShrink => {
+        PropertyName::FlexGrow | PropertyName::FlexShrink | PropertyName::ScrollbarSize => {
             parse_integer(input).map(CssValue::Integer)
         }
 
@@ -298,7 +320,6 @@ pub fn parse_property_value(
         | PropertyName::Visibility
         | PropertyName::TextAlign
         | PropertyName::ContentAlign
-        | PropertyName::TextStyle
         | PropertyName::Border
         | PropertyName::BorderTop
         | PropertyName::BorderRight
@@ -309,12 +330,20 @@ pub fn parse_property_value(
 
 /// Parse a complete TCSS stylesheet from a string.
 pub fn parse_stylesheet(input: &str) -> Result<Stylesheet, TcssError> {
+    parse_stylesheet_with_states(input, &[])
+}
+
+/// Parse a complete TCSS stylesheet from a string, accepting the
+/// widget-defined `states` as pseudo-classes besides
+/// [`WIDGET_STATES`](crate::tcss::WIDGET_STATES). Rules using other
+/// unknown pseudo-classes are dropped.
+pub fn parse_stylesheet_with_states(input: &str, states: &[&str]) -> Result<Stylesheet, TcssError> {
     let mut parser_input = ParserInput::new(input);
     let mut parser = Parser::new(&mut parser_input);
     let mut stylesheet = Stylesheet::new();
 
     while !parser.is_exhausted() {
-        match parse_rule(&mut parser) {
+        match parse_rule(&mut parser, states) {
             Ok(rule) => stylesheet.add_rule(rule),
             Err(_) => {
                 // Error recovery: skip to next block or end.
@@ -334,9 +363,9 @@ enum BlockItem {
 }
 
 /// Parse a single CSS rule: `selectors { declarations and/or variable definitions }`.
-fn parse_rule(input: &mut Parser<'_, '_>) -> Result<Rule, TcssError> {
+fn parse_rule(input: &mut Parser<'_, '_>, states: &[&str]) -> Result<Rule, TcssError> {
     // Parse selector list (everything before `{`).
-    let selectors = SelectorList::parse_from(input)?;
+    let selectors = SelectorList::parse_from_with_states(input, states)?;
 
     // Consume the `{` token to open the block.
     input
@@ -663,6 +692,36 @@ mod tests {
         assert_eq!(result, Ok("flex".into()));
     }
 
+    #[test]
+    fn parse_keyword_list_value() {
+        let result = parse_with("bold  underline curly", parse_keyword_list);
+        assert_eq!(result, Ok("bold underline curly".into()));
+    }
+
+    #[test]
+    fn parse_text_style_multiple_keywords() {
+        let result = parse_declaration("text-decoration: underline dotted !important;");
+        match result {
+            Ok(decl) => {
+                assert_eq!(decl.property, PropertyName::TextStyle);
+                assert_eq!(decl.value, CssValue::Keyword("underline dotted".into()));
+                assert!(decl.important);
+            }
+            Err(_) => unreachable!("declaration should parse"),
+        }
+    }
+
+    #[test]
+    fn parse_text_decoration_color() {
+        let result = parse_with("#ff0000", |p| {
+            parse_property_value(&PropertyName::TextDecorationColor, p)
+        });
+        assert_eq!(
+            result,
+            Ok(CssValue::Color(Color::Rgb { r: 255, g: 0, b: 0 }))
+        );
+    }
+
     #[test]
     fn parse_property_color() {
         let result = parse_with("red", |p| parse_property_value(&PropertyName::Color, p));
@@ -687,12 +746,39 @@ mod tests {
         assert_eq!(result, Ok(CssValue::Integer(2)));
     }
 
+    #[test]
+    fn parse_background_transparent() {
+        let result = parse_with("transparent", |p| {
+            parse_property_value(&PropertyName::Background, p)
+        });
+        assert_eq!(result, Ok(CssValue::Keyword("transparent".into())));
+        let result = parse_with("red", |p| {
+            parse_property_value(&PropertyName::Background, p)
+        });
+        assert!(matches!(result, Ok(CssValue::Color(_))));
+    }
+
     #[test]
     fn parse_property_opacity() {
         let result = parse_with("0.8", |p| parse_property_value(&PropertyName::Opacity, p));
         assert_eq!(result, Ok(CssValue::Float(0.8)));
     }
 
+    #[test]
+    fn parse_property_scrollbar() {
+        let size = parse_with("2", |p| {
+            parse_property_value(&PropertyName::ScrollbarSize, p)
+        });
+        assert_eq!(size, Ok(CssValue::Integer(2)));
+        let color = parse_with("#00ff00", |p| {
+            parse_property_value(&PropertyName::ScrollbarColor, p)
+        });
+        assert_eq!(
+            color,
+            Ok(CssValue::Color(Color::Rgb { r: 0, g: 255, b: 0 }))
+        );
+    }
+
     #[test]
     fn parse_property_background_hex() {
         let result = parse_with("#ff0000", |p| {
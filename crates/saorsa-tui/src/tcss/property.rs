@@ -14,8 +14,12 @@ pub enum PropertyName {
     BorderColor,
 
     // --- Text decoration ---
-    /// Text style (bold, italic, dim, underline, strikethrough, reverse).
+    /// Text style: a space-separated list of bold, italic, dim, underline
+    /// (optionally with a double/curly/dotted/dashed shape), strikethrough,
+    /// reverse, blink, hidden and overline.
     TextStyle,
+    /// Underline color, independent of the text color.
+    TextDecorationColor,
 
     // --- Dimensions ---
     /// Widget width.
@@ -109,10 +113,18 @@ pub enum PropertyName {
     /// Vertical overflow behavior.
     OverflowY,
 
+    // --- Scrollbars ---
+    /// Scrollbar thumb color.
+    ScrollbarColor,
+    /// Scrollbar track color.
+    ScrollbarBackground,
+    /// Scrollbar thickness in cells (0 hides scrollbars).
+    ScrollbarSize,
+
     // --- Visibility ---
     /// Visibility (visible, hidden).
     Visibility,
-    /// Opacity (0.0 to 1.0, maps to dim).
+    /// Opacity (0.0 to 1.0), blended over what is beneath the widget.
     Opacity,
 
     // --- Content alignment ---
@@ -133,6 +145,7 @@ impl PropertyName {
             "background" | "background-color" => Some(Self::Background),
             "border-color" => Some(Self::BorderColor),
             "text-style" | "text-decoration" => Some(Self::TextStyle),
+            "text-decoration-color" | "underline-color" => Some(Self::TextDecorationColor),
             "width" => Some(Self::Width),
             "height" => Some(Self::Height),
             "min-width" => Some(Self::MinWidth),
@@ -172,6 +185,9 @@ impl PropertyName {
             "overflow" => Some(Self::Overflow),
             "overflow-x" => Some(Self::OverflowX),
             "overflow-y" => Some(Self::OverflowY),
+            "scrollbar-color" => Some(Self::ScrollbarColor),
+            "scrollbar-background" => Some(Self::ScrollbarBackground),
+            "scrollbar-size" => Some(Self::ScrollbarSize),
             "visibility" => Some(Self::Visibility),
             "opacity" => Some(Self::Opacity),
             "text-align" => Some(Self::TextAlign),
@@ -187,6 +203,7 @@ impl PropertyName {
             Self::Background => "background",
             Self::BorderColor => "border-color",
             Self::TextStyle => "text-style",
+            Self::TextDecorationColor => "text-decoration-color",
             Self::Width => "width",
             Self::Height => "height",
             Self::MinWidth => "min-width",
@@ -226,6 +243,9 @@ impl PropertyName {
             Self::Overflow => "overflow",
             Self::OverflowX => "overflow-x",
             Self::OverflowY => "overflow-y",
+            Self::ScrollbarColor => "scrollbar-color",
+            Self::ScrollbarBackground => "scrollbar-background",
+            Self::ScrollbarSize => "scrollbar-size",
             Self::Visibility => "visibility",
             Self::Opacity => "opacity",
             Self::TextAlign => "text-align",
@@ -287,6 +307,14 @@ mod tests {
             PropertyName::from_css("border-color"),
             Some(PropertyName::BorderColor)
         );
+        assert_eq!(
+            PropertyName::from_css("text-decoration-color"),
+            Some(PropertyName::TextDecorationColor)
+        );
+        assert_eq!(
+            PropertyName::from_css("underline-color"),
+            Some(PropertyName::TextDecorationColor)
+        );
     }
 
     #[test]
@@ -371,6 +399,9 @@ mod tests {
             PropertyName::GridTemplateColumns,
             PropertyName::Dock,
             PropertyName::Opacity,
+            PropertyName::ScrollbarColor,
+            PropertyName::ScrollbarBackground,
+            PropertyName::ScrollbarSize,
         ];
         for prop in &properties {
             let name = prop.css_name();
@@ -10,7 +10,7 @@ use notify::{Event, EventKind, RecommendedWatcher, Watcher};
 
 use crate::tcss::ast::Stylesheet;
 use crate::tcss::error::TcssError;
-use crate::tcss::parser::parse_stylesheet;
+use crate::tcss::parser::{parse_stylesheet, parse_stylesheet_with_states};
 use crate::tcss::theme::{Theme, extract_themes};
 use crate::tcss::variable::VariableMap;
 
@@ -27,6 +27,8 @@ pub struct StylesheetLoader {
     path: Option<PathBuf>,
     /// Generation counter (incremented on each reload).
     generation: u64,
+    /// Widget-defined pseudo-classes the stylesheet may use.
+    widget_states: Vec<&'static str>,
 }
 
 /// Events emitted by the stylesheet loader.
@@ -50,12 +52,19 @@ impl StylesheetLoader {
             themes: Vec::new(),
             path: None,
             generation: 0,
+            widget_states: Vec::new(),
         }
     }
 
     /// Load and parse a stylesheet from a CSS string.
     pub fn load_string(css: &str) -> Result<Self, TcssError> {
-        let stylesheet = parse_stylesheet(css)?;
+        Self::load_string_with_states(css, &[])
+    }
+
+    /// Load and parse a stylesheet from a CSS string that may select the
+    /// widget-defined `states` (see [`parse_stylesheet_with_states`]).
+    pub fn load_string_with_states(css: &str, states: &[&'static str]) -> Result<Self, TcssError> {
+        let stylesheet = parse_stylesheet_with_states(css, states)?;
         let (globals, themes) = extract_themes(&stylesheet);
         Ok(Self {
             stylesheet,
@@ -63,13 +72,20 @@ impl StylesheetLoader {
             themes,
             path: None,
             generation: 1,
+            widget_states: states.to_vec(),
         })
     }
 
     /// Load and parse a stylesheet from a file.
     pub fn load_file(path: &Path) -> Result<Self, TcssError> {
+        Self::load_file_with_states(path, &[])
+    }
+
+    /// Load and parse a stylesheet from a file that may select the
+    /// widget-defined `states` (see [`parse_stylesheet_with_states`]).
+    pub fn load_file_with_states(path: &Path, states: &[&'static str]) -> Result<Self, TcssError> {
         let css = std::fs::read_to_string(path).map_err(|e| TcssError::Parse(e.to_string()))?;
-        let stylesheet = parse_stylesheet(&css)?;
+        let stylesheet = parse_stylesheet_with_states(&css, states)?;
         let (globals, themes) = extract_themes(&stylesheet);
         Ok(Self {
             stylesheet,
@@ -77,9 +93,20 @@ impl StylesheetLoader {
             themes,
             path: Some(path.to_path_buf()),
             generation: 1,
+            widget_states: states.to_vec(),
         })
     }
 
+    /// Set the widget-defined pseudo-classes later reloads accept.
+    pub fn set_widget_states(&mut self, states: Vec<&'static str>) {
+        self.widget_states = states;
+    }
+
+    /// The widget-defined pseudo-classes the stylesheet may use.
+    pub fn widget_states(&self) -> &[&'static str] {
+        &self.widget_states
+    }
+
     /// Reload the stylesheet from the associated file.
     ///
     /// Returns an error if no file path is set or if parsing fails.
@@ -91,7 +118,7 @@ impl StylesheetLoader {
             .clone();
 
         let css = std::fs::read_to_string(&path).map_err(|e| TcssError::Parse(e.to_string()))?;
-        let stylesheet = parse_stylesheet(&css)?;
+        let stylesheet = parse_stylesheet_with_states(&css, &self.widget_states)?;
         let (globals, themes) = extract_themes(&stylesheet);
 
         self.stylesheet = stylesheet;
@@ -106,7 +133,7 @@ impl StylesheetLoader {
 
     /// Reload from a new CSS string (useful for testing or in-memory updates).
     pub fn reload_string(&mut self, css: &str) -> Result<StylesheetEvent, TcssError> {
-        let stylesheet = parse_stylesheet(css)?;
+        let stylesheet = parse_stylesheet_with_states(css, &self.widget_states)?;
         let (globals, themes) = extract_themes(&stylesheet);
 
         self.stylesheet = stylesheet;
@@ -21,8 +21,159 @@ pub enum SimpleSelector {
     Universal,
     /// Pseudo-class: `:focus`, `:hover`.
     PseudoClass(PseudoClass),
+    /// Attribute selector: `[name]`, `[kind="error"]`.
+    Attribute(AttributeSelector),
 }
 
+/// The comparison performed by an attribute selector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeOperator {
+    /// `[attr]` — the attribute is present.
+    Exists,
+    /// `[attr=value]` — exact match.
+    Equals,
+    /// `[attr~=value]` — whitespace-separated list contains `value`.
+    Includes,
+    /// `[attr|=value]` — equals `value` or starts with `value-`.
+    DashMatch,
+    /// `[attr^=value]` — starts with `value`.
+    Prefix,
+    /// `[attr$=value]` — ends with `value`.
+    Suffix,
+    /// `[attr*=value]` — contains `value`.
+    Substring,
+}
+
+impl AttributeOperator {
+    /// The CSS source form of this operator (empty for [`Self::Exists`]).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Exists => "",
+            Self::Equals => "=",
+            Self::Includes => "~=",
+            Self::DashMatch => "|=",
+            Self::Prefix => "^=",
+            Self::Suffix => "$=",
+            Self::Substring => "*=",
+        }
+    }
+}
+
+/// An attribute selector: `[name op "value" i]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttributeSelector {
+    /// Attribute name.
+    pub name: String,
+    /// Comparison operator.
+    pub operator: AttributeOperator,
+    /// Value to compare against (empty for [`AttributeOperator::Exists`]).
+    pub value: String,
+    /// Whether the comparison ignores ASCII case (the `i` flag).
+    pub case_insensitive: bool,
+}
+
+impl AttributeSelector {
+    /// Create a presence selector: `[name]`.
+    pub fn exists(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            operator: AttributeOperator::Exists,
+            value: String::new(),
+            case_insensitive: false,
+        }
+    }
+
+    /// Create a selector comparing the attribute with `value`.
+    pub fn new(
+        name: impl Into<String>,
+        operator: AttributeOperator,
+        value: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            operator,
+            value: value.into(),
+            case_insensitive: false,
+        }
+    }
+
+    /// Check whether an attribute value (or `None` if absent) satisfies this selector.
+    pub fn matches_value(&self, actual: Option<&str>) -> bool {
+        let Some(actual) = actual else {
+            return false;
+        };
+        let (actual, expected) = if self.case_insensitive {
+            (actual.to_ascii_lowercase(), self.value.to_ascii_lowercase())
+        } else {
+            (actual.to_string(), self.value.clone())
+        };
+        match self.operator {
+            AttributeOperator::Exists => true,
+            AttributeOperator::Equals => actual == expected,
+            AttributeOperator::Includes => {
+                !expected.is_empty() && actual.split_whitespace().any(|w| w == expected)
+            }
+            AttributeOperator::DashMatch => {
+                actual == expected
+                    || actual
+                        .strip_prefix(expected.as_str())
+                        .is_some_and(|rest| rest.starts_with('-'))
+            }
+            AttributeOperator::Prefix => !expected.is_empty() && actual.starts_with(&expected),
+            AttributeOperator::Suffix => !expected.is_empty() && actual.ends_with(&expected),
+            AttributeOperator::Substring => !expected.is_empty() && actual.contains(&expected),
+        }
+    }
+}
+
+impl fmt::Display for AttributeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}", self.name)?;
+        if self.operator != AttributeOperator::Exists {
+            write!(f, "{}\"{}\"", self.operator.as_str(), self.value)?;
+            if self.case_insensitive {
+                write!(f, " i")?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+/// A selector relative to an anchor element, as used inside `:has()`.
+///
+/// For example, in `Container:has(> Label.error)` the relative selector
+/// is `> Label.error`: its leftmost compound must be a child of the
+/// element being tested.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelativeSelector {
+    /// How the leftmost compound relates to the anchor element.
+    pub combinator: Combinator,
+    /// The selector to match, read left to right from the anchor.
+    pub selector: Selector,
+}
+
+impl fmt::Display for RelativeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.combinator {
+            Combinator::Descendant => write!(f, "{}", self.selector),
+            Combinator::Child => write!(f, "> {}", self.selector),
+            Combinator::AdjacentSibling => write!(f, "+ {}", self.selector),
+            Combinator::GeneralSibling => write!(f, "~ {}", self.selector),
+        }
+    }
+}
+
+/// Pseudo-classes that widgets set on themselves to expose internal
+/// state, such as the editing mode of a text widget
+/// (`TextArea:vim-insert`).
+pub const WIDGET_STATES: &[&str] = &[
+    "vim-normal",
+    "vim-insert",
+    "vim-visual",
+    "vim-visual-line",
+    "emacs",
+];
+
 /// Supported pseudo-classes.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PseudoClass {
@@ -40,17 +191,39 @@ pub enum PseudoClass {
     LastChild,
     /// `:nth-child(n)` — nth child of parent.
     NthChild(i32),
+    /// `:nth-child(an+b)` — child whose 1-based index is `a*k + b` for some `k >= 0`.
+    NthChildFormula(i32, i32),
+    /// `:first-of-type` — first sibling with the same type name.
+    FirstOfType,
+    /// `:last-of-type` — last sibling with the same type name.
+    LastOfType,
+    /// `:empty` — widget has no children.
+    Empty,
     /// `:even` — even-positioned child.
     Even,
     /// `:odd` — odd-positioned child.
     Odd,
     /// `:root` — matches the root element (used for variable/theme definitions).
     Root,
+    /// `:not(selectors)` — widget matches none of the selectors.
+    Not(SelectorList),
+    /// `:has(relative selectors)` — some related widget matches one of the selectors.
+    Has(Vec<RelativeSelector>),
+    /// A widget-defined state from [`WIDGET_STATES`] or the states given
+    /// to the parser, e.g. `:vim-normal`.
+    State(String),
 }
 
 impl PseudoClass {
     /// Parse a pseudo-class from its name.
     pub fn from_name(name: &str) -> Option<Self> {
+        Self::from_name_with_states(name, &[])
+    }
+
+    /// Parse a pseudo-class from its name, also accepting the
+    /// widget-defined `states` besides [`WIDGET_STATES`]. Names are
+    /// case-insensitive.
+    pub fn from_name_with_states(name: &str, states: &[&str]) -> Option<Self> {
         match name.to_ascii_lowercase().as_str() {
             "focus" => Some(Self::Focus),
             "hover" => Some(Self::Hover),
@@ -61,9 +234,45 @@ impl PseudoClass {
             "even" => Some(Self::Even),
             "odd" => Some(Self::Odd),
             "root" => Some(Self::Root),
+            "first-of-type" => Some(Self::FirstOfType),
+            "last-of-type" => Some(Self::LastOfType),
+            "empty" => Some(Self::Empty),
+            state
+                if WIDGET_STATES.contains(&state)
+                    || states.iter().any(|s| s.eq_ignore_ascii_case(state)) =>
+            {
+                Some(Self::State(state.to_string()))
+            }
             _ => None,
         }
     }
+
+    /// Build an `:nth-child(an+b)` pseudo-class, normalising to the
+    /// simplest equivalent variant.
+    pub fn nth_child(a: i32, b: i32) -> Self {
+        match (a, b) {
+            (0, b) => Self::NthChild(b),
+            (2, 0) => Self::Even,
+            (2, 1) => Self::Odd,
+            (a, b) => Self::NthChildFormula(a, b),
+        }
+    }
+
+    /// Specificity contributed by this pseudo-class.
+    ///
+    /// Plain pseudo-classes count as a class. `:not()` and `:has()` take
+    /// the specificity of their most specific argument instead.
+    pub fn specificity(&self) -> (u16, u16, u16) {
+        match self {
+            Self::Not(list) => list.max_specificity(),
+            Self::Has(relative) => relative
+                .iter()
+                .map(|r| r.selector.specificity())
+                .max()
+                .unwrap_or((0, 0, 0)),
+            _ => (0, 1, 0),
+        }
+    }
 }
 
 impl fmt::Display for PseudoClass {
@@ -76,9 +285,29 @@ impl fmt::Display for PseudoClass {
             Self::FirstChild => write!(f, ":first-child"),
             Self::LastChild => write!(f, ":last-child"),
             Self::NthChild(n) => write!(f, ":nth-child({n})"),
+            Self::NthChildFormula(a, b) => match b {
+                0 => write!(f, ":nth-child({a}n)"),
+                b if *b < 0 => write!(f, ":nth-child({a}n{b})"),
+                b => write!(f, ":nth-child({a}n+{b})"),
+            },
+            Self::FirstOfType => write!(f, ":first-of-type"),
+            Self::LastOfType => write!(f, ":last-of-type"),
+            Self::Empty => write!(f, ":empty"),
             Self::Even => write!(f, ":even"),
             Self::Odd => write!(f, ":odd"),
             Self::Root => write!(f, ":root"),
+            Self::State(name) => write!(f, ":{name}"),
+            Self::Not(list) => write!(f, ":not({list})"),
+            Self::Has(relative) => {
+                write!(f, ":has(")?;
+                for (i, r) in relative.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{r}")?;
+                }
+                write!(f, ")")
+            }
         }
     }
 }
@@ -108,9 +337,10 @@ impl CompoundSelector {
     /// Calculate CSS specificity as (id_count, class_count, type_count).
     ///
     /// - ID selectors contribute to the first component.
-    /// - Class selectors and pseudo-classes contribute to the second.
+    /// - Class, attribute and pseudo-class selectors contribute to the second.
     /// - Type selectors contribute to the third.
     /// - Universal selector contributes nothing.
+    /// - `:not()` and `:has()` contribute their most specific argument.
     pub fn specificity(&self) -> (u16, u16, u16) {
         let mut ids: u16 = 0;
         let mut classes: u16 = 0;
@@ -119,9 +349,15 @@ impl CompoundSelector {
         for component in &self.components {
             match component {
                 SimpleSelector::Id(_) => ids = ids.saturating_add(1),
-                SimpleSelector::Class(_) | SimpleSelector::PseudoClass(_) => {
+                SimpleSelector::Class(_) | SimpleSelector::Attribute(_) => {
                     classes = classes.saturating_add(1);
                 }
+                SimpleSelector::PseudoClass(pc) => {
+                    let (i, c, t) = pc.specificity();
+                    ids = ids.saturating_add(i);
+                    classes = classes.saturating_add(c);
+                    types = types.saturating_add(t);
+                }
                 SimpleSelector::Type(_) => types = types.saturating_add(1),
                 SimpleSelector::Universal => {}
             }
@@ -140,6 +376,7 @@ impl fmt::Display for CompoundSelector {
                 SimpleSelector::Id(name) => write!(f, "#{name}")?,
                 SimpleSelector::Universal => write!(f, "*")?,
                 SimpleSelector::PseudoClass(pc) => write!(f, "{pc}")?,
+                SimpleSelector::Attribute(attr) => write!(f, "{attr}")?,
             }
         }
         Ok(())
@@ -237,17 +474,33 @@ impl SelectorList {
 
     /// Parse a comma-separated selector list from a CSS string.
     pub fn parse(input: &str) -> Result<Self, TcssError> {
+        Self::parse_with_states(input, &[])
+    }
+
+    /// Parse a comma-separated selector list from a CSS string, accepting
+    /// the widget-defined `states` as pseudo-classes besides
+    /// [`WIDGET_STATES`].
+    pub fn parse_with_states(input: &str, states: &[&str]) -> Result<Self, TcssError> {
         let mut parser_input = ParserInput::new(input);
         let mut parser = Parser::new(&mut parser_input);
-        Self::parse_from(&mut parser)
+        Self::parse_from_with_states(&mut parser, states)
     }
 
     /// Parse a selector list from a cssparser `Parser`.
     pub fn parse_from(input: &mut Parser<'_, '_>) -> Result<Self, TcssError> {
-        let mut selectors = vec![parse_selector(input)?];
+        Self::parse_from_with_states(input, &[])
+    }
+
+    /// Parse a selector list from a cssparser `Parser`, accepting the
+    /// widget-defined `states` as pseudo-classes besides [`WIDGET_STATES`].
+    pub fn parse_from_with_states(
+        input: &mut Parser<'_, '_>,
+        states: &[&str],
+    ) -> Result<Self, TcssError> {
+        let mut selectors = vec![parse_selector(input, states)?];
 
         while input.try_parse(|p| p.expect_comma()).is_ok() {
-            selectors.push(parse_selector(input)?);
+            selectors.push(parse_selector(input, states)?);
         }
 
         Ok(Self { selectors })
@@ -278,8 +531,8 @@ impl fmt::Display for SelectorList {
 /// Parse a single complex selector from CSS input.
 ///
 /// A complex selector is one or more compound selectors joined by combinators.
-fn parse_selector(input: &mut Parser<'_, '_>) -> Result<Selector, TcssError> {
-    let mut compounds = vec![parse_compound_selector(input)?];
+fn parse_selector(input: &mut Parser<'_, '_>, states: &[&str]) -> Result<Selector, TcssError> {
+    let mut compounds = vec![parse_compound_selector(input, states)?];
     let mut combinators = Vec::new();
 
     loop {
@@ -305,7 +558,8 @@ fn parse_selector(input: &mut Parser<'_, '_>) -> Result<Selector, TcssError> {
                     | Token::Delim('.')
                     | Token::Delim('*')
                     | Token::Colon
-                    | Token::IDHash(_),
+                    | Token::IDHash(_)
+                    | Token::SquareBracketBlock,
                 ) => {
                     input.reset(&state);
                     Ok(Combinator::Descendant)
@@ -320,7 +574,7 @@ fn parse_selector(input: &mut Parser<'_, '_>) -> Result<Selector, TcssError> {
         match combinator {
             Ok(c) => {
                 combinators.push(c);
-                compounds.push(parse_compound_selector(input)?);
+                compounds.push(parse_compound_selector(input, states)?);
             }
             Err(_) => break,
         }
@@ -339,11 +593,14 @@ fn parse_selector(input: &mut Parser<'_, '_>) -> Result<Selector, TcssError> {
 }
 
 /// Parse a compound selector (no combinators).
-fn parse_compound_selector(input: &mut Parser<'_, '_>) -> Result<CompoundSelector, TcssError> {
+fn parse_compound_selector(
+    input: &mut Parser<'_, '_>,
+    states: &[&str],
+) -> Result<CompoundSelector, TcssError> {
     let mut components = Vec::new();
 
     // Parse the first component (required).
-    components.push(parse_simple_selector(input)?);
+    components.push(parse_simple_selector(input, states)?);
 
     // Parse additional components that are directly attached (no whitespace).
     loop {
@@ -359,31 +616,25 @@ fn parse_compound_selector(input: &mut Parser<'_, '_>) -> Result<CompoundSelecto
                     Token::Colon => {
                         let next = input.next_including_whitespace()?.clone();
                         match &next {
-                            Token::Ident(name) => PseudoClass::from_name(name.as_ref())
-                                .map(SimpleSelector::PseudoClass)
-                                .ok_or_else(|| input.new_error_for_next_token::<()>()),
-                            Token::Function(name) if name.eq_ignore_ascii_case("nth-child") => {
+                            Token::Ident(name) => {
+                                PseudoClass::from_name_with_states(name.as_ref(), states)
+                                    .map(SimpleSelector::PseudoClass)
+                                    .ok_or_else(|| input.new_error_for_next_token::<()>())
+                            }
+                            Token::Function(name) => {
+                                let name = name.to_string();
                                 let pseudo = input.parse_nested_block(|p| {
-                                    if let Ok(n) = p.try_parse(|p| p.expect_integer()) {
-                                        Ok(PseudoClass::NthChild(n))
-                                    } else if let Ok(ident) = p.try_parse(|p| {
-                                        let s = p.expect_ident()?;
-                                        Ok::<String, cssparser::ParseError<'_, ()>>(s.to_string())
-                                    }) {
-                                        match ident.to_ascii_lowercase().as_str() {
-                                            "odd" => Ok(PseudoClass::Odd),
-                                            "even" => Ok(PseudoClass::Even),
-                                            _ => Err(p.new_error_for_next_token::<()>()),
-                                        }
-                                    } else {
-                                        Err(p.new_error_for_next_token::<()>())
-                                    }
+                                    parse_functional_pseudo(&name, p, states)
                                 })?;
                                 Ok(SimpleSelector::PseudoClass(pseudo))
                             }
                             _ => Err(input.new_error_for_next_token::<()>()),
                         }
                     }
+                    Token::SquareBracketBlock => {
+                        let attr = input.parse_nested_block(parse_attribute_selector)?;
+                        Ok(SimpleSelector::Attribute(attr))
+                    }
                     Token::IDHash(name) => Ok(SimpleSelector::Id(name.to_string())),
                     _ => Err(input.new_error_for_next_token::<()>()),
                 }
@@ -399,7 +650,10 @@ fn parse_compound_selector(input: &mut Parser<'_, '_>) -> Result<CompoundSelecto
 }
 
 /// Parse a single simple selector.
-fn parse_simple_selector(input: &mut Parser<'_, '_>) -> Result<SimpleSelector, TcssError> {
+fn parse_simple_selector(
+    input: &mut Parser<'_, '_>,
+    states: &[&str],
+) -> Result<SimpleSelector, TcssError> {
     let token = input
         .next()
         .map_err(|e| TcssError::SelectorError(format!("{e:?}")))?
@@ -416,6 +670,14 @@ fn parse_simple_selector(input: &mut Parser<'_, '_>) -> Result<SimpleSelector, T
             Ok(SimpleSelector::Class(name))
         }
         Token::IDHash(name) => Ok(SimpleSelector::Id(name.to_string())),
+        Token::SquareBracketBlock => {
+            let attr = input
+                .parse_nested_block(parse_attribute_selector)
+                .map_err(|e| {
+                    TcssError::SelectorError(format!("invalid attribute selector: {e:?}"))
+                })?;
+            Ok(SimpleSelector::Attribute(attr))
+        }
         Token::Colon => {
             let next = input
                 .next()
@@ -424,31 +686,19 @@ fn parse_simple_selector(input: &mut Parser<'_, '_>) -> Result<SimpleSelector, T
             match &next {
                 Token::Ident(name) => {
                     let name = name.to_string();
-                    PseudoClass::from_name(&name)
+                    PseudoClass::from_name_with_states(&name, states)
                         .map(SimpleSelector::PseudoClass)
                         .ok_or_else(|| {
                             TcssError::SelectorError(format!("unknown pseudo-class: {name}"))
                         })
                 }
-                Token::Function(name) if name.eq_ignore_ascii_case("nth-child") => {
+                Token::Function(name) => {
+                    let name = name.to_string();
                     let pseudo = input
-                        .parse_nested_block(|p| {
-                            if let Ok(n) = p.try_parse(|p| p.expect_integer()) {
-                                Ok(PseudoClass::NthChild(n))
-                            } else if let Ok(ident) = p.try_parse(|p| {
-                                let s = p.expect_ident()?;
-                                Ok::<String, cssparser::ParseError<'_, ()>>(s.to_string())
-                            }) {
-                                match ident.to_ascii_lowercase().as_str() {
-                                    "odd" => Ok(PseudoClass::Odd),
-                                    "even" => Ok(PseudoClass::Even),
-                                    _ => Err(p.new_error_for_next_token::<()>()),
-                                }
-                            } else {
-                                Err(p.new_error_for_next_token::<()>())
-                            }
-                        })
-                        .map_err(|e| TcssError::SelectorError(format!("{e:?}")))?;
+                        .parse_nested_block(|p| parse_functional_pseudo(&name, p, states))
+                        .map_err(|e| {
+                            TcssError::SelectorError(format!("invalid :{name}() argument: {e:?}"))
+                        })?;
                     Ok(SimpleSelector::PseudoClass(pseudo))
                 }
                 other => Err(TcssError::SelectorError(format!(
@@ -462,6 +712,94 @@ fn parse_simple_selector(input: &mut Parser<'_, '_>) -> Result<SimpleSelector, T
     }
 }
 
+/// Parse the argument of a functional pseudo-class such as `:nth-child(2n+1)`,
+/// `:not(.hidden)` or `:has(> Label)`.
+///
+/// `input` is positioned inside the parentheses.
+fn parse_functional_pseudo<'i>(
+    name: &str,
+    input: &mut Parser<'i, '_>,
+    states: &[&str],
+) -> Result<PseudoClass, cssparser::ParseError<'i, ()>> {
+    let pseudo = match name.to_ascii_lowercase().as_str() {
+        "nth-child" => {
+            let (a, b) = cssparser::parse_nth(input)?;
+            PseudoClass::nth_child(a, b)
+        }
+        "not" => {
+            let list = SelectorList::parse_from_with_states(input, states)
+                .map_err(|_| input.new_custom_error(()))?;
+            PseudoClass::Not(list)
+        }
+        "has" => {
+            let mut relative = vec![parse_relative_selector(input, states)?];
+            while input.try_parse(|p| p.expect_comma()).is_ok() {
+                relative.push(parse_relative_selector(input, states)?);
+            }
+            PseudoClass::Has(relative)
+        }
+        _ => return Err(input.new_custom_error(())),
+    };
+    input.expect_exhausted()?;
+    Ok(pseudo)
+}
+
+/// Parse a relative selector (`> Label`, `+ .item`, `Label`) inside `:has()`.
+fn parse_relative_selector<'i>(
+    input: &mut Parser<'i, '_>,
+    states: &[&str],
+) -> Result<RelativeSelector, cssparser::ParseError<'i, ()>> {
+    let combinator = if input.try_parse(|p| p.expect_delim('>')).is_ok() {
+        Combinator::Child
+    } else if input.try_parse(|p| p.expect_delim('+')).is_ok() {
+        Combinator::AdjacentSibling
+    } else if input.try_parse(|p| p.expect_delim('~')).is_ok() {
+        Combinator::GeneralSibling
+    } else {
+        Combinator::Descendant
+    };
+    let selector = parse_selector(input, states).map_err(|_| input.new_custom_error(()))?;
+    Ok(RelativeSelector {
+        combinator,
+        selector,
+    })
+}
+
+/// Parse the contents of an attribute selector block: `name`, `name=value`,
+/// `name^="value" i`, etc.
+///
+/// `input` is positioned inside the square brackets.
+fn parse_attribute_selector<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<AttributeSelector, cssparser::ParseError<'i, ()>> {
+    let name = input.expect_ident()?.to_string();
+    if input.is_exhausted() {
+        return Ok(AttributeSelector::exists(name));
+    }
+
+    let operator = match input.next()?.clone() {
+        Token::Delim('=') => AttributeOperator::Equals,
+        Token::IncludeMatch => AttributeOperator::Includes,
+        Token::DashMatch => AttributeOperator::DashMatch,
+        Token::PrefixMatch => AttributeOperator::Prefix,
+        Token::SuffixMatch => AttributeOperator::Suffix,
+        Token::SubstringMatch => AttributeOperator::Substring,
+        other => return Err(input.new_unexpected_token_error(other)),
+    };
+    let value = input.expect_ident_or_string()?.to_string();
+
+    let mut attr = AttributeSelector::new(name, operator, value);
+    if let Ok(flag) = input.try_parse(|p| p.expect_ident().map(|s| s.to_string())) {
+        if flag.eq_ignore_ascii_case("i") {
+            attr.case_insensitive = true;
+        } else if !flag.eq_ignore_ascii_case("s") {
+            return Err(input.new_custom_error(()));
+        }
+    }
+    input.expect_exhausted()?;
+    Ok(attr)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -537,6 +875,33 @@ mod tests {
         assert_eq!(PseudoClass::from_name("unknown"), None);
     }
 
+    #[test]
+    fn widget_states_parse_as_pseudo_classes() {
+        assert_eq!(
+            PseudoClass::from_name("vim-normal"),
+            Some(PseudoClass::State("vim-normal".into()))
+        );
+        let list = SelectorList::parse("TextArea:vim-insert");
+        assert!(list.is_ok_and(|l| l.to_string() == "TextArea:vim-insert"));
+    }
+
+    #[test]
+    fn given_widget_states_parse() {
+        let states = ["playing", "Buffering"];
+        assert!(SelectorList::parse("Player:playing").is_err());
+        assert_eq!(PseudoClass::from_name("playing"), None);
+        assert_eq!(
+            PseudoClass::from_name_with_states("Playing", &states),
+            Some(PseudoClass::State("playing".into()))
+        );
+        let list = SelectorList::parse_with_states("Player:playing, :not(:buffering)", &states);
+        assert!(list.is_ok_and(|l| l.to_string() == "Player:playing, :not(:buffering)"));
+        let list = SelectorList::parse_with_states("Deck:has(> Player:playing)", &states);
+        assert!(list.is_ok());
+        // Typos are still rejected.
+        assert!(SelectorList::parse_with_states("Player:playng", &states).is_err());
+    }
+
     #[test]
     fn pseudo_class_case_insensitive() {
         assert_eq!(PseudoClass::from_name("FOCUS"), Some(PseudoClass::Focus));
@@ -803,4 +1168,100 @@ mod tests {
         // Container(0,0,1) + Label(0,0,1) + .error(0,1,0) + #main(1,0,0) = (1,1,2)
         assert_eq!(list.selectors[0].specificity(), (1, 1, 2));
     }
+
+    #[test]
+    fn parse_nth_child_formulas() {
+        let cases = [
+            ("Item:nth-child(2n+1)", PseudoClass::Odd),
+            ("Item:nth-child(even)", PseudoClass::Even),
+            ("Item:nth-child(3n+1)", PseudoClass::NthChildFormula(3, 1)),
+            ("Item:nth-child(-n+3)", PseudoClass::NthChildFormula(-1, 3)),
+            ("Item:nth-child(4)", PseudoClass::NthChild(4)),
+        ];
+        for (css, expected) in cases {
+            let list = parse_ok(css);
+            assert_eq!(
+                list.selectors[0].head.components[1],
+                SimpleSelector::PseudoClass(expected),
+                "{css}"
+            );
+        }
+        assert_eq!(
+            PseudoClass::NthChildFormula(3, -2).to_string(),
+            ":nth-child(3n-2)"
+        );
+    }
+
+    #[test]
+    fn parse_structural_pseudo_classes() {
+        let list = parse_ok("Label:last-of-type:empty");
+        let head = &list.selectors[0].head;
+        assert_eq!(
+            head.components[1],
+            SimpleSelector::PseudoClass(PseudoClass::LastOfType)
+        );
+        assert_eq!(
+            head.components[2],
+            SimpleSelector::PseudoClass(PseudoClass::Empty)
+        );
+    }
+
+    #[test]
+    fn parse_not_pseudo_class() {
+        let list = parse_ok("Label:not(.error, #main)");
+        let head = &list.selectors[0].head;
+        let SimpleSelector::PseudoClass(PseudoClass::Not(inner)) = &head.components[1] else {
+            unreachable!("expected :not, got {:?}", head.components[1]);
+        };
+        assert_eq!(inner.selectors.len(), 2);
+        assert_eq!(list.selectors[0].to_string(), "Label:not(.error, #main)");
+        // :not takes the specificity of its most specific argument.
+        assert_eq!(list.selectors[0].specificity(), (1, 0, 1));
+    }
+
+    #[test]
+    fn parse_has_pseudo_class() {
+        let list = parse_ok("Container:has(> Label.error, + Input)");
+        let head = &list.selectors[0].head;
+        let SimpleSelector::PseudoClass(PseudoClass::Has(relative)) = &head.components[1] else {
+            unreachable!("expected :has, got {:?}", head.components[1]);
+        };
+        assert_eq!(relative.len(), 2);
+        assert_eq!(relative[0].combinator, Combinator::Child);
+        assert_eq!(relative[1].combinator, Combinator::AdjacentSibling);
+        assert_eq!(
+            list.selectors[0].to_string(),
+            "Container:has(> Label.error, + Input)"
+        );
+        assert_eq!(list.selectors[0].specificity(), (0, 1, 2));
+    }
+
+    #[test]
+    fn parse_attribute_selectors() {
+        let list = parse_ok("Label[kind]");
+        assert_eq!(
+            list.selectors[0].head.components[1],
+            SimpleSelector::Attribute(AttributeSelector::exists("kind"))
+        );
+
+        let list = parse_ok("[lang|=\"en\" i] Label");
+        let sel = &list.selectors[0];
+        let SimpleSelector::Attribute(attr) = &sel.chain[0].1.components[0] else {
+            unreachable!("expected attribute selector");
+        };
+        assert_eq!(attr.operator, AttributeOperator::DashMatch);
+        assert_eq!(attr.value, "en");
+        assert!(attr.case_insensitive);
+        assert_eq!(sel.to_string(), "[lang|=\"en\" i] Label");
+        // Attribute selectors count like classes.
+        assert_eq!(sel.specificity(), (0, 1, 1));
+    }
+
+    #[test]
+    fn parse_invalid_pseudo_functions() {
+        assert!(SelectorList::parse("Label:not()").is_err());
+        assert!(SelectorList::parse("Label:nth-child(foo)").is_err());
+        assert!(SelectorList::parse("Label:unknown(1)").is_err());
+        assert!(SelectorList::parse("[kind==x]").is_err());
+    }
 }
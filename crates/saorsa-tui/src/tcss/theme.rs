@@ -8,6 +8,7 @@ use std::collections::HashMap;
 
 use crate::Color;
 use crate::tcss::error::TcssError;
+use crate::tcss::themes::ThemeVariant;
 use crate::tcss::value::CssValue;
 use crate::tcss::variable::{VariableEnvironment, VariableMap};
 
@@ -313,6 +314,69 @@ pub fn builtin_light() -> Theme {
     Theme::with_variables("light", vars)
 }
 
+/// Look up a built-in theme by name (as registered by [`register_all_themes`]).
+pub fn builtin_theme(name: &str) -> Option<Theme> {
+    use crate::tcss::themes::{
+        catppuccin_frappe, catppuccin_latte, catppuccin_macchiato, catppuccin_mocha, dracula_dark,
+        dracula_light, nord_dark, solarized_dark, solarized_light,
+    };
+
+    let theme = match name {
+        "dark" => builtin_dark(),
+        "light" => builtin_light(),
+        "catppuccin-mocha" => catppuccin_mocha(),
+        "catppuccin-macchiato" => catppuccin_macchiato(),
+        "catppuccin-frappe" => catppuccin_frappe(),
+        "catppuccin-latte" => catppuccin_latte(),
+        "dracula" => dracula_dark(),
+        "dracula-light" => dracula_light(),
+        "nord" => nord_dark(),
+        "solarized-dark" => solarized_dark(),
+        "solarized-light" => solarized_light(),
+        _ => return None,
+    };
+    Some(theme)
+}
+
+/// Built-in theme families as `(family, dark, light)` variant names.
+const THEME_FAMILIES: &[(&str, Option<&str>, Option<&str>)] = &[
+    ("builtin", Some("dark"), Some("light")),
+    (
+        "catppuccin",
+        Some("catppuccin-mocha"),
+        Some("catppuccin-latte"),
+    ),
+    ("dracula", Some("dracula"), Some("dracula-light")),
+    ("nord", Some("nord"), None),
+    ("solarized", Some("solarized-dark"), Some("solarized-light")),
+];
+
+/// Name of the built-in theme of `name`'s family for `variant`.
+///
+/// `name` may be a family (`"catppuccin"`) or any theme in it
+/// (`"catppuccin-frappe"`, `"light"`). Returns `None` for unknown families
+/// and for families without that variant (Nord has no light theme).
+pub fn theme_variant(name: &str, variant: ThemeVariant) -> Option<&'static str> {
+    let (_, dark, light) = THEME_FAMILIES.iter().find(|(family, dark, light)| {
+        name == *family
+            || name.starts_with(&format!("{family}-"))
+            || Some(name) == *dark
+            || Some(name) == *light
+    })?;
+    match variant {
+        ThemeVariant::Dark => *dark,
+        ThemeVariant::Light => *light,
+    }
+}
+
+/// The built-in default theme for `variant`.
+pub fn builtin_for_variant(variant: ThemeVariant) -> Theme {
+    match variant {
+        ThemeVariant::Dark => builtin_dark(),
+        ThemeVariant::Light => builtin_light(),
+    }
+}
+
 /// Extract themes from a parsed stylesheet.
 ///
 /// Rules with a single class selector (`.dark`, `.light`) that contain
@@ -682,4 +746,54 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn builtin_theme_covers_registered_names() {
+        let mgr = ThemeManager::with_defaults();
+        for name in mgr.theme_names() {
+            let theme = builtin_theme(name);
+            assert_eq!(theme.as_ref().map(Theme::name), Some(name));
+        }
+        assert!(builtin_theme("nope").is_none());
+    }
+
+    #[test]
+    fn theme_variant_maps_families() {
+        use ThemeVariant::{Dark, Light};
+        assert_eq!(theme_variant("builtin", Light), Some("light"));
+        assert_eq!(theme_variant("dark", Light), Some("light"));
+        assert_eq!(theme_variant("catppuccin", Dark), Some("catppuccin-mocha"));
+        assert_eq!(
+            theme_variant("catppuccin-frappe", Light),
+            Some("catppuccin-latte")
+        );
+        assert_eq!(theme_variant("dracula-light", Dark), Some("dracula"));
+        assert_eq!(
+            theme_variant("solarized-dark", Light),
+            Some("solarized-light")
+        );
+        assert_eq!(theme_variant("nord", Light), None);
+        assert_eq!(theme_variant("unknown", Dark), None);
+    }
+
+    #[test]
+    fn variant_from_background_luminance() {
+        let rgb = |r, g, b| Color::Rgb { r, g, b };
+        assert_eq!(
+            ThemeVariant::for_background(&rgb(30, 30, 46)),
+            Some(ThemeVariant::Dark)
+        );
+        assert_eq!(
+            ThemeVariant::for_background(&rgb(253, 246, 227)),
+            Some(ThemeVariant::Light)
+        );
+        assert_eq!(
+            ThemeVariant::for_foreground(&rgb(40, 40, 40)),
+            Some(ThemeVariant::Light)
+        );
+        assert_eq!(
+            ThemeVariant::for_background(&Color::Named(NamedColor::Black)),
+            None
+        );
+    }
 }
@@ -190,6 +190,33 @@ pub enum ThemeVariant {
     Dark,
 }
 
+impl ThemeVariant {
+    /// Backgrounds darker than this relative luminance (mid-grey, L* = 50)
+    /// call for a dark theme.
+    const LUMINANCE_THRESHOLD: f32 = 0.18;
+
+    /// The variant suited to a terminal with this background color.
+    ///
+    /// Returns `None` if the color is not RGB (e.g. a palette index whose
+    /// value is unknown).
+    pub fn for_background(background: &Color) -> Option<Self> {
+        let lum = background.relative_luminance()?;
+        Some(if lum < Self::LUMINANCE_THRESHOLD {
+            Self::Dark
+        } else {
+            Self::Light
+        })
+    }
+
+    /// The variant suited to a terminal with this foreground (text) color.
+    pub fn for_foreground(foreground: &Color) -> Option<Self> {
+        Some(match Self::for_background(foreground)? {
+            Self::Dark => Self::Light,
+            Self::Light => Self::Dark,
+        })
+    }
+}
+
 /// A complete theme with colors and metadata.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Theme {
@@ -19,6 +19,8 @@ pub struct WidgetState {
     pub disabled: bool,
     /// Whether this widget is being activated (e.g., pressed).
     pub active: bool,
+    /// Widget-defined states matched by [`PseudoClass::State`](crate::tcss::selector::PseudoClass::State).
+    pub custom: Vec<String>,
 }
 
 /// A node in the widget tree carrying CSS metadata.
@@ -32,6 +34,8 @@ pub struct WidgetNode {
     pub classes: Vec<String>,
     /// Optional CSS ID (should be unique within the tree).
     pub css_id: Option<String>,
+    /// Arbitrary attributes used by attribute selectors (`[kind="error"]`).
+    pub attributes: HashMap<String, String>,
     /// Current pseudo-class state.
     pub state: WidgetState,
     /// Parent node ID, if any.
@@ -50,6 +54,7 @@ impl WidgetNode {
             type_name: type_name.into(),
             classes: Vec::new(),
             css_id: None,
+            attributes: HashMap::new(),
             state: WidgetState::default(),
             parent: None,
             children: Vec::new(),
@@ -68,10 +73,21 @@ impl WidgetNode {
         self
     }
 
+    /// Builder: set an attribute.
+    pub fn with_attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(name.into(), value.into());
+        self
+    }
+
     /// Check whether this node has a given CSS class.
     pub fn has_class(&self, name: &str) -> bool {
         self.classes.iter().any(|c| c == name)
     }
+
+    /// Look up an attribute value by name.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
 }
 
 /// A tree of widget nodes with parent/child relationships.
@@ -210,6 +226,37 @@ impl WidgetTree {
         parent.children.iter().position(|&c| c == id)
     }
 
+    /// Return the siblings before this node (nearest last), excluding itself.
+    ///
+    /// Returns an empty slice for the root or unknown nodes.
+    pub fn preceding_siblings(&self, id: WidgetId) -> &[WidgetId] {
+        match (self.parent(id), self.child_index(id)) {
+            (Some(parent), Some(idx)) => &parent.children[..idx],
+            _ => &[],
+        }
+    }
+
+    /// Return the siblings after this node (nearest first), excluding itself.
+    ///
+    /// Returns an empty slice for the root or unknown nodes.
+    pub fn following_siblings(&self, id: WidgetId) -> &[WidgetId] {
+        match (self.parent(id), self.child_index(id)) {
+            (Some(parent), Some(idx)) => &parent.children[idx + 1..],
+            _ => &[],
+        }
+    }
+
+    /// Return all descendants of this node in pre-order, excluding itself.
+    pub fn descendants(&self, id: WidgetId) -> Vec<WidgetId> {
+        let mut result = Vec::new();
+        let mut stack: Vec<WidgetId> = self.children(id).iter().rev().copied().collect();
+        while let Some(next) = stack.pop() {
+            result.push(next);
+            stack.extend(self.children(next).iter().rev().copied());
+        }
+        result
+    }
+
     /// Number of nodes in the tree.
     pub fn len(&self) -> usize {
         self.nodes.len()
@@ -341,6 +388,32 @@ mod tests {
         assert_eq!(tree.child_index(1), None);
     }
 
+    #[test]
+    fn sibling_and_descendant_queries() {
+        let mut tree = WidgetTree::new();
+        tree.add_node(WidgetNode::new(1, "Root"));
+        for id in 2..=4 {
+            let mut child = WidgetNode::new(id, "Child");
+            child.parent = Some(1);
+            tree.add_node(child);
+        }
+        let mut grandchild = WidgetNode::new(5, "Leaf");
+        grandchild.parent = Some(3);
+        tree.add_node(grandchild);
+
+        assert_eq!(tree.preceding_siblings(3), &[2]);
+        assert_eq!(tree.following_siblings(3), &[4]);
+        assert!(tree.preceding_siblings(1).is_empty());
+        assert_eq!(tree.descendants(1), vec![2, 3, 5, 4]);
+    }
+
+    #[test]
+    fn widget_node_attributes() {
+        let node = WidgetNode::new(1, "Label").with_attribute("kind", "error");
+        assert_eq!(node.attribute("kind"), Some("error"));
+        assert_eq!(node.attribute("missing"), None);
+    }
+
     #[test]
     fn widget_node_builder() {
         let node = WidgetNode::new(1, "Label")
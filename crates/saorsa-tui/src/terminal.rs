@@ -35,6 +35,8 @@
 //! - Device Attributes (DA1) for color support
 //! - DECRPM for synchronized output support
 //! - Kitty keyboard protocol query
+//! - OSC 10/11/4 for the default colors and palette, used to pick a light
+//!   or dark theme ([`detect_theme_variant`])
 //!
 //! Runtime queries override static profiles when they succeed, allowing detection of
 //! unknown terminals or upgraded capabilities.
@@ -105,6 +107,7 @@ mod detect;
 pub mod multiplexer;
 mod profiles;
 mod query;
+mod recorder;
 mod test_backend;
 mod traits;
 
@@ -113,6 +116,10 @@ pub use detect::{
     MultiplexerKind, TerminalInfo, TerminalKind, detect, detect_multiplexer, detect_terminal,
 };
 pub use profiles::{merge_multiplexer_limits, profile_for};
-pub use query::{LiveQuerier, MockQuerier, TerminalQuerier, detect_capabilities};
+pub use query::{
+    LiveQuerier, MockQuerier, QueryInput, TerminalColors, TerminalQuerier, detect_capabilities,
+    detect_theme_variant, parse_osc_color,
+};
+pub use recorder::{RecordingClock, RecordingTerminal};
 pub use test_backend::TestBackend;
 pub use traits::{ColorSupport, Terminal, TerminalCapabilities};
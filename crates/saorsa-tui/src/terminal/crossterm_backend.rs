@@ -3,23 +3,45 @@
 use std::io::{self, Write};
 
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    cursor,
+    event::{
+        DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
     terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
 use crate::error::Result;
-use crate::geometry::Size;
+use crate::geometry::{Position, Size};
 
 use super::detect::{MultiplexerKind, detect_multiplexer, detect_terminal};
 use super::profiles::{merge_multiplexer_limits, profile_for};
 use super::traits::{Terminal, TerminalCapabilities};
 
+/// Kitty keyboard protocol flags pushed on terminals that support it.
+///
+/// Disambiguation makes Shift+Enter, Ctrl+Enter, Ctrl+I vs Tab, etc.
+/// distinguishable; event types add repeat/release reporting; alternate
+/// keys report shifted characters (e.g. `A` rather than `a` + Shift).
+const KEYBOARD_FLAGS: KeyboardEnhancementFlags =
+    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+        .union(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+        .union(KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS);
+
 /// Terminal backend using crossterm for real terminal I/O.
+///
+/// Entering raw mode switches to the alternate screen (unless disabled with
+/// [`CrosstermBackend::with_alternate_screen`] for inline rendering) and,
+/// when the capabilities allow, pushes Kitty keyboard enhancement flags and enables
+/// focus change reporting. Both are undone on exit or drop.
 pub struct CrosstermBackend {
     capabilities: TerminalCapabilities,
     multiplexer: MultiplexerKind,
     raw_mode: bool,
+    alternate_screen: bool,
+    keyboard_enhanced: bool,
+    focus_reporting: bool,
 }
 
 impl CrosstermBackend {
@@ -37,6 +59,9 @@ impl CrosstermBackend {
             capabilities,
             multiplexer,
             raw_mode: false,
+            alternate_screen: true,
+            keyboard_enhanced: false,
+            focus_reporting: false,
         }
     }
 
@@ -49,9 +74,22 @@ impl CrosstermBackend {
             capabilities,
             multiplexer: MultiplexerKind::None,
             raw_mode: false,
+            alternate_screen: true,
+            keyboard_enhanced: false,
+            focus_reporting: false,
         }
     }
 
+    /// Choose whether raw mode switches to the alternate screen (default
+    /// `true`). Disable it for inline viewports
+    /// ([`crate::RenderContext::new_inline`]) so output stays in the
+    /// shell's scrollback.
+    #[must_use]
+    pub fn with_alternate_screen(mut self, enabled: bool) -> Self {
+        self.alternate_screen = enabled;
+        self
+    }
+
     /// Returns the detected multiplexer kind.
     ///
     /// This is used internally for escape sequence wrapping when sending
@@ -80,15 +118,28 @@ impl Terminal for CrosstermBackend {
     fn enter_raw_mode(&mut self) -> Result<()> {
         if !self.raw_mode {
             terminal::enable_raw_mode()?;
-            execute!(io::stdout(), EnterAlternateScreen)?;
+            if self.alternate_screen {
+                execute!(io::stdout(), EnterAlternateScreen)?;
+            }
             self.raw_mode = true;
+            if self.capabilities.kitty_keyboard {
+                execute!(io::stdout(), PushKeyboardEnhancementFlags(KEYBOARD_FLAGS))?;
+                self.keyboard_enhanced = true;
+            }
+            if self.capabilities.focus_events {
+                execute!(io::stdout(), EnableFocusChange)?;
+                self.focus_reporting = true;
+            }
         }
         Ok(())
     }
 
     fn exit_raw_mode(&mut self) -> Result<()> {
         if self.raw_mode {
-            execute!(io::stdout(), LeaveAlternateScreen)?;
+            self.restore_input_modes()?;
+            if self.alternate_screen {
+                execute!(io::stdout(), LeaveAlternateScreen)?;
+            }
             terminal::disable_raw_mode()?;
             self.raw_mode = false;
         }
@@ -114,12 +165,43 @@ impl Terminal for CrosstermBackend {
         execute!(io::stdout(), DisableMouseCapture)?;
         Ok(())
     }
+
+    fn multiplexer(&self) -> MultiplexerKind {
+        self.multiplexer
+    }
+
+    fn cursor_position(&mut self) -> Result<Option<Position>> {
+        let (x, y) = cursor::position()?;
+        Ok(Some(Position::new(x, y)))
+    }
+
+    fn keyboard_enhanced(&self) -> bool {
+        self.keyboard_enhanced
+    }
+}
+
+impl CrosstermBackend {
+    /// Pop keyboard enhancement flags and disable focus reporting.
+    fn restore_input_modes(&mut self) -> Result<()> {
+        if self.keyboard_enhanced {
+            execute!(io::stdout(), PopKeyboardEnhancementFlags)?;
+            self.keyboard_enhanced = false;
+        }
+        if self.focus_reporting {
+            execute!(io::stdout(), DisableFocusChange)?;
+            self.focus_reporting = false;
+        }
+        Ok(())
+    }
 }
 
 impl Drop for CrosstermBackend {
     fn drop(&mut self) {
         if self.raw_mode {
-            let _ = execute!(io::stdout(), LeaveAlternateScreen);
+            let _ = self.restore_input_modes();
+            if self.alternate_screen {
+                let _ = execute!(io::stdout(), LeaveAlternateScreen);
+            }
             let _ = terminal::disable_raw_mode();
         }
     }
@@ -136,6 +218,8 @@ mod tests {
         let backend = CrosstermBackend::new();
         // Just verify it created successfully with some capabilities
         assert!(!backend.raw_mode);
+        assert!(backend.alternate_screen);
+        assert!(!backend.with_alternate_screen(false).alternate_screen);
     }
 
     #[test]
@@ -150,6 +234,9 @@ mod tests {
             focus_events: true,
             hyperlinks: true,
             sixel: false,
+            styled_underlines: true,
+            kitty_graphics: false,
+            iterm2_images: false,
         };
 
         let backend = CrosstermBackend::with_capabilities(caps.clone());
@@ -19,6 +19,9 @@ pub fn profile_for(kind: TerminalKind) -> TerminalCapabilities {
             focus_events: true,
             hyperlinks: true,
             sixel: false,
+            styled_underlines: true,
+            kitty_graphics: false,
+            iterm2_images: true,
         },
         TerminalKind::Kitty => TerminalCapabilities {
             color: ColorSupport::TrueColor,
@@ -30,6 +33,9 @@ pub fn profile_for(kind: TerminalKind) -> TerminalCapabilities {
             focus_events: true,
             hyperlinks: true,
             sixel: false,
+            styled_underlines: true,
+            kitty_graphics: true,
+            iterm2_images: false,
         },
         TerminalKind::Alacritty => TerminalCapabilities {
             color: ColorSupport::TrueColor,
@@ -41,6 +47,9 @@ pub fn profile_for(kind: TerminalKind) -> TerminalCapabilities {
             focus_events: true,
             hyperlinks: false,
             sixel: false,
+            styled_underlines: true,
+            kitty_graphics: false,
+            iterm2_images: false,
         },
         TerminalKind::WezTerm => TerminalCapabilities {
             color: ColorSupport::TrueColor,
@@ -52,6 +61,9 @@ pub fn profile_for(kind: TerminalKind) -> TerminalCapabilities {
             focus_events: true,
             hyperlinks: true,
             sixel: true,
+            styled_underlines: true,
+            kitty_graphics: true,
+            iterm2_images: true,
         },
         TerminalKind::TerminalApp => TerminalCapabilities {
             color: ColorSupport::Basic16,
@@ -63,6 +75,9 @@ pub fn profile_for(kind: TerminalKind) -> TerminalCapabilities {
             focus_events: false,
             hyperlinks: false,
             sixel: false,
+            styled_underlines: false,
+            kitty_graphics: false,
+            iterm2_images: false,
         },
         TerminalKind::WindowsTerminal => TerminalCapabilities {
             color: ColorSupport::TrueColor,
@@ -74,6 +89,9 @@ pub fn profile_for(kind: TerminalKind) -> TerminalCapabilities {
             focus_events: false,
             hyperlinks: true,
             sixel: false,
+            styled_underlines: false,
+            kitty_graphics: false,
+            iterm2_images: false,
         },
         TerminalKind::Xterm => TerminalCapabilities {
             color: ColorSupport::Extended256,
@@ -85,6 +103,9 @@ pub fn profile_for(kind: TerminalKind) -> TerminalCapabilities {
             focus_events: true,
             hyperlinks: false,
             sixel: false,
+            styled_underlines: false,
+            kitty_graphics: false,
+            iterm2_images: false,
         },
         TerminalKind::VTE => TerminalCapabilities {
             color: ColorSupport::TrueColor,
@@ -96,6 +117,9 @@ pub fn profile_for(kind: TerminalKind) -> TerminalCapabilities {
             focus_events: true,
             hyperlinks: true,
             sixel: false,
+            styled_underlines: true,
+            kitty_graphics: false,
+            iterm2_images: false,
         },
         TerminalKind::Unknown => TerminalCapabilities {
             color: ColorSupport::Basic16,
@@ -107,6 +131,9 @@ pub fn profile_for(kind: TerminalKind) -> TerminalCapabilities {
             focus_events: false,
             hyperlinks: false,
             sixel: false,
+            styled_underlines: false,
+            kitty_graphics: false,
+            iterm2_images: false,
         },
     }
 }
@@ -147,6 +174,9 @@ pub fn merge_multiplexer_limits(
             caps.kitty_keyboard = false;
             caps.hyperlinks = false;
             caps.sixel = false;
+            caps.styled_underlines = false;
+            caps.kitty_graphics = false;
+            caps.iterm2_images = false;
         }
         MultiplexerKind::Zellij => {
             // Zellij is modern and mostly transparent
@@ -160,6 +190,40 @@ pub fn merge_multiplexer_limits(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_image_protocol_support() {
+        let kitty = profile_for(TerminalKind::Kitty);
+        assert!(kitty.kitty_graphics);
+        assert!(!kitty.iterm2_images);
+        let iterm = profile_for(TerminalKind::ITerm2);
+        assert!(iterm.iterm2_images);
+        assert!(!iterm.kitty_graphics);
+        let xterm = profile_for(TerminalKind::Xterm);
+        assert!(!xterm.kitty_graphics && !xterm.iterm2_images && !xterm.sixel);
+
+        let screen = merge_multiplexer_limits(kitty.clone(), MultiplexerKind::Screen);
+        assert!(!screen.kitty_graphics);
+        let tmux = merge_multiplexer_limits(kitty, MultiplexerKind::Tmux);
+        assert!(tmux.kitty_graphics);
+    }
+
+    #[test]
+    fn test_styled_underline_support() {
+        assert!(profile_for(TerminalKind::Kitty).styled_underlines);
+        assert!(profile_for(TerminalKind::WezTerm).styled_underlines);
+        assert!(profile_for(TerminalKind::VTE).styled_underlines);
+        assert!(!profile_for(TerminalKind::Xterm).styled_underlines);
+        assert!(!profile_for(TerminalKind::TerminalApp).styled_underlines);
+        assert!(!profile_for(TerminalKind::Unknown).styled_underlines);
+
+        let screen =
+            merge_multiplexer_limits(profile_for(TerminalKind::Kitty), MultiplexerKind::Screen);
+        assert!(!screen.styled_underlines);
+        let tmux =
+            merge_multiplexer_limits(profile_for(TerminalKind::Kitty), MultiplexerKind::Tmux);
+        assert!(tmux.styled_underlines);
+    }
+
     #[test]
     fn test_iterm2_profile() {
         let caps = profile_for(TerminalKind::ITerm2);
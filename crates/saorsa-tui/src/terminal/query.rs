@@ -3,8 +3,10 @@
 use super::detect::{MultiplexerKind, TerminalKind};
 use super::profiles::{merge_multiplexer_limits, profile_for};
 use super::traits::{ColorSupport, TerminalCapabilities};
+use crate::color::Color;
+use crate::tcss::ThemeVariant;
 use std::io::{Read, Write};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Escape sequence constants for terminal queries.
 mod sequences {
@@ -20,6 +22,20 @@ mod sequences {
     /// Kitty keyboard protocol query.
     /// Response format: CSI ? flags u (if supported)
     pub const KITTY_KEYBOARD_QUERY: &[u8] = b"\x1b[?u";
+
+    /// OSC 10 - default foreground color query.
+    /// Response format: OSC 10 ; rgb:RRRR/GGGG/BBBB ST
+    pub const OSC_FOREGROUND: &[u8] = b"\x1b]10;?\x1b\\";
+
+    /// OSC 11 - default background color query.
+    /// Response format: OSC 11 ; rgb:RRRR/GGGG/BBBB ST
+    pub const OSC_BACKGROUND: &[u8] = b"\x1b]11;?\x1b\\";
+
+    /// OSC 4 - palette color query for `index`.
+    /// Response format: OSC 4 ; index ; rgb:RRRR/GGGG/BBBB ST
+    pub fn osc_palette(index: u8) -> Vec<u8> {
+        format!("\x1b]4;{index};?\x1b\\").into_bytes()
+    }
 }
 
 /// Trait for querying terminal capabilities at runtime.
@@ -41,17 +57,141 @@ pub trait TerminalQuerier {
     /// Sends CSI ? u and checks for a valid response.
     /// Returns `None` on timeout or parse failure.
     fn query_kitty_keyboard(&mut self) -> Option<bool>;
+
+    /// Query the default foreground color via OSC 10.
+    ///
+    /// Returns `None` on timeout, parse failure, or if unsupported.
+    fn query_foreground_color(&mut self) -> Option<Color> {
+        None
+    }
+
+    /// Query the default background color via OSC 11.
+    ///
+    /// Returns `None` on timeout, parse failure, or if unsupported.
+    fn query_background_color(&mut self) -> Option<Color> {
+        None
+    }
+
+    /// Query palette color `index` via OSC 4.
+    ///
+    /// Returns `None` on timeout, parse failure, or if unsupported.
+    fn query_palette_color(&mut self, _index: u8) -> Option<Color> {
+        None
+    }
+}
+
+/// Parse the color in an OSC 4/10/11 response.
+///
+/// Terminals answer with X11 `rgb:R/G/B` specs where each component has
+/// one to four hex digits (some add an alpha component with `rgba:`);
+/// components are scaled to eight bits.
+pub fn parse_osc_color(response: &[u8]) -> Option<Color> {
+    let text = String::from_utf8_lossy(response);
+    let start = match text.find("rgb:") {
+        Some(i) => i + 4,
+        None => text.find("rgba:")? + 5,
+    };
+    let spec = &text[start..];
+    let spec = spec.split(['\x07', '\x1b']).next().unwrap_or_default();
+    let mut parts = spec.split('/').map(|part| {
+        let digits = u32::try_from(part.len())
+            .ok()
+            .filter(|n| (1..=4).contains(n))?;
+        let value = u32::from_str_radix(part, 16).ok()?;
+        let max = (1u32 << (4 * digits)) - 1;
+        u8::try_from((value * 255 + max / 2) / max).ok()
+    });
+    let r = parts.next()??;
+    let g = parts.next()??;
+    let b = parts.next()??;
+    Some(Color::Rgb { r, g, b })
+}
+
+/// Terminal input a [`LiveQuerier`] can wait on.
+pub trait QueryInput: Read {
+    /// Wait up to `timeout` for input. Returns whether a read would not
+    /// block.
+    fn wait_readable(&mut self, timeout: Duration) -> bool;
+}
+
+impl<T: QueryInput + ?Sized> QueryInput for &mut T {
+    fn wait_readable(&mut self, timeout: Duration) -> bool {
+        (**self).wait_readable(timeout)
+    }
+}
+
+/// Canned input, for tests: readable until exhausted.
+impl QueryInput for &[u8] {
+    fn wait_readable(&mut self, _timeout: Duration) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl QueryInput for std::io::Stdin {
+    fn wait_readable(&mut self, timeout: Duration) -> bool {
+        poll_readable(self, timeout)
+    }
+}
+
+impl QueryInput for std::fs::File {
+    fn wait_readable(&mut self, timeout: Duration) -> bool {
+        poll_readable(self, timeout)
+    }
+}
+
+/// Wait for `input` to become readable with `poll(2)`.
+#[cfg(unix)]
+fn poll_readable(input: &impl std::os::fd::AsFd, timeout: Duration) -> bool {
+    use rustix::event::{PollFd, PollFlags, poll};
+
+    let millis = i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX);
+    let mut fds = [PollFd::new(input, PollFlags::IN)];
+    matches!(poll(&mut fds, millis), Ok(n) if n > 0)
+}
+
+/// Without `poll(2)`, reads may block until the terminal answers.
+#[cfg(not(unix))]
+fn poll_readable<T>(_input: &T, _timeout: Duration) -> bool {
+    true
+}
+
+/// The end of the primary device attributes reply (`CSI ? Ps ; ... c`) in
+/// `response`, if it holds one.
+fn da1_reply_end(response: &[u8]) -> Option<usize> {
+    let mut from = 0;
+    while let Some(start) = response
+        .get(from..)?
+        .windows(3)
+        .position(|w| w == b"\x1b[?")
+    {
+        let params = from + start + 3;
+        let end = response
+            .get(params..)?
+            .iter()
+            .position(|b| !(b.is_ascii_digit() || *b == b';'))
+            .map(|i| params + i);
+        if let Some(end) = end
+            && response.get(end) == Some(&b'c')
+        {
+            return Some(end + 1);
+        }
+        from = params;
+    }
+    None
 }
 
 /// Live terminal querier that talks to a real TTY.
-pub struct LiveQuerier<R: Read, W: Write> {
+///
+/// Each query is followed by a DA1 request, which every terminal answers:
+/// the querier reads until that reply arrives or the timeout passes, so a
+/// terminal that ignores a query costs one round trip instead of a hang.
+pub struct LiveQuerier<R: QueryInput, W: Write> {
     reader: R,
     writer: W,
-    #[allow(dead_code)] // Used for future non-blocking I/O implementation
     timeout: Duration,
 }
 
-impl<R: Read, W: Write> LiveQuerier<R, W> {
+impl<R: QueryInput, W: Write> LiveQuerier<R, W> {
     /// Create a new live querier with the given reader/writer and timeout.
     ///
     /// # Arguments
@@ -67,32 +207,37 @@ impl<R: Read, W: Write> LiveQuerier<R, W> {
         }
     }
 
-    /// Send a query and read response with timeout.
+    /// Send a query followed by a DA1 sentinel and read the replies.
     ///
-    /// Returns `None` if timeout occurs or I/O error.
+    /// Returns everything read up to and including the DA1 reply, or
+    /// `None` if nothing arrived before the timeout or on I/O error.
+    /// Bytes after the DA1 reply are left unread.
     fn query(&mut self, sequence: &[u8]) -> Option<Vec<u8>> {
-        // Write query sequence
-        if self.writer.write_all(sequence).is_err() {
-            return None;
-        }
-        if self.writer.flush().is_err() {
-            return None;
+        self.writer.write_all(sequence).ok()?;
+        if sequence != sequences::DA1 {
+            self.writer.write_all(sequences::DA1).ok()?;
         }
-
-        // Read response with timeout (simplified: just read what's available)
-        // In a real implementation, this would use non-blocking I/O or select/poll
-        let mut buf = vec![0u8; 256];
-        match self.reader.read(&mut buf) {
-            Ok(n) if n > 0 => {
-                buf.truncate(n);
-                Some(buf)
+        self.writer.flush().ok()?;
+
+        // Read a byte at a time so input after the DA1 reply stays unread.
+        let deadline = Instant::now() + self.timeout;
+        let mut response = Vec::new();
+        let mut byte = [0u8; 1];
+        while da1_reply_end(&response).is_none() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !self.reader.wait_readable(remaining) {
+                break;
+            }
+            match self.reader.read(&mut byte) {
+                Ok(1) => response.push(byte[0]),
+                _ => break,
             }
-            _ => None,
         }
+        (!response.is_empty()).then_some(response)
     }
 }
 
-impl<R: Read, W: Write> TerminalQuerier for LiveQuerier<R, W> {
+impl<R: QueryInput, W: Write> TerminalQuerier for LiveQuerier<R, W> {
     fn query_color_support(&mut self) -> Option<ColorSupport> {
         let response = self.query(sequences::DA1)?;
 
@@ -137,16 +282,26 @@ impl<R: Read, W: Write> TerminalQuerier for LiveQuerier<R, W> {
     fn query_kitty_keyboard(&mut self) -> Option<bool> {
         let response = self.query(sequences::KITTY_KEYBOARD_QUERY)?;
 
-        // Response: CSI ? flags u (if supported)
-        // No response or error means not supported
+        // Response: CSI ? flags u (if supported), before the DA1 reply.
+        // Only the DA1 reply means not supported.
         let response_str = String::from_utf8_lossy(&response);
+        Some(response_str.split("\x1b[?").skip(1).any(|reply| {
+            reply
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .starts_with('u')
+        }))
+    }
 
-        // Look for "?...u" pattern (flags can vary)
-        if response_str.contains('?') && response_str.contains('u') {
-            Some(true)
-        } else {
-            Some(false)
-        }
+    fn query_foreground_color(&mut self) -> Option<Color> {
+        parse_osc_color(&self.query(sequences::OSC_FOREGROUND)?)
+    }
+
+    fn query_background_color(&mut self) -> Option<Color> {
+        parse_osc_color(&self.query(sequences::OSC_BACKGROUND)?)
+    }
+
+    fn query_palette_color(&mut self, index: u8) -> Option<Color> {
+        parse_osc_color(&self.query(&sequences::osc_palette(index))?)
     }
 }
 
@@ -157,6 +312,9 @@ pub struct MockQuerier {
     color_support: Option<ColorSupport>,
     synchronized_output: Option<bool>,
     kitty_keyboard: Option<bool>,
+    foreground: Option<Color>,
+    background: Option<Color>,
+    palette: [Option<Color>; 16],
 }
 
 impl MockQuerier {
@@ -166,6 +324,9 @@ impl MockQuerier {
             color_support: None,
             synchronized_output: None,
             kitty_keyboard: None,
+            foreground: None,
+            background: None,
+            palette: Default::default(),
         }
     }
 
@@ -186,6 +347,32 @@ impl MockQuerier {
         self.kitty_keyboard = Some(supported);
         self
     }
+
+    /// Set the response for the foreground color query.
+    pub fn with_foreground_color(mut self, color: Color) -> Self {
+        self.foreground = Some(color);
+        self
+    }
+
+    /// Set the response for the background color query.
+    pub fn with_background_color(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Set the response for the palette color query of `index` (0-15).
+    pub fn with_palette_color(mut self, index: u8, color: Color) -> Self {
+        if let Some(slot) = self.palette.get_mut(usize::from(index)) {
+            *slot = Some(color);
+        }
+        self
+    }
+
+    /// Change the background color response, e.g. to simulate the
+    /// terminal switching between light and dark mode.
+    pub fn set_background_color(&mut self, color: Option<Color>) {
+        self.background = color;
+    }
 }
 
 impl Default for MockQuerier {
@@ -206,6 +393,88 @@ impl TerminalQuerier for MockQuerier {
     fn query_kitty_keyboard(&mut self) -> Option<bool> {
         self.kitty_keyboard
     }
+
+    fn query_foreground_color(&mut self) -> Option<Color> {
+        self.foreground.clone()
+    }
+
+    fn query_background_color(&mut self) -> Option<Color> {
+        self.background.clone()
+    }
+
+    fn query_palette_color(&mut self, index: u8) -> Option<Color> {
+        self.palette.get(usize::from(index)).cloned().flatten()
+    }
+}
+
+/// Colors reported by the terminal.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TerminalColors {
+    /// Default foreground color (OSC 10).
+    pub foreground: Option<Color>,
+    /// Default background color (OSC 11).
+    pub background: Option<Color>,
+    /// The 16 ANSI palette colors (OSC 4), if queried.
+    pub palette: [Option<Color>; 16],
+}
+
+impl TerminalColors {
+    /// Query the default colors, and the ANSI palette if `palette` is set.
+    pub fn query(querier: &mut dyn TerminalQuerier, palette: bool) -> Self {
+        let mut colors = Self {
+            foreground: querier.query_foreground_color(),
+            background: querier.query_background_color(),
+            palette: Default::default(),
+        };
+        if palette {
+            for (index, slot) in (0u8..).zip(colors.palette.iter_mut()) {
+                *slot = querier.query_palette_color(index);
+            }
+        }
+        colors
+    }
+
+    /// The theme variant suited to these colors, judged by the background's
+    /// luminance or, failing that, the foreground's.
+    pub fn variant(&self) -> Option<ThemeVariant> {
+        self.background
+            .as_ref()
+            .and_then(ThemeVariant::for_background)
+            .or_else(|| {
+                self.foreground
+                    .as_ref()
+                    .and_then(ThemeVariant::for_foreground)
+            })
+    }
+}
+
+/// Detect whether the terminal has a light or dark background.
+///
+/// Queries only what is needed: the background first, then the foreground.
+/// Returns `None` if the terminal answers neither query.
+///
+/// # Examples
+///
+/// ```
+/// use saorsa_tui::Color;
+/// use saorsa_tui::tcss::ThemeVariant;
+/// use saorsa_tui::terminal::{MockQuerier, detect_theme_variant};
+///
+/// let mut querier = MockQuerier::new().with_background_color(Color::Rgb { r: 250, g: 250, b: 250 });
+/// assert_eq!(detect_theme_variant(&mut querier), Some(ThemeVariant::Light));
+/// ```
+pub fn detect_theme_variant(querier: &mut dyn TerminalQuerier) -> Option<ThemeVariant> {
+    if let Some(variant) = querier
+        .query_background_color()
+        .as_ref()
+        .and_then(ThemeVariant::for_background)
+    {
+        return Some(variant);
+    }
+    querier
+        .query_foreground_color()
+        .as_ref()
+        .and_then(ThemeVariant::for_foreground)
 }
 
 /// Detect terminal capabilities combining static profiles and dynamic queries.
@@ -363,6 +632,136 @@ mod tests {
         assert!(!caps.synchronized_output); // Alacritty static profile
     }
 
+    #[test]
+    fn parse_osc_color_responses() {
+        let rgb = |r, g, b| Some(Color::Rgb { r, g, b });
+        assert_eq!(
+            parse_osc_color(b"\x1b]11;rgb:1e1e/1e1e/2e2e\x1b\\"),
+            rgb(30, 30, 46)
+        );
+        assert_eq!(
+            parse_osc_color(b"\x1b]4;1;rgb:cd/00/00\x07"),
+            rgb(205, 0, 0)
+        );
+        assert_eq!(parse_osc_color(b"\x1b]10;rgb:f/8/0\x07"), rgb(255, 136, 0));
+        assert_eq!(
+            parse_osc_color(b"\x1b]11;rgba:ffff/ffff/ffff/ffff\x07"),
+            rgb(255, 255, 255)
+        );
+        assert_eq!(parse_osc_color(b"\x1b]11;rgb:zz/00/00\x07"), None);
+        assert_eq!(parse_osc_color(b"\x1b]11;rgb:ff/00\x07"), None);
+        assert_eq!(parse_osc_color(b"\x1b[?62c"), None);
+    }
+
+    #[test]
+    fn live_querier_parses_background_response() {
+        let input: &[u8] = b"\x1b]11;rgb:fdfd/f6f6/e3e3\x1b\\";
+        let mut output = Vec::new();
+        let mut querier = LiveQuerier::new(input, &mut output, Duration::from_millis(50));
+        assert_eq!(
+            querier.query_background_color(),
+            Some(Color::Rgb {
+                r: 253,
+                g: 246,
+                b: 227
+            })
+        );
+        assert_eq!(output, b"\x1b]11;?\x1b\\\x1b[c");
+    }
+
+    #[test]
+    fn live_querier_sends_palette_query() {
+        let input: &[u8] = b"\x1b]4;3;rgb:cdcd/cdcd/0000\x07";
+        let mut output = Vec::new();
+        let mut querier = LiveQuerier::new(input, &mut output, Duration::from_millis(50));
+        assert!(querier.query_palette_color(3).is_some());
+        assert_eq!(output, b"\x1b]4;3;?\x1b\\\x1b[c");
+    }
+
+    #[test]
+    fn live_querier_stops_at_the_da1_reply() {
+        let mut input: &[u8] = b"\x1b]11;rgb:0000/0000/0000\x07\x1b[?62;22cq";
+        let mut querier = LiveQuerier::new(&mut input, Vec::new(), Duration::from_secs(5));
+        assert_eq!(
+            querier.query_background_color(),
+            Some(Color::Rgb { r: 0, g: 0, b: 0 })
+        );
+        // A key typed after the reply is left for the event reader.
+        assert_eq!(input, b"q");
+
+        // A terminal that ignores the query only answers DA1.
+        let mut input: &[u8] = b"\x1b[?62;22c";
+        let mut querier = LiveQuerier::new(&mut input, Vec::new(), Duration::from_secs(5));
+        assert_eq!(querier.query_foreground_color(), None);
+    }
+
+    #[test]
+    fn live_querier_detects_kitty_keyboard_before_da1() {
+        let input: &[u8] = b"\x1b[?1u\x1b[?62c";
+        let mut querier = LiveQuerier::new(input, Vec::new(), Duration::from_millis(50));
+        assert_eq!(querier.query_kitty_keyboard(), Some(true));
+        let input: &[u8] = b"\x1b[?62;4c";
+        let mut querier = LiveQuerier::new(input, Vec::new(), Duration::from_millis(50));
+        assert_eq!(querier.query_kitty_keyboard(), Some(false));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn live_querier_times_out_on_silent_terminal() {
+        let Ok((reader, _writer)) = std::io::pipe() else {
+            unreachable!("pipe");
+        };
+        let reader = std::fs::File::from(std::os::fd::OwnedFd::from(reader));
+        let mut querier = LiveQuerier::new(reader, Vec::new(), Duration::from_millis(30));
+        let start = Instant::now();
+        assert_eq!(querier.query_background_color(), None);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn da1_reply_detection() {
+        assert_eq!(da1_reply_end(b"\x1b[?62;22c"), Some(9));
+        assert_eq!(da1_reply_end(b"x\x1b[?1u\x1b[?6c!"), Some(11));
+        assert_eq!(da1_reply_end(b"\x1b[?62;22"), None);
+        assert_eq!(da1_reply_end(b"\x1b[?2026;2$y"), None);
+    }
+
+    #[test]
+    fn terminal_colors_query_and_variant() {
+        let white = Color::Rgb {
+            r: 255,
+            g: 255,
+            b: 255,
+        };
+        let mut querier = MockQuerier::new()
+            .with_foreground_color(white.clone())
+            .with_palette_color(1, Color::Rgb { r: 1, g: 0, b: 0 });
+        let colors = TerminalColors::query(&mut querier, true);
+        assert_eq!(colors.foreground, Some(white));
+        assert_eq!(colors.background, None);
+        assert_eq!(colors.palette[1], Some(Color::Rgb { r: 1, g: 0, b: 0 }));
+        assert_eq!(colors.palette[0], None);
+        // Light text implies a dark background.
+        assert_eq!(colors.variant(), Some(ThemeVariant::Dark));
+
+        let colors = TerminalColors::query(&mut querier, false);
+        assert_eq!(colors.palette[1], None);
+    }
+
+    #[test]
+    fn detect_theme_variant_prefers_background() {
+        let mut querier = MockQuerier::new()
+            .with_foreground_color(Color::Rgb { r: 0, g: 0, b: 0 })
+            .with_background_color(Color::Rgb { r: 0, g: 0, b: 0 });
+        assert_eq!(detect_theme_variant(&mut querier), Some(ThemeVariant::Dark));
+        querier.set_background_color(None);
+        assert_eq!(
+            detect_theme_variant(&mut querier),
+            Some(ThemeVariant::Light)
+        );
+        assert_eq!(detect_theme_variant(&mut MockQuerier::new()), None);
+    }
+
     #[test]
     fn test_live_querier_creation() {
         let input: &[u8] = &[];
@@ -0,0 +1,300 @@
+//! Terminal wrapper that records output as an asciicast v2 file.
+//!
+//! The [asciicast v2] format is a JSON header line followed by one JSON
+//! array per event: `[seconds, "o", data]` for output and
+//! `[seconds, "r", "COLSxROWS"]` for resizes. Recordings play back with
+//! `asciinema play` and embed in web pages with the asciinema player.
+//!
+//! [asciicast v2]: https://docs.asciinema.org/manual/asciicast/v2/
+
+use std::io::Write;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::error::Result;
+use crate::geometry::{Position, Size};
+
+use super::detect::MultiplexerKind;
+use super::traits::{Terminal, TerminalCapabilities};
+
+/// Returns the time elapsed since the recording started.
+pub type RecordingClock = Box<dyn FnMut() -> Duration + Send>;
+
+/// A [`Terminal`] that forwards to `inner` and records everything written
+/// to it as asciicast v2 into `out`.
+///
+/// Output is recorded as one event per [`Terminal::flush`], so a rendered
+/// frame becomes a single event. The header is written on the first flush,
+/// using the inner terminal's size at that point; later size changes are
+/// recorded as resize events.
+pub struct RecordingTerminal<T, W> {
+    inner: T,
+    out: W,
+    clock: RecordingClock,
+    timestamp: Option<u64>,
+    title: Option<String>,
+    pending: Vec<u8>,
+    size: Option<Size>,
+}
+
+impl<T: Terminal, W: Write + Send> RecordingTerminal<T, W> {
+    /// Record `inner` into `out`, timing events with the wall clock.
+    pub fn new(inner: T, out: W) -> Self {
+        let start = Instant::now();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+        Self {
+            inner,
+            out,
+            clock: Box::new(move || start.elapsed()),
+            timestamp,
+            title: None,
+            pending: Vec::new(),
+            size: None,
+        }
+    }
+
+    /// Time events with `clock` instead of the wall clock (e.g. an app's
+    /// virtual clock in tests).
+    #[must_use]
+    pub fn with_clock(mut self, clock: RecordingClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the recording title.
+    #[must_use]
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the header's Unix timestamp (`None` omits it).
+    #[must_use]
+    pub fn with_timestamp(mut self, timestamp: Option<u64>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// The wrapped terminal.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// The wrapped terminal, mutably.
+    ///
+    /// Bytes written directly to it are not recorded.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Record any pending output and return the terminal and writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if flushing the terminal or writing fails.
+    pub fn finish(mut self) -> Result<(T, W)> {
+        self.flush()?;
+        self.out.flush()?;
+        Ok((self.inner, self.out))
+    }
+
+    fn record(&mut self) -> Result<()> {
+        let size = self.inner.size()?;
+        let now = (self.clock)().as_secs_f64();
+        match self.size {
+            None => self.write_header(size)?,
+            Some(previous) if previous != size => {
+                let dims = format!("{}x{}", size.width, size.height);
+                writeln!(self.out, "[{now:.6}, \"r\", {}]", json_string(&dims))?;
+            }
+            Some(_) => {}
+        }
+        self.size = Some(size);
+
+        // Keep an incomplete UTF-8 sequence for the next event.
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(s) => s.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        if valid == 0 {
+            return Ok(());
+        }
+        let data: Vec<u8> = self.pending.drain(..valid).collect();
+        let text = String::from_utf8_lossy(&data);
+        writeln!(self.out, "[{now:.6}, \"o\", {}]", json_string(&text))?;
+        Ok(())
+    }
+
+    fn write_header(&mut self, size: Size) -> Result<()> {
+        let mut header = format!(
+            "{{\"version\": 2, \"width\": {}, \"height\": {}",
+            size.width, size.height
+        );
+        if let Some(ts) = self.timestamp {
+            header.push_str(&format!(", \"timestamp\": {ts}"));
+        }
+        if let Some(title) = &self.title {
+            header.push_str(&format!(", \"title\": {}", json_string(title)));
+        }
+        header.push('}');
+        writeln!(self.out, "{header}")?;
+        Ok(())
+    }
+}
+
+impl<T: Terminal, W: Write + Send> Terminal for RecordingTerminal<T, W> {
+    fn size(&self) -> Result<Size> {
+        self.inner.size()
+    }
+
+    fn capabilities(&self) -> &TerminalCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn enter_raw_mode(&mut self) -> Result<()> {
+        self.inner.enter_raw_mode()
+    }
+
+    fn exit_raw_mode(&mut self) -> Result<()> {
+        self.inner.exit_raw_mode()
+    }
+
+    fn write_raw(&mut self, data: &[u8]) -> Result<()> {
+        self.pending.extend_from_slice(data);
+        self.inner.write_raw(data)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()?;
+        self.record()
+    }
+
+    fn enable_mouse(&mut self) -> Result<()> {
+        self.inner.enable_mouse()
+    }
+
+    fn disable_mouse(&mut self) -> Result<()> {
+        self.inner.disable_mouse()
+    }
+
+    fn multiplexer(&self) -> MultiplexerKind {
+        self.inner.multiplexer()
+    }
+
+    fn cursor_position(&mut self) -> Result<Option<Position>> {
+        self.inner.cursor_position()
+    }
+
+    fn keyboard_enhanced(&self) -> bool {
+        self.inner.keyboard_enhanced()
+    }
+}
+
+/// Encode `s` as a JSON string literal.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 || c == '\u{7f}' => {
+                out.push_str(&format!("\\u{:04x}", u32::from(c)));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+    use crate::terminal::TestBackend;
+
+    fn recorder(backend: TestBackend) -> (RecordingTerminal<TestBackend, Vec<u8>>, Arc<AtomicU64>) {
+        let millis = Arc::new(AtomicU64::new(0));
+        let clock = Arc::clone(&millis);
+        let rec = RecordingTerminal::new(backend, Vec::new())
+            .with_timestamp(None)
+            .with_title("demo \"1\"")
+            .with_clock(Box::new(move || {
+                Duration::from_millis(clock.load(Ordering::SeqCst))
+            }));
+        (rec, millis)
+    }
+
+    #[test]
+    fn json_string_escapes_controls() {
+        assert_eq!(json_string("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(json_string("\x1b[1m\n"), "\"\\u001b[1m\\n\"");
+    }
+
+    #[test]
+    fn records_header_and_one_event_per_flush() {
+        let (mut rec, millis) = recorder(TestBackend::new(20, 5));
+        assert!(rec.write_raw(b"\x1b[1mhi").is_ok());
+        assert!(rec.write_raw(b"!").is_ok());
+        assert!(rec.flush().is_ok());
+        millis.store(1500, Ordering::SeqCst);
+        assert!(rec.write_raw("é".as_bytes()).is_ok());
+        assert!(rec.flush().is_ok());
+        // Nothing written: no event.
+        assert!(rec.flush().is_ok());
+
+        let (backend, out) = match rec.finish() {
+            Ok(parts) => parts,
+            Err(e) => unreachable!("finish failed: {e}"),
+        };
+        assert_eq!(backend.buffer(), "\x1b[1mhi!é".as_bytes());
+        let text = String::from_utf8_lossy(&out);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "{\"version\": 2, \"width\": 20, \"height\": 5, \"title\": \"demo \\\"1\\\"\"}",
+                "[0.000000, \"o\", \"\\u001b[1mhi!\"]",
+                "[1.500000, \"o\", \"é\"]",
+            ]
+        );
+    }
+
+    #[test]
+    fn records_resizes_and_keeps_split_utf8() {
+        let (mut rec, millis) = recorder(TestBackend::new(10, 2));
+        assert!(rec.write_raw(b"a").is_ok());
+        assert!(rec.flush().is_ok());
+        rec.inner_mut().set_size(12, 3);
+        millis.store(250, Ordering::SeqCst);
+        let snow = "☃".as_bytes();
+        assert!(rec.write_raw(&snow[..1]).is_ok());
+        assert!(rec.flush().is_ok());
+        assert!(rec.write_raw(&snow[1..]).is_ok());
+        assert!(rec.flush().is_ok());
+
+        let out = match rec.finish() {
+            Ok((_, out)) => out,
+            Err(e) => unreachable!("finish failed: {e}"),
+        };
+        let text = String::from_utf8_lossy(&out);
+        let lines: Vec<&str> = text.lines().skip(1).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "[0.000000, \"o\", \"a\"]",
+                "[0.250000, \"r\", \"12x3\"]",
+                "[0.250000, \"o\", \"☃\"]",
+            ]
+        );
+    }
+}
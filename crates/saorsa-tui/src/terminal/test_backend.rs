@@ -1,8 +1,9 @@
 //! In-memory terminal backend for testing.
 
 use crate::error::Result;
-use crate::geometry::Size;
+use crate::geometry::{Position, Size};
 
+use super::detect::MultiplexerKind;
 use super::traits::{Terminal, TerminalCapabilities};
 
 /// In-memory terminal backend for testing.
@@ -11,9 +12,11 @@ use super::traits::{Terminal, TerminalCapabilities};
 pub struct TestBackend {
     size: Size,
     capabilities: TerminalCapabilities,
+    multiplexer: MultiplexerKind,
     buffer: Vec<u8>,
     raw_mode: bool,
     mouse_enabled: bool,
+    cursor: Option<Position>,
 }
 
 impl TestBackend {
@@ -22,12 +25,35 @@ impl TestBackend {
         Self {
             size: Size::new(width, height),
             capabilities: TerminalCapabilities::default(),
+            multiplexer: MultiplexerKind::None,
             buffer: Vec::new(),
             raw_mode: false,
             mouse_enabled: false,
+            cursor: Some(Position::new(0, 0)),
         }
     }
 
+    /// Use the given capabilities instead of the defaults.
+    #[must_use]
+    pub fn with_capabilities(mut self, capabilities: TerminalCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Pretend to run inside the given multiplexer.
+    #[must_use]
+    pub fn with_multiplexer(mut self, multiplexer: MultiplexerKind) -> Self {
+        self.multiplexer = multiplexer;
+        self
+    }
+
+    /// Report `position` as the cursor position (`None`: cannot be queried).
+    #[must_use]
+    pub fn with_cursor_position(mut self, position: Option<Position>) -> Self {
+        self.cursor = position;
+        self
+    }
+
     /// Get the bytes written to this backend.
     pub fn buffer(&self) -> &[u8] {
         &self.buffer
@@ -91,6 +117,18 @@ impl Terminal for TestBackend {
         self.mouse_enabled = false;
         Ok(())
     }
+
+    fn multiplexer(&self) -> MultiplexerKind {
+        self.multiplexer
+    }
+
+    fn cursor_position(&mut self) -> Result<Option<Position>> {
+        Ok(self.cursor)
+    }
+
+    fn keyboard_enhanced(&self) -> bool {
+        self.raw_mode && self.capabilities.kitty_keyboard
+    }
 }
 
 #[cfg(test)]
@@ -129,6 +167,19 @@ mod tests {
         assert!(!tb.is_raw_mode());
     }
 
+    #[test]
+    fn test_backend_builders() {
+        let caps = TerminalCapabilities {
+            kitty_graphics: true,
+            ..TerminalCapabilities::default()
+        };
+        let tb = TestBackend::new(80, 24)
+            .with_capabilities(caps)
+            .with_multiplexer(MultiplexerKind::Tmux);
+        assert!(tb.capabilities().kitty_graphics);
+        assert_eq!(tb.multiplexer(), MultiplexerKind::Tmux);
+    }
+
     #[test]
     fn test_backend_mouse() {
         let mut tb = TestBackend::new(80, 24);
@@ -146,4 +197,22 @@ mod tests {
         let size = tb.size().ok();
         assert_eq!(size, Some(Size::new(120, 40)));
     }
+
+    #[test]
+    fn keyboard_enhanced_follows_raw_mode_and_caps() {
+        let caps = TerminalCapabilities {
+            kitty_keyboard: true,
+            ..TerminalCapabilities::default()
+        };
+        let mut tb = TestBackend::new(10, 5).with_capabilities(caps);
+        assert!(!tb.keyboard_enhanced());
+        tb.enter_raw_mode().ok();
+        assert!(tb.keyboard_enhanced());
+        tb.exit_raw_mode().ok();
+        assert!(!tb.keyboard_enhanced());
+
+        let mut plain = TestBackend::new(10, 5);
+        plain.enter_raw_mode().ok();
+        assert!(!plain.keyboard_enhanced());
+    }
 }
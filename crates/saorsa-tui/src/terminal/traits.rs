@@ -1,7 +1,9 @@
 //! Terminal trait and capability types.
 
 use crate::error::Result;
-use crate::geometry::Size;
+use crate::geometry::{Position, Size};
+
+use super::detect::MultiplexerKind;
 
 /// Level of color support available.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -38,6 +40,13 @@ pub struct TerminalCapabilities {
     pub hyperlinks: bool,
     /// Whether Sixel graphics are supported.
     pub sixel: bool,
+    /// Whether extended underline styles (`CSI 4:x m`) and underline
+    /// colors (`CSI 58`) are supported.
+    pub styled_underlines: bool,
+    /// Whether the Kitty graphics protocol is supported.
+    pub kitty_graphics: bool,
+    /// Whether iTerm2 inline images (OSC 1337) are supported.
+    pub iterm2_images: bool,
 }
 
 impl Default for TerminalCapabilities {
@@ -52,6 +61,9 @@ impl Default for TerminalCapabilities {
             focus_events: true,
             hyperlinks: true,
             sixel: false,
+            styled_underlines: true,
+            kitty_graphics: false,
+            iterm2_images: false,
         }
     }
 }
@@ -81,4 +93,24 @@ pub trait Terminal: Send {
 
     /// Disable mouse event capture.
     fn disable_mouse(&mut self) -> Result<()>;
+
+    /// The multiplexer this terminal runs inside, used to wrap escape
+    /// sequences that must reach the outer terminal (e.g. images).
+    fn multiplexer(&self) -> MultiplexerKind {
+        MultiplexerKind::None
+    }
+
+    /// Query the cursor position (0-based), if the terminal can report it.
+    ///
+    /// Used to place inline viewports. Backends that cannot query the
+    /// terminal return `Ok(None)`.
+    fn cursor_position(&mut self) -> Result<Option<Position>> {
+        Ok(None)
+    }
+
+    /// Whether Kitty keyboard enhancement flags are active, i.e. whether
+    /// modified keys like Shift+Enter and key releases are reported.
+    fn keyboard_enhanced(&self) -> bool {
+        false
+    }
 }
@@ -5,6 +5,13 @@
 
 use ropey::Rope;
 use std::fmt;
+use std::ops::Range;
+
+/// Bracket pairs matched by [`TextBuffer::matching_bracket`].
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+/// How far [`TextBuffer::matching_bracket`] scans for a partner.
+const BRACKET_SCAN_LIMIT: usize = 100_000;
 
 /// A text buffer backed by a rope data structure for efficient editing.
 ///
@@ -127,6 +134,173 @@ impl TextBuffer {
         (start..end).filter_map(|i| self.line(i)).collect()
     }
 
+    /// Convert a `(line, col)` position to a character index.
+    ///
+    /// Lines past the end map to the end of the buffer and columns past
+    /// the end of a line map to the end of that line (before its newline).
+    pub fn char_index(&self, line: usize, col: usize) -> usize {
+        if line >= self.rope.len_lines() {
+            return self.rope.len_chars();
+        }
+        let line_start = self.rope.line_to_char(line);
+        line_start + col.min(self.content_len(line))
+    }
+
+    /// Convert a character index to a `(line, col)` position.
+    ///
+    /// Indices past the end map to the end of the buffer.
+    pub fn position_of(&self, char_idx: usize) -> (usize, usize) {
+        let idx = char_idx.min(self.rope.len_chars());
+        let line = self.rope.char_to_line(idx);
+        (line, idx - self.rope.line_to_char(line))
+    }
+
+    /// The character at a character index.
+    pub fn char_at(&self, char_idx: usize) -> Option<char> {
+        self.rope.get_char(char_idx)
+    }
+
+    /// The text in a range of character indices (clamped to the buffer).
+    pub fn slice(&self, range: Range<usize>) -> String {
+        let end = range.end.min(self.rope.len_chars());
+        let start = range.start.min(end);
+        self.rope.slice(start..end).to_string()
+    }
+
+    /// Replace a range of character indices with `text`.
+    pub fn replace(&mut self, range: Range<usize>, text: &str) {
+        let end = range.end.min(self.rope.len_chars());
+        let start = range.start.min(end);
+        if start < end {
+            self.rope.remove(start..end);
+        }
+        if !text.is_empty() {
+            self.rope.insert(start, text);
+        }
+    }
+
+    /// Convert a byte offset into the buffer's text to a character index.
+    pub fn byte_to_char(&self, byte_idx: usize) -> usize {
+        self.rope.byte_to_char(byte_idx.min(self.rope.len_bytes()))
+    }
+
+    /// Convert a character index to a byte offset into the buffer's text.
+    pub fn char_to_byte(&self, char_idx: usize) -> usize {
+        self.rope.char_to_byte(char_idx.min(self.rope.len_chars()))
+    }
+
+    /// The start of the word before `char_idx`: skips whitespace (including
+    /// newlines) backwards, then a run of word characters or of punctuation.
+    pub fn word_start_before(&self, char_idx: usize) -> usize {
+        let mut idx = char_idx.min(self.rope.len_chars());
+        while idx > 0 && char_class(self.rope.char(idx - 1)) == CharClass::Space {
+            idx -= 1;
+        }
+        if idx == 0 {
+            return 0;
+        }
+        let class = char_class(self.rope.char(idx - 1));
+        while idx > 0 && char_class(self.rope.char(idx - 1)) == class {
+            idx -= 1;
+        }
+        idx
+    }
+
+    /// The end of the word after `char_idx`: skips whitespace (including
+    /// newlines), then a run of word characters or of punctuation.
+    pub fn word_end_after(&self, char_idx: usize) -> usize {
+        let len = self.rope.len_chars();
+        let mut idx = char_idx.min(len);
+        while idx < len && char_class(self.rope.char(idx)) == CharClass::Space {
+            idx += 1;
+        }
+        if idx == len {
+            return len;
+        }
+        let class = char_class(self.rope.char(idx));
+        while idx < len && char_class(self.rope.char(idx)) == class {
+            idx += 1;
+        }
+        idx
+    }
+
+    /// The range of the word (or punctuation run) touching `char_idx`,
+    /// preferring the one at the index over the one ending at it.
+    ///
+    /// Returns `None` between two whitespace characters.
+    pub fn word_at(&self, char_idx: usize) -> Option<Range<usize>> {
+        let len = self.rope.len_chars();
+        let idx = char_idx.min(len);
+        let class_at = |i: usize| self.rope.get_char(i).map(char_class);
+        let anchor = match class_at(idx) {
+            Some(c) if c != CharClass::Space => idx,
+            _ if idx > 0 && class_at(idx - 1).is_some_and(|c| c != CharClass::Space) => idx - 1,
+            _ => return None,
+        };
+        let class = class_at(anchor);
+        let mut start = anchor;
+        while start > 0 && class_at(start - 1) == class {
+            start -= 1;
+        }
+        let mut end = anchor + 1;
+        while end < len && class_at(end) == class {
+            end += 1;
+        }
+        Some(start..end)
+    }
+
+    /// The index of the bracket matching the one at `char_idx`, skipping
+    /// nested pairs. Returns `None` if there is no bracket at the index or
+    /// it is unbalanced.
+    pub fn matching_bracket(&self, char_idx: usize) -> Option<usize> {
+        let ch = self.rope.get_char(char_idx)?;
+        let (open, close, forward) = BRACKETS.iter().find_map(|&(open, close)| {
+            if ch == open {
+                Some((open, close, true))
+            } else if ch == close {
+                Some((open, close, false))
+            } else {
+                None
+            }
+        })?;
+        let mut depth = 0usize;
+        let len = self.rope.len_chars();
+        for step in 1..=BRACKET_SCAN_LIMIT {
+            let i = if forward {
+                char_idx + step
+            } else {
+                char_idx.checked_sub(step)?
+            };
+            if i >= len {
+                return None;
+            }
+            let c = self.rope.char(i);
+            if c == open || c == close {
+                if (c == open) == forward {
+                    depth += 1;
+                } else if depth == 0 {
+                    return Some(i);
+                } else {
+                    depth -= 1;
+                }
+            }
+        }
+        None
+    }
+
+    /// Number of characters in a line, excluding its line ending.
+    fn content_len(&self, line: usize) -> usize {
+        let slice = self.rope.line(line);
+        let mut len = slice.len_chars();
+        if len > 0 && slice.char(len - 1) == '\n' {
+            len -= 1;
+            if len > 0 && slice.char(len - 1) == '\r' {
+                len -= 1;
+            }
+        }
+        len
+    }
+
     /// Convert a `(line, col)` position to a character index into the rope.
     ///
     /// Returns `None` if the line is out of bounds. If the column is
@@ -147,6 +321,24 @@ impl TextBuffer {
     }
 }
 
+/// Character classes for word motions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CharClass {
+    Space,
+    Word,
+    Punct,
+}
+
+fn char_class(ch: char) -> CharClass {
+    if ch.is_whitespace() {
+        CharClass::Space
+    } else if ch.is_alphanumeric() || ch == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punct
+    }
+}
+
 impl Default for TextBuffer {
     fn default() -> Self {
         Self::new()
@@ -351,4 +543,45 @@ mod tests {
         assert!(buf.line_count() == 1);
         assert!(buf.total_chars() == 0);
     }
+
+    // --- Character indices and words ---
+
+    #[test]
+    fn char_index_round_trips_and_clamps() {
+        let buf = TextBuffer::from_text("ab\r\ncdé\n");
+        assert_eq!(buf.char_index(1, 2), 6);
+        assert_eq!(buf.position_of(6), (1, 2));
+        // Columns clamp before the line ending, lines past the end clamp to the end.
+        assert_eq!(buf.char_index(0, 10), 2);
+        assert_eq!(buf.char_index(9, 0), buf.total_chars());
+        assert_eq!(buf.char_at(6), Some('é'));
+        assert_eq!(buf.char_to_byte(7), 8);
+        assert_eq!(buf.byte_to_char(8), 7);
+    }
+
+    #[test]
+    fn slice_and_replace() {
+        let mut buf = TextBuffer::from_text("hello world");
+        assert_eq!(buf.slice(6..11), "world");
+        buf.replace(0..5, "goodbye");
+        assert_eq!(buf.to_string(), "goodbye world");
+        buf.replace(7..7, ",");
+        assert_eq!(buf.to_string(), "goodbye, world");
+    }
+
+    #[test]
+    fn word_boundaries() {
+        let buf = TextBuffer::from_text("let foo_bar = a.b;\n  next");
+        assert_eq!(buf.word_end_after(0), 3);
+        assert_eq!(buf.word_end_after(3), 11);
+        assert_eq!(buf.word_end_after(11), 13);
+        assert_eq!(buf.word_start_before(11), 4);
+        assert_eq!(buf.word_start_before(4), 0);
+        // Newlines are whitespace.
+        assert_eq!(buf.word_start_before(21), 17);
+        assert_eq!(buf.word_end_after(18), 25);
+        assert_eq!(buf.word_at(6), Some(4..11));
+        assert_eq!(buf.word_at(11), Some(4..11));
+        assert_eq!(buf.word_at(20), None);
+    }
 }
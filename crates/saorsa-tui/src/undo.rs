@@ -2,7 +2,8 @@
 //!
 //! Provides an [`UndoStack`] that tracks [`EditOperation`] values and
 //! supports bounded undo/redo history. Each operation is invertible so
-//! that undo can be implemented by applying the inverse.
+//! that undo can be implemented by applying the inverse. Operations can
+//! be grouped into a single undo step, and consecutive typing merges.
 
 use crate::cursor::CursorPosition;
 
@@ -32,6 +33,8 @@ pub enum EditOperation {
         /// The new text that replaced it.
         new_text: String,
     },
+    /// Several operations undone and redone as one step, applied in order.
+    Group(Vec<EditOperation>),
 }
 
 impl EditOperation {
@@ -40,6 +43,7 @@ impl EditOperation {
     /// Applying the inverse undoes the original operation:
     /// - `Insert` → `Delete` (and vice versa)
     /// - `Replace` swaps `old_text` and `new_text`
+    /// - `Group` inverts each operation, in reverse order
     pub fn inverse(&self) -> Self {
         match self {
             Self::Insert { pos, text } => Self::Delete {
@@ -59,6 +63,57 @@ impl EditOperation {
                 old_text: new_text.clone(),
                 new_text: old_text.clone(),
             },
+            Self::Group(ops) => Self::Group(ops.iter().rev().map(Self::inverse).collect()),
+        }
+    }
+
+    /// Try to extend this operation with the one that followed it, as when
+    /// typing or backspacing through a line. Returns `false` (leaving both
+    /// untouched) if they are not contiguous single-line edits, or if `next`
+    /// starts a new word.
+    fn merge(&mut self, next: &EditOperation) -> bool {
+        match (self, next) {
+            (
+                Self::Insert { pos, text },
+                Self::Insert {
+                    pos: next_pos,
+                    text: next_text,
+                },
+            ) => {
+                let contiguous =
+                    next_pos.line == pos.line && next_pos.col == pos.col + text.chars().count();
+                let new_word = next_text.starts_with(char::is_whitespace)
+                    && !text.ends_with(char::is_whitespace);
+                if !contiguous || new_word || text.contains('\n') || next_text.contains('\n') {
+                    return false;
+                }
+                text.push_str(next_text);
+                true
+            }
+            (
+                Self::Delete { pos, text },
+                Self::Delete {
+                    pos: next_pos,
+                    text: next_text,
+                },
+            ) => {
+                if text.contains('\n') || next_text.contains('\n') || next_pos.line != pos.line {
+                    return false;
+                }
+                if next_pos.col + next_text.chars().count() == pos.col {
+                    // Backspace.
+                    text.insert_str(0, next_text);
+                    pos.col = next_pos.col;
+                    true
+                } else if next_pos == pos {
+                    // Forward delete.
+                    text.push_str(next_text);
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => false,
         }
     }
 }
@@ -73,6 +128,12 @@ pub struct UndoStack {
     undo_stack: Vec<EditOperation>,
     redo_stack: Vec<EditOperation>,
     max_history: usize,
+    /// Operations pushed while a group is open.
+    group: Option<Vec<EditOperation>>,
+    /// Open [`begin_group`](Self::begin_group) calls.
+    group_depth: usize,
+    /// Whether the last operation may absorb the next merging push.
+    mergeable: bool,
 }
 
 impl UndoStack {
@@ -82,6 +143,9 @@ impl UndoStack {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             max_history,
+            group: None,
+            group_depth: 0,
+            mergeable: false,
         }
     }
 
@@ -91,16 +155,80 @@ impl UndoStack {
     /// `max_history`, the oldest operation is dropped.
     pub fn push(&mut self, op: EditOperation) {
         self.redo_stack.clear();
+        self.mergeable = false;
+        if let Some(group) = &mut self.group {
+            group.push(op);
+            return;
+        }
         self.undo_stack.push(op);
         if self.undo_stack.len() > self.max_history {
             self.undo_stack.remove(0);
         }
     }
 
+    /// Push an operation, merging it into the previous one when both are
+    /// contiguous typing or deletion on one line (so undo removes a word
+    /// at a time rather than a character).
+    ///
+    /// Merging stops after [`seal`](Self::seal), a plain
+    /// [`push`](Self::push), an undo or a redo.
+    pub fn push_merging(&mut self, op: EditOperation) {
+        if self.mergeable
+            && self.group.is_none()
+            && let Some(last) = self.undo_stack.last_mut()
+            && last.merge(&op)
+        {
+            self.redo_stack.clear();
+            return;
+        }
+        self.push(op);
+        self.mergeable = self.group.is_none();
+    }
+
+    /// Stop the next [`push_merging`](Self::push_merging) from merging
+    /// into the last operation, e.g. after the cursor moved.
+    pub fn seal(&mut self) {
+        self.mergeable = false;
+    }
+
+    /// Start grouping pushed operations into one undo step.
+    ///
+    /// Groups nest; the step is recorded when the outermost group ends.
+    pub fn begin_group(&mut self) {
+        if self.group_depth == 0 {
+            self.group = Some(Vec::new());
+        }
+        self.group_depth += 1;
+    }
+
+    /// End a group started with [`begin_group`](Self::begin_group).
+    ///
+    /// A group with a single operation is recorded as that operation, and
+    /// an empty group records nothing.
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth > 0 {
+            return;
+        }
+        let Some(mut ops) = self.group.take() else {
+            return;
+        };
+        match ops.len() {
+            0 => {}
+            1 => {
+                if let Some(op) = ops.pop() {
+                    self.push(op);
+                }
+            }
+            _ => self.push(EditOperation::Group(ops)),
+        }
+    }
+
     /// Pop the most recent operation and return its inverse for undoing.
     ///
     /// The operation is moved to the redo stack.
     pub fn undo(&mut self) -> Option<EditOperation> {
+        self.mergeable = false;
         let op = self.undo_stack.pop()?;
         let inverse = op.inverse();
         self.redo_stack.push(op);
@@ -111,6 +239,7 @@ impl UndoStack {
     ///
     /// The operation is moved back to the undo stack.
     pub fn redo(&mut self) -> Option<EditOperation> {
+        self.mergeable = false;
         let op = self.redo_stack.pop()?;
         let result = op.clone();
         self.undo_stack.push(op);
@@ -131,6 +260,7 @@ impl UndoStack {
     pub fn clear(&mut self) {
         self.undo_stack.clear();
         self.redo_stack.clear();
+        self.mergeable = false;
     }
 }
 
@@ -289,4 +419,71 @@ mod tests {
         assert!(stack.undo().is_none());
         assert!(stack.redo().is_none());
     }
+
+    #[test]
+    fn typing_merges_by_word() {
+        let mut stack = UndoStack::new(100);
+        for (col, ch) in "ab cd".chars().enumerate() {
+            stack.push_merging(insert_op(0, col, &ch.to_string()));
+        }
+        match stack.undo() {
+            Some(EditOperation::Delete { pos, ref text }) if text == " cd" => {
+                assert_eq!(pos, CursorPosition::new(0, 2));
+            }
+            other => unreachable!("expected Delete(' cd'), got {other:?}"),
+        }
+        match stack.undo() {
+            Some(EditOperation::Delete { ref text, .. }) if text == "ab" => {}
+            other => unreachable!("expected Delete('ab'), got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn seal_and_gaps_stop_merging() {
+        let mut stack = UndoStack::new(100);
+        stack.push_merging(insert_op(0, 0, "a"));
+        stack.seal();
+        stack.push_merging(insert_op(0, 1, "b"));
+        stack.push_merging(insert_op(0, 5, "c"));
+        assert!(stack.undo().is_some());
+        assert!(stack.undo().is_some());
+        assert!(stack.undo().is_some());
+        assert!(!stack.can_undo());
+    }
+
+    #[test]
+    fn backspace_and_delete_merge() {
+        let mut stack = UndoStack::new(100);
+        stack.push_merging(delete_op(0, 4, "d"));
+        stack.push_merging(delete_op(0, 3, "c"));
+        stack.push_merging(delete_op(0, 3, "e"));
+        match stack.undo() {
+            Some(EditOperation::Insert { pos, ref text }) if text == "cde" => {
+                assert_eq!(pos, CursorPosition::new(0, 3));
+            }
+            other => unreachable!("expected Insert('cde'), got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn groups_undo_as_one_step() {
+        let mut stack = UndoStack::new(100);
+        stack.begin_group();
+        stack.push(insert_op(1, 0, "x"));
+        stack.begin_group();
+        stack.push(insert_op(0, 0, "x"));
+        stack.end_group();
+        stack.end_group();
+        match stack.undo() {
+            Some(EditOperation::Group(ops)) => {
+                assert_eq!(ops, vec![delete_op(0, 0, "x"), delete_op(1, 0, "x")]);
+            }
+            other => unreachable!("expected a group, got {other:?}"),
+        }
+        assert!(!stack.can_undo());
+
+        stack.begin_group();
+        stack.end_group();
+        assert!(!stack.can_undo());
+    }
 }
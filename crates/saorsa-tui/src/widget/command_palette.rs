@@ -0,0 +1,881 @@
+//! Command palette overlay with fuzzy search over named commands.
+//!
+//! A [`CommandPalette`] lists [`PaletteEntry`] items — application
+//! actions, slash commands, settings — with their descriptions and key
+//! hints. Typing fuzzy-filters the list and highlights the matched
+//! characters; Enter executes the selection. Recently executed entries
+//! are listed first.
+
+use std::cell::Cell as StdCell;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use unicode_width::UnicodeWidthChar;
+
+use crate::app::ActionInfo;
+use crate::buffer::ScreenBuffer;
+use crate::cell::Cell;
+use crate::color::{Color, NamedColor};
+use crate::event::{Event, KeyCode, KeyEvent, Modifiers, MouseEventKind};
+use crate::geometry::{Position, Rect};
+use crate::style::Style;
+
+use super::{BorderStyle, EventResult, InteractiveWidget, Widget};
+
+/// Type alias for the execute callback.
+type ExecuteFn = Option<Box<dyn FnMut(&PaletteEntry)>>;
+
+/// Number of recently executed entries remembered by default.
+const DEFAULT_RECENT_LIMIT: usize = 8;
+
+/// A command listed in a [`CommandPalette`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaletteEntry {
+    /// Identifier the application uses to execute the command.
+    pub id: String,
+    /// Text shown and searched.
+    pub title: String,
+    /// Optional explanation shown after the title, also searched.
+    pub description: Option<String>,
+    /// Optional key chord shown at the right edge, e.g. `Ctrl+P`.
+    pub key_hint: Option<String>,
+}
+
+impl PaletteEntry {
+    /// Create an entry without description or key hint.
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            description: None,
+            key_hint: None,
+        }
+    }
+
+    /// Set the description.
+    #[must_use]
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the key hint.
+    #[must_use]
+    pub fn with_key_hint(mut self, key_hint: impl Into<String>) -> Self {
+        self.key_hint = Some(key_hint.into());
+        self
+    }
+}
+
+/// An entry for an [`App`](crate::app::App) action, identified by the
+/// action's name and hinting at its key bindings.
+impl From<&ActionInfo> for PaletteEntry {
+    fn from(action: &ActionInfo) -> Self {
+        let keys: Vec<String> = action.keys.iter().map(KeyEvent::to_string).collect();
+        Self {
+            id: action.name.clone(),
+            title: action.name.clone(),
+            description: action.description.clone(),
+            key_hint: (!keys.is_empty()).then(|| keys.join(", ")),
+        }
+    }
+}
+
+/// An entry matching the query, with the matched character indices.
+#[derive(Clone, Debug)]
+struct Match {
+    /// Index into the palette's entries.
+    entry: usize,
+    /// Matched character indices in the title.
+    title: Vec<usize>,
+    /// Matched character indices in the description.
+    description: Vec<usize>,
+}
+
+/// A command palette overlay with fuzzy search over named commands.
+///
+/// The palette starts closed; [`open`](Self::open) it, typically from a
+/// key binding, and render it over the application. While open it takes
+/// all key input:
+///
+/// - typing edits the query, Backspace deletes and Ctrl+U clears it
+/// - Up/Down (or Ctrl+P/Ctrl+N), PageUp/PageDown and Home/End move the
+///   selection
+/// - Enter executes the selection and Escape closes the palette
+///
+/// Executing calls the [`with_on_execute`](Self::with_on_execute)
+/// callback and leaves the entry for [`take_executed`](Self::take_executed),
+/// for applications that dispatch from their event loop.
+pub struct CommandPalette {
+    /// All commands, in display order for an empty query.
+    entries: Vec<PaletteEntry>,
+    /// Current search text.
+    query: String,
+    /// Entries matching the query, best first.
+    matches: Vec<Match>,
+    /// Index of the selected match.
+    selected: usize,
+    /// Ids of recently executed entries, most recent first.
+    recent: Vec<String>,
+    /// Maximum number of recent ids kept.
+    recent_limit: usize,
+    /// Whether the palette is shown and takes input.
+    open: bool,
+    /// The last executed entry, until taken.
+    executed: Option<PaletteEntry>,
+    /// Callback run when an entry is executed.
+    on_execute: ExecuteFn,
+    /// Text shown in the prompt while the query is empty.
+    placeholder: String,
+    /// Base style for the border and entries.
+    style: Style,
+    /// Border style.
+    border: BorderStyle,
+    /// Style for the query line.
+    prompt_style: Style,
+    /// Style for the selected entry.
+    selected_style: Style,
+    /// Style merged over matched characters.
+    match_style: Style,
+    /// Style merged over descriptions.
+    description_style: Style,
+    /// Style merged over key hints.
+    key_style: Style,
+    /// Area of the last render, for mouse hit testing and paging.
+    last_area: StdCell<Rect>,
+}
+
+impl CommandPalette {
+    /// Create a closed palette listing `entries`.
+    pub fn new(entries: Vec<PaletteEntry>) -> Self {
+        let mut palette = Self {
+            entries,
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+            recent: Vec::new(),
+            recent_limit: DEFAULT_RECENT_LIMIT,
+            open: false,
+            executed: None,
+            on_execute: None,
+            placeholder: "Type a command…".into(),
+            style: Style::default(),
+            border: BorderStyle::Rounded,
+            prompt_style: Style::new().bold(true),
+            selected_style: Style::new().reverse(true),
+            match_style: Style::new().fg(Color::Named(NamedColor::Yellow)).bold(true),
+            description_style: Style::new().dim(true),
+            key_style: Style::new().fg(Color::Named(NamedColor::Cyan)),
+            last_area: StdCell::new(Rect::new(0, 0, 0, 0)),
+        };
+        palette.refilter();
+        palette
+    }
+
+    /// Set the base style for the border and entries.
+    #[must_use]
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the border style.
+    #[must_use]
+    pub fn with_border(mut self, border: BorderStyle) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// Set the style for the query line.
+    #[must_use]
+    pub fn with_prompt_style(mut self, style: Style) -> Self {
+        self.prompt_style = style;
+        self
+    }
+
+    /// Set the style for the selected entry.
+    #[must_use]
+    pub fn with_selected_style(mut self, style: Style) -> Self {
+        self.selected_style = style;
+        self
+    }
+
+    /// Set the style merged over matched characters.
+    #[must_use]
+    pub fn with_match_style(mut self, style: Style) -> Self {
+        self.match_style = style;
+        self
+    }
+
+    /// Set the style merged over descriptions.
+    #[must_use]
+    pub fn with_description_style(mut self, style: Style) -> Self {
+        self.description_style = style;
+        self
+    }
+
+    /// Set the style merged over key hints.
+    #[must_use]
+    pub fn with_key_style(mut self, style: Style) -> Self {
+        self.key_style = style;
+        self
+    }
+
+    /// Set the text shown while the query is empty.
+    #[must_use]
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Set how many recently executed entries are remembered (default 8).
+    #[must_use]
+    pub fn with_recent_limit(mut self, limit: usize) -> Self {
+        self.recent_limit = limit;
+        self.recent.truncate(limit);
+        self
+    }
+
+    /// Set a callback invoked when an entry is executed.
+    #[must_use]
+    pub fn with_on_execute<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&PaletteEntry) + 'static,
+    {
+        self.on_execute = Some(Box::new(f));
+        self
+    }
+
+    /// Set the base style for the border and entries.
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    /// Get the border style.
+    pub fn border_style_kind(&self) -> BorderStyle {
+        self.border
+    }
+
+    /// Set the border style.
+    pub fn set_border(&mut self, border: BorderStyle) {
+        self.border = border;
+    }
+
+    /// All entries.
+    pub fn entries(&self) -> &[PaletteEntry] {
+        &self.entries
+    }
+
+    /// Replace the entries, keeping the query and the recent history.
+    pub fn set_entries(&mut self, entries: Vec<PaletteEntry>) {
+        self.entries = entries;
+        self.refilter();
+    }
+
+    /// Show the palette with an empty query.
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.refilter();
+    }
+
+    /// Hide the palette.
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Whether the palette is shown and takes input.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// The search text.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Replace the search text and filter the entries.
+    pub fn set_query(&mut self, query: &str) {
+        self.query = query.to_string();
+        self.refilter();
+    }
+
+    /// The entries matching the query, in display order.
+    pub fn matches(&self) -> Vec<&PaletteEntry> {
+        self.matches
+            .iter()
+            .filter_map(|m| self.entries.get(m.entry))
+            .collect()
+    }
+
+    /// The selected entry, if any entry matches.
+    pub fn selected_entry(&self) -> Option<&PaletteEntry> {
+        self.matches
+            .get(self.selected)
+            .and_then(|m| self.entries.get(m.entry))
+    }
+
+    /// Move the selection by `delta` matches (positive = down), clamped.
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.matches.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Execute the selected entry: remember it as recent, run the
+    /// callback and close the palette. Returns the executed entry.
+    pub fn execute(&mut self) -> Option<PaletteEntry> {
+        let entry = self.selected_entry()?.clone();
+        self.mark_used(&entry.id);
+        if let Some(callback) = &mut self.on_execute {
+            callback(&entry);
+        }
+        self.open = false;
+        self.executed = Some(entry.clone());
+        Some(entry)
+    }
+
+    /// Take the entry executed since the last call, if any.
+    pub fn take_executed(&mut self) -> Option<PaletteEntry> {
+        self.executed.take()
+    }
+
+    /// Ids of recently executed entries, most recent first.
+    pub fn recent(&self) -> &[String] {
+        &self.recent
+    }
+
+    /// Replace the recent history, e.g. with one saved by a previous
+    /// session. Ids are ordered most recent first.
+    pub fn set_recent(&mut self, ids: Vec<String>) {
+        self.recent = ids;
+        self.recent.truncate(self.recent_limit);
+        self.refilter();
+    }
+
+    /// Record `id` as the most recently used entry, as if it had been
+    /// executed from the palette.
+    pub fn mark_used(&mut self, id: &str) {
+        self.recent.retain(|recent| recent != id);
+        self.recent.insert(0, id.to_string());
+        self.recent.truncate(self.recent_limit);
+    }
+
+    /// Position of `id` in the recent history, if present.
+    fn recent_rank(&self, id: &str) -> Option<usize> {
+        self.recent.iter().position(|recent| recent == id)
+    }
+
+    /// Recompute the matches for the query and select the best one.
+    ///
+    /// Without a query, recent entries come first and the rest keep their
+    /// order. Otherwise entries are ranked by fuzzy score, with matches in
+    /// the description ranked below similar matches in the title and ties
+    /// going to the more recently used entry.
+    fn refilter(&mut self) {
+        self.selected = 0;
+        let query = self.query.trim();
+        if query.is_empty() {
+            let mut order: Vec<(Option<usize>, usize)> = self
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| (self.recent_rank(&entry.id), i))
+                .collect();
+            // `None` sorts before `Some`, so sort on the rank's absence.
+            order.sort_by_key(|&(rank, i)| (rank.is_none(), rank, i));
+            self.matches = order
+                .into_iter()
+                .map(|(_, entry)| Match {
+                    entry,
+                    title: Vec::new(),
+                    description: Vec::new(),
+                })
+                .collect();
+            return;
+        }
+
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, usize, Match)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(entry, e)| {
+                if let Some((score, title)) = matcher.fuzzy_indices(&e.title, query) {
+                    return Some((
+                        score,
+                        entry,
+                        Match {
+                            entry,
+                            title,
+                            description: Vec::new(),
+                        },
+                    ));
+                }
+                let description = e.description.as_deref()?;
+                let (score, description) = matcher.fuzzy_indices(description, query)?;
+                Some((
+                    score / 2,
+                    entry,
+                    Match {
+                        entry,
+                        title: Vec::new(),
+                        description,
+                    },
+                ))
+            })
+            .collect();
+        scored.sort_by_key(|(score, entry, _)| {
+            let rank = self.recent_rank(&self.entries[*entry].id);
+            (std::cmp::Reverse(*score), rank.is_none(), rank, *entry)
+        });
+        self.matches = scored.into_iter().map(|(_, _, m)| m).collect();
+    }
+
+    /// Number of entry rows that fit in the last rendered area.
+    fn page_rows(&self) -> usize {
+        let inner = super::border::inner_area(self.last_area.get(), self.border);
+        usize::from(inner.size.height.saturating_sub(1)).max(1)
+    }
+
+    /// Index of the first match shown in `rows` rows.
+    fn first_visible(&self, rows: usize) -> usize {
+        (self.selected + 1).saturating_sub(rows)
+    }
+
+    fn handle_key(&mut self, key: &KeyEvent) -> EventResult {
+        let ctrl = key.modifiers.contains(Modifiers::CTRL);
+        match key.code {
+            KeyCode::Escape => self.close(),
+            KeyCode::Enter => {
+                self.execute();
+            }
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('p') if ctrl => self.move_selection(-1),
+            KeyCode::Char('n') if ctrl => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-(self.page_rows() as isize)),
+            KeyCode::PageDown => self.move_selection(self.page_rows() as isize),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = self.matches.len().saturating_sub(1),
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.refilter();
+            }
+            KeyCode::Backspace if !self.query.is_empty() => {
+                self.query.pop();
+                self.refilter();
+            }
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(Modifiers::ALT) => {
+                self.query.push(c);
+                self.refilter();
+            }
+            // The palette is modal: other keys do nothing while it is open.
+            _ => {}
+        }
+        EventResult::Consumed
+    }
+
+    /// Select the match under a click, returning whether there is one.
+    fn select_at(&mut self, x: u16, y: u16) -> bool {
+        let inner = super::border::inner_area(self.last_area.get(), self.border);
+        if !inner.contains(Position::new(x, y)) || y == inner.position.y {
+            return false;
+        }
+        let rows = usize::from(inner.size.height.saturating_sub(1));
+        let index = self.first_visible(rows) + usize::from(y - inner.position.y - 1);
+        if index >= self.matches.len() {
+            return false;
+        }
+        self.selected = index;
+        true
+    }
+
+    /// Draw the query line, or the placeholder, with a block cursor.
+    fn render_prompt(&self, inner: Rect, buf: &mut ScreenBuffer) {
+        let y = inner.position.y;
+        let end = inner.position.x + inner.size.width;
+        let fill = self.style.merge(&self.prompt_style);
+        fill_row(buf, inner, y, &fill);
+        let mut x = draw_chars(buf, inner.position.x, y, end, "> ".chars(), &fill);
+        if self.query.is_empty() {
+            let cursor = fill.merge(&Style::new().reverse(true));
+            x = draw_chars(buf, x, y, end, " ".chars(), &cursor);
+            let placeholder = fill.merge(&self.description_style);
+            draw_chars(buf, x, y, end, self.placeholder.chars(), &placeholder);
+        } else {
+            x = draw_chars(buf, x, y, end, self.query.chars(), &fill);
+            let cursor = fill.merge(&Style::new().reverse(true));
+            draw_chars(buf, x, y, end, " ".chars(), &cursor);
+        }
+    }
+
+    /// Draw one match: its title and description with the matched
+    /// characters highlighted, and its key hint at the right edge.
+    fn render_match(&self, m: &Match, selected: bool, inner: Rect, y: u16, buf: &mut ScreenBuffer) {
+        let Some(entry) = self.entries.get(m.entry) else {
+            return;
+        };
+        let base = if selected {
+            self.style.merge(&self.selected_style)
+        } else {
+            self.style.clone()
+        };
+        fill_row(buf, inner, y, &base);
+
+        let mut end = inner.position.x + inner.size.width;
+        if let Some(hint) = &entry.key_hint {
+            let width: usize = hint.chars().filter_map(UnicodeWidthChar::width).sum();
+            // Keep room for at least a few characters of the title.
+            if width + 6 <= usize::from(inner.size.width) {
+                let x = end - width as u16;
+                let style = base.merge(&self.key_style);
+                draw_chars(buf, x, y, end, hint.chars(), &style);
+                end = x.saturating_sub(1);
+            }
+        }
+
+        let highlighted = |text: &str, indices: &[usize], style: Style| {
+            let matched = style.merge(&self.match_style);
+            text.chars()
+                .enumerate()
+                .map(|(i, ch)| {
+                    let style = if indices.contains(&i) {
+                        matched.clone()
+                    } else {
+                        style.clone()
+                    };
+                    (ch, style)
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut x = inner.position.x + 1;
+        for (ch, style) in highlighted(&entry.title, &m.title, base.clone()) {
+            x = draw_chars(buf, x, y, end, std::iter::once(ch), &style);
+        }
+        if let Some(description) = &entry.description {
+            let style = base.merge(&self.description_style);
+            x = draw_chars(buf, x, y, end, "  ".chars(), &style);
+            for (ch, style) in highlighted(description, &m.description, style) {
+                x = draw_chars(buf, x, y, end, std::iter::once(ch), &style);
+            }
+        }
+    }
+}
+
+/// Fill row `y` of `area` with blanks in `style`.
+fn fill_row(buf: &mut ScreenBuffer, area: Rect, y: u16, style: &Style) {
+    for x in area.position.x..area.position.x + area.size.width {
+        buf.set(x, y, Cell::new(" ", style.clone()));
+    }
+}
+
+/// Draw `chars` from column `x` up to (excluding) `end`, skipping
+/// zero-width characters. Returns the column after the last drawn one.
+fn draw_chars(
+    buf: &mut ScreenBuffer,
+    mut x: u16,
+    y: u16,
+    end: u16,
+    chars: impl Iterator<Item = char>,
+    style: &Style,
+) -> u16 {
+    for ch in chars {
+        let width = ch.width().unwrap_or(0) as u16;
+        if width == 0 {
+            continue;
+        }
+        if x + width > end {
+            break;
+        }
+        buf.set(x, y, Cell::new(ch.to_string(), style.clone()));
+        x += width;
+    }
+    x
+}
+
+impl Widget for CommandPalette {
+    fn render(&self, area: Rect, buf: &mut ScreenBuffer) {
+        self.last_area.set(area);
+        if !self.open || area.size.width == 0 || area.size.height == 0 {
+            return;
+        }
+        super::border::render_border(area, self.border, self.style.clone(), buf);
+        let inner = super::border::inner_area(area, self.border);
+        if inner.size.width == 0 || inner.size.height == 0 {
+            return;
+        }
+        self.render_prompt(inner, buf);
+
+        let rows = usize::from(inner.size.height.saturating_sub(1));
+        for row in 0..rows {
+            fill_row(buf, inner, inner.position.y + 1 + row as u16, &self.style);
+        }
+        if self.matches.is_empty() && rows > 0 {
+            let style = self.style.merge(&self.description_style);
+            let end = inner.position.x + inner.size.width;
+            let text = "No matching commands".chars();
+            draw_chars(
+                buf,
+                inner.position.x + 1,
+                inner.position.y + 1,
+                end,
+                text,
+                &style,
+            );
+            return;
+        }
+        let first = self.first_visible(rows);
+        for (row, (index, m)) in self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(rows)
+            .enumerate()
+        {
+            let y = inner.position.y + 1 + row as u16;
+            self.render_match(m, index == self.selected, inner, y, buf);
+        }
+    }
+}
+
+impl InteractiveWidget for CommandPalette {
+    fn handle_event(&mut self, event: &Event) -> EventResult {
+        if !self.open {
+            return EventResult::Ignored;
+        }
+        match event {
+            Event::Key(key) => self.handle_key(key),
+            Event::Paste(text) => {
+                self.query.extend(text.chars().filter(|c| !c.is_control()));
+                self.refilter();
+                EventResult::Consumed
+            }
+            Event::Mouse(mouse) => {
+                match mouse.kind {
+                    MouseEventKind::ScrollUp => self.move_selection(-1),
+                    MouseEventKind::ScrollDown => self.move_selection(1),
+                    MouseEventKind::Press => {
+                        let position = Position::new(mouse.x, mouse.y);
+                        if !self.last_area.get().contains(position) {
+                            // Clicking outside dismisses the palette.
+                            self.close();
+                        } else if self.select_at(mouse.x, mouse.y) {
+                            self.execute();
+                        }
+                    }
+                    _ => {}
+                }
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::MouseEvent;
+    use crate::geometry::Size;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn palette() -> CommandPalette {
+        let mut palette = CommandPalette::new(vec![
+            PaletteEntry::new("help", "Help").with_description("Show available commands"),
+            PaletteEntry::new("model", "Switch model").with_key_hint("Ctrl+P"),
+            PaletteEntry::new("clear", "Clear conversation"),
+            PaletteEntry::new("quit", "Quit").with_key_hint("Ctrl+C"),
+        ]);
+        palette.open();
+        palette
+    }
+
+    fn key(palette: &mut CommandPalette, code: KeyCode) -> EventResult {
+        palette.handle_event(&Event::Key(KeyEvent::plain(code)))
+    }
+
+    fn type_text(palette: &mut CommandPalette, text: &str) {
+        for c in text.chars() {
+            key(palette, KeyCode::Char(c));
+        }
+    }
+
+    fn ids(palette: &CommandPalette) -> Vec<&str> {
+        palette.matches().iter().map(|e| e.id.as_str()).collect()
+    }
+
+    fn row_text(buf: &ScreenBuffer, y: u16, width: u16) -> String {
+        (0..width)
+            .filter_map(|x| buf.get(x, y).map(|c| c.grapheme.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn filters_fuzzily_and_searches_descriptions() {
+        let mut palette = palette();
+        assert_eq!(ids(&palette), ["help", "model", "clear", "quit"]);
+
+        type_text(&mut palette, "cnv");
+        assert_eq!(ids(&palette), ["clear"]);
+
+        key(&mut palette, KeyCode::Backspace);
+        key(&mut palette, KeyCode::Backspace);
+        key(&mut palette, KeyCode::Backspace);
+        // "available" only occurs in a description.
+        type_text(&mut palette, "avail");
+        assert_eq!(ids(&palette), ["help"]);
+
+        palette.set_query("zzz");
+        assert!(palette.matches().is_empty());
+        assert!(palette.selected_entry().is_none());
+        assert!(palette.execute().is_none());
+    }
+
+    #[test]
+    fn executes_and_remembers_recent_entries() {
+        let executed = Rc::new(RefCell::new(Vec::new()));
+        let log = executed.clone();
+        let mut palette = palette().with_on_execute(move |e| log.borrow_mut().push(e.id.clone()));
+        palette.open();
+
+        key(&mut palette, KeyCode::Down);
+        key(&mut palette, KeyCode::Down);
+        assert_eq!(key(&mut palette, KeyCode::Enter), EventResult::Consumed);
+        assert_eq!(*executed.borrow(), ["clear"]);
+        assert!(!palette.is_open());
+        assert_eq!(palette.take_executed().map(|e| e.id), Some("clear".into()));
+        assert!(palette.take_executed().is_none());
+        // Closed palettes ignore input.
+        assert_eq!(key(&mut palette, KeyCode::Enter), EventResult::Ignored);
+
+        palette.open();
+        assert_eq!(ids(&palette), ["clear", "help", "model", "quit"]);
+        palette.mark_used("quit");
+        palette.open();
+        assert_eq!(ids(&palette), ["quit", "clear", "help", "model"]);
+        assert_eq!(palette.recent(), ["quit", "clear"]);
+
+        // Recency breaks ties between equally good matches.
+        let mut palette = CommandPalette::new(vec![
+            PaletteEntry::new("a", "Model: alpha"),
+            PaletteEntry::new("b", "Model: beta"),
+        ])
+        .with_recent_limit(1);
+        palette.set_recent(vec!["b".into(), "a".into()]);
+        assert_eq!(palette.recent(), ["b"]);
+        palette.set_query("model");
+        assert_eq!(ids(&palette), ["b", "a"]);
+    }
+
+    #[test]
+    fn navigation_and_escape() {
+        let mut palette = palette();
+        key(&mut palette, KeyCode::Up);
+        assert_eq!(
+            palette.selected_entry().map(|e| e.id.as_str()),
+            Some("help")
+        );
+        key(&mut palette, KeyCode::End);
+        assert_eq!(
+            palette.selected_entry().map(|e| e.id.as_str()),
+            Some("quit")
+        );
+        key(&mut palette, KeyCode::Down);
+        assert_eq!(
+            palette.selected_entry().map(|e| e.id.as_str()),
+            Some("quit")
+        );
+        let ctrl_p = KeyEvent::new(KeyCode::Char('p'), Modifiers::CTRL);
+        palette.handle_event(&Event::Key(ctrl_p));
+        assert_eq!(
+            palette.selected_entry().map(|e| e.id.as_str()),
+            Some("clear")
+        );
+
+        type_text(&mut palette, "qu");
+        let ctrl_u = KeyEvent::new(KeyCode::Char('u'), Modifiers::CTRL);
+        palette.handle_event(&Event::Key(ctrl_u));
+        assert_eq!(palette.query(), "");
+        // Unbound keys are swallowed while open.
+        assert_eq!(key(&mut palette, KeyCode::F(3)), EventResult::Consumed);
+        key(&mut palette, KeyCode::Escape);
+        assert!(!palette.is_open());
+        assert!(palette.take_executed().is_none());
+    }
+
+    #[test]
+    fn renders_matches_with_highlights_and_key_hints() {
+        let mut palette = palette();
+        let mut buf = ScreenBuffer::new(Size::new(40, 6));
+        palette.render(Rect::new(0, 0, 40, 6), &mut buf);
+        assert_eq!(
+            row_text(&buf, 1, 40).trim_end_matches(['│', ' ']),
+            "│>  Type a command…"
+        );
+        assert!(row_text(&buf, 2, 40).starts_with("│ Help  Show available commands"));
+        assert_eq!(
+            row_text(&buf, 3, 40),
+            format!("│ Switch model{}Ctrl+P│", " ".repeat(19))
+        );
+        // The selected row is highlighted.
+        assert!(buf.get(2, 2).is_some_and(|c| c.style.reverse));
+        assert!(buf.get(2, 3).is_some_and(|c| !c.style.reverse));
+
+        palette.set_query("swmo");
+        palette.render(Rect::new(0, 0, 40, 6), &mut buf);
+        assert!(row_text(&buf, 1, 40).starts_with("│> swmo "));
+        let bold = |x| buf.get(x, 2).is_some_and(|c| c.style.bold);
+        // "Sw" of "Switch" and "mo" of "model" are highlighted.
+        assert!(bold(2) && bold(3) && !bold(4) && bold(9) && bold(10));
+        assert_eq!(row_text(&buf, 3, 40).trim_matches(['│', ' ']), "");
+
+        palette.set_query("zzz");
+        palette.render(Rect::new(0, 0, 40, 6), &mut buf);
+        assert!(row_text(&buf, 2, 40).contains("No matching commands"));
+
+        palette.close();
+        let mut buf = ScreenBuffer::new(Size::new(40, 6));
+        palette.render(Rect::new(0, 0, 40, 6), &mut buf);
+        assert_eq!(row_text(&buf, 0, 40).trim(), "");
+    }
+
+    #[test]
+    fn mouse_clicks_execute_and_dismiss() {
+        let mut palette = palette();
+        let mut buf = ScreenBuffer::new(Size::new(40, 10));
+        palette.render(Rect::new(0, 0, 40, 6), &mut buf);
+        let click = |x, y| {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Press,
+                x,
+                y,
+                modifiers: Modifiers::NONE,
+            })
+        };
+        palette.handle_event(&click(5, 4));
+        assert_eq!(palette.take_executed().map(|e| e.id), Some("clear".into()));
+
+        palette.open();
+        palette.handle_event(&click(5, 8));
+        assert!(!palette.is_open());
+        assert!(palette.take_executed().is_none());
+    }
+
+    #[test]
+    fn entries_from_app_actions() {
+        let action = ActionInfo {
+            name: "save".into(),
+            description: Some("Save the file".into()),
+            keys: vec![
+                KeyEvent::new(KeyCode::Char('s'), Modifiers::CTRL),
+                KeyEvent::plain(KeyCode::F(2)),
+            ],
+        };
+        let entry = PaletteEntry::from(&action);
+        assert_eq!(entry.id, "save");
+        assert_eq!(entry.description.as_deref(), Some("Save the file"));
+        assert_eq!(entry.key_hint.as_deref(), Some("Ctrl+S, F2"));
+    }
+}
@@ -1,19 +1,35 @@
 //! Scrollable data table widget with columns and rows.
 //!
-//! Displays tabular data with column headers, row selection,
-//! vertical/horizontal scrolling, and keyboard navigation.
+//! Displays tabular data with column headers, row or cell selection,
+//! multi-column sorting, per-column filters, frozen columns, and
+//! vertical/horizontal scrolling. Rows come from a [`TableSource`] and
+//! only the rows on screen are fetched when rendering.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ops::Range;
 
 use crate::buffer::ScreenBuffer;
 use crate::cell::Cell;
-use crate::event::{Event, KeyCode, KeyEvent};
+use crate::clipboard::Clipboard;
+use crate::error::Result;
+use crate::event::{Event, KeyCode, KeyEvent, Modifiers};
 use crate::geometry::Rect;
 use crate::style::Style;
-use crate::text::truncate_to_display_width;
 use crate::widget::label::Alignment;
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
+use super::table_filter::FilterExpr;
+use super::table_source::{TableCell, TableSource};
 use super::{BorderStyle, EventResult, InteractiveWidget, Widget};
 
+/// Rows read from the source per call while sorting or filtering.
+const SCAN_CHUNK: usize = 4096;
+
+/// Rows moved by PageUp/PageDown.
+const PAGE_ROWS: usize = 20;
+
 /// A column definition for a [`DataTable`].
 #[derive(Clone, Debug)]
 pub struct Column {
@@ -43,21 +59,93 @@ impl Column {
     }
 }
 
+/// One key of a (multi-column) sort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortKey {
+    /// Column index.
+    pub column: usize,
+    /// Sort direction.
+    pub ascending: bool,
+}
+
+impl SortKey {
+    /// Sort `column` in ascending order.
+    pub fn ascending(column: usize) -> Self {
+        Self {
+            column,
+            ascending: true,
+        }
+    }
+
+    /// Sort `column` in descending order.
+    pub fn descending(column: usize) -> Self {
+        Self {
+            column,
+            ascending: false,
+        }
+    }
+}
+
+/// Where a table's rows live.
+enum TableRows {
+    Owned(Vec<Vec<TableCell>>),
+    Source(Box<dyn TableSource>),
+}
+
+impl TableRows {
+    fn source(&self) -> &dyn TableSource {
+        match self {
+            Self::Owned(rows) => rows,
+            Self::Source(source) => source.as_ref(),
+        }
+    }
+}
+
+/// The on-screen part of one column (including its trailing separator).
+#[derive(Clone, Copy, Debug)]
+struct ColumnSpan {
+    column: usize,
+    /// Screen column, relative to the table's inner area.
+    x: u16,
+    /// Cells of the column scrolled off to the left.
+    skip: u16,
+    /// Visible cells.
+    width: u16,
+}
+
 /// A scrollable data table widget with columns and rows.
 ///
-/// Supports keyboard navigation, row selection, and both vertical
-/// and horizontal scrolling.
+/// Rows are read from a [`TableSource`]: either rows owned by the table
+/// ([`Self::push_row`], [`Self::set_rows`]) or an external source such as
+/// a [`CsvSource`](super::CsvSource) set with [`Self::set_source`]. Only
+/// visible rows are fetched for rendering; sorting and filtering scan the
+/// source once in chunks, keeping just the key cells, and then map display
+/// rows to source rows.
+///
+/// Supports keyboard navigation, row or cell selection with copy,
+/// multi-column sorting, per-column [`FilterExpr`] filters, frozen
+/// columns, and both vertical and horizontal scrolling.
 pub struct DataTable {
     /// Column definitions.
     columns: Vec<Column>,
-    /// Row data: each row is a `Vec<String>`, one per column.
-    rows: Vec<Vec<String>>,
-    /// Selected row index.
+    /// Row data.
+    rows: TableRows,
+    /// Source row index of each display row, when sorted or filtered.
+    view: Option<Vec<usize>>,
+    /// Selected (display) row index.
     selected_row: usize,
+    /// Selected column index, used in cell selection mode.
+    selected_column: usize,
+    /// Other corner of a range selection: (row, column).
+    anchor: Option<(usize, usize)>,
     /// Scroll offset (first visible row index).
     row_offset: usize,
-    /// Horizontal scroll offset (pixel offset in characters).
+    /// Horizontal scroll offset of the unfrozen columns, in characters.
     col_offset: u16,
+    /// Number of leading columns that do not scroll horizontally.
+    frozen_columns: usize,
+    /// Whether single cells rather than whole rows are selected.
+    cell_selection: bool,
     /// Style for headers.
     header_style: Style,
     /// Style for unselected rows.
@@ -66,12 +154,13 @@ pub struct DataTable {
     selected_style: Style,
     /// Border style.
     border: BorderStyle,
-    /// Sort state: (column_index, ascending).
-    sort_state: Option<(usize, bool)>,
+    /// Sort keys, most significant first.
+    sort: Vec<SortKey>,
+    /// Filter per column index.
+    filters: BTreeMap<usize, FilterExpr>,
     /// Whether columns can be resized.
     resizable_columns: bool,
-    /// Original row order (for restoring after clear_sort).
-    original_order: Vec<usize>,
+    clipboard: Clipboard,
 }
 
 impl DataTable {
@@ -79,17 +168,23 @@ impl DataTable {
     pub fn new(columns: Vec<Column>) -> Self {
         Self {
             columns,
-            rows: Vec::new(),
+            rows: TableRows::Owned(Vec::new()),
+            view: None,
             selected_row: 0,
+            selected_column: 0,
+            anchor: None,
             row_offset: 0,
             col_offset: 0,
+            frozen_columns: 0,
+            cell_selection: false,
             header_style: Style::default().bold(true),
             row_style: Style::default(),
             selected_style: Style::default().reverse(true),
             border: BorderStyle::None,
-            sort_state: None,
+            sort: Vec::new(),
+            filters: BTreeMap::new(),
             resizable_columns: false,
-            original_order: Vec::new(),
+            clipboard: Clipboard::new(),
         }
     }
 
@@ -121,23 +216,108 @@ impl DataTable {
         self
     }
 
-    /// Add a row of data.
+    /// Read rows from an external source.
+    #[must_use]
+    pub fn with_source(mut self, source: Box<dyn TableSource>) -> Self {
+        self.set_source(source);
+        self
+    }
+
+    /// Keep the first `count` columns in place when scrolling horizontally.
+    #[must_use]
+    pub fn with_frozen_columns(mut self, count: usize) -> Self {
+        self.frozen_columns = count;
+        self
+    }
+
+    /// Select single cells (moved with Left/Right) instead of whole rows.
+    #[must_use]
+    pub fn with_cell_selection(mut self, enabled: bool) -> Self {
+        self.cell_selection = enabled;
+        self
+    }
+
+    /// Use a shared clipboard (e.g. [`crate::app::App::clipboard`]).
+    #[must_use]
+    pub fn with_clipboard(mut self, clipboard: Clipboard) -> Self {
+        self.clipboard = clipboard;
+        self
+    }
+
+    /// The clipboard used for copying.
+    pub fn clipboard(&self) -> &Clipboard {
+        &self.clipboard
+    }
+
+    /// Add a row of text cells.
+    ///
+    /// Has no effect while rows come from an external [`TableSource`].
     pub fn push_row(&mut self, row: Vec<String>) {
-        self.rows.push(row);
+        self.push_cells(row.into_iter().map(TableCell::Text).collect());
+    }
+
+    /// Add a row of typed cells.
+    ///
+    /// Has no effect while rows come from an external [`TableSource`].
+    pub fn push_cells(&mut self, row: Vec<TableCell>) {
+        let TableRows::Owned(rows) = &mut self.rows else {
+            return;
+        };
+        rows.push(row);
+        if self.view.is_some() {
+            self.rebuild_view();
+        }
     }
 
-    /// Set all rows at once, resetting selection.
+    /// Set all rows at once as text cells, resetting selection and sort.
     pub fn set_rows(&mut self, rows: Vec<Vec<String>>) {
+        self.set_cell_rows(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(TableCell::Text).collect())
+                .collect(),
+        );
+    }
+
+    /// Set all rows at once as typed cells, resetting selection and sort.
+    pub fn set_cell_rows(&mut self, rows: Vec<Vec<TableCell>>) {
+        self.replace_rows(TableRows::Owned(rows));
+    }
+
+    /// Read rows from an external source, resetting selection and sort.
+    ///
+    /// Filters are kept and applied to the new rows.
+    pub fn set_source(&mut self, source: Box<dyn TableSource>) {
+        self.replace_rows(TableRows::Source(source));
+    }
+
+    fn replace_rows(&mut self, rows: TableRows) {
         self.rows = rows;
         self.selected_row = 0;
         self.row_offset = 0;
-        self.sort_state = None;
-        self.original_order.clear();
+        self.anchor = None;
+        self.sort.clear();
+        self.rebuild_view();
+    }
+
+    /// Re-read the source and re-apply sorting and filtering, e.g. after
+    /// an external source's data changed.
+    pub fn refresh(&mut self) {
+        self.rebuild_view();
+        self.anchor = None;
+        self.set_selected_row(self.selected_row);
     }
 
-    /// Get the number of rows.
+    /// Get the number of rows shown (after filtering).
     pub fn row_count(&self) -> usize {
-        self.rows.len()
+        match &self.view {
+            Some(view) => view.len(),
+            None => self.total_row_count(),
+        }
+    }
+
+    /// Get the number of rows in the source, ignoring filters.
+    pub fn total_row_count(&self) -> usize {
+        self.rows.source().row_count()
     }
 
     /// Get the number of columns.
@@ -152,16 +332,43 @@ impl DataTable {
 
     /// Set the selected row (clamped to valid range).
     pub fn set_selected_row(&mut self, idx: usize) {
-        if self.rows.is_empty() {
-            self.selected_row = 0;
-        } else {
-            self.selected_row = idx.min(self.rows.len().saturating_sub(1));
-        }
+        self.selected_row = idx.min(self.row_count().saturating_sub(1));
+    }
+
+    /// Get the selected column index.
+    pub fn selected_column(&self) -> usize {
+        self.selected_column
+    }
+
+    /// Set the selected column (clamped to valid range).
+    pub fn set_selected_column(&mut self, idx: usize) {
+        self.selected_column = idx.min(self.columns.len().saturating_sub(1));
+        self.scroll_to_selected_column();
+    }
+
+    /// Get the cells of a displayed row as text.
+    pub fn row_data(&self, idx: usize) -> Option<Vec<String>> {
+        self.row_cells(idx)
+            .map(|row| row.iter().map(TableCell::text).collect())
+    }
+
+    /// Get the cells of a displayed row.
+    pub fn row_cells(&self, idx: usize) -> Option<Vec<TableCell>> {
+        self.fetch(idx..idx.saturating_add(1)).into_iter().next()
     }
 
     /// Get the data for the selected row.
-    pub fn selected_row_data(&self) -> Option<&[String]> {
-        self.rows.get(self.selected_row).map(|r| r.as_slice())
+    pub fn selected_row_data(&self) -> Option<Vec<String>> {
+        self.row_data(self.selected_row)
+    }
+
+    /// Get the selected cell.
+    pub fn selected_cell(&self) -> Option<TableCell> {
+        self.row_cells(self.selected_row)
+            .map(|mut row| match row.get_mut(self.selected_column) {
+                Some(cell) => std::mem::take(cell),
+                None => TableCell::Empty,
+            })
     }
 
     /// Get the column definitions.
@@ -174,6 +381,63 @@ impl DataTable {
         self.col_offset
     }
 
+    /// Get the number of frozen columns.
+    pub fn frozen_columns(&self) -> usize {
+        self.frozen_columns
+    }
+
+    // --- Selection and copy ---
+
+    /// The selected rows and columns.
+    ///
+    /// Shift with the arrow keys extends the selection from where it
+    /// started. Without cell selection every column of the selected rows is
+    /// included.
+    pub fn selection_range(&self) -> (Range<usize>, Range<usize>) {
+        let (row, col) = (self.selected_row, self.selected_column);
+        let (anchor_row, anchor_col) = self.anchor.unwrap_or((row, col));
+        let rows = row.min(anchor_row)..row.max(anchor_row) + 1;
+        let cols = if self.cell_selection {
+            col.min(anchor_col)..col.max(anchor_col) + 1
+        } else {
+            0..self.columns.len()
+        };
+        (rows, cols)
+    }
+
+    /// The selected cells as text: cells separated by tabs, rows by
+    /// newlines.
+    pub fn selected_text(&self) -> Option<String> {
+        if self.row_count() == 0 || self.columns.is_empty() {
+            return None;
+        }
+        let (rows, cols) = self.selection_range();
+        let lines: Vec<String> = self
+            .fetch(rows)
+            .iter()
+            .map(|row| {
+                cols.clone()
+                    .map(|c| row.get(c).map(TableCell::text).unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    /// Copy the selected cells to the clipboard.
+    ///
+    /// Returns `true` if there was anything to copy.
+    pub fn copy(&mut self) -> bool {
+        match self.selected_text() {
+            Some(text) => {
+                self.clipboard.set_text(text);
+                true
+            }
+            None => false,
+        }
+    }
+
     // --- Sorting API ---
 
     /// Enable column resizing.
@@ -183,7 +447,7 @@ impl DataTable {
         self
     }
 
-    /// Sort by the given column index (toggle ascending/descending).
+    /// Sort by the given column index alone (toggle ascending/descending).
     ///
     /// First call sorts ascending; repeated calls on the same column
     /// toggle direction.
@@ -191,58 +455,110 @@ impl DataTable {
         if col_idx >= self.columns.len() {
             return;
         }
-
-        // Save original order if not yet saved
-        if self.original_order.is_empty() {
-            self.original_order = (0..self.rows.len()).collect();
-        }
-
-        let ascending = match self.sort_state {
-            Some((prev_col, prev_asc)) if prev_col == col_idx => !prev_asc,
+        let ascending = match self.sort.as_slice() {
+            [only] if only.column == col_idx => !only.ascending,
             _ => true,
         };
+        self.set_sort(vec![SortKey {
+            column: col_idx,
+            ascending,
+        }]);
+    }
 
-        self.sort_state = Some((col_idx, ascending));
-
-        // Sort rows by the column value
-        let col = col_idx;
-        self.rows.sort_by(|a, b| {
-            let va = a.get(col).map(|s| s.as_str()).unwrap_or("");
-            let vb = b.get(col).map(|s| s.as_str()).unwrap_or("");
-            if ascending { va.cmp(vb) } else { vb.cmp(va) }
-        });
+    /// Add a column to the sort as the least significant key, or toggle
+    /// its direction if it is already sorted.
+    pub fn add_sort_column(&mut self, col_idx: usize) {
+        if col_idx >= self.columns.len() {
+            return;
+        }
+        let mut keys = self.sort.clone();
+        match keys.iter_mut().find(|k| k.column == col_idx) {
+            Some(key) => key.ascending = !key.ascending,
+            None => keys.push(SortKey::ascending(col_idx)),
+        }
+        self.set_sort(keys);
+    }
 
-        // Keep selection at row 0 after sort
-        self.selected_row = 0;
-        self.row_offset = 0;
+    /// Sort by several columns, most significant first.
+    ///
+    /// Cells compare by type ([`TableCell::compare`]); empty cells always
+    /// sort last. Keys for columns that do not exist are dropped.
+    pub fn set_sort(&mut self, mut keys: Vec<SortKey>) {
+        keys.retain(|k| k.column < self.columns.len());
+        self.sort = keys;
+        self.rebuild_view();
+        self.reset_selection();
     }
 
     /// Clear the sort and restore original order.
     pub fn clear_sort(&mut self) {
-        if self.original_order.is_empty() || self.sort_state.is_none() {
-            self.sort_state = None;
+        if self.sort.is_empty() {
             return;
         }
+        self.sort.clear();
+        self.rebuild_view();
+        self.reset_selection();
+    }
 
-        // Rebuild original order
-        let mut indexed: Vec<(usize, Vec<String>)> = self
-            .original_order
-            .iter()
-            .zip(self.rows.drain(..))
-            .map(|(&orig_idx, row)| (orig_idx, row))
-            .collect();
-        indexed.sort_by_key(|(idx, _)| *idx);
-        self.rows = indexed.into_iter().map(|(_, row)| row).collect();
-
-        self.sort_state = None;
-        self.original_order.clear();
-        self.selected_row = 0;
-        self.row_offset = 0;
+    /// The sort keys, most significant first.
+    pub fn sort_keys(&self) -> &[SortKey] {
+        &self.sort
     }
 
-    /// Get the current sort state: (column_index, ascending).
+    /// Get the primary sort key: (column_index, ascending).
     pub fn sort_state(&self) -> Option<(usize, bool)> {
-        self.sort_state
+        self.sort.first().map(|k| (k.column, k.ascending))
+    }
+
+    // --- Filtering API ---
+
+    /// Filter a column with a [`FilterExpr`] expression; an empty (or
+    /// blank) expression clears the column's filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression does not parse; the current
+    /// filter is kept.
+    pub fn set_filter(&mut self, col_idx: usize, expr: &str) -> Result<()> {
+        if expr.trim().is_empty() {
+            self.clear_filter(col_idx);
+            return Ok(());
+        }
+        let expr = FilterExpr::parse(expr)?;
+        self.set_filter_expr(col_idx, expr);
+        Ok(())
+    }
+
+    /// Filter a column with a parsed expression.
+    pub fn set_filter_expr(&mut self, col_idx: usize, expr: FilterExpr) {
+        if col_idx >= self.columns.len() {
+            return;
+        }
+        self.filters.insert(col_idx, expr);
+        self.rebuild_view();
+        self.reset_selection();
+    }
+
+    /// Remove a column's filter.
+    pub fn clear_filter(&mut self, col_idx: usize) {
+        if self.filters.remove(&col_idx).is_some() {
+            self.rebuild_view();
+            self.reset_selection();
+        }
+    }
+
+    /// Remove every filter.
+    pub fn clear_filters(&mut self) {
+        if !self.filters.is_empty() {
+            self.filters.clear();
+            self.rebuild_view();
+            self.reset_selection();
+        }
+    }
+
+    /// The filter on a column, if any.
+    pub fn filter(&self, col_idx: usize) -> Option<&FilterExpr> {
+        self.filters.get(&col_idx)
     }
 
     /// Set the width of a column by index.
@@ -257,97 +573,287 @@ impl DataTable {
         self.columns.get(col_idx).map(|c| c.width)
     }
 
+    fn reset_selection(&mut self) {
+        self.selected_row = 0;
+        self.row_offset = 0;
+        self.anchor = None;
+    }
+
+    /// Recompute which source rows are shown, and in what order.
+    ///
+    /// Reads the source in chunks and keeps only the cells of sorted and
+    /// filtered columns.
+    fn rebuild_view(&mut self) {
+        if self.sort.is_empty() && self.filters.is_empty() {
+            self.view = None;
+            return;
+        }
+        let mut key_columns: Vec<usize> = self
+            .sort
+            .iter()
+            .map(|k| k.column)
+            .chain(self.filters.keys().copied())
+            .collect();
+        key_columns.sort_unstable();
+        key_columns.dedup();
+        let slot = |col: usize| key_columns.iter().position(|&c| c == col).unwrap_or(0);
+        let filters: Vec<(usize, &FilterExpr)> =
+            self.filters.iter().map(|(&c, f)| (slot(c), f)).collect();
+        let sort: Vec<(usize, bool)> = self
+            .sort
+            .iter()
+            .map(|k| (slot(k.column), k.ascending))
+            .collect();
+
+        let source = self.rows.source();
+        let total = source.row_count();
+        let mut view = Vec::new();
+        let mut keys: Vec<Vec<TableCell>> = Vec::new();
+        let mut start = 0;
+        while start < total {
+            let end = start.saturating_add(SCAN_CHUNK).min(total);
+            for (offset, mut row) in source.rows(start..end).into_iter().enumerate() {
+                let key: Vec<TableCell> = key_columns
+                    .iter()
+                    .map(|&c| row.get_mut(c).map(std::mem::take).unwrap_or_default())
+                    .collect();
+                if filters.iter().all(|(slot, f)| f.matches(&key[*slot])) {
+                    view.push(start + offset);
+                    keys.push(key);
+                }
+            }
+            start = end;
+        }
+
+        if !sort.is_empty() {
+            let mut order: Vec<usize> = (0..view.len()).collect();
+            order.sort_by(|&a, &b| {
+                sort.iter()
+                    .map(|&(slot, ascending)| {
+                        compare_keys(&keys[a][slot], &keys[b][slot], ascending)
+                    })
+                    .find(|ord| ord.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            });
+            view = order.into_iter().map(|i| view[i]).collect();
+        }
+        self.view = Some(view);
+    }
+
+    /// Fetch displayed rows, batching runs of consecutive source rows.
+    fn fetch(&self, range: Range<usize>) -> Vec<Vec<TableCell>> {
+        let source = self.rows.source();
+        let Some(view) = &self.view else {
+            return source.rows(range);
+        };
+        let end = range.end.min(view.len());
+        let start = range.start.min(end);
+        let ids = &view[start..end];
+        let mut rows = Vec::with_capacity(ids.len());
+        let mut i = 0;
+        while i < ids.len() {
+            let mut j = i + 1;
+            while j < ids.len() && ids[j] == ids[j - 1] + 1 {
+                j += 1;
+            }
+            let mut run = source.rows(ids[i]..ids[j - 1] + 1);
+            run.resize_with(j - i, Vec::new);
+            rows.extend(run);
+            i = j;
+        }
+        rows
+    }
+
     /// Calculate total width of all columns (including separators).
     fn total_columns_width(&self) -> u16 {
-        if self.columns.is_empty() {
-            return 0;
+        (0..self.columns.len())
+            .map(|idx| self.strip_width(idx))
+            .fold(0, u16::saturating_add)
+    }
+
+    /// Width of a column plus its separator (every column but the last).
+    fn strip_width(&self, idx: usize) -> u16 {
+        let width = self.columns.get(idx).map_or(0, |c| c.width);
+        if idx + 1 < self.columns.len() {
+            width.saturating_add(1)
+        } else {
+            width
         }
-        // Each column takes its width + 1 separator, except the last
-        let sum: u16 = self.columns.iter().map(|c| c.width).sum();
-        let separators = self.columns.len().saturating_sub(1) as u16;
-        sum.saturating_add(separators)
     }
 
-    /// Render a row of text cells with alignment, truncation, and horizontal scroll.
-    fn render_row(
-        &self,
-        cells: &[String],
-        y: u16,
-        x_start: u16,
-        available_width: u16,
-        style: &Style,
-        buf: &mut ScreenBuffer,
-    ) {
-        let mut x_offset: u16 = 0;
-        let col_off = self.col_offset;
+    /// Start and end of an unfrozen column, relative to the first unfrozen
+    /// column.
+    fn scroll_bounds(&self, idx: usize) -> (u16, u16) {
+        let start = (self.frozen_columns.min(self.columns.len())..idx)
+            .map(|i| self.strip_width(i))
+            .fold(0, u16::saturating_add);
+        (start, start.saturating_add(self.strip_width(idx)))
+    }
 
-        for (col_idx, col) in self.columns.iter().enumerate() {
-            let cell_text = cells.get(col_idx).map(|s| s.as_str()).unwrap_or("");
-            let col_w = col.width as usize;
+    /// Scroll left far enough to show the start of the selected column.
+    ///
+    /// Scrolling right to show its end needs the viewport width, so
+    /// rendering takes care of that.
+    fn scroll_to_selected_column(&mut self) {
+        if self.cell_selection && self.selected_column >= self.frozen_columns {
+            let (start, _) = self.scroll_bounds(self.selected_column);
+            self.col_offset = self.col_offset.min(start);
+        }
+    }
 
-            // Column start position (before horizontal scroll)
-            let col_start = x_offset;
-            let col_end = x_offset.saturating_add(col.width);
+    /// Where each visible column goes on screen.
+    fn column_spans(&self, available: u16) -> Vec<ColumnSpan> {
+        let frozen = self.frozen_columns.min(self.columns.len());
+        let mut spans = Vec::new();
+        let mut x: u16 = 0;
+        for idx in 0..frozen {
+            if x >= available {
+                break;
+            }
+            let width = self.strip_width(idx).min(available - x);
+            spans.push(ColumnSpan {
+                column: idx,
+                x,
+                skip: 0,
+                width,
+            });
+            x += width;
+        }
 
-            // Add separator width (1 char between columns)
-            let next_x = if col_idx + 1 < self.columns.len() {
-                col_end.saturating_add(1)
-            } else {
-                col_end
-            };
+        let scroll_x = x;
+        let scroll_width = available - x;
+        let mut offset = self.col_offset;
+        if self.cell_selection && self.selected_column >= frozen {
+            let (start, end) = self.scroll_bounds(self.selected_column);
+            if end > offset.saturating_add(scroll_width) {
+                offset = end.saturating_sub(scroll_width);
+            }
+            offset = offset.min(start);
+        }
+        let window_end = offset.saturating_add(scroll_width);
+        let mut pos: u16 = 0;
+        for idx in frozen..self.columns.len() {
+            let end = pos.saturating_add(self.strip_width(idx));
+            let (from, to) = (pos.max(offset), end.min(window_end));
+            if from < to {
+                spans.push(ColumnSpan {
+                    column: idx,
+                    x: scroll_x + (from - offset),
+                    skip: from - pos,
+                    width: to - from,
+                });
+            }
+            pos = end;
+        }
+        spans
+    }
 
-            // Check if this column is visible after horizontal scroll
-            if col_end > col_off && col_start < col_off.saturating_add(available_width) {
-                // Visible portion
-                let vis_start = col_start.saturating_sub(col_off);
-                let screen_x = x_start.saturating_add(vis_start);
-
-                let truncated = truncate_to_display_width(cell_text, col_w);
-                let text_width = UnicodeWidthStr::width(truncated);
-
-                // Apply alignment
-                let padding = col_w.saturating_sub(text_width);
-                let (left_pad, _right_pad) = match col.alignment {
-                    Alignment::Left => (0, padding),
-                    Alignment::Center => (padding / 2, padding.saturating_sub(padding / 2)),
-                    Alignment::Right => (padding, 0),
-                };
+    /// Lay out one cell across its column and separator.
+    fn cell_strip(&self, idx: usize, cell: Option<&TableCell>, style: &Style) -> Vec<Cell> {
+        let Some(column) = self.columns.get(idx) else {
+            return Vec::new();
+        };
+        let width = usize::from(column.width);
+        let pieces: Vec<(String, Style)> = match cell {
+            Some(TableCell::Styled(segments)) => segments
+                .iter()
+                .map(|s| (s.text.clone(), style.merge(&s.style)))
+                .collect(),
+            Some(cell) => vec![(cell.text(), style.clone())],
+            None => Vec::new(),
+        };
 
-                // Render the aligned text
-                let mut cx = screen_x;
-                // Left padding
-                for _ in 0..left_pad {
-                    if cx < x_start.saturating_add(available_width) {
-                        buf.set(cx, y, Cell::new(" ", style.clone()));
-                        cx += 1;
-                    }
+        let mut content = Vec::new();
+        let mut used = 0;
+        'pieces: for (text, piece_style) in &pieces {
+            for grapheme in text.graphemes(true) {
+                let w = UnicodeWidthStr::width(grapheme);
+                if w == 0 {
+                    continue;
                 }
-                // Text
-                for ch in truncated.chars() {
-                    let char_w = UnicodeWidthStr::width(ch.encode_utf8(&mut [0; 4]) as &str);
-                    if cx as usize + char_w > (x_start + available_width) as usize {
-                        break;
-                    }
-                    buf.set(cx, y, Cell::new(ch.to_string(), style.clone()));
-                    cx += char_w as u16;
+                if used + w > width {
+                    break 'pieces;
                 }
-                // Fill remaining column width
-                while cx < screen_x.saturating_add(col.width) && cx < x_start + available_width {
-                    buf.set(cx, y, Cell::new(" ", style.clone()));
-                    cx += 1;
+                content.push(Cell::new(grapheme, piece_style.clone()));
+                if w > 1 {
+                    content.push(Cell::continuation());
                 }
+                used += w;
+            }
+        }
 
-                // Render separator
-                if col_idx + 1 < self.columns.len() && cx < x_start.saturating_add(available_width)
-                {
-                    buf.set(cx, y, Cell::new("\u{2502}", style.clone()));
+        let padding = width - used;
+        let left_pad = match column.alignment {
+            Alignment::Left => 0,
+            Alignment::Center => padding / 2,
+            Alignment::Right => padding,
+        };
+        let blank = Cell::new(" ", style.clone());
+        let mut strip = vec![blank.clone(); left_pad];
+        strip.extend(content);
+        strip.resize(width, blank);
+        if idx + 1 < self.columns.len() {
+            strip.push(Cell::new("\u{2502}", style.clone()));
+        }
+        strip
+    }
+
+    /// Render a row of cells into the visible column spans.
+    fn render_row<'s>(
+        &'s self,
+        cells: &[TableCell],
+        y: u16,
+        x_start: u16,
+        spans: &[ColumnSpan],
+        style_for: impl Fn(usize) -> &'s Style,
+        buf: &mut ScreenBuffer,
+    ) {
+        for span in spans {
+            let style = style_for(span.column);
+            let strip = self.cell_strip(span.column, cells.get(span.column), style);
+            let from = usize::from(span.skip).min(strip.len());
+            let to = (from + usize::from(span.width)).min(strip.len());
+            let visible = &strip[from..to];
+            for (i, cell) in visible.iter().enumerate() {
+                let x = x_start + span.x + i as u16;
+                // Wide characters cut by scrolling become blanks.
+                if cell.is_continuation() {
+                    if i == 0 {
+                        buf.set(x, y, Cell::new(" ", style.clone()));
+                    }
+                } else if cell.is_wide() && i + 1 >= visible.len() {
+                    buf.set(x, y, Cell::new(" ", style.clone()));
+                } else {
+                    buf.set(x, y, cell.clone());
                 }
             }
-
-            x_offset = next_x;
         }
     }
 
+    /// Header text with sort indicators; the sort priority is shown when
+    /// sorting by more than one column.
+    fn header_cells(&self) -> Vec<TableCell> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(idx, c)| {
+                let Some(priority) = self.sort.iter().position(|k| k.column == idx) else {
+                    return TableCell::Text(c.header.clone());
+                };
+                let indicator = if self.sort[priority].ascending {
+                    "\u{2191}"
+                } else {
+                    "\u{2193}"
+                };
+                if self.sort.len() > 1 {
+                    TableCell::Text(format!("{}{indicator}{}", c.header, priority + 1))
+                } else {
+                    TableCell::Text(format!("{}{indicator}", c.header))
+                }
+            })
+            .collect()
+    }
+
     /// Ensure the selected row is visible by adjusting row_offset.
     fn ensure_selected_visible(&mut self, visible_height: usize) {
         if visible_height == 0 {
@@ -362,6 +868,26 @@ impl DataTable {
                 .saturating_sub(visible_height.saturating_sub(1));
         }
     }
+
+    /// Start or drop a range selection before moving the cursor.
+    fn update_anchor(&mut self, extend: bool) {
+        if !extend {
+            self.anchor = None;
+        } else if self.anchor.is_none() {
+            self.anchor = Some((self.selected_row, self.selected_column));
+        }
+    }
+}
+
+/// Compare sort keys, keeping empty cells last in either direction.
+fn compare_keys(a: &TableCell, b: &TableCell, ascending: bool) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) if ascending => a.compare(b),
+        (false, false) => b.compare(a),
+    }
 }
 
 impl Widget for DataTable {
@@ -379,32 +905,17 @@ impl Widget for DataTable {
 
         let available_width = inner.size.width;
         let total_height = inner.size.height as usize;
+        let spans = self.column_spans(available_width);
 
         // First row: headers (with sort indicators)
-        if total_height > 0 {
-            let headers: Vec<String> = self
-                .columns
-                .iter()
-                .enumerate()
-                .map(|(idx, c)| {
-                    if let Some((sort_col, ascending)) = self.sort_state
-                        && sort_col == idx
-                    {
-                        let indicator = if ascending { "\u{2191}" } else { "\u{2193}" };
-                        return format!("{}{indicator}", c.header);
-                    }
-                    c.header.clone()
-                })
-                .collect();
-            self.render_row(
-                &headers,
-                inner.position.y,
-                inner.position.x,
-                available_width,
-                &self.header_style,
-                buf,
-            );
-        }
+        self.render_row(
+            &self.header_cells(),
+            inner.position.y,
+            inner.position.x,
+            &spans,
+            |_| &self.header_style,
+            buf,
+        );
 
         // Remaining rows: data
         let data_height = total_height.saturating_sub(1);
@@ -412,64 +923,91 @@ impl Widget for DataTable {
             return;
         }
 
-        let max_offset = self.rows.len().saturating_sub(data_height.max(1));
+        let row_count = self.row_count();
+        let max_offset = row_count.saturating_sub(data_height.max(1));
         let scroll = self.row_offset.min(max_offset);
-        let visible_end = (scroll + data_height).min(self.rows.len());
+        let visible_end = (scroll + data_height).min(row_count);
+        let (selected_rows, selected_cols) = self.selection_range();
 
-        for (row_idx, data_idx) in (scroll..visible_end).enumerate() {
+        for (row_idx, row_data) in self.fetch(scroll..visible_end).iter().enumerate() {
+            let data_idx = scroll + row_idx;
             let y = inner.position.y + 1 + row_idx as u16;
-            if let Some(row_data) = self.rows.get(data_idx) {
-                let is_selected = data_idx == self.selected_row;
-                let style = if is_selected {
-                    &self.selected_style
-                } else {
-                    &self.row_style
+            let is_selected = selected_rows.contains(&data_idx);
+
+            if self.cell_selection {
+                let style_for = |col: usize| {
+                    if is_selected && selected_cols.contains(&col) {
+                        &self.selected_style
+                    } else {
+                        &self.row_style
+                    }
                 };
+                self.render_row(row_data, y, inner.position.x, &spans, style_for, buf);
+                continue;
+            }
 
-                // If selected, fill entire row first
-                if is_selected {
-                    for col in 0..available_width {
-                        buf.set(inner.position.x + col, y, Cell::new(" ", style.clone()));
-                    }
-                }
+            let style = if is_selected {
+                &self.selected_style
+            } else {
+                &self.row_style
+            };
 
-                self.render_row(row_data, y, inner.position.x, available_width, style, buf);
+            // If selected, fill entire row first
+            if is_selected {
+                for col in 0..available_width {
+                    buf.set(inner.position.x + col, y, Cell::new(" ", style.clone()));
+                }
             }
+
+            self.render_row(row_data, y, inner.position.x, &spans, |_| style, buf);
         }
     }
 }
 
 impl InteractiveWidget for DataTable {
     fn handle_event(&mut self, event: &Event) -> EventResult {
-        let Event::Key(KeyEvent { code, modifiers }) = event else {
+        let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event
+        else {
             return EventResult::Ignored;
         };
+        let has_ctrl = modifiers.contains(Modifiers::CTRL);
+        let has_shift = modifiers.contains(Modifiers::SHIFT);
+        let last_row = self.row_count().saturating_sub(1);
+        let last_col = self.columns.len().saturating_sub(1);
 
         match code {
             KeyCode::Up => {
+                self.update_anchor(has_shift);
                 if self.selected_row > 0 {
                     self.selected_row -= 1;
-                    self.ensure_selected_visible(20);
+                    self.ensure_selected_visible(PAGE_ROWS);
                 }
                 EventResult::Consumed
             }
             KeyCode::Down => {
-                if !self.rows.is_empty() && self.selected_row < self.rows.len().saturating_sub(1) {
+                self.update_anchor(has_shift);
+                if self.selected_row < last_row {
                     self.selected_row += 1;
-                    self.ensure_selected_visible(20);
+                    self.ensure_selected_visible(PAGE_ROWS);
                 }
                 EventResult::Consumed
             }
             KeyCode::Left => {
-                let has_ctrl = modifiers.contains(crate::event::Modifiers::CTRL);
-                let has_shift = modifiers.contains(crate::event::Modifiers::SHIFT);
                 if has_ctrl && has_shift && self.resizable_columns {
                     // Ctrl+Shift+Left: decrease selected column width
-                    let max_col = self.columns.len().saturating_sub(1);
-                    let target = self.selected_row.min(max_col);
-                    if let Some(col) = self.columns.get_mut(target) {
+                    if let Some(col) = self.columns.get_mut(self.selected_column) {
                         col.width = col.width.saturating_sub(1).max(3);
                     }
+                } else if self.cell_selection {
+                    self.update_anchor(has_shift && !has_ctrl);
+                    let target = if has_ctrl {
+                        0
+                    } else {
+                        self.selected_column.saturating_sub(1)
+                    };
+                    self.set_selected_column(target);
                 } else if has_ctrl {
                     self.col_offset = 0;
                 } else {
@@ -478,15 +1016,19 @@ impl InteractiveWidget for DataTable {
                 EventResult::Consumed
             }
             KeyCode::Right => {
-                let has_ctrl = modifiers.contains(crate::event::Modifiers::CTRL);
-                let has_shift = modifiers.contains(crate::event::Modifiers::SHIFT);
                 if has_ctrl && has_shift && self.resizable_columns {
                     // Ctrl+Shift+Right: increase selected column width
-                    let max_col = self.columns.len().saturating_sub(1);
-                    let target = self.selected_row.min(max_col);
-                    if let Some(col) = self.columns.get_mut(target) {
+                    if let Some(col) = self.columns.get_mut(self.selected_column) {
                         col.width = (col.width + 1).min(50);
                     }
+                } else if self.cell_selection {
+                    self.update_anchor(has_shift && !has_ctrl);
+                    let target = if has_ctrl {
+                        last_col
+                    } else {
+                        self.selected_column + 1
+                    };
+                    self.set_selected_column(target);
                 } else if has_ctrl {
                     self.col_offset = self.total_columns_width();
                 } else {
@@ -495,45 +1037,55 @@ impl InteractiveWidget for DataTable {
                 EventResult::Consumed
             }
             KeyCode::PageUp => {
-                let page = 20;
-                self.selected_row = self.selected_row.saturating_sub(page);
-                self.ensure_selected_visible(20);
+                self.anchor = None;
+                self.selected_row = self.selected_row.saturating_sub(PAGE_ROWS);
+                self.ensure_selected_visible(PAGE_ROWS);
                 EventResult::Consumed
             }
             KeyCode::PageDown => {
-                let page = 20;
-                if !self.rows.is_empty() {
-                    self.selected_row =
-                        (self.selected_row + page).min(self.rows.len().saturating_sub(1));
-                    self.ensure_selected_visible(20);
-                }
+                self.anchor = None;
+                self.selected_row = (self.selected_row + PAGE_ROWS).min(last_row);
+                self.ensure_selected_visible(PAGE_ROWS);
                 EventResult::Consumed
             }
             KeyCode::Home => {
+                self.anchor = None;
                 self.selected_row = 0;
                 self.row_offset = 0;
                 EventResult::Consumed
             }
             KeyCode::End => {
-                if !self.rows.is_empty() {
-                    self.selected_row = self.rows.len().saturating_sub(1);
-                    self.ensure_selected_visible(20);
-                }
+                self.anchor = None;
+                self.selected_row = last_row;
+                self.ensure_selected_visible(PAGE_ROWS);
                 EventResult::Consumed
             }
+            KeyCode::Escape if self.anchor.is_some() => {
+                self.anchor = None;
+                EventResult::Consumed
+            }
+            // Ctrl+C: copy the selection
+            KeyCode::Char('c') if has_ctrl => {
+                if self.copy() {
+                    EventResult::Consumed
+                } else {
+                    EventResult::Ignored
+                }
+            }
             // Ctrl+0: clear sort
-            KeyCode::Char('0') if modifiers.contains(crate::event::Modifiers::CTRL) => {
+            KeyCode::Char('0') if has_ctrl => {
                 self.clear_sort();
                 EventResult::Consumed
             }
-            // Ctrl+1..9: sort by column 1-9
+            // Ctrl+1..9: sort by column 1-9; Alt+1..9: add it to the sort
             KeyCode::Char(ch)
-                if modifiers.contains(crate::event::Modifiers::CTRL)
-                    && ('1'..='9').contains(ch) =>
+                if (has_ctrl || modifiers.contains(Modifiers::ALT)) && ('1'..='9').contains(ch) =>
             {
                 let col_idx = (*ch as usize) - ('1' as usize);
-                if col_idx < self.columns.len() {
+                if has_ctrl {
                     self.sort_by_column(col_idx);
+                } else {
+                    self.add_sort_column(col_idx);
                 }
                 EventResult::Consumed
             }
@@ -683,14 +1235,8 @@ mod tests {
     fn vertical_scrolling_with_navigation() {
         let mut table = make_test_table();
 
-        let down = Event::Key(KeyEvent {
-            code: KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let up = Event::Key(KeyEvent {
-            code: KeyCode::Up,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = Event::Key(KeyEvent::new(KeyCode::Down, crate::event::Modifiers::NONE));
+        let up = Event::Key(KeyEvent::new(KeyCode::Up, crate::event::Modifiers::NONE));
 
         assert_eq!(table.selected_row(), 0);
         table.handle_event(&down);
@@ -707,14 +1253,8 @@ mod tests {
     fn horizontal_scrolling() {
         let mut table = make_test_table();
 
-        let right = Event::Key(KeyEvent {
-            code: KeyCode::Right,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let left = Event::Key(KeyEvent {
-            code: KeyCode::Left,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let right = Event::Key(KeyEvent::new(KeyCode::Right, crate::event::Modifiers::NONE));
+        let left = Event::Key(KeyEvent::new(KeyCode::Left, crate::event::Modifiers::NONE));
 
         assert_eq!(table.col_offset(), 0);
         table.handle_event(&right);
@@ -733,14 +1273,14 @@ mod tests {
             table.push_row(vec![format!("Row {i}")]);
         }
 
-        let page_down = Event::Key(KeyEvent {
-            code: KeyCode::PageDown,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let page_up = Event::Key(KeyEvent {
-            code: KeyCode::PageUp,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let page_down = Event::Key(KeyEvent::new(
+            KeyCode::PageDown,
+            crate::event::Modifiers::NONE,
+        ));
+        let page_up = Event::Key(KeyEvent::new(
+            KeyCode::PageUp,
+            crate::event::Modifiers::NONE,
+        ));
 
         table.handle_event(&page_down);
         assert_eq!(table.selected_row(), 20);
@@ -752,14 +1292,8 @@ mod tests {
     fn home_end_navigation() {
         let mut table = make_test_table();
 
-        let end = Event::Key(KeyEvent {
-            code: KeyCode::End,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let home = Event::Key(KeyEvent {
-            code: KeyCode::Home,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let end = Event::Key(KeyEvent::new(KeyCode::End, crate::event::Modifiers::NONE));
+        let home = Event::Key(KeyEvent::new(KeyCode::Home, crate::event::Modifiers::NONE));
 
         table.handle_event(&end);
         assert_eq!(table.selected_row(), 2);
@@ -834,10 +1368,7 @@ mod tests {
     #[test]
     fn unhandled_event_ignored() {
         let mut table = make_test_table();
-        let tab = Event::Key(KeyEvent {
-            code: KeyCode::Tab,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let tab = Event::Key(KeyEvent::new(KeyCode::Tab, crate::event::Modifiers::NONE));
         assert_eq!(table.handle_event(&tab), EventResult::Ignored);
     }
 
@@ -849,7 +1380,7 @@ mod tests {
         // Rows: Alice, Bob, Charlie
         table.sort_by_column(0); // Sort by Name ascending
         assert_eq!(table.sort_state(), Some((0, true)));
-        match table.rows.first().map(|r| r[0].as_str()) {
+        match table.row_data(0).as_ref().map(|r| r[0].as_str()) {
             Some("Alice") => {}
             other => panic!("Expected Alice first, got {other:?}"),
         }
@@ -862,7 +1393,7 @@ mod tests {
         assert_eq!(table.sort_state(), Some((0, true)));
         table.sort_by_column(0); // toggle to descending
         assert_eq!(table.sort_state(), Some((0, false)));
-        match table.rows.first().map(|r| r[0].as_str()) {
+        match table.row_data(0).as_ref().map(|r| r[0].as_str()) {
             Some("Charlie") => {}
             other => panic!("Expected Charlie first (descending), got {other:?}"),
         }
@@ -931,10 +1462,10 @@ mod tests {
     fn keyboard_sort_ctrl_1() {
         let mut table = make_test_table();
 
-        let ctrl_1 = Event::Key(KeyEvent {
-            code: KeyCode::Char('1'),
-            modifiers: crate::event::Modifiers::CTRL,
-        });
+        let ctrl_1 = Event::Key(KeyEvent::new(
+            KeyCode::Char('1'),
+            crate::event::Modifiers::CTRL,
+        ));
 
         assert_eq!(table.handle_event(&ctrl_1), EventResult::Consumed);
         assert_eq!(table.sort_state(), Some((0, true)));
@@ -946,10 +1477,10 @@ mod tests {
         table.sort_by_column(0);
         assert!(table.sort_state().is_some());
 
-        let ctrl_0 = Event::Key(KeyEvent {
-            code: KeyCode::Char('0'),
-            modifiers: crate::event::Modifiers::CTRL,
-        });
+        let ctrl_0 = Event::Key(KeyEvent::new(
+            KeyCode::Char('0'),
+            crate::event::Modifiers::CTRL,
+        ));
 
         assert_eq!(table.handle_event(&ctrl_0), EventResult::Consumed);
         assert!(table.sort_state().is_none());
@@ -962,10 +1493,10 @@ mod tests {
         let original = table.column_width(0);
         assert_eq!(original, Some(10));
 
-        let ctrl_shift_right = Event::Key(KeyEvent {
-            code: KeyCode::Right,
-            modifiers: crate::event::Modifiers::CTRL | crate::event::Modifiers::SHIFT,
-        });
+        let ctrl_shift_right = Event::Key(KeyEvent::new(
+            KeyCode::Right,
+            crate::event::Modifiers::CTRL | crate::event::Modifiers::SHIFT,
+        ));
 
         table.handle_event(&ctrl_shift_right);
         assert_eq!(table.column_width(0), Some(11));
@@ -975,10 +1506,10 @@ mod tests {
     fn keyboard_resize_ctrl_shift_left() {
         let mut table = make_test_table().with_resizable_columns(true);
 
-        let ctrl_shift_left = Event::Key(KeyEvent {
-            code: KeyCode::Left,
-            modifiers: crate::event::Modifiers::CTRL | crate::event::Modifiers::SHIFT,
-        });
+        let ctrl_shift_left = Event::Key(KeyEvent::new(
+            KeyCode::Left,
+            crate::event::Modifiers::CTRL | crate::event::Modifiers::SHIFT,
+        ));
 
         table.handle_event(&ctrl_shift_left);
         assert_eq!(table.column_width(0), Some(9));
@@ -1000,4 +1531,283 @@ mod tests {
         table.sort_by_column(0);
         assert_eq!(table.selected_row(), 0);
     }
+
+    // --- Typed cells, sources, filters and selection ---
+
+    fn num(n: f64) -> TableCell {
+        TableCell::Number(n)
+    }
+
+    fn first_column(table: &DataTable) -> Vec<String> {
+        (0..table.row_count())
+            .filter_map(|i| {
+                table
+                    .row_data(i)
+                    .map(|r| r.first().cloned().unwrap_or_default())
+            })
+            .collect()
+    }
+
+    /// A million-row source that records which rows were fetched.
+    struct CountingSource {
+        fetched: std::rc::Rc<std::cell::RefCell<Vec<Range<usize>>>>,
+    }
+
+    impl TableSource for CountingSource {
+        fn row_count(&self) -> usize {
+            1_000_000
+        }
+
+        fn rows(&self, range: Range<usize>) -> Vec<Vec<TableCell>> {
+            self.fetched.borrow_mut().push(range.clone());
+            range
+                .map(|i| vec![TableCell::Text(format!("row {i}")), num(i as f64)])
+                .collect()
+        }
+    }
+
+    #[test]
+    fn numbers_sort_numerically() {
+        let mut table = DataTable::new(vec![Column::new("N", 5)]);
+        for n in [10.0, 9.0, 100.0] {
+            table.push_cells(vec![num(n)]);
+        }
+        table.sort_by_column(0);
+        assert_eq!(first_column(&table), vec!["9", "10", "100"]);
+    }
+
+    #[test]
+    fn multi_column_sort() {
+        let mut table = DataTable::new(vec![Column::new("City", 8), Column::new("Age", 4)]);
+        table.push_cells(vec!["Paris".into(), num(30.0)]);
+        table.push_cells(vec!["London".into(), num(25.0)]);
+        table.push_cells(vec!["Paris".into(), num(41.0)]);
+        table.push_cells(vec!["London".into(), num(52.0)]);
+        table.set_sort(vec![SortKey::ascending(0), SortKey::descending(1)]);
+        let ages: Vec<String> = (0..4)
+            .filter_map(|i| table.row_data(i).map(|r| r[1].clone()))
+            .collect();
+        assert_eq!(ages, vec!["52", "25", "41", "30"]);
+
+        // Alt+2 toggles the secondary key.
+        let alt_2 = Event::Key(KeyEvent::new(KeyCode::Char('2'), Modifiers::ALT));
+        assert_eq!(table.handle_event(&alt_2), EventResult::Consumed);
+        assert_eq!(
+            table.sort_keys(),
+            &[SortKey::ascending(0), SortKey::ascending(1)]
+        );
+        assert_eq!(table.row_data(0).map(|r| r[1].clone()), Some("25".into()));
+    }
+
+    #[test]
+    fn multi_sort_headers_show_priority() {
+        let mut table = make_test_table();
+        table.set_sort(vec![SortKey::ascending(2), SortKey::descending(0)]);
+        let mut buf = ScreenBuffer::new(Size::new(35, 5));
+        table.render(Rect::new(0, 0, 35, 5), &mut buf);
+        let header: String = (0..35)
+            .filter_map(|x| buf.get(x, 0).map(|c| c.grapheme.clone()))
+            .collect();
+        assert!(header.starts_with("Name\u{2193}2"));
+        assert!(header.contains("City\u{2191}1"));
+    }
+
+    #[test]
+    fn empty_cells_sort_last_in_both_directions() {
+        let mut table = DataTable::new(vec![Column::new("N", 5)]);
+        table.push_cells(vec![TableCell::Empty]);
+        table.push_cells(vec![num(2.0)]);
+        table.push_cells(vec![]);
+        table.push_cells(vec![num(1.0)]);
+        table.sort_by_column(0);
+        assert_eq!(first_column(&table), vec!["1", "2", "", ""]);
+        table.sort_by_column(0);
+        assert_eq!(first_column(&table), vec!["2", "1", "", ""]);
+    }
+
+    #[test]
+    fn filters_hide_rows() {
+        let mut table = make_test_table();
+        assert!(table.set_filter(1, ">28").is_ok());
+        assert_eq!(table.row_count(), 2);
+        assert_eq!(table.total_row_count(), 3);
+        assert_eq!(first_column(&table), vec!["Alice", "Charlie"]);
+
+        assert!(table.set_filter(2, "o").is_ok());
+        assert_eq!(first_column(&table), vec!["Alice", "Charlie"]);
+        assert!(table.set_filter(2, "^new").is_ok());
+        assert_eq!(first_column(&table), vec!["Alice"]);
+
+        // An invalid expression keeps the previous filter.
+        assert!(table.set_filter(2, ">=").is_err());
+        assert_eq!(table.row_count(), 1);
+
+        assert!(table.set_filter(2, "").is_ok());
+        assert!(table.filter(2).is_none());
+        table.clear_filters();
+        assert_eq!(table.row_count(), 3);
+    }
+
+    #[test]
+    fn filters_combine_with_sort_and_new_rows() {
+        let mut table = make_test_table();
+        assert!(table.set_filter(1, "<40").is_ok());
+        table.sort_by_column(1);
+        table.push_row(vec!["Dana".into(), "20".into(), "Oslo".into()]);
+        table.push_row(vec!["Eve".into(), "50".into(), "Rome".into()]);
+        assert_eq!(
+            first_column(&table),
+            vec!["Dana", "Bob", "Alice", "Charlie"]
+        );
+    }
+
+    #[test]
+    fn lazy_source_fetches_only_visible_rows() {
+        let fetched = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let mut table = DataTable::new(vec![Column::new("Name", 12), Column::new("N", 8)])
+            .with_source(Box::new(CountingSource {
+                fetched: fetched.clone(),
+            }));
+        assert_eq!(table.row_count(), 1_000_000);
+        table.set_selected_row(500_000);
+        table.row_offset = 500_000;
+
+        let mut buf = ScreenBuffer::new(Size::new(25, 6));
+        table.render(Rect::new(0, 0, 25, 6), &mut buf);
+        assert_eq!(*fetched.borrow(), vec![500_000..500_005]);
+        assert_eq!(buf.get(0, 1).map(|c| c.grapheme.as_str()), Some("r"));
+        assert!(buf.get(0, 1).is_some_and(|c| c.style.reverse));
+
+        // Pushing rows does nothing to an external source.
+        table.push_row(vec!["x".into()]);
+        assert_eq!(table.total_row_count(), 1_000_000);
+    }
+
+    #[test]
+    fn sorted_source_fetches_visible_rows_in_display_order() {
+        let fetched = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let mut table = DataTable::new(vec![Column::new("Name", 12), Column::new("N", 8)])
+            .with_source(Box::new(CountingSource {
+                fetched: fetched.clone(),
+            }));
+        table.sort_by_column(1);
+        table.sort_by_column(1);
+        assert_eq!(
+            table.row_data(0).map(|r| r[1].clone()),
+            Some("999999".into())
+        );
+        fetched.borrow_mut().clear();
+
+        let mut buf = ScreenBuffer::new(Size::new(25, 4));
+        table.render(Rect::new(0, 0, 25, 4), &mut buf);
+        assert_eq!(
+            *fetched.borrow(),
+            vec![999_999..1_000_000, 999_998..999_999, 999_997..999_998]
+        );
+    }
+
+    #[test]
+    fn frozen_columns_do_not_scroll() {
+        let mut table = make_test_table().with_frozen_columns(1);
+        table.col_offset = 6;
+        let mut buf = ScreenBuffer::new(Size::new(20, 3));
+        table.render(Rect::new(0, 0, 20, 3), &mut buf);
+        let row: String = (0..20)
+            .filter_map(|x| buf.get(x, 2).map(|c| c.grapheme.clone()))
+            .collect();
+        // Name stays; Age ("25" + separator) scrolled away, City shows.
+        assert_eq!(row, "Bob       \u{2502}London   ");
+    }
+
+    #[test]
+    fn scrolling_clips_partially_visible_columns() {
+        let mut table = make_test_table();
+        table.col_offset = 3;
+        let mut buf = ScreenBuffer::new(Size::new(12, 3));
+        table.render(Rect::new(0, 0, 12, 3), &mut buf);
+        let row: String = (0..12)
+            .filter_map(|x| buf.get(x, 2).map(|c| c.grapheme.clone()))
+            .collect();
+        assert_eq!(row, "       \u{2502}25  ");
+    }
+
+    #[test]
+    fn cell_selection_moves_and_copies_ranges() {
+        let mut table = make_test_table().with_cell_selection(true);
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+        table.handle_event(&key(KeyCode::Right, Modifiers::NONE));
+        assert_eq!(table.selected_column(), 1);
+        assert_eq!(table.selected_cell(), Some(TableCell::from("30")));
+
+        table.handle_event(&key(KeyCode::Down, Modifiers::SHIFT));
+        table.handle_event(&key(KeyCode::Right, Modifiers::SHIFT));
+        assert_eq!(table.selection_range(), (0..2, 1..3));
+        assert_eq!(
+            table.handle_event(&key(KeyCode::Char('c'), Modifiers::CTRL)),
+            EventResult::Consumed
+        );
+        assert_eq!(
+            table.clipboard().text().as_deref(),
+            Some("30\tNew York\n25\tLondon")
+        );
+
+        // Only the selected cells are highlighted.
+        let mut buf = ScreenBuffer::new(Size::new(30, 5));
+        table.render(Rect::new(0, 0, 30, 5), &mut buf);
+        assert!(buf.get(0, 1).is_some_and(|c| !c.style.reverse));
+        assert!(buf.get(11, 1).is_some_and(|c| c.style.reverse));
+        assert!(buf.get(11, 3).is_some_and(|c| !c.style.reverse));
+
+        assert_eq!(
+            table.handle_event(&key(KeyCode::Escape, Modifiers::NONE)),
+            EventResult::Consumed
+        );
+        assert_eq!(table.selection_range(), (1..2, 2..3));
+    }
+
+    #[test]
+    fn row_selection_copies_whole_rows() {
+        let mut table = make_test_table();
+        table.set_selected_row(1);
+        assert_eq!(table.selected_text().as_deref(), Some("Bob\t25\tLondon"));
+        assert!(table.copy());
+        assert_eq!(table.clipboard().text().as_deref(), Some("Bob\t25\tLondon"));
+        assert!(!DataTable::new(vec![Column::new("A", 3)]).copy());
+    }
+
+    #[test]
+    fn selected_cell_scrolls_into_view() {
+        let mut table = make_test_table().with_cell_selection(true);
+        table.set_selected_column(2);
+        let mut buf = ScreenBuffer::new(Size::new(15, 3));
+        table.render(Rect::new(0, 0, 15, 3), &mut buf);
+        let header: String = (0..15)
+            .filter_map(|x| buf.get(x, 0).map(|c| c.grapheme.clone()))
+            .collect();
+        assert!(header.ends_with("City        "));
+
+        table.set_selected_column(0);
+        assert_eq!(table.col_offset(), 0);
+    }
+
+    #[test]
+    fn styled_cells_keep_segment_styles() {
+        let mut table = DataTable::new(vec![Column::new("S", 6)]);
+        table.push_cells(vec![TableCell::Styled(vec![
+            crate::segment::Segment::new("ok "),
+            crate::segment::Segment::styled("!", Style::default().bold(true)),
+        ])]);
+        table.push_cells(vec![]);
+        table.set_selected_row(1);
+        let mut buf = ScreenBuffer::new(Size::new(6, 3));
+        table.render(Rect::new(0, 0, 6, 3), &mut buf);
+        assert!(
+            buf.get(0, 1)
+                .is_some_and(|c| c.grapheme == "o" && !c.style.bold)
+        );
+        assert!(
+            buf.get(3, 1)
+                .is_some_and(|c| c.grapheme == "!" && c.style.bold)
+        );
+    }
 }
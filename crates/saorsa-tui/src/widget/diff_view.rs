@@ -1,19 +1,40 @@
 //! Diff viewer widget with unified and side-by-side display modes.
 //!
 //! Uses the [`similar`] crate to compute line-by-line diffs and displays
-//! them with color-coded added/removed/unchanged styling.
+//! them with color-coded added/removed/unchanged styling. Within a changed
+//! line, the words that changed are emphasised using `similar`'s inline
+//! diff. Changes are grouped into hunks with a few lines of context; the
+//! unchanged lines between hunks fold away, and each hunk can be accepted
+//! or rejected. A view can also be read from a unified diff or git patch.
+
+mod patch;
+
+use std::ops::Range;
+use std::path::Path;
 
 use similar::{ChangeTag, TextDiff};
+use unicode_width::UnicodeWidthChar;
 
 use crate::buffer::ScreenBuffer;
 use crate::cell::Cell;
+use crate::color::{Color, NamedColor};
+use crate::error::Result;
 use crate::event::{Event, KeyCode, KeyEvent};
 use crate::geometry::Rect;
+use crate::highlight::{HighlightSpan, SyntaxTheme, highlighter_for_language};
 use crate::style::Style;
-use crate::text::truncate_to_display_width;
 
 use super::{BorderStyle, EventResult, InteractiveWidget, Widget};
 
+/// Unchanged lines shown around each change by default.
+const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Rows moved by PageUp/PageDown.
+const PAGE_ROWS: usize = 20;
+
+/// Type alias for the hunk accept/reject callbacks.
+type HunkFn = Option<Box<dyn FnMut(&DiffHunk)>>;
+
 /// Display mode for the diff viewer.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DiffMode {
@@ -23,36 +44,146 @@ pub enum DiffMode {
     SideBySide,
 }
 
+/// Whether a hunk has been accepted or rejected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HunkStatus {
+    /// Not decided yet.
+    #[default]
+    Pending,
+    /// Accepted.
+    Accepted,
+    /// Rejected.
+    Rejected,
+}
+
+/// A group of nearby changes and the context around them.
+///
+/// Line numbers are 1-based. As in a unified diff header, a side with no
+/// lines starts at the line before the hunk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// Path of the old file, for hunks read from a patch.
+    pub old_path: Option<String>,
+    /// Path of the new file, for hunks read from a patch.
+    pub new_path: Option<String>,
+    /// First old line.
+    pub old_start: usize,
+    /// Number of old lines.
+    pub old_len: usize,
+    /// First new line.
+    pub new_start: usize,
+    /// Number of new lines.
+    pub new_len: usize,
+    /// Text after the header's closing `@@`, such as the enclosing function.
+    pub section: String,
+    /// Whether the hunk has been accepted or rejected.
+    pub status: HunkStatus,
+    /// The hunk's lines in the view's line list.
+    lines: Range<usize>,
+}
+
+impl DiffHunk {
+    /// The `@@ -a,b +c,d @@` header line.
+    pub fn header(&self) -> String {
+        let range = |start: usize, len: usize| {
+            if len == 1 {
+                start.to_string()
+            } else {
+                format!("{start},{len}")
+            }
+        };
+        let mut header = format!(
+            "@@ -{} +{} @@",
+            range(self.old_start, self.old_len),
+            range(self.new_start, self.new_len)
+        );
+        if !self.section.is_empty() {
+            header.push(' ');
+            header.push_str(&self.section);
+        }
+        header
+    }
+
+    /// The file the hunk changes: its new path, or the old one for a
+    /// deleted file.
+    pub fn path(&self) -> Option<&str> {
+        self.new_path.as_deref().or(self.old_path.as_deref())
+    }
+
+    /// The first old line the hunk covers.
+    fn old_first(&self) -> usize {
+        if self.old_len == 0 {
+            self.old_start + 1
+        } else {
+            self.old_start
+        }
+    }
+
+    /// The first new line the hunk covers.
+    fn new_first(&self) -> usize {
+        if self.new_len == 0 {
+            self.new_start + 1
+        } else {
+            self.new_start
+        }
+    }
+}
+
 /// A line in the computed diff.
 #[derive(Clone, Debug)]
 struct DiffLine {
     /// The diff tag for this line.
     tag: ChangeTag,
+    /// Line number in the old text, if the line is there.
+    old_number: Option<usize>,
+    /// Line number in the new text, if the line is there.
+    new_number: Option<usize>,
     /// The text content (without newline).
     text: String,
+    /// Syntax highlight spans for the text.
+    spans: Vec<HighlightSpan>,
+    /// Character ranges that changed within the line.
+    emphasis: Vec<Range<usize>>,
 }
 
-/// A pair of lines for side-by-side display.
-#[derive(Clone, Debug)]
-struct SideBySidePair {
-    /// Left (old) line, if any.
-    left: Option<DiffLine>,
-    /// Right (new) line, if any.
-    right: Option<DiffLine>,
+/// A display row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Row {
+    /// The path of the file a hunk starts, by hunk index.
+    File(usize),
+    /// A hunk header, by hunk index.
+    Header(usize),
+    /// A number of unchanged lines folded away.
+    Fold(usize),
+    /// A line, in unified mode.
+    Line(usize),
+    /// Old and new lines side by side.
+    Pair(Option<usize>, Option<usize>),
 }
 
 /// A diff viewer widget that displays text differences.
 ///
 /// Supports unified and side-by-side display modes with color-coded
-/// added, removed, and unchanged lines.
+/// added, removed, and unchanged lines and emphasis on the words that
+/// changed. Hunk headers, line numbers for both sides and folding of
+/// unchanged context are opt-in. With a language set (and the
+/// `tree-sitter` feature), line text is syntax highlighted on top of the
+/// line colors; hunks read from a patch are highlighted by their file
+/// extension when no language is set.
+///
+/// Keys: arrows, PageUp/PageDown and Home/End scroll; `n` and `p` move to
+/// the next and previous hunk; `a` and `r` accept and reject the current
+/// hunk; `z` folds or unfolds unchanged context; `m` switches mode.
 pub struct DiffView {
     /// Original text.
     old_text: String,
     /// Modified text.
     new_text: String,
+    /// Whether the lines were read from a patch rather than two texts.
+    from_patch: bool,
     /// Display mode.
     mode: DiffMode,
-    /// Scroll offset (first visible line).
+    /// Scroll offset (first visible row).
     scroll_offset: usize,
     /// Style for unchanged lines.
     unchanged_style: Style,
@@ -60,12 +191,42 @@ pub struct DiffView {
     added_style: Style,
     /// Style for removed lines.
     removed_style: Style,
+    /// Style for changed words on added lines.
+    added_emphasis_style: Style,
+    /// Style for changed words on removed lines.
+    removed_emphasis_style: Style,
+    /// Style for hunk headers and file paths.
+    header_style: Style,
+    /// Style for line numbers and folded context.
+    line_number_style: Style,
     /// Border style.
     border: BorderStyle,
-    /// Cached unified diff lines.
-    unified_lines: Vec<DiffLine>,
-    /// Cached side-by-side pairs.
-    sbs_pairs: Vec<SideBySidePair>,
+    /// Language for syntax highlighting.
+    language: Option<String>,
+    /// Styles for syntax highlighting.
+    syntax_theme: SyntaxTheme,
+    /// Unchanged lines kept around each change.
+    context_lines: usize,
+    /// Whether unchanged lines between hunks are folded.
+    context_collapsed: bool,
+    /// Whether line numbers are shown.
+    line_numbers: bool,
+    /// Whether hunk headers are shown.
+    hunk_headers: bool,
+    /// The diff's lines; for a patch, only the lines of its hunks.
+    lines: Vec<DiffLine>,
+    /// Hunks over `lines`.
+    hunks: Vec<DiffHunk>,
+    /// Index of the current hunk.
+    current_hunk: Option<usize>,
+    /// Cached display rows for the mode and folding.
+    rows: Vec<Row>,
+    /// Digits in the largest line number.
+    number_width: usize,
+    /// Callback invoked when a hunk is accepted.
+    on_accept: HunkFn,
+    /// Callback invoked when a hunk is rejected.
+    on_reject: HunkFn,
 }
 
 impl DiffView {
@@ -74,25 +235,59 @@ impl DiffView {
         let mut view = Self {
             old_text: old_text.to_string(),
             new_text: new_text.to_string(),
+            from_patch: false,
             mode: DiffMode::Unified,
             scroll_offset: 0,
             unchanged_style: Style::default(),
-            added_style: Style::default()
-                .bg(crate::color::Color::Named(crate::color::NamedColor::Green)),
-            removed_style: Style::default()
-                .bg(crate::color::Color::Named(crate::color::NamedColor::Red)),
+            added_style: Style::default().bg(Color::Named(NamedColor::Green)),
+            removed_style: Style::default().bg(Color::Named(NamedColor::Red)),
+            added_emphasis_style: Style::default()
+                .bg(Color::Named(NamedColor::BrightGreen))
+                .bold(true),
+            removed_emphasis_style: Style::default()
+                .bg(Color::Named(NamedColor::BrightRed))
+                .bold(true),
+            header_style: Style::default().fg(Color::Named(NamedColor::Cyan)),
+            line_number_style: Style::default().dim(true),
             border: BorderStyle::None,
-            unified_lines: Vec::new(),
-            sbs_pairs: Vec::new(),
+            language: None,
+            syntax_theme: SyntaxTheme::default(),
+            context_lines: DEFAULT_CONTEXT_LINES,
+            context_collapsed: false,
+            line_numbers: false,
+            hunk_headers: false,
+            lines: Vec::new(),
+            hunks: Vec::new(),
+            current_hunk: None,
+            rows: Vec::new(),
+            number_width: 0,
+            on_accept: None,
+            on_reject: None,
         };
         view.compute_diff();
         view
     }
 
+    /// Create a diff view from a unified diff or git patch, which may
+    /// cover several files.
+    ///
+    /// Only the lines in the patch's hunks are known, so the unchanged
+    /// lines between hunks always stay folded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SaorsaTuiError::Widget`](crate::error::SaorsaTuiError::Widget)
+    /// for a malformed hunk.
+    pub fn from_patch(patch: &str) -> Result<Self> {
+        let mut view = Self::new("", "");
+        view.set_patch(patch)?;
+        Ok(view)
+    }
+
     /// Set the display mode.
     #[must_use]
     pub fn with_mode(mut self, mode: DiffMode) -> Self {
-        self.mode = mode;
+        self.set_mode(mode);
         self
     }
 
@@ -117,6 +312,34 @@ impl DiffView {
         self
     }
 
+    /// Set the style merged over the changed words of added lines.
+    #[must_use]
+    pub fn with_added_emphasis_style(mut self, style: Style) -> Self {
+        self.added_emphasis_style = style;
+        self
+    }
+
+    /// Set the style merged over the changed words of removed lines.
+    #[must_use]
+    pub fn with_removed_emphasis_style(mut self, style: Style) -> Self {
+        self.removed_emphasis_style = style;
+        self
+    }
+
+    /// Set the style for hunk headers and file paths.
+    #[must_use]
+    pub fn with_header_style(mut self, style: Style) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Set the style for line numbers and folded context.
+    #[must_use]
+    pub fn with_line_number_style(mut self, style: Style) -> Self {
+        self.line_number_style = style;
+        self
+    }
+
     /// Set the border style.
     #[must_use]
     pub fn with_border(mut self, border: BorderStyle) -> Self {
@@ -124,18 +347,148 @@ impl DiffView {
         self
     }
 
+    /// Syntax highlight both texts as `language` (a name or file
+    /// extension such as `"rust"` or `"py"`).
+    #[must_use]
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.set_language(Some(language));
+        self
+    }
+
+    /// Set how many unchanged lines are kept around each change.
+    #[must_use]
+    pub fn with_context_lines(mut self, lines: usize) -> Self {
+        self.set_context_lines(lines);
+        self
+    }
+
+    /// Fold or show (the default) the unchanged lines between hunks.
+    #[must_use]
+    pub fn with_context_collapsed(mut self, collapsed: bool) -> Self {
+        self.set_context_collapsed(collapsed);
+        self
+    }
+
+    /// Show or hide (the default) line numbers.
+    #[must_use]
+    pub fn with_line_numbers(mut self, show: bool) -> Self {
+        self.line_numbers = show;
+        self
+    }
+
+    /// Show or hide (the default) a `@@` header above each hunk.
+    #[must_use]
+    pub fn with_hunk_headers(mut self, show: bool) -> Self {
+        self.set_hunk_headers(show);
+        self
+    }
+
+    /// Set a callback invoked when a hunk is accepted.
+    #[must_use]
+    pub fn with_on_accept<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&DiffHunk) + 'static,
+    {
+        self.on_accept = Some(Box::new(f));
+        self
+    }
+
+    /// Set a callback invoked when a hunk is rejected.
+    #[must_use]
+    pub fn with_on_reject<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&DiffHunk) + 'static,
+    {
+        self.on_reject = Some(Box::new(f));
+        self
+    }
+
+    /// Set or clear the syntax highlighting language.
+    pub fn set_language(&mut self, language: Option<&str>) {
+        self.language = language.map(str::to_string);
+        self.highlight();
+    }
+
+    /// Set the syntax highlighting styles.
+    pub fn set_syntax_theme(&mut self, theme: &SyntaxTheme) {
+        if self.syntax_theme != *theme {
+            self.syntax_theme = theme.clone();
+            self.highlight();
+        }
+    }
+
+    /// Set the style for unchanged lines.
+    pub fn set_unchanged_style(&mut self, style: Style) {
+        self.unchanged_style = style;
+    }
+
+    /// Get the border style.
+    pub fn border_style_kind(&self) -> BorderStyle {
+        self.border
+    }
+
+    /// Set the border style.
+    pub fn set_border(&mut self, border: BorderStyle) {
+        self.border = border;
+    }
+
     /// Set new texts and recompute the diff.
     pub fn set_texts(&mut self, old_text: &str, new_text: &str) {
         self.old_text = old_text.to_string();
         self.new_text = new_text.to_string();
+        self.from_patch = false;
         self.scroll_offset = 0;
         self.compute_diff();
     }
 
+    /// Replace the diff with the hunks of a unified diff or git patch.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SaorsaTuiError::Widget`](crate::error::SaorsaTuiError::Widget)
+    /// for a malformed hunk, leaving the view unchanged.
+    pub fn set_patch(&mut self, patch: &str) -> Result<()> {
+        let parsed = patch::parse_patch(patch)?;
+        self.old_text.clear();
+        self.new_text.clear();
+        self.from_patch = true;
+        self.scroll_offset = 0;
+        self.lines.clear();
+        self.hunks.clear();
+        for patch::PatchHunk { mut hunk, lines } in parsed {
+            let start = self.lines.len();
+            let mut old = hunk.old_first();
+            let mut new = hunk.new_first();
+            for (tag, text) in lines {
+                let old_number = (tag != ChangeTag::Insert).then(|| {
+                    old += 1;
+                    old - 1
+                });
+                let new_number = (tag != ChangeTag::Delete).then(|| {
+                    new += 1;
+                    new - 1
+                });
+                self.lines.push(DiffLine {
+                    tag,
+                    old_number,
+                    new_number,
+                    text,
+                    spans: Vec::new(),
+                    emphasis: Vec::new(),
+                });
+            }
+            hunk.lines = start..self.lines.len();
+            self.hunks.push(hunk);
+        }
+        self.refresh();
+        Ok(())
+    }
+
     /// Switch display mode.
     pub fn set_mode(&mut self, mode: DiffMode) {
         self.mode = mode;
         self.scroll_offset = 0;
+        self.build_rows();
     }
 
     /// Get the current display mode.
@@ -143,69 +496,309 @@ impl DiffView {
         self.mode
     }
 
-    /// Get the total number of display lines for the current mode.
-    pub fn line_count(&self) -> usize {
-        match self.mode {
-            DiffMode::Unified => self.unified_lines.len(),
-            DiffMode::SideBySide => self.sbs_pairs.len(),
+    /// Set how many unchanged lines are kept around each change. Hunks
+    /// are regrouped, which resets their status; a patch keeps its own
+    /// hunks.
+    pub fn set_context_lines(&mut self, lines: usize) {
+        self.context_lines = lines;
+        if !self.from_patch {
+            self.hunks = text_hunks(&self.lines, lines);
+            self.current_hunk = (!self.hunks.is_empty()).then_some(0);
+            self.build_rows();
         }
     }
 
+    /// How many unchanged lines are kept around each change.
+    pub fn context_lines(&self) -> usize {
+        self.context_lines
+    }
+
+    /// Fold or show the unchanged lines between hunks.
+    pub fn set_context_collapsed(&mut self, collapsed: bool) {
+        self.context_collapsed = collapsed;
+        self.build_rows();
+    }
+
+    /// Whether the unchanged lines between hunks are folded.
+    pub fn context_collapsed(&self) -> bool {
+        self.context_collapsed
+    }
+
+    /// Show or hide line numbers.
+    pub fn set_line_numbers(&mut self, show: bool) {
+        self.line_numbers = show;
+    }
+
+    /// Show or hide a `@@` header above each hunk.
+    pub fn set_hunk_headers(&mut self, show: bool) {
+        self.hunk_headers = show;
+        self.build_rows();
+    }
+
+    /// Get the total number of display rows for the current mode,
+    /// including hunk headers and folded context.
+    pub fn line_count(&self) -> usize {
+        self.rows.len()
+    }
+
     /// Get the scroll offset.
     pub fn scroll_offset(&self) -> usize {
         self.scroll_offset
     }
 
+    /// The diff's hunks.
+    pub fn hunks(&self) -> &[DiffHunk] {
+        &self.hunks
+    }
+
+    /// Index of the current hunk, which `a` and `r` act on.
+    pub fn current_hunk(&self) -> Option<usize> {
+        self.current_hunk
+    }
+
+    /// Make a hunk current and scroll it to the top.
+    pub fn select_hunk(&mut self, index: usize) {
+        if index >= self.hunks.len() {
+            return;
+        }
+        self.current_hunk = Some(index);
+        // A hunk starting a file is scrolled to with its path.
+        let lines = &self.hunks[index].lines;
+        if let Some(row) = self.rows.iter().position(|r| match *r {
+            Row::File(i) | Row::Header(i) => i == index,
+            Row::Line(i) => lines.contains(&i),
+            Row::Pair(old, new) => old.or(new).is_some_and(|i| lines.contains(&i)),
+            Row::Fold(_) => false,
+        }) {
+            self.scroll_offset = row;
+        }
+    }
+
+    /// Move to the next hunk.
+    pub fn next_hunk(&mut self) {
+        match self.current_hunk {
+            Some(index) => self.select_hunk(index + 1),
+            None => self.select_hunk(0),
+        }
+    }
+
+    /// Move to the previous hunk.
+    pub fn prev_hunk(&mut self) {
+        if let Some(index) = self.current_hunk.and_then(|i| i.checked_sub(1)) {
+            self.select_hunk(index);
+        }
+    }
+
+    /// Accept a hunk and invoke the accept callback.
+    pub fn accept_hunk(&mut self, index: usize) {
+        self.decide(index, HunkStatus::Accepted);
+    }
+
+    /// Reject a hunk and invoke the reject callback.
+    pub fn reject_hunk(&mut self, index: usize) {
+        self.decide(index, HunkStatus::Rejected);
+    }
+
+    /// A hunk as a unified diff, with file headers when its paths are
+    /// known, ready to apply with `git apply` or `patch`.
+    pub fn hunk_patch(&self, index: usize) -> Option<String> {
+        let hunk = self.hunks.get(index)?;
+        let mut out = String::new();
+        if hunk.path().is_some() {
+            let path = |path: &Option<String>, prefix: &str| {
+                path.as_ref()
+                    .map_or_else(|| "/dev/null".to_string(), |p| format!("{prefix}{p}"))
+            };
+            out.push_str(&format!("--- {}\n", path(&hunk.old_path, "a/")));
+            out.push_str(&format!("+++ {}\n", path(&hunk.new_path, "b/")));
+        }
+        out.push_str(&hunk.header());
+        out.push('\n');
+        for line in &self.lines[hunk.lines.clone()] {
+            out.push_str(Self::prefix_for_tag(line.tag));
+            out.push_str(&line.text);
+            out.push('\n');
+        }
+        Some(out)
+    }
+
+    /// Set a hunk's status and invoke the matching callback.
+    fn decide(&mut self, index: usize, status: HunkStatus) {
+        let Some(hunk) = self.hunks.get_mut(index) else {
+            return;
+        };
+        hunk.status = status;
+        let callback = match status {
+            HunkStatus::Accepted => &mut self.on_accept,
+            HunkStatus::Rejected => &mut self.on_reject,
+            HunkStatus::Pending => return,
+        };
+        if let Some(callback) = callback {
+            callback(hunk);
+        }
+    }
+
     /// Compute the diff between old and new text.
     fn compute_diff(&mut self) {
         let diff = TextDiff::from_lines(&self.old_text, &self.new_text);
-
-        // Build unified lines
-        self.unified_lines.clear();
-        for change in diff.iter_all_changes() {
-            let text = change.to_string_lossy().trim_end_matches('\n').to_string();
-            self.unified_lines.push(DiffLine {
+        self.lines = diff
+            .iter_all_changes()
+            .map(|change| DiffLine {
                 tag: change.tag(),
-                text,
-            });
+                old_number: change.old_index().map(|i| i + 1),
+                new_number: change.new_index().map(|i| i + 1),
+                text: change.to_string_lossy().trim_end_matches('\n').to_string(),
+                spans: Vec::new(),
+                emphasis: Vec::new(),
+            })
+            .collect();
+        self.hunks = text_hunks(&self.lines, self.context_lines);
+        self.refresh();
+    }
+
+    /// Recompute what derives from the lines and hunks.
+    fn refresh(&mut self) {
+        for hunk in &self.hunks {
+            emphasize(&mut self.lines[hunk.lines.clone()]);
         }
+        self.highlight();
+        self.number_width = self
+            .lines
+            .iter()
+            .flat_map(|l| [l.old_number, l.new_number])
+            .flatten()
+            .max()
+            .map_or(0, |n| n.to_string().len());
+        self.current_hunk = (!self.hunks.is_empty()).then_some(0);
+        self.build_rows();
+    }
 
-        // Build side-by-side pairs
-        self.sbs_pairs.clear();
-        let mut old_lines: Vec<DiffLine> = Vec::new();
-        let mut new_lines: Vec<DiffLine> = Vec::new();
+    /// Syntax highlight the lines: the whole diff for two texts, or hunk
+    /// by hunk for a patch, since only those lines are known.
+    fn highlight(&mut self) {
+        for line in &mut self.lines {
+            line.spans.clear();
+        }
+        let groups: Vec<(Range<usize>, Option<String>)> = if self.from_patch {
+            self.hunks
+                .iter()
+                .map(|hunk| {
+                    let extension = hunk
+                        .path()
+                        .and_then(|p| Path::new(p).extension())
+                        .and_then(|e| e.to_str())
+                        .map(str::to_string);
+                    (hunk.lines.clone(), self.language.clone().or(extension))
+                })
+                .collect()
+        } else {
+            vec![(0..self.lines.len(), self.language.clone())]
+        };
 
-        for change in diff.iter_all_changes() {
-            let text = change.to_string_lossy().trim_end_matches('\n').to_string();
-            match change.tag() {
-                ChangeTag::Equal => {
-                    flush_sbs_pairs(&mut self.sbs_pairs, &mut old_lines, &mut new_lines);
-                    self.sbs_pairs.push(SideBySidePair {
-                        left: Some(DiffLine {
-                            tag: ChangeTag::Equal,
-                            text: text.clone(),
-                        }),
-                        right: Some(DiffLine {
-                            tag: ChangeTag::Equal,
-                            text,
-                        }),
-                    });
+        for (range, language) in groups {
+            let Some(language) = language else {
+                continue;
+            };
+            let lines = &mut self.lines[range];
+            let side = |skip: ChangeTag| -> String {
+                lines
+                    .iter()
+                    .filter(|l| l.tag != skip)
+                    .map(|l| format!("{}\n", l.text))
+                    .collect()
+            };
+            let (old_text, new_text) = (side(ChangeTag::Insert), side(ChangeTag::Delete));
+            let Some(old) = highlighter_for_language(&language, &self.syntax_theme, &old_text)
+            else {
+                continue;
+            };
+            let Some(new) = highlighter_for_language(&language, &self.syntax_theme, &new_text)
+            else {
+                continue;
+            };
+            let (mut old_idx, mut new_idx) = (0, 0);
+            for line in lines.iter_mut() {
+                line.spans = match line.tag {
+                    ChangeTag::Insert => new.highlight_line(new_idx, &line.text),
+                    _ => old.highlight_line(old_idx, &line.text),
+                };
+                if line.tag != ChangeTag::Insert {
+                    old_idx += 1;
                 }
-                ChangeTag::Delete => {
-                    old_lines.push(DiffLine {
-                        tag: ChangeTag::Delete,
-                        text,
-                    });
+                if line.tag != ChangeTag::Delete {
+                    new_idx += 1;
                 }
-                ChangeTag::Insert => {
-                    new_lines.push(DiffLine {
-                        tag: ChangeTag::Insert,
-                        text,
-                    });
+            }
+        }
+    }
+
+    /// Rebuild the display rows for the mode and folding.
+    fn build_rows(&mut self) {
+        let mut rows = Vec::new();
+        let mut shown = 0;
+        for (index, hunk) in self.hunks.iter().enumerate() {
+            let prev = index.checked_sub(1).and_then(|i| self.hunks.get(i));
+            let same_file =
+                prev.is_some_and(|p| p.old_path == hunk.old_path && p.new_path == hunk.new_path);
+            if !same_file && hunk.path().is_some() {
+                rows.push(Row::File(index));
+            }
+            let hidden = if self.from_patch {
+                let next = match prev {
+                    Some(p) if same_file => p.old_first() + p.old_len,
+                    _ => 1,
+                };
+                hunk.old_first().saturating_sub(next)
+            } else {
+                hunk.lines.start - shown
+            };
+            self.push_gap(&mut rows, shown..hunk.lines.start, hidden);
+            if self.hunk_headers {
+                rows.push(Row::Header(index));
+            }
+            self.push_lines(&mut rows, hunk.lines.clone());
+            shown = hunk.lines.end;
+        }
+
+        let rest = shown..self.lines.len();
+        if self.hunks.is_empty() {
+            self.push_lines(&mut rows, rest);
+        } else {
+            self.push_gap(&mut rows, rest.clone(), rest.len());
+        }
+        self.rows = rows;
+    }
+
+    /// Push unchanged lines outside the hunks, or a fold standing for
+    /// `hidden` of them.
+    fn push_gap(&self, rows: &mut Vec<Row>, lines: Range<usize>, hidden: usize) {
+        if !self.context_collapsed && !lines.is_empty() {
+            self.push_lines(rows, lines);
+        } else if hidden > 0 {
+            rows.push(Row::Fold(hidden));
+        }
+    }
+
+    /// Push rows for a run of lines, pairing removed with added lines in
+    /// side-by-side mode.
+    fn push_lines(&self, rows: &mut Vec<Row>, lines: Range<usize>) {
+        if self.mode == DiffMode::Unified {
+            rows.extend(lines.map(Row::Line));
+            return;
+        }
+        let mut old_lines = Vec::new();
+        let mut new_lines = Vec::new();
+        for i in lines {
+            match self.lines[i].tag {
+                ChangeTag::Equal => {
+                    flush_sbs_pairs(rows, &mut old_lines, &mut new_lines);
+                    rows.push(Row::Pair(Some(i), Some(i)));
                 }
+                ChangeTag::Delete => old_lines.push(i),
+                ChangeTag::Insert => new_lines.push(i),
             }
         }
-        flush_sbs_pairs(&mut self.sbs_pairs, &mut old_lines, &mut new_lines);
+        flush_sbs_pairs(rows, &mut old_lines, &mut new_lines);
     }
 
     /// Get the style for a given change tag.
@@ -226,76 +819,168 @@ impl DiffView {
         }
     }
 
-    /// Render a single line of text into the buffer at the given position.
+    /// A line number padded to the gutter width, or blanks.
+    fn gutter_number(&self, number: Option<usize>) -> String {
+        let width = self.number_width;
+        number.map_or_else(|| " ".repeat(width), |n| format!("{n:>width$}"))
+    }
+
+    /// The rows in view for a given height.
+    fn visible_rows(&self, height: u16) -> Range<usize> {
+        let height = height as usize;
+        let count = self.rows.len();
+        let max_offset = count.saturating_sub(height.max(1));
+        let scroll = self.scroll_offset.min(max_offset);
+        scroll..(scroll + height).min(count)
+    }
+
+    /// Render a single line of text into the buffer at the given position,
+    /// overlaying its highlight spans and changed-word emphasis on `style`.
     fn render_line(
         &self,
-        text: &str,
+        line: &DiffLine,
         style: &Style,
         x: u16,
         y: u16,
         max_width: usize,
         buf: &mut ScreenBuffer,
     ) {
-        let truncated = truncate_to_display_width(text, max_width);
-        let mut col: u16 = 0;
-        for ch in truncated.chars() {
-            let char_w = unicode_width::UnicodeWidthStr::width(ch.encode_utf8(&mut [0; 4]) as &str);
-            if col as usize + char_w > max_width {
+        let emphasis = match line.tag {
+            ChangeTag::Equal => style,
+            ChangeTag::Insert => &self.added_emphasis_style,
+            ChangeTag::Delete => &self.removed_emphasis_style,
+        };
+        let mut col = 0;
+        for (i, ch) in line.text.chars().enumerate() {
+            let char_w = UnicodeWidthChar::width(ch).unwrap_or(0);
+            if col + char_w > max_width {
                 break;
             }
-            buf.set(x + col, y, Cell::new(ch.to_string(), style.clone()));
-            col += char_w as u16;
+            let mut char_style = match line
+                .spans
+                .iter()
+                .find(|s| i >= s.start_col && i < s.end_col)
+            {
+                Some(span) => style.merge(&span.style),
+                None => style.clone(),
+            };
+            if line.emphasis.iter().any(|r| r.contains(&i)) {
+                char_style = char_style.merge(emphasis);
+            }
+            buf.set(x + col as u16, y, Cell::new(ch.to_string(), char_style));
+            col += char_w;
         }
     }
 
-    /// Render in unified mode.
-    fn render_unified(&self, inner: Rect, buf: &mut ScreenBuffer) {
-        let height = inner.size.height as usize;
-        let width = inner.size.width as usize;
-        let count = self.unified_lines.len();
-        let max_offset = count.saturating_sub(height.max(1));
-        let scroll = self.scroll_offset.min(max_offset);
-        let end = (scroll + height).min(count);
-
-        for (row, line_idx) in (scroll..end).enumerate() {
-            let y = inner.position.y + row as u16;
-            if let Some(line) = self.unified_lines.get(line_idx) {
-                let style = self.style_for_tag(line.tag);
-                let prefix = Self::prefix_for_tag(line.tag);
-
-                // Fill the full row with the style
-                for col in 0..inner.size.width {
-                    buf.set(inner.position.x + col, y, Cell::new(" ", style.clone()));
-                }
+    /// Render a diff line filling `area`: the gutter in the line number
+    /// style, then the prefix and text over the line's background.
+    fn render_diff_line(
+        &self,
+        line: &DiffLine,
+        gutter: &str,
+        prefix: &str,
+        area: Rect,
+        buf: &mut ScreenBuffer,
+    ) {
+        let style = self.style_for_tag(line.tag);
+        let y = area.position.y;
+        let right = area.position.x + area.size.width;
+        let mut x = area.position.x;
+        x += draw_text(
+            buf,
+            x,
+            y,
+            (right - x) as usize,
+            gutter,
+            &self.line_number_style,
+        );
+        for col in x..right {
+            buf.set(col, y, Cell::new(" ", style.clone()));
+        }
+        x += draw_text(buf, x, y, (right - x) as usize, prefix, style);
+        self.render_line(line, style, x, y, (right - x) as usize, buf);
+    }
 
-                // Render prefix
-                if width > 0 {
-                    buf.set(inner.position.x, y, Cell::new(prefix, style.clone()));
+    /// Render a file path, hunk header or fold across `area`.
+    fn render_banner(&self, row: &Row, area: Rect, buf: &mut ScreenBuffer) {
+        let (text, style) = match *row {
+            Row::File(index) => {
+                let hunk = &self.hunks[index];
+                let text = match (&hunk.old_path, &hunk.new_path) {
+                    (Some(old), Some(new)) if old != new => format!("{old} \u{2192} {new}"),
+                    _ => hunk.path().unwrap_or_default().to_string(),
+                };
+                (text, self.header_style.clone().bold(true))
+            }
+            Row::Header(index) => {
+                let hunk = &self.hunks[index];
+                let mut text = hunk.header();
+                match hunk.status {
+                    HunkStatus::Pending => {}
+                    HunkStatus::Accepted => text.push_str("  \u{2713} accepted"),
+                    HunkStatus::Rejected => text.push_str("  \u{2717} rejected"),
                 }
+                let style = if self.current_hunk == Some(index) {
+                    self.header_style.clone().reverse(true)
+                } else {
+                    self.header_style.clone()
+                };
+                (text, style)
+            }
+            Row::Fold(hidden) => {
+                let plural = if hidden == 1 { "" } else { "s" };
+                (
+                    format!("\u{22ef} {hidden} unchanged line{plural}"),
+                    self.line_number_style.clone(),
+                )
+            }
+            Row::Line(_) | Row::Pair(..) => return,
+        };
+        let y = area.position.y;
+        for col in 0..area.size.width {
+            buf.set(area.position.x + col, y, Cell::new(" ", style.clone()));
+        }
+        draw_text(
+            buf,
+            area.position.x,
+            y,
+            area.size.width as usize,
+            &text,
+            &style,
+        );
+    }
 
-                // Render text content (after prefix)
-                if width > 1 {
-                    self.render_line(
-                        &line.text,
-                        style,
-                        inner.position.x + 1,
-                        y,
-                        width.saturating_sub(1),
-                        buf,
-                    );
+    /// Render in unified mode.
+    fn render_unified(&self, inner: Rect, buf: &mut ScreenBuffer) {
+        for (row, index) in self.visible_rows(inner.size.height).enumerate() {
+            let area = Rect::new(
+                inner.position.x,
+                inner.position.y + row as u16,
+                inner.size.width,
+                1,
+            );
+            match &self.rows[index] {
+                Row::Line(i) => {
+                    let line = &self.lines[*i];
+                    let gutter = if self.line_numbers {
+                        format!(
+                            "{} {} ",
+                            self.gutter_number(line.old_number),
+                            self.gutter_number(line.new_number)
+                        )
+                    } else {
+                        String::new()
+                    };
+                    self.render_diff_line(line, &gutter, Self::prefix_for_tag(line.tag), area, buf);
                 }
+                other => self.render_banner(other, area, buf),
             }
         }
     }
 
     /// Render in side-by-side mode.
     fn render_side_by_side(&self, inner: Rect, buf: &mut ScreenBuffer) {
-        let height = inner.size.height as usize;
         let total_width = inner.size.width as usize;
-        let count = self.sbs_pairs.len();
-        let max_offset = count.saturating_sub(height.max(1));
-        let scroll = self.scroll_offset.min(max_offset);
-        let end = (scroll + height).min(count);
 
         // Split width: left half | separator | right half
         if total_width < 3 {
@@ -314,32 +999,39 @@ impl DiffView {
             );
         }
 
-        for (row, pair_idx) in (scroll..end).enumerate() {
+        for (row, index) in self.visible_rows(inner.size.height).enumerate() {
             let y = inner.position.y + row as u16;
-            if let Some(pair) = self.sbs_pairs.get(pair_idx) {
-                // Left side
-                if let Some(ref left) = pair.left {
-                    let style = self.style_for_tag(left.tag);
-                    // Fill left side with style
-                    for col in 0..left_width {
-                        buf.set(
-                            inner.position.x + col as u16,
-                            y,
-                            Cell::new(" ", style.clone()),
-                        );
+            match self.rows[index] {
+                Row::Pair(left, right) => {
+                    let sides = [
+                        (left, inner.position.x, left_width),
+                        (
+                            right,
+                            inner.position.x + separator_col as u16 + 1,
+                            right_width,
+                        ),
+                    ];
+                    for (side, (line, x, width)) in sides.into_iter().enumerate() {
+                        let Some(line) = line.and_then(|i| self.lines.get(i)) else {
+                            continue;
+                        };
+                        let gutter = if self.line_numbers {
+                            let number = if side == 0 {
+                                line.old_number
+                            } else {
+                                line.new_number
+                            };
+                            format!("{} ", self.gutter_number(number))
+                        } else {
+                            String::new()
+                        };
+                        let area = Rect::new(x, y, width as u16, 1);
+                        self.render_diff_line(line, &gutter, "", area, buf);
                     }
-                    self.render_line(&left.text, style, inner.position.x, y, left_width, buf);
                 }
-
-                // Right side
-                if let Some(ref right) = pair.right {
-                    let style = self.style_for_tag(right.tag);
-                    let right_x = inner.position.x + separator_col as u16 + 1;
-                    // Fill right side with style
-                    for col in 0..right_width {
-                        buf.set(right_x + col as u16, y, Cell::new(" ", style.clone()));
-                    }
-                    self.render_line(&right.text, style, right_x, y, right_width, buf);
+                ref other => {
+                    let area = Rect::new(inner.position.x, y, inner.size.width, 1);
+                    self.render_banner(other, area, buf);
                 }
             }
         }
@@ -388,12 +1080,13 @@ impl InteractiveWidget for DiffView {
                 EventResult::Consumed
             }
             KeyCode::PageUp => {
-                self.scroll_offset = self.scroll_offset.saturating_sub(20);
+                self.scroll_offset = self.scroll_offset.saturating_sub(PAGE_ROWS);
                 EventResult::Consumed
             }
             KeyCode::PageDown => {
                 if count > 0 {
-                    self.scroll_offset = (self.scroll_offset + 20).min(count.saturating_sub(1));
+                    self.scroll_offset =
+                        (self.scroll_offset + PAGE_ROWS).min(count.saturating_sub(1));
                 }
                 EventResult::Consumed
             }
@@ -408,11 +1101,35 @@ impl InteractiveWidget for DiffView {
                 EventResult::Consumed
             }
             KeyCode::Char('m') => {
-                self.mode = match self.mode {
+                self.set_mode(match self.mode {
                     DiffMode::Unified => DiffMode::SideBySide,
                     DiffMode::SideBySide => DiffMode::Unified,
-                };
-                self.scroll_offset = 0;
+                });
+                EventResult::Consumed
+            }
+            KeyCode::Char('n') => {
+                self.next_hunk();
+                EventResult::Consumed
+            }
+            KeyCode::Char('p') => {
+                self.prev_hunk();
+                EventResult::Consumed
+            }
+            KeyCode::Char('a') | KeyCode::Char('r') => {
+                if let Some(index) = self.current_hunk {
+                    if *code == KeyCode::Char('a') {
+                        self.accept_hunk(index);
+                    } else {
+                        self.reject_hunk(index);
+                    }
+                }
+                EventResult::Consumed
+            }
+            KeyCode::Char('z') => {
+                self.set_context_collapsed(!self.context_collapsed);
+                if let Some(index) = self.current_hunk {
+                    self.select_hunk(index);
+                }
                 EventResult::Consumed
             }
             _ => EventResult::Ignored,
@@ -420,18 +1137,138 @@ impl InteractiveWidget for DiffView {
     }
 }
 
+/// Group changed lines into hunks with `context` unchanged lines around
+/// them, merging hunks whose context would touch.
+fn text_hunks(lines: &[DiffLine], context: usize) -> Vec<DiffHunk> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.tag == ChangeTag::Equal {
+            continue;
+        }
+        let start = i.saturating_sub(context);
+        let end = (i + 1 + context).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|range| {
+            let before = &lines[..range.start];
+            let within = &lines[range.clone()];
+            let old_len = within.iter().filter(|l| l.old_number.is_some()).count();
+            let new_len = within.iter().filter(|l| l.new_number.is_some()).count();
+            let old_before = before.iter().rev().find_map(|l| l.old_number).unwrap_or(0);
+            let new_before = before.iter().rev().find_map(|l| l.new_number).unwrap_or(0);
+            DiffHunk {
+                old_path: None,
+                new_path: None,
+                old_start: old_before + usize::from(old_len > 0),
+                old_len,
+                new_start: new_before + usize::from(new_len > 0),
+                new_len,
+                section: String::new(),
+                status: HunkStatus::Pending,
+                lines: range,
+            }
+        })
+        .collect()
+}
+
+/// Mark the words that changed between the removed and added lines of
+/// each run of changes in a hunk, using `similar`'s inline diff. Lines that are
+/// mostly rewritten get no emphasis.
+fn emphasize(lines: &mut [DiffLine]) {
+    let mut start = 0;
+    while start < lines.len() {
+        let end = lines[start..]
+            .iter()
+            .position(|l| l.tag == ChangeTag::Equal)
+            .map_or(lines.len(), |p| start + p);
+        if end > start {
+            emphasize_run(&mut lines[start..end]);
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+}
+
+/// Emphasise the changed words within one run of removed and added lines.
+fn emphasize_run(run: &mut [DiffLine]) {
+    let (old, new): (Vec<usize>, Vec<usize>) =
+        (0..run.len()).partition(|&i| run[i].tag == ChangeTag::Delete);
+    if old.is_empty() || new.is_empty() {
+        return;
+    }
+    let joined = |indices: &[usize]| -> String {
+        indices
+            .iter()
+            .map(|&i| format!("{}\n", run[i].text))
+            .collect()
+    };
+    let (old_text, new_text) = (joined(&old), joined(&new));
+
+    let diff = TextDiff::from_lines(&old_text, &new_text);
+    for op in diff.ops() {
+        for change in diff.iter_inline_changes(op) {
+            let line = match change.tag() {
+                ChangeTag::Delete => change.old_index().and_then(|i| old.get(i)),
+                ChangeTag::Insert => change.new_index().and_then(|i| new.get(i)),
+                ChangeTag::Equal => None,
+            };
+            let Some(&line) = line else {
+                continue;
+            };
+            let mut ranges: Vec<Range<usize>> = Vec::new();
+            let mut pos = 0;
+            for (emphasized, value) in change.iter_strings_lossy() {
+                let len = value.trim_end_matches('\n').chars().count();
+                if emphasized && len > 0 {
+                    match ranges.last_mut() {
+                        Some(last) if last.end == pos => last.end = pos + len,
+                        _ => ranges.push(pos..pos + len),
+                    }
+                }
+                pos += len;
+            }
+            run[line].emphasis = ranges;
+        }
+    }
+}
+
+/// Draw `text` from `x`, clipped to `width` columns; returns the columns
+/// used.
+fn draw_text(
+    buf: &mut ScreenBuffer,
+    x: u16,
+    y: u16,
+    width: usize,
+    text: &str,
+    style: &Style,
+) -> u16 {
+    let mut col = 0;
+    for ch in text.chars() {
+        let char_w = UnicodeWidthChar::width(ch).unwrap_or(0);
+        if col + char_w > width {
+            break;
+        }
+        buf.set(x + col as u16, y, Cell::new(ch.to_string(), style.clone()));
+        col += char_w;
+    }
+    col as u16
+}
+
 /// Flush accumulated old/new lines into side-by-side pairs.
-fn flush_sbs_pairs(
-    pairs: &mut Vec<SideBySidePair>,
-    old_lines: &mut Vec<DiffLine>,
-    new_lines: &mut Vec<DiffLine>,
-) {
+fn flush_sbs_pairs(rows: &mut Vec<Row>, old_lines: &mut Vec<usize>, new_lines: &mut Vec<usize>) {
     let max_len = old_lines.len().max(new_lines.len());
     for i in 0..max_len {
-        pairs.push(SideBySidePair {
-            left: old_lines.get(i).cloned(),
-            right: new_lines.get(i).cloned(),
-        });
+        rows.push(Row::Pair(
+            old_lines.get(i).copied(),
+            new_lines.get(i).copied(),
+        ));
     }
     old_lines.clear();
     new_lines.clear();
@@ -441,8 +1278,33 @@ fn flush_sbs_pairs(
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::event::Modifiers;
     use crate::geometry::Size;
 
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, Modifiers::NONE))
+    }
+
+    fn row_text(buf: &ScreenBuffer, y: u16) -> String {
+        let mut text = String::new();
+        for x in 0..buf.width() {
+            if let Some(cell) = buf.get(x, y) {
+                text.push_str(&cell.grapheme);
+            }
+        }
+        text.trim_end().to_string()
+    }
+
+    /// Twenty numbered lines with lines 2 and 18 changed, shown with
+    /// hunk headers and folded context.
+    fn two_hunks() -> DiffView {
+        let old: String = (1..=20).map(|i| format!("l{i}\n")).collect();
+        let new = old.replace("l2\n", "L2\n").replace("l18\n", "L18\n");
+        DiffView::new(&old, &new)
+            .with_hunk_headers(true)
+            .with_context_collapsed(true)
+    }
+
     #[test]
     fn create_diff_view() {
         let dv = DiffView::new("hello\nworld\n", "hello\nrust\n");
@@ -455,13 +1317,14 @@ mod tests {
         let dv = DiffView::new("aaa\nbbb\n", "aaa\nccc\n");
 
         // Should have: " aaa", "-bbb", "+ccc"
-        assert_eq!(dv.unified_lines.len(), 3);
-        assert_eq!(dv.unified_lines[0].tag, ChangeTag::Equal);
-        assert_eq!(dv.unified_lines[0].text, "aaa");
-        assert_eq!(dv.unified_lines[1].tag, ChangeTag::Delete);
-        assert_eq!(dv.unified_lines[1].text, "bbb");
-        assert_eq!(dv.unified_lines[2].tag, ChangeTag::Insert);
-        assert_eq!(dv.unified_lines[2].text, "ccc");
+        assert_eq!(dv.lines.len(), 3);
+        assert_eq!(dv.lines[0].tag, ChangeTag::Equal);
+        assert_eq!(dv.lines[0].text, "aaa");
+        assert_eq!(dv.lines[1].tag, ChangeTag::Delete);
+        assert_eq!(dv.lines[1].text, "bbb");
+        assert_eq!(dv.lines[2].tag, ChangeTag::Insert);
+        assert_eq!(dv.lines[2].text, "ccc");
+        assert_eq!(dv.rows, vec![Row::Line(0), Row::Line(1), Row::Line(2)]);
     }
 
     #[test]
@@ -471,24 +1334,9 @@ mod tests {
         assert_eq!(dv.mode(), DiffMode::SideBySide);
         // Pair 1: aaa | aaa (equal)
         // Pair 2: bbb | ccc (delete | insert)
-        assert_eq!(dv.sbs_pairs.len(), 2);
-
-        assert!(dv.sbs_pairs[0].left.is_some());
-        assert!(dv.sbs_pairs[0].right.is_some());
         assert_eq!(
-            dv.sbs_pairs[0].left.as_ref().map(|l| l.tag),
-            Some(ChangeTag::Equal)
-        );
-
-        assert!(dv.sbs_pairs[1].left.is_some());
-        assert!(dv.sbs_pairs[1].right.is_some());
-        assert_eq!(
-            dv.sbs_pairs[1].left.as_ref().map(|l| l.tag),
-            Some(ChangeTag::Delete)
-        );
-        assert_eq!(
-            dv.sbs_pairs[1].right.as_ref().map(|l| l.tag),
-            Some(ChangeTag::Insert)
+            dv.rows,
+            vec![Row::Pair(Some(0), Some(0)), Row::Pair(Some(1), Some(2))]
         );
     }
 
@@ -496,14 +1344,8 @@ mod tests {
     fn scroll_up_down() {
         let mut dv = DiffView::new("a\nb\nc\nd\ne\nf\n", "a\nb\nc\nd\ne\nf\n");
 
-        let down = Event::Key(KeyEvent {
-            code: KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let up = Event::Key(KeyEvent {
-            code: KeyCode::Up,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = key(KeyCode::Down);
+        let up = key(KeyCode::Up);
 
         assert_eq!(dv.scroll_offset(), 0);
         dv.handle_event(&down);
@@ -517,23 +1359,12 @@ mod tests {
 
     #[test]
     fn page_up_down() {
-        let mut dv = DiffView::new(
-            "line1\nline2\nline3\nline4\nline5\nline6\nline7\nline8\nline9\nline10\nline11\nline12\nline13\nline14\nline15\nline16\nline17\nline18\nline19\nline20\nline21\nline22\nline23\nline24\nline25\n",
-            "line1\nline2\nline3\nline4\nline5\nline6\nline7\nline8\nline9\nline10\nline11\nline12\nline13\nline14\nline15\nline16\nline17\nline18\nline19\nline20\nline21\nline22\nline23\nline24\nline25\n",
-        );
-
-        let pgdn = Event::Key(KeyEvent {
-            code: KeyCode::PageDown,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let pgup = Event::Key(KeyEvent {
-            code: KeyCode::PageUp,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let text: String = (1..=25).map(|i| format!("line{i}\n")).collect();
+        let mut dv = DiffView::new(&text, &text);
 
-        dv.handle_event(&pgdn);
+        dv.handle_event(&key(KeyCode::PageDown));
         assert_eq!(dv.scroll_offset(), 20);
-        dv.handle_event(&pgup);
+        dv.handle_event(&key(KeyCode::PageUp));
         assert_eq!(dv.scroll_offset(), 0);
     }
 
@@ -541,18 +1372,9 @@ mod tests {
     fn home_end() {
         let mut dv = DiffView::new("a\nb\nc\nd\ne\n", "a\nb\nc\nd\ne\n");
 
-        let end_key = Event::Key(KeyEvent {
-            code: KeyCode::End,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let home_key = Event::Key(KeyEvent {
-            code: KeyCode::Home,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-
-        dv.handle_event(&end_key);
+        dv.handle_event(&key(KeyCode::End));
         assert_eq!(dv.scroll_offset(), dv.line_count().saturating_sub(1));
-        dv.handle_event(&home_key);
+        dv.handle_event(&key(KeyCode::Home));
         assert_eq!(dv.scroll_offset(), 0);
     }
 
@@ -561,11 +1383,7 @@ mod tests {
         let mut dv = DiffView::new("a\n", "b\n");
         assert_eq!(dv.mode(), DiffMode::Unified);
 
-        let m = Event::Key(KeyEvent {
-            code: KeyCode::Char('m'),
-            modifiers: crate::event::Modifiers::NONE,
-        });
-
+        let m = key(KeyCode::Char('m'));
         dv.handle_event(&m);
         assert_eq!(dv.mode(), DiffMode::SideBySide);
         dv.handle_event(&m);
@@ -575,31 +1393,36 @@ mod tests {
     #[test]
     fn empty_diff_identical_texts() {
         let dv = DiffView::new("same\n", "same\n");
-        assert_eq!(dv.unified_lines.len(), 1);
-        assert_eq!(dv.unified_lines[0].tag, ChangeTag::Equal);
+        assert_eq!(dv.lines.len(), 1);
+        assert_eq!(dv.lines[0].tag, ChangeTag::Equal);
+        // Nothing changed, so nothing folds.
+        assert!(dv.hunks().is_empty());
+        assert_eq!(dv.rows, vec![Row::Line(0)]);
     }
 
     #[test]
     fn all_added_old_empty() {
         let dv = DiffView::new("", "new1\nnew2\n");
-        for line in &dv.unified_lines {
+        for line in &dv.lines {
             assert_eq!(line.tag, ChangeTag::Insert);
         }
+        assert_eq!(dv.hunks()[0].header(), "@@ -0,0 +1,2 @@");
     }
 
     #[test]
     fn all_removed_new_empty() {
         let dv = DiffView::new("old1\nold2\n", "");
-        for line in &dv.unified_lines {
+        for line in &dv.lines {
             assert_eq!(line.tag, ChangeTag::Delete);
         }
+        assert_eq!(dv.hunks()[0].header(), "@@ -1,2 +0,0 @@");
     }
 
     #[test]
     fn mixed_changes() {
         let dv = DiffView::new("a\nb\nc\n", "a\nB\nc\nd\n");
         // a = equal, b = delete, B = insert, c = equal, d = insert
-        let tags: Vec<ChangeTag> = dv.unified_lines.iter().map(|l| l.tag).collect();
+        let tags: Vec<ChangeTag> = dv.lines.iter().map(|l| l.tag).collect();
         assert!(tags.contains(&ChangeTag::Equal));
         assert!(tags.contains(&ChangeTag::Delete));
         assert!(tags.contains(&ChangeTag::Insert));
@@ -630,18 +1453,207 @@ mod tests {
         );
     }
 
+    #[test]
+    fn line_numbers_for_both_sides() {
+        let dv = DiffView::new("a\nb\nc\n", "a\nc\nd\n").with_line_numbers(true);
+        let mut buf = ScreenBuffer::new(Size::new(20, 6));
+        dv.render(Rect::new(0, 0, 20, 6), &mut buf);
+
+        let rows: Vec<String> = (0..4).map(|y| row_text(&buf, y)).collect();
+        assert_eq!(rows, vec!["1 1  a", "2   -b", "3 2  c", "  3 +d"]);
+        assert!(buf.get(0, 0).is_some_and(|c| c.style.dim));
+
+        let dv = dv.with_mode(DiffMode::SideBySide);
+        dv.render(Rect::new(0, 0, 20, 6), &mut buf);
+        assert_eq!(row_text(&buf, 1), "2 b       \u{2502}");
+    }
+
+    #[test]
+    fn hunk_headers_are_opt_in() {
+        let mut dv = DiffView::new("a\nb\nc\n", "a\nc\nd\n").with_hunk_headers(true);
+        let mut buf = ScreenBuffer::new(Size::new(20, 6));
+        dv.render(Rect::new(0, 0, 20, 6), &mut buf);
+        assert_eq!(row_text(&buf, 0), "@@ -1,3 +1,3 @@");
+        assert_eq!(row_text(&buf, 1), " a");
+
+        dv.set_hunk_headers(false);
+        assert!(!dv.rows.contains(&Row::Header(0)));
+    }
+
+    #[test]
+    fn defaults_show_every_line() {
+        let old: String = (1..=20).map(|i| format!("l{i}\n")).collect();
+        let new = old.replace("l2\n", "L2\n").replace("l18\n", "L18\n");
+        let mut dv = DiffView::new(&old, &new);
+        assert_eq!(dv.hunks().len(), 2);
+        assert!(dv.rows.iter().all(|r| matches!(r, Row::Line(_))));
+        assert_eq!(dv.line_count(), 22);
+
+        // Without headers, hunk navigation scrolls to the hunk's first line.
+        dv.handle_event(&key(KeyCode::Char('n')));
+        assert_eq!(dv.scroll_offset(), 15);
+        assert_eq!(dv.rows[15], Row::Line(dv.hunks()[1].lines.start));
+    }
+
+    #[test]
+    fn changed_words_are_emphasised() {
+        let dv = DiffView::new("let total = 1;\n", "let total = 2;\n");
+        // Words include their punctuation: "1;" became "2;".
+        for line in dv.lines.iter().filter(|l| l.tag != ChangeTag::Equal) {
+            assert_eq!(line.emphasis.len(), 1);
+            assert_eq!(line.emphasis[0], 12..14);
+        }
+
+        let mut buf = ScreenBuffer::new(Size::new(30, 6));
+        dv.render(Rect::new(0, 0, 30, 6), &mut buf);
+        let cell = |x, y| buf.get(x, y).map(|c| c.style.clone()).unwrap_or_default();
+        // Row 0 is "-let total = 1;": the changed word is emphasised.
+        assert!(cell(13, 0).bold);
+        assert_eq!(cell(13, 0).bg, Some(Color::Named(NamedColor::BrightRed)));
+        assert!(!cell(5, 0).bold);
+    }
+
+    #[test]
+    fn rewritten_lines_are_not_emphasised() {
+        let dv = DiffView::new("abc\n", "something else entirely\n");
+        assert!(dv.lines.iter().all(|l| l.emphasis.is_empty()));
+    }
+
+    #[test]
+    fn distant_context_folds_between_hunks() {
+        let mut dv = two_hunks();
+        let headers: Vec<String> = dv.hunks().iter().map(DiffHunk::header).collect();
+        assert_eq!(headers, vec!["@@ -1,5 +1,5 @@", "@@ -15,6 +15,6 @@"]);
+        assert_eq!(dv.rows[7], Row::Fold(9));
+        assert_eq!(dv.line_count(), 1 + 6 + 1 + 1 + 7);
+
+        let mut buf = ScreenBuffer::new(Size::new(30, 20));
+        dv.render(Rect::new(0, 0, 30, 20), &mut buf);
+        assert_eq!(row_text(&buf, 7), "\u{22ef} 9 unchanged lines");
+
+        dv.handle_event(&key(KeyCode::Char('z')));
+        assert!(!dv.context_collapsed());
+        assert!(!dv.rows.iter().any(|r| matches!(r, Row::Fold(_))));
+        assert_eq!(dv.line_count(), 22 + 2);
+
+        dv.set_context_collapsed(true);
+        dv.set_context_lines(10);
+        assert_eq!(dv.hunks().len(), 1);
+        dv.set_context_lines(0);
+        assert_eq!(dv.hunks()[0].header(), "@@ -2 +2 @@");
+        assert_eq!(dv.rows[0], Row::Fold(1));
+    }
+
+    #[test]
+    fn hunk_navigation_scrolls_to_headers() {
+        let mut dv = two_hunks();
+        assert_eq!(dv.current_hunk(), Some(0));
+        dv.handle_event(&key(KeyCode::Char('n')));
+        assert_eq!(dv.current_hunk(), Some(1));
+        assert_eq!(dv.scroll_offset(), 8);
+        // Already at the last hunk.
+        dv.handle_event(&key(KeyCode::Char('n')));
+        assert_eq!(dv.current_hunk(), Some(1));
+        dv.handle_event(&key(KeyCode::Char('p')));
+        assert_eq!((dv.current_hunk(), dv.scroll_offset()), (Some(0), 0));
+
+        let mut buf = ScreenBuffer::new(Size::new(30, 4));
+        dv.render(Rect::new(0, 0, 30, 4), &mut buf);
+        assert!(buf.get(0, 0).is_some_and(|c| c.style.reverse));
+    }
+
+    #[test]
+    fn accept_and_reject_invoke_callbacks() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let decided = Rc::new(RefCell::new(Vec::new()));
+        let accepted = Rc::clone(&decided);
+        let rejected = Rc::clone(&decided);
+        let mut dv = two_hunks()
+            .with_on_accept(move |h: &DiffHunk| accepted.borrow_mut().push(("a", h.old_start)))
+            .with_on_reject(move |h: &DiffHunk| rejected.borrow_mut().push(("r", h.old_start)));
+
+        dv.handle_event(&key(KeyCode::Char('a')));
+        dv.handle_event(&key(KeyCode::Char('n')));
+        dv.handle_event(&key(KeyCode::Char('r')));
+        assert_eq!(*decided.borrow(), vec![("a", 1), ("r", 15)]);
+        assert_eq!(dv.hunks()[0].status, HunkStatus::Accepted);
+        assert_eq!(dv.hunks()[1].status, HunkStatus::Rejected);
+
+        let mut buf = ScreenBuffer::new(Size::new(40, 20));
+        dv.render(Rect::new(0, 0, 40, 20), &mut buf);
+        assert_eq!(row_text(&buf, 0), "@@ -1,5 +1,5 @@  \u{2713} accepted");
+    }
+
+    #[test]
+    fn patch_hunks_keep_their_line_numbers() {
+        let patch = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+            @@ -2,2 +2,2 @@ mod a\n one\n-two\n+TWO\n@@ -10 +10 @@\n-ten\n+TEN\n";
+        let dv = DiffView::from_patch(patch)
+            .unwrap()
+            .with_hunk_headers(true)
+            .with_line_numbers(true);
+        assert_eq!(
+            dv.rows,
+            vec![
+                Row::File(0),
+                Row::Fold(1),
+                Row::Header(0),
+                Row::Line(0),
+                Row::Line(1),
+                Row::Line(2),
+                Row::Fold(6),
+                Row::Header(1),
+                Row::Line(3),
+                Row::Line(4),
+            ]
+        );
+        assert_eq!(dv.lines[2].new_number, Some(3));
+        assert_eq!(dv.lines[3].old_number, Some(10));
+        assert_eq!(dv.hunks()[0].section, "mod a");
+
+        let mut buf = ScreenBuffer::new(Size::new(30, 10));
+        dv.render(Rect::new(0, 0, 30, 10), &mut buf);
+        assert_eq!(row_text(&buf, 0), "src/lib.rs");
+        assert_eq!(row_text(&buf, 2), "@@ -2,2 +2,2 @@ mod a");
+        assert_eq!(row_text(&buf, 8), "10    -ten");
+
+        // Patches cannot unfold what they do not contain.
+        let mut dv = dv.with_context_collapsed(false);
+        dv.set_context_lines(0);
+        assert!(dv.rows.contains(&Row::Fold(6)));
+        assert_eq!(dv.line_count(), 10);
+        assert!(DiffView::from_patch("@@ -1 +1 @@\n").is_err());
+    }
+
+    #[test]
+    fn hunk_patch_round_trips() {
+        let dv = two_hunks();
+        let patch = dv.hunk_patch(1).unwrap();
+        assert!(patch.starts_with("@@ -15,6 +15,6 @@\n l15\n"));
+        let again = DiffView::from_patch(&patch).unwrap();
+        assert_eq!(again.hunks()[0].header(), "@@ -15,6 +15,6 @@");
+        assert_eq!(again.hunk_patch(0), Some(patch));
+        assert_eq!(dv.hunk_patch(2), None);
+
+        let file = DiffView::from_patch("--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n").unwrap();
+        assert_eq!(
+            file.hunk_patch(0).unwrap(),
+            "--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n"
+        );
+    }
+
     #[test]
     fn set_texts_recomputes() {
         let mut dv = DiffView::new("a\n", "b\n");
-        let initial_count = dv.line_count();
+        dv.handle_event(&key(KeyCode::Down));
 
         dv.set_texts("x\ny\nz\n", "x\nw\nz\n");
         // Should have recomputed
-        assert!(dv.line_count() > 0);
+        assert_eq!(dv.hunks()[0].header(), "@@ -1,3 +1,3 @@");
         // Scroll should be reset
         assert_eq!(dv.scroll_offset(), 0);
-        // Count may differ
-        let _ = initial_count;
     }
 
     #[test]
@@ -664,4 +1676,36 @@ mod tests {
         // Should render without panic
         assert!(buf.get(0, 0).is_some());
     }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn language_highlights_over_line_colors() {
+        let theme = SyntaxTheme::empty().with("keyword", Style::new().bold(true));
+        let mut dv = DiffView::new("let a = 1;\n", "let a = 2;\n").with_language("rust");
+        dv.set_syntax_theme(&theme);
+        let mut buf = ScreenBuffer::new(Size::new(30, 3));
+        dv.render(Rect::new(0, 0, 30, 3), &mut buf);
+        for y in 0..2 {
+            let style = buf.get(1, y).map(|c| c.style.clone()).unwrap_or_default();
+            assert!(style.bold, "`let` is a keyword on row {y}");
+            assert!(style.bg.is_some(), "the diff background stays on row {y}");
+            let plain = buf.get(5, y).map(|c| c.style.clone()).unwrap_or_default();
+            assert!(!plain.bold);
+        }
+
+        dv.set_language(None);
+        dv.render(Rect::new(0, 0, 30, 3), &mut buf);
+        assert!(!buf.get(1, 0).is_some_and(|c| c.style.bold));
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn patch_hunks_highlight_by_extension() {
+        let theme = SyntaxTheme::empty().with("keyword", Style::new().italic(true));
+        let mut dv =
+            DiffView::from_patch("--- a/m.rs\n+++ b/m.rs\n@@ -1 +1 @@\n-let a = 1;\n+fn f() {}\n")
+                .unwrap();
+        dv.set_syntax_theme(&theme);
+        assert!(dv.lines.iter().all(|l| !l.spans.is_empty()));
+    }
 }
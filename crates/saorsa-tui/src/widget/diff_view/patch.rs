@@ -0,0 +1,200 @@
+//! Reading hunks from unified diffs and git patches.
+
+use similar::ChangeTag;
+
+use crate::error::{Result, SaorsaTuiError};
+
+use super::{DiffHunk, HunkStatus};
+
+/// A hunk read from a patch, with its lines.
+pub(super) struct PatchHunk {
+    pub hunk: DiffHunk,
+    pub lines: Vec<(ChangeTag, String)>,
+}
+
+/// Read every hunk in `text`, a unified diff or a git patch covering any
+/// number of files. Text outside file and hunk headers (commit messages,
+/// `index` lines, mode changes) is skipped.
+pub(super) fn parse_patch(text: &str) -> Result<Vec<PatchHunk>> {
+    let mut hunks = Vec::new();
+    let mut old_path = None;
+    let mut new_path = None;
+    let mut lines = text.lines().enumerate().peekable();
+
+    while let Some((n, line)) = lines.next() {
+        if line.starts_with("diff ") {
+            old_path = None;
+            new_path = None;
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            old_path = file_path(path, "a/");
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            new_path = file_path(path, "b/");
+        } else if line.starts_with("@@") {
+            let Some((old_start, old_len, new_start, new_len, section)) = parse_header(line) else {
+                return Err(invalid(n, "malformed hunk header"));
+            };
+            let mut old_left = old_len;
+            let mut new_left = new_len;
+            let mut body = Vec::new();
+            while old_left > 0 || new_left > 0 {
+                let Some((m, line)) = lines.next() else {
+                    return Err(invalid(n, "hunk ends before its header says"));
+                };
+                let tag = match line.chars().next() {
+                    // Some tools strip the space from empty context lines.
+                    Some(' ') | None => ChangeTag::Equal,
+                    Some('-') => ChangeTag::Delete,
+                    Some('+') => ChangeTag::Insert,
+                    Some('\\') => continue,
+                    Some(_) => return Err(invalid(m, "unexpected line in hunk")),
+                };
+                let (old, new) = match tag {
+                    ChangeTag::Equal => (1, 1),
+                    ChangeTag::Delete => (1, 0),
+                    ChangeTag::Insert => (0, 1),
+                };
+                if old > old_left || new > new_left {
+                    return Err(invalid(m, "hunk is longer than its header says"));
+                }
+                old_left -= old;
+                new_left -= new;
+                body.push((tag, line.get(1..).unwrap_or("").to_string()));
+            }
+            // "\ No newline at end of file" after the last line.
+            while lines.next_if(|(_, line)| line.starts_with('\\')).is_some() {}
+
+            hunks.push(PatchHunk {
+                hunk: DiffHunk {
+                    old_path: old_path.clone(),
+                    new_path: new_path.clone(),
+                    old_start,
+                    old_len,
+                    new_start,
+                    new_len,
+                    section,
+                    status: HunkStatus::Pending,
+                    lines: 0..0,
+                },
+                lines: body,
+            });
+        }
+    }
+    Ok(hunks)
+}
+
+/// An error for line `index` (zero-based) of a patch.
+fn invalid(index: usize, reason: &str) -> SaorsaTuiError {
+    SaorsaTuiError::Widget(format!("invalid patch at line {}: {reason}", index + 1))
+}
+
+/// The path on a `---`/`+++` line without its timestamp or git's `a/`
+/// and `b/` prefixes; `None` for `/dev/null`.
+fn file_path(path: &str, prefix: &str) -> Option<String> {
+    let path = path.split('\t').next().unwrap_or(path).trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
+
+/// The ranges and section text of a `@@ -a,b +c,d @@ section` line.
+fn parse_header(line: &str) -> Option<(usize, usize, usize, usize, String)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let (old_start, old_len) = parse_range(old)?;
+    let (new_start, new_len) = parse_range(new)?;
+    Some((
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+        section.trim().to_string(),
+    ))
+}
+
+/// `start,len`, where a missing length means one line.
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "From 1234 Mon Sep 17 00:00:00 2001\n\
+        Subject: [PATCH] Tweak\n\
+        \n\
+        ---\n\
+        diff --git a/src/lib.rs b/src/lib.rs\n\
+        index 83db48f..bf269f4 100644\n\
+        --- a/src/lib.rs\n\
+        +++ b/src/lib.rs\n\
+        @@ -1,3 +1,3 @@ fn main() {\n\
+        \x20one\n\
+        -two\n\
+        +TWO\n\
+        \n\
+        @@ -10 +10,2 @@\n\
+        \x20ten\n\
+        +eleven\n\
+        \\ No newline at end of file\n\
+        diff --git a/old.txt b/old.txt\n\
+        deleted file mode 100644\n\
+        --- a/old.txt\n\
+        +++ /dev/null\n\
+        @@ -1 +0,0 @@\n\
+        -gone\n";
+
+    #[test]
+    fn reads_hunks_across_files() {
+        let hunks = match parse_patch(PATCH) {
+            Ok(hunks) => hunks,
+            Err(e) => unreachable!("patch should parse: {e}"),
+        };
+        assert_eq!(hunks.len(), 3);
+
+        let first = &hunks[0];
+        assert_eq!(first.hunk.new_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(
+            (
+                first.hunk.old_start,
+                first.hunk.old_len,
+                first.hunk.new_start
+            ),
+            (1, 3, 1)
+        );
+        assert_eq!(first.hunk.section, "fn main() {");
+        let tags: Vec<ChangeTag> = first.lines.iter().map(|(tag, _)| *tag).collect();
+        use ChangeTag::{Delete, Equal, Insert};
+        assert_eq!(tags, vec![Equal, Delete, Insert, Equal]);
+        assert_eq!(first.lines[3].1, "");
+
+        assert_eq!((hunks[1].hunk.old_len, hunks[1].hunk.new_len), (1, 2));
+        assert_eq!(hunks[2].hunk.old_path.as_deref(), Some("old.txt"));
+        assert_eq!(hunks[2].hunk.new_path, None);
+        assert_eq!(hunks[2].lines, vec![(Delete, "gone".to_string())]);
+    }
+
+    #[test]
+    fn plain_unified_diff_keeps_paths() {
+        let diff = "--- notes.txt\t2024-01-01 10:00:00\n+++ notes.txt\t2024-01-02 10:00:00\n\
+            @@ -1 +1 @@\n-a\n+b\n";
+        let hunks = parse_patch(diff).unwrap_or_default();
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].hunk.old_path.as_deref(), Some("notes.txt"));
+    }
+
+    #[test]
+    fn malformed_patches_are_errors() {
+        let bad_header = parse_patch("@@ -x +1 @@\n");
+        assert!(matches!(bad_header, Err(SaorsaTuiError::Widget(ref m)) if m.contains("line 1")));
+        assert!(parse_patch("@@ -1,2 +1,2 @@\n a\n").is_err());
+        assert!(parse_patch("@@ -1 +1 @@\n-a\n-b\n").is_err());
+        assert!(parse_patch("@@ -1 +1 @@\n*a\n").is_err());
+        assert!(matches!(parse_patch("no hunks here\n"), Ok(h) if h.is_empty()));
+    }
+}
@@ -1,30 +1,272 @@
 //! Filesystem directory tree widget.
 //!
 //! Wraps [`Tree<PathBuf>`] with lazy directory loading, file/directory
-//! icons, hidden file filtering, and sorted entries (directories first).
-
-use std::path::{Path, PathBuf};
+//! icons, hidden and `.gitignore` filtering, git status decorations, and
+//! sorted entries (directories first). The tree can follow filesystem
+//! changes, jump to a file by fuzzy search, and create, rename and delete
+//! files after confirmation.
+//!
+//! Keys, besides the [`Tree`] navigation keys: `/` jumps to a file, `a`
+//! creates a file (a name ending in `/` creates a directory), `r` or F2
+//! renames, `d` or Delete deletes, `.` toggles hidden files, `i` toggles
+//! `.gitignore` filtering and F5 refreshes.
+
+mod git;
+
+use std::cell::{Cell as StdCell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use ignore::WalkBuilder;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use unicode_width::UnicodeWidthChar;
 
 use crate::buffer::ScreenBuffer;
+use crate::cell::Cell;
+use crate::color::{Color, NamedColor};
 use crate::error::SaorsaTuiError;
-use crate::event::Event;
+use crate::event::{Event, KeyCode, KeyEvent, Modifiers};
 use crate::geometry::Rect;
 use crate::segment::Segment;
 use crate::style::Style;
+use git::GitStatus;
+
+pub use git::GitFileStatus;
 
 use super::tree::{Tree, TreeNode};
 use super::{BorderStyle, EventResult, InteractiveWidget, Widget};
 
+/// Most files indexed for jumping.
+const MAX_JUMP_FILES: usize = 100_000;
+
+/// Most matches listed while jumping.
+const MAX_JUMP_MATCHES: usize = 100;
+
+/// How long the watched directory must stay quiet before a refresh.
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Longest a refresh is put off while changes keep arriving.
+const MAX_REFRESH_DELAY: Duration = Duration::from_secs(2);
+
+/// A file operation requested from the keyboard, passed to the hook set
+/// with [`DirectoryTree::with_on_file_op`] before it is applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileOp {
+    /// Create a file or directory.
+    Create {
+        /// The path to create.
+        path: PathBuf,
+        /// Whether to create a directory.
+        is_dir: bool,
+    },
+    /// Rename a file or directory.
+    Rename {
+        /// The current path.
+        from: PathBuf,
+        /// The new path.
+        to: PathBuf,
+    },
+    /// Delete a file, or a directory and its contents.
+    Delete {
+        /// The path to delete.
+        path: PathBuf,
+    },
+}
+
+/// Type alias for the file operation hook.
+type FileOpFn = Option<Box<dyn FnMut(&FileOp) -> bool>>;
+
+/// Which directory entries are listed.
+#[derive(Clone, Copy, Debug)]
+struct Listing {
+    show_hidden: bool,
+    gitignore: bool,
+}
+
+impl Listing {
+    fn walker(self, dir: &Path) -> WalkBuilder {
+        let mut builder = WalkBuilder::new(dir);
+        builder
+            .hidden(!self.show_hidden)
+            .ignore(false)
+            .git_ignore(self.gitignore)
+            .git_exclude(self.gitignore)
+            .git_global(self.gitignore)
+            .require_git(false);
+        if self.gitignore {
+            builder.filter_entry(|entry| entry.file_name() != ".git");
+        }
+        builder
+    }
+
+    /// Whether `path` is listed in its parent directory.
+    fn lists(self, path: &Path) -> bool {
+        let Some(parent) = path.parent() else {
+            return false;
+        };
+        self.walker(parent)
+            .max_depth(Some(1))
+            .build()
+            .flatten()
+            .any(|entry| entry.depth() == 1 && entry.path() == path)
+    }
+
+    /// `dir` and the listed directories below it, parents first. `.git`
+    /// directories are never included.
+    fn dirs(self, dir: &Path) -> Vec<PathBuf> {
+        self.walker(dir)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build()
+            .flatten()
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_dir()))
+            .map(ignore::DirEntry::into_path)
+            .collect()
+    }
+
+    /// Load directory contents, sorted (directories first, then files,
+    /// alphabetically).
+    ///
+    /// Silently skips entries that cannot be read (permission denied, etc.).
+    fn load(self, path: &Path) -> Vec<TreeNode<PathBuf>> {
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        let mut files: Vec<PathBuf> = Vec::new();
+
+        for entry in self.walker(path).max_depth(Some(1)).build().flatten() {
+            if entry.depth() == 0 {
+                continue;
+            }
+            let entry_path = entry.into_path();
+            if entry_path.is_dir() {
+                dirs.push(entry_path);
+            } else {
+                files.push(entry_path);
+            }
+        }
+
+        // Sort alphabetically (case-insensitive)
+        dirs.sort_by_cached_key(|p| sort_key(p));
+        files.sort_by_cached_key(|p| sort_key(p));
+
+        dirs.into_iter()
+            .map(TreeNode::branch)
+            .chain(files.into_iter().map(TreeNode::new))
+            .collect()
+    }
+
+    /// Every listed file below `root`, up to [`MAX_JUMP_FILES`].
+    fn files(self, root: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .walker(root)
+            .build()
+            .flatten()
+            .filter(|entry| entry.file_type().is_some_and(|t| !t.is_dir()))
+            .map(ignore::DirEntry::into_path)
+            .take(MAX_JUMP_FILES)
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+fn sort_key(path: &Path) -> Option<String> {
+    path.file_name().map(|n| n.to_string_lossy().to_lowercase())
+}
+
+/// Git statuses and how to show them, shared with the tree's render
+/// function.
+struct Decorations {
+    status: Option<GitStatus>,
+    /// Styles, indexed like [`GitFileStatus::ALL`].
+    styles: [Style; 7],
+}
+
+/// A file matched while jumping.
+struct JumpMatch {
+    path: PathBuf,
+    /// Path relative to the root, as shown.
+    label: String,
+    /// Character indices of `label` matching the query.
+    indices: Vec<usize>,
+}
+
+/// An open prompt, which takes all key events.
+enum Prompt {
+    /// Fuzzy search for a file to jump to.
+    Jump {
+        query: String,
+        matches: Vec<JumpMatch>,
+        selected: usize,
+    },
+    /// Name of a file or directory to create in `dir`.
+    Create { dir: PathBuf, name: String },
+    /// New name for `path`.
+    Rename { path: PathBuf, name: String },
+    /// Confirmation to delete `path`.
+    Delete { path: PathBuf },
+}
+
+/// A change reported by the watcher.
+enum Change {
+    /// Paths outside `.git` changed; empty when unknown.
+    Tree(Vec<PathBuf>),
+    /// Git's index, `HEAD` or refs changed, e.g. after `git commit`.
+    GitState,
+}
+
+/// Watches the tree's listed directories for changes.
+struct Watch {
+    watcher: RecommendedWatcher,
+    /// Directories watched (non-recursively) for tree changes.
+    watched: HashSet<PathBuf>,
+    changes: mpsc::Receiver<Change>,
+    /// When the first and the latest tree change not yet refreshed arrived.
+    pending: Option<(Instant, Instant)>,
+    /// Likewise for git state changes, which only reload git statuses.
+    git_pending: Option<(Instant, Instant)>,
+}
+
 /// A directory tree widget backed by the filesystem.
 ///
 /// Lazily loads directory contents on expand, with icons for files
-/// and directories. Supports hiding dotfiles and sorting (directories
+/// and directories. Supports hiding dotfiles and `.gitignore`d files,
+/// decorating entries with their git status, and sorting (directories
 /// first, then files, alphabetically).
 pub struct DirectoryTree {
     /// The underlying generic tree widget.
     tree: Tree<PathBuf>,
-    /// Whether to show hidden (dot) files.
-    show_hidden: bool,
+    /// The directory shown at the top of the tree.
+    root: PathBuf,
+    /// Which entries are listed, shared with the lazy load function.
+    listing: Rc<StdCell<Listing>>,
+    /// Git statuses and their styles, shared with the render function.
+    decorations: Rc<RefCell<Decorations>>,
+    /// Whether git statuses are read.
+    git_status: bool,
+    /// Border style, to place prompts inside it.
+    border: BorderStyle,
+    /// Style for unselected nodes.
+    node_style: Style,
+    /// Style for the selected node and jump match.
+    selected_style: Style,
+    /// Style for prompts and messages.
+    prompt_style: Style,
+    /// Style merged onto the characters matching a jump query.
+    match_style: Style,
+    /// The open prompt, if any.
+    prompt: Option<Prompt>,
+    /// The result of the last file operation, shown until the next key.
+    message: Option<String>,
+    /// Hook called before applying a file operation from the keyboard.
+    on_file_op: FileOpFn,
+    /// Files to jump to, indexed when first needed.
+    files: Option<Vec<PathBuf>>,
+    /// Filesystem watcher, once started.
+    watch: Option<Watch>,
 }
 
 impl DirectoryTree {
@@ -45,11 +287,19 @@ impl DirectoryTree {
             )));
         }
 
-        let root_node = TreeNode::branch(root);
-        let show_hidden = false;
-
-        let tree = Tree::new(vec![root_node])
-            .with_render_fn(|data: &PathBuf, _depth, _expanded, is_leaf| {
+        let listing = Rc::new(StdCell::new(Listing {
+            show_hidden: false,
+            gitignore: true,
+        }));
+        let decorations = Rc::new(RefCell::new(Decorations {
+            status: GitStatus::read(&root),
+            styles: default_status_styles(),
+        }));
+
+        let render_decorations = Rc::clone(&decorations);
+        let load_listing = Rc::clone(&listing);
+        let tree = Tree::new(vec![TreeNode::branch(root.clone())])
+            .with_render_fn(move |data: &PathBuf, _depth, _expanded, is_leaf| {
                 let name = data
                     .file_name()
                     .map(|n| n.to_string_lossy().to_string())
@@ -59,36 +309,97 @@ impl DirectoryTree {
                 } else {
                     "\u{1f4c1}" // 📁
                 };
-                vec![Segment::new(format!("{icon} {name}"))]
+                let label = format!("{icon} {name}");
+                let decorations = render_decorations.borrow();
+                let Some(status) = decorations.status.as_ref().and_then(|s| s.get(data)) else {
+                    return vec![Segment::new(label)];
+                };
+                let style = decorations.styles[status as usize].clone();
+                let mut segments = vec![Segment::styled(label, style.clone())];
+                if let Some(marker) = status.marker() {
+                    segments.push(Segment::styled(format!(" {marker}"), style));
+                }
+                segments
             })
-            .with_lazy_load(move |path: &PathBuf| load_directory(path, false));
-
-        Ok(Self { tree, show_hidden })
+            .with_lazy_load(move |path: &PathBuf| load_listing.get().load(path));
+
+        Ok(Self {
+            tree,
+            root,
+            listing,
+            decorations,
+            git_status: true,
+            border: BorderStyle::None,
+            node_style: Style::default(),
+            selected_style: Style::default().reverse(true),
+            prompt_style: Style::new().reverse(true),
+            match_style: Style::new().fg(Color::Named(NamedColor::Yellow)).bold(true),
+            prompt: None,
+            message: None,
+            on_file_op: None,
+            files: None,
+            watch: None,
+        })
     }
 
     /// Set whether to show hidden (dot) files.
     #[must_use]
     pub fn with_show_hidden(mut self, enabled: bool) -> Self {
-        self.show_hidden = enabled;
-        // Rebuild lazy load with updated visibility setting
-        let show = self.show_hidden;
-        self.tree = self
-            .tree
-            .with_lazy_load(move |path: &PathBuf| load_directory(path, show));
+        self.set_show_hidden(enabled);
+        self
+    }
+
+    /// Set whether to hide files matched by `.gitignore`, git's exclude
+    /// file and the global excludes file. On by default.
+    #[must_use]
+    pub fn with_gitignore(mut self, enabled: bool) -> Self {
+        self.set_gitignore(enabled);
+        self
+    }
+
+    /// Set whether to decorate entries with their git status. On by
+    /// default; has no effect outside a git repository.
+    #[must_use]
+    pub fn with_git_status(mut self, enabled: bool) -> Self {
+        self.git_status = enabled;
+        self.reload_git_status();
+        self
+    }
+
+    /// Set the style for entries with the given git status.
+    #[must_use]
+    pub fn with_status_style(self, status: GitFileStatus, style: Style) -> Self {
+        self.decorations.borrow_mut().styles[status as usize] = style;
         self
     }
 
     /// Set the style for unselected nodes.
     #[must_use]
     pub fn with_node_style(mut self, style: Style) -> Self {
-        self.tree = self.tree.with_node_style(style);
+        self.tree = self.tree.with_node_style(style.clone());
+        self.node_style = style;
         self
     }
 
     /// Set the style for the selected node.
     #[must_use]
     pub fn with_selected_style(mut self, style: Style) -> Self {
-        self.tree = self.tree.with_selected_style(style);
+        self.tree = self.tree.with_selected_style(style.clone());
+        self.selected_style = style;
+        self
+    }
+
+    /// Set the style for prompts and messages.
+    #[must_use]
+    pub fn with_prompt_style(mut self, style: Style) -> Self {
+        self.prompt_style = style;
+        self
+    }
+
+    /// Set the style merged onto the characters matching a jump query.
+    #[must_use]
+    pub fn with_match_style(mut self, style: Style) -> Self {
+        self.match_style = style;
         self
     }
 
@@ -96,9 +407,27 @@ impl DirectoryTree {
     #[must_use]
     pub fn with_border(mut self, border: BorderStyle) -> Self {
         self.tree = self.tree.with_border(border);
+        self.border = border;
+        self
+    }
+
+    /// Set a hook called with each file operation confirmed from the
+    /// keyboard, before it is applied. Returning `false` cancels the
+    /// operation.
+    #[must_use]
+    pub fn with_on_file_op<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&FileOp) -> bool + 'static,
+    {
+        self.on_file_op = Some(Box::new(f));
         self
     }
 
+    /// Get the root directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
     /// Get the selected path, if any.
     pub fn selected_path(&self) -> Option<&PathBuf> {
         self.tree.selected_node().map(|node| &node.data)
@@ -126,72 +455,791 @@ impl DirectoryTree {
 
     /// Get whether hidden files are shown.
     pub fn show_hidden(&self) -> bool {
-        self.show_hidden
+        self.listing.get().show_hidden
     }
-}
 
-impl Widget for DirectoryTree {
-    fn render(&self, area: Rect, buf: &mut ScreenBuffer) {
-        self.tree.render(area, buf);
+    /// Show or hide hidden (dot) files, reloading loaded directories.
+    pub fn set_show_hidden(&mut self, enabled: bool) {
+        let listing = self.listing.get();
+        if listing.show_hidden != enabled {
+            self.listing.set(Listing {
+                show_hidden: enabled,
+                ..listing
+            });
+            self.rewatch();
+            self.refresh();
+        }
     }
-}
 
-impl InteractiveWidget for DirectoryTree {
-    fn handle_event(&mut self, event: &Event) -> EventResult {
-        self.tree.handle_event(event)
+    /// Get whether files matched by `.gitignore` are hidden.
+    pub fn respects_gitignore(&self) -> bool {
+        self.listing.get().gitignore
     }
-}
 
-/// Load directory contents, sorted (directories first, then files, alphabetically).
-///
-/// Silently skips entries that cannot be read (permission denied, etc.).
-fn load_directory(path: &Path, show_hidden: bool) -> Vec<TreeNode<PathBuf>> {
-    let entries = match std::fs::read_dir(path) {
-        Ok(e) => e,
-        Err(_) => return Vec::new(),
-    };
-
-    let mut dirs: Vec<PathBuf> = Vec::new();
-    let mut files: Vec<PathBuf> = Vec::new();
-
-    for entry in entries.flatten() {
-        let entry_path = entry.path();
-        let name = entry.file_name().to_string_lossy().to_string();
-
-        // Filter hidden files
-        if !show_hidden && name.starts_with('.') {
-            continue;
+    /// Hide or show files matched by `.gitignore`, reloading loaded
+    /// directories.
+    pub fn set_gitignore(&mut self, enabled: bool) {
+        let listing = self.listing.get();
+        if listing.gitignore != enabled {
+            self.listing.set(Listing {
+                gitignore: enabled,
+                ..listing
+            });
+            self.rewatch();
+            self.refresh();
+        }
+    }
+
+    /// Get the git status of a path, if it differs from the last commit
+    /// or is ignored. Directories report the most important status of
+    /// their contents.
+    pub fn git_status(&self, path: &Path) -> Option<GitFileStatus> {
+        self.decorations.borrow().status.as_ref()?.get(path)
+    }
+
+    /// Get the message left by the last file operation, such as an error.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    fn reload_git_status(&mut self) {
+        self.decorations.borrow_mut().status = if self.git_status {
+            GitStatus::read(&self.root)
+        } else {
+            None
+        };
+    }
+
+    /// Reload the loaded directories and git statuses from the
+    /// filesystem, keeping expanded directories and the selection.
+    pub fn refresh(&mut self) {
+        let selected = self.selected_path().cloned();
+        let listing = self.listing.get();
+        for root in self.tree.roots_mut() {
+            refresh_node(root, listing);
+        }
+        if let Some(selected) = selected {
+            // Fall back to the nearest remaining ancestor.
+            for path in selected.ancestors() {
+                if self.tree.select_where(|p| p == path) {
+                    break;
+                }
+            }
+        }
+        self.reload_git_status();
+        self.files = None;
+        if let Some(watch) = &mut self.watch {
+            // Watches on removed directories went with them.
+            watch.watched.retain(|dir| dir.is_dir());
+        }
+    }
+
+    /// Start watching the root directory, so that [`poll_changes`]
+    /// refreshes the tree after files change.
+    ///
+    /// Only listed directories are watched, each non-recursively, so
+    /// hidden and ignored trees such as `target/` use no watches;
+    /// directories are watched as they are created. Inside `.git` only
+    /// the index, `HEAD` and refs are followed, and their changes (after
+    /// `git add`, `commit`, `checkout`...) reload git statuses without
+    /// re-reading the tree.
+    ///
+    /// [`poll_changes`]: DirectoryTree::poll_changes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the watcher cannot be created or the root
+    /// cannot be watched.
+    pub fn watch(&mut self) -> Result<(), SaorsaTuiError> {
+        let (tx, rx) = mpsc::channel();
+        let root = self.root.clone();
+        let git_dir = GitStatus::git_dir(&self.root);
+        let event_git_dir = git_dir.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
+                let Ok(event) = res else {
+                    return;
+                };
+                if matches!(event.kind, EventKind::Access(_)) {
+                    return;
+                }
+                let unknown = event.paths.is_empty();
+                let mut git_state = false;
+                let paths: Vec<PathBuf> = event
+                    .paths
+                    .into_iter()
+                    .filter(|path| match &event_git_dir {
+                        Some(git_dir) if path.starts_with(git_dir) => {
+                            git_state |= is_git_state(git_dir, path);
+                            false
+                        }
+                        _ => !in_git_dir(&root, path),
+                    })
+                    .collect();
+                if git_state {
+                    let _ = tx.send(Change::GitState);
+                }
+                if unknown || !paths.is_empty() {
+                    let _ = tx.send(Change::Tree(paths));
+                }
+            })
+            .map_err(|e| SaorsaTuiError::Widget(format!("failed to create watcher: {e}")))?;
+        if let Some(git_dir) = &git_dir {
+            watcher
+                .watch(git_dir, RecursiveMode::NonRecursive)
+                .and_then(|()| watcher.watch(&git_dir.join("refs"), RecursiveMode::Recursive))
+                .map_err(watch_error)?;
+        }
+        self.watch = Some(Watch {
+            watcher,
+            watched: HashSet::new(),
+            changes: rx,
+            pending: None,
+            git_pending: None,
+        });
+        let root = self.root.clone();
+        let watched = self.watch_listed(&root);
+        if watched.is_err() {
+            self.watch = None;
+        }
+        watched
+    }
+
+    /// Watch `dir` and the listed directories below it.
+    fn watch_listed(&mut self, dir: &Path) -> Result<(), SaorsaTuiError> {
+        let listing = self.listing.get();
+        let Some(watch) = &mut self.watch else {
+            return Ok(());
+        };
+        for dir in listing.dirs(dir) {
+            if !watch.watched.contains(&dir) {
+                watch
+                    .watcher
+                    .watch(&dir, RecursiveMode::NonRecursive)
+                    .map_err(watch_error)?;
+                watch.watched.insert(dir);
+            }
+        }
+        Ok(())
+    }
+
+    /// Watch the directories listed now that hidden or ignored files are
+    /// shown or hidden.
+    fn rewatch(&mut self) {
+        let Some(watch) = &mut self.watch else {
+            return;
+        };
+        for dir in watch.watched.drain() {
+            // Fails for directories removed meanwhile.
+            let _ = watch.watcher.unwatch(&dir);
+        }
+        let root = self.root.clone();
+        if self.watch_listed(&root).is_err() {
+            self.watch = None;
+        }
+    }
+
+    /// Refresh the tree if the watched directories changed and have since
+    /// been quiet for a moment, so a burst of changes refreshes it once.
+    /// Changes to git's state alone reload just the git statuses, with
+    /// the same delay. Returns whether anything was reloaded.
+    pub fn poll_changes(&mut self) -> bool {
+        let Some(watch) = &mut self.watch else {
+            return false;
+        };
+        let now = Instant::now();
+        let listing = self.listing.get();
+        let mut new_dirs = Vec::new();
+        {
+            let decorations = self.decorations.borrow();
+            let hidden = |path: &PathBuf| {
+                listing.gitignore
+                    && decorations
+                        .status
+                        .as_ref()
+                        .and_then(|status| status.get(path))
+                        == Some(GitFileStatus::Ignored)
+            };
+            while let Ok(change) = watch.changes.try_recv() {
+                let pending = match change {
+                    Change::GitState => &mut watch.git_pending,
+                    Change::Tree(paths) => {
+                        new_dirs.extend(
+                            paths
+                                .iter()
+                                .filter(|path| !watch.watched.contains(*path) && path.is_dir())
+                                .cloned(),
+                        );
+                        if !paths.is_empty() && paths.iter().all(hidden) {
+                            continue;
+                        }
+                        &mut watch.pending
+                    }
+                };
+                let first = pending.map_or(now, |(first, _)| first);
+                *pending = Some((first, now));
+            }
         }
+        for dir in new_dirs {
+            if listing.lists(&dir) {
+                // The directory may already be gone again, which the
+                // pending refresh picks up.
+                let _ = self.watch_listed(&dir);
+            }
+        }
+
+        let Some(watch) = &mut self.watch else {
+            return false;
+        };
+        let due = |pending: Option<(Instant, Instant)>| {
+            pending.is_some_and(|(first, last)| {
+                now - last >= REFRESH_DEBOUNCE || now - first >= MAX_REFRESH_DELAY
+            })
+        };
+        if due(watch.pending) {
+            watch.pending = None;
+            watch.git_pending = None;
+            self.refresh();
+            true
+        } else if due(watch.git_pending) {
+            watch.git_pending = None;
+            self.reload_git_status();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the files matching a fuzzy query, best first. Files are listed
+    /// like the tree: hidden and ignored files only when shown.
+    pub fn jump_matches(&mut self, query: &str) -> Vec<PathBuf> {
+        self.find_matches(query)
+            .into_iter()
+            .map(|m| m.path)
+            .collect()
+    }
+
+    fn find_matches(&mut self, query: &str) -> Vec<JumpMatch> {
+        let root = &self.root;
+        let listing = self.listing.get();
+        let files = self.files.get_or_insert_with(|| listing.files(root));
+        let matcher = SkimMatcherV2::default();
+        let mut matches: Vec<(i64, JumpMatch)> = files
+            .iter()
+            .filter_map(|path| {
+                let label = path
+                    .strip_prefix(root)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .into_owned();
+                let (score, indices) = matcher.fuzzy_indices(&label, query)?;
+                Some((
+                    score,
+                    JumpMatch {
+                        path: path.clone(),
+                        label,
+                        indices,
+                    },
+                ))
+            })
+            .collect();
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then(a.label.len().cmp(&b.label.len()))
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        matches.truncate(MAX_JUMP_MATCHES);
+        matches.into_iter().map(|(_, m)| m).collect()
+    }
+
+    /// Expand the directories leading to `path` and select it. Returns
+    /// `false` if the path is not listed in the tree.
+    pub fn reveal(&mut self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        let listing = self.listing.get();
+        let Some(mut node) = self.tree.roots_mut().first_mut() else {
+            return false;
+        };
+        let mut current = self.root.clone();
+        for component in relative.components() {
+            if node.is_leaf {
+                return false;
+            }
+            if node.children.is_empty() {
+                node.children = listing.load(&node.data);
+            }
+            node.expanded = true;
+            current.push(component);
+            let Some(child) = node.children.iter_mut().find(|c| c.data == current) else {
+                return false;
+            };
+            node = child;
+        }
+        self.tree.select_where(|p| p == path)
+    }
+
+    /// Check that `path` is strictly inside the root.
+    fn check_inside(&self, path: &Path) -> Result<(), SaorsaTuiError> {
+        let inside = path
+            .strip_prefix(&self.root)
+            .is_ok_and(|rest| rest.components().next().is_some())
+            && !path.components().any(|c| c == Component::ParentDir);
+        if inside {
+            Ok(())
+        } else {
+            Err(SaorsaTuiError::Widget(format!(
+                "path is outside the tree: {}",
+                path.display()
+            )))
+        }
+    }
+
+    /// Create a file, or a directory when `is_dir` is set, creating
+    /// missing parent directories, then refresh and select it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is outside the tree, already exists,
+    /// or cannot be created.
+    pub fn create(&mut self, path: &Path, is_dir: bool) -> Result<(), SaorsaTuiError> {
+        self.check_inside(path)?;
+        if path.exists() {
+            return Err(SaorsaTuiError::Widget(format!(
+                "path already exists: {}",
+                path.display()
+            )));
+        }
+        if is_dir {
+            std::fs::create_dir_all(path)?;
+        } else {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(path)?;
+        }
+        self.refresh();
+        self.reveal(path);
+        Ok(())
+    }
+
+    /// Rename a file or directory, then refresh and select it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either path is outside the tree, the new path
+    /// already exists, or the rename fails.
+    pub fn rename(&mut self, from: &Path, to: &Path) -> Result<(), SaorsaTuiError> {
+        self.check_inside(from)?;
+        self.check_inside(to)?;
+        if to.exists() {
+            return Err(SaorsaTuiError::Widget(format!(
+                "path already exists: {}",
+                to.display()
+            )));
+        }
+        std::fs::rename(from, to)?;
+        self.refresh();
+        self.reveal(to);
+        Ok(())
+    }
 
-        if entry_path.is_dir() {
-            dirs.push(entry_path);
+    /// Delete a file, or a directory and everything in it, then refresh.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is outside the tree or cannot be
+    /// deleted.
+    pub fn delete(&mut self, path: &Path) -> Result<(), SaorsaTuiError> {
+        self.check_inside(path)?;
+        if path.is_dir() && !path.is_symlink() {
+            std::fs::remove_dir_all(path)?;
         } else {
-            files.push(entry_path);
+            std::fs::remove_file(path)?;
+        }
+        self.refresh();
+        Ok(())
+    }
+
+    /// Run a file operation confirmed from the keyboard, unless the hook
+    /// cancels it, and leave a message describing the outcome.
+    fn apply(&mut self, op: FileOp) {
+        if let Some(hook) = &mut self.on_file_op
+            && !hook(&op)
+        {
+            self.message = Some("cancelled".to_string());
+            return;
+        }
+        let result = match &op {
+            FileOp::Create { path, is_dir } => self.create(path, *is_dir),
+            FileOp::Rename { from, to } => self.rename(from, to),
+            FileOp::Delete { path } => self.delete(path),
+        };
+        self.message = match result {
+            Ok(()) => None,
+            Err(e) => Some(e.to_string()),
+        };
+    }
+
+    /// Open a prompt for the selected entry.
+    fn open_prompt(&mut self, code: KeyCode) {
+        let Some(selected) = self.selected_path().cloned() else {
+            return;
+        };
+        let is_root = selected == self.root;
+        self.prompt = match code {
+            KeyCode::Char('/') => Some(Prompt::Jump {
+                matches: self.find_matches(""),
+                query: String::new(),
+                selected: 0,
+            }),
+            KeyCode::Char('a') => {
+                let dir = if selected.is_dir() {
+                    selected
+                } else {
+                    selected
+                        .parent()
+                        .map_or_else(|| self.root.clone(), Path::to_path_buf)
+                };
+                Some(Prompt::Create {
+                    dir,
+                    name: String::new(),
+                })
+            }
+            KeyCode::Char('r') | KeyCode::F(2) if !is_root => Some(Prompt::Rename {
+                name: file_name(&selected),
+                path: selected,
+            }),
+            KeyCode::Char('d') | KeyCode::Delete if !is_root => {
+                Some(Prompt::Delete { path: selected })
+            }
+            _ => None,
+        };
+    }
+
+    /// Handle a key while a prompt is open.
+    fn prompt_key(&mut self, code: KeyCode) {
+        let Some(mut prompt) = self.prompt.take() else {
+            return;
+        };
+        if code == KeyCode::Escape {
+            return;
+        }
+        match &mut prompt {
+            Prompt::Jump {
+                query,
+                matches,
+                selected,
+            } => match code {
+                KeyCode::Enter => {
+                    if let Some(m) = matches.get(*selected) {
+                        let path = m.path.clone();
+                        self.reveal(&path);
+                    }
+                    return;
+                }
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(matches.len().saturating_sub(1)),
+                KeyCode::Char(c) => {
+                    query.push(c);
+                    *matches = self.find_matches(query);
+                    *selected = 0;
+                }
+                KeyCode::Backspace => {
+                    query.pop();
+                    *matches = self.find_matches(query);
+                    *selected = 0;
+                }
+                _ => {}
+            },
+            Prompt::Create { dir, name } => match code {
+                KeyCode::Enter => {
+                    if let Some(path) = child_path(dir, name) {
+                        let is_dir = name.ends_with('/');
+                        self.apply(FileOp::Create { path, is_dir });
+                    }
+                    return;
+                }
+                code => edit_name(name, code),
+            },
+            Prompt::Rename { path, name } => match code {
+                KeyCode::Enter => {
+                    let parent = path.parent().unwrap_or(&self.root);
+                    if let Some(to) = child_path(parent, name)
+                        && to != *path
+                    {
+                        self.apply(FileOp::Rename {
+                            from: path.clone(),
+                            to,
+                        });
+                    }
+                    return;
+                }
+                code => edit_name(name, code),
+            },
+            Prompt::Delete { path } => {
+                if matches!(code, KeyCode::Char('y' | 'Y')) {
+                    let path = path.clone();
+                    self.apply(FileOp::Delete { path });
+                }
+                return;
+            }
         }
+        self.prompt = Some(prompt);
     }
 
-    // Sort alphabetically (case-insensitive)
-    dirs.sort_by(|a, b| {
-        let a_name = a.file_name().map(|n| n.to_string_lossy().to_lowercase());
-        let b_name = b.file_name().map(|n| n.to_string_lossy().to_lowercase());
-        a_name.cmp(&b_name)
-    });
-    files.sort_by(|a, b| {
-        let a_name = a.file_name().map(|n| n.to_string_lossy().to_lowercase());
-        let b_name = b.file_name().map(|n| n.to_string_lossy().to_lowercase());
-        a_name.cmp(&b_name)
-    });
+    /// Draw the jump search over the tree's area.
+    fn render_jump(
+        &self,
+        query: &str,
+        matches: &[JumpMatch],
+        selected: usize,
+        inner: Rect,
+        buf: &mut ScreenBuffer,
+    ) {
+        let prompt = format!("> {query}");
+        draw_line(
+            buf,
+            inner,
+            inner.position.y,
+            prompt.chars().map(|ch| (ch, self.prompt_style.clone())),
+            &self.prompt_style,
+        );
+        let rows = usize::from(inner.size.height.saturating_sub(1));
+        let first = (selected + 1).saturating_sub(rows);
+        for (row, (index, m)) in matches
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(rows)
+            .enumerate()
+        {
+            let style = if index == selected {
+                &self.selected_style
+            } else {
+                &self.node_style
+            };
+            let chars = m.label.chars().enumerate().map(|(i, ch)| {
+                if m.indices.contains(&i) {
+                    (ch, style.merge(&self.match_style))
+                } else {
+                    (ch, style.clone())
+                }
+            });
+            draw_line(buf, inner, inner.position.y + 1 + row as u16, chars, style);
+        }
+        for row in matches.len().saturating_sub(first).min(rows)..rows {
+            draw_line(
+                buf,
+                inner,
+                inner.position.y + 1 + row as u16,
+                std::iter::empty(),
+                &self.node_style,
+            );
+        }
+    }
+
+    /// The text of the bottom line: an open prompt or the last message.
+    fn status_line(&self) -> Option<String> {
+        let relative = |path: &Path| {
+            let shown = path.strip_prefix(&self.root).unwrap_or(path);
+            shown.to_string_lossy().into_owned()
+        };
+        Some(match &self.prompt {
+            Some(Prompt::Create { dir, name }) => {
+                let dir = relative(dir);
+                let dir = if dir.is_empty() { ".".into() } else { dir };
+                format!("New in {dir}/: {name}")
+            }
+            Some(Prompt::Rename { path, name }) => format!("Rename {}: {name}", relative(path)),
+            Some(Prompt::Delete { path }) => format!("Delete {}? (y/n)", relative(path)),
+            Some(Prompt::Jump { .. }) => return None,
+            None => self.message.clone()?,
+        })
+    }
+}
+
+/// Reload an expanded node's children, keeping the state of those still
+/// present. Collapsed directories are reloaded when next expanded.
+fn refresh_node(node: &mut TreeNode<PathBuf>, listing: Listing) {
+    if !node.expanded {
+        node.children.clear();
+        return;
+    }
+    let mut old: HashMap<PathBuf, TreeNode<PathBuf>> = node
+        .children
+        .drain(..)
+        .map(|child| (child.data.clone(), child))
+        .collect();
+    node.children = listing
+        .load(&node.data)
+        .into_iter()
+        .map(|fresh| match old.remove(&fresh.data) {
+            Some(mut kept) if kept.is_leaf == fresh.is_leaf => {
+                refresh_node(&mut kept, listing);
+                kept
+            }
+            _ => fresh,
+        })
+        .collect();
+}
+
+fn default_status_styles() -> [Style; 7] {
+    let fg = |color| Style::new().fg(Color::Named(color));
+    [
+        fg(NamedColor::BrightBlack),
+        fg(NamedColor::Green),
+        fg(NamedColor::Green),
+        fg(NamedColor::Cyan),
+        fg(NamedColor::Red),
+        fg(NamedColor::Yellow),
+        fg(NamedColor::Red).bold(true),
+    ]
+}
+
+/// Whether `path`, inside `git_dir`, is part of the state `git status`
+/// compares against: the index, `HEAD` or a ref.
+fn is_git_state(git_dir: &Path, path: &Path) -> bool {
+    path.strip_prefix(git_dir).is_ok_and(|rest| {
+        rest == Path::new("index") || rest == Path::new("HEAD") || rest.starts_with("refs")
+    })
+}
+
+fn watch_error(e: notify::Error) -> SaorsaTuiError {
+    SaorsaTuiError::Widget(format!("failed to watch path: {e}"))
+}
+
+/// Whether `path` is inside a `.git` directory below `root`.
+fn in_git_dir(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .any(|c| c.as_os_str() == ".git")
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// `dir` joined with a typed name, or `None` if the name is empty or
+/// leaves `dir`.
+fn child_path(dir: &Path, name: &str) -> Option<PathBuf> {
+    let name = Path::new(name.trim_end_matches('/'));
+    let valid = name.components().next().is_some()
+        && name.components().all(|c| matches!(c, Component::Normal(_)));
+    valid.then(|| dir.join(name))
+}
 
-    let mut nodes = Vec::with_capacity(dirs.len() + files.len());
+/// Apply an editing key to a typed name.
+fn edit_name(name: &mut String, code: KeyCode) {
+    match code {
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::Backspace => {
+            name.pop();
+        }
+        _ => {}
+    }
+}
 
-    for dir in dirs {
-        nodes.push(TreeNode::branch(dir));
+/// Draw styled characters across row `y` of `area`, padding with `fill`.
+fn draw_line(
+    buf: &mut ScreenBuffer,
+    area: Rect,
+    y: u16,
+    chars: impl Iterator<Item = (char, Style)>,
+    fill: &Style,
+) {
+    let width = usize::from(area.size.width);
+    let mut col = 0;
+    for (ch, style) in chars {
+        let char_w = ch.width().unwrap_or(0);
+        if char_w == 0 {
+            continue;
+        }
+        if col + char_w > width {
+            break;
+        }
+        buf.set(
+            area.position.x + col as u16,
+            y,
+            Cell::new(ch.to_string(), style),
+        );
+        col += char_w;
     }
-    for file in files {
-        nodes.push(TreeNode::new(file));
+    for col in col..width {
+        buf.set(
+            area.position.x + col as u16,
+            y,
+            Cell::new(" ", fill.clone()),
+        );
     }
+}
 
-    nodes
+impl Widget for DirectoryTree {
+    fn render(&self, area: Rect, buf: &mut ScreenBuffer) {
+        self.tree.render(area, buf);
+        let inner = super::border::inner_area(area, self.border);
+        if inner.size.width == 0 || inner.size.height == 0 {
+            return;
+        }
+        if let Some(Prompt::Jump {
+            query,
+            matches,
+            selected,
+        }) = &self.prompt
+        {
+            self.render_jump(query, matches, *selected, inner, buf);
+        } else if let Some(line) = self.status_line() {
+            let y = inner.position.y + inner.size.height - 1;
+            let chars = line.chars().map(|ch| (ch, self.prompt_style.clone()));
+            draw_line(buf, inner, y, chars, &self.prompt_style);
+        }
+    }
+}
+
+impl InteractiveWidget for DirectoryTree {
+    fn handle_event(&mut self, event: &Event) -> EventResult {
+        let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event
+        else {
+            return EventResult::Ignored;
+        };
+        self.message = None;
+        if self.prompt.is_some() {
+            self.prompt_key(code.clone());
+            return EventResult::Consumed;
+        }
+        if modifiers.contains(Modifiers::CTRL) || modifiers.contains(Modifiers::ALT) {
+            return self.tree.handle_event(event);
+        }
+        match code {
+            KeyCode::Char('/' | 'a' | 'r' | 'd') | KeyCode::F(2) | KeyCode::Delete => {
+                self.open_prompt(code.clone());
+                EventResult::Consumed
+            }
+            KeyCode::Char('.') => {
+                self.set_show_hidden(!self.show_hidden());
+                EventResult::Consumed
+            }
+            KeyCode::Char('i') => {
+                self.set_gitignore(!self.respects_gitignore());
+                EventResult::Consumed
+            }
+            KeyCode::F(5) => {
+                self.refresh();
+                EventResult::Consumed
+            }
+            _ => self.tree.handle_event(event),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -299,10 +1347,10 @@ mod tests {
         dt.expand_selected(); // root
 
         // Navigate to alpha (should be first child, a dir)
-        let down = Event::Key(crate::event::KeyEvent {
-            code: crate::event::KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = Event::Key(crate::event::KeyEvent::new(
+            crate::event::KeyCode::Down,
+            crate::event::Modifiers::NONE,
+        ));
         dt.handle_event(&down); // select alpha
 
         // Expand alpha
@@ -345,10 +1393,10 @@ mod tests {
         dt.expand_selected();
 
         // Navigate down to check order: dirs first (alpha, beta), then files (file_a, file_b)
-        let down = Event::Key(crate::event::KeyEvent {
-            code: crate::event::KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = Event::Key(crate::event::KeyEvent::new(
+            crate::event::KeyCode::Down,
+            crate::event::Modifiers::NONE,
+        ));
 
         // First child should be alpha (directory)
         dt.handle_event(&down);
@@ -394,4 +1442,362 @@ mod tests {
         // Top-left corner
         assert_eq!(buf.get(0, 0).map(|c| c.grapheme.as_str()), Some("\u{250c}"));
     }
+
+    fn key(dt: &mut DirectoryTree, code: KeyCode) {
+        dt.handle_event(&Event::Key(KeyEvent::new(code, Modifiers::NONE)));
+    }
+
+    fn type_text(dt: &mut DirectoryTree, text: &str) {
+        for c in text.chars() {
+            key(dt, KeyCode::Char(c));
+        }
+    }
+
+    fn names(dt: &DirectoryTree) -> Vec<String> {
+        dt.tree.roots()[0]
+            .children
+            .iter()
+            .map(|n| file_name(&n.data))
+            .collect()
+    }
+
+    #[test]
+    fn gitignore_filters_entries() {
+        let tmp = create_test_dir();
+        fs::write(tmp.path().join(".gitignore"), "*.log\nbeta/\n").unwrap();
+        fs::write(tmp.path().join("debug.log"), "").unwrap();
+        let mut dt = DirectoryTree::new(tmp.path().to_path_buf()).unwrap();
+        dt.expand_selected();
+        assert_eq!(names(&dt), ["alpha", "file_a.txt", "file_b.txt"]);
+
+        key(&mut dt, KeyCode::Char('i'));
+        assert!(!dt.respects_gitignore());
+        assert_eq!(
+            names(&dt),
+            ["alpha", "beta", "debug.log", "file_a.txt", "file_b.txt"]
+        );
+    }
+
+    #[test]
+    fn refresh_keeps_expansion_and_selection() {
+        let tmp = create_test_dir();
+        let mut dt = DirectoryTree::new(tmp.path().to_path_buf()).unwrap();
+        dt.expand_selected();
+        assert!(dt.reveal(&tmp.path().join("alpha/child.txt")));
+        fs::write(tmp.path().join("alpha/new.txt"), "").unwrap();
+        fs::remove_file(tmp.path().join("file_b.txt")).unwrap();
+        dt.refresh();
+        // root, alpha, nested, child.txt, new.txt, beta, file_a.txt
+        assert_eq!(dt.visible_count(), 7);
+        assert_eq!(
+            dt.selected_path(),
+            Some(&tmp.path().join("alpha/child.txt"))
+        );
+
+        fs::remove_dir_all(tmp.path().join("alpha")).unwrap();
+        dt.refresh();
+        assert_eq!(dt.selected_path(), Some(&tmp.path().to_path_buf()));
+    }
+
+    #[test]
+    fn git_status_decorations() {
+        let tmp = create_test_dir();
+        let root = tmp.path();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(root)
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if !git(&["init", "-q"]) {
+            return; // git is not installed
+        }
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        assert!(git(&["add", "."]));
+        assert!(git(&["commit", "-qm", "init"]));
+        fs::write(root.join("alpha/child.txt"), "changed").unwrap();
+        fs::write(root.join("untracked.txt"), "").unwrap();
+        fs::write(root.join("debug.log"), "").unwrap();
+
+        let mut dt = DirectoryTree::new(root.to_path_buf())
+            .unwrap()
+            .with_gitignore(false);
+        assert_eq!(
+            dt.git_status(&root.join("alpha/child.txt")),
+            Some(GitFileStatus::Modified)
+        );
+        assert_eq!(
+            dt.git_status(&root.join("alpha")),
+            Some(GitFileStatus::Modified)
+        );
+        assert_eq!(
+            dt.git_status(&root.join("untracked.txt")),
+            Some(GitFileStatus::Untracked)
+        );
+        assert_eq!(
+            dt.git_status(&root.join("debug.log")),
+            Some(GitFileStatus::Ignored)
+        );
+        assert_eq!(dt.git_status(&root.join("file_a.txt")), None);
+
+        dt.expand_selected();
+        let mut buf = ScreenBuffer::new(Size::new(40, 10));
+        dt.render(Rect::new(0, 0, 40, 10), &mut buf);
+        // "  ▶ 📁 alpha M" on the second row.
+        let row: String = (0..40)
+            .filter_map(|x| buf.get(x, 1).map(|c| c.grapheme.clone()))
+            .collect();
+        assert!(row.trim_end().ends_with("alpha M"), "row was {row:?}");
+        let yellow = Some(Color::Named(NamedColor::Yellow));
+        assert!(buf.get(7, 1).is_some_and(|c| c.style.fg == yellow));
+
+        let clean = DirectoryTree::new(root.to_path_buf())
+            .unwrap()
+            .with_git_status(false);
+        assert_eq!(clean.git_status(&root.join("untracked.txt")), None);
+    }
+
+    #[test]
+    fn jump_to_file_reveals_it() {
+        let tmp = create_test_dir();
+        let mut dt = DirectoryTree::new(tmp.path().to_path_buf()).unwrap();
+        let matches = dt.jump_matches("chtxt");
+        assert_eq!(matches, [tmp.path().join("alpha/child.txt")]);
+        assert!(dt.jump_matches("hidden").is_empty());
+
+        key(&mut dt, KeyCode::Char('/'));
+        type_text(&mut dt, "child");
+        let mut buf = ScreenBuffer::new(Size::new(30, 5));
+        dt.render(Rect::new(0, 0, 30, 5), &mut buf);
+        assert_eq!(buf.get(2, 0).map(|c| c.grapheme.as_str()), Some("c"));
+        assert_eq!(buf.get(6, 1).map(|c| c.grapheme.as_str()), Some("c"));
+        assert!(buf.get(6, 1).is_some_and(|c| c.style.bold));
+
+        key(&mut dt, KeyCode::Enter);
+        assert_eq!(
+            dt.selected_path(),
+            Some(&tmp.path().join("alpha/child.txt"))
+        );
+        // root, alpha, nested, child.txt, beta, file_a.txt, file_b.txt
+        assert_eq!(dt.visible_count(), 7);
+    }
+
+    #[test]
+    fn file_operations_from_keys() {
+        let tmp = create_test_dir();
+        let root = tmp.path();
+        let ops = Rc::new(RefCell::new(Vec::new()));
+        let seen = Rc::clone(&ops);
+        let mut dt = DirectoryTree::new(root.to_path_buf())
+            .unwrap()
+            .with_on_file_op(move |op| {
+                seen.borrow_mut().push(op.clone());
+                !matches!(op, FileOp::Delete { path } if path.ends_with("file_a.txt"))
+            });
+        dt.expand_selected();
+
+        key(&mut dt, KeyCode::Char('a'));
+        type_text(&mut dt, "src/lib.rs");
+        key(&mut dt, KeyCode::Enter);
+        assert!(root.join("src/lib.rs").is_file());
+        assert_eq!(dt.selected_path(), Some(&root.join("src/lib.rs")));
+
+        key(&mut dt, KeyCode::Char('r'));
+        for _ in 0.."lib.rs".len() {
+            key(&mut dt, KeyCode::Backspace);
+        }
+        type_text(&mut dt, "main.rs");
+        key(&mut dt, KeyCode::Enter);
+        assert!(root.join("src/main.rs").is_file());
+        assert!(!root.join("src/lib.rs").exists());
+
+        key(&mut dt, KeyCode::Char('d'));
+        let mut buf = ScreenBuffer::new(Size::new(40, 6));
+        dt.render(Rect::new(0, 0, 40, 6), &mut buf);
+        let prompt: String = (0..24)
+            .filter_map(|x| buf.get(x, 5).map(|c| c.grapheme.clone()))
+            .collect();
+        assert_eq!(prompt, "Delete src/main.rs? (y/n");
+        key(&mut dt, KeyCode::Char('n'));
+        assert!(root.join("src/main.rs").exists());
+        key(&mut dt, KeyCode::Delete);
+        key(&mut dt, KeyCode::Char('y'));
+        assert!(!root.join("src/main.rs").exists());
+
+        // The hook can cancel an operation.
+        assert!(dt.reveal(&root.join("file_a.txt")));
+        key(&mut dt, KeyCode::Char('d'));
+        key(&mut dt, KeyCode::Char('y'));
+        assert!(root.join("file_a.txt").exists());
+        assert_eq!(dt.message(), Some("cancelled"));
+
+        assert_eq!(
+            ops.borrow()[..3],
+            [
+                FileOp::Create {
+                    path: root.join("src/lib.rs"),
+                    is_dir: false
+                },
+                FileOp::Rename {
+                    from: root.join("src/lib.rs"),
+                    to: root.join("src/main.rs")
+                },
+                FileOp::Delete {
+                    path: root.join("src/main.rs")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn file_operations_stay_inside_the_root() {
+        let tmp = create_test_dir();
+        let mut dt = DirectoryTree::new(tmp.path().join("alpha")).unwrap();
+        assert!(dt.create(&tmp.path().join("outside.txt"), false).is_err());
+        assert!(dt.create(&tmp.path().join("alpha/../x"), false).is_err());
+        assert!(dt.delete(&tmp.path().join("alpha")).is_err());
+        assert!(
+            dt.create(&tmp.path().join("alpha/child.txt"), false)
+                .is_err()
+        );
+        assert!(dt.create(&tmp.path().join("alpha/dir"), true).is_ok());
+        assert!(tmp.path().join("alpha/dir").is_dir());
+    }
+
+    #[test]
+    fn watch_refreshes_after_changes() {
+        let tmp = create_test_dir();
+        let mut dt = DirectoryTree::new(tmp.path().to_path_buf()).unwrap();
+        assert!(!dt.poll_changes());
+        dt.watch().unwrap();
+        dt.expand_selected();
+        fs::write(tmp.path().join("file_c.txt"), "").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while dt.visible_count() == 5 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            dt.poll_changes();
+        }
+        assert_eq!(dt.visible_count(), 6);
+    }
+
+    #[test]
+    fn watch_coalesces_bursts_of_changes() {
+        let tmp = create_test_dir();
+        let mut dt = DirectoryTree::new(tmp.path().to_path_buf()).unwrap();
+        dt.watch().unwrap();
+        dt.expand_selected();
+        for i in 0..20 {
+            fs::write(tmp.path().join(format!("burst_{i}.txt")), "").unwrap();
+        }
+
+        let mut refreshes = 0;
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while dt.visible_count() < 25 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            refreshes += usize::from(dt.poll_changes());
+        }
+        assert_eq!(dt.visible_count(), 25);
+        assert_eq!(refreshes, 1);
+    }
+
+    /// Poll until `done`, for up to five seconds.
+    fn poll_until(dt: &mut DirectoryTree, done: impl Fn(&DirectoryTree) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done(dt) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            dt.poll_changes();
+        }
+    }
+
+    #[test]
+    fn watch_reloads_git_status_after_git_commands() {
+        let tmp = create_test_dir();
+        let root = tmp.path();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(root)
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if !git(&["init", "-q"]) {
+            return; // git is not installed
+        }
+        fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        fs::create_dir(root.join("target")).unwrap();
+        assert!(git(&["add", "."]));
+        assert!(git(&["commit", "-qm", "init"]));
+        fs::write(root.join("new.txt"), "").unwrap();
+        let mut dt = DirectoryTree::new(root.to_path_buf()).unwrap();
+        dt.watch().unwrap();
+        let watched = &dt.watch.as_ref().unwrap().watched;
+        assert!(watched.contains(&root.join("alpha/nested")));
+        assert!(!watched.contains(&root.join("target")));
+        assert!(!watched.contains(&root.join(".git")));
+        assert_eq!(
+            dt.git_status(&root.join("new.txt")),
+            Some(GitFileStatus::Untracked)
+        );
+        // Index the files for jumping; a tree refresh would drop them.
+        assert!(!dt.jump_matches("new").is_empty());
+
+        assert!(git(&["add", "new.txt"]));
+        let added = |dt: &DirectoryTree| dt.git_status(&root.join("new.txt")).is_some();
+        poll_until(&mut dt, |dt| {
+            dt.git_status(&root.join("new.txt")) == Some(GitFileStatus::Added)
+        });
+        assert_eq!(
+            dt.git_status(&root.join("new.txt")),
+            Some(GitFileStatus::Added)
+        );
+        assert!(dt.files.is_some());
+
+        assert!(git(&["commit", "-qm", "add"]));
+        poll_until(&mut dt, |dt| !added(dt));
+        assert_eq!(dt.git_status(&root.join("new.txt")), None);
+        assert!(dt.files.is_some());
+
+        // Ignored trees are not watched at all.
+        fs::write(root.join("target/build.out"), "").unwrap();
+        let deadline = Instant::now() + Duration::from_millis(500);
+        while Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            assert!(!dt.poll_changes());
+        }
+
+        assert!(is_git_state(
+            &root.join(".git"),
+            &root.join(".git/refs/heads/main")
+        ));
+        assert!(!is_git_state(
+            &root.join(".git"),
+            &root.join(".git/index.lock")
+        ));
+        assert!(in_git_dir(root, &root.join("sub/.git/HEAD")));
+        assert!(!in_git_dir(root, &root.join(".github/workflows")));
+    }
+
+    #[test]
+    fn watch_follows_new_directories() {
+        let tmp = create_test_dir();
+        let root = tmp.path();
+        let mut dt = DirectoryTree::new(root.to_path_buf()).unwrap();
+        dt.watch().unwrap();
+        dt.expand_selected();
+        fs::create_dir_all(root.join("gamma/deep")).unwrap();
+        poll_until(&mut dt, |dt| dt.visible_count() == 6);
+        assert_eq!(dt.visible_count(), 6);
+        let watched = &dt.watch.as_ref().unwrap().watched;
+        assert!(watched.contains(&root.join("gamma/deep")));
+
+        assert!(dt.reveal(&root.join("gamma/deep")));
+        dt.expand_selected();
+        fs::write(root.join("gamma/deep/file.txt"), "").unwrap();
+        poll_until(&mut dt, |dt| dt.visible_count() == 8);
+        assert_eq!(dt.visible_count(), 8);
+    }
 }
@@ -0,0 +1,233 @@
+//! Git status of the files in a [`DirectoryTree`](super::DirectoryTree).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The git status of a file or directory in the working tree.
+///
+/// Variants are ordered by importance; a directory shows the most
+/// important status among its contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GitFileStatus {
+    /// Matched by `.gitignore`.
+    Ignored,
+    /// Not tracked by git.
+    Untracked,
+    /// Added to the index.
+    Added,
+    /// Renamed in the index.
+    Renamed,
+    /// Deleted from the working tree or the index.
+    Deleted,
+    /// Changed since the last commit.
+    Modified,
+    /// Unmerged after a conflicting merge.
+    Conflicted,
+}
+
+impl GitFileStatus {
+    /// Every status, from least to most important.
+    pub const ALL: [GitFileStatus; 7] = [
+        GitFileStatus::Ignored,
+        GitFileStatus::Untracked,
+        GitFileStatus::Added,
+        GitFileStatus::Renamed,
+        GitFileStatus::Deleted,
+        GitFileStatus::Modified,
+        GitFileStatus::Conflicted,
+    ];
+
+    /// The letter shown after a file's name, if any.
+    pub fn marker(self) -> Option<char> {
+        match self {
+            GitFileStatus::Ignored => None,
+            GitFileStatus::Untracked => Some('U'),
+            GitFileStatus::Added => Some('A'),
+            GitFileStatus::Renamed => Some('R'),
+            GitFileStatus::Deleted => Some('D'),
+            GitFileStatus::Modified => Some('M'),
+            GitFileStatus::Conflicted => Some('C'),
+        }
+    }
+
+    /// Parse the two status letters of `git status --porcelain`.
+    fn from_porcelain(xy: &str) -> Option<Self> {
+        let mut chars = xy.chars();
+        let (x, y) = (chars.next()?, chars.next()?);
+        Some(match (x, y) {
+            ('?', '?') => GitFileStatus::Untracked,
+            ('!', '!') => GitFileStatus::Ignored,
+            ('D', 'D') | ('A', 'A') | ('U', _) | (_, 'U') => GitFileStatus::Conflicted,
+            ('R', _) | (_, 'R') => GitFileStatus::Renamed,
+            ('A', _) | ('C', _) => GitFileStatus::Added,
+            ('D', _) | (_, 'D') => GitFileStatus::Deleted,
+            _ => GitFileStatus::Modified,
+        })
+    }
+}
+
+/// The statuses of the changed, untracked and ignored paths under a
+/// directory.
+#[derive(Clone, Debug, Default)]
+pub(super) struct GitStatus {
+    /// Statuses reported by git. Untracked and ignored directories are
+    /// reported whole.
+    paths: HashMap<PathBuf, GitFileStatus>,
+    /// The most important status within each directory containing
+    /// changes.
+    dirs: HashMap<PathBuf, GitFileStatus>,
+}
+
+impl GitStatus {
+    /// Run `git status` for the repository containing `root`. Returns
+    /// `None` outside a repository or when git is not available.
+    pub fn read(root: &Path) -> Option<Self> {
+        let prefix = git(root, &["rev-parse", "--show-prefix"])?;
+        let output = git(
+            root,
+            &[
+                "status",
+                "--porcelain=v1",
+                "-z",
+                "--ignored=matching",
+                "--untracked-files=normal",
+            ],
+        )?;
+        Some(Self::parse(root, prefix.trim_end(), &output))
+    }
+
+    /// The git directory of the repository containing `root`, such as
+    /// `root/.git`. Returns `None` outside a repository or when git is
+    /// not available.
+    pub fn git_dir(root: &Path) -> Option<PathBuf> {
+        let dir = git(root, &["rev-parse", "--absolute-git-dir"])?;
+        Some(PathBuf::from(dir.trim_end()))
+    }
+
+    /// Parse `git status --porcelain=v1 -z` output. Its paths are relative
+    /// to the repository root, which is `prefix` above `root`.
+    pub fn parse(root: &Path, prefix: &str, output: &str) -> Self {
+        let mut status = Self::default();
+        let mut fields = output.split('\0');
+        while let Some(field) = fields.next() {
+            let (Some(xy), Some(path)) = (field.get(..2), field.get(3..)) else {
+                continue;
+            };
+            let Some(kind) = GitFileStatus::from_porcelain(xy) else {
+                continue;
+            };
+            if xy.contains(['R', 'C']) {
+                // The path the entry was renamed or copied from.
+                fields.next();
+            }
+            let Some(relative) = path.strip_prefix(prefix) else {
+                continue;
+            };
+            let relative = relative.trim_end_matches('/');
+            if relative.is_empty() {
+                continue;
+            }
+            status.insert(root, root.join(relative), kind);
+        }
+        status
+    }
+
+    fn insert(&mut self, root: &Path, path: PathBuf, kind: GitFileStatus) {
+        if kind != GitFileStatus::Ignored {
+            // Directories show changes inside them as modifications.
+            let summary = match kind {
+                GitFileStatus::Renamed | GitFileStatus::Deleted => GitFileStatus::Modified,
+                other => other,
+            };
+            for dir in path.ancestors().skip(1) {
+                let entry = self.dirs.entry(dir.to_path_buf()).or_insert(summary);
+                *entry = (*entry).max(summary);
+                if dir == root {
+                    break;
+                }
+            }
+        }
+        self.paths.insert(path, kind);
+    }
+
+    /// The status of `path`, if it differs from the last commit or is
+    /// ignored.
+    pub fn get(&self, path: &Path) -> Option<GitFileStatus> {
+        if let Some(&kind) = self.paths.get(path) {
+            return Some(kind);
+        }
+        // Files inside untracked or ignored directories share their status.
+        let inherited = path
+            .ancestors()
+            .skip(1)
+            .find_map(|dir| self.paths.get(dir))
+            .copied();
+        inherited.or_else(|| self.dirs.get(path).copied())
+    }
+}
+
+/// Run git in `dir`, returning its output if it succeeds. Git is told
+/// not to refresh the index, which would write to `.git` and wake the
+/// tree's watcher again.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("--no-optional-locks")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_porcelain_output() {
+        let root = Path::new("/repo/sub");
+        let output = [
+            " M sub/src/main.rs",
+            "A  sub/new.rs",
+            "R  sub/renamed.rs",
+            "sub/old.rs",
+            "UU sub/src/conflict.rs",
+            "?? sub/scratch/",
+            "!! sub/target/",
+            " M elsewhere.rs",
+            "",
+        ]
+        .join("\0");
+        let status = GitStatus::parse(root, "sub/", &output);
+
+        let get = |p: &str| status.get(&root.join(p));
+        assert_eq!(get("src/main.rs"), Some(GitFileStatus::Modified));
+        assert_eq!(get("new.rs"), Some(GitFileStatus::Added));
+        assert_eq!(get("renamed.rs"), Some(GitFileStatus::Renamed));
+        assert_eq!(get("old.rs"), None);
+        assert_eq!(get("src"), Some(GitFileStatus::Conflicted));
+        assert_eq!(get("scratch/notes.txt"), Some(GitFileStatus::Untracked));
+        assert_eq!(get("target/debug"), Some(GitFileStatus::Ignored));
+        assert_eq!(get("clean.rs"), None);
+        assert_eq!(status.get(Path::new("/repo/elsewhere.rs")), None);
+        // The root summarises everything but ignored files.
+        assert_eq!(status.get(root), Some(GitFileStatus::Conflicted));
+    }
+
+    #[test]
+    fn porcelain_status_letters() {
+        let parse = GitFileStatus::from_porcelain;
+        assert_eq!(parse("MM"), Some(GitFileStatus::Modified));
+        assert_eq!(parse("AM"), Some(GitFileStatus::Added));
+        assert_eq!(parse(" D"), Some(GitFileStatus::Deleted));
+        assert_eq!(parse("AA"), Some(GitFileStatus::Conflicted));
+        assert_eq!(parse("x"), None);
+        assert_eq!(GitFileStatus::Modified.marker(), Some('M'));
+        assert_eq!(GitFileStatus::Ignored.marker(), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! Image widget for inline terminal graphics.
+//!
+//! Draws a decoded PNG/JPEG image using the best protocol the terminal
+//! supports (Kitty graphics, iTerm2 inline images, Sixel), falling back
+//! to Unicode half blocks everywhere else.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::buffer::ScreenBuffer;
+use crate::cell::Cell;
+use crate::error::Result;
+use crate::geometry::Rect;
+use crate::graphics::{GraphicPlacement, ImageData, ImageProtocol, halfblock};
+
+use super::{SizedWidget, Widget};
+
+/// Source of stable image identifiers (used as Kitty image ids).
+static NEXT_IMAGE_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Approximate cell size in pixels used to derive a preferred size.
+const CELL_PIXELS: (u32, u32) = (10, 20);
+
+/// A widget that displays an image.
+///
+/// With a graphical protocol the widget reserves its cells and registers
+/// a [`GraphicPlacement`] on the buffer; the render context draws the image
+/// after the cell diff and clears it when it moves, is covered or goes
+/// away. With [`ImageProtocol::HalfBlock`] the image is rendered directly
+/// into cells.
+pub struct Image {
+    id: u32,
+    data: Arc<ImageData>,
+    protocol: ImageProtocol,
+    preserve_aspect: bool,
+}
+
+impl Image {
+    /// Create an image widget, picking the protocol for the detected terminal.
+    pub fn new(data: ImageData) -> Self {
+        Self {
+            id: NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed),
+            data: Arc::new(data),
+            protocol: ImageProtocol::detect(),
+            preserve_aspect: true,
+        }
+    }
+
+    /// Decode a PNG or JPEG image from memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image cannot be decoded.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ImageData::decode(bytes).map(Self::new)
+    }
+
+    /// Read and decode a PNG or JPEG file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or decoded.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        ImageData::open(path).map(Self::new)
+    }
+
+    /// Override the drawing protocol.
+    #[must_use]
+    pub fn with_protocol(mut self, protocol: ImageProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Keep the image's aspect ratio when fitting it to its area (default `true`).
+    #[must_use]
+    pub fn with_preserve_aspect(mut self, preserve: bool) -> Self {
+        self.preserve_aspect = preserve;
+        self
+    }
+
+    /// Replace the displayed image.
+    pub fn set_image(&mut self, data: ImageData) {
+        self.data = Arc::new(data);
+    }
+
+    /// Change the drawing protocol.
+    pub fn set_protocol(&mut self, protocol: ImageProtocol) {
+        self.protocol = protocol;
+    }
+
+    /// The image currently displayed.
+    pub fn image(&self) -> &ImageData {
+        &self.data
+    }
+
+    /// The protocol used to draw the image.
+    pub fn protocol(&self) -> ImageProtocol {
+        self.protocol
+    }
+
+    /// The stable identifier of this widget's image.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The cells the image occupies within `area`.
+    ///
+    /// When preserving the aspect ratio, cells are assumed to be twice as
+    /// tall as they are wide and the image is anchored top-left.
+    pub fn fit(&self, area: Rect) -> Rect {
+        let (w, h) = (self.data.width(), self.data.height());
+        if !self.preserve_aspect || area.is_empty() || w == 0 || h == 0 {
+            return area;
+        }
+        let cols = f64::from(area.size.width);
+        let rows = f64::from(area.size.height);
+        let scale = (cols / f64::from(w)).min(rows * 2.0 / f64::from(h));
+        let fit_cols = (f64::from(w) * scale).round().clamp(1.0, cols) as u16;
+        let fit_rows = (f64::from(h) * scale / 2.0).round().clamp(1.0, rows) as u16;
+        Rect::new(area.position.x, area.position.y, fit_cols, fit_rows)
+    }
+}
+
+impl Widget for Image {
+    fn render(&self, area: Rect, buf: &mut ScreenBuffer) {
+        let target = self.fit(area);
+        if target.is_empty() {
+            return;
+        }
+        if !self.protocol.is_graphical() {
+            halfblock::render(&self.data, target, buf);
+            return;
+        }
+        for y in target.position.y..target.bottom() {
+            for x in target.position.x..target.right() {
+                buf.set(x, y, Cell::blank());
+            }
+        }
+        buf.place_graphic(GraphicPlacement::new(
+            self.id,
+            target,
+            self.protocol,
+            Arc::clone(&self.data),
+        ));
+    }
+}
+
+impl SizedWidget for Image {
+    fn min_size(&self) -> (u16, u16) {
+        (1, 1)
+    }
+
+    fn preferred_size(&self) -> (u16, u16) {
+        let cols = self.data.width().div_ceil(CELL_PIXELS.0).max(1);
+        let rows = self.data.height().div_ceil(CELL_PIXELS.1).max(1);
+        (
+            u16::try_from(cols).unwrap_or(u16::MAX),
+            u16::try_from(rows).unwrap_or(u16::MAX),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Size;
+
+    fn solid(width: u32, height: u32) -> ImageData {
+        let data = [10, 20, 30, 255].repeat((width * height) as usize);
+        match ImageData::from_rgba(width, height, data) {
+            Ok(img) => img,
+            Err(_) => unreachable!("valid buffer"),
+        }
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let a = Image::new(solid(1, 1));
+        let b = Image::new(solid(1, 1));
+        assert_ne!(a.id(), b.id());
+    }
+
+    #[test]
+    fn fit_preserves_aspect() {
+        // 40x20 px is twice as wide as tall: 20 cols x 5 rows in a 20x10 area.
+        let img = Image::new(solid(40, 20));
+        assert_eq!(img.fit(Rect::new(2, 3, 20, 10)), Rect::new(2, 3, 20, 5));
+        let stretched = Image::new(solid(40, 20)).with_preserve_aspect(false);
+        assert_eq!(
+            stretched.fit(Rect::new(2, 3, 20, 10)),
+            Rect::new(2, 3, 20, 10)
+        );
+    }
+
+    #[test]
+    fn graphical_render_reserves_cells_and_places() {
+        let img = Image::new(solid(4, 4)).with_protocol(ImageProtocol::Kitty);
+        let mut buf = ScreenBuffer::new(Size::new(10, 5));
+        img.render(Rect::new(1, 1, 4, 2), &mut buf);
+        assert_eq!(buf.graphics().len(), 1);
+        let placement = &buf.graphics()[0];
+        assert_eq!(placement.id, img.id());
+        assert_eq!(placement.area, Rect::new(1, 1, 4, 2));
+        assert!(!placement.occluded);
+    }
+
+    #[test]
+    fn half_block_render_writes_cells() {
+        let img = Image::new(solid(2, 2)).with_protocol(ImageProtocol::HalfBlock);
+        let mut buf = ScreenBuffer::new(Size::new(4, 2));
+        img.render(Rect::new(0, 0, 2, 1), &mut buf);
+        assert!(buf.graphics().is_empty());
+        assert_eq!(buf.get(0, 0).map(|c| c.grapheme.as_str()), Some("\u{2580}"));
+    }
+
+    #[test]
+    fn preferred_size_from_pixels() {
+        let img = Image::new(solid(95, 41));
+        assert_eq!(img.preferred_size(), (10, 3));
+        assert_eq!(img.min_size(), (1, 1));
+    }
+}
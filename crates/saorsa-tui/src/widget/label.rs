@@ -1,8 +1,10 @@
 //! Label widget — a single line of styled text.
 
+use crate::ansi::parse_ansi;
 use crate::buffer::ScreenBuffer;
 use crate::cell::Cell;
 use crate::geometry::Rect;
+use crate::segment::Segment;
 use crate::style::Style;
 use unicode_width::UnicodeWidthStr;
 
@@ -29,6 +31,9 @@ pub struct Label {
     style: Style,
     /// Text alignment within the available area.
     alignment: Alignment,
+    /// Styled runs of `text`, merged over `style`, if the text came with
+    /// its own styles.
+    segments: Vec<Segment>,
 }
 
 impl Label {
@@ -38,9 +43,18 @@ impl Label {
             text: text.into(),
             style: Style::default(),
             alignment: Alignment::Left,
+            segments: Vec::new(),
         }
     }
 
+    /// Create a label from text with ANSI escape sequences, keeping its
+    /// colours and attributes. Lines are joined with spaces.
+    pub fn from_ansi(text: &str) -> Self {
+        let mut label = Self::new("");
+        label.set_ansi(text);
+        label
+    }
+
     /// Set the style.
     #[must_use]
     pub fn style(mut self, style: Style) -> Self {
@@ -63,6 +77,21 @@ impl Label {
     /// Set new text content.
     pub fn set_text(&mut self, text: impl Into<String>) {
         self.text = text.into();
+        self.segments.clear();
+    }
+
+    /// Set text with ANSI escape sequences, keeping its colours and
+    /// attributes over the label's style. Lines are joined with spaces.
+    pub fn set_ansi(&mut self, text: &str) {
+        let mut segments = Vec::new();
+        for (i, line) in parse_ansi(text).into_iter().enumerate() {
+            if i > 0 {
+                segments.push(Segment::new(" "));
+            }
+            segments.extend(line);
+        }
+        self.text = segments.iter().map(|s| s.text.as_str()).collect();
+        self.segments = segments;
     }
 
     /// Get the current style.
@@ -123,19 +152,32 @@ impl Widget for Label {
             Alignment::Right => width.saturating_sub(display_width),
         };
 
+        // Per-character styles for styled text; a truncating ellipsis
+        // keeps the label's own style.
+        let styles: Vec<Style> = self
+            .segments
+            .iter()
+            .flat_map(|s| s.text.chars().map(|_| self.style.merge(&s.style)))
+            .collect();
+        let kept = if text_width > width {
+            display_text.chars().count().saturating_sub(1)
+        } else {
+            usize::MAX
+        };
+
         // Write characters to buffer
         let mut col = 0usize;
-        for ch in display_text.chars() {
+        for (i, ch) in display_text.chars().enumerate() {
             let ch_width = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
             let x = area.position.x + (offset + col) as u16;
             if x >= area.position.x + area.size.width {
                 break;
             }
-            buf.set(
-                x,
-                area.position.y,
-                Cell::new(ch.to_string(), self.style.clone()),
-            );
+            let style = match styles.get(i) {
+                Some(style) if i < kept => style.clone(),
+                _ => self.style.clone(),
+            };
+            buf.set(x, area.position.y, Cell::new(ch.to_string(), style));
             col += ch_width;
         }
     }
@@ -235,4 +277,38 @@ mod tests {
         label.set_text("after");
         assert_eq!(label.text(), "after");
     }
+
+    #[test]
+    fn label_from_ansi_keeps_colours() {
+        let label = Label::from_ansi("\x1b[31merr\x1b[0m ok\nnext").style(Style::new().bold(true));
+        assert_eq!(label.text(), "err ok next");
+
+        let mut buf = ScreenBuffer::new(Size::new(20, 1));
+        label.render(Rect::new(0, 0, 20, 1), &mut buf);
+        let cell = buf.get(0, 0);
+        assert_eq!(
+            cell.and_then(|c| c.style.fg.clone()),
+            Some(Color::Named(NamedColor::Red))
+        );
+        assert!(cell.is_some_and(|c| c.style.bold));
+        assert_eq!(buf.get(4, 0).and_then(|c| c.style.fg.clone()), None);
+    }
+
+    #[test]
+    fn label_ansi_truncation_and_set_text() {
+        let mut label = Label::from_ansi("\x1b[32mgreen text\x1b[0m");
+        let mut buf = ScreenBuffer::new(Size::new(5, 1));
+        label.render(Rect::new(0, 0, 5, 1), &mut buf);
+        assert_eq!(
+            buf.get(3, 0).and_then(|c| c.style.fg.clone()),
+            Some(Color::Named(NamedColor::Green))
+        );
+        assert_eq!(buf.get(4, 0).map(|c| c.grapheme.as_str()), Some("\u{2026}"));
+        assert_eq!(buf.get(4, 0).and_then(|c| c.style.fg.clone()), None);
+
+        label.set_text("plain");
+        let mut buf = ScreenBuffer::new(Size::new(5, 1));
+        label.render(Rect::new(0, 0, 5, 1), &mut buf);
+        assert_eq!(buf.get(0, 0).and_then(|c| c.style.fg.clone()), None);
+    }
 }
@@ -1,15 +1,33 @@
 //! Streaming markdown renderer for styled terminal output.
 //!
-//! Uses [`pulldown_cmark`] to parse CommonMark and produce styled
+//! Uses [`pulldown_cmark`] to parse GitHub-flavoured markdown (tables,
+//! task lists, strikethrough and footnotes) and produce styled
 //! [`Segment`] lines suitable for rendering in a terminal. Designed
-//! for incremental rendering of streaming LLM output.
+//! for incremental rendering of streaming LLM output: blocks that can no
+//! longer change are rendered once and cached, and only the trailing open
+//! block is reparsed as text arrives.
+
+use std::cell::{Ref, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::color::{Color, NamedColor};
+use crate::highlight::{SyntaxTheme, highlight_segments, highlighter_for_language};
 use crate::segment::Segment;
 use crate::style::Style;
+use crate::tcss::apply::style_from_value;
+use crate::tcss::variable::VariableEnvironment;
 use crate::text::truncate_to_display_width;
-use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
-use unicode_width::UnicodeWidthStr;
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Widest a horizontal rule is drawn.
+const MAX_RULE_WIDTH: usize = 80;
+
+/// The GitHub-flavoured extensions the renderer parses.
+const PARSE_OPTIONS: Options = Options::ENABLE_TABLES
+    .union(Options::ENABLE_STRIKETHROUGH)
+    .union(Options::ENABLE_TASKLISTS)
+    .union(Options::ENABLE_FOOTNOTES);
 
 /// A block-level element in rendered markdown.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -30,15 +48,200 @@ pub enum MarkdownBlock {
     Table,
 }
 
+/// Styles for the elements of rendered markdown.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkdownTheme {
+    /// Heading styles, for levels 1 to 6.
+    pub headings: [Style; 6],
+    /// Inline `code`.
+    pub inline_code: Style,
+    /// Code blocks, under any syntax highlighting.
+    pub code_block: Style,
+    /// Link text, on top of the link's URL.
+    pub link: Style,
+    /// Text inside block quotes.
+    pub quote: Style,
+    /// The bar drawn beside block quotes.
+    pub quote_bar: Style,
+    /// List bullets, numbers and task checkboxes.
+    pub list_marker: Style,
+    /// Table borders.
+    pub table_border: Style,
+    /// Table header cells.
+    pub table_header: Style,
+    /// Horizontal rules.
+    pub rule: Style,
+    /// Footnote references and labels.
+    pub footnote: Style,
+}
+
+impl MarkdownTheme {
+    /// Build a theme from TCSS variables, on top of the default theme.
+    ///
+    /// Each element reads its own `$markdown-*` variable (`$markdown-h1`
+    /// … `$markdown-h6`, `$markdown-link`, `$markdown-code`,
+    /// `$markdown-quote`, `$markdown-border`, `$markdown-footnote`) and
+    /// otherwise falls back to the active theme's palette. A color keeps
+    /// the element's default text style; keywords such as `red italic`
+    /// replace it.
+    pub fn from_variables(vars: &VariableEnvironment) -> Self {
+        let mut theme = Self::default();
+        let [h1, h2, h3, h4, h5, h6] = &mut theme.headings;
+        let slots: [(&mut Style, &[&str]); 13] = [
+            (h1, &["markdown-h1", "blue", "primary"]),
+            (h2, &["markdown-h2", "mauve", "secondary"]),
+            (h3, &["markdown-h3", "peach", "warning"]),
+            (h4, &["markdown-h4"]),
+            (h5, &["markdown-h5"]),
+            (h6, &["markdown-h6"]),
+            (
+                &mut theme.inline_code,
+                &["markdown-code", "green", "success"],
+            ),
+            (&mut theme.link, &["markdown-link", "sapphire", "secondary"]),
+            (
+                &mut theme.quote_bar,
+                &["markdown-quote", "overlay1", "border"],
+            ),
+            (&mut theme.list_marker, &["markdown-marker", "primary"]),
+            (&mut theme.table_border, &["markdown-border", "border"]),
+            (&mut theme.rule, &["markdown-border", "border"]),
+            (&mut theme.footnote, &["markdown-footnote", "secondary"]),
+        ];
+        for (slot, names) in slots {
+            let value = names.iter().find_map(|name| vars.resolve(name));
+            if let Some(style) = value.and_then(|v| style_from_value(v, slot.clone())) {
+                *slot = style;
+            }
+        }
+        theme
+    }
+
+    /// The style for a heading level (1-6).
+    pub fn heading(&self, level: u8) -> &Style {
+        &self.headings[usize::from(level.clamp(1, 6)) - 1]
+    }
+}
+
+impl Default for MarkdownTheme {
+    fn default() -> Self {
+        let named = |c| Style::new().fg(Color::Named(c));
+        let bold = Style::new().bold(true);
+        Self {
+            headings: [
+                named(NamedColor::Cyan).bold(true),
+                named(NamedColor::Green).bold(true),
+                named(NamedColor::Yellow).bold(true),
+                bold.clone(),
+                bold.clone(),
+                bold.clone(),
+            ],
+            inline_code: named(NamedColor::Yellow),
+            code_block: Style::new().dim(true),
+            link: named(NamedColor::Blue).underline(true),
+            quote: Style::new().dim(true),
+            quote_bar: Style::new().dim(true),
+            list_marker: Style::new(),
+            table_border: Style::new().dim(true),
+            table_header: bold,
+            rule: Style::new().dim(true),
+            footnote: named(NamedColor::Cyan),
+        }
+    }
+}
+
 /// A stateful incremental markdown renderer.
 ///
 /// Text is pushed in chunks via [`push_str`](MarkdownRenderer::push_str)
 /// and the full rendered output is produced by
 /// [`render_to_lines`](MarkdownRenderer::render_to_lines). Incomplete
-/// markdown is handled gracefully — the renderer re-parses the
-/// accumulated text on each call.
+/// markdown is handled gracefully — an unterminated code fence renders as
+/// a code block running to the end of the text.
+///
+/// Rendering is incremental: once a new top-level block starts, the
+/// blocks before it can no longer change, so their lines are cached and
+/// only the trailing open block is reparsed on the next call. The cache
+/// is rebuilt when the width or the theme changes, and when a link
+/// reference or footnote definition arrives or changes, since those
+/// apply to the whole text.
+///
+/// GitHub-flavoured extensions are rendered too: tables are drawn with
+/// box-drawing borders and their columns shrunk to fit, task list items
+/// get checkboxes, and footnote references are shown as `[label]`. Link
+/// text carries its URL in [`Style::link`].
+///
+/// Fenced code blocks tagged with a language that has a bundled grammar
+/// are syntax highlighted with the `tree-sitter` feature.
 pub struct MarkdownRenderer {
     text: String,
+    theme: MarkdownTheme,
+    syntax_theme: SyntaxTheme,
+    cache: RefCell<BlockCache>,
+}
+
+/// Rendered lines of the blocks that can no longer change.
+#[derive(Default)]
+struct BlockCache {
+    /// The width the lines were wrapped to.
+    width: usize,
+    /// Byte offset where the open tail of the text starts.
+    stable: usize,
+    /// Lines of the cached blocks followed by those of the open tail.
+    lines: Vec<Vec<Segment>>,
+    /// How many of `lines` belong to the cached blocks.
+    stable_lines: usize,
+    /// Definitions made in the cached blocks.
+    definitions: Definitions,
+    /// Definitions in the whole text, which the lines were rendered with.
+    rendered_with: Definitions,
+}
+
+/// Link reference and footnote definitions, by label.
+#[derive(Clone, Default, PartialEq)]
+struct Definitions {
+    /// Stand-in source for each link reference definition, keyed by its
+    /// case-folded label.
+    links: BTreeMap<String, String>,
+    footnotes: BTreeSet<String>,
+}
+
+impl Definitions {
+    /// Add the definitions made in `text`, a run of whole blocks. Like in
+    /// markdown, the first definition of a label wins.
+    fn scan(&mut self, text: &str) {
+        let parser = Parser::new_ext(text, PARSE_OPTIONS);
+        for (label, def) in parser.reference_definitions().iter() {
+            self.links.entry(label.to_lowercase()).or_insert_with(|| {
+                let title = def
+                    .title
+                    .as_ref()
+                    .map(|t| format!(" \"{}\"", escape_punctuation(t)))
+                    .unwrap_or_default();
+                format!("[{label}]: <{}>{title}\n", escape_punctuation(&def.dest))
+            });
+        }
+        for event in parser {
+            if let Event::Start(Tag::FootnoteDefinition(label)) = event {
+                self.footnotes.insert(label.to_string());
+            }
+        }
+    }
+
+    /// Source making every definition, closed off by a comment so text
+    /// after it parses as if it came first.
+    fn source(&self) -> String {
+        let mut source: String = self.links.values().map(String::as_str).collect();
+        if !source.is_empty() {
+            source.push('\n');
+        }
+        for label in &self.footnotes {
+            source.push_str(&format!("[^{label}]:\n"));
+        }
+        if !source.is_empty() {
+            source.push_str("\n<!-- -->\n\n");
+        }
+        source
+    }
 }
 
 impl MarkdownRenderer {
@@ -46,9 +249,53 @@ impl MarkdownRenderer {
     pub fn new() -> Self {
         Self {
             text: String::new(),
+            theme: MarkdownTheme::default(),
+            syntax_theme: SyntaxTheme::default(),
+            cache: RefCell::default(),
         }
     }
 
+    /// Set the styles for headings, links, quotes and other elements.
+    #[must_use]
+    pub fn with_theme(mut self, theme: MarkdownTheme) -> Self {
+        self.theme = theme;
+        self.cache = RefCell::default();
+        self
+    }
+
+    /// Set the styles for headings, links, quotes and other elements.
+    pub fn set_theme(&mut self, theme: MarkdownTheme) {
+        self.theme = theme;
+        self.cache = RefCell::default();
+    }
+
+    /// The styles for headings, links, quotes and other elements.
+    pub fn theme(&self) -> &MarkdownTheme {
+        &self.theme
+    }
+
+    /// Set the styles for highlighted code blocks.
+    #[must_use]
+    pub fn with_syntax_theme(mut self, theme: SyntaxTheme) -> Self {
+        self.syntax_theme = theme;
+        self.cache = RefCell::default();
+        self
+    }
+
+    /// Set the styles for highlighted code blocks.
+    pub fn set_syntax_theme(&mut self, theme: SyntaxTheme) {
+        self.syntax_theme = theme;
+        self.cache = RefCell::default();
+    }
+
+    /// Follow the active TCSS theme: derive both the [`MarkdownTheme`] and
+    /// the code [`SyntaxTheme`] from its variables.
+    pub fn apply_variables(&mut self, vars: &VariableEnvironment) {
+        self.theme = MarkdownTheme::from_variables(vars);
+        self.syntax_theme = SyntaxTheme::from_variables(vars);
+        self.cache = RefCell::default();
+    }
+
     /// Append a text chunk (supports streaming).
     pub fn push_str(&mut self, text: &str) {
         self.text.push_str(text);
@@ -57,268 +304,802 @@ impl MarkdownRenderer {
     /// Clear all accumulated text and reset the renderer.
     pub fn clear(&mut self) {
         self.text.clear();
+        self.cache = RefCell::default();
     }
 
     /// Render the current accumulated text to styled lines.
     ///
     /// Each line is a `Vec<Segment>`. The text is word-wrapped to
-    /// the given width. Styles are applied for headings, bold, italic,
-    /// inline code, code blocks, and list items.
+    /// the given width. Styles are applied for headings, emphasis,
+    /// strikethrough, links, inline code, code blocks, quotes, lists and
+    /// tables.
     pub fn render_to_lines(&self, width: u16) -> Vec<Vec<Segment>> {
-        let w = width as usize;
-        if w == 0 || self.text.is_empty() {
-            return Vec::new();
+        self.lines(width).to_vec()
+    }
+
+    /// Render the current accumulated text like
+    /// [`render_to_lines`](MarkdownRenderer::render_to_lines), but borrow
+    /// the lines from the renderer's cache instead of copying them, so a
+    /// call only costs as much as the open tail of the text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if lines borrowed from an earlier call are still alive.
+    pub fn lines(&self, width: u16) -> Ref<'_, [Vec<Segment>]> {
+        self.update_cache(&mut self.cache.borrow_mut(), width as usize);
+        Ref::map(self.cache.borrow(), |cache| cache.lines.as_slice())
+    }
+
+    /// Bring the cached lines up to date with the text.
+    fn update_cache(&self, cache: &mut BlockCache, width: usize) {
+        if width == 0 || self.text.is_empty() {
+            *cache = BlockCache::default();
+            return;
+        }
+        if cache.width != width {
+            *cache = BlockCache {
+                width,
+                ..BlockCache::default()
+            };
         }
 
-        let mut lines: Vec<Vec<Segment>> = Vec::new();
-        let mut style_stack: Vec<Style> = vec![Style::default()];
-        let mut current_line: Vec<Segment> = Vec::new();
-        let mut current_width: usize = 0;
-        let mut in_code_block = false;
-        let mut list_depth: usize = 0;
-        let mut in_list_item = false;
+        // Definitions apply to the whole text, so the cached blocks are
+        // rendered again whenever one arrives or changes.
+        let mut definitions = cache.definitions.clone();
+        definitions.scan(&self.text[cache.stable..]);
+        if definitions != cache.rendered_with {
+            *cache = BlockCache {
+                width,
+                rendered_with: definitions,
+                ..BlockCache::default()
+            };
+        }
 
-        let opts = Options::empty();
-        let parser = Parser::new_ext(&self.text, opts);
+        let open = &self.text[cache.stable..];
+        let finished = &open[..last_block_start(open)];
+        if !finished.is_empty() {
+            cache.lines.truncate(cache.stable_lines);
+            let continued = cache.stable_lines > 0;
+            let lines = self.render_document(finished, width, continued, &cache.rendered_with);
+            cache.lines.extend(lines);
+            cache.stable_lines = cache.lines.len();
+            cache.definitions.scan(finished);
+            cache.stable += finished.len();
+        }
 
-        for event in parser {
-            match event {
-                Event::Start(tag) => match tag {
-                    Tag::Heading { level, .. } => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                        let level_num = level as u8;
-                        let heading_style = heading_style(level_num);
-                        style_stack.push(heading_style);
-                    }
-                    Tag::Paragraph => {
-                        // Add blank line before paragraph (unless at start)
-                        if !lines.is_empty() {
-                            lines.push(Vec::new());
-                        }
-                    }
-                    Tag::CodeBlock(kind) => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                        in_code_block = true;
-                        let _lang = match kind {
-                            pulldown_cmark::CodeBlockKind::Fenced(lang) => {
-                                let l = lang.to_string();
-                                if l.is_empty() { None } else { Some(l) }
-                            }
-                            pulldown_cmark::CodeBlockKind::Indented => None,
-                        };
-                        style_stack.push(code_block_style());
-                    }
-                    Tag::Emphasis => {
-                        let base = current_style(&style_stack);
-                        style_stack.push(base.italic(true));
-                    }
-                    Tag::Strong => {
-                        let base = current_style(&style_stack);
-                        style_stack.push(base.bold(true));
-                    }
-                    Tag::List(_) => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                        list_depth += 1;
-                    }
-                    Tag::Item => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                        in_list_item = true;
-                        // Add list marker
-                        let indent = "  ".repeat(list_depth.saturating_sub(1));
-                        let marker = format!("{indent}- ");
-                        let marker_w = UnicodeWidthStr::width(marker.as_str());
-                        current_line.push(Segment::new(marker));
-                        current_width = marker_w;
-                    }
-                    Tag::BlockQuote(_) => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                        let base = current_style(&style_stack);
-                        style_stack.push(base.dim(true));
-                    }
-                    _ => {}
-                },
-                Event::End(tag_end) => match tag_end {
-                    TagEnd::Heading(_) => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                        style_stack.pop();
-                    }
-                    TagEnd::Paragraph => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                    }
-                    TagEnd::CodeBlock => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                        in_code_block = false;
-                        style_stack.pop();
-                    }
-                    TagEnd::Emphasis | TagEnd::Strong => {
-                        style_stack.pop();
+        let tail = visible_tail(&self.text[cache.stable..]);
+        let continued = cache.stable_lines > 0;
+        let lines = self.render_document(tail, width, continued, &cache.rendered_with);
+        cache.lines.truncate(cache.stable_lines);
+        cache.lines.extend(lines);
+    }
+
+    /// Render a run of whole blocks. `continued` says whether rendered
+    /// lines come before them, so the first block is separated from those;
+    /// `definitions` are the definitions made anywhere in the text.
+    fn render_document(
+        &self,
+        text: &str,
+        width: usize,
+        continued: bool,
+        definitions: &Definitions,
+    ) -> Vec<Vec<Segment>> {
+        // Stand-in definitions let references to links and footnotes
+        // defined outside `text` resolve; their own events are skipped.
+        let mut source = String::new();
+        if text.contains('[') {
+            source = definitions.source();
+        }
+        let skip = source.len();
+        source.push_str(text);
+
+        let mut writer = Writer::new(self, width);
+        writer.continued = continued;
+        for (event, range) in Parser::new_ext(&source, PARSE_OPTIONS).into_offset_iter() {
+            if range.start >= skip {
+                writer.event(event);
+            }
+        }
+        writer.finish()
+    }
+
+    /// Render a code block's lines as-is (no word wrap), truncated to
+    /// `width` and highlighted when its language has a grammar.
+    fn render_code_block(&self, lang: Option<&str>, code: &str, width: usize) -> Vec<Vec<Segment>> {
+        let base = &self.theme.code_block;
+        let highlighter =
+            lang.and_then(|lang| highlighter_for_language(lang, &self.syntax_theme, code));
+        code.lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let truncated = truncate_to_display_width(line, width);
+                match &highlighter {
+                    Some(h) => {
+                        // Spans are for the whole line; columns past the
+                        // truncation point are simply never reached.
+                        let spans = h.highlight_line(i, line);
+                        highlight_segments(truncated, &spans, &Style::default())
+                            .into_iter()
+                            .map(|seg| {
+                                if seg.style.is_empty() {
+                                    Segment::styled(seg.text, base.clone())
+                                } else {
+                                    seg
+                                }
+                            })
+                            .collect()
                     }
-                    TagEnd::List(_) => {
-                        list_depth = list_depth.saturating_sub(1);
+                    None => vec![Segment::styled(truncated.to_string(), base.clone())],
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for MarkdownRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A piece of inline content waiting to be wrapped.
+#[derive(Clone, Debug)]
+enum Inline {
+    /// Text that may break at whitespace.
+    Text(Segment),
+    /// Text kept on one line, such as inline code.
+    Atom(Segment),
+    /// A hard line break.
+    Break,
+}
+
+/// A block that prefixes each of its lines.
+enum Container {
+    /// A block quote, drawn as a bar.
+    Quote,
+    /// A list item or footnote definition: its marker on the first line,
+    /// then an indent as wide as the marker.
+    Item {
+        marker: Segment,
+        width: usize,
+        printed: bool,
+    },
+}
+
+/// A table being collected, cell by cell.
+struct Table {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<Vec<Inline>>>,
+    /// Rows belonging to the header.
+    head_rows: usize,
+    row: Vec<Vec<Inline>>,
+    cell: Option<Vec<Inline>>,
+}
+
+/// Turns parser events into lines.
+struct Writer<'a> {
+    renderer: &'a MarkdownRenderer,
+    theme: &'a MarkdownTheme,
+    width: usize,
+    lines: Vec<Vec<Segment>>,
+    /// Inline content of the block being built.
+    inline: Vec<Inline>,
+    style_stack: Vec<Style>,
+    containers: Vec<Container>,
+    /// The next number of each open list; `None` for bullet lists.
+    lists: Vec<Option<u64>>,
+    /// Language and text of the code block being collected.
+    code_block: Option<(Option<String>, String)>,
+    table: Option<Table>,
+    /// Whether the last line is a blank separator.
+    gap: bool,
+    /// Whether a quote, item or footnote has just opened, so its first
+    /// block needs no separator.
+    fresh: bool,
+    /// Whether these lines follow earlier rendered blocks.
+    continued: bool,
+}
+
+impl<'a> Writer<'a> {
+    fn new(renderer: &'a MarkdownRenderer, width: usize) -> Self {
+        Self {
+            renderer,
+            theme: &renderer.theme,
+            width,
+            lines: Vec::new(),
+            inline: Vec::new(),
+            style_stack: vec![Style::default()],
+            containers: Vec::new(),
+            lists: Vec::new(),
+            code_block: None,
+            table: None,
+            gap: false,
+            fresh: false,
+            continued: false,
+        }
+    }
+
+    fn finish(mut self) -> Vec<Vec<Segment>> {
+        self.flush();
+        if let Some(table) = self.table.take() {
+            // A table cut off mid-stream still shows what has arrived.
+            self.render_table(table);
+        }
+        self.lines
+    }
+
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => match self.code_block.as_mut() {
+                Some((_, code)) => code.push_str(&text),
+                None => self.push(Inline::Text(Segment::styled(
+                    text.to_string(),
+                    self.style(),
+                ))),
+            },
+            Event::Code(code) => {
+                let style = self.style().merge(&self.theme.inline_code);
+                self.push(Inline::Atom(Segment::styled(format!("`{code}`"), style)));
+            }
+            Event::FootnoteReference(label) => {
+                let style = self.style().merge(&self.theme.footnote);
+                self.push(Inline::Atom(Segment::styled(format!("[{label}]"), style)));
+            }
+            Event::TaskListMarker(checked) => self.task_marker(checked),
+            Event::SoftBreak => self.push(Inline::Text(Segment::styled(" ", self.style()))),
+            Event::HardBreak => self.push(Inline::Break),
+            Event::Rule => {
+                self.flush();
+                self.separate();
+                let rule = "─".repeat(self.available().min(MAX_RULE_WIDTH));
+                self.emit(vec![Segment::styled(rule, self.theme.rule.clone())]);
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Paragraph => {
+                self.flush();
+                self.separate();
+            }
+            Tag::Heading { level, .. } => {
+                self.flush();
+                self.separate();
+                let style = self.theme.heading(level as u8).clone();
+                self.style_stack.push(style);
+            }
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.separate();
+                self.containers.push(Container::Quote);
+                self.fresh = true;
+                self.push_style(&self.theme.quote.clone());
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush();
+                self.separate();
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) => {
+                        // Info strings may carry attributes after the language.
+                        let lang = lang.split_whitespace().next().unwrap_or_default();
+                        (!lang.is_empty()).then(|| lang.to_string())
                     }
-                    TagEnd::Item => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                        in_list_item = false;
+                    CodeBlockKind::Indented => None,
+                };
+                self.code_block = Some((lang, String::new()));
+            }
+            Tag::List(start) => {
+                self.flush();
+                if self.lists.is_empty() {
+                    self.separate();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        let marker = format!("{n}. ");
+                        *n += 1;
+                        marker
                     }
-                    TagEnd::BlockQuote(_) => {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
-                        style_stack.pop();
+                    _ => "- ".to_string(),
+                };
+                self.open_item(marker);
+            }
+            Tag::FootnoteDefinition(label) => {
+                self.flush();
+                self.separate();
+                self.open_item(format!("[{label}]: "));
+                if let Some(Container::Item { marker, .. }) = self.containers.last_mut() {
+                    marker.style = self.theme.footnote.clone();
+                }
+            }
+            Tag::Table(alignments) => {
+                self.flush();
+                self.separate();
+                self.table = Some(Table {
+                    alignments,
+                    rows: Vec::new(),
+                    head_rows: 0,
+                    row: Vec::new(),
+                    cell: None,
+                });
+            }
+            Tag::TableCell => {
+                if let Some(table) = self.table.as_mut() {
+                    table.cell = Some(Vec::new());
+                }
+            }
+            Tag::Emphasis => self.push_style(&Style::new().italic(true)),
+            Tag::Strong => self.push_style(&Style::new().bold(true)),
+            Tag::Strikethrough => self.push_style(&Style::new().strikethrough(true)),
+            Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. } => {
+                let style = self.theme.link.clone().link(dest_url.to_string());
+                self.push_style(&style);
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => self.flush(),
+            TagEnd::Heading(_) => {
+                self.flush();
+                self.style_stack.pop();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.containers.pop();
+                self.style_stack.pop();
+                self.fresh = false;
+            }
+            TagEnd::CodeBlock => {
+                if let Some((lang, code)) = self.code_block.take() {
+                    let lines =
+                        self.renderer
+                            .render_code_block(lang.as_deref(), &code, self.available());
+                    for line in lines {
+                        self.emit(line);
                     }
-                    _ => {}
-                },
-                Event::Text(text) => {
-                    let style = current_style(&style_stack);
-                    if in_code_block {
-                        // Code blocks: render each line as-is (no word wrap)
-                        for (i, line) in text.lines().enumerate() {
-                            if i > 0 {
-                                flush_line(&mut lines, &mut current_line, &mut current_width);
-                            }
-                            let truncated = truncate_to_display_width(line, w);
-                            current_line
-                                .push(Segment::styled(truncated.to_string(), style.clone()));
-                            current_width += UnicodeWidthStr::width(truncated);
-                        }
-                        if text.ends_with('\n') {
-                            flush_line(&mut lines, &mut current_line, &mut current_width);
-                        }
+                }
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+            }
+            TagEnd::Item | TagEnd::FootnoteDefinition => {
+                self.flush();
+                // An empty item still shows its marker.
+                if matches!(
+                    self.containers.last(),
+                    Some(Container::Item { printed: false, .. })
+                ) {
+                    self.emit(Vec::new());
+                }
+                self.containers.pop();
+                self.fresh = false;
+            }
+            TagEnd::Table => {
+                if let Some(table) = self.table.take() {
+                    self.render_table(table);
+                }
+            }
+            TagEnd::TableHead => {
+                if let Some(table) = self.table.as_mut() {
+                    table.rows.push(std::mem::take(&mut table.row));
+                    table.head_rows = table.rows.len();
+                }
+            }
+            TagEnd::TableRow => {
+                if let Some(table) = self.table.as_mut() {
+                    table.rows.push(std::mem::take(&mut table.row));
+                }
+            }
+            TagEnd::TableCell => {
+                if let Some(table) = self.table.as_mut() {
+                    let cell = table.cell.take().unwrap_or_default();
+                    table.row.push(cell);
+                }
+            }
+            TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Link
+            | TagEnd::Image => {
+                self.style_stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    /// The current inline style.
+    fn style(&self) -> Style {
+        self.style_stack.last().cloned().unwrap_or_default()
+    }
+
+    /// Push `style` on top of the current style.
+    fn push_style(&mut self, style: &Style) {
+        let merged = self.style().merge(style);
+        self.style_stack.push(merged);
+    }
+
+    /// Add inline content to the open table cell or the current block.
+    fn push(&mut self, inline: Inline) {
+        match self.table.as_mut().and_then(|t| t.cell.as_mut()) {
+            Some(cell) => cell.push(inline),
+            None => self.inline.push(inline),
+        }
+    }
+
+    fn open_item(&mut self, marker: String) {
+        let width = UnicodeWidthStr::width(marker.as_str());
+        self.containers.push(Container::Item {
+            marker: Segment::styled(marker, self.theme.list_marker.clone()),
+            width,
+            printed: false,
+        });
+        self.fresh = true;
+    }
+
+    /// Show a task checkbox in place of the item's bullet, or after its
+    /// number.
+    fn task_marker(&mut self, checked: bool) {
+        let bullet = matches!(self.lists.last(), Some(None));
+        if let Some(Container::Item { marker, width, .. }) = self.containers.last_mut() {
+            let checkbox = if checked { "☑ " } else { "☐ " };
+            marker.text = if bullet {
+                checkbox.to_string()
+            } else {
+                format!("{}{checkbox}", marker.text)
+            };
+            *width = UnicodeWidthStr::width(marker.text.as_str());
+        }
+    }
+
+    /// Columns left for content after the container prefixes.
+    fn available(&self) -> usize {
+        let prefix: usize = self
+            .containers
+            .iter()
+            .map(|c| match c {
+                Container::Quote => 2,
+                Container::Item { width, .. } => *width,
+            })
+            .sum();
+        self.width.saturating_sub(prefix).max(1)
+    }
+
+    /// Wrap the pending inline content into lines.
+    fn flush(&mut self) {
+        if self.inline.is_empty() {
+            return;
+        }
+        let inline = std::mem::take(&mut self.inline);
+        for line in wrap_inline(&inline, self.available()) {
+            self.emit(line);
+        }
+    }
+
+    /// Push a content line behind the container prefixes.
+    fn emit(&mut self, content: Vec<Segment>) {
+        let mut line = Vec::new();
+        for container in &mut self.containers {
+            match container {
+                Container::Quote => {
+                    line.push(Segment::styled("│ ", self.theme.quote_bar.clone()));
+                }
+                Container::Item {
+                    marker,
+                    width,
+                    printed,
+                } => {
+                    if *printed {
+                        line.push(Segment::new(" ".repeat(*width)));
                     } else {
-                        // Normal text: word wrap
-                        let mut state = WrapState {
-                            lines: &mut lines,
-                            current_line: &mut current_line,
-                            current_width: &mut current_width,
-                            in_list_item,
-                            list_depth,
-                        };
-                        wrap_text_into(&text, &style, w, &mut state);
+                        line.push(marker.clone());
+                        *printed = true;
                     }
                 }
-                Event::Code(code) => {
-                    let style = inline_code_style();
-                    let code_str = format!("`{code}`");
-                    let code_w = UnicodeWidthStr::width(code_str.as_str());
-                    if current_width + code_w > w && !current_line.is_empty() {
-                        flush_line(&mut lines, &mut current_line, &mut current_width);
+            }
+        }
+        line.extend(content);
+        self.lines.push(line);
+        self.gap = false;
+        self.fresh = false;
+    }
+
+    /// Separate a new block from the one before with a blank line, which
+    /// keeps the quote bars it sits inside.
+    fn separate(&mut self) {
+        if std::mem::take(&mut self.fresh) || self.gap || (self.lines.is_empty() && !self.continued)
+        {
+            return;
+        }
+        let quotes = self
+            .containers
+            .iter()
+            .rposition(|c| matches!(c, Container::Quote));
+        let mut line = Vec::new();
+        if let Some(last) = quotes {
+            for (i, container) in self.containers[..=last].iter().enumerate() {
+                match container {
+                    Container::Quote => {
+                        let bar = if i == last { "│" } else { "│ " };
+                        line.push(Segment::styled(bar, self.theme.quote_bar.clone()));
                     }
-                    current_line.push(Segment::styled(code_str, style));
-                    current_width += code_w;
-                }
-                Event::SoftBreak => {
-                    // Treat as space
-                    let style = current_style(&style_stack);
-                    if current_width < w {
-                        current_line.push(Segment::styled(" ".to_string(), style));
-                        current_width += 1;
+                    Container::Item { width, .. } => {
+                        line.push(Segment::new(" ".repeat(*width)));
                     }
                 }
-                Event::HardBreak => {
-                    flush_line(&mut lines, &mut current_line, &mut current_width);
-                }
-                Event::Rule => {
-                    flush_line(&mut lines, &mut current_line, &mut current_width);
-                    let rule = "─".repeat(w.min(80));
-                    lines.push(vec![Segment::styled(rule, Style::new().dim(true))]);
-                }
-                _ => {}
             }
         }
+        self.lines.push(line);
+        self.gap = true;
+    }
+
+    /// Draw a table with box-drawing borders, shrinking the widest
+    /// columns until it fits and wrapping their cells.
+    fn render_table(&mut self, table: Table) {
+        let columns = table
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain([table.alignments.len()])
+            .max()
+            .unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        let mut widths = vec![1; columns];
+        for row in &table.rows {
+            for (c, cell) in row.iter().enumerate() {
+                widths[c] = widths[c].max(inline_width(cell));
+            }
+        }
+        // Each column has a bar and a space either side of its content.
+        let room = self
+            .available()
+            .saturating_sub(3 * columns + 1)
+            .max(columns);
+        while widths.iter().sum::<usize>() > room {
+            let Some((widest, _)) = widths.iter().enumerate().max_by_key(|&(i, w)| (*w, i)) else {
+                break;
+            };
+            if widths[widest] <= 1 {
+                break;
+            }
+            widths[widest] -= 1;
+        }
 
-        // Flush remaining content
-        flush_line(&mut lines, &mut current_line, &mut current_width);
+        let border = self.theme.table_border.clone();
+        let rule = |left: &str, mid: &str, right: &str| {
+            let bars: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+            vec![Segment::styled(
+                format!("{left}{}{right}", bars.join(mid)),
+                border.clone(),
+            )]
+        };
+        let top = rule("┌", "┬", "┐");
+        let middle = rule("├", "┼", "┤");
+        let bottom = rule("└", "┴", "┘");
 
-        lines
+        self.emit(top);
+        for (r, row) in table.rows.iter().enumerate() {
+            if r > 0 && r == table.head_rows {
+                self.emit(middle.clone());
+            }
+            let header = r < table.head_rows;
+            let cells: Vec<Vec<Vec<Segment>>> = (0..columns)
+                .map(|c| {
+                    let cell = row.get(c).map(Vec::as_slice).unwrap_or_default();
+                    wrap_inline(cell, widths[c])
+                })
+                .collect();
+            let height = cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+            for i in 0..height {
+                let mut line = vec![Segment::styled("│", border.clone())];
+                for (c, cell) in cells.iter().enumerate() {
+                    let content = cell.get(i).cloned().unwrap_or_default();
+                    let pad = widths[c].saturating_sub(segments_width(&content));
+                    let (before, after) = match table.alignments.get(c) {
+                        Some(Alignment::Right) => (pad, 0),
+                        Some(Alignment::Center) => (pad / 2, pad - pad / 2),
+                        _ => (0, pad),
+                    };
+                    line.push(Segment::new(" ".repeat(before + 1)));
+                    line.extend(content.into_iter().map(|mut seg| {
+                        if header {
+                            seg.style = self.theme.table_header.merge(&seg.style);
+                        }
+                        seg
+                    }));
+                    line.push(Segment::new(" ".repeat(after + 1)));
+                    line.push(Segment::styled("│", border.clone()));
+                }
+                self.emit(line);
+            }
+        }
+        self.emit(bottom);
     }
 }
 
-impl Default for MarkdownRenderer {
-    fn default() -> Self {
-        Self::new()
+/// The byte offset of the line where the last top-level block of `text`
+/// that follows a blank line starts. Everything before it is finished:
+/// more text cannot pull a line back into a block after a blank line,
+/// except digits still being typed, which may yet become the next item
+/// of an ordered list.
+fn last_block_start(text: &str) -> usize {
+    let mut depth = 0usize;
+    let mut last = 0;
+    let top_level = |start: usize, last: &mut usize| {
+        let line = text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let after_blank = line > 0
+            && text[..line - 1]
+                .rsplit('\n')
+                .next()
+                .is_some_and(|prev| prev.trim().is_empty());
+        let rest = &text[line..];
+        let digits = !rest.contains('\n') && rest.trim().bytes().all(|b| b.is_ascii_digit());
+        if after_blank && !digits {
+            *last = line;
+        }
+    };
+    for (event, range) in Parser::new_ext(text, PARSE_OPTIONS).into_offset_iter() {
+        match event {
+            Event::Start(_) => {
+                if depth == 0 {
+                    top_level(range.start, &mut last);
+                }
+                depth += 1;
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Rule if depth == 0 => top_level(range.start, &mut last),
+            _ => {}
+        }
     }
+    last
 }
 
-/// Flush the current line segments into the lines list.
-fn flush_line(
-    lines: &mut Vec<Vec<Segment>>,
-    current_line: &mut Vec<Segment>,
-    current_width: &mut usize,
-) {
-    if !current_line.is_empty() {
-        lines.push(std::mem::take(current_line));
+/// `text` with every ASCII punctuation character backslash-escaped, so
+/// it reads back literally in a link destination or title.
+fn escape_punctuation(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii_punctuation() {
+            escaped.push('\\');
+        }
+        escaped.push(c);
     }
-    *current_width = 0;
+    escaped
 }
 
-/// Get the current style from the style stack.
-fn current_style(stack: &[Style]) -> Style {
-    stack.last().cloned().unwrap_or_default()
+/// The open tail without a code fence still being typed on its last
+/// line, so a half-streamed marker does not flash up as text.
+fn visible_tail(tail: &str) -> &str {
+    let start = tail.rfind('\n').map_or(0, |i| i + 1);
+    let last = tail[start..].trim_start();
+    let fence = last.starts_with("```")
+        || last.starts_with("~~~")
+        || (!last.is_empty() && last.chars().all(|c| c == '`' || c == '~'));
+    if fence { &tail[..start] } else { tail }
 }
 
-/// Style for headings by level.
-fn heading_style(level: u8) -> Style {
-    match level {
-        1 => Style::new().bold(true).fg(Color::Named(NamedColor::Cyan)),
-        2 => Style::new().bold(true).fg(Color::Named(NamedColor::Green)),
-        3 => Style::new().bold(true).fg(Color::Named(NamedColor::Yellow)),
-        _ => Style::new().bold(true),
-    }
+/// Display width of inline content laid out on one line.
+fn inline_width(inline: &[Inline]) -> usize {
+    wrap_inline(inline, usize::MAX)
+        .iter()
+        .map(|line| segments_width(line))
+        .max()
+        .unwrap_or(0)
 }
 
-/// Style for inline code.
-fn inline_code_style() -> Style {
-    Style::new().fg(Color::Named(NamedColor::Yellow))
+fn segments_width(line: &[Segment]) -> usize {
+    line.iter()
+        .map(|s| UnicodeWidthStr::width(s.text.as_str()))
+        .sum()
 }
 
-/// Style for code blocks.
-fn code_block_style() -> Style {
-    Style::new().dim(true)
+/// Append text to a line, extending the last segment when the style
+/// matches.
+fn push_text(line: &mut Vec<Segment>, text: &str, style: &Style) {
+    match line.last_mut() {
+        Some(last) if last.style == *style => last.text.push_str(text),
+        _ => line.push(Segment::styled(text.to_string(), style.clone())),
+    }
 }
 
-/// Mutable rendering state for word wrapping.
-struct WrapState<'a> {
-    lines: &'a mut Vec<Vec<Segment>>,
-    current_line: &'a mut Vec<Segment>,
-    current_width: &'a mut usize,
-    in_list_item: bool,
-    list_depth: usize,
+/// Word-wrap inline content to `width` columns. Runs of whitespace become
+/// one space, dropped at line breaks; words wider than a line are split.
+fn wrap_inline(inline: &[Inline], width: usize) -> Vec<Vec<Segment>> {
+    let mut wrap = Wrap {
+        width: width.max(1),
+        lines: Vec::new(),
+        line: Vec::new(),
+        line_w: 0,
+        space: None,
+    };
+    for piece in inline {
+        match piece {
+            Inline::Break => wrap.break_line(),
+            Inline::Atom(seg) => wrap.word(&seg.text, &seg.style),
+            Inline::Text(seg) => {
+                let mut rest = seg.text.as_str();
+                while let Some(first) = rest.chars().next() {
+                    let (run, is_space) = if first.is_whitespace() {
+                        (rest.find(|c: char| !c.is_whitespace()), true)
+                    } else {
+                        (rest.find(char::is_whitespace), false)
+                    };
+                    let (run, tail) = rest.split_at(run.unwrap_or(rest.len()));
+                    if is_space {
+                        wrap.space(&seg.style);
+                    } else {
+                        wrap.word(run, &seg.style);
+                    }
+                    rest = tail;
+                }
+            }
+        }
+    }
+    wrap.break_line();
+    wrap.lines
 }
 
-/// Word-wrap text and push segments into the output.
-fn wrap_text_into(text: &str, style: &Style, width: usize, state: &mut WrapState<'_>) {
-    for word in text.split_whitespace() {
-        let word_w = UnicodeWidthStr::width(word);
-
-        // If adding this word would overflow, wrap
-        let space_needed = if state.current_line.is_empty() || *state.current_width == 0 {
-            0
-        } else {
-            1
-        };
+/// Line-filling state for [`wrap_inline`].
+struct Wrap {
+    width: usize,
+    lines: Vec<Vec<Segment>>,
+    line: Vec<Segment>,
+    line_w: usize,
+    /// The style of a space owed before the next word.
+    space: Option<Style>,
+}
 
-        if *state.current_width + space_needed + word_w > width && !state.current_line.is_empty() {
-            flush_line(state.lines, state.current_line, state.current_width);
-            // Add indent for continuation lines in list items
-            if state.in_list_item {
-                let indent = "  ".repeat(state.list_depth.saturating_sub(1));
-                let cont_indent = format!("{indent}  ");
-                let indent_w = UnicodeWidthStr::width(cont_indent.as_str());
-                state.current_line.push(Segment::new(cont_indent));
-                *state.current_width = indent_w;
-            }
+impl Wrap {
+    fn space(&mut self, style: &Style) {
+        if self.line_w > 0 {
+            self.space = Some(style.clone());
         }
+    }
 
-        // Add space before word (unless at start of line)
-        if !state.current_line.is_empty() && *state.current_width > 0 {
-            state
-                .current_line
-                .push(Segment::styled(" ".to_string(), style.clone()));
-            *state.current_width += 1;
+    fn break_line(&mut self) {
+        if !self.line.is_empty() {
+            self.lines.push(std::mem::take(&mut self.line));
         }
+        self.line_w = 0;
+        self.space = None;
+    }
 
-        state
-            .current_line
-            .push(Segment::styled(word.to_string(), style.clone()));
-        *state.current_width += word_w;
+    fn word(&mut self, word: &str, style: &Style) {
+        let word_w = UnicodeWidthStr::width(word);
+        let space_w = usize::from(self.space.is_some());
+        if self.line_w > 0 && self.line_w + space_w + word_w > self.width {
+            self.break_line();
+        }
+        if let Some(space) = self.space.take() {
+            push_text(&mut self.line, " ", &space);
+            self.line_w += 1;
+        }
+        if self.line_w + word_w <= self.width {
+            push_text(&mut self.line, word, style);
+            self.line_w += word_w;
+            return;
+        }
+        // Too wide for a line of its own: split it.
+        for ch in word.chars() {
+            let ch_w = UnicodeWidthChar::width(ch).unwrap_or(0);
+            if self.line_w > 0 && self.line_w + ch_w > self.width {
+                self.break_line();
+            }
+            push_text(&mut self.line, ch.encode_utf8(&mut [0; 4]), style);
+            self.line_w += ch_w;
+        }
     }
 }
 
@@ -450,4 +1231,300 @@ mod tests {
         let lines = r.render_to_lines(80);
         assert!(lines.len() >= 4);
     }
+
+    #[test]
+    fn code_block_without_grammar_is_plain() {
+        let mut r = MarkdownRenderer::new();
+        r.push_str("```cobol\nDISPLAY 'HI'.\n\nSTOP RUN.\n```");
+        let lines = r.render_to_lines(80);
+        assert_eq!(lines.len(), 3);
+        for line in &lines {
+            assert!(
+                line.iter()
+                    .all(|s| s.style == MarkdownTheme::default().code_block)
+            );
+        }
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn fenced_code_block_is_highlighted() {
+        let theme = SyntaxTheme::empty().with("keyword", Style::new().bold(true));
+        let mut r = MarkdownRenderer::new().with_syntax_theme(theme);
+        r.push_str("```rust\nfn main() {}\n```");
+        let lines = r.render_to_lines(8);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0][0].text, "fn");
+        assert!(lines[0][0].style.bold);
+        // The rest is plain code, truncated to the width.
+        let rest: String = lines[0][1..].iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(rest, " main(");
+        assert!(
+            lines[0][1..]
+                .iter()
+                .all(|s| s.style == MarkdownTheme::default().code_block)
+        );
+    }
+
+    fn render(md: &str, width: u16) -> Vec<String> {
+        let mut r = MarkdownRenderer::new();
+        r.push_str(md);
+        r.render_to_lines(width)
+            .iter()
+            .map(|l| l.iter().map(|s| s.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn table_has_borders_and_alignment() {
+        let lines = render("| a | bb |\n|---|--:|\n| 1 | 2 |", 40);
+        assert_eq!(
+            lines,
+            vec![
+                "┌───┬────┐",
+                "│ a │ bb │",
+                "├───┼────┤",
+                "│ 1 │  2 │",
+                "└───┴────┘",
+            ]
+        );
+
+        let mut r = MarkdownRenderer::new();
+        r.push_str("| a |\n|---|\n| 1 |");
+        let styled = r.render_to_lines(40);
+        assert!(styled[1].iter().any(|s| s.text == "a" && s.style.bold));
+        assert!(styled[3].iter().any(|s| s.text == "1" && !s.style.bold));
+    }
+
+    #[test]
+    fn table_columns_shrink_to_fit() {
+        let md = "| name | description |\n|---|---|\n| x | a fairly long description |";
+        let lines = render(md, 24);
+        for line in &lines {
+            assert!(UnicodeWidthStr::width(line.as_str()) <= 24, "{line:?}");
+        }
+        let text = lines.join("\n");
+        assert!(text.contains("fairly"));
+        assert!(text.contains("description"));
+        // The long cell wrapped onto more rows than the one-row table.
+        assert!(lines.len() > 5);
+    }
+
+    #[test]
+    fn task_lists_show_checkboxes() {
+        let lines = render("- [x] done\n- [ ] todo\n1. [ ] first", 40);
+        assert_eq!(lines, vec!["☑ done", "☐ todo", "", "1. ☐ first"]);
+    }
+
+    #[test]
+    fn strikethrough_is_styled() {
+        let mut r = MarkdownRenderer::new();
+        r.push_str("~~gone~~ kept");
+        let lines = r.render_to_lines(40);
+        assert!(
+            lines[0]
+                .iter()
+                .any(|s| s.text == "gone" && s.style.strikethrough)
+        );
+        assert!(
+            lines[0]
+                .iter()
+                .any(|s| s.text.contains("kept") && !s.style.strikethrough)
+        );
+    }
+
+    #[test]
+    fn ordered_lists_are_numbered() {
+        let lines = render("3. three\n4. four\n   - nested\n5. five", 40);
+        assert_eq!(lines, vec!["3. three", "4. four", "   - nested", "5. five"]);
+    }
+
+    #[test]
+    fn list_items_wrap_under_their_text() {
+        let lines = render("- aaa bbb ccc", 8);
+        assert_eq!(lines, vec!["- aaa", "  bbb", "  ccc"]);
+    }
+
+    #[test]
+    fn nested_blockquotes_have_bars() {
+        let lines = render("> outer\n>\n> > inner", 40);
+        assert_eq!(lines, vec!["│ outer", "│", "│ │ inner"]);
+    }
+
+    #[test]
+    fn horizontal_rule_spans_the_width() {
+        let lines = render("above\n\n---\n\nbelow", 10);
+        assert_eq!(lines, vec!["above", "", "──────────", "", "below"]);
+    }
+
+    #[test]
+    fn links_carry_their_url() {
+        let mut r = MarkdownRenderer::new();
+        r.push_str("see [the docs](https://example.com/docs)");
+        let lines = r.render_to_lines(40);
+        let link = lines[0].iter().find(|s| s.text == "the docs");
+        assert!(link.is_some_and(|s| s.style.link.as_deref() == Some("https://example.com/docs")));
+        assert!(
+            lines[0]
+                .iter()
+                .any(|s| s.text == "see " && s.style.link.is_none())
+        );
+    }
+
+    #[test]
+    fn footnotes_render_as_labels() {
+        let lines = render("Claim[^1].\n\n[^1]: Source.", 40);
+        assert_eq!(lines, vec!["Claim[1].", "", "[1]: Source."]);
+    }
+
+    #[test]
+    fn long_words_are_split() {
+        let lines = render("abcdefghij", 4);
+        assert_eq!(lines, vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn theme_follows_tcss_variables() {
+        use crate::tcss::value::CssValue;
+
+        let mut vars = VariableEnvironment::new();
+        vars.set_theme("primary", CssValue::Color(Color::Named(NamedColor::Red)));
+        vars.set_global("markdown-link", CssValue::Keyword("magenta italic".into()));
+        let theme = MarkdownTheme::from_variables(&vars);
+        // Palette colors keep the heading bold.
+        assert_eq!(theme.heading(1).fg, Some(Color::Named(NamedColor::Red)));
+        assert!(theme.heading(1).bold);
+        assert_eq!(theme.link.fg, Some(Color::Named(NamedColor::Magenta)));
+        assert!(theme.link.italic);
+
+        let mut r = MarkdownRenderer::new();
+        r.apply_variables(&vars);
+        r.push_str("# Title");
+        let lines = r.render_to_lines(40);
+        assert_eq!(lines[0][0].style.fg, Some(Color::Named(NamedColor::Red)));
+    }
+
+    const STREAMED: &str = "# Report\n\n[^0]: Defined early.\n\n\
+        Intro with **bold** and a [link](https://x.y).\n\n\
+        - one\n- two\n  continued\n\n1. first\n2. second\n\n10. loose\n\n\
+        | a | b |\n|---|---|\n| 1 | 2 |\n\n> quote\n> > nested\n\n\
+        ```rust\nfn main() {}\n\nlet x = 1;\n```\n\n---\n\nNote[^1] and[^0].\n\n[^1]: Detail.\n";
+
+    fn texts(lines: &[Vec<Segment>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.iter().map(|s| s.text.as_str()).collect())
+            .collect()
+    }
+
+    /// Stream `md` one character at a time, checking that every step
+    /// renders like the same prefix rendered in one go.
+    fn stream(md: &str, width: u16) -> MarkdownRenderer {
+        let full = MarkdownRenderer::new();
+        let mut r = MarkdownRenderer::new();
+        let mut prefix = String::new();
+        for ch in md.chars() {
+            r.push_str(ch.encode_utf8(&mut [0; 4]));
+            prefix.push(ch);
+            let expected = full.render_document(
+                visible_tail(&prefix),
+                width as usize,
+                false,
+                &Definitions::default(),
+            );
+            assert_eq!(r.render_to_lines(width), expected, "after {prefix:?}");
+        }
+        r
+    }
+
+    #[test]
+    fn streaming_matches_a_full_render() {
+        let r = stream(STREAMED, 30);
+        // Everything but the last block is cached.
+        let stable = r.cache.borrow().stable;
+        assert_eq!(&STREAMED[stable..], "[^1]: Detail.\n");
+    }
+
+    #[test]
+    fn late_definitions_resolve_cached_blocks() {
+        let r = stream("Text with [link][r].\n\n[r]: http://x \"T\"\n", 40);
+        let lines = r.render_to_lines(40);
+        assert_eq!(texts(&lines), vec!["Text with link."]);
+        assert!(
+            lines[0]
+                .iter()
+                .any(|s| s.style.link.as_deref() == Some("http://x"))
+        );
+
+        let r = stream("See[^1].\n\nMore.\n\n[^1]: x\n", 40);
+        assert_eq!(texts(&r.render_to_lines(40))[0], "See[1].");
+
+        // The first definition of a label wins, wherever it is cached.
+        stream(
+            "[a]: <one>\n\n[A][] and [b]\n\n[b]: /b\\)\n\n[a]: two\n\ntext",
+            40,
+        );
+    }
+
+    #[test]
+    fn lines_borrow_the_cache() {
+        let mut r = MarkdownRenderer::new();
+        r.push_str("# Title\n\nbody");
+        let copied = r.render_to_lines(40);
+        assert_eq!(&*r.lines(40), copied.as_slice());
+        r.push_str(" more");
+        assert_eq!(texts(&r.lines(40)), vec!["Title", "", "body more"]);
+    }
+
+    #[test]
+    fn width_change_rewraps_cached_blocks() {
+        let mut r = MarkdownRenderer::new();
+        r.push_str("first paragraph with several words\n\nsecond");
+        let wide = r.render_to_lines(80);
+        assert_eq!(texts(&wide)[0], "first paragraph with several words");
+        let narrow = r.render_to_lines(12);
+        assert_eq!(
+            texts(&narrow),
+            vec!["first", "paragraph", "with several", "words", "", "second"]
+        );
+        assert_eq!(r.render_to_lines(80), wide);
+    }
+
+    #[test]
+    fn unterminated_code_fence_streams_as_code() {
+        let mut r = MarkdownRenderer::new();
+        r.push_str("text\n\n``");
+        // A half-typed fence is held back rather than shown as text.
+        assert_eq!(texts(&r.render_to_lines(40)), vec!["text"]);
+        r.push_str("`rust\nfn main() {");
+        let lines = r.render_to_lines(40);
+        assert_eq!(texts(&lines), vec!["text", "", "fn main() {"]);
+        assert!(lines[2].iter().any(|s| s.text.contains("main")));
+        r.push_str("}\n``");
+        assert_eq!(
+            texts(&r.render_to_lines(40)),
+            vec!["text", "", "fn main() {}"]
+        );
+    }
+
+    #[test]
+    fn clear_and_theme_changes_drop_the_cache() {
+        let mut r = MarkdownRenderer::new();
+        r.push_str("# Title\n\nbody");
+        r.render_to_lines(40);
+        assert!(r.cache.borrow().stable > 0);
+
+        let theme = MarkdownTheme {
+            headings: std::array::from_fn(|_| Style::new().italic(true)),
+            ..MarkdownTheme::default()
+        };
+        r.set_theme(theme);
+        let lines = r.render_to_lines(40);
+        assert!(lines[0][0].style.italic);
+
+        r.clear();
+        assert_eq!(r.cache.borrow().stable, 0);
+        r.push_str("new");
+        assert_eq!(texts(&r.render_to_lines(40)), vec!["new"]);
+    }
 }
@@ -2,11 +2,13 @@
 
 pub mod border;
 pub mod collapsible;
+pub mod command_palette;
 pub mod container;
 pub mod data_table;
 pub mod diff_view;
 pub mod directory_tree;
 pub mod form_controls;
+pub mod image;
 pub mod label;
 pub mod loading_indicator;
 pub mod markdown;
@@ -17,30 +19,38 @@ pub mod rich_log;
 pub mod select_list;
 pub mod sparkline;
 pub mod static_widget;
+pub mod table_filter;
+pub mod table_source;
 pub mod tabs;
+pub mod terminal_view;
 pub mod text_area;
 pub mod toast;
 pub mod tooltip;
 pub mod tree;
 
 pub use collapsible::Collapsible;
+pub use command_palette::{CommandPalette, PaletteEntry};
 pub use container::{BorderStyle, Container};
-pub use data_table::{Column, DataTable};
-pub use diff_view::{DiffMode, DiffView};
-pub use directory_tree::DirectoryTree;
+pub use data_table::{Column, DataTable, SortKey};
+pub use diff_view::{DiffHunk, DiffMode, DiffView, HunkStatus};
+pub use directory_tree::{DirectoryTree, FileOp, GitFileStatus};
 pub use form_controls::{Checkbox, RadioButton, Switch};
+pub use image::Image;
 pub use label::{Alignment, Label};
 pub use loading_indicator::{IndicatorStyle, LoadingIndicator};
-pub use markdown::MarkdownRenderer;
+pub use markdown::{MarkdownRenderer, MarkdownTheme};
 pub use modal::Modal;
 pub use option_list::OptionList;
 pub use progress_bar::{ProgressBar, ProgressMode};
-pub use rich_log::RichLog;
+pub use rich_log::{LogLevel, RichLog};
 pub use select_list::SelectList;
 pub use sparkline::Sparkline;
 pub use static_widget::StaticWidget;
+pub use table_filter::{FilterExpr, FilterOp};
+pub use table_source::{CsvSource, TableCell, TableDate, TableSource};
 pub use tabs::{Tab, TabBarPosition, Tabs};
-pub use text_area::TextArea;
+pub use terminal_view::TerminalView;
+pub use text_area::{SearchOptions, TextArea, TextMotion};
 pub use toast::{Toast, ToastPosition};
 pub use tooltip::Tooltip;
 pub use tree::{Tree, TreeNode};
@@ -78,6 +88,46 @@ pub trait SizedWidget: Widget {
 pub trait InteractiveWidget: Widget {
     /// Handle an input event. Returns whether the event was consumed.
     fn handle_event(&mut self, event: &Event) -> EventResult;
+
+    /// Deliver an event the way an [`App`](crate::app::App) does: key
+    /// releases are dropped, so widgets act once per press under the Kitty
+    /// keyboard protocol. Use this when driving a widget directly.
+    fn dispatch_event(&mut self, event: &Event) -> EventResult {
+        if event.is_key_release() {
+            return EventResult::Ignored;
+        }
+        self.handle_event(event)
+    }
+
+    /// Widget-defined pseudo-classes currently in effect, from
+    /// [`WIDGET_STATES`](crate::tcss::WIDGET_STATES) or
+    /// [`declared_pseudo_classes`](Self::declared_pseudo_classes). Default
+    /// is none.
+    fn pseudo_classes(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Every pseudo-class outside [`WIDGET_STATES`](crate::tcss::WIDGET_STATES)
+    /// that [`pseudo_classes`](Self::pseudo_classes) may return. An
+    /// [`App`](crate::app::App) accepts these in its stylesheets when the
+    /// widget is in the DOM as they are parsed. Default is none.
+    fn declared_pseudo_classes(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Whether Tab and Shift+Tab go to this widget while it has focus,
+    /// instead of moving focus. Default is false.
+    fn captures_tab(&self) -> bool {
+        false
+    }
+
+    /// Whether the widget changed on its own since it was last rendered,
+    /// e.g. from a background thread, and needs repainting. An
+    /// [`App`](crate::app::App) checks this before each frame. Default is
+    /// false.
+    fn needs_repaint(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
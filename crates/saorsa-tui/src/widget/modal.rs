@@ -19,6 +19,7 @@ pub struct Modal {
     border_style: BorderStyle,
     width: u16,
     height: u16,
+    opacity: f32,
 }
 
 impl Modal {
@@ -31,6 +32,7 @@ impl Modal {
             border_style: BorderStyle::Single,
             width,
             height,
+            opacity: 1.0,
         }
     }
 
@@ -55,6 +57,14 @@ impl Modal {
         self
     }
 
+    /// Set the modal opacity in `0.0..=1.0` so the content beneath shows
+    /// through its background.
+    #[must_use]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
     /// Render the modal to lines ready for the compositor.
     ///
     /// Produces a bordered box with the title in the top border line and
@@ -133,6 +143,7 @@ impl Modal {
             size: crate::geometry::Size::new(self.width, self.height),
             z_offset: 0,
             dim_background: true,
+            opacity: self.opacity,
         }
     }
 }
@@ -1,28 +1,119 @@
 //! Scrollable log widget that displays styled entries.
 //!
-//! Each entry is a line of [`Segment`]s. The log supports vertical
-//! scrolling via keyboard and optional auto-scrolling to the bottom
-//! when new entries are added.
+//! Each entry is a line of [`Segment`]s, optionally tagged with a
+//! [`LogLevel`]. The log can keep only its most recent entries, hide
+//! entries below a level, wrap long entries, search its entries and
+//! follow new output, pausing when scrolled up.
+//!
+//! Keys: arrows, PageUp/PageDown, Home/End scroll (End resumes following),
+//! `f` toggles following, `w` toggles wrapping, `l` cycles the level
+//! filter, `/` starts an incremental search, `n`/`N` move between matches
+//! and Esc clears the search.
+
+mod search;
 
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::ansi::parse_ansi;
 use crate::buffer::ScreenBuffer;
 use crate::cell::Cell;
-use crate::event::{Event, KeyCode, KeyEvent};
+use crate::color::{Color, NamedColor};
+use crate::error::Result;
+use crate::event::{Event, KeyCode, KeyEvent, Modifiers};
 use crate::geometry::Rect;
 use crate::segment::Segment;
 use crate::style::Style;
-use crate::text::truncate_to_display_width;
-use unicode_width::UnicodeWidthStr;
+use crate::wrap::wrap_line;
+use search::LogSearch;
+use unicode_width::UnicodeWidthChar;
+
+use super::{BorderStyle, EventResult, InteractiveWidget, SearchOptions, Widget};
+
+/// Columns taken by the level label in front of a tagged entry.
+const LABEL_WIDTH: usize = 6;
+
+/// Entries scrolled by PageUp and PageDown.
+const PAGE: usize = 20;
+
+/// The severity of a log entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// Fine-grained tracing.
+    Trace,
+    /// Debugging detail.
+    Debug,
+    /// Normal operation.
+    Info,
+    /// Something unexpected that the program recovered from.
+    Warn,
+    /// A failure.
+    Error,
+}
+
+impl LogLevel {
+    /// Every level, from least to most severe.
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+    ];
+
+    /// The label shown in front of entries at this level.
+    pub fn label(self) -> &'static str {
+        match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    /// The default style of the label.
+    fn default_style(self) -> Style {
+        match self {
+            LogLevel::Trace => Style::new().dim(true),
+            LogLevel::Debug => Style::new().fg(Color::Named(NamedColor::Blue)),
+            LogLevel::Info => Style::new().fg(Color::Named(NamedColor::Green)),
+            LogLevel::Warn => Style::new().fg(Color::Named(NamedColor::Yellow)),
+            LogLevel::Error => Style::new().fg(Color::Named(NamedColor::Red)).bold(true),
+        }
+    }
+}
+
+/// One line of the log.
+#[derive(Clone, Debug)]
+struct LogEntry {
+    level: Option<LogLevel>,
+    segments: Vec<Segment>,
+}
 
-use super::{BorderStyle, EventResult, InteractiveWidget, Widget};
+impl LogEntry {
+    /// The entry's text without styles.
+    fn text(&self) -> String {
+        self.segments.iter().map(|s| s.text.as_str()).collect()
+    }
+}
 
 /// A scrollable log widget that displays styled entries.
 ///
 /// Each entry is a vector of [`Segment`]s representing one line.
 /// Supports vertical scrolling and optional auto-scroll to bottom.
+/// Scroll offsets count the entries shown by the level filter.
 #[derive(Clone, Debug)]
 pub struct RichLog {
-    /// Log entries: each entry is a line of segments.
-    entries: Vec<Vec<Segment>>,
+    /// Log entries, oldest first.
+    entries: VecDeque<LogEntry>,
+    /// Id of the oldest entry; ids keep counting as entries are evicted.
+    first_id: u64,
+    /// Number of entries that pass the level filter.
+    visible: usize,
+    /// Most entries to keep, or `None` for no limit.
+    max_lines: Option<usize>,
     /// Index of the first visible entry.
     scroll_offset: usize,
     /// Base style for the log area.
@@ -31,17 +122,52 @@ pub struct RichLog {
     auto_scroll: bool,
     /// Border style (optional).
     border: BorderStyle,
+    /// Least severe level shown; untagged entries are always shown.
+    min_level: Option<LogLevel>,
+    /// Label styles, indexed like [`LogLevel::ALL`].
+    level_styles: [Style; 5],
+    /// Whether long entries wrap onto further rows.
+    wrap: bool,
+    /// The active search, if any.
+    search: Option<LogSearch>,
+    /// Options for searches typed after `/`.
+    search_options: SearchOptions,
+    /// The query being typed after `/`, while the prompt is open.
+    search_input: Option<String>,
+    /// Style for search matches.
+    match_style: Style,
+    /// Style for the current search match.
+    current_match_style: Style,
+    /// Style for the search prompt.
+    prompt_style: Style,
 }
 
 impl RichLog {
     /// Create a new empty log.
     pub fn new() -> Self {
         Self {
-            entries: Vec::new(),
+            entries: VecDeque::new(),
+            first_id: 0,
+            visible: 0,
+            max_lines: None,
             scroll_offset: 0,
             style: Style::default(),
             auto_scroll: true,
             border: BorderStyle::None,
+            min_level: None,
+            level_styles: LogLevel::ALL.map(LogLevel::default_style),
+            wrap: false,
+            search: None,
+            search_options: SearchOptions::default(),
+            search_input: None,
+            match_style: Style::new()
+                .fg(Color::Named(NamedColor::Black))
+                .bg(Color::Named(NamedColor::Yellow)),
+            current_match_style: Style::new()
+                .fg(Color::Named(NamedColor::Black))
+                .bg(Color::Named(NamedColor::BrightYellow))
+                .bold(true),
+            prompt_style: Style::new().reverse(true),
         }
     }
 
@@ -86,31 +212,99 @@ impl RichLog {
         self
     }
 
+    /// Keep at most `max` entries, dropping the oldest (at least one).
+    #[must_use]
+    pub fn with_max_lines(mut self, max: usize) -> Self {
+        self.set_max_lines(Some(max));
+        self
+    }
+
+    /// Keep at most `max` entries, or any number for `None`.
+    pub fn set_max_lines(&mut self, max: Option<usize>) {
+        self.max_lines = max.map(|max| max.max(1));
+        self.evict();
+    }
+
+    /// Most entries kept, if limited.
+    pub fn max_lines(&self) -> Option<usize> {
+        self.max_lines
+    }
+
+    /// Set the label style for entries at `level`.
+    #[must_use]
+    pub fn with_level_style(mut self, level: LogLevel, style: Style) -> Self {
+        self.level_styles[level as usize] = style;
+        self
+    }
+
+    /// Set the style for search matches.
+    #[must_use]
+    pub fn with_match_style(mut self, style: Style) -> Self {
+        self.match_style = style;
+        self
+    }
+
+    /// Set the style for the current search match.
+    #[must_use]
+    pub fn with_current_match_style(mut self, style: Style) -> Self {
+        self.current_match_style = style;
+        self
+    }
+
+    /// Wrap long entries onto further rows instead of truncating them.
+    #[must_use]
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Set whether long entries wrap.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    /// Whether long entries wrap.
+    pub fn wraps(&self) -> bool {
+        self.wrap
+    }
+
     /// Add a log entry (single line of segments).
     pub fn push(&mut self, entry: Vec<Segment>) {
-        self.entries.push(entry);
-        if self.auto_scroll {
-            // Will be applied on next render based on visible height;
-            // for now, set offset to show last entry.
-            // We use saturating_sub to handle the case where we don't know
-            // the visible height yet - scroll_to_bottom() can be called
-            // explicitly or it adjusts in render.
-            self.scroll_offset = self.entries.len().saturating_sub(1);
-        }
+        self.push_entry(None, entry);
     }
 
     /// Add a plain text entry (convenience method).
     pub fn push_text(&mut self, text: &str) {
-        self.entries.push(vec![Segment::new(text)]);
-        if self.auto_scroll {
-            self.scroll_offset = self.entries.len().saturating_sub(1);
+        self.push_entry(None, vec![Segment::new(text)]);
+    }
+
+    /// Add an entry tagged with a level.
+    pub fn push_with_level(&mut self, level: LogLevel, entry: Vec<Segment>) {
+        self.push_entry(Some(level), entry);
+    }
+
+    /// Add a plain text entry tagged with a level.
+    pub fn push_text_with_level(&mut self, level: LogLevel, text: &str) {
+        self.push_entry(Some(level), vec![Segment::new(text)]);
+    }
+
+    /// Add text containing ANSI escape sequences, such as subprocess
+    /// output, as one entry per line with its colours kept.
+    pub fn push_ansi(&mut self, text: &str) {
+        for line in parse_ansi(text) {
+            self.push_entry(None, line);
         }
     }
 
     /// Clear all entries and reset scroll.
     pub fn clear(&mut self) {
+        self.first_id += self.entries.len() as u64;
         self.entries.clear();
+        self.visible = 0;
         self.scroll_offset = 0;
+        if let Some(search) = &mut self.search {
+            search.evict(self.first_id);
+        }
     }
 
     /// Get total entry count.
@@ -123,10 +317,33 @@ impl RichLog {
         self.entries.is_empty()
     }
 
+    /// Number of entries shown by the level filter.
+    pub fn visible_len(&self) -> usize {
+        self.visible
+    }
+
+    /// Show only entries at `level` or above, or every entry for `None`.
+    /// Entries without a level are always shown.
+    pub fn set_min_level(&mut self, level: Option<LogLevel>) {
+        self.min_level = level;
+        self.visible = self.entries.iter().filter(|e| self.shows(e)).count();
+        self.rescan();
+        if self.auto_scroll {
+            self.scroll_to_bottom();
+        } else {
+            self.scroll_offset = self.scroll_offset.min(self.visible.saturating_sub(1));
+        }
+    }
+
+    /// The level filter.
+    pub fn min_level(&self) -> Option<LogLevel> {
+        self.min_level
+    }
+
     /// Scroll to the bottom (last entry visible).
     pub fn scroll_to_bottom(&mut self) {
-        if !self.entries.is_empty() {
-            self.scroll_offset = self.entries.len().saturating_sub(1);
+        if self.visible > 0 {
+            self.scroll_offset = self.visible - 1;
         }
     }
 
@@ -140,7 +357,7 @@ impl RichLog {
         self.scroll_offset
     }
 
-    /// Scroll up by `lines`, clamping at 0.
+    /// Scroll up by `lines`, clamping at 0. This pauses following.
     pub fn scroll_up_by(&mut self, lines: usize) {
         self.scroll_offset = self.scroll_offset.saturating_sub(lines);
         self.auto_scroll = false;
@@ -148,13 +365,355 @@ impl RichLog {
 
     /// Scroll down by `lines`, clamping at the last entry.
     pub fn scroll_down_by(&mut self, lines: usize) {
-        if self.entries.is_empty() {
+        if self.visible == 0 {
             return;
         }
-        let max = self.entries.len().saturating_sub(1);
+        let max = self.visible - 1;
         self.scroll_offset = (self.scroll_offset + lines).min(max);
         self.auto_scroll = false;
     }
+
+    /// Follow new entries, scrolling to the bottom, or stop following.
+    pub fn set_follow(&mut self, follow: bool) {
+        self.auto_scroll = follow;
+        if follow {
+            self.scroll_to_bottom();
+        }
+    }
+
+    /// Whether the log scrolls to new entries as they arrive.
+    pub fn is_following(&self) -> bool {
+        self.auto_scroll
+    }
+
+    /// Search the shown entries for `query`, making the newest match
+    /// current when following and otherwise the first match from the top
+    /// of the view, and scroll to it. Returns the number of matches; an
+    /// invalid regex is an error.
+    pub fn find(&mut self, query: &str, options: SearchOptions) -> Result<usize> {
+        self.search = Some(LogSearch::new(query, options)?);
+        self.search_options = options;
+        self.rescan();
+        let top = self.entry_id_at(self.scroll_offset);
+        let follow = self.auto_scroll;
+        let Some(search) = &mut self.search else {
+            return Ok(0);
+        };
+        let count = search.matches.len();
+        search.current = match top {
+            _ if count == 0 => None,
+            Some(top) if !follow => Some(
+                search
+                    .matches
+                    .iter()
+                    .position(|m| m.entry >= top)
+                    .unwrap_or(0),
+            ),
+            _ => Some(count - 1),
+        };
+        self.scroll_to_current_match();
+        Ok(count)
+    }
+
+    /// Move to the next match, wrapping around. Returns `false` if there
+    /// are no matches.
+    pub fn find_next(&mut self) -> bool {
+        self.step_match(true)
+    }
+
+    /// Move to the previous match, wrapping around. Returns `false` if
+    /// there are no matches.
+    pub fn find_previous(&mut self) -> bool {
+        self.step_match(false)
+    }
+
+    /// End the search and remove its highlighting.
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.search_input = None;
+    }
+
+    /// Number of matches of the active search.
+    pub fn match_count(&self) -> usize {
+        self.search.as_ref().map_or(0, |s| s.matches.len())
+    }
+
+    /// Index of the current match among [`match_count`](Self::match_count).
+    pub fn current_match(&self) -> Option<usize> {
+        self.search.as_ref().and_then(|s| s.current)
+    }
+
+    /// The retained entries as plain text, one per line, with level
+    /// labels. The level filter does not apply.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            if let Some(level) = entry.level {
+                out.push_str(&format!("{:<5} ", level.label()));
+            }
+            out.push_str(&entry.text());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Write [`to_text`](Self::to_text) to a file.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    /// Append an entry, evicting old ones past the limit.
+    fn push_entry(&mut self, level: Option<LogLevel>, segments: Vec<Segment>) {
+        let entry = LogEntry { level, segments };
+        if self.shows(&entry) {
+            self.visible += 1;
+            if let Some(search) = &mut self.search {
+                let id = self.first_id + self.entries.len() as u64;
+                search.scan(id, &entry.text());
+            }
+        }
+        self.entries.push_back(entry);
+        self.evict();
+        if self.auto_scroll {
+            self.scroll_to_bottom();
+        }
+    }
+
+    /// Drop the oldest entries past `max_lines`. A paused view keeps
+    /// showing the same entries.
+    fn evict(&mut self) {
+        let Some(max) = self.max_lines else {
+            return;
+        };
+        while self.entries.len() > max {
+            if let Some(entry) = self.entries.pop_front() {
+                self.first_id += 1;
+                if self.shows(&entry) {
+                    self.visible -= 1;
+                    if !self.auto_scroll {
+                        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+                    }
+                }
+            }
+        }
+        if let Some(search) = &mut self.search {
+            search.evict(self.first_id);
+        }
+    }
+
+    /// Whether the level filter shows `entry`.
+    fn shows(&self, entry: &LogEntry) -> bool {
+        match (entry.level, self.min_level) {
+            (Some(level), Some(min)) => level >= min,
+            _ => true,
+        }
+    }
+
+    /// The shown entries with their ids.
+    fn visible_entries(&self) -> impl Iterator<Item = (u64, &LogEntry)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| self.shows(e))
+            .map(|(i, e)| (self.first_id + i as u64, e))
+    }
+
+    /// Id of the shown entry at `index`.
+    fn entry_id_at(&self, index: usize) -> Option<u64> {
+        self.visible_entries().nth(index).map(|(id, _)| id)
+    }
+
+    /// Rebuild the matches of the active search over the shown entries,
+    /// keeping the current match on or after the entry it was in.
+    fn rescan(&mut self) {
+        let Some(mut search) = self.search.take() else {
+            return;
+        };
+        let current = search.current_entry();
+        search.matches.clear();
+        for (id, entry) in self.visible_entries() {
+            search.scan(id, &entry.text());
+        }
+        search.current = current.map(|id| {
+            search
+                .matches
+                .iter()
+                .position(|m| m.entry >= id)
+                .unwrap_or(0)
+        });
+        if search.matches.is_empty() {
+            search.current = None;
+        }
+        self.search = Some(search);
+    }
+
+    /// Move the current match forwards or backwards and scroll to it.
+    fn step_match(&mut self, forward: bool) -> bool {
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+        let count = search.matches.len();
+        if count == 0 {
+            return false;
+        }
+        search.current = Some(match (search.current, forward) {
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+            (None, true) => 0,
+            (None, false) => count - 1,
+        });
+        self.scroll_to_current_match()
+    }
+
+    /// Scroll the entry holding the current match to the top of the view,
+    /// pausing following.
+    fn scroll_to_current_match(&mut self) -> bool {
+        let Some(id) = self.search.as_ref().and_then(LogSearch::current_entry) else {
+            return false;
+        };
+        let index = usize::try_from(id - self.first_id).unwrap_or(usize::MAX);
+        self.scroll_offset = self
+            .entries
+            .iter()
+            .take(index)
+            .filter(|e| self.shows(e))
+            .count();
+        self.auto_scroll = false;
+        true
+    }
+
+    /// Re-run the search typed after `/`.
+    fn update_incremental_search(&mut self) {
+        let query = self.search_input.clone().unwrap_or_default();
+        if self.find(&query, self.search_options).is_err() {
+            // A regex is often invalid while it is being typed.
+            self.search = None;
+        }
+    }
+
+    /// Cycle the level filter through all, debug, info, warn and error.
+    fn cycle_min_level(&mut self) {
+        let next = match self.min_level {
+            None | Some(LogLevel::Trace) => Some(LogLevel::Debug),
+            Some(LogLevel::Debug) => Some(LogLevel::Info),
+            Some(LogLevel::Info) => Some(LogLevel::Warn),
+            Some(LogLevel::Warn) => Some(LogLevel::Error),
+            Some(LogLevel::Error) => None,
+        };
+        self.set_min_level(next);
+    }
+
+    /// The character ranges of `entry`'s text shown on each of its rows.
+    fn entry_rows(&self, entry: &LogEntry, text: &str, width: usize) -> Vec<Range<usize>> {
+        if !self.wrap {
+            return std::iter::once(0..text.chars().count()).collect();
+        }
+        let indent = if entry.level.is_some() {
+            LABEL_WIDTH
+        } else {
+            0
+        };
+        wrap_line(text, width.saturating_sub(indent).max(1))
+            .into_iter()
+            .map(|(row, start)| start..start + row.chars().count())
+            .collect()
+    }
+
+    /// Draw up to `max_rows` rows of an entry from row `y`, leaving out its
+    /// first `skip` rows, and return the number of rows drawn.
+    #[allow(clippy::too_many_arguments)]
+    fn render_entry(
+        &self,
+        id: u64,
+        entry: &LogEntry,
+        inner: Rect,
+        y: u16,
+        skip: usize,
+        max_rows: usize,
+        buf: &mut ScreenBuffer,
+    ) -> usize {
+        let width = inner.size.width as usize;
+        let text = entry.text();
+        let matches: Vec<(Range<usize>, bool)> = self
+            .search
+            .as_ref()
+            .map(|s| s.in_entry(id).collect())
+            .unwrap_or_default();
+
+        let mut chars = Vec::new();
+        let mut byte = 0;
+        for segment in &entry.segments {
+            for ch in segment.text.chars() {
+                let style = match matches.iter().find(|(range, _)| range.contains(&byte)) {
+                    Some((_, true)) => &self.current_match_style,
+                    Some((_, false)) => &self.match_style,
+                    None => &segment.style,
+                };
+                chars.push((ch, style));
+                byte += ch.len_utf8();
+            }
+        }
+
+        let rows = self.entry_rows(entry, &text, width);
+        let shown = rows.len().saturating_sub(skip).min(max_rows);
+        for (row, range) in rows.iter().enumerate().skip(skip).take(shown) {
+            let y = y + (row - skip) as u16;
+            let mut col = 0;
+            if let Some(level) = entry.level {
+                if row == 0 {
+                    let label = format!("{:<5} ", level.label());
+                    let style = &self.level_styles[level as usize];
+                    for ch in label.chars().take(width) {
+                        let x = inner.position.x + col as u16;
+                        buf.set(x, y, Cell::new(ch.to_string(), style.clone()));
+                        col += 1;
+                    }
+                }
+                col = LABEL_WIDTH.min(width);
+            }
+            for &(ch, style) in chars.get(range.clone()).unwrap_or_default() {
+                let char_w = ch.width().unwrap_or(0);
+                if char_w == 0 {
+                    continue;
+                }
+                if col + char_w > width {
+                    break;
+                }
+                let x = inner.position.x + col as u16;
+                buf.set(x, y, Cell::new(ch.to_string(), style.clone()));
+                col += char_w;
+            }
+        }
+        shown
+    }
+
+    /// Draw the search prompt on row `y`.
+    fn render_prompt(&self, query: &str, inner: Rect, y: u16, buf: &mut ScreenBuffer) {
+        let status = match &self.search {
+            Some(search) if !search.matches.is_empty() => format!(
+                "  [{}/{}]",
+                search.current.map_or(0, |i| i + 1),
+                search.matches.len()
+            ),
+            _ if query.is_empty() => String::new(),
+            _ => "  [no matches]".to_string(),
+        };
+        let prompt = format!("/{query}{status}");
+        let width = inner.size.width as usize;
+        let mut chars = prompt.chars().filter(|ch| ch.width().unwrap_or(0) > 0);
+        let mut col = 0;
+        while col < width {
+            let ch = chars.next().unwrap_or(' ');
+            let char_w = ch.width().unwrap_or(1);
+            if col + char_w > width {
+                break;
+            }
+            let x = inner.position.x + col as u16;
+            buf.set(x, y, Cell::new(ch.to_string(), self.prompt_style.clone()));
+            col += char_w;
+        }
+    }
 }
 
 impl Default for RichLog {
@@ -177,46 +736,79 @@ impl Widget for RichLog {
             return;
         }
 
-        let height = inner.size.height as usize;
         let width = inner.size.width as usize;
+        let mut height = inner.size.height as usize;
+        if let Some(query) = &self.search_input {
+            height -= 1;
+            let y = inner.position.y + height as u16;
+            self.render_prompt(query, inner, y, buf);
+        }
 
-        // Clamp scroll offset (use a local copy since render takes &self)
-        let max_offset = self.entries.len().saturating_sub(height.max(1));
-        let scroll = self.scroll_offset.min(max_offset);
-
-        let visible_end = (scroll + height).min(self.entries.len());
+        let visible: Vec<(u64, &LogEntry)> = self.visible_entries().collect();
+
+        // Clamp scroll offset so the last page stays full (use a local
+        // copy since render takes &self). When wrapping, the top entry of
+        // the last page may show only its final rows.
+        let (max_offset, max_skip) = if self.wrap {
+            let mut start = visible.len();
+            let mut rows = 0;
+            let mut skip = 0;
+            while rows < height
+                && let Some(&(_, entry)) = start.checked_sub(1).and_then(|i| visible.get(i))
+            {
+                let n = self.entry_rows(entry, &entry.text(), width).len();
+                skip = (rows + n).saturating_sub(height);
+                rows += n;
+                start -= 1;
+            }
+            (start, skip)
+        } else {
+            (visible.len().saturating_sub(height.max(1)), 0)
+        };
+        let (scroll, mut skip) = if self.scroll_offset >= max_offset {
+            (max_offset, max_skip)
+        } else {
+            (self.scroll_offset, 0)
+        };
 
-        for (row, entry_idx) in (scroll..visible_end).enumerate() {
-            let y = inner.position.y + row as u16;
-            if let Some(entry) = self.entries.get(entry_idx) {
-                let mut col: u16 = 0;
-                for segment in entry {
-                    if col as usize >= width {
-                        break;
-                    }
-                    let remaining = width.saturating_sub(col as usize);
-                    let truncated = truncate_to_display_width(&segment.text, remaining);
-                    for ch in truncated.chars() {
-                        let char_w = UnicodeWidthStr::width(ch.encode_utf8(&mut [0; 4]) as &str);
-                        if col as usize + char_w > width {
-                            break;
-                        }
-                        let x = inner.position.x + col;
-                        buf.set(x, y, Cell::new(ch.to_string(), segment.style.clone()));
-                        col += char_w as u16;
-                    }
-                }
+        let mut row = 0;
+        for &(id, entry) in visible.iter().skip(scroll) {
+            if row >= height {
+                break;
             }
+            let y = inner.position.y + row as u16;
+            row += self.render_entry(id, entry, inner, y, skip, height - row, buf);
+            skip = 0;
         }
     }
 }
 
 impl InteractiveWidget for RichLog {
     fn handle_event(&mut self, event: &Event) -> EventResult {
-        let Event::Key(KeyEvent { code, .. }) = event else {
+        let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event
+        else {
             return EventResult::Ignored;
         };
 
+        if let Some(query) = &mut self.search_input {
+            match code {
+                KeyCode::Char(c) if !modifiers.contains(Modifiers::CTRL) => {
+                    query.push(*c);
+                    self.update_incremental_search();
+                }
+                KeyCode::Backspace => {
+                    query.pop();
+                    self.update_incremental_search();
+                }
+                KeyCode::Enter => self.search_input = None,
+                KeyCode::Escape => self.clear_search(),
+                _ => return EventResult::Ignored,
+            }
+            return EventResult::Consumed;
+        }
+
         match code {
             KeyCode::Up => {
                 if self.scroll_offset > 0 {
@@ -226,9 +818,7 @@ impl InteractiveWidget for RichLog {
                 EventResult::Consumed
             }
             KeyCode::Down => {
-                if !self.entries.is_empty()
-                    && self.scroll_offset < self.entries.len().saturating_sub(1)
-                {
+                if self.visible > 0 && self.scroll_offset < self.visible - 1 {
                     self.scroll_offset += 1;
                     self.auto_scroll = false;
                 }
@@ -236,18 +826,11 @@ impl InteractiveWidget for RichLog {
             }
             KeyCode::PageUp => {
                 // Scroll by a page (assume ~20 lines if we don't know height)
-                let page = 20;
-                self.scroll_offset = self.scroll_offset.saturating_sub(page);
-                self.auto_scroll = false;
+                self.scroll_up_by(PAGE);
                 EventResult::Consumed
             }
             KeyCode::PageDown => {
-                let page = 20;
-                if !self.entries.is_empty() {
-                    self.scroll_offset =
-                        (self.scroll_offset + page).min(self.entries.len().saturating_sub(1));
-                    self.auto_scroll = false;
-                }
+                self.scroll_down_by(PAGE);
                 EventResult::Consumed
             }
             KeyCode::Home => {
@@ -256,8 +839,37 @@ impl InteractiveWidget for RichLog {
                 EventResult::Consumed
             }
             KeyCode::End => {
-                self.scroll_to_bottom();
                 // Scrolling to end re-enables auto_scroll behavior
+                self.set_follow(true);
+                EventResult::Consumed
+            }
+            KeyCode::Char('f') => {
+                self.set_follow(!self.auto_scroll);
+                EventResult::Consumed
+            }
+            KeyCode::Char('w') => {
+                self.wrap = !self.wrap;
+                EventResult::Consumed
+            }
+            KeyCode::Char('l') => {
+                self.cycle_min_level();
+                EventResult::Consumed
+            }
+            KeyCode::Char('/') => {
+                self.search = None;
+                self.search_input = Some(String::new());
+                EventResult::Consumed
+            }
+            KeyCode::Char('n') => {
+                self.find_next();
+                EventResult::Consumed
+            }
+            KeyCode::Char('N') => {
+                self.find_previous();
+                EventResult::Consumed
+            }
+            KeyCode::Escape if self.search.is_some() => {
+                self.clear_search();
                 EventResult::Consumed
             }
             _ => EventResult::Ignored,
@@ -522,4 +1134,241 @@ mod tests {
         assert!(!log.auto_scroll);
         assert!(matches!(log.border, BorderStyle::Rounded));
     }
+
+    fn row_text(buf: &ScreenBuffer, y: u16, width: u16) -> String {
+        (0..width)
+            .filter_map(|x| buf.get(x, y).map(|c| c.grapheme.clone()))
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::plain(code))
+    }
+
+    #[test]
+    fn max_lines_drops_oldest_entries() {
+        let mut log = RichLog::new().with_max_lines(3);
+        for i in 0..5 {
+            log.push_text(&format!("line {i}"));
+        }
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.to_text(), "line 2\nline 3\nline 4\n");
+        assert_eq!(log.scroll_offset(), 2);
+
+        log.set_max_lines(Some(1));
+        assert_eq!(log.to_text(), "line 4\n");
+    }
+
+    #[test]
+    fn eviction_keeps_a_paused_view_in_place() {
+        let mut log = RichLog::new().with_max_lines(10);
+        for i in 0..10 {
+            log.push_text(&format!("line {i}"));
+        }
+        log.scroll_up_by(4);
+        assert_eq!(log.scroll_offset(), 5);
+        log.push_text("line 10");
+        // "line 5" is still the top entry, now one place earlier.
+        assert_eq!(log.scroll_offset(), 4);
+    }
+
+    #[test]
+    fn level_filter_hides_lower_levels() {
+        let mut log = RichLog::new().with_auto_scroll(false);
+        log.push_text_with_level(LogLevel::Debug, "details");
+        log.push_text_with_level(LogLevel::Warn, "careful");
+        log.push_text("untagged");
+        log.push_text_with_level(LogLevel::Error, "broken");
+
+        log.set_min_level(Some(LogLevel::Warn));
+        assert_eq!(log.visible_len(), 3);
+        let mut buf = ScreenBuffer::new(Size::new(20, 4));
+        log.render(Rect::new(0, 0, 20, 4), &mut buf);
+        assert_eq!(row_text(&buf, 0, 20), "WARN  careful");
+        assert_eq!(row_text(&buf, 1, 20), "untagged");
+        assert_eq!(row_text(&buf, 2, 20), "ERROR broken");
+        assert!(buf.get(0, 2).is_some_and(|c| c.style.bold));
+
+        // `l` cycles all → debug → info → warn → error → all.
+        log.set_min_level(None);
+        for _ in 0..4 {
+            log.handle_event(&key(KeyCode::Char('l')));
+        }
+        assert_eq!(log.min_level(), Some(LogLevel::Error));
+        assert_eq!(log.visible_len(), 2);
+        log.handle_event(&key(KeyCode::Char('l')));
+        assert_eq!(log.min_level(), None);
+    }
+
+    #[test]
+    fn follow_pauses_on_scroll_up_and_resumes() {
+        let mut log = RichLog::new();
+        for i in 0..10 {
+            log.push_text(&format!("line {i}"));
+        }
+        assert!(log.is_following());
+        log.handle_event(&key(KeyCode::PageUp));
+        assert!(!log.is_following());
+        log.push_text("more");
+        assert_eq!(log.scroll_offset(), 0);
+
+        log.handle_event(&key(KeyCode::End));
+        assert!(log.is_following());
+        assert_eq!(log.scroll_offset(), 10);
+
+        log.handle_event(&key(KeyCode::Char('f')));
+        assert!(!log.is_following());
+        log.handle_event(&key(KeyCode::Char('f')));
+        assert!(log.is_following());
+    }
+
+    #[test]
+    fn wrapping_indents_past_the_level_label() {
+        let mut log = RichLog::new().with_wrap(true);
+        log.push_text_with_level(LogLevel::Info, "one two three four");
+        log.push_text("last");
+
+        let mut buf = ScreenBuffer::new(Size::new(14, 4));
+        log.render(Rect::new(0, 0, 14, 4), &mut buf);
+        assert_eq!(row_text(&buf, 0, 14), "INFO  one two");
+        assert_eq!(row_text(&buf, 1, 14), "      three");
+        assert_eq!(row_text(&buf, 2, 14), "      four");
+        assert_eq!(row_text(&buf, 3, 14), "last");
+
+        // Following keeps the last entry on screen when rows run out.
+        let mut small = ScreenBuffer::new(Size::new(14, 2));
+        log.render(Rect::new(0, 0, 14, 2), &mut small);
+        assert_eq!(row_text(&small, 0, 14), "      four");
+        assert_eq!(row_text(&small, 1, 14), "last");
+
+        log.handle_event(&key(KeyCode::Char('w')));
+        assert!(!log.wraps());
+    }
+
+    #[test]
+    fn search_highlights_and_navigates_matches() {
+        let mut log = RichLog::new();
+        log.push_text("error: disk");
+        log.push_text("ok");
+        log.push_text("ERROR again, error");
+
+        assert_eq!(log.find("error", SearchOptions::new()).ok(), Some(3));
+        // Following makes the newest match current.
+        assert_eq!(log.current_match(), Some(2));
+        assert!(!log.is_following());
+        assert_eq!(log.scroll_offset(), 2);
+
+        assert!(log.find_next());
+        assert_eq!(log.current_match(), Some(0));
+        assert_eq!(log.scroll_offset(), 0);
+        assert!(log.find_previous());
+        assert_eq!(log.current_match(), Some(2));
+
+        let mut buf = ScreenBuffer::new(Size::new(20, 3));
+        log.scroll_to_top();
+        log.render(Rect::new(0, 0, 20, 3), &mut buf);
+        let bg = |x, y| buf.get(x, y).and_then(|c| c.style.bg.clone());
+        assert_eq!(bg(0, 0), Some(Color::Named(NamedColor::Yellow)));
+        assert_eq!(bg(5, 0), None);
+        assert_eq!(bg(0, 2), Some(Color::Named(NamedColor::Yellow)));
+        assert_eq!(bg(13, 2), Some(Color::Named(NamedColor::BrightYellow)));
+
+        // New entries are searched as they arrive.
+        log.push_text("late error");
+        assert_eq!(log.match_count(), 4);
+
+        log.clear_search();
+        assert_eq!(log.match_count(), 0);
+        assert!(!log.find_next());
+        assert!(log.find("(", SearchOptions::new().regex(true)).is_err());
+    }
+
+    #[test]
+    fn incremental_search_prompt() {
+        let mut log = RichLog::new();
+        log.push_text("alpha");
+        log.push_text("beta");
+        log.push_text("alphabet");
+
+        log.handle_event(&key(KeyCode::Char('/')));
+        for c in "alp".chars() {
+            log.handle_event(&key(KeyCode::Char(c)));
+        }
+        assert_eq!(log.match_count(), 2);
+        let mut buf = ScreenBuffer::new(Size::new(20, 4));
+        log.render(Rect::new(0, 0, 20, 4), &mut buf);
+        assert_eq!(row_text(&buf, 3, 20), "/alp  [2/2]");
+
+        log.handle_event(&key(KeyCode::Char('x')));
+        assert_eq!(log.match_count(), 0);
+        log.handle_event(&key(KeyCode::Backspace));
+        log.handle_event(&key(KeyCode::Enter));
+        assert_eq!(log.match_count(), 2);
+
+        // With the prompt closed, n and N move between matches.
+        log.handle_event(&key(KeyCode::Char('N')));
+        assert_eq!(log.current_match(), Some(0));
+        log.handle_event(&key(KeyCode::Char('n')));
+        assert_eq!(log.current_match(), Some(1));
+        assert_eq!(
+            log.handle_event(&key(KeyCode::Escape)),
+            EventResult::Consumed
+        );
+        assert_eq!(log.match_count(), 0);
+        assert_eq!(
+            log.handle_event(&key(KeyCode::Escape)),
+            EventResult::Ignored
+        );
+    }
+
+    #[test]
+    fn search_skips_filtered_and_evicted_entries() {
+        let mut log = RichLog::new().with_max_lines(3);
+        log.push_text_with_level(LogLevel::Debug, "hit");
+        log.push_text_with_level(LogLevel::Info, "hit");
+        assert_eq!(log.find("hit", SearchOptions::new()).ok(), Some(2));
+        log.set_min_level(Some(LogLevel::Info));
+        assert_eq!(log.match_count(), 1);
+        log.set_min_level(None);
+        log.push_text("miss");
+        log.push_text("miss");
+        assert_eq!(log.match_count(), 1);
+    }
+
+    #[test]
+    fn push_ansi_keeps_colours() {
+        let mut log = RichLog::new();
+        log.push_ansi("\x1b[31mred\x1b[0m plain\nnext\n");
+        assert_eq!(log.len(), 2);
+
+        let mut buf = ScreenBuffer::new(Size::new(10, 2));
+        log.render(Rect::new(0, 0, 10, 2), &mut buf);
+        assert_eq!(row_text(&buf, 0, 10), "red plain");
+        assert_eq!(
+            buf.get(0, 0).and_then(|c| c.style.fg.clone()),
+            Some(Color::Named(NamedColor::Red))
+        );
+        assert_eq!(buf.get(4, 0).and_then(|c| c.style.fg.clone()), None);
+    }
+
+    #[test]
+    fn save_to_file_writes_labelled_text() {
+        let mut log = RichLog::new();
+        log.push_text_with_level(LogLevel::Warn, "low disk");
+        log.push(vec![make_segment("a"), make_segment("b")]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        log.save_to_file(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "WARN  low disk\nab\n"
+        );
+        assert!(
+            log.save_to_file(dir.path().join("missing/log.txt"))
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,124 @@
+//! Searching the entries of a [`RichLog`](super::RichLog).
+
+use std::ops::Range;
+
+use regex::Regex;
+
+use crate::error::Result;
+use crate::widget::SearchOptions;
+
+/// A match within one log entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct LogMatch {
+    /// Id of the entry the match is in.
+    pub entry: u64,
+    /// Byte range into the entry's text.
+    pub range: Range<usize>,
+}
+
+/// A compiled query and the matches it found, in entry order.
+#[derive(Clone, Debug)]
+pub(super) struct LogSearch {
+    regex: Option<Regex>,
+    pub matches: Vec<LogMatch>,
+    /// Index into `matches` of the current match.
+    pub current: Option<usize>,
+}
+
+impl LogSearch {
+    /// Compile a query; an invalid regex is a widget error.
+    pub fn new(query: &str, options: SearchOptions) -> Result<Self> {
+        Ok(Self {
+            regex: options.compile(query)?,
+            matches: Vec::new(),
+            current: None,
+        })
+    }
+
+    /// Record the matches in a newly added entry. Entries must be scanned
+    /// in id order.
+    pub fn scan(&mut self, entry: u64, text: &str) {
+        let Some(regex) = &self.regex else {
+            return;
+        };
+        self.matches.extend(
+            regex
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| LogMatch {
+                    entry,
+                    range: m.range(),
+                }),
+        );
+    }
+
+    /// Forget the matches in entries evicted from the log, those with ids
+    /// below `first_id`.
+    pub fn evict(&mut self, first_id: u64) {
+        let gone = self.matches.partition_point(|m| m.entry < first_id);
+        if gone == 0 {
+            return;
+        }
+        self.matches.drain(..gone);
+        self.current = match self.current {
+            _ if self.matches.is_empty() => None,
+            Some(i) => Some(i.saturating_sub(gone)),
+            None => None,
+        };
+    }
+
+    /// The matches in entry `id`, each with whether it is the current one.
+    pub fn in_entry(&self, id: u64) -> impl Iterator<Item = (Range<usize>, bool)> + '_ {
+        let first = self.matches.partition_point(|m| m.entry < id);
+        self.matches[first..]
+            .iter()
+            .take_while(move |m| m.entry == id)
+            .enumerate()
+            .map(move |(i, m)| (m.range.clone(), self.current == Some(first + i)))
+    }
+
+    /// The entry id of the current match.
+    pub fn current_entry(&self) -> Option<u64> {
+        self.current
+            .and_then(|i| self.matches.get(i))
+            .map(|m| m.entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(query: &str) -> LogSearch {
+        match LogSearch::new(query, SearchOptions::new()) {
+            Ok(search) => search,
+            Err(e) => unreachable!("query should compile: {e}"),
+        }
+    }
+
+    #[test]
+    fn scans_entries_and_evicts_old_matches() {
+        let mut s = search("ab");
+        s.scan(0, "ab AB");
+        s.scan(1, "none");
+        s.scan(2, "xab");
+        assert_eq!(s.matches.len(), 3);
+        s.current = Some(2);
+        let ranges: Vec<_> = s.in_entry(0).collect();
+        assert_eq!(ranges, vec![(0..2, false), (3..5, false)]);
+        assert_eq!(s.in_entry(2).collect::<Vec<_>>(), vec![(1..3, true)]);
+
+        s.evict(1);
+        assert_eq!(s.matches.len(), 1);
+        assert_eq!(s.current, Some(0));
+        assert_eq!(s.current_entry(), Some(2));
+        s.evict(3);
+        assert_eq!(s.current, None);
+    }
+
+    #[test]
+    fn invalid_regex_is_an_error() {
+        assert!(LogSearch::new("(", SearchOptions::new().regex(true)).is_err());
+        assert!(search("").matches.is_empty());
+    }
+}
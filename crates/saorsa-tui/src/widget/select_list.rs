@@ -304,7 +304,7 @@ impl<T> SelectList<T> {
                 })
                 .collect();
             // Sort by score descending (best match first)
-            scored.sort_by(|a, b| b.1.cmp(&a.1));
+            scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
             self.filtered_indices = scored.into_iter().map(|(idx, _)| idx).collect();
         } else {
             // No search function: show all items
@@ -593,14 +593,8 @@ mod tests {
     fn keyboard_navigation_up_down() {
         let mut list = make_string_list(vec!["A", "B", "C"]);
 
-        let down = Event::Key(KeyEvent {
-            code: KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let up = Event::Key(KeyEvent {
-            code: KeyCode::Up,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = Event::Key(KeyEvent::new(KeyCode::Down, crate::event::Modifiers::NONE));
+        let up = Event::Key(KeyEvent::new(KeyCode::Up, crate::event::Modifiers::NONE));
 
         assert_eq!(list.handle_event(&down), EventResult::Consumed);
         assert_eq!(list.selected(), 1);
@@ -616,14 +610,8 @@ mod tests {
     fn keyboard_home_end() {
         let mut list = make_string_list(vec!["A", "B", "C", "D", "E"]);
 
-        let end = Event::Key(KeyEvent {
-            code: KeyCode::End,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let home = Event::Key(KeyEvent {
-            code: KeyCode::Home,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let end = Event::Key(KeyEvent::new(KeyCode::End, crate::event::Modifiers::NONE));
+        let home = Event::Key(KeyEvent::new(KeyCode::Home, crate::event::Modifiers::NONE));
 
         assert_eq!(list.handle_event(&end), EventResult::Consumed);
         assert_eq!(list.selected(), 4);
@@ -636,14 +624,14 @@ mod tests {
         let items: Vec<String> = (0..50).map(|i| format!("Item {i}")).collect();
         let mut list = SelectList::new(items).with_render_fn(|s| vec![Segment::new(s)]);
 
-        let page_down = Event::Key(KeyEvent {
-            code: KeyCode::PageDown,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let page_up = Event::Key(KeyEvent {
-            code: KeyCode::PageUp,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let page_down = Event::Key(KeyEvent::new(
+            KeyCode::PageDown,
+            crate::event::Modifiers::NONE,
+        ));
+        let page_up = Event::Key(KeyEvent::new(
+            KeyCode::PageUp,
+            crate::event::Modifiers::NONE,
+        ));
 
         assert_eq!(list.handle_event(&page_down), EventResult::Consumed);
         assert_eq!(list.selected(), 20);
@@ -666,10 +654,7 @@ mod tests {
 
         list.set_selected(1);
 
-        let enter = Event::Key(KeyEvent {
-            code: KeyCode::Enter,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let enter = Event::Key(KeyEvent::new(KeyCode::Enter, crate::event::Modifiers::NONE));
 
         assert_eq!(list.handle_event(&enter), EventResult::Consumed);
         assert_eq!(*selected_value.borrow(), "Beta");
@@ -719,14 +704,8 @@ mod tests {
         let mut list: SelectList<String> =
             SelectList::new(vec![]).with_render_fn(|s| vec![Segment::new(s)]);
 
-        let down = Event::Key(KeyEvent {
-            code: KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let enter = Event::Key(KeyEvent {
-            code: KeyCode::Enter,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = Event::Key(KeyEvent::new(KeyCode::Down, crate::event::Modifiers::NONE));
+        let enter = Event::Key(KeyEvent::new(KeyCode::Enter, crate::event::Modifiers::NONE));
 
         assert_eq!(list.handle_event(&down), EventResult::Consumed);
         assert_eq!(list.handle_event(&enter), EventResult::Consumed);
@@ -737,10 +716,7 @@ mod tests {
     fn unhandled_event_returns_ignored() {
         let mut list = make_string_list(vec!["A"]);
 
-        let tab = Event::Key(KeyEvent {
-            code: KeyCode::Tab,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let tab = Event::Key(KeyEvent::new(KeyCode::Tab, crate::event::Modifiers::NONE));
 
         assert_eq!(list.handle_event(&tab), EventResult::Ignored);
     }
@@ -867,10 +843,7 @@ mod tests {
         list.enable_filter();
         list.set_filter_query("ap");
 
-        let down = Event::Key(KeyEvent {
-            code: KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = Event::Key(KeyEvent::new(KeyCode::Down, crate::event::Modifiers::NONE));
 
         // Move down within filtered items
         list.handle_event(&down);
@@ -900,10 +873,10 @@ mod tests {
         assert!(list.filtered_items().is_empty());
 
         // Backspace via event
-        let backspace = Event::Key(KeyEvent {
-            code: KeyCode::Backspace,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let backspace = Event::Key(KeyEvent::new(
+            KeyCode::Backspace,
+            crate::event::Modifiers::NONE,
+        ));
 
         list.handle_event(&backspace); // remove 'z'
         list.handle_event(&backspace); // remove 'y'
@@ -920,10 +893,10 @@ mod tests {
         list.set_filter_query("ap");
         assert!(list.is_filter_active());
 
-        let esc = Event::Key(KeyEvent {
-            code: KeyCode::Escape,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let esc = Event::Key(KeyEvent::new(
+            KeyCode::Escape,
+            crate::event::Modifiers::NONE,
+        ));
 
         list.handle_event(&esc);
         assert!(!list.is_filter_active());
@@ -988,14 +961,14 @@ mod tests {
         let mut list = make_searchable_list(vec!["Apple", "Banana", "Apricot"]);
         list.enable_filter();
 
-        let char_a = Event::Key(KeyEvent {
-            code: KeyCode::Char('a'),
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let char_p = Event::Key(KeyEvent {
-            code: KeyCode::Char('p'),
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let char_a = Event::Key(KeyEvent::new(
+            KeyCode::Char('a'),
+            crate::event::Modifiers::NONE,
+        ));
+        let char_p = Event::Key(KeyEvent::new(
+            KeyCode::Char('p'),
+            crate::event::Modifiers::NONE,
+        ));
 
         assert_eq!(list.handle_event(&char_a), EventResult::Consumed);
         assert_eq!(list.filter_query(), "a");
@@ -1038,16 +1011,10 @@ mod tests {
         list.set_filter_query("ap");
 
         // Navigate to second filtered item
-        let down = Event::Key(KeyEvent {
-            code: KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = Event::Key(KeyEvent::new(KeyCode::Down, crate::event::Modifiers::NONE));
         list.handle_event(&down);
 
-        let enter = Event::Key(KeyEvent {
-            code: KeyCode::Enter,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let enter = Event::Key(KeyEvent::new(KeyCode::Enter, crate::event::Modifiers::NONE));
         list.handle_event(&enter);
 
         // Should have selected one of the "ap" matching items
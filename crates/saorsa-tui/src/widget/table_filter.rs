@@ -0,0 +1,230 @@
+//! Per-column filter expressions for [`DataTable`](super::DataTable).
+//!
+//! A filter is a small expression matched against one cell:
+//!
+//! | Expression | Matches |
+//! |------------|---------|
+//! | `lon` or `~lon` | text containing `lon`, ignoring case |
+//! | `^lon` | text starting with `lon`, ignoring case |
+//! | `=London`, `!=London` | equal or unequal values |
+//! | `>30`, `>=30`, `<30`, `<=30` | ordered comparisons |
+//! | `!expr` | cells not matching `expr` |
+//! | `a && b`, `a \|\| b` | both or either (`&&` binds tighter) |
+//!
+//! Comparison values are typed like [`TableCell::infer`]: `>30` compares
+//! numbers, `<2024-01-01` compares dates, and anything else compares text
+//! without case. Text cells holding numbers or dates compare as such.
+
+use std::cmp::Ordering;
+
+use crate::error::{Result, SaorsaTuiError};
+
+use super::table_source::TableCell;
+
+/// How a [`FilterExpr::Compare`] relates a cell to its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterOp {
+    /// `=` or `==`.
+    Eq,
+    /// `!=`.
+    Ne,
+    /// `<`.
+    Lt,
+    /// `<=`.
+    Le,
+    /// `>`.
+    Gt,
+    /// `>=`.
+    Ge,
+}
+
+/// A parsed column filter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterExpr {
+    /// Text contains the (lowercase) needle, ignoring case.
+    Contains(String),
+    /// Text starts with the (lowercase) prefix, ignoring case.
+    StartsWith(String),
+    /// Compare the cell with a value.
+    Compare(FilterOp, TableCell),
+    /// Negation.
+    Not(Box<FilterExpr>),
+    /// Every expression matches.
+    All(Vec<FilterExpr>),
+    /// Any expression matches.
+    Any(Vec<FilterExpr>),
+}
+
+impl FilterExpr {
+    /// Parse a filter expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SaorsaTuiError::Widget`] if a term is empty or an operator
+    /// has no value.
+    pub fn parse(input: &str) -> Result<Self> {
+        let any = input
+            .split("||")
+            .map(|alternative| {
+                let all = alternative
+                    .split("&&")
+                    .map(|term| parse_term(input, term))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(flatten(all, Self::All))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(flatten(any, Self::Any))
+    }
+
+    /// Whether `cell` passes the filter.
+    pub fn matches(&self, cell: &TableCell) -> bool {
+        match self {
+            Self::Contains(needle) => cell.text().to_lowercase().contains(needle.as_str()),
+            Self::StartsWith(prefix) => cell.text().to_lowercase().starts_with(prefix.as_str()),
+            Self::Compare(op, value) => {
+                let ord = compare(cell, value);
+                match op {
+                    FilterOp::Eq => ord == Some(Ordering::Equal),
+                    FilterOp::Ne => ord != Some(Ordering::Equal),
+                    FilterOp::Lt => ord == Some(Ordering::Less),
+                    FilterOp::Le => matches!(ord, Some(Ordering::Less | Ordering::Equal)),
+                    FilterOp::Gt => ord == Some(Ordering::Greater),
+                    FilterOp::Ge => matches!(ord, Some(Ordering::Greater | Ordering::Equal)),
+                }
+            }
+            Self::Not(inner) => !inner.matches(cell),
+            Self::All(exprs) => exprs.iter().all(|e| e.matches(cell)),
+            Self::Any(exprs) => exprs.iter().any(|e| e.matches(cell)),
+        }
+    }
+}
+
+/// Unwrap single-expression combinators.
+fn flatten(mut exprs: Vec<FilterExpr>, wrap: fn(Vec<FilterExpr>) -> FilterExpr) -> FilterExpr {
+    if exprs.len() == 1
+        && let Some(only) = exprs.pop()
+    {
+        return only;
+    }
+    wrap(exprs)
+}
+
+fn parse_term(input: &str, term: &str) -> Result<FilterExpr> {
+    let term = term.trim();
+    if term.is_empty() {
+        return Err(SaorsaTuiError::Widget(format!(
+            "filter `{input}` has an empty term"
+        )));
+    }
+    if let Some(rest) = term.strip_prefix('!')
+        && !rest.starts_with('=')
+    {
+        return Ok(FilterExpr::Not(Box::new(parse_term(input, rest)?)));
+    }
+
+    const OPERATORS: [(&str, Option<FilterOp>); 9] = [
+        (">=", Some(FilterOp::Ge)),
+        ("<=", Some(FilterOp::Le)),
+        ("!=", Some(FilterOp::Ne)),
+        ("==", Some(FilterOp::Eq)),
+        (">", Some(FilterOp::Gt)),
+        ("<", Some(FilterOp::Lt)),
+        ("=", Some(FilterOp::Eq)),
+        ("^", None),
+        ("~", None),
+    ];
+    for (symbol, op) in OPERATORS {
+        let Some(value) = term.strip_prefix(symbol) else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(SaorsaTuiError::Widget(format!(
+                "filter `{input}` has no value after `{symbol}`"
+            )));
+        }
+        return Ok(match op {
+            Some(op) => FilterExpr::Compare(op, TableCell::infer(value)),
+            None if symbol == "^" => FilterExpr::StartsWith(value.to_lowercase()),
+            None => FilterExpr::Contains(value.to_lowercase()),
+        });
+    }
+    Ok(FilterExpr::Contains(term.to_lowercase()))
+}
+
+/// Compare a cell with a filter value, typed by the value.
+fn compare(cell: &TableCell, value: &TableCell) -> Option<Ordering> {
+    match value {
+        TableCell::Number(n) => cell.as_number().map(|c| c.total_cmp(n)),
+        TableCell::Date(d) => cell.as_date().map(|c| c.cmp(d)),
+        _ if cell.is_empty() => None,
+        _ => Some(cell.text().to_lowercase().cmp(&value.text().to_lowercase())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::table_source::TableDate;
+
+    fn parse(input: &str) -> FilterExpr {
+        match FilterExpr::parse(input) {
+            Ok(expr) => expr,
+            Err(e) => unreachable!("`{input}` should parse: {e}"),
+        }
+    }
+
+    #[test]
+    fn bare_text_is_case_insensitive_contains() {
+        let expr = parse("LON");
+        assert_eq!(expr, FilterExpr::Contains("lon".into()));
+        assert!(expr.matches(&TableCell::from("London")));
+        assert!(!expr.matches(&TableCell::from("Paris")));
+    }
+
+    #[test]
+    fn numeric_comparisons_parse_text_cells() {
+        let expr = parse(">= 30");
+        assert!(expr.matches(&TableCell::Number(30.0)));
+        assert!(expr.matches(&TableCell::from("35")));
+        assert!(!expr.matches(&TableCell::from("25")));
+        assert!(!expr.matches(&TableCell::from("n/a")));
+    }
+
+    #[test]
+    fn date_comparisons() {
+        let expr = parse("<2024-06-01");
+        let date =
+            |m, d| TableCell::Date(TableDate::new(2024, m, d).unwrap_or_else(|| unreachable!()));
+        assert!(expr.matches(&date(5, 31)));
+        assert!(!expr.matches(&date(6, 1)));
+        assert!(expr.matches(&TableCell::from("2023-12-31")));
+    }
+
+    #[test]
+    fn combinators_and_negation() {
+        let expr = parse(">10 && <20 || =99");
+        assert!(expr.matches(&TableCell::Number(15.0)));
+        assert!(expr.matches(&TableCell::Number(99.0)));
+        assert!(!expr.matches(&TableCell::Number(25.0)));
+
+        let expr = parse("!^a");
+        assert!(expr.matches(&TableCell::from("Bob")));
+        assert!(!expr.matches(&TableCell::from("alice")));
+        assert!(parse("!=bob").matches(&TableCell::from("Alice")));
+    }
+
+    #[test]
+    fn equality_ignores_case() {
+        let expr = parse("=london");
+        assert!(expr.matches(&TableCell::from("London")));
+        assert!(!expr.matches(&TableCell::Empty));
+    }
+
+    #[test]
+    fn invalid_filters_are_errors() {
+        assert!(FilterExpr::parse("").is_err());
+        assert!(FilterExpr::parse(">").is_err());
+        assert!(FilterExpr::parse("a && ").is_err());
+    }
+}
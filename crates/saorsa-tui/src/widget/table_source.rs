@@ -0,0 +1,513 @@
+//! Typed table cells and lazy row sources for [`DataTable`].
+//!
+//! A [`TableSource`] hands rows to the table by index range, so the table
+//! only ever asks for the rows it is about to draw (plus the key columns
+//! when sorting or filtering). In-memory rows implement it directly, and
+//! [`CsvSource`] reads records from disk on demand.
+//!
+//! [`DataTable`]: super::DataTable
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::segment::Segment;
+
+/// A calendar date in a [`TableCell`].
+///
+/// Ordered chronologically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TableDate {
+    /// Year.
+    pub year: i32,
+    /// Month, `1..=12`.
+    pub month: u8,
+    /// Day of the month, starting at 1.
+    pub day: u8,
+}
+
+impl TableDate {
+    /// Create a date, or `None` if the month or day is out of range.
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+
+    /// Parse an ISO 8601 `YYYY-MM-DD` date.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().splitn(3, '-');
+        let year = parts.next()?;
+        let month = parts.next()?;
+        let day = parts.next()?;
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None;
+        }
+        let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if !(digits(year) && digits(month) && digits(day)) {
+            return None;
+        }
+        Self::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+    }
+}
+
+impl fmt::Display for TableDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The value of one table cell.
+///
+/// Cells sort by type first (numbers, then dates, then text, then empty
+/// cells) and by value within a type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TableCell {
+    /// No value.
+    #[default]
+    Empty,
+    /// Plain text, compared as a string.
+    Text(String),
+    /// A number, compared numerically.
+    Number(f64),
+    /// A date, compared chronologically.
+    Date(TableDate),
+    /// Styled text, compared by its text.
+    Styled(Vec<Segment>),
+}
+
+impl TableCell {
+    /// Infer a cell from text: numbers and `YYYY-MM-DD` dates are typed,
+    /// blank text is [`TableCell::Empty`] and everything else is text.
+    pub fn infer(text: &str) -> Self {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return Self::Empty;
+        }
+        if let Some(n) = parse_number(trimmed) {
+            return Self::Number(n);
+        }
+        if let Some(d) = TableDate::parse(trimmed) {
+            return Self::Date(d);
+        }
+        Self::Text(text.to_string())
+    }
+
+    /// The text shown for this cell.
+    pub fn text(&self) -> String {
+        match self {
+            Self::Empty => String::new(),
+            Self::Text(s) => s.clone(),
+            Self::Number(n) => n.to_string(),
+            Self::Date(d) => d.to_string(),
+            Self::Styled(segments) => segments.iter().map(|s| s.text.as_str()).collect(),
+        }
+    }
+
+    /// Whether the cell has no value.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    /// The cell as a number, parsing text cells.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            Self::Text(_) | Self::Styled(_) => parse_number(self.text().trim()),
+            Self::Empty | Self::Date(_) => None,
+        }
+    }
+
+    /// The cell as a date, parsing text cells.
+    pub fn as_date(&self) -> Option<TableDate> {
+        match self {
+            Self::Date(d) => Some(*d),
+            Self::Text(_) | Self::Styled(_) => TableDate::parse(&self.text()),
+            Self::Empty | Self::Number(_) => None,
+        }
+    }
+
+    /// Type-aware total order used for sorting.
+    pub fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Date(a), Self::Date(b)) => a.cmp(b),
+            (Self::Text(a), Self::Text(b)) => a.cmp(b),
+            (a, b) if a.rank() == b.rank() && a.rank() == 2 => a.text().cmp(&b.text()),
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Number(_) => 0,
+            Self::Date(_) => 1,
+            Self::Text(_) | Self::Styled(_) => 2,
+            Self::Empty => 3,
+        }
+    }
+}
+
+/// Parse a finite number; `inf` and `NaN` stay text.
+fn parse_number(text: &str) -> Option<f64> {
+    text.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+impl From<&str> for TableCell {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<String> for TableCell {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<f64> for TableCell {
+    fn from(n: f64) -> Self {
+        Self::Number(n)
+    }
+}
+
+impl From<i64> for TableCell {
+    fn from(n: i64) -> Self {
+        Self::Number(n as f64)
+    }
+}
+
+impl From<TableDate> for TableCell {
+    fn from(date: TableDate) -> Self {
+        Self::Date(date)
+    }
+}
+
+impl From<Vec<Segment>> for TableCell {
+    fn from(segments: Vec<Segment>) -> Self {
+        Self::Styled(segments)
+    }
+}
+
+/// Rows for a [`DataTable`](super::DataTable), fetched by index range.
+///
+/// Implementations backed by files or database cursors should only load
+/// the requested rows. Methods take `&self` because the table fetches rows
+/// while rendering; sources that need to move a cursor can use interior
+/// mutability.
+pub trait TableSource {
+    /// The number of rows.
+    fn row_count(&self) -> usize;
+
+    /// The rows in `range`, clamped to [`Self::row_count`].
+    ///
+    /// Rows may be shorter than the table's column count; missing cells
+    /// are treated as empty.
+    fn rows(&self, range: Range<usize>) -> Vec<Vec<TableCell>>;
+
+    /// A single row.
+    fn row(&self, index: usize) -> Option<Vec<TableCell>> {
+        self.rows(index..index.saturating_add(1)).into_iter().next()
+    }
+}
+
+impl TableSource for Vec<Vec<TableCell>> {
+    fn row_count(&self) -> usize {
+        self.len()
+    }
+
+    fn rows(&self, range: Range<usize>) -> Vec<Vec<TableCell>> {
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+        self[start..end].to_vec()
+    }
+}
+
+/// A lazily read CSV file.
+///
+/// Opening the file indexes where each record starts; records are parsed
+/// only when the table asks for them. Fields are typed with
+/// [`TableCell::infer`]. Quoted fields may contain the delimiter, doubled
+/// quotes and newlines.
+pub struct CsvSource {
+    reader: RefCell<BufReader<File>>,
+    headers: Vec<String>,
+    /// Byte offset of each record, plus the end of the last one.
+    offsets: Vec<u64>,
+    delimiter: u8,
+}
+
+impl CsvSource {
+    /// Open a comma-separated file whose first record is the header.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with(path, b',', true)
+    }
+
+    /// Open a CSV file with a custom delimiter, optionally without headers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn open_with(path: impl AsRef<Path>, delimiter: u8, has_headers: bool) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut offsets = index_records(&mut reader)?;
+        let mut headers = Vec::new();
+        if has_headers && offsets.len() > 1 {
+            reader.seek(SeekFrom::Start(offsets[0]))?;
+            let mut bytes = vec![0; (offsets[1] - offsets[0]) as usize];
+            reader.read_exact(&mut bytes)?;
+            headers = parse_record(&String::from_utf8_lossy(&bytes), delimiter);
+            offsets.remove(0);
+        }
+        Ok(Self {
+            reader: RefCell::new(reader),
+            headers,
+            offsets,
+            delimiter,
+        })
+    }
+
+    /// The header record, empty if the file was opened without headers.
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    fn read_range(&self, start: usize, end: usize) -> Result<Vec<Vec<TableCell>>> {
+        let (Some(&from), Some(&to)) = (self.offsets.get(start), self.offsets.get(end)) else {
+            return Ok(Vec::new());
+        };
+        let mut reader = self.reader.borrow_mut();
+        reader.seek(SeekFrom::Start(from))?;
+        let mut bytes = vec![0; (to - from) as usize];
+        reader.read_exact(&mut bytes)?;
+        let text = String::from_utf8_lossy(&bytes);
+        let mut rows = Vec::with_capacity(end - start);
+        for window in self.offsets[start..=end].windows(2) {
+            let a = (window[0] - from) as usize;
+            let b = (window[1] - from) as usize;
+            let record = text.get(a..b).unwrap_or_default();
+            let fields = parse_record(record, self.delimiter);
+            rows.push(fields.iter().map(|f| TableCell::infer(f)).collect());
+        }
+        Ok(rows)
+    }
+}
+
+impl TableSource for CsvSource {
+    fn row_count(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    fn rows(&self, range: Range<usize>) -> Vec<Vec<TableCell>> {
+        let end = range.end.min(self.row_count());
+        let start = range.start.min(end);
+        match self.read_range(start, end) {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::warn!("failed to read CSV rows {start}..{end}: {e}");
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Find the start of every record, plus the end of the last one.
+///
+/// Blank lines are folded into the preceding record, whose parser ignores
+/// trailing newlines.
+fn index_records(reader: &mut BufReader<File>) -> Result<Vec<u64>> {
+    let mut offsets = Vec::new();
+    let mut pos = 0u64;
+    let mut record_start = 0u64;
+    let mut in_quotes = false;
+    let mut has_content = false;
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+        let len = chunk.len();
+        for (i, &byte) in chunk.iter().enumerate() {
+            match byte {
+                b'"' => {
+                    in_quotes = !in_quotes;
+                    has_content = true;
+                }
+                b'\n' if !in_quotes => {
+                    let next = pos + i as u64 + 1;
+                    if has_content {
+                        offsets.push(record_start);
+                    }
+                    record_start = next;
+                    has_content = false;
+                }
+                b'\r' => {}
+                _ => has_content = true,
+            }
+        }
+        pos += len as u64;
+        reader.consume(len);
+    }
+    if has_content {
+        offsets.push(record_start);
+    }
+    if !offsets.is_empty() {
+        offsets.push(pos);
+    }
+    Ok(offsets)
+}
+
+/// Split one CSV record into fields.
+fn parse_record(record: &str, delimiter: u8) -> Vec<String> {
+    let record = record.trim_end_matches(['\r', '\n']);
+    let delimiter = char::from(delimiter);
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = record.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn date(year: i32, month: u8, day: u8) -> TableDate {
+        match TableDate::new(year, month, day) {
+            Some(d) => d,
+            None => unreachable!("invalid test date"),
+        }
+    }
+
+    #[test]
+    fn dates_parse_and_validate() {
+        assert_eq!(TableDate::parse("2024-02-29"), Some(date(2024, 2, 29)));
+        assert_eq!(TableDate::parse("2023-02-29"), None);
+        assert_eq!(TableDate::parse("2024-13-01"), None);
+        assert_eq!(TableDate::parse("24-01-01"), None);
+        assert_eq!(date(2024, 3, 5).to_string(), "2024-03-05");
+    }
+
+    #[test]
+    fn infer_types_cells() {
+        assert_eq!(TableCell::infer(" 42 "), TableCell::Number(42.0));
+        assert_eq!(
+            TableCell::infer("2024-01-31"),
+            TableCell::Date(date(2024, 1, 31))
+        );
+        assert_eq!(TableCell::infer("  "), TableCell::Empty);
+        assert_eq!(TableCell::infer("NaN"), TableCell::from("NaN"));
+        assert_eq!(TableCell::Number(2.5).text(), "2.5");
+        assert_eq!(TableCell::Number(30.0).text(), "30");
+    }
+
+    #[test]
+    fn compare_orders_by_type_then_value() {
+        let mut cells = [
+            TableCell::Empty,
+            TableCell::from("b"),
+            TableCell::Date(date(2020, 1, 1)),
+            TableCell::Number(10.0),
+            TableCell::Styled(vec![Segment::new("a")]),
+            TableCell::Number(9.0),
+        ];
+        cells.sort_by(TableCell::compare);
+        let text: Vec<String> = cells.iter().map(TableCell::text).collect();
+        assert_eq!(text, vec!["9", "10", "2020-01-01", "a", "b", ""]);
+    }
+
+    #[test]
+    fn vec_source_clamps_ranges() {
+        let rows = vec![vec![TableCell::from("a")], vec![TableCell::from("b")]];
+        assert_eq!(rows.rows(1..5).len(), 1);
+        assert!(rows.rows(3..5).is_empty());
+        assert_eq!(rows.row(1), Some(vec![TableCell::from("b")]));
+    }
+
+    #[test]
+    fn csv_source_reads_records_on_demand() {
+        let mut file = match tempfile::NamedTempFile::new() {
+            Ok(f) => f,
+            Err(e) => unreachable!("temp file: {e}"),
+        };
+        let csv = "name,age,joined\r\n\
+                   Alice,30,2020-01-05\r\n\
+                   \"Bob, Jr.\",25,\r\n\
+                   \"multi\nline \"\"quoted\"\"\",41,2021-07-01\n\
+                   \n\
+                   Dana,19,2019-12-31";
+        assert!(file.write_all(csv.as_bytes()).is_ok());
+
+        let source = match CsvSource::open(file.path()) {
+            Ok(s) => s,
+            Err(e) => unreachable!("open CSV: {e}"),
+        };
+        assert_eq!(source.headers(), ["name", "age", "joined"]);
+        assert_eq!(source.row_count(), 4);
+
+        let rows = source.rows(1..3);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], TableCell::from("Bob, Jr."));
+        assert_eq!(rows[0][2], TableCell::Empty);
+        assert_eq!(rows[1][0], TableCell::from("multi\nline \"quoted\""));
+        assert_eq!(rows[1][1], TableCell::Number(41.0));
+        assert_eq!(
+            source.row(3),
+            Some(vec![
+                TableCell::from("Dana"),
+                TableCell::Number(19.0),
+                TableCell::Date(date(2019, 12, 31)),
+            ])
+        );
+        assert!(source.rows(4..10).is_empty());
+    }
+
+    #[test]
+    fn csv_source_without_headers() {
+        let mut file = match tempfile::NamedTempFile::new() {
+            Ok(f) => f,
+            Err(e) => unreachable!("temp file: {e}"),
+        };
+        assert!(file.write_all(b"a;1\nb;2\n").is_ok());
+        let source = match CsvSource::open_with(file.path(), b';', false) {
+            Ok(s) => s,
+            Err(e) => unreachable!("open CSV: {e}"),
+        };
+        assert!(source.headers().is_empty());
+        assert_eq!(source.row_count(), 2);
+        assert_eq!(
+            source.row(1),
+            Some(vec![TableCell::from("b"), TableCell::Number(2.0)])
+        );
+    }
+}
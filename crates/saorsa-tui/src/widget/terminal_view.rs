@@ -0,0 +1,582 @@
+//! Embedded terminal widget.
+//!
+//! [`TerminalView`] emulates an xterm-compatible terminal: it interprets a
+//! program's output on a grid of cells (cursor movement, scroll regions,
+//! the alternate screen, SGR styles), renders that grid, and encodes
+//! keyboard, mouse and paste events as the bytes the program expects.
+//!
+//! With the `pty` feature the view can run a process on a pseudo-terminal,
+//! reading its output on a background thread and resizing the terminal
+//! when the widget's layout area changes. Without it, output is passed to
+//! [`TerminalView::feed`] and input collected with
+//! [`TerminalView::take_input`].
+
+mod input;
+mod vt;
+
+use std::cell::Cell as StdCell;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::buffer::ScreenBuffer;
+use crate::cell::Cell;
+use crate::event::{Event, MouseEventKind};
+use crate::geometry::{Position, Rect};
+use crate::style::Style;
+use vt::{MouseMode, VtScreen};
+
+use super::{BorderStyle, EventResult, InteractiveWidget, Widget};
+
+#[cfg(feature = "pty")]
+pub use portable_pty::CommandBuilder;
+
+/// A terminal emulator widget.
+///
+/// Focused key, mouse and paste events are sent to the program; mouse
+/// events only once the program has enabled mouse reporting. Tab and
+/// Shift+Tab reach the program too rather than moving focus in an
+/// [`App`].
+///
+/// Output from a spawned process is read on a background thread. An
+/// [`App`] repaints the view when new output has arrived, so event loops
+/// should call [`App::render_if_needed`] on a regular tick (for example
+/// with a short event poll timeout) rather than only after input events.
+///
+/// [`App`]: crate::app::App
+/// [`App::render_if_needed`]: crate::app::App::render_if_needed
+pub struct TerminalView {
+    /// The emulated screen, shared with the pty reader thread.
+    screen: Arc<Mutex<VtScreen>>,
+    /// Base style, under the program's own styles.
+    style: Style,
+    /// Border style (optional).
+    border: BorderStyle,
+    /// Style merged onto the cell under the cursor.
+    cursor_style: Style,
+    /// Inner area at the last render, for mouse hit testing.
+    last_area: StdCell<Rect>,
+    /// Bytes for the program not yet collected, when no process runs.
+    input: Vec<u8>,
+    /// The running process, if any.
+    #[cfg(feature = "pty")]
+    pty: Option<pty::Pty>,
+}
+
+impl TerminalView {
+    /// Create a terminal of `cols` × `rows` cells. The size follows the
+    /// widget's area once rendered.
+    pub fn new(cols: u16, rows: u16) -> Self {
+        Self {
+            screen: Arc::new(Mutex::new(VtScreen::new(
+                usize::from(cols),
+                usize::from(rows),
+            ))),
+            style: Style::default(),
+            border: BorderStyle::None,
+            cursor_style: Style::new().reverse(true),
+            last_area: StdCell::new(Rect::default()),
+            input: Vec::new(),
+            #[cfg(feature = "pty")]
+            pty: None,
+        }
+    }
+
+    /// Set the base style.
+    #[must_use]
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the border style.
+    #[must_use]
+    pub fn with_border(mut self, border: BorderStyle) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// Set the style merged onto the cell under the cursor.
+    #[must_use]
+    pub fn with_cursor_style(mut self, style: Style) -> Self {
+        self.cursor_style = style;
+        self
+    }
+
+    fn screen(&self) -> MutexGuard<'_, VtScreen> {
+        self.screen.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Interpret program output. Replies to terminal queries are sent to
+    /// the running process, or queued for [`take_input`](Self::take_input).
+    pub fn feed(&mut self, bytes: &[u8]) {
+        let responses = {
+            let mut screen = self.screen();
+            screen.feed(bytes);
+            std::mem::take(&mut screen.responses)
+        };
+        self.send(&responses);
+    }
+
+    /// Take the bytes queued for the program while no process is running:
+    /// encoded input and replies to queries.
+    pub fn take_input(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.input)
+    }
+
+    /// Send bytes to the program.
+    fn send(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        #[cfg(feature = "pty")]
+        if let Some(pty) = &self.pty {
+            pty.write(bytes);
+            return;
+        }
+        self.input.extend_from_slice(bytes);
+    }
+
+    /// The terminal size in columns and rows.
+    pub fn size(&self) -> (u16, u16) {
+        let (cols, rows) = self.screen().size();
+        (to_u16(cols), to_u16(rows))
+    }
+
+    /// Resize the terminal, and the pseudo-terminal of a running process.
+    /// Rendering does this whenever the widget's area changes.
+    pub fn resize(&self, cols: u16, rows: u16) {
+        self.screen().resize(usize::from(cols), usize::from(rows));
+        #[cfg(feature = "pty")]
+        if let Some(pty) = &self.pty {
+            pty.resize(cols.max(1), rows.max(1));
+        }
+    }
+
+    /// The screen as text, one line per row without trailing spaces.
+    pub fn screen_text(&self) -> String {
+        self.screen().text()
+    }
+
+    /// The cell at column `col`, row `row` of the screen.
+    pub fn cell(&self, col: u16, row: u16) -> Option<Cell> {
+        self.screen()
+            .grid()
+            .get(usize::from(row))
+            .and_then(|r| r.get(usize::from(col)))
+            .cloned()
+    }
+
+    /// The cursor's column and row.
+    pub fn cursor_position(&self) -> (u16, u16) {
+        let (col, row) = self.screen().cursor();
+        (to_u16(col), to_u16(row))
+    }
+
+    /// The window title the program set, if any.
+    pub fn title(&self) -> String {
+        self.screen().title.clone()
+    }
+
+    /// Whether the program switched to the alternate screen.
+    pub fn is_alternate_screen(&self) -> bool {
+        self.screen().is_alternate()
+    }
+
+    /// The terminal cell under screen position `(x, y)`, if it is inside
+    /// the last rendered area.
+    fn cell_at(&self, x: u16, y: u16) -> Option<(u16, u16)> {
+        let area = self.last_area.get();
+        area.contains(Position::new(x, y))
+            .then(|| (x - area.position.x, y - area.position.y))
+    }
+}
+
+fn to_u16(n: usize) -> u16 {
+    u16::try_from(n).unwrap_or(u16::MAX)
+}
+
+impl std::fmt::Debug for TerminalView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("TerminalView");
+        debug
+            .field("size", &self.size())
+            .field("style", &self.style);
+        #[cfg(feature = "pty")]
+        debug.field("process_id", &self.process_id());
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl Widget for TerminalView {
+    fn render(&self, area: Rect, buf: &mut ScreenBuffer) {
+        if area.size.width == 0 || area.size.height == 0 {
+            return;
+        }
+        // Output arriving from here on shows in the next frame.
+        #[cfg(feature = "pty")]
+        self.take_output_ready();
+        super::border::render_border(area, self.border, self.style.clone(), buf);
+        let inner = super::border::inner_area(area, self.border);
+        self.last_area.set(inner);
+        if inner.size.width == 0 || inner.size.height == 0 {
+            return;
+        }
+        if self.size() != (inner.size.width, inner.size.height) {
+            self.resize(inner.size.width, inner.size.height);
+        }
+
+        let screen = self.screen();
+        for (y, row) in (inner.position.y..inner.bottom()).zip(screen.grid()) {
+            for (x, cell) in (inner.position.x..inner.right()).zip(row) {
+                if cell.is_continuation() {
+                    continue;
+                }
+                let style = self.style.merge(&cell.style);
+                buf.set(x, y, Cell::new(cell.grapheme.clone(), style));
+            }
+        }
+        if screen.cursor_visible {
+            let (col, row) = screen.cursor();
+            let (x, y) = (
+                inner.position.x + to_u16(col),
+                inner.position.y + to_u16(row),
+            );
+            if let Some(cell) = buf.get_mut(x, y) {
+                cell.style = cell.style.merge(&self.cursor_style);
+            }
+        }
+    }
+}
+
+impl InteractiveWidget for TerminalView {
+    fn handle_event(&mut self, event: &Event) -> EventResult {
+        let bytes = match event {
+            Event::Key(key) => input::encode_key(key, self.screen().app_cursor_keys),
+            Event::Paste(text) => Some(input::encode_paste(text, self.screen().bracketed_paste)),
+            Event::Mouse(mouse) => {
+                let (mode, sgr) = {
+                    let screen = self.screen();
+                    (screen.mouse, screen.sgr_mouse)
+                };
+                let wanted = match mouse.kind {
+                    MouseEventKind::Move => mode == MouseMode::Any,
+                    MouseEventKind::Drag => matches!(mode, MouseMode::Drag | MouseMode::Any),
+                    _ => mode != MouseMode::Off,
+                };
+                match self.cell_at(mouse.x, mouse.y) {
+                    Some((col, row)) if wanted => input::encode_mouse(mouse, col, row, sgr),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        match bytes {
+            Some(bytes) => {
+                self.send(&bytes);
+                EventResult::Consumed
+            }
+            None => EventResult::Ignored,
+        }
+    }
+
+    /// Tab goes to the program, e.g. for shell completion.
+    fn captures_tab(&self) -> bool {
+        true
+    }
+
+    /// Output from the running process arrived since the last render.
+    fn needs_repaint(&self) -> bool {
+        #[cfg(feature = "pty")]
+        {
+            self.output_pending()
+        }
+        #[cfg(not(feature = "pty"))]
+        {
+            false
+        }
+    }
+}
+
+#[cfg(feature = "pty")]
+mod pty {
+    //! Running a process on a pseudo-terminal.
+
+    use std::io::{Read, Write};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    use portable_pty::{Child, CommandBuilder, MasterPty, PtySize, native_pty_system};
+
+    use super::{TerminalView, VtScreen};
+    use crate::error::{Result, SaorsaTuiError};
+
+    type Writer = Arc<Mutex<Box<dyn Write + Send>>>;
+
+    /// A process and the pseudo-terminal it runs on.
+    pub(super) struct Pty {
+        master: Box<dyn MasterPty + Send>,
+        writer: Writer,
+        child: Box<dyn Child + Send + Sync>,
+        /// Exit code, once the process has exited.
+        exit_code: Option<u32>,
+        /// Set by the reader thread when new output arrives.
+        output_ready: Arc<AtomicBool>,
+    }
+
+    impl Pty {
+        pub fn write(&self, bytes: &[u8]) {
+            write_all(&self.writer, bytes);
+        }
+
+        pub fn resize(&self, cols: u16, rows: u16) {
+            // A process that exited can no longer be resized; nothing to do.
+            let _ = self.master.resize(pty_size(cols, rows));
+        }
+    }
+
+    fn pty_size(cols: u16, rows: u16) -> PtySize {
+        PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        }
+    }
+
+    fn write_all(writer: &Writer, bytes: &[u8]) {
+        let mut writer = writer.lock().unwrap_or_else(PoisonError::into_inner);
+        // Writes fail once the process has exited; its exit is reported by
+        // `TerminalView::exit_code`.
+        let _ = writer.write_all(bytes).and_then(|()| writer.flush());
+    }
+
+    fn pty_error(action: &str, e: impl std::fmt::Display) -> SaorsaTuiError {
+        SaorsaTuiError::Terminal(format!("failed to {action}: {e}"))
+    }
+
+    /// Feed everything read from the pty to the screen, answering queries.
+    fn read_output(
+        mut reader: Box<dyn Read + Send>,
+        screen: Arc<Mutex<VtScreen>>,
+        writer: Writer,
+        output_ready: Arc<AtomicBool>,
+    ) {
+        let mut buf = [0; 8192];
+        // Reading fails with EIO on some platforms once the process exits.
+        while let Ok(n @ 1..) = reader.read(&mut buf) {
+            let responses = {
+                let mut screen = screen.lock().unwrap_or_else(PoisonError::into_inner);
+                screen.feed(&buf[..n]);
+                std::mem::take(&mut screen.responses)
+            };
+            if !responses.is_empty() {
+                write_all(&writer, &responses);
+            }
+            output_ready.store(true, Ordering::Release);
+        }
+        output_ready.store(true, Ordering::Release);
+    }
+
+    impl TerminalView {
+        /// Run `command` on a pseudo-terminal the size of the view,
+        /// replacing any process already running. `TERM` is set to
+        /// `xterm-256color` unless the command sets it.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`SaorsaTuiError::Terminal`] if the pseudo-terminal
+        /// cannot be opened or the command cannot be started.
+        pub fn spawn(&mut self, mut command: CommandBuilder) -> Result<()> {
+            self.kill();
+            let (cols, rows) = self.size();
+            let pair = native_pty_system()
+                .openpty(pty_size(cols, rows))
+                .map_err(|e| pty_error("open pseudo-terminal", e))?;
+            if command.get_env("TERM").is_none() {
+                command.env("TERM", "xterm-256color");
+            }
+            if command.get_env("COLORTERM").is_none() {
+                command.env("COLORTERM", "truecolor");
+            }
+            let child = pair
+                .slave
+                .spawn_command(command)
+                .map_err(|e| pty_error("start process", e))?;
+            // Only the child keeps the slave side open, so reads end when
+            // it exits.
+            drop(pair.slave);
+            let reader = pair
+                .master
+                .try_clone_reader()
+                .map_err(|e| pty_error("read pseudo-terminal", e))?;
+            let writer: Writer = Arc::new(Mutex::new(
+                pair.master
+                    .take_writer()
+                    .map_err(|e| pty_error("write pseudo-terminal", e))?,
+            ));
+            let output_ready = Arc::new(AtomicBool::new(false));
+
+            let screen = Arc::clone(&self.screen);
+            let thread_writer = Arc::clone(&writer);
+            let thread_ready = Arc::clone(&output_ready);
+            std::thread::Builder::new()
+                .name("terminal-view-reader".into())
+                .spawn(move || read_output(reader, screen, thread_writer, thread_ready))
+                .map_err(|e| pty_error("start reader thread", e))?;
+
+            // Input queued before the process started goes to it first.
+            write_all(&writer, &std::mem::take(&mut self.input));
+            self.pty = Some(Pty {
+                master: pair.master,
+                writer,
+                child,
+                exit_code: None,
+                output_ready,
+            });
+            Ok(())
+        }
+
+        /// Run the user's shell (`$SHELL`, or the platform default).
+        ///
+        /// # Errors
+        ///
+        /// See [`spawn`](Self::spawn).
+        pub fn spawn_shell(&mut self) -> Result<()> {
+            self.spawn(CommandBuilder::new_default_prog())
+        }
+
+        /// The exit code of the process, once it has exited.
+        pub fn exit_code(&mut self) -> Option<u32> {
+            let pty = self.pty.as_mut()?;
+            if pty.exit_code.is_none()
+                && let Ok(Some(status)) = pty.child.try_wait()
+            {
+                pty.exit_code = Some(status.exit_code());
+            }
+            pty.exit_code
+        }
+
+        /// Whether a process was started and has not exited.
+        pub fn is_running(&mut self) -> bool {
+            self.pty.is_some() && self.exit_code().is_none()
+        }
+
+        /// The id of the process, while it runs.
+        pub fn process_id(&self) -> Option<u32> {
+            self.pty.as_ref().and_then(|pty| pty.child.process_id())
+        }
+
+        /// Whether output arrived since the last call or render, meaning
+        /// the view needs rendering again.
+        ///
+        /// An [`App`](crate::app::App) checks this itself before each
+        /// frame; other callers poll it and redraw.
+        pub fn take_output_ready(&self) -> bool {
+            self.pty
+                .as_ref()
+                .is_some_and(|pty| pty.output_ready.swap(false, Ordering::AcqRel))
+        }
+
+        /// Whether output arrived since the last render, without clearing it.
+        pub(super) fn output_pending(&self) -> bool {
+            self.pty
+                .as_ref()
+                .is_some_and(|pty| pty.output_ready.load(Ordering::Acquire))
+        }
+
+        /// Kill the process, if one is running, and detach from it.
+        pub fn kill(&mut self) {
+            if self.is_running()
+                && let Some(pty) = &mut self.pty
+            {
+                // The process may exit between the check and the kill.
+                let _ = pty.child.kill();
+                let _ = pty.child.wait();
+            }
+            self.pty = None;
+        }
+    }
+
+    impl Drop for TerminalView {
+        fn drop(&mut self) {
+            self.kill();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{KeyCode, KeyEvent, Modifiers, MouseEvent};
+    use crate::geometry::Size;
+
+    fn render(view: &TerminalView, width: u16, height: u16) -> ScreenBuffer {
+        let mut buf = ScreenBuffer::new(Size::new(width, height));
+        view.render(Rect::new(0, 0, width, height), &mut buf);
+        buf
+    }
+
+    #[test]
+    fn renders_program_output_and_cursor() {
+        let mut view = TerminalView::new(10, 3);
+        view.feed(b"\x1b[31mhi\x1b[0m\r\n$ ");
+        let buf = render(&view, 10, 3);
+        assert_eq!(buf.get(0, 0).map(|c| c.grapheme.as_str()), Some("h"));
+        assert!(buf.get(0, 0).is_some_and(|c| c.style.fg.is_some()));
+        assert!(buf.get(2, 1).is_some_and(|c| c.style.reverse));
+        view.feed(b"\x1b[?25l");
+        let buf = render(&view, 10, 3);
+        assert!(!buf.get(2, 1).is_some_and(|c| c.style.reverse));
+    }
+
+    #[test]
+    fn render_resizes_to_the_inner_area() {
+        let view = TerminalView::new(80, 24).with_border(BorderStyle::Single);
+        render(&view, 12, 5);
+        assert_eq!(view.size(), (10, 3));
+    }
+
+    #[test]
+    fn queries_are_answered_through_input() {
+        let mut view = TerminalView::new(10, 3);
+        view.feed(b"ab\x1b[6n");
+        assert_eq!(view.take_input(), b"\x1b[1;3R".to_vec());
+        assert!(view.take_input().is_empty());
+    }
+
+    #[test]
+    fn input_follows_the_program_modes() {
+        let mut view = TerminalView::new(10, 3);
+        let up = Event::Key(KeyEvent::plain(KeyCode::Up));
+        assert_eq!(view.handle_event(&up), EventResult::Consumed);
+        view.feed(b"\x1b[?1h\x1b[?2004h");
+        view.handle_event(&up);
+        view.handle_event(&Event::Paste("p".into()));
+        assert_eq!(
+            view.take_input(),
+            b"\x1b[A\x1bOA\x1b[200~p\x1b[201~".to_vec()
+        );
+    }
+
+    #[test]
+    fn mouse_is_reported_only_when_enabled() {
+        let mut view = TerminalView::new(10, 3).with_border(BorderStyle::Single);
+        render(&view, 12, 5);
+        let click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Press,
+            x: 3,
+            y: 2,
+            modifiers: Modifiers::NONE,
+        });
+        assert_eq!(view.handle_event(&click), EventResult::Ignored);
+        view.feed(b"\x1b[?1000h\x1b[?1006h");
+        assert_eq!(view.handle_event(&click), EventResult::Consumed);
+        assert_eq!(view.take_input(), b"\x1b[<0;3;2M".to_vec());
+        let outside = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Press,
+            x: 0,
+            y: 0,
+            modifiers: Modifiers::NONE,
+        });
+        assert_eq!(view.handle_event(&outside), EventResult::Ignored);
+    }
+}
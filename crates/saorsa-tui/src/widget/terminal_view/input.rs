@@ -0,0 +1,253 @@
+//! Encoding input events as the bytes a terminal program reads.
+
+use crate::event::{KeyCode, KeyEvent, Modifiers, MouseEvent, MouseEventKind};
+
+/// The xterm modifier parameter (`1 + shift + 2·alt + 4·ctrl`), or `None`
+/// without modifiers.
+fn modifier_param(modifiers: Modifiers) -> Option<u8> {
+    let mut param = 0;
+    if modifiers.contains(Modifiers::SHIFT) {
+        param += 1;
+    }
+    if modifiers.contains(Modifiers::ALT) {
+        param += 2;
+    }
+    if modifiers.contains(Modifiers::CTRL) {
+        param += 4;
+    }
+    (param > 0).then_some(param + 1)
+}
+
+/// `CSI 1;m <final>` with modifiers, otherwise `CSI <final>` or, in
+/// application cursor mode, `SS3 <final>`.
+fn cursor_key(final_byte: char, modifiers: Modifiers, app_cursor: bool) -> Vec<u8> {
+    match modifier_param(modifiers) {
+        Some(m) => format!("\x1b[1;{m}{final_byte}").into_bytes(),
+        None if app_cursor => format!("\x1bO{final_byte}").into_bytes(),
+        None => format!("\x1b[{final_byte}").into_bytes(),
+    }
+}
+
+/// `CSI n ~` or `CSI n;m ~`.
+fn tilde_key(n: u8, modifiers: Modifiers) -> Vec<u8> {
+    match modifier_param(modifiers) {
+        Some(m) => format!("\x1b[{n};{m}~").into_bytes(),
+        None => format!("\x1b[{n}~").into_bytes(),
+    }
+}
+
+/// The bytes for a key press, or `None` for releases and keys a terminal
+/// cannot send.
+pub(super) fn encode_key(key: &KeyEvent, app_cursor: bool) -> Option<Vec<u8>> {
+    if key.is_release() {
+        return None;
+    }
+    let modifiers = key.modifiers;
+    let alt = modifiers.contains(Modifiers::ALT);
+    let bytes = match key.code {
+        KeyCode::Char(c) => {
+            let mut bytes = Vec::new();
+            if alt {
+                bytes.push(0x1b);
+            }
+            match control_byte(c).filter(|_| modifiers.contains(Modifiers::CTRL)) {
+                Some(byte) => bytes.push(byte),
+                None => {
+                    let mut utf8 = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+                }
+            }
+            return Some(bytes);
+        }
+        KeyCode::Enter => b"\r".to_vec(),
+        KeyCode::Tab if modifiers.contains(Modifiers::SHIFT) => return Some(b"\x1b[Z".to_vec()),
+        KeyCode::Tab => b"\t".to_vec(),
+        KeyCode::Backspace if modifiers.contains(Modifiers::CTRL) => vec![0x08],
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Escape => vec![0x1b],
+        KeyCode::Up => return Some(cursor_key('A', modifiers, app_cursor)),
+        KeyCode::Down => return Some(cursor_key('B', modifiers, app_cursor)),
+        KeyCode::Right => return Some(cursor_key('C', modifiers, app_cursor)),
+        KeyCode::Left => return Some(cursor_key('D', modifiers, app_cursor)),
+        KeyCode::Home => return Some(cursor_key('H', modifiers, app_cursor)),
+        KeyCode::End => return Some(cursor_key('F', modifiers, app_cursor)),
+        KeyCode::Insert => return Some(tilde_key(2, modifiers)),
+        KeyCode::Delete => return Some(tilde_key(3, modifiers)),
+        KeyCode::PageUp => return Some(tilde_key(5, modifiers)),
+        KeyCode::PageDown => return Some(tilde_key(6, modifiers)),
+        KeyCode::F(n @ 1..=4) => {
+            let final_byte = char::from(b'P' + n - 1);
+            return Some(match modifier_param(modifiers) {
+                Some(m) => format!("\x1b[1;{m}{final_byte}").into_bytes(),
+                None => format!("\x1bO{final_byte}").into_bytes(),
+            });
+        }
+        KeyCode::F(n @ 5..=12) => {
+            const CODES: [u8; 8] = [15, 17, 18, 19, 20, 21, 23, 24];
+            return Some(tilde_key(CODES[usize::from(n - 5)], modifiers));
+        }
+        _ => return None,
+    };
+    // Alt prefixes the plain keys with ESC.
+    Some(if alt {
+        [&[0x1b][..], &bytes].concat()
+    } else {
+        bytes
+    })
+}
+
+/// The C0 control byte for Ctrl+`c`.
+fn control_byte(c: char) -> Option<u8> {
+    match c {
+        'a'..='z' => Some(c as u8 - b'a' + 1),
+        'A'..='Z' => Some(c as u8 - b'A' + 1),
+        ' ' | '@' | '2' => Some(0),
+        '[' | '3' => Some(0x1b),
+        '\\' | '4' => Some(0x1c),
+        ']' | '5' => Some(0x1d),
+        '^' | '6' => Some(0x1e),
+        '_' | '/' | '7' => Some(0x1f),
+        '8' | '?' => Some(0x7f),
+        _ => None,
+    }
+}
+
+/// The bytes reporting a mouse event at 0-based cell `(col, row)`, in SGR
+/// form when `sgr` is set and X10 form otherwise. Returns `None` for
+/// events X10 cannot express.
+pub(super) fn encode_mouse(event: &MouseEvent, col: u16, row: u16, sgr: bool) -> Option<Vec<u8>> {
+    let (mut button, release) = match event.kind {
+        MouseEventKind::Press => (0, false),
+        MouseEventKind::Release => (if sgr { 0 } else { 3 }, true),
+        MouseEventKind::Drag => (32, false),
+        MouseEventKind::Move => (35, false),
+        MouseEventKind::ScrollUp => (64, false),
+        MouseEventKind::ScrollDown => (65, false),
+    };
+    if event.modifiers.contains(Modifiers::SHIFT) {
+        button += 4;
+    }
+    if event.modifiers.contains(Modifiers::ALT) {
+        button += 8;
+    }
+    if event.modifiers.contains(Modifiers::CTRL) {
+        button += 16;
+    }
+    let (x, y) = (u32::from(col) + 1, u32::from(row) + 1);
+    if sgr {
+        let end = if release { 'm' } else { 'M' };
+        return Some(format!("\x1b[<{button};{x};{y}{end}").into_bytes());
+    }
+    // X10 coordinates are single bytes offset by 32.
+    let x = u8::try_from(x + 32).ok()?;
+    let y = u8::try_from(y + 32).ok()?;
+    Some(vec![0x1b, b'[', b'M', button + 32, x, y])
+}
+
+/// The bytes for pasted text, between bracketed paste markers when the
+/// program enabled them. ESC is removed so the paste cannot end early.
+pub(super) fn encode_paste(text: &str, bracketed: bool) -> Vec<u8> {
+    let text = text.replace("\r\n", "\r").replace('\n', "\r");
+    if !bracketed {
+        return text.into_bytes();
+    }
+    let mut bytes = b"\x1b[200~".to_vec();
+    bytes.extend(text.bytes().filter(|&b| b != 0x1b));
+    bytes.extend_from_slice(b"\x1b[201~");
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::KeyEventKind;
+
+    fn key(code: KeyCode, modifiers: Modifiers) -> Option<Vec<u8>> {
+        encode_key(&KeyEvent::new(code, modifiers), false)
+    }
+
+    #[test]
+    fn encodes_text_and_control_keys() {
+        assert_eq!(key(KeyCode::Char('é'), Modifiers::NONE), Some("é".into()));
+        assert_eq!(key(KeyCode::Char('c'), Modifiers::CTRL), Some(vec![3]));
+        assert_eq!(
+            key(KeyCode::Char('x'), Modifiers::ALT),
+            Some(b"\x1bx".to_vec())
+        );
+        assert_eq!(key(KeyCode::Enter, Modifiers::NONE), Some(b"\r".to_vec()));
+        assert_eq!(key(KeyCode::Backspace, Modifiers::NONE), Some(vec![0x7f]));
+        assert_eq!(
+            key(KeyCode::Tab, Modifiers::SHIFT),
+            Some(b"\x1b[Z".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::Escape, Modifiers::ALT),
+            Some(b"\x1b\x1b".to_vec())
+        );
+        let release = KeyEvent::plain(KeyCode::Char('a')).with_kind(KeyEventKind::Release);
+        assert_eq!(encode_key(&release, false), None);
+    }
+
+    #[test]
+    fn encodes_cursor_and_function_keys() {
+        assert_eq!(key(KeyCode::Up, Modifiers::NONE), Some(b"\x1b[A".to_vec()));
+        let up = encode_key(&KeyEvent::plain(KeyCode::Up), true);
+        assert_eq!(up, Some(b"\x1bOA".to_vec()));
+        assert_eq!(
+            key(KeyCode::Left, Modifiers::CTRL),
+            Some(b"\x1b[1;5D".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::Delete, Modifiers::NONE),
+            Some(b"\x1b[3~".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::PageUp, Modifiers::SHIFT),
+            Some(b"\x1b[5;2~".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::F(1), Modifiers::NONE),
+            Some(b"\x1bOP".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::F(12), Modifiers::NONE),
+            Some(b"\x1b[24~".to_vec())
+        );
+        assert_eq!(key(KeyCode::F(13), Modifiers::NONE), None);
+    }
+
+    #[test]
+    fn encodes_mouse_events() {
+        let event = |kind| MouseEvent {
+            kind,
+            x: 0,
+            y: 0,
+            modifiers: Modifiers::NONE,
+        };
+        let press = event(MouseEventKind::Press);
+        assert_eq!(
+            encode_mouse(&press, 2, 4, true),
+            Some(b"\x1b[<0;3;5M".to_vec())
+        );
+        let release = event(MouseEventKind::Release);
+        assert_eq!(
+            encode_mouse(&release, 2, 4, true),
+            Some(b"\x1b[<0;3;5m".to_vec())
+        );
+        let scroll = event(MouseEventKind::ScrollDown);
+        assert_eq!(
+            encode_mouse(&scroll, 0, 0, false),
+            Some(vec![0x1b, b'[', b'M', 97, 33, 33])
+        );
+        assert_eq!(encode_mouse(&press, 300, 0, false), None);
+    }
+
+    #[test]
+    fn encodes_pastes() {
+        assert_eq!(encode_paste("a\nb", false), b"a\rb".to_vec());
+        assert_eq!(
+            encode_paste("x\x1by", true),
+            b"\x1b[200~xy\x1b[201~".to_vec()
+        );
+    }
+}
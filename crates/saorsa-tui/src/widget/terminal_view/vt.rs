@@ -0,0 +1,866 @@
+//! The VT emulator behind [`TerminalView`](super::TerminalView).
+//!
+//! Interprets the output of a program written for an xterm-compatible
+//! terminal on a fixed grid of cells: printing with autowrap and insert
+//! mode, cursor movement, scroll regions, line and character editing,
+//! erasing, the alternate screen, SGR styles, the DEC line-drawing
+//! character set, and the modes that change how input is encoded.
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::ansi::{MAX_OSC_LEN, apply_sgr};
+use crate::cell::Cell;
+use crate::style::Style;
+
+/// Columns between tab stops.
+const TAB_WIDTH: usize = 8;
+
+/// Longest CSI parameter string interpreted; longer sequences are dropped.
+const MAX_CSI_LEN: usize = 256;
+
+/// Which mouse events the program asked to receive.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum MouseMode {
+    /// No mouse reporting.
+    #[default]
+    Off,
+    /// Presses, releases and the wheel (mode 1000).
+    Press,
+    /// Also motion while a button is held (mode 1002).
+    Drag,
+    /// Also all motion (mode 1003).
+    Any,
+}
+
+/// Where the parser is within an escape sequence.
+#[derive(Clone, Debug, Default)]
+enum State {
+    #[default]
+    Ground,
+    /// After `ESC`.
+    Escape,
+    /// After `ESC` and an intermediate byte such as `(` or `#`.
+    EscapeIntermediate(char),
+    /// In a CSI sequence, collecting parameters.
+    Csi(String),
+    /// In an OSC string.
+    Osc(String),
+    /// In a DCS, SOS, PM or APC string, which is ignored.
+    Ignore,
+    /// After `ESC` inside a string, expecting `\`.
+    StringEscape(Option<String>),
+}
+
+/// Cursor position and the attributes saved with it by `DECSC`.
+#[derive(Clone, Debug, Default)]
+struct Cursor {
+    row: usize,
+    col: usize,
+    style: Style,
+    /// The last column was written; the next character wraps first.
+    pending_wrap: bool,
+    /// G0 is the DEC special graphics (line drawing) set.
+    line_drawing: bool,
+}
+
+/// A terminal screen fed with program output.
+#[derive(Clone, Debug)]
+pub(super) struct VtScreen {
+    cols: usize,
+    rows: usize,
+    grid: Vec<Vec<Cell>>,
+    /// The primary screen and its cursor while the alternate screen is
+    /// shown.
+    primary: Option<(Vec<Vec<Cell>>, Cursor)>,
+    cursor: Cursor,
+    saved: Cursor,
+    /// Scroll region, inclusive.
+    top: usize,
+    bottom: usize,
+    autowrap: bool,
+    insert: bool,
+    pub cursor_visible: bool,
+    pub app_cursor_keys: bool,
+    pub bracketed_paste: bool,
+    pub mouse: MouseMode,
+    pub sgr_mouse: bool,
+    pub title: String,
+    /// Replies to queries (cursor position, device attributes) to send
+    /// back to the program.
+    pub responses: Vec<u8>,
+    state: State,
+    /// Bytes of an unfinished UTF-8 character.
+    utf8: Vec<u8>,
+}
+
+impl VtScreen {
+    /// A blank screen of the given size (at least 1×1).
+    pub fn new(cols: usize, rows: usize) -> Self {
+        let cols = cols.max(1);
+        let rows = rows.max(1);
+        Self {
+            cols,
+            rows,
+            grid: blank_grid(cols, rows),
+            primary: None,
+            cursor: Cursor::default(),
+            saved: Cursor::default(),
+            top: 0,
+            bottom: rows - 1,
+            autowrap: true,
+            insert: false,
+            cursor_visible: true,
+            app_cursor_keys: false,
+            bracketed_paste: false,
+            mouse: MouseMode::Off,
+            sgr_mouse: false,
+            title: String::new(),
+            responses: Vec::new(),
+            state: State::Ground,
+            utf8: Vec::new(),
+        }
+    }
+
+    /// Columns and rows.
+    pub fn size(&self) -> (usize, usize) {
+        (self.cols, self.rows)
+    }
+
+    /// The visible rows of cells.
+    pub fn grid(&self) -> &[Vec<Cell>] {
+        &self.grid
+    }
+
+    /// Cursor column and row.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.cursor.col, self.cursor.row)
+    }
+
+    /// Whether the alternate screen is shown.
+    pub fn is_alternate(&self) -> bool {
+        self.primary.is_some()
+    }
+
+    /// The screen as text, one line per row without trailing spaces.
+    pub fn text(&self) -> String {
+        self.grid
+            .iter()
+            .map(|row| {
+                let line: String = row.iter().map(|c| c.grapheme.as_str()).collect();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Interpret program output. UTF-8 characters and escape sequences may
+    /// be split across calls.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.utf8.extend_from_slice(bytes);
+        let pending = std::mem::take(&mut self.utf8);
+        let mut rest = pending.as_slice();
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(text) => {
+                    text.chars().for_each(|ch| self.advance(ch));
+                    break;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    String::from_utf8_lossy(valid)
+                        .chars()
+                        .for_each(|ch| self.advance(ch));
+                    match e.error_len() {
+                        Some(len) => {
+                            self.advance(char::REPLACEMENT_CHARACTER);
+                            rest = &after[len..];
+                        }
+                        None => {
+                            self.utf8 = after.to_vec();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Change the screen size, keeping the rows around the cursor.
+    pub fn resize(&mut self, cols: usize, rows: usize) {
+        let cols = cols.max(1);
+        let rows = rows.max(1);
+        if (cols, rows) == (self.cols, self.rows) {
+            return;
+        }
+        // Drop rows from the top when the cursor would fall off the bottom.
+        let excess = (self.cursor.row + 1).saturating_sub(rows);
+        resize_grid(&mut self.grid, cols, rows, excess);
+        self.cursor.row -= excess;
+        if let Some((grid, cursor)) = &mut self.primary {
+            let excess = (cursor.row + 1).saturating_sub(rows);
+            resize_grid(grid, cols, rows, excess);
+            cursor.row -= excess;
+            clamp_cursor(cursor, cols, rows);
+        }
+        self.cols = cols;
+        self.rows = rows;
+        clamp_cursor(&mut self.cursor, cols, rows);
+        clamp_cursor(&mut self.saved, cols, rows);
+        self.top = 0;
+        self.bottom = rows - 1;
+    }
+
+    fn advance(&mut self, ch: char) {
+        match std::mem::take(&mut self.state) {
+            State::Ground => self.ground(ch),
+            State::Escape => self.escape(ch),
+            State::EscapeIntermediate(intermediate) => {
+                if intermediate == '(' {
+                    self.cursor.line_drawing = ch == '0';
+                }
+            }
+            State::Csi(mut params) => {
+                if ('@'..='~').contains(&ch) {
+                    self.csi(&params, ch);
+                } else if ch == '\x1b' {
+                    self.state = State::Escape;
+                } else if ch.is_control() {
+                    self.control(ch);
+                    self.state = State::Csi(params);
+                } else if params.len() < MAX_CSI_LEN {
+                    params.push(ch);
+                    self.state = State::Csi(params);
+                }
+                // Past the limit the sequence is dropped.
+            }
+            State::Osc(mut data) => match ch {
+                '\x07' | '\u{9c}' => self.osc(&data),
+                '\x1b' => self.state = State::StringEscape(Some(data)),
+                c if data.len() < MAX_OSC_LEN => {
+                    data.push(c);
+                    self.state = State::Osc(data);
+                }
+                // Past the limit the string is dropped, so a missing
+                // terminator cannot grow it without bound.
+                _ => {}
+            },
+            State::Ignore => match ch {
+                '\x1b' => self.state = State::StringEscape(None),
+                '\x07' | '\u{9c}' => {}
+                _ => self.state = State::Ignore,
+            },
+            State::StringEscape(data) => {
+                if let Some(data) = data {
+                    self.osc(&data);
+                }
+                if ch != '\\' {
+                    self.advance('\x1b');
+                    self.advance(ch);
+                }
+            }
+        }
+    }
+
+    fn ground(&mut self, ch: char) {
+        match ch {
+            '\x1b' => self.state = State::Escape,
+            c if c.is_control() => self.control(c),
+            c => self.print(c),
+        }
+    }
+
+    /// Execute a C0 control character.
+    fn control(&mut self, ch: char) {
+        match ch {
+            '\n' | '\x0b' | '\x0c' => self.linefeed(),
+            '\r' => {
+                self.cursor.col = 0;
+                self.cursor.pending_wrap = false;
+            }
+            '\x08' => {
+                self.cursor.col = self.cursor.col.saturating_sub(1);
+                self.cursor.pending_wrap = false;
+            }
+            '\t' => {
+                self.cursor.col =
+                    ((self.cursor.col / TAB_WIDTH + 1) * TAB_WIDTH).min(self.cols - 1);
+                self.cursor.pending_wrap = false;
+            }
+            _ => {}
+        }
+    }
+
+    fn escape(&mut self, ch: char) {
+        match ch {
+            '[' => self.state = State::Csi(String::new()),
+            ']' => self.state = State::Osc(String::new()),
+            'P' | 'X' | '^' | '_' => self.state = State::Ignore,
+            '(' | ')' | '*' | '+' | '#' | '%' => self.state = State::EscapeIntermediate(ch),
+            '7' => self.saved = self.cursor.clone(),
+            '8' => self.restore_cursor(),
+            'D' => self.linefeed(),
+            'E' => {
+                self.linefeed();
+                self.cursor.col = 0;
+            }
+            'M' => self.reverse_index(),
+            'c' => *self = Self::new(self.cols, self.rows),
+            _ => {}
+        }
+    }
+
+    /// Write a printable character at the cursor.
+    fn print(&mut self, ch: char) {
+        let ch = if self.cursor.line_drawing {
+            line_drawing(ch)
+        } else {
+            ch
+        };
+        let width = ch.width().unwrap_or(0);
+        if width == 0 {
+            // Combining marks join the character before the cursor.
+            let end = if self.cursor.pending_wrap {
+                self.cols
+            } else {
+                self.cursor.col
+            };
+            if let Some(cell) = self.grid[self.cursor.row][..end]
+                .iter_mut()
+                .rev()
+                .find(|c| !c.is_continuation())
+            {
+                cell.grapheme.push(ch);
+            }
+            return;
+        }
+        if self.cursor.pending_wrap || (width == 2 && self.cursor.col + 1 >= self.cols) {
+            if self.autowrap {
+                self.cursor.col = 0;
+                self.linefeed();
+            } else if width == 2 {
+                self.cursor.col = self.cols.saturating_sub(2);
+            }
+            self.cursor.pending_wrap = false;
+        }
+        if width > self.cols {
+            return;
+        }
+
+        let col = self.cursor.col;
+        let blank = self.blank();
+        let row = &mut self.grid[self.cursor.row];
+        if self.insert {
+            for _ in 0..width {
+                row.insert(col, blank.clone());
+            }
+            row.truncate(self.cols);
+            fix_wide_tail(row);
+        }
+        // Blank the halves of any wide characters being overwritten.
+        if row[col].is_continuation() && col > 0 {
+            row[col - 1] = blank.clone();
+        }
+        let end = col + width;
+        if row[end - 1].is_wide() && end < row.len() {
+            row[end] = blank;
+        }
+        row[col] = Cell::new(ch.to_string(), self.cursor.style.clone());
+        if width == 2 {
+            row[col + 1] = Cell::continuation();
+        }
+
+        if end >= self.cols {
+            self.cursor.col = self.cols - 1;
+            self.cursor.pending_wrap = self.autowrap;
+        } else {
+            self.cursor.col = end;
+        }
+    }
+
+    /// A cleared cell, which keeps the current background colour.
+    fn blank(&self) -> Cell {
+        Cell::new(
+            " ",
+            Style {
+                bg: self.cursor.style.bg.clone(),
+                ..Style::default()
+            },
+        )
+    }
+
+    fn blank_row(&self) -> Vec<Cell> {
+        vec![self.blank(); self.cols]
+    }
+
+    /// Move down a row, scrolling the region at its bottom margin.
+    fn linefeed(&mut self) {
+        self.cursor.pending_wrap = false;
+        if self.cursor.row == self.bottom {
+            self.scroll_up(1);
+        } else if self.cursor.row + 1 < self.rows {
+            self.cursor.row += 1;
+        }
+    }
+
+    /// Move up a row, scrolling the region down at its top margin.
+    fn reverse_index(&mut self) {
+        self.cursor.pending_wrap = false;
+        if self.cursor.row == self.top {
+            self.scroll_down(1);
+        } else {
+            self.cursor.row = self.cursor.row.saturating_sub(1);
+        }
+    }
+
+    /// Scroll the region up by `n` rows, adding blank rows at the bottom.
+    fn scroll_up(&mut self, n: usize) {
+        let n = n.min(self.bottom - self.top + 1);
+        let blank = self.blank_row();
+        self.grid.drain(self.top..self.top + n);
+        for _ in 0..n {
+            self.grid.insert(self.bottom + 1 - n, blank.clone());
+        }
+    }
+
+    /// Scroll the region down by `n` rows, adding blank rows at the top.
+    fn scroll_down(&mut self, n: usize) {
+        let n = n.min(self.bottom - self.top + 1);
+        let blank = self.blank_row();
+        self.grid.drain(self.bottom + 1 - n..=self.bottom);
+        for _ in 0..n {
+            self.grid.insert(self.top, blank.clone());
+        }
+    }
+
+    fn restore_cursor(&mut self) {
+        self.cursor = self.saved.clone();
+        clamp_cursor(&mut self.cursor, self.cols, self.rows);
+    }
+
+    /// Handle a CSI sequence with final byte `action`.
+    fn csi(&mut self, params: &str, action: char) {
+        let private = params.starts_with('?');
+        if let Some(marker) = params.chars().next()
+            && matches!(marker, '>' | '<' | '=')
+        {
+            return;
+        }
+        let body = params.trim_start_matches('?');
+        if action == 'm' {
+            if !private {
+                apply_sgr(body, &mut self.cursor.style);
+            }
+            return;
+        }
+        let nums: Vec<usize> = body
+            .split(';')
+            .map(|p| p.parse::<u16>().map_or(0, usize::from))
+            .collect();
+        let arg = |i: usize| nums.get(i).copied().unwrap_or(0);
+        let count = arg(0).max(1);
+        let (cols, rows) = (self.cols, self.rows);
+        if !matches!(action, 'h' | 'l' | 'n' | 'c' | 's' | 'u') {
+            self.cursor.pending_wrap = false;
+        }
+        match action {
+            'A' => {
+                self.cursor.row = self
+                    .cursor
+                    .row
+                    .saturating_sub(count)
+                    .max(self.top_for_cursor())
+            }
+            'B' | 'e' => self.cursor.row = (self.cursor.row + count).min(self.bottom_for_cursor()),
+            'C' | 'a' => self.cursor.col = (self.cursor.col + count).min(cols - 1),
+            'D' => self.cursor.col = self.cursor.col.saturating_sub(count),
+            'E' => {
+                self.cursor.row = (self.cursor.row + count).min(self.bottom_for_cursor());
+                self.cursor.col = 0;
+            }
+            'F' => {
+                self.cursor.row = self
+                    .cursor
+                    .row
+                    .saturating_sub(count)
+                    .max(self.top_for_cursor());
+                self.cursor.col = 0;
+            }
+            'G' | '`' => self.cursor.col = (count - 1).min(cols - 1),
+            'd' => self.cursor.row = (count - 1).min(rows - 1),
+            'H' | 'f' => {
+                self.cursor.row = (count - 1).min(rows - 1);
+                self.cursor.col = (arg(1).max(1) - 1).min(cols - 1);
+            }
+            'J' => self.erase_display(arg(0)),
+            'K' => self.erase_line(arg(0)),
+            'X' => {
+                let blank = self.blank();
+                let (col, row) = (self.cursor.col, &mut self.grid[self.cursor.row]);
+                let end = (col + count).min(cols);
+                row[col..end].fill(blank);
+                fix_wide_edges(row, col, end);
+            }
+            '@' => {
+                let blank = self.blank();
+                let col = self.cursor.col;
+                let row = &mut self.grid[self.cursor.row];
+                for _ in 0..count.min(cols - col) {
+                    row.insert(col, blank.clone());
+                }
+                row.truncate(cols);
+                fix_wide_tail(row);
+            }
+            'P' => {
+                let blank = self.blank();
+                let col = self.cursor.col;
+                let row = &mut self.grid[self.cursor.row];
+                let n = count.min(cols - col);
+                row.drain(col..col + n);
+                row.resize(cols, blank);
+                if row[col].is_continuation() {
+                    row[col] = Cell::blank();
+                }
+            }
+            'L' | 'M' if (self.top..=self.bottom).contains(&self.cursor.row) => {
+                let top = self.top;
+                self.top = self.cursor.row;
+                if action == 'L' {
+                    self.scroll_down(count);
+                } else {
+                    self.scroll_up(count);
+                }
+                self.top = top;
+                self.cursor.col = 0;
+            }
+            'S' => self.scroll_up(count),
+            'T' if nums.len() <= 1 => self.scroll_down(count),
+            'r' if !private => {
+                let top = arg(0).max(1) - 1;
+                let bottom = if arg(1) == 0 { rows } else { arg(1).min(rows) } - 1;
+                if top < bottom {
+                    self.top = top;
+                    self.bottom = bottom;
+                    self.cursor.row = 0;
+                    self.cursor.col = 0;
+                }
+            }
+            's' if !private => self.saved = self.cursor.clone(),
+            'u' if !private => self.restore_cursor(),
+            'h' | 'l' => {
+                let on = action == 'h';
+                for &mode in &nums {
+                    if private {
+                        self.set_private_mode(mode, on);
+                    } else if mode == 4 {
+                        self.insert = on;
+                    }
+                }
+            }
+            'n' if !private => match arg(0) {
+                5 => self.responses.extend_from_slice(b"\x1b[0n"),
+                6 => {
+                    let reply = format!("\x1b[{};{}R", self.cursor.row + 1, self.cursor.col + 1);
+                    self.responses.extend_from_slice(reply.as_bytes());
+                }
+                _ => {}
+            },
+            'c' if !private && arg(0) == 0 => self.responses.extend_from_slice(b"\x1b[?1;2c"),
+            _ => {}
+        }
+    }
+
+    /// Upper limit for relative cursor movement: the region's top margin
+    /// when the cursor is inside it.
+    fn top_for_cursor(&self) -> usize {
+        if self.cursor.row >= self.top {
+            self.top
+        } else {
+            0
+        }
+    }
+
+    /// Lower limit for relative cursor movement.
+    fn bottom_for_cursor(&self) -> usize {
+        if self.cursor.row <= self.bottom {
+            self.bottom
+        } else {
+            self.rows - 1
+        }
+    }
+
+    fn set_private_mode(&mut self, mode: usize, on: bool) {
+        match mode {
+            1 => self.app_cursor_keys = on,
+            7 => self.autowrap = on,
+            25 => self.cursor_visible = on,
+            47 | 1047 | 1049 => self.set_alternate(on, mode == 1049),
+            1000 => self.mouse = if on { MouseMode::Press } else { MouseMode::Off },
+            1002 => self.mouse = if on { MouseMode::Drag } else { MouseMode::Off },
+            1003 => self.mouse = if on { MouseMode::Any } else { MouseMode::Off },
+            1006 => self.sgr_mouse = on,
+            2004 => self.bracketed_paste = on,
+            _ => {}
+        }
+    }
+
+    /// Switch to a blank alternate screen or back to the primary one.
+    fn set_alternate(&mut self, on: bool, save_cursor: bool) {
+        match (on, self.primary.take()) {
+            (true, None) => {
+                let grid = std::mem::replace(&mut self.grid, blank_grid(self.cols, self.rows));
+                self.primary = Some((grid, self.cursor.clone()));
+            }
+            (false, Some((grid, cursor))) => {
+                self.grid = grid;
+                if save_cursor {
+                    self.cursor = cursor;
+                }
+            }
+            (_, primary) => self.primary = primary,
+        }
+        self.top = 0;
+        self.bottom = self.rows - 1;
+    }
+
+    fn erase_display(&mut self, mode: usize) {
+        let blank = self.blank_row();
+        match mode {
+            0 => {
+                self.erase_line(0);
+                for row in &mut self.grid[self.cursor.row + 1..] {
+                    row.clone_from(&blank);
+                }
+            }
+            1 => {
+                for row in &mut self.grid[..self.cursor.row] {
+                    row.clone_from(&blank);
+                }
+                self.erase_line(1);
+            }
+            _ => self.grid.fill(blank),
+        }
+    }
+
+    fn erase_line(&mut self, mode: usize) {
+        let blank = self.blank();
+        let col = self.cursor.col;
+        let row = &mut self.grid[self.cursor.row];
+        let range = match mode {
+            0 => col..row.len(),
+            1 => 0..(col + 1).min(row.len()),
+            _ => 0..row.len(),
+        };
+        let (start, end) = (range.start, range.end);
+        row[range].fill(blank);
+        fix_wide_edges(row, start, end);
+    }
+
+    /// Handle an OSC string; only the window title matters.
+    fn osc(&mut self, data: &str) {
+        if let Some((kind, text)) = data.split_once(';')
+            && matches!(kind, "0" | "2")
+        {
+            self.title = text.to_string();
+        }
+    }
+}
+
+fn blank_grid(cols: usize, rows: usize) -> Vec<Vec<Cell>> {
+    vec![vec![Cell::blank(); cols]; rows]
+}
+
+/// Resize a grid, first dropping `drop_top` rows from the top.
+fn resize_grid(grid: &mut Vec<Vec<Cell>>, cols: usize, rows: usize, drop_top: usize) {
+    grid.drain(..drop_top.min(grid.len()));
+    grid.resize(rows, vec![Cell::blank(); cols]);
+    for row in grid {
+        row.resize(cols, Cell::blank());
+        fix_wide_tail(row);
+    }
+}
+
+fn clamp_cursor(cursor: &mut Cursor, cols: usize, rows: usize) {
+    cursor.col = cursor.col.min(cols - 1);
+    cursor.row = cursor.row.min(rows - 1);
+    cursor.pending_wrap = false;
+}
+
+/// Blank a wide character cut in half at the end of a row.
+fn fix_wide_tail(row: &mut [Cell]) {
+    if let Some(last) = row.last_mut()
+        && last.is_wide()
+    {
+        *last = Cell::blank();
+    }
+}
+
+/// Blank the halves of wide characters cut by clearing `start..end`.
+fn fix_wide_edges(row: &mut [Cell], start: usize, end: usize) {
+    if start > 0 && row.get(start).is_some_and(|c| c.grapheme == " ") && row[start - 1].is_wide() {
+        row[start - 1] = Cell::blank();
+    }
+    if row.get(end).is_some_and(Cell::is_continuation) {
+        row[end] = Cell::blank();
+    }
+}
+
+/// Map a character through the DEC special graphics set.
+fn line_drawing(ch: char) -> char {
+    match ch {
+        'j' => '┘',
+        'k' => '┐',
+        'l' => '┌',
+        'm' => '└',
+        'n' => '┼',
+        'q' => '─',
+        't' => '├',
+        'u' => '┤',
+        'v' => '┴',
+        'w' => '┬',
+        'x' => '│',
+        'a' => '▒',
+        '`' => '◆',
+        'f' => '°',
+        'g' => '±',
+        '~' => '·',
+        'y' => '≤',
+        'z' => '≥',
+        '|' => '≠',
+        '}' => '£',
+        _ => ch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{Color, NamedColor};
+
+    fn screen(cols: usize, rows: usize, output: &str) -> VtScreen {
+        let mut screen = VtScreen::new(cols, rows);
+        screen.feed(output.as_bytes());
+        screen
+    }
+
+    #[test]
+    fn prints_and_wraps() {
+        let s = screen(5, 3, "hello world\r\nx");
+        assert_eq!(s.text(), " worl\nd\nx");
+        // A full last row scrolls once the next character arrives.
+        let s = screen(3, 2, "abcdef\r\nz");
+        assert_eq!(s.text(), "def\nz");
+        // The cursor waits at the margin until the next character.
+        let s = screen(3, 2, "abc");
+        assert_eq!(s.cursor(), (2, 0));
+    }
+
+    #[test]
+    fn cursor_movement_and_erasing() {
+        let s = screen(
+            10,
+            3,
+            "1234567890\x1b[2;3HAB\x1b[1;5H\x1b[K\x1b[3;1Hxyz\x1b[2D\x1b[1K",
+        );
+        assert_eq!(s.text(), "1234\n  AB\n  z");
+        let s = screen(6, 3, "aaaaaa\r\nbbbbbb\r\ncccccc\x1b[2;3H\x1b[J");
+        assert_eq!(s.text(), "aaaaaa\nbb\n");
+        let s = screen(6, 1, "abcdef\x1b[1;2H\x1b[2P\x1b[2@\x1b[1X");
+        assert_eq!(s.text(), "a  def");
+    }
+
+    #[test]
+    fn scroll_region_and_line_editing() {
+        // Region rows 2-3; a linefeed at its bottom leaves rows 1 and 4.
+        let s = screen(4, 4, "top\r\nr2\r\nr3\r\nbot\x1b[2;3r\x1b[3;1H\nnew");
+        assert_eq!(s.text(), "top\nr3\nnew\nbot");
+        let s = screen(4, 4, "a\r\nb\r\nc\r\nd\x1b[2;1H\x1b[L");
+        assert_eq!(s.text(), "a\n\nb\nc");
+        let s = screen(4, 4, "a\r\nb\r\nc\r\nd\x1b[2;1H\x1b[2M");
+        assert_eq!(s.text(), "a\nd\n\n");
+        let s = screen(4, 3, "a\r\nb\r\nc\x1b[1;1H\x1bMz");
+        assert_eq!(s.text(), "z\na\nb");
+    }
+
+    #[test]
+    fn alternate_screen_keeps_the_primary() {
+        let mut s = screen(8, 2, "shell$ ");
+        s.feed(b"\x1b[?1049h\x1b[Hfull screen");
+        assert!(s.is_alternate());
+        assert_eq!(s.text(), "full scr\neen");
+        s.feed(b"\x1b[?1049l");
+        assert!(!s.is_alternate());
+        assert_eq!(s.text(), "shell$\n");
+        assert_eq!(s.cursor(), (7, 0));
+    }
+
+    #[test]
+    fn styles_modes_and_replies() {
+        let s = screen(10, 2, "\x1b[1;31mred\x1b[0m\x1b[44m\x1b[K");
+        assert_eq!(s.grid()[0][0].style.fg, Some(Color::Named(NamedColor::Red)));
+        assert!(s.grid()[0][0].style.bold);
+        assert_eq!(
+            s.grid()[0][5].style.bg,
+            Some(Color::Named(NamedColor::Blue))
+        );
+
+        let mut s = screen(10, 2, "\x1b[?1h\x1b[?25l\x1b[?1002h\x1b[?1006h\x1b[?2004h");
+        assert!(s.app_cursor_keys && !s.cursor_visible && s.sgr_mouse && s.bracketed_paste);
+        assert_eq!(s.mouse, MouseMode::Drag);
+        s.feed(b"ab\x1b[6n\x1b[c\x1b]0;my title\x07");
+        assert_eq!(s.responses, b"\x1b[1;3R\x1b[?1;2c");
+        assert_eq!(s.title, "my title");
+    }
+
+    #[test]
+    fn unterminated_strings_stay_bounded() {
+        let pending = |s: &VtScreen| match &s.state {
+            State::Csi(params) => params.len(),
+            State::Osc(data) | State::StringEscape(Some(data)) => data.len(),
+            _ => 0,
+        };
+        let mut s = VtScreen::new(10, 2);
+        s.feed(b"\x1b]0;");
+        let chunk = [b'x'; 8192];
+        for _ in 0..256 {
+            s.feed(&chunk);
+            assert!(pending(&s) <= MAX_OSC_LEN);
+        }
+        assert!(s.title.is_empty());
+        s.feed(b"\x1b[2J\x1b]0;ok\x07");
+        assert_eq!(s.title, "ok");
+
+        s.feed(b"\x1b[");
+        for _ in 0..256 {
+            s.feed(&[b'1', b';'].repeat(4096));
+            assert!(pending(&s) <= MAX_CSI_LEN);
+        }
+    }
+
+    #[test]
+    fn utf8_and_sequences_split_across_reads() {
+        let mut s = VtScreen::new(10, 1);
+        let bytes = "\x1b[32mé日\x1b(0qx\x1b(B".as_bytes();
+        for chunk in bytes.chunks(1) {
+            s.feed(chunk);
+        }
+        assert_eq!(s.text(), "é日─│");
+        assert_eq!(
+            s.grid()[0][1].style.fg,
+            Some(Color::Named(NamedColor::Green))
+        );
+        assert!(s.grid()[0][2].is_continuation());
+        s.feed(&[0xff, b'!']);
+        assert_eq!(s.text(), "é日─│\u{fffd}!");
+    }
+
+    #[test]
+    fn resize_keeps_the_cursor_row() {
+        let mut s = screen(6, 4, "1\r\n2\r\n3\r\n4");
+        s.resize(3, 2);
+        assert_eq!(s.text(), "3\n4");
+        assert_eq!(s.cursor(), (1, 1));
+        s.resize(4, 3);
+        assert_eq!(s.size(), (4, 3));
+        assert_eq!(s.text(), "3\n4\n");
+    }
+}
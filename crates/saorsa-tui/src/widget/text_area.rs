@@ -1,28 +1,88 @@
-//! Multi-line text editing widget with cursor, selection, soft wrap,
-//! line numbers, and syntax highlighting.
+//! Multi-line text editing widget with cursors, selection, soft wrap,
+//! find and replace, line numbers, and syntax highlighting.
+
+mod search;
+
+pub use search::SearchOptions;
 
 use crate::buffer::ScreenBuffer;
 use crate::cell::Cell;
+use crate::clipboard::Clipboard;
+use crate::color::{Color, NamedColor};
 use crate::cursor::{CursorPosition, CursorState, Selection};
-use crate::event::{Event, KeyCode, KeyEvent, Modifiers};
-use crate::geometry::Rect;
-use crate::highlight::{Highlighter, NoHighlighter};
+use crate::error::Result;
+use crate::event::{Event, KeyCode, KeyEvent, Modifiers, MouseEvent, MouseEventKind};
+use crate::geometry::{Position, Rect};
+use crate::highlight::{HighlightSpan, Highlighter, NoHighlighter, SyntaxTheme, TextEdit};
+use crate::keymap::{EditMode, Keymap, KeymapKind, TextEditor};
 use crate::style::Style;
 use crate::text_buffer::TextBuffer;
 use crate::undo::{EditOperation, UndoStack};
 use crate::wrap::wrap_line;
+use search::Search;
+use std::cell::Cell as StdCell;
+use std::ops::Range;
 use unicode_width::UnicodeWidthChar;
 
 use super::{EventResult, InteractiveWidget, Widget};
 
+/// Opening brackets and their closers, for auto-indent.
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+/// Rows scrolled per mouse wheel notch.
+const WHEEL_LINES: usize = 3;
+
+/// A cursor movement, applied to every cursor by
+/// [`TextArea::move_cursor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextMotion {
+    /// One character left, wrapping to the previous line.
+    Left,
+    /// One character right, wrapping to the next line.
+    Right,
+    /// One visual row up.
+    Up,
+    /// One visual row down.
+    Down,
+    /// To the start of the previous word.
+    WordLeft,
+    /// To the end of the next word.
+    WordRight,
+    /// To the start of the line.
+    LineStart,
+    /// To the end of the line.
+    LineEnd,
+    /// To the start of the buffer.
+    BufferStart,
+    /// To the end of the buffer.
+    BufferEnd,
+    /// One viewport up.
+    PageUp,
+    /// One viewport down.
+    PageDown,
+}
+
 /// A multi-line text editing widget.
 ///
 /// Supports cursor movement, text selection, undo/redo, soft wrapping,
-/// optional line numbers, and pluggable syntax highlighting.
+/// optional line numbers, and pluggable syntax highlighting. Copy, cut
+/// and paste go through a [`Clipboard`], which also forwards copies to the
+/// system clipboard when shared with the app runtime.
+///
+/// Beyond the basics it edits like a code editor: word-wise motion and
+/// deletion (Ctrl+arrows, Ctrl+Backspace/Delete), paging, Shift-selection
+/// and mouse drag selection, incremental find (Ctrl+F, F3) and regex
+/// replace (Ctrl+H), auto-indent, bracket matching and multiple cursors
+/// (Alt+click, Ctrl+Alt+Up/Down, Ctrl+D). Edits made at several cursors
+/// at once undo as one step, and consecutive typing undoes a word at a
+/// time.
+///
+/// With [`with_keymap`](Self::with_keymap) it edits modally with Vim
+/// bindings or with Emacs bindings instead; see [`crate::keymap`].
 pub struct TextArea {
     /// The text content.
     pub buffer: TextBuffer,
-    /// Cursor and selection state.
+    /// Cursor and selection state of the primary cursor.
     pub cursor: CursorState,
     /// Undo/redo history.
     pub undo_stack: UndoStack,
@@ -39,6 +99,77 @@ pub struct TextArea {
     pub selection_style: Style,
     /// Style for line numbers.
     pub line_number_style: Style,
+    /// Style for search matches.
+    pub match_style: Style,
+    /// Style for the current search match.
+    pub current_match_style: Style,
+    /// Style merged onto a bracket and its partner under the cursor.
+    pub bracket_style: Style,
+    /// Style for the find bar.
+    pub find_bar_style: Style,
+    clipboard: Clipboard,
+    /// Cursors other than the primary one.
+    extra_cursors: Vec<CursorState>,
+    soft_wrap: bool,
+    auto_indent: bool,
+    indent_unit: String,
+    /// Display columns scrolled off the left edge when not wrapping.
+    h_scroll: usize,
+    search: Option<Search>,
+    find_bar: Option<FindBar>,
+    /// The area of the last render, for mouse hit testing and paging.
+    last_area: StdCell<Rect>,
+    /// Where a mouse drag selection started.
+    drag_anchor: Option<CursorPosition>,
+    keymap: Keymap,
+}
+
+/// The find bar's input fields.
+#[derive(Default)]
+struct FindBar {
+    query: String,
+    replacement: String,
+    /// Whether the replace field is shown.
+    replace: bool,
+    /// Whether typing goes to the replace field.
+    editing_replacement: bool,
+}
+
+/// A replacement of a range of characters, positioning a cursor
+/// `cursor` characters into the new text.
+struct Edit {
+    range: Range<usize>,
+    text: String,
+    cursor: usize,
+}
+
+impl Edit {
+    fn delete(range: Range<usize>) -> Self {
+        Self {
+            range,
+            text: String::new(),
+            cursor: 0,
+        }
+    }
+
+    fn insert(range: Range<usize>, text: String) -> Self {
+        let cursor = text.chars().count();
+        Self {
+            range,
+            text,
+            cursor,
+        }
+    }
+}
+
+/// Where the text region of the widget sits within its area.
+#[derive(Clone, Copy)]
+struct View {
+    x: u16,
+    y: u16,
+    gutter: usize,
+    width: usize,
+    height: usize,
 }
 
 impl TextArea {
@@ -55,6 +186,26 @@ impl TextArea {
             cursor_style: Style::new().reverse(true),
             selection_style: Style::new().reverse(true),
             line_number_style: Style::new().dim(true),
+            match_style: Style::new()
+                .fg(Color::Named(NamedColor::Black))
+                .bg(Color::Named(NamedColor::Yellow)),
+            current_match_style: Style::new()
+                .fg(Color::Named(NamedColor::Black))
+                .bg(Color::Named(NamedColor::BrightYellow))
+                .bold(true),
+            bracket_style: Style::new().bold(true).underline(true),
+            find_bar_style: Style::new().reverse(true),
+            clipboard: Clipboard::new(),
+            extra_cursors: Vec::new(),
+            soft_wrap: true,
+            auto_indent: true,
+            indent_unit: "    ".to_string(),
+            h_scroll: 0,
+            search: None,
+            find_bar: None,
+            last_area: StdCell::new(Rect::default()),
+            drag_anchor: None,
+            keymap: Keymap::default(),
         }
     }
 
@@ -66,12 +217,21 @@ impl TextArea {
     }
 
     /// Set a custom syntax highlighter.
+    ///
+    /// The highlighter receives the current text, then each edit as it
+    /// happens.
     #[must_use]
-    pub fn with_highlighter(mut self, h: Box<dyn Highlighter>) -> Self {
+    pub fn with_highlighter(mut self, mut h: Box<dyn Highlighter>) -> Self {
+        h.set_text(&self.buffer.to_string());
         self.highlighter = h;
         self
     }
 
+    /// Restyle the highlighter with a syntax theme.
+    pub fn set_syntax_theme(&mut self, theme: &SyntaxTheme) {
+        self.highlighter.set_theme(theme);
+    }
+
     /// Set the base text style.
     #[must_use]
     pub fn with_style(mut self, s: Style) -> Self {
@@ -100,6 +260,78 @@ impl TextArea {
         self
     }
 
+    /// Enable or disable soft wrapping (enabled by default).
+    ///
+    /// Without it long lines scroll horizontally to follow the cursor.
+    #[must_use]
+    pub fn with_soft_wrap(mut self, wrap: bool) -> Self {
+        self.set_soft_wrap(wrap);
+        self
+    }
+
+    /// Enable or disable soft wrapping.
+    pub fn set_soft_wrap(&mut self, wrap: bool) {
+        self.soft_wrap = wrap;
+        self.h_scroll = 0;
+        self.cursor.preferred_col = None;
+    }
+
+    /// Whether long lines are soft wrapped.
+    pub fn soft_wrap(&self) -> bool {
+        self.soft_wrap
+    }
+
+    /// Enable or disable auto-indent on Enter (enabled by default).
+    #[must_use]
+    pub fn with_auto_indent(mut self, auto_indent: bool) -> Self {
+        self.auto_indent = auto_indent;
+        self
+    }
+
+    /// Set the text inserted for one level of indentation (four spaces by
+    /// default).
+    #[must_use]
+    pub fn with_indent_unit(mut self, unit: &str) -> Self {
+        self.indent_unit = unit.to_string();
+        self
+    }
+
+    /// Edit with a Vim or Emacs keymap instead of the standard bindings.
+    #[must_use]
+    pub fn with_keymap(mut self, kind: KeymapKind) -> Self {
+        self.set_keymap(kind);
+        self
+    }
+
+    /// Switch keymaps, closing any change the old one had open.
+    pub fn set_keymap(&mut self, kind: KeymapKind) {
+        let mut old = std::mem::replace(&mut self.keymap, Keymap::new(kind));
+        old.finish(self);
+    }
+
+    /// The keymap, for its mode and pending keys.
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// The current editing mode, also exposed to TCSS as a pseudo-class
+    /// (`TextArea:vim-normal`).
+    pub fn edit_mode(&self) -> EditMode {
+        self.keymap.mode()
+    }
+
+    /// Use a shared clipboard (e.g. [`crate::app::App::clipboard`]).
+    #[must_use]
+    pub fn with_clipboard(mut self, clipboard: Clipboard) -> Self {
+        self.clipboard = clipboard;
+        self
+    }
+
+    /// The clipboard used for copy, cut and paste.
+    pub fn clipboard(&self) -> &Clipboard {
+        &self.clipboard
+    }
+
     /// Get the current text content as a string.
     pub fn text(&self) -> String {
         self.buffer.to_string()
@@ -107,133 +339,198 @@ impl TextArea {
 
     // --- Editing operations ---
 
-    /// Insert a character at the cursor position.
+    /// Insert a character at each cursor, replacing any selections.
     pub fn insert_char(&mut self, ch: char) {
-        self.delete_selection_if_active();
-        let pos = self.cursor.position;
-        self.buffer.insert_char(pos.line, pos.col, ch);
-        self.undo_stack.push(EditOperation::Insert {
-            pos,
-            text: ch.to_string(),
-        });
-        self.highlighter.on_edit(pos.line);
-        // Advance cursor
-        if ch == '\n' {
-            self.cursor.position.line += 1;
-            self.cursor.position.col = 0;
-        } else {
-            self.cursor.position.col += 1;
-        }
-        self.cursor.preferred_col = None;
+        let merge = ch != '\n' && !self.has_selection();
+        let text = ch.to_string();
+        self.edit_cursors(merge, |_, range, _| Some(Edit::insert(range, text.clone())));
     }
 
-    /// Insert a string at the cursor position.
+    /// Insert a string at each cursor, replacing any selections.
     pub fn insert_str(&mut self, text: &str) {
-        self.delete_selection_if_active();
-        let pos = self.cursor.position;
-        self.buffer.insert_str(pos.line, pos.col, text);
-        self.undo_stack.push(EditOperation::Insert {
-            pos,
-            text: text.to_string(),
+        self.edit_cursors(false, |_, range, _| {
+            Some(Edit::insert(range, text.to_string()))
         });
-        self.highlighter.on_edit(pos.line);
+    }
 
-        // Advance cursor past inserted text
-        for ch in text.chars() {
-            if ch == '\n' {
-                self.cursor.position.line += 1;
-                self.cursor.position.col = 0;
+    /// Delete the character before each cursor (backspace), or the
+    /// selections if there are any.
+    pub fn delete_backward(&mut self) {
+        let merge = !self.has_selection();
+        self.edit_cursors(merge, |buffer, range, _| {
+            if !range.is_empty() {
+                return Some(Edit::delete(range));
+            }
+            let idx = range.start;
+            let mut start = idx.checked_sub(1)?;
+            if buffer.char_at(start) == Some('\n')
+                && start > 0
+                && buffer.char_at(start - 1) == Some('\r')
+            {
+                start -= 1;
+            }
+            Some(Edit::delete(start..idx))
+        });
+    }
+
+    /// Delete the character at each cursor (delete key), or the selections
+    /// if there are any.
+    pub fn delete_forward(&mut self) {
+        let merge = !self.has_selection();
+        self.edit_cursors(merge, |buffer, range, _| {
+            if !range.is_empty() {
+                return Some(Edit::delete(range));
+            }
+            let idx = range.start;
+            let end = match buffer.char_at(idx)? {
+                '\r' if buffer.char_at(idx + 1) == Some('\n') => idx + 2,
+                _ => idx + 1,
+            };
+            Some(Edit::delete(idx..end))
+        });
+    }
+
+    /// Delete from each cursor back to the start of the previous word, or
+    /// the selections if there are any.
+    pub fn delete_word_backward(&mut self) {
+        self.edit_cursors(false, |buffer, range, _| {
+            if range.is_empty() {
+                let start = buffer.word_start_before(range.start);
+                (start < range.start).then(|| Edit::delete(start..range.start))
             } else {
-                self.cursor.position.col += 1;
+                Some(Edit::delete(range))
             }
-        }
-        self.cursor.preferred_col = None;
+        });
     }
 
-    /// Delete the character before the cursor (backspace).
-    pub fn delete_backward(&mut self) {
-        if self.delete_selection_if_active() {
-            return;
-        }
-        let pos = self.cursor.position;
-        if pos.col > 0 {
-            // Delete within line
-            let del_col = pos.col - 1;
-            if let Some(line_text) = self.buffer.line(pos.line) {
-                let deleted: String = line_text
-                    .chars()
-                    .nth(del_col)
-                    .map(String::from)
-                    .unwrap_or_default();
-                self.buffer.delete_char(pos.line, del_col);
-                self.undo_stack.push(EditOperation::Delete {
-                    pos: CursorPosition::new(pos.line, del_col),
-                    text: deleted,
-                });
-                self.highlighter.on_edit(pos.line);
-                self.cursor.position.col -= 1;
-            }
-        } else if pos.line > 0 {
-            // Join with previous line
-            let prev_line_len = self.buffer.line_len(pos.line - 1).unwrap_or(0);
-            self.buffer.delete_char(pos.line - 1, prev_line_len);
-            self.undo_stack.push(EditOperation::Delete {
-                pos: CursorPosition::new(pos.line - 1, prev_line_len),
-                text: "\n".to_string(),
-            });
-            self.highlighter.on_edit(pos.line - 1);
-            self.cursor.position.line -= 1;
-            self.cursor.position.col = prev_line_len;
-        }
-        self.cursor.preferred_col = None;
+    /// Delete from each cursor to the end of the next word, or the
+    /// selections if there are any.
+    pub fn delete_word_forward(&mut self) {
+        self.edit_cursors(false, |buffer, range, _| {
+            if range.is_empty() {
+                let end = buffer.word_end_after(range.start);
+                (end > range.start).then(|| Edit::delete(range.start..end))
+            } else {
+                Some(Edit::delete(range))
+            }
+        });
     }
 
-    /// Delete the character at the cursor position (delete key).
-    pub fn delete_forward(&mut self) {
-        if self.delete_selection_if_active() {
-            return;
+    /// Delete the selected text at every cursor, if any.
+    ///
+    /// Returns `true` if a selection was deleted.
+    pub fn delete_selection(&mut self) -> bool {
+        if !self.has_selection() {
+            return false;
         }
-        let pos = self.cursor.position;
-        let line_len = self.buffer.line_len(pos.line).unwrap_or(0);
-        if pos.col < line_len {
-            if let Some(line_text) = self.buffer.line(pos.line) {
-                let deleted: String = line_text
-                    .chars()
-                    .nth(pos.col)
-                    .map(String::from)
-                    .unwrap_or_default();
-                self.buffer.delete_char(pos.line, pos.col);
-                self.undo_stack
-                    .push(EditOperation::Delete { pos, text: deleted });
-                self.highlighter.on_edit(pos.line);
-            }
-        } else if pos.line + 1 < self.buffer.line_count() {
-            // Join with next line
-            self.buffer.delete_char(pos.line, pos.col);
-            self.undo_stack.push(EditOperation::Delete {
-                pos,
-                text: "\n".to_string(),
-            });
-            self.highlighter.on_edit(pos.line);
+        self.edit_cursors(false, |_, range, _| {
+            (!range.is_empty()).then(|| Edit::delete(range))
+        });
+        true
+    }
+
+    /// The text selected by the primary cursor, if any.
+    pub fn selected_text(&self) -> Option<String> {
+        self.cursor
+            .selection
+            .as_ref()
+            .and_then(|sel| self.selected_text_for(sel))
+    }
+
+    /// Copy the selection to the clipboard.
+    ///
+    /// With several cursors the selections are joined with newlines, in
+    /// document order. Returns `true` if there was a selection to copy.
+    pub fn copy(&mut self) -> bool {
+        let texts: Vec<String> = self
+            .sorted_cursors()
+            .iter()
+            .filter_map(|c| c.selection.as_ref())
+            .filter_map(|sel| self.selected_text_for(sel))
+            .collect();
+        if texts.is_empty() {
+            return false;
         }
+        self.clipboard.set_text(texts.join("\n"));
+        true
     }
 
-    /// Delete the currently selected text, if any.
+    /// Copy the selection to the clipboard and delete it.
     ///
-    /// Returns `true` if a selection was deleted.
-    pub fn delete_selection(&mut self) -> bool {
-        self.delete_selection_if_active()
+    /// Returns `true` if there was a selection to cut.
+    pub fn cut(&mut self) -> bool {
+        self.copy() && self.delete_selection()
+    }
+
+    /// Insert the clipboard contents at each cursor, replacing any
+    /// selections.
+    ///
+    /// If the clipboard holds one line per cursor, each cursor gets its
+    /// own line. Returns `true` if the clipboard held text.
+    pub fn paste(&mut self) -> bool {
+        let Some(text) = self.clipboard.text() else {
+            return false;
+        };
+        let cursors = self.extra_cursors.len() + 1;
+        let lines: Vec<&str> = text.lines().collect();
+        if cursors > 1 && lines.len() == cursors {
+            self.edit_cursors(false, |_, range, rank| {
+                Some(Edit::insert(range, lines[rank].to_string()))
+            });
+        } else {
+            self.insert_str(&text);
+        }
+        true
     }
 
-    /// Insert a newline at the cursor position.
+    /// Insert a newline at each cursor.
+    ///
+    /// With auto-indent on, the new line copies the current line's
+    /// indentation, indents one level further after an opening bracket,
+    /// and moves a matching closing bracket onto a line of its own.
     pub fn new_line(&mut self) {
-        self.insert_char('\n');
+        if !self.auto_indent {
+            self.insert_char('\n');
+            return;
+        }
+        let unit = self.indent_unit.clone();
+        self.edit_cursors(false, |buffer, range, _| {
+            let (line, col) = buffer.position_of(range.start);
+            let indent: String = buffer
+                .line(line)
+                .unwrap_or_default()
+                .chars()
+                .take(col)
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .collect();
+            let before = range.start.checked_sub(1).and_then(|i| buffer.char_at(i));
+            let after = buffer.char_at(range.end);
+            let opener = BRACKETS.iter().find(|(open, _)| Some(*open) == before);
+            let mut text = format!("\n{indent}");
+            match opener {
+                Some((_, close)) => {
+                    text.push_str(&unit);
+                    let cursor = text.chars().count();
+                    if after == Some(*close) {
+                        text.push('\n');
+                        text.push_str(&indent);
+                    }
+                    Some(Edit {
+                        range,
+                        text,
+                        cursor,
+                    })
+                }
+                None => Some(Edit::insert(range, text)),
+            }
+        });
     }
 
     /// Undo the last operation.
     pub fn undo(&mut self) {
         if let Some(op) = self.undo_stack.undo() {
             self.apply_operation(&op);
+            self.after_history_change();
         }
     }
 
@@ -241,48 +538,513 @@ impl TextArea {
     pub fn redo(&mut self) {
         if let Some(op) = self.undo_stack.redo() {
             self.apply_operation(&op);
+            self.after_history_change();
+        }
+    }
+
+    // --- Cursors and selection ---
+
+    /// Move every cursor, extending their selections if `extend` is set
+    /// and clearing them otherwise.
+    pub fn move_cursor(&mut self, motion: TextMotion, extend: bool) {
+        let mut cursors = self.take_cursors();
+        for c in &mut cursors {
+            if extend && c.selection.is_none() {
+                c.start_selection();
+            }
+            let (position, preferred_col) = self.motion_target(c, motion);
+            c.position = position;
+            c.preferred_col = preferred_col;
+            if extend {
+                c.extend_selection();
+            } else {
+                c.clear_selection();
+            }
         }
+        self.set_cursors(cursors);
+        self.undo_stack.seal();
+        self.scroll_to_cursor();
+    }
+
+    /// Select the whole buffer, dropping any extra cursors.
+    pub fn select_all(&mut self) {
+        self.extra_cursors.clear();
+        let end = self.end_position();
+        self.cursor.selection = Some(Selection::new(CursorPosition::beginning(), end));
+        self.cursor.position = end;
+        self.cursor.preferred_col = None;
+        self.undo_stack.seal();
+    }
+
+    /// Add a cursor at `pos` (clamped to the buffer).
+    ///
+    /// Typing, deleting and moving then happen at every cursor.
+    pub fn add_cursor(&mut self, pos: CursorPosition) {
+        let pos = self.clamp(pos);
+        if self.cursor_positions().contains(&pos) {
+            return;
+        }
+        self.extra_cursors.push(CursorState::new(pos.line, pos.col));
+        self.undo_stack.seal();
+    }
+
+    /// Add a cursor one row above the topmost cursor (or below the
+    /// bottommost one), at the same column.
+    pub fn add_cursor_vertically(&mut self, up: bool) {
+        let cursors = self.sorted_cursors();
+        let edge = if up { cursors.first() } else { cursors.last() };
+        let Some(edge) = edge else {
+            return;
+        };
+        let motion = if up { TextMotion::Up } else { TextMotion::Down };
+        let (position, preferred_col) = self.motion_target(edge, motion);
+        if position == edge.position {
+            return;
+        }
+        let mut cursor = CursorState::new(position.line, position.col);
+        cursor.preferred_col = preferred_col;
+        if !self.cursor_positions().contains(&position) {
+            self.extra_cursors.push(cursor);
+        }
+        self.undo_stack.seal();
+        self.scroll_to_cursor();
+    }
+
+    /// Select the word at the primary cursor or, if it already has a
+    /// selection, add a cursor selecting the next occurrence of the same
+    /// text.
+    ///
+    /// Returns `true` if anything was selected.
+    pub fn select_next_occurrence(&mut self) -> bool {
+        let Some(needle) = self.selected_text() else {
+            let idx = self.index_of(self.cursor.position);
+            let Some(word) = self.buffer.word_at(idx) else {
+                return false;
+            };
+            self.select_range(word);
+            return true;
+        };
+        let text = self.text();
+        let selected: Vec<Range<usize>> = self
+            .all_cursors()
+            .iter()
+            .filter_map(|c| self.selection_range(c))
+            .collect();
+        let from = selected.iter().map(|r| r.end).max().unwrap_or(0);
+        let from_byte = self.buffer.char_to_byte(from);
+        let found = text[from_byte..]
+            .match_indices(&needle)
+            .map(|(i, _)| from_byte + i)
+            .chain(text[..from_byte].match_indices(&needle).map(|(i, _)| i))
+            .map(|b| {
+                let start = self.buffer.byte_to_char(b);
+                start..start + needle.chars().count()
+            })
+            .find(|r| !selected.contains(r));
+        let Some(range) = found else {
+            return false;
+        };
+        let (start, end) = (self.position(range.start), self.position(range.end));
+        let mut cursor = CursorState::new(end.line, end.col);
+        cursor.selection = Some(Selection::new(start, end));
+        self.extra_cursors.push(cursor);
+        self.undo_stack.seal();
+        true
+    }
+
+    /// Drop all cursors but the primary one.
+    pub fn clear_extra_cursors(&mut self) {
+        self.extra_cursors.clear();
+    }
+
+    /// The positions of all cursors, in document order.
+    pub fn cursor_positions(&self) -> Vec<CursorPosition> {
+        self.sorted_cursors().iter().map(|c| c.position).collect()
+    }
+
+    /// The position of the bracket matching the one at `pos`, if the
+    /// character at `pos` is a bracket with a partner.
+    pub fn matching_bracket(&self, pos: CursorPosition) -> Option<CursorPosition> {
+        let idx = self.index_of(pos);
+        self.buffer.matching_bracket(idx).map(|i| self.position(i))
     }
 
     /// Ensure the cursor is within the visible area, adjusting scroll.
+    ///
+    /// Accounts for soft-wrapped rows, and scrolls horizontally when soft
+    /// wrap is off.
     pub fn ensure_cursor_visible(&mut self, area_height: u16) {
         let height = area_height as usize;
         if height == 0 {
             return;
         }
-        let line = self.cursor.position.line;
-        if line < self.scroll_offset {
-            self.scroll_offset = line;
-        } else if line >= self.scroll_offset + height {
-            self.scroll_offset = line.saturating_sub(height - 1);
+        let pos = self.cursor.position;
+        if pos.line < self.scroll_offset {
+            self.scroll_offset = pos.line;
+        } else {
+            // The lowest first line that still shows the cursor's row.
+            let mut used = row_of(&self.rows(pos.line), pos.col) + 1;
+            let mut first = pos.line;
+            while first > self.scroll_offset {
+                let rows = self.rows(first - 1).len();
+                if used + rows > height {
+                    break;
+                }
+                used += rows;
+                first -= 1;
+            }
+            self.scroll_offset = first;
+        }
+
+        if !self.soft_wrap
+            && let Some(view) = self.last_view()
+            && view.width > 0
+        {
+            let line = self.buffer.line(pos.line).unwrap_or_default();
+            let x = display_offset(&line, 0, pos.col);
+            if x < self.h_scroll {
+                self.h_scroll = x;
+            } else if x >= self.h_scroll + view.width {
+                self.h_scroll = x + 1 - view.width;
+            }
+        }
+    }
+
+    // --- Find and replace ---
+
+    /// Search for `query`, selecting the first match at or after the
+    /// cursor.
+    ///
+    /// Matches stay highlighted (and follow edits) until
+    /// [`clear_search`](Self::clear_search). Returns the number of
+    /// matches, or an error if `query` is an invalid regular expression.
+    pub fn find(&mut self, query: &str, options: SearchOptions) -> Result<usize> {
+        let search = Search::new(query, options);
+        search.check()?;
+        self.search = Some(search);
+        let from = self.search_origin();
+        self.refresh_search(from);
+        self.select_current_match();
+        Ok(self.search_matches().len())
+    }
+
+    /// Select the next match, wrapping around. Returns `false` if there
+    /// are no matches.
+    pub fn find_next(&mut self) -> bool {
+        let from = self.index_of(self.cursor.position);
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+        search.current = search.index_from(from);
+        self.select_current_match()
+    }
+
+    /// Select the previous match, wrapping around. Returns `false` if
+    /// there are no matches.
+    pub fn find_previous(&mut self) -> bool {
+        let to = self.search_origin();
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+        search.current = search.index_before(to);
+        self.select_current_match()
+    }
+
+    /// Replace the current match and select the next one.
+    ///
+    /// For regex searches the replacement may refer to capture groups
+    /// (`$1`, `${name}`). Returns `false` if there was no match to
+    /// replace.
+    pub fn replace_current(&mut self, replacement: &str) -> Result<bool> {
+        let Some(search) = &self.search else {
+            return Ok(false);
+        };
+        search.check()?;
+        let Some(range) = search.current.and_then(|i| search.matches.get(i)).cloned() else {
+            return Ok(false);
+        };
+        let text = search.expand(&self.buffer.slice(range.clone()), replacement);
+        let end = range.start + text.chars().count();
+        self.extra_cursors.clear();
+        self.apply_edits(&[(range, text)], false);
+        let pos = self.position(end);
+        self.cursor = CursorState::new(pos.line, pos.col);
+        self.refresh_search(end);
+        self.select_current_match();
+        Ok(true)
+    }
+
+    /// Replace every match as one undoable edit. Returns the number of
+    /// replacements.
+    pub fn replace_all(&mut self, replacement: &str) -> Result<usize> {
+        let Some(search) = &self.search else {
+            return Ok(0);
+        };
+        search.check()?;
+        let edits: Vec<(Range<usize>, String)> = search
+            .matches
+            .iter()
+            .map(|m| {
+                let text = search.expand(&self.buffer.slice(m.clone()), replacement);
+                (m.clone(), text)
+            })
+            .collect();
+        let cursor = self.index_of(self.cursor.position);
+        let shift: isize = edits
+            .iter()
+            .filter(|(range, _)| range.end <= cursor)
+            .map(|(range, text)| text.chars().count() as isize - range.len() as isize)
+            .sum();
+        self.extra_cursors.clear();
+        self.apply_edits(&edits, false);
+        let pos = self.position(cursor.saturating_add_signed(shift));
+        self.cursor = CursorState::new(pos.line, pos.col);
+        self.refresh_search(self.index_of(pos));
+        Ok(edits.len())
+    }
+
+    /// Stop searching and remove match highlights.
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.find_bar = None;
+    }
+
+    /// The current search matches, as character ranges into the buffer.
+    pub fn search_matches(&self) -> &[Range<usize>] {
+        self.search.as_ref().map_or(&[], |s| s.matches.as_slice())
+    }
+
+    /// Open the find bar, seeded with the selected text if it is a single
+    /// line. With `replace`, the bar also shows a replace field.
+    pub fn open_find(&mut self, replace: bool) {
+        let mut bar = self.find_bar.take().unwrap_or_default();
+        if let Some(selected) = self.selected_text().filter(|s| !s.contains('\n')) {
+            bar.query = selected;
         }
+        bar.replace = replace;
+        bar.editing_replacement = replace && !bar.query.is_empty();
+        self.find_bar = Some(bar);
+        self.update_search_from_bar();
+    }
+
+    /// Whether the find bar is open.
+    pub fn is_find_open(&self) -> bool {
+        self.find_bar.is_some()
     }
 
     // --- Private helpers ---
 
-    /// Delete the active selection text and place cursor at start.
-    fn delete_selection_if_active(&mut self) -> bool {
-        let sel = match self.cursor.selection.take() {
-            Some(s) if !s.is_empty() => s,
-            other => {
-                self.cursor.selection = other;
-                return false;
+    /// Replace the characters in `range` with `text` and tell the
+    /// highlighter.
+    fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        let line = self.position(range.start).line;
+        let start_byte = self.buffer.char_to_byte(range.start);
+        let old_end_byte = self.buffer.char_to_byte(range.end);
+        self.buffer.replace(range, text);
+        self.highlighter.on_edit(&TextEdit {
+            line,
+            start_byte,
+            old_end_byte,
+            text,
+        });
+    }
+
+    /// All cursors, primary first.
+    fn all_cursors(&self) -> Vec<CursorState> {
+        let mut cursors = vec![self.cursor.clone()];
+        cursors.extend(self.extra_cursors.iter().cloned());
+        cursors
+    }
+
+    /// All cursors in document order.
+    fn sorted_cursors(&self) -> Vec<CursorState> {
+        let mut cursors = self.all_cursors();
+        cursors.sort_by_key(|c| c.position);
+        cursors
+    }
+
+    /// Remove all cursors, primary first.
+    fn take_cursors(&mut self) -> Vec<CursorState> {
+        let mut cursors = vec![self.cursor.clone()];
+        cursors.append(&mut self.extra_cursors);
+        cursors
+    }
+
+    /// Store cursors (primary first), dropping any that landed on the
+    /// same position as an earlier one.
+    fn set_cursors(&mut self, cursors: Vec<CursorState>) {
+        let mut iter = cursors.into_iter();
+        if let Some(primary) = iter.next() {
+            self.cursor = primary;
+        }
+        self.extra_cursors.clear();
+        for c in iter {
+            if c.position != self.cursor.position
+                && !self.extra_cursors.iter().any(|e| e.position == c.position)
+            {
+                self.extra_cursors.push(c);
             }
-        };
+        }
+    }
+
+    fn has_selection(&self) -> bool {
+        self.all_cursors()
+            .iter()
+            .any(|c| self.selection_range(c).is_some())
+    }
+
+    fn index_of(&self, pos: CursorPosition) -> usize {
+        self.buffer.char_index(pos.line, pos.col)
+    }
+
+    fn position(&self, idx: usize) -> CursorPosition {
+        let (line, col) = self.buffer.position_of(idx);
+        CursorPosition::new(line, col)
+    }
 
+    fn clamp(&self, pos: CursorPosition) -> CursorPosition {
+        self.position(self.index_of(pos))
+    }
+
+    fn end_position(&self) -> CursorPosition {
+        let last = self.buffer.line_count().saturating_sub(1);
+        CursorPosition::new(last, self.buffer.line_len(last).unwrap_or(0))
+    }
+
+    /// A cursor's non-empty selection as a character range.
+    fn selection_range(&self, c: &CursorState) -> Option<Range<usize>> {
+        let sel = c.selection.as_ref().filter(|s| !s.is_empty())?;
         let (start, end) = sel.ordered();
-        if let Some(selected) = self.selected_text_for(&sel) {
-            self.buffer
-                .delete_range(start.line, start.col, end.line, end.col);
-            self.undo_stack.push(EditOperation::Delete {
-                pos: start,
-                text: selected,
+        Some(self.index_of(start)..self.index_of(end))
+    }
+
+    /// Select a character range with the primary cursor, dropping extra
+    /// cursors.
+    fn select_range(&mut self, range: Range<usize>) {
+        let (start, end) = (self.position(range.start), self.position(range.end));
+        self.extra_cursors.clear();
+        self.cursor.selection = Some(Selection::new(start, end));
+        self.cursor.position = end;
+        self.cursor.preferred_col = None;
+        self.undo_stack.seal();
+    }
+
+    /// Apply an edit at every cursor.
+    ///
+    /// `f` receives the buffer, the cursor's selection (or an empty range
+    /// at the cursor) and the cursor's rank in document order. Edits that
+    /// overlap an earlier one are dropped. All edits undo as one step;
+    /// with `merge`, a single edit may merge with the previous one.
+    fn edit_cursors(
+        &mut self,
+        merge: bool,
+        mut f: impl FnMut(&TextBuffer, Range<usize>, usize) -> Option<Edit>,
+    ) {
+        let cursors = self.take_cursors();
+        let mut order: Vec<usize> = (0..cursors.len()).collect();
+        order.sort_by_key(|&i| cursors[i].position);
+
+        // Edits in document order, with the cursor each belongs to.
+        let mut edits: Vec<(usize, Edit)> = Vec::new();
+        for (rank, &i) in order.iter().enumerate() {
+            let c = &cursors[i];
+            let range = self.selection_range(c).unwrap_or_else(|| {
+                let idx = self.index_of(c.position);
+                idx..idx
             });
-            self.highlighter.on_edit(start.line);
-            self.cursor.position = start;
-            self.cursor.preferred_col = None;
+            if let Some(edit) = f(&self.buffer, range, rank)
+                && edits
+                    .last()
+                    .is_none_or(|(_, prev)| edit.range.start >= prev.range.end)
+            {
+                edits.push((i, edit));
+            }
         }
-        true
+
+        // Where each cursor ends up once every edit is applied.
+        let mut targets: Vec<usize> = Vec::with_capacity(cursors.len());
+        for (i, c) in cursors.iter().enumerate() {
+            let idx = self.index_of(c.position);
+            let mut shift: isize = 0;
+            let mut target = None;
+            for (owner, edit) in &edits {
+                if *owner == i {
+                    target = Some(edit.range.start.saturating_add_signed(shift) + edit.cursor);
+                    break;
+                }
+                if edit.range.start >= idx {
+                    break;
+                }
+                if edit.range.end > idx {
+                    target = Some(edit.range.start.saturating_add_signed(shift));
+                    break;
+                }
+                shift += edit.text.chars().count() as isize - edit.range.len() as isize;
+            }
+            targets.push(target.unwrap_or_else(|| idx.saturating_add_signed(shift)));
+        }
+
+        let plain: Vec<(Range<usize>, String)> =
+            edits.into_iter().map(|(_, e)| (e.range, e.text)).collect();
+        self.apply_edits(&plain, merge);
+
+        let cursors = targets
+            .into_iter()
+            .map(|idx| {
+                let pos = self.position(idx);
+                CursorState::new(pos.line, pos.col)
+            })
+            .collect();
+        self.set_cursors(cursors);
+        self.scroll_to_cursor();
+    }
+
+    /// Apply non-overlapping replacements, given in document order, and
+    /// record them as one undo step.
+    fn apply_edits(&mut self, edits: &[(Range<usize>, String)], merge: bool) {
+        if edits.is_empty() {
+            return;
+        }
+        let mut ops = Vec::with_capacity(edits.len());
+        for (range, text) in edits.iter().rev() {
+            let pos = self.position(range.start);
+            let old = self.buffer.slice(range.clone());
+            self.replace_range(range.clone(), text);
+            ops.push(match (old.is_empty(), text.is_empty()) {
+                (true, _) => EditOperation::Insert {
+                    pos,
+                    text: text.clone(),
+                },
+                (false, true) => EditOperation::Delete { pos, text: old },
+                (false, false) => EditOperation::Replace {
+                    pos,
+                    old_text: old,
+                    new_text: text.clone(),
+                },
+            });
+        }
+        if merge && ops.len() == 1 {
+            if let Some(op) = ops.pop() {
+                self.undo_stack.push_merging(op);
+            }
+        } else {
+            self.undo_stack.begin_group();
+            for op in ops {
+                self.undo_stack.push(op);
+            }
+            self.undo_stack.end_group();
+        }
+        let from = self.search_origin();
+        self.refresh_search(from);
+    }
+
+    /// Clean up after an undo or redo moved the text under the cursors.
+    fn after_history_change(&mut self) {
+        self.extra_cursors.clear();
+        self.cursor.position = self.clamp(self.cursor.position);
+        let from = self.index_of(self.cursor.position);
+        self.refresh_search(from);
+        self.scroll_to_cursor();
     }
 
     /// Get text for a selection (without clearing it).
@@ -291,65 +1053,21 @@ impl TextArea {
             return None;
         }
         let (start, end) = sel.ordered();
-        let mut result = String::new();
-        for line_idx in start.line..=end.line {
-            if let Some(line_text) = self.buffer.line(line_idx) {
-                let ls = if line_idx == start.line { start.col } else { 0 };
-                let le = if line_idx == end.line {
-                    end.col.min(line_text.chars().count())
-                } else {
-                    line_text.chars().count()
-                };
-                let chars: String = line_text
-                    .chars()
-                    .skip(ls)
-                    .take(le.saturating_sub(ls))
-                    .collect();
-                result.push_str(&chars);
-                if line_idx < end.line {
-                    result.push('\n');
-                }
-            }
-        }
-        if result.is_empty() {
-            None
-        } else {
-            Some(result)
-        }
+        let text = self.buffer.slice(self.index_of(start)..self.index_of(end));
+        (!text.is_empty()).then_some(text)
     }
 
     /// Apply an edit operation (for undo/redo).
     fn apply_operation(&mut self, op: &EditOperation) {
         match op {
             EditOperation::Insert { pos, text } => {
-                self.buffer.insert_str(pos.line, pos.col, text);
-                // Move cursor to end of inserted text
-                let mut line = pos.line;
-                let mut col = pos.col;
-                for ch in text.chars() {
-                    if ch == '\n' {
-                        line += 1;
-                        col = 0;
-                    } else {
-                        col += 1;
-                    }
-                }
-                self.cursor.position = CursorPosition::new(line, col);
+                let start = self.index_of(*pos);
+                self.replace_range(start..start, text);
+                self.cursor.position = self.position(start + text.chars().count());
             }
             EditOperation::Delete { pos, text } => {
-                // Calculate end position
-                let mut end_line = pos.line;
-                let mut end_col = pos.col;
-                for ch in text.chars() {
-                    if ch == '\n' {
-                        end_line += 1;
-                        end_col = 0;
-                    } else {
-                        end_col += 1;
-                    }
-                }
-                self.buffer
-                    .delete_range(pos.line, pos.col, end_line, end_col);
+                let start = self.index_of(*pos);
+                self.replace_range(start..start + text.chars().count(), "");
                 self.cursor.position = *pos;
             }
             EditOperation::Replace {
@@ -357,349 +1075,845 @@ impl TextArea {
                 old_text,
                 new_text,
             } => {
-                // Delete old text, insert new
-                let mut end_line = pos.line;
-                let mut end_col = pos.col;
-                for ch in old_text.chars() {
-                    if ch == '\n' {
-                        end_line += 1;
-                        end_col = 0;
-                    } else {
-                        end_col += 1;
-                    }
-                }
-                self.buffer
-                    .delete_range(pos.line, pos.col, end_line, end_col);
-                self.buffer.insert_str(pos.line, pos.col, new_text);
+                let start = self.index_of(*pos);
+                self.replace_range(start..start + old_text.chars().count(), new_text);
                 self.cursor.position = *pos;
             }
+            EditOperation::Group(ops) => {
+                for op in ops {
+                    self.apply_operation(op);
+                }
+            }
         }
+        self.cursor.selection = None;
         self.cursor.preferred_col = None;
     }
-}
 
-impl Default for TextArea {
-    fn default() -> Self {
-        Self::new()
+    /// Where a motion takes a cursor, and the display column to keep for
+    /// further vertical movement.
+    fn motion_target(
+        &self,
+        c: &CursorState,
+        motion: TextMotion,
+    ) -> (CursorPosition, Option<usize>) {
+        let pos = c.position;
+        let idx = self.index_of(pos);
+        let horizontal = |target: usize| (self.position(target), None);
+        match motion {
+            TextMotion::Left => {
+                let mut target = idx.saturating_sub(1);
+                if self.buffer.char_at(target) == Some('\n')
+                    && target > 0
+                    && self.buffer.char_at(target - 1) == Some('\r')
+                {
+                    target -= 1;
+                }
+                horizontal(target)
+            }
+            TextMotion::Right => {
+                let pos_right = if pos.col < self.buffer.line_len(pos.line).unwrap_or(0) {
+                    CursorPosition::new(pos.line, pos.col + 1)
+                } else if pos.line + 1 < self.buffer.line_count() {
+                    CursorPosition::new(pos.line + 1, 0)
+                } else {
+                    pos
+                };
+                (pos_right, None)
+            }
+            TextMotion::WordLeft => horizontal(self.buffer.word_start_before(idx)),
+            TextMotion::WordRight => horizontal(self.buffer.word_end_after(idx)),
+            TextMotion::LineStart => (CursorPosition::new(pos.line, 0), None),
+            TextMotion::LineEnd => (
+                CursorPosition::new(pos.line, self.buffer.line_len(pos.line).unwrap_or(0)),
+                None,
+            ),
+            TextMotion::BufferStart => (CursorPosition::beginning(), None),
+            TextMotion::BufferEnd => (self.end_position(), None),
+            TextMotion::Up => self.vertical(c, 1, true),
+            TextMotion::Down => self.vertical(c, 1, false),
+            TextMotion::PageUp => self.vertical(c, self.page_rows(), true),
+            TextMotion::PageDown => self.vertical(c, self.page_rows(), false),
+        }
     }
-}
 
-impl Widget for TextArea {
-    fn render(&self, area: Rect, buf: &mut ScreenBuffer) {
-        if area.size.width == 0 || area.size.height == 0 {
-            return;
+    /// Move `rows` visual rows up or down, keeping the display column.
+    fn vertical(&self, c: &CursorState, rows: usize, up: bool) -> (CursorPosition, Option<usize>) {
+        let mut line = c.position.line;
+        let mut wrapped = self.rows(line);
+        let mut row = row_of(&wrapped, c.position.col);
+        let x = c.preferred_col.unwrap_or_else(|| {
+            let (text, start) = &wrapped[row];
+            display_offset(text, *start, c.position.col)
+        });
+        let mut moved = false;
+        for _ in 0..rows {
+            if up && row > 0 {
+                row -= 1;
+            } else if up && line > 0 {
+                line -= 1;
+                wrapped = self.rows(line);
+                row = wrapped.len() - 1;
+            } else if !up && row + 1 < wrapped.len() {
+                row += 1;
+            } else if !up && line + 1 < self.buffer.line_count() {
+                line += 1;
+                wrapped = self.rows(line);
+                row = 0;
+            } else {
+                break;
+            }
+            moved = true;
+        }
+        if !moved {
+            return (c.position, Some(x));
         }
+        (CursorPosition::new(line, col_at(&wrapped, row, x)), Some(x))
+    }
 
-        let height = area.size.height as usize;
-        let total_width = area.size.width as usize;
+    /// Rows moved by PageUp/PageDown.
+    fn page_rows(&self) -> usize {
+        self.last_view()
+            .map_or(1, |v| v.height.saturating_sub(1))
+            .max(1)
+    }
+
+    /// The visual rows of a line: its soft-wrapped pieces and their start
+    /// columns, or the whole line when not wrapping.
+    fn rows(&self, line: usize) -> Vec<(String, usize)> {
+        let text = self.buffer.line(line).unwrap_or_default();
+        match self.wrap_width() {
+            Some(width) => wrap_line(&text, width),
+            None => vec![(text, 0)],
+        }
+    }
+
+    /// The width lines wrap at, if soft wrapping and rendered.
+    fn wrap_width(&self) -> Option<usize> {
+        if !self.soft_wrap {
+            return None;
+        }
+        self.last_view().map(|v| v.width).filter(|w| *w > 0)
+    }
+
+    fn last_view(&self) -> Option<View> {
+        let area = self.last_area.get();
+        (!area.is_empty()).then(|| self.view(area))
+    }
 
-        // Calculate gutter width for line numbers
-        let gutter_width = if self.show_line_numbers {
+    /// Lay out the text region and gutter within `area`.
+    fn view(&self, area: Rect) -> View {
+        let gutter = if self.show_line_numbers {
             let digits = crate::wrap::line_number_width(self.buffer.line_count()) as usize;
             digits + 1 // extra space after number
         } else {
             0
         };
+        let bar_rows = self
+            .find_bar
+            .as_ref()
+            .map_or(0, |bar| if bar.replace { 2 } else { 1 });
+        View {
+            x: area.position.x,
+            y: area.position.y,
+            gutter,
+            width: (area.size.width as usize).saturating_sub(gutter),
+            height: (area.size.height as usize).saturating_sub(bar_rows),
+        }
+    }
 
-        let text_width = total_width.saturating_sub(gutter_width);
-        if text_width == 0 {
+    /// Scroll the primary cursor into view, if the widget has been
+    /// rendered.
+    fn scroll_to_cursor(&mut self) {
+        if let Some(view) = self.last_view() {
+            self.ensure_cursor_visible(u16::try_from(view.height).unwrap_or(u16::MAX));
+        }
+    }
+
+    /// The buffer position under a screen cell, clamped to the text.
+    fn position_at(&self, x: u16, y: u16) -> Option<CursorPosition> {
+        let view = self.last_view()?;
+        let target_row = (y.saturating_sub(view.y) as usize).min(view.height.saturating_sub(1));
+        let text_x = view.x as usize + view.gutter;
+        let mut dx = (x as usize).saturating_sub(text_x);
+        if !self.soft_wrap {
+            dx += self.h_scroll;
+        }
+        let mut row = 0;
+        for line in self.scroll_offset..self.buffer.line_count() {
+            let wrapped = self.rows(line);
+            if target_row < row + wrapped.len() {
+                return Some(CursorPosition::new(
+                    line,
+                    col_at(&wrapped, target_row - row, dx),
+                ));
+            }
+            row += wrapped.len();
+        }
+        Some(self.end_position())
+    }
+
+    /// The bracket pair touching the primary cursor: the bracket under it,
+    /// else the one just before it.
+    fn bracket_pair_at_cursor(&self) -> Option<(usize, usize)> {
+        let idx = self.index_of(self.cursor.position);
+        self.buffer
+            .matching_bracket(idx)
+            .map(|p| (idx, p))
+            .or_else(|| {
+                let before = idx.checked_sub(1)?;
+                self.buffer.matching_bracket(before).map(|p| (before, p))
+            })
+    }
+
+    /// Where searches start from: the start of the primary selection, or
+    /// the cursor.
+    fn search_origin(&self) -> usize {
+        self.selection_range(&self.cursor)
+            .map_or_else(|| self.index_of(self.cursor.position), |r| r.start)
+    }
+
+    fn refresh_search(&mut self, from: usize) {
+        if let Some(search) = &mut self.search {
+            search.refresh(&self.buffer, from);
+        }
+    }
+
+    /// Select the current match with the primary cursor.
+    fn select_current_match(&mut self) -> bool {
+        let current = self
+            .search
+            .as_ref()
+            .and_then(|s| s.current.and_then(|i| s.matches.get(i)))
+            .cloned();
+        match current {
+            Some(range) => {
+                self.select_range(range);
+                self.scroll_to_cursor();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-run the search after the find bar's query changed.
+    fn update_search_from_bar(&mut self) {
+        let Some(bar) = &self.find_bar else {
+            return;
+        };
+        let options = self.search.as_ref().map(|s| s.options).unwrap_or_default();
+        self.search = Some(Search::new(&bar.query, options));
+        let from = self.search_origin();
+        self.refresh_search(from);
+        self.select_current_match();
+    }
+}
+
+/// The row of `rows` (as returned by `wrap_line`) showing column `col`.
+fn row_of(rows: &[(String, usize)], col: usize) -> usize {
+    rows.iter()
+        .rposition(|(_, start)| *start <= col)
+        .unwrap_or(0)
+}
+
+/// Display width from the start of a row to column `col` of its line.
+/// Columns past the row's text (whitespace dropped at a wrap) count as
+/// one cell each.
+fn display_offset(text: &str, start: usize, col: usize) -> usize {
+    let n = col.saturating_sub(start);
+    let count = text.chars().count();
+    let width: usize = text
+        .chars()
+        .take(n)
+        .map(|c| UnicodeWidthChar::width(c).unwrap_or(0))
+        .sum();
+    width + n.saturating_sub(count)
+}
+
+/// The column of `rows[row]` at display offset `x`, staying on the row.
+fn col_at(rows: &[(String, usize)], row: usize, x: usize) -> usize {
+    let Some((text, start)) = rows.get(row) else {
+        return 0;
+    };
+    let mut width = 0;
+    let mut col = *start + text.chars().count();
+    for (i, ch) in text.chars().enumerate() {
+        let w = UnicodeWidthChar::width(ch).unwrap_or(0);
+        if width + w > x {
+            col = start + i;
+            break;
+        }
+        width += w;
+    }
+    // The end of a wrapped row is the start of the next: stay before it.
+    match rows.get(row + 1) {
+        Some((_, next)) if col >= *next => next.saturating_sub(1).max(*start),
+        _ => col,
+    }
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for TextArea {
+    fn render(&self, area: Rect, buf: &mut ScreenBuffer) {
+        if area.size.width == 0 || area.size.height == 0 {
             return;
         }
+        self.last_area.set(area);
+        let view = self.view(area);
+        if view.width > 0 {
+            self.render_text(view, area, buf);
+        }
+        self.render_find_bar(area, buf);
+    }
+}
+
+impl TextArea {
+    fn render_text(&self, view: View, area: Rect, buf: &mut ScreenBuffer) {
+        let highlighter = &self.highlighter;
+
+        let right = area.position.x + area.size.width;
+        let gutter_x = view.x + view.gutter as u16;
+        let h_scroll = if self.soft_wrap { 0 } else { self.h_scroll };
+        let cursors = self.all_cursors();
+        let selections: Vec<Range<usize>> = cursors
+            .iter()
+            .filter_map(|c| self.selection_range(c))
+            .collect();
+        let brackets = self.bracket_pair_at_cursor();
 
         // Render visible lines
         let mut row: usize = 0;
         let mut logical_line = self.scroll_offset;
 
-        while row < height && logical_line < self.buffer.line_count() {
+        while row < view.height && logical_line < self.buffer.line_count() {
             let line_text = self.buffer.line(logical_line).unwrap_or_default();
+            let line_start = self.buffer.char_index(logical_line, 0);
 
             // Get highlight spans for this line
-            let spans = self.highlighter.highlight_line(logical_line, &line_text);
+            let spans = highlighter.highlight_line(logical_line, &line_text);
 
-            // Soft wrap the line
-            let wrapped = wrap_line(&line_text, text_width);
+            let wrapped = self.rows(logical_line);
 
             for (wrap_idx, (visual_text, start_col)) in wrapped.iter().enumerate() {
-                if row >= height {
+                if row >= view.height {
                     break;
                 }
 
-                let y = area.position.y + row as u16;
+                let y = view.y + row as u16;
 
                 // Render line number (only for first visual line of each logical line)
                 if self.show_line_numbers {
-                    if wrap_idx == 0 {
-                        let num_str = format!("{}", logical_line + 1);
-                        let padded = format!("{:>width$} ", num_str, width = gutter_width - 1);
-                        for (i, ch) in padded.chars().enumerate() {
-                            let x = area.position.x + i as u16;
-                            if x < area.position.x + area.size.width {
-                                buf.set(
-                                    x,
-                                    y,
-                                    Cell::new(ch.to_string(), self.line_number_style.clone()),
-                                );
-                            }
-                        }
+                    let gutter = if wrap_idx == 0 {
+                        format!("{:>width$} ", logical_line + 1, width = view.gutter - 1)
                     } else {
                         // Blank gutter for continuation lines
-                        for i in 0..gutter_width {
-                            let x = area.position.x + i as u16;
-                            if x < area.position.x + area.size.width {
-                                buf.set(
-                                    x,
-                                    y,
-                                    Cell::new(" ".to_string(), self.line_number_style.clone()),
-                                );
-                            }
+                        " ".repeat(view.gutter)
+                    };
+                    for (i, ch) in gutter.chars().enumerate() {
+                        let x = view.x + i as u16;
+                        if x < right {
+                            buf.set(
+                                x,
+                                y,
+                                Cell::new(ch.to_string(), self.line_number_style.clone()),
+                            );
                         }
                     }
                 }
 
-                // Render text content
-                let gutter_x = area.position.x + gutter_width as u16;
-                let mut col_offset: usize = 0;
+                // Render text content
+                let mut col_offset: usize = 0;
+                for (char_idx, ch) in visual_text.chars().enumerate() {
+                    let buffer_col = start_col + char_idx;
+                    let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+                    let offset = col_offset;
+                    col_offset += ch_width;
+                    if offset < h_scroll {
+                        continue;
+                    }
+                    let x = gutter_x + (offset - h_scroll) as u16;
+                    if x >= right || offset - h_scroll + ch_width > view.width {
+                        break;
+                    }
+
+                    let idx = line_start + buffer_col;
+                    let char_style =
+                        self.resolve_style(idx, buffer_col, &spans, &selections, brackets);
+                    buf.set(x, y, Cell::new(ch.to_string(), char_style));
+                }
+
+                // Render cursors on this row
+                for cursor in &cursors {
+                    let col = cursor.position.col;
+                    if cursor.position.line != logical_line || row_of(&wrapped, col) != wrap_idx {
+                        continue;
+                    }
+                    let offset = display_offset(visual_text, *start_col, col);
+                    if offset < h_scroll || offset - h_scroll >= view.width {
+                        continue;
+                    }
+                    let cursor_x = gutter_x + (offset - h_scroll) as u16;
+                    if cursor_x < right {
+                        let cursor_ch = line_text
+                            .chars()
+                            .nth(col)
+                            .map(|c| c.to_string())
+                            .unwrap_or_else(|| " ".to_string());
+                        buf.set(cursor_x, y, Cell::new(cursor_ch, self.cursor_style.clone()));
+                    }
+                }
+
+                row += 1;
+            }
+
+            logical_line += 1;
+        }
+    }
+
+    /// Resolve the style for the character at buffer index `idx`
+    /// (column `col` of its line).
+    fn resolve_style(
+        &self,
+        idx: usize,
+        col: usize,
+        spans: &[HighlightSpan],
+        selections: &[Range<usize>],
+        brackets: Option<(usize, usize)>,
+    ) -> Style {
+        // Selection takes priority
+        if selections.iter().any(|s| s.contains(&idx)) {
+            return self.selection_style.clone();
+        }
+
+        // Then search matches
+        if let Some(search) = &self.search {
+            let i = search.matches.partition_point(|m| m.end <= idx);
+            if search.matches.get(i).is_some_and(|m| m.contains(&idx)) {
+                return if search.current == Some(i) {
+                    self.current_match_style.clone()
+                } else {
+                    self.match_style.clone()
+                };
+            }
+        }
+
+        // Check highlight spans, falling back to the base style
+        let style = spans
+            .iter()
+            .find(|span| col >= span.start_col && col < span.end_col)
+            .map_or_else(|| self.style.clone(), |span| span.style.clone());
+
+        match brackets {
+            Some((a, b)) if idx == a || idx == b => style.merge(&self.bracket_style),
+            _ => style,
+        }
+    }
+
+    /// Draw the find bar (and replace field) on the bottom rows.
+    fn render_find_bar(&self, area: Rect, buf: &mut ScreenBuffer) {
+        let Some(bar) = &self.find_bar else {
+            return;
+        };
+        let status = match &self.search {
+            Some(s) if s.error.is_some() => "invalid pattern".to_string(),
+            Some(s) if !s.matches.is_empty() => {
+                format!("{}/{}", s.current.map_or(0, |i| i + 1), s.matches.len())
+            }
+            Some(s) if !s.query.is_empty() => "no matches".to_string(),
+            _ => String::new(),
+        };
+        let options = self.search.as_ref().map(|s| s.options).unwrap_or_default();
+        let flags = format!(
+            "{}{}",
+            if options.regex { " .*" } else { "" },
+            if options.case_sensitive { " Aa" } else { "" }
+        );
+        let mut fields = vec![(
+            format!("Find: {}", bar.query),
+            !bar.editing_replacement,
+            format!("{status}{flags}"),
+        )];
+        if bar.replace {
+            fields.push((
+                format!("Replace: {}", bar.replacement),
+                bar.editing_replacement,
+                String::new(),
+            ));
+        }
+
+        let width = area.size.width as usize;
+        let top = (area.position.y + area.size.height).saturating_sub(fields.len() as u16);
+        for (i, (text, active, status)) in fields.into_iter().enumerate() {
+            let y = top + i as u16;
+            if y < area.position.y {
+                continue;
+            }
+            let mut cells: Vec<(String, Style)> = text
+                .chars()
+                .map(|c| (c.to_string(), self.find_bar_style.clone()))
+                .collect();
+            if active {
+                cells.push((" ".to_string(), self.cursor_style.clone()));
+            }
+            let used: usize = cells.iter().map(|(g, _)| display_width(g)).sum();
+            let status_width = display_width(&status);
+            let pad = width.saturating_sub(used + status_width);
+            cells.extend((0..pad).map(|_| (" ".to_string(), self.find_bar_style.clone())));
+            cells.extend(
+                status
+                    .chars()
+                    .map(|c| (c.to_string(), self.find_bar_style.clone())),
+            );
+
+            let mut x = 0;
+            for (grapheme, style) in cells {
+                let w = display_width(&grapheme);
+                if x + w > width {
+                    break;
+                }
+                buf.set(area.position.x + x as u16, y, Cell::new(grapheme, style));
+                x += w.max(1);
+            }
+        }
+    }
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| UnicodeWidthChar::width(c).unwrap_or(0))
+        .sum()
+}
+
+impl InteractiveWidget for TextArea {
+    fn handle_event(&mut self, event: &Event) -> EventResult {
+        match event {
+            Event::Key(key_event) => {
+                let result = match self.find_bar {
+                    Some(_) => match self.handle_find_key(key_event) {
+                        EventResult::Ignored => self.handle_keymap_key(key_event),
+                        handled => handled,
+                    },
+                    None => self.handle_keymap_key(key_event),
+                };
+                if result == EventResult::Consumed {
+                    self.scroll_to_cursor();
+                }
+                result
+            }
+            Event::Mouse(mouse) => self.handle_mouse(mouse),
+            Event::Paste(text) => {
+                match &mut self.find_bar {
+                    Some(bar) if bar.editing_replacement => bar.replacement.push_str(text),
+                    Some(bar) => {
+                        bar.query.push_str(text);
+                        self.update_search_from_bar();
+                    }
+                    None => self.insert_str(text),
+                }
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn pseudo_classes(&self) -> Vec<&'static str> {
+        self.edit_mode().pseudo_class().into_iter().collect()
+    }
+}
 
-                for (char_idx, ch) in visual_text.chars().enumerate() {
-                    let buffer_col = start_col + char_idx;
-                    let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
-                    let x = gutter_x + col_offset as u16;
+impl TextEditor for TextArea {
+    fn buffer(&self) -> &TextBuffer {
+        &self.buffer
+    }
 
-                    if x >= area.position.x + area.size.width {
-                        break;
-                    }
+    fn cursor(&self) -> usize {
+        self.index_of(self.cursor.position)
+    }
 
-                    // Determine style: selection > highlight > base
-                    let char_style = self.resolve_style(logical_line, buffer_col, &spans);
+    fn set_cursor(&mut self, idx: usize) {
+        self.extra_cursors.clear();
+        self.cursor = CursorState::new(0, 0);
+        self.cursor.position = self.position(idx);
+        self.undo_stack.seal();
+    }
 
-                    buf.set(x, y, Cell::new(ch.to_string(), char_style));
-                    col_offset += ch_width;
-                }
+    fn set_selection(&mut self, range: Option<Range<usize>>) {
+        self.cursor.selection =
+            range.map(|r| Selection::new(self.position(r.start), self.position(r.end)));
+    }
 
-                // Render cursor
-                if logical_line == self.cursor.position.line {
-                    let col = self.cursor.position.col;
-                    let end_col = start_col + visual_text.chars().count();
-                    let is_last_wrap = wrap_idx == wrapped.len() - 1;
-                    let cursor_in_wrap = col >= *start_col && (col < end_col || is_last_wrap);
-
-                    if cursor_in_wrap {
-                        let cursor_visual_col = self.cursor.position.col - start_col;
-                        // Calculate display offset for cursor
-                        let cursor_x_offset: usize = visual_text
-                            .chars()
-                            .take(cursor_visual_col)
-                            .map(|c| UnicodeWidthChar::width(c).unwrap_or(0))
-                            .sum();
-                        let cursor_x = gutter_x + cursor_x_offset as u16;
-                        if cursor_x < area.position.x + area.size.width {
-                            let cursor_ch = visual_text
-                                .chars()
-                                .nth(cursor_visual_col)
-                                .map(|c| c.to_string())
-                                .unwrap_or_else(|| " ".to_string());
-                            buf.set(cursor_x, y, Cell::new(cursor_ch, self.cursor_style.clone()));
-                        }
-                    }
-                }
+    fn replace(&mut self, range: Range<usize>, text: &str) {
+        let end = range.start + text.chars().count();
+        self.apply_edits(&[(range, text.to_string())], false);
+        TextEditor::set_cursor(self, end);
+    }
 
-                row += 1;
-            }
+    fn begin_change(&mut self) {
+        self.undo_stack.begin_group();
+    }
 
-            logical_line += 1;
-        }
+    fn end_change(&mut self) {
+        self.undo_stack.end_group();
     }
-}
 
-impl TextArea {
-    /// Resolve the style for a character at (line, col).
-    fn resolve_style(
-        &self,
-        line: usize,
-        col: usize,
-        spans: &[crate::highlight::HighlightSpan],
-    ) -> Style {
-        let pos = CursorPosition::new(line, col);
+    fn undo(&mut self) {
+        TextArea::undo(self);
+    }
 
-        // Selection takes priority
-        if let Some(ref sel) = self.cursor.selection
-            && sel.contains(pos)
-        {
-            return self.selection_style.clone();
-        }
+    fn redo(&mut self) {
+        TextArea::redo(self);
+    }
 
-        // Check highlight spans
-        for span in spans {
-            if col >= span.start_col && col < span.end_col {
-                return span.style.clone();
-            }
-        }
+    fn insert_key(&mut self, key: &KeyEvent) -> EventResult {
+        self.handle_key(key)
+    }
 
-        // Base style
-        self.style.clone()
+    fn page_rows(&self) -> usize {
+        TextArea::page_rows(self)
     }
-}
 
-impl InteractiveWidget for TextArea {
-    fn handle_event(&mut self, event: &Event) -> EventResult {
-        match event {
-            Event::Key(key_event) => self.handle_key(key_event),
-            _ => EventResult::Ignored,
-        }
+    fn indent_unit(&self) -> &str {
+        &self.indent_unit
+    }
+
+    fn clipboard(&self) -> Option<&Clipboard> {
+        Some(&self.clipboard)
     }
 }
 
 impl TextArea {
+    /// Handle a key through the keymap, which falls back to
+    /// [`handle_key`](Self::handle_key) for keys it does not bind.
+    fn handle_keymap_key(&mut self, key: &KeyEvent) -> EventResult {
+        if self.keymap.kind() == KeymapKind::Standard {
+            return self.handle_key(key);
+        }
+        let mut keymap = std::mem::take(&mut self.keymap);
+        let result = keymap.handle_key(self, key);
+        self.keymap = keymap;
+        result
+    }
+
     /// Handle a key event.
     fn handle_key(&mut self, key: &KeyEvent) -> EventResult {
         let shift = key.modifiers.contains(Modifiers::SHIFT);
         let ctrl = key.modifiers.contains(Modifiers::CTRL);
+        let alt = key.modifiers.contains(Modifiers::ALT);
+
+        let motion = match key.code {
+            KeyCode::Up | KeyCode::Down if ctrl && alt => {
+                self.add_cursor_vertically(key.code == KeyCode::Up);
+                return EventResult::Consumed;
+            }
+            KeyCode::Left if ctrl => Some(TextMotion::WordLeft),
+            KeyCode::Right if ctrl => Some(TextMotion::WordRight),
+            KeyCode::Left => Some(TextMotion::Left),
+            KeyCode::Right => Some(TextMotion::Right),
+            KeyCode::Up => Some(TextMotion::Up),
+            KeyCode::Down => Some(TextMotion::Down),
+            KeyCode::Home if ctrl => Some(TextMotion::BufferStart),
+            KeyCode::End if ctrl => Some(TextMotion::BufferEnd),
+            KeyCode::Home => Some(TextMotion::LineStart),
+            KeyCode::End => Some(TextMotion::LineEnd),
+            KeyCode::PageUp => Some(TextMotion::PageUp),
+            KeyCode::PageDown => Some(TextMotion::PageDown),
+            _ => None,
+        };
+        if let Some(motion) = motion {
+            self.move_cursor(motion, shift);
+            return EventResult::Consumed;
+        }
 
         match key.code {
-            KeyCode::Left => {
+            KeyCode::Backspace if ctrl || alt => self.delete_word_backward(),
+            KeyCode::Delete if ctrl || alt => self.delete_word_forward(),
+            KeyCode::Backspace => self.delete_backward(),
+            KeyCode::Delete => self.delete_forward(),
+            KeyCode::Enter => self.new_line(),
+            KeyCode::F(3) => {
                 if shift {
-                    if self.cursor.selection.is_none() {
-                        self.cursor.start_selection();
-                    }
-                    self.cursor.position = self.move_left_pos();
-                    self.cursor.extend_selection();
+                    self.find_previous();
                 } else {
-                    self.cursor.move_left(&self.buffer);
+                    self.find_next();
                 }
-                EventResult::Consumed
             }
-            KeyCode::Right => {
-                if shift {
-                    if self.cursor.selection.is_none() {
-                        self.cursor.start_selection();
-                    }
-                    self.cursor.position = self.move_right_pos();
-                    self.cursor.extend_selection();
-                } else {
-                    self.cursor.move_right(&self.buffer);
+            KeyCode::Escape => {
+                if !self.extra_cursors.is_empty() {
+                    self.extra_cursors.clear();
+                } else if self.search.is_some() {
+                    self.clear_search();
+                } else if self.cursor.selection.take().is_none() {
+                    return EventResult::Ignored;
                 }
-                EventResult::Consumed
             }
-            KeyCode::Up => {
-                if shift {
-                    if self.cursor.selection.is_none() {
-                        self.cursor.start_selection();
+            KeyCode::Char(ch) if ctrl => match ch.to_ascii_lowercase() {
+                'z' if shift => self.redo(),
+                'z' => self.undo(),
+                'y' => self.redo(),
+                'a' => self.select_all(),
+                'c' => {
+                    if !self.copy() {
+                        return EventResult::Ignored;
                     }
-                    self.move_up_no_clear();
-                    self.cursor.extend_selection();
-                } else {
-                    self.cursor.move_up(&self.buffer);
                 }
-                EventResult::Consumed
-            }
-            KeyCode::Down => {
-                if shift {
-                    if self.cursor.selection.is_none() {
-                        self.cursor.start_selection();
+                'x' => {
+                    if !self.cut() {
+                        return EventResult::Ignored;
                     }
-                    self.move_down_no_clear();
-                    self.cursor.extend_selection();
+                }
+                'v' => {
+                    self.paste();
+                }
+                'f' => self.open_find(false),
+                'h' => self.open_find(true),
+                'd' => {
+                    self.select_next_occurrence();
+                }
+                _ => return EventResult::Ignored,
+            },
+            KeyCode::Char('z') if alt => {
+                let wrap = !self.soft_wrap;
+                self.set_soft_wrap(wrap);
+            }
+            KeyCode::Char(ch) => self.insert_char(ch),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    /// Handle a key while the find bar is open. Keys it does not use fall
+    /// through to normal editing.
+    fn handle_find_key(&mut self, key: &KeyEvent) -> EventResult {
+        let shift = key.modifiers.contains(Modifiers::SHIFT);
+        let ctrl = key.modifiers.contains(Modifiers::CTRL);
+        let alt = key.modifiers.contains(Modifiers::ALT);
+        let Some(bar) = &mut self.find_bar else {
+            return EventResult::Ignored;
+        };
+
+        match key.code {
+            KeyCode::Escape => {
+                self.clear_search();
+            }
+            KeyCode::Enter if alt && bar.replace => {
+                let replacement = bar.replacement.clone();
+                // The find bar shows invalid patterns; nothing to replace.
+                let _ = self.replace_all(&replacement);
+            }
+            KeyCode::Enter if bar.editing_replacement => {
+                let replacement = bar.replacement.clone();
+                let _ = self.replace_current(&replacement);
+            }
+            KeyCode::Enter if shift => {
+                self.find_previous();
+            }
+            KeyCode::Enter => {
+                self.find_next();
+            }
+            KeyCode::Up | KeyCode::Down if bar.replace && !ctrl => {
+                bar.editing_replacement = key.code == KeyCode::Down;
+            }
+            KeyCode::Backspace => {
+                if bar.editing_replacement {
+                    bar.replacement.pop();
                 } else {
-                    self.cursor.move_down(&self.buffer);
+                    bar.query.pop();
+                    self.update_search_from_bar();
                 }
-                EventResult::Consumed
             }
-            KeyCode::Home => {
-                if ctrl {
-                    self.cursor.move_to_buffer_start();
+            KeyCode::Char(ch) if alt && matches!(ch.to_ascii_lowercase(), 'r' | 'c') => {
+                let mut options = self.search.as_ref().map(|s| s.options).unwrap_or_default();
+                if ch.eq_ignore_ascii_case(&'r') {
+                    options.regex = !options.regex;
                 } else {
-                    self.cursor.move_to_line_start();
+                    options.case_sensitive = !options.case_sensitive;
                 }
-                EventResult::Consumed
+                self.search = Some(Search::new(&bar.query, options));
+                let from = self.search_origin();
+                self.refresh_search(from);
+                self.select_current_match();
             }
-            KeyCode::End => {
-                if ctrl {
-                    self.cursor.move_to_buffer_end(&self.buffer);
+            KeyCode::Char(ch) if !ctrl && !alt => {
+                if bar.editing_replacement {
+                    bar.replacement.push(ch);
                 } else {
-                    self.cursor.move_to_line_end(&self.buffer);
+                    bar.query.push(ch);
+                    self.update_search_from_bar();
                 }
-                EventResult::Consumed
             }
-            KeyCode::Backspace => {
-                self.delete_backward();
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    /// Handle a mouse event: click to place the cursor, drag to select,
+    /// Shift+click to extend the selection, Alt+click to add a cursor and
+    /// the wheel to scroll.
+    fn handle_mouse(&mut self, mouse: &MouseEvent) -> EventResult {
+        let area = self.last_area.get();
+        let inside = area.contains(Position::new(mouse.x, mouse.y));
+        match mouse.kind {
+            MouseEventKind::Press if inside => {
+                let Some(view) = self.last_view() else {
+                    return EventResult::Ignored;
+                };
+                if (mouse.y - view.y) as usize >= view.height {
+                    // On the find bar.
+                    return EventResult::Consumed;
+                }
+                let Some(pos) = self.position_at(mouse.x, mouse.y) else {
+                    return EventResult::Ignored;
+                };
+                self.undo_stack.seal();
+                if mouse.modifiers.contains(Modifiers::ALT) {
+                    self.add_cursor(pos);
+                    return EventResult::Consumed;
+                }
+                self.extra_cursors.clear();
+                let anchor = if mouse.modifiers.contains(Modifiers::SHIFT) {
+                    self.cursor
+                        .selection
+                        .as_ref()
+                        .map_or(self.cursor.position, |sel| sel.anchor)
+                } else {
+                    pos
+                };
+                self.cursor.position = pos;
+                self.cursor.preferred_col = None;
+                self.cursor.selection = (anchor != pos).then(|| Selection::new(anchor, pos));
+                self.drag_anchor = Some(anchor);
                 EventResult::Consumed
             }
-            KeyCode::Delete => {
-                self.delete_forward();
+            MouseEventKind::Drag => {
+                let Some(anchor) = self.drag_anchor else {
+                    return EventResult::Ignored;
+                };
+                let Some(pos) = self.position_at(mouse.x, mouse.y) else {
+                    return EventResult::Ignored;
+                };
+                self.cursor.position = pos;
+                self.cursor.preferred_col = None;
+                self.cursor.selection = Some(Selection::new(anchor, pos));
+                self.scroll_to_cursor();
                 EventResult::Consumed
             }
-            KeyCode::Enter => {
-                self.new_line();
+            MouseEventKind::Release if self.drag_anchor.take().is_some() => EventResult::Consumed,
+            MouseEventKind::ScrollUp if inside => {
+                self.scroll_offset = self.scroll_offset.saturating_sub(WHEEL_LINES);
                 EventResult::Consumed
             }
-            KeyCode::Char(ch) => {
-                if ctrl && ch == 'z' {
-                    self.undo();
-                } else if ctrl && ch == 'y' {
-                    self.redo();
-                } else if !ctrl {
-                    self.insert_char(ch);
-                } else {
-                    return EventResult::Ignored;
-                }
+            MouseEventKind::ScrollDown if inside => {
+                let last = self.buffer.line_count().saturating_sub(1);
+                self.scroll_offset = (self.scroll_offset + WHEEL_LINES).min(last);
                 EventResult::Consumed
             }
             _ => EventResult::Ignored,
         }
     }
-
-    /// Move cursor left without clearing selection.
-    fn move_left_pos(&self) -> CursorPosition {
-        let mut pos = self.cursor.position;
-        if pos.col > 0 {
-            pos.col -= 1;
-        } else if pos.line > 0 {
-            pos.line -= 1;
-            pos.col = self.buffer.line_len(pos.line).unwrap_or(0);
-        }
-        pos
-    }
-
-    /// Move cursor right without clearing selection.
-    fn move_right_pos(&self) -> CursorPosition {
-        let mut pos = self.cursor.position;
-        let line_len = self.buffer.line_len(pos.line).unwrap_or(0);
-        if pos.col < line_len {
-            pos.col += 1;
-        } else if pos.line + 1 < self.buffer.line_count() {
-            pos.line += 1;
-            pos.col = 0;
-        }
-        pos
-    }
-
-    /// Move cursor up without clearing selection.
-    fn move_up_no_clear(&mut self) {
-        if self.cursor.position.line > 0 {
-            let target_col = self
-                .cursor
-                .preferred_col
-                .unwrap_or(self.cursor.position.col);
-            self.cursor.preferred_col = Some(target_col);
-            self.cursor.position.line -= 1;
-            let line_len = self.buffer.line_len(self.cursor.position.line).unwrap_or(0);
-            self.cursor.position.col = target_col.min(line_len);
-        }
-    }
-
-    /// Move cursor down without clearing selection.
-    fn move_down_no_clear(&mut self) {
-        if self.cursor.position.line + 1 < self.buffer.line_count() {
-            let target_col = self
-                .cursor
-                .preferred_col
-                .unwrap_or(self.cursor.position.col);
-            self.cursor.preferred_col = Some(target_col);
-            self.cursor.position.line += 1;
-            let line_len = self.buffer.line_len(self.cursor.position.line).unwrap_or(0);
-            self.cursor.position.col = target_col.min(line_len);
-        }
-    }
 }
 
 #[cfg(test)]
@@ -868,10 +2082,7 @@ mod tests {
     #[test]
     fn handle_event_char_input() {
         let mut ta = TextArea::new();
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Char('a'),
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('a'), Modifiers::NONE));
         let result = ta.handle_event(&event);
         assert!(result == EventResult::Consumed);
         assert!(ta.text() == "a");
@@ -881,11 +2092,485 @@ mod tests {
     fn handle_event_ctrl_z_undoes() {
         let mut ta = TextArea::new();
         ta.insert_char('x');
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Char('z'),
-            modifiers: Modifiers::CTRL,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('z'), Modifiers::CTRL));
         ta.handle_event(&event);
         assert!(ta.text().is_empty());
     }
+
+    fn ctrl(ch: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(ch), Modifiers::CTRL))
+    }
+
+    #[test]
+    fn copy_and_paste_selection() {
+        let mut ta = TextArea::from_text("hello world");
+        ta.cursor.selection = Some(Selection::new(
+            CursorPosition::new(0, 0),
+            CursorPosition::new(0, 5),
+        ));
+        assert_eq!(ta.selected_text().as_deref(), Some("hello"));
+        assert_eq!(ta.handle_event(&ctrl('c')), EventResult::Consumed);
+        assert_eq!(ta.clipboard().text().as_deref(), Some("hello"));
+        assert_eq!(ta.text(), "hello world");
+
+        ta.cursor.selection = None;
+        ta.cursor.position = CursorPosition::new(0, 11);
+        ta.handle_event(&ctrl('v'));
+        assert_eq!(ta.text(), "hello worldhello");
+    }
+
+    #[test]
+    fn cut_removes_selection() {
+        let mut ta = TextArea::from_text("abc\ndef");
+        ta.cursor.selection = Some(Selection::new(
+            CursorPosition::new(0, 1),
+            CursorPosition::new(1, 1),
+        ));
+        assert_eq!(ta.handle_event(&ctrl('x')), EventResult::Consumed);
+        assert_eq!(ta.text(), "aef");
+        assert_eq!(ta.clipboard().text().as_deref(), Some("bc\nd"));
+        ta.undo();
+        assert_eq!(ta.text(), "abc\ndef");
+    }
+
+    #[test]
+    fn copy_without_selection_is_ignored() {
+        let mut ta = TextArea::from_text("abc");
+        assert_eq!(ta.handle_event(&ctrl('c')), EventResult::Ignored);
+        assert!(ta.clipboard().text().is_none());
+    }
+
+    #[test]
+    fn shared_clipboard() {
+        let clipboard = Clipboard::new();
+        clipboard.set_text("shared");
+        let mut ta = TextArea::new().with_clipboard(clipboard.clone());
+        assert!(ta.paste());
+        assert_eq!(ta.text(), "shared");
+        ta.cursor.selection = Some(Selection::new(
+            CursorPosition::new(0, 0),
+            CursorPosition::new(0, 3),
+        ));
+        ta.copy();
+        assert_eq!(clipboard.text().as_deref(), Some("sha"));
+    }
+
+    #[test]
+    fn paste_event_inserts_text() {
+        let mut ta = TextArea::new();
+        let result = ta.handle_event(&Event::Paste("one\ntwo".into()));
+        assert_eq!(result, EventResult::Consumed);
+        assert_eq!(ta.text(), "one\ntwo");
+        assert_eq!(ta.cursor.position, CursorPosition::new(1, 3));
+    }
+
+    /// An edit's line, byte range and inserted text.
+    type RecordedEdit = (usize, usize, usize, String);
+
+    /// Records what the text area tells its highlighter.
+    #[derive(Default)]
+    struct Recorder {
+        edits: std::rc::Rc<std::cell::RefCell<Vec<RecordedEdit>>>,
+        texts: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+    }
+
+    impl Highlighter for Recorder {
+        fn highlight_line(&self, _line_idx: usize, _text: &str) -> Vec<HighlightSpan> {
+            Vec::new()
+        }
+
+        fn on_edit(&mut self, edit: &TextEdit<'_>) {
+            self.edits.borrow_mut().push((
+                edit.line,
+                edit.start_byte,
+                edit.old_end_byte,
+                edit.text.to_string(),
+            ));
+        }
+
+        fn set_text(&mut self, text: &str) {
+            self.texts.borrow_mut().push(text.to_string());
+        }
+    }
+
+    #[test]
+    fn highlighter_sees_each_edit_as_it_happens() {
+        let recorder = Recorder::default();
+        let (edits, texts) = (recorder.edits.clone(), recorder.texts.clone());
+        let mut ta = TextArea::from_text("ab\nçd").with_highlighter(Box::new(recorder));
+        assert_eq!(*texts.borrow(), vec!["ab\nçd".to_string()]);
+
+        ta.cursor.position = CursorPosition::new(1, 2);
+        ta.insert_char('e');
+        ta.insert_char(' ');
+        ta.undo();
+        let mut buf = ScreenBuffer::new(Size::new(10, 3));
+        ta.render(Rect::new(0, 0, 10, 3), &mut buf);
+        assert_eq!(
+            *edits.borrow(),
+            vec![
+                (1, 6, 6, "e".to_string()),
+                (1, 7, 7, " ".to_string()),
+                (1, 7, 8, String::new()),
+            ]
+        );
+        // Only the initial text is handed over whole.
+        assert_eq!(texts.borrow().len(), 1);
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn tree_sitter_highlighting_follows_typing() {
+        use crate::highlight::{SyntaxLanguage, TreeSitterHighlighter};
+
+        let highlighter = match TreeSitterHighlighter::new(SyntaxLanguage::Rust) {
+            Ok(h) => h,
+            Err(e) => unreachable!("rust grammar should load: {e}"),
+        };
+        let mut ta = TextArea::from_text("x").with_highlighter(Box::new(highlighter));
+        let mut buf = ScreenBuffer::new(Size::new(20, 2));
+        ta.cursor.position = CursorPosition::new(0, 1);
+        ta.insert_str(" // note");
+        ta.render(Rect::new(0, 0, 20, 2), &mut buf);
+        let comment = SyntaxTheme::default().style_for("comment").cloned();
+        assert_eq!(buf.get(4, 0).map(|c| c.style.clone()), comment);
+    }
+
+    fn key(code: KeyCode, modifiers: Modifiers) -> Event {
+        Event::Key(KeyEvent::new(code, modifiers))
+    }
+
+    fn press(ta: &mut TextArea, code: KeyCode, modifiers: Modifiers) {
+        ta.handle_event(&key(code, modifiers));
+    }
+
+    fn type_str(ta: &mut TextArea, text: &str) {
+        for ch in text.chars() {
+            press(ta, KeyCode::Char(ch), Modifiers::NONE);
+        }
+    }
+
+    fn rendered(ta: &TextArea, width: u16, height: u16) -> ScreenBuffer {
+        let mut buf = ScreenBuffer::new(Size::new(width, height));
+        ta.render(Rect::new(0, 0, width, height), &mut buf);
+        buf
+    }
+
+    fn row_text(buf: &ScreenBuffer, y: u16) -> String {
+        buf.get_row(y)
+            .map(|cells| cells.iter().map(|c| c.grapheme.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn word_motion_and_deletion() {
+        let mut ta = TextArea::from_text("let foo = bar.baz;");
+        press(&mut ta, KeyCode::Right, Modifiers::CTRL);
+        assert_eq!(ta.cursor.position.col, 3);
+        press(&mut ta, KeyCode::Right, Modifiers::CTRL);
+        assert_eq!(ta.cursor.position.col, 7);
+        press(&mut ta, KeyCode::End, Modifiers::NONE);
+        press(&mut ta, KeyCode::Left, Modifiers::CTRL);
+        assert_eq!(ta.cursor.position.col, 17);
+        press(&mut ta, KeyCode::Left, Modifiers::CTRL);
+        assert_eq!(ta.cursor.position.col, 14);
+
+        press(&mut ta, KeyCode::Backspace, Modifiers::CTRL);
+        assert_eq!(ta.text(), "let foo = barbaz;");
+        press(&mut ta, KeyCode::Delete, Modifiers::CTRL);
+        assert_eq!(ta.text(), "let foo = bar;");
+        ta.undo();
+        ta.undo();
+        assert_eq!(ta.text(), "let foo = bar.baz;");
+    }
+
+    #[test]
+    fn shift_motions_extend_the_selection() {
+        let mut ta = TextArea::from_text("hello world\nsecond");
+        press(
+            &mut ta,
+            KeyCode::Right,
+            Modifiers::SHIFT.union(Modifiers::CTRL),
+        );
+        assert_eq!(ta.selected_text().as_deref(), Some("hello"));
+        press(&mut ta, KeyCode::End, Modifiers::SHIFT);
+        assert_eq!(ta.selected_text().as_deref(), Some("hello world"));
+        press(&mut ta, KeyCode::Down, Modifiers::SHIFT);
+        assert_eq!(ta.selected_text().as_deref(), Some("hello world\nsecond"));
+        press(&mut ta, KeyCode::Left, Modifiers::NONE);
+        assert!(ta.selected_text().is_none());
+
+        press(&mut ta, KeyCode::Char('a'), Modifiers::CTRL);
+        press(&mut ta, KeyCode::Char('x'), Modifiers::NONE);
+        assert_eq!(ta.text(), "x");
+    }
+
+    #[test]
+    fn page_keys_move_by_the_viewport() {
+        let text: Vec<String> = (0..20).map(|i| format!("line {i}")).collect();
+        let mut ta = TextArea::from_text(&text.join("\n"));
+        rendered(&ta, 20, 5);
+        press(&mut ta, KeyCode::PageDown, Modifiers::NONE);
+        assert_eq!(ta.cursor.position.line, 4);
+        press(&mut ta, KeyCode::PageDown, Modifiers::SHIFT);
+        assert_eq!(ta.cursor.position.line, 8);
+        assert_eq!(ta.scroll_offset, 4);
+        assert_eq!(ta.selected_text().map(|t| t.lines().count()), Some(4));
+        press(&mut ta, KeyCode::PageUp, Modifiers::NONE);
+        assert_eq!(ta.cursor.position.line, 4);
+    }
+
+    fn mouse(kind: MouseEventKind, x: u16, y: u16, modifiers: Modifiers) -> Event {
+        Event::Mouse(MouseEvent {
+            kind,
+            x,
+            y,
+            modifiers,
+        })
+    }
+
+    #[test]
+    fn mouse_click_and_drag_select() {
+        let mut ta = TextArea::from_text("abc\ndefgh\nij").with_line_numbers(true);
+        let mut buf = ScreenBuffer::new(Size::new(20, 6));
+        ta.render(Rect::new(0, 1, 20, 5), &mut buf);
+
+        // Gutter is two columns wide; row 1 is the first text row.
+        let click = mouse(MouseEventKind::Press, 3, 2, Modifiers::NONE);
+        assert_eq!(ta.handle_event(&click), EventResult::Consumed);
+        assert_eq!(ta.cursor.position, CursorPosition::new(1, 1));
+        ta.handle_event(&mouse(MouseEventKind::Drag, 15, 3, Modifiers::NONE));
+        assert_eq!(ta.selected_text().as_deref(), Some("efgh\nij"));
+        assert_eq!(
+            ta.handle_event(&mouse(MouseEventKind::Release, 15, 3, Modifiers::NONE)),
+            EventResult::Consumed
+        );
+
+        ta.handle_event(&mouse(MouseEventKind::Press, 3, 1, Modifiers::SHIFT));
+        assert_eq!(ta.selected_text().as_deref(), Some("bc\nd"));
+
+        // Clicks outside the widget are left to others.
+        let outside = mouse(MouseEventKind::Press, 3, 0, Modifiers::NONE);
+        assert_eq!(ta.handle_event(&outside), EventResult::Ignored);
+    }
+
+    #[test]
+    fn incremental_find_highlights_matches() {
+        let mut ta = TextArea::from_text("one two one\nthree one");
+        press(&mut ta, KeyCode::Char('f'), Modifiers::CTRL);
+        assert!(ta.is_find_open());
+        type_str(&mut ta, "on");
+        assert_eq!(ta.search_matches(), &[0..2, 8..10, 18..20]);
+        assert_eq!(ta.selected_text().as_deref(), Some("on"));
+        type_str(&mut ta, "e");
+        assert_eq!(ta.search_matches().len(), 3);
+
+        press(&mut ta, KeyCode::Enter, Modifiers::NONE);
+        assert_eq!(ta.cursor.position, CursorPosition::new(0, 11));
+        let buf = rendered(&ta, 20, 4);
+        assert_eq!(
+            buf.get(0, 0).map(|c| c.style.clone()),
+            Some(ta.match_style.clone())
+        );
+        assert_eq!(
+            buf.get(8, 0).map(|c| c.style.clone()),
+            Some(ta.selection_style.clone())
+        );
+        assert!(row_text(&buf, 3).starts_with("Find: one"));
+        assert!(row_text(&buf, 3).trim_end().ends_with("2/3"));
+
+        press(&mut ta, KeyCode::Enter, Modifiers::SHIFT);
+        assert_eq!(ta.cursor.position, CursorPosition::new(0, 3));
+        press(&mut ta, KeyCode::F(3), Modifiers::NONE);
+        press(&mut ta, KeyCode::F(3), Modifiers::NONE);
+        assert_eq!(ta.cursor.position, CursorPosition::new(1, 9));
+
+        press(&mut ta, KeyCode::Escape, Modifiers::NONE);
+        assert!(!ta.is_find_open());
+        assert!(ta.search_matches().is_empty());
+    }
+
+    #[test]
+    fn regex_replace_all_is_one_undo_step() {
+        let mut ta = TextArea::from_text("let a = 1;\nlet b = 22;");
+        let options = SearchOptions::new().regex(true);
+        assert!(matches!(ta.find(r"let (\w) = (\d+)", options), Ok(2)));
+        assert!(matches!(ta.replace_all("const $1: u8 = $2"), Ok(2)));
+        assert_eq!(ta.text(), "const a: u8 = 1;\nconst b: u8 = 22;");
+        ta.undo();
+        assert_eq!(ta.text(), "let a = 1;\nlet b = 22;");
+
+        assert!(ta.find("(", options).is_err());
+        assert!(matches!(ta.find("a", SearchOptions::new()), Ok(1)));
+        assert!(matches!(ta.replace_current("x"), Ok(true)));
+        assert_eq!(ta.text(), "let x = 1;\nlet b = 22;");
+    }
+
+    #[test]
+    fn replace_from_the_find_bar() {
+        let mut ta = TextArea::from_text("cat cat cat");
+        press(&mut ta, KeyCode::Char('h'), Modifiers::CTRL);
+        type_str(&mut ta, "cat");
+        press(&mut ta, KeyCode::Down, Modifiers::NONE);
+        type_str(&mut ta, "dog");
+        press(&mut ta, KeyCode::Enter, Modifiers::NONE);
+        assert_eq!(ta.text(), "dog cat cat");
+        assert_eq!(ta.selected_text().as_deref(), Some("cat"));
+        press(&mut ta, KeyCode::Enter, Modifiers::ALT);
+        assert_eq!(ta.text(), "dog dog dog");
+        let buf = rendered(&ta, 20, 4);
+        assert!(row_text(&buf, 3).starts_with("Replace: dog"));
+    }
+
+    #[test]
+    fn soft_wrap_maps_cursor_rows() {
+        let mut ta = TextArea::from_text("aaaa bbbb cccc\nxy");
+        rendered(&ta, 5, 5);
+        ta.cursor.position = CursorPosition::new(0, 6);
+        press(&mut ta, KeyCode::Down, Modifiers::NONE);
+        assert_eq!(ta.cursor.position, CursorPosition::new(0, 11));
+        press(&mut ta, KeyCode::Down, Modifiers::NONE);
+        assert_eq!(ta.cursor.position, CursorPosition::new(1, 1));
+        press(&mut ta, KeyCode::Up, Modifiers::NONE);
+        assert_eq!(ta.cursor.position, CursorPosition::new(0, 11));
+
+        // The cursor is drawn on its wrapped row.
+        let buf = rendered(&ta, 5, 5);
+        assert_eq!(buf.get(1, 2).map(|c| c.style.reverse), Some(true));
+        assert_eq!(
+            buf.get(1, 2).map(|c| c.grapheme.clone()),
+            Some("c".to_string())
+        );
+    }
+
+    #[test]
+    fn ensure_cursor_visible_counts_wrapped_rows() {
+        let mut ta = TextArea::from_text("aaaa bbbb\ncccc dddd\nee");
+        rendered(&ta, 5, 3);
+        ta.cursor.position = CursorPosition::new(2, 0);
+        ta.ensure_cursor_visible(3);
+        assert_eq!(ta.scroll_offset, 1);
+    }
+
+    #[test]
+    fn without_soft_wrap_lines_scroll_horizontally() {
+        let mut ta = TextArea::from_text("abcdefghij").with_soft_wrap(false);
+        rendered(&ta, 4, 2);
+        press(&mut ta, KeyCode::End, Modifiers::NONE);
+        let buf = rendered(&ta, 4, 2);
+        assert_eq!(row_text(&buf, 0), "hij ");
+        assert_eq!(row_text(&buf, 1), "    ");
+
+        press(&mut ta, KeyCode::Char('z'), Modifiers::ALT);
+        assert!(ta.soft_wrap());
+        let buf = rendered(&ta, 4, 3);
+        assert_eq!(row_text(&buf, 0), "abcd");
+    }
+
+    #[test]
+    fn enter_auto_indents() {
+        let mut ta = TextArea::from_text("    fn f() {}");
+        ta.cursor.position = CursorPosition::new(0, 12);
+        press(&mut ta, KeyCode::Enter, Modifiers::NONE);
+        assert_eq!(ta.text(), "    fn f() {\n        \n    }");
+        assert_eq!(ta.cursor.position, CursorPosition::new(1, 8));
+        type_str(&mut ta, "x;");
+        press(&mut ta, KeyCode::Enter, Modifiers::NONE);
+        assert_eq!(ta.cursor.position, CursorPosition::new(2, 8));
+
+        let mut plain = TextArea::from_text("  a").with_auto_indent(false);
+        plain.cursor.position = CursorPosition::new(0, 3);
+        plain.new_line();
+        assert_eq!(plain.text(), "  a\n");
+    }
+
+    #[test]
+    fn brackets_are_matched() {
+        let ta = TextArea::from_text("f(a[0], {b})");
+        assert_eq!(
+            ta.matching_bracket(CursorPosition::new(0, 1)),
+            Some(CursorPosition::new(0, 11))
+        );
+        assert_eq!(
+            ta.matching_bracket(CursorPosition::new(0, 10)),
+            Some(CursorPosition::new(0, 8))
+        );
+        assert_eq!(ta.matching_bracket(CursorPosition::new(0, 0)), None);
+
+        let mut ta = ta;
+        ta.cursor.position = CursorPosition::new(0, 2);
+        let buf = rendered(&ta, 20, 1);
+        assert_eq!(buf.get(1, 0).map(|c| c.style.bold), Some(true));
+        assert_eq!(buf.get(11, 0).map(|c| c.style.underline), Some(true));
+        assert_eq!(buf.get(3, 0).map(|c| c.style.bold), Some(false));
+    }
+
+    #[test]
+    fn multiple_cursors_edit_together() {
+        let mut ta = TextArea::from_text("a\nb\nc");
+        press(
+            &mut ta,
+            KeyCode::Down,
+            Modifiers::CTRL.union(Modifiers::ALT),
+        );
+        ta.add_cursor(CursorPosition::new(2, 0));
+        assert_eq!(ta.cursor_positions().len(), 3);
+        type_str(&mut ta, "- ");
+        assert_eq!(ta.text(), "- a\n- b\n- c");
+        press(&mut ta, KeyCode::End, Modifiers::NONE);
+        press(&mut ta, KeyCode::Backspace, Modifiers::NONE);
+        assert_eq!(ta.text(), "- \n- \n- ");
+        ta.undo();
+        assert_eq!(ta.text(), "- a\n- b\n- c");
+        assert_eq!(ta.cursor_positions().len(), 1);
+
+        press(&mut ta, KeyCode::Escape, Modifiers::NONE);
+        assert_eq!(
+            ta.handle_event(&key(KeyCode::Escape, Modifiers::NONE)),
+            EventResult::Ignored
+        );
+    }
+
+    #[test]
+    fn select_next_occurrence_adds_cursors() {
+        let mut ta = TextArea::from_text("foo bar foo baz foo");
+        press(&mut ta, KeyCode::Char('d'), Modifiers::CTRL);
+        assert_eq!(ta.selected_text().as_deref(), Some("foo"));
+        press(&mut ta, KeyCode::Char('d'), Modifiers::CTRL);
+        press(&mut ta, KeyCode::Char('d'), Modifiers::CTRL);
+        assert!(!ta.select_next_occurrence());
+        type_str(&mut ta, "qux");
+        assert_eq!(ta.text(), "qux bar qux baz qux");
+    }
+
+    #[test]
+    fn multi_cursor_copy_and_paste_distribute_lines() {
+        let mut ta = TextArea::from_text("one\ntwo");
+        ta.add_cursor(CursorPosition::new(1, 0));
+        press(&mut ta, KeyCode::End, Modifiers::SHIFT);
+        assert!(ta.copy());
+        assert_eq!(ta.clipboard().text().as_deref(), Some("one\ntwo"));
+        press(&mut ta, KeyCode::Home, Modifiers::NONE);
+        ta.paste();
+        assert_eq!(ta.text(), "oneone\ntwotwo");
+    }
+
+    #[test]
+    fn typing_undoes_a_word_at_a_time() {
+        let mut ta = TextArea::new();
+        type_str(&mut ta, "hello world");
+        ta.undo();
+        assert_eq!(ta.text(), "hello");
+        type_str(&mut ta, "!");
+        press(&mut ta, KeyCode::Left, Modifiers::NONE);
+        type_str(&mut ta, "?");
+        ta.undo();
+        assert_eq!(ta.text(), "hello!");
+        press(
+            &mut ta,
+            KeyCode::Char('Z'),
+            Modifiers::CTRL.union(Modifiers::SHIFT),
+        );
+        assert_eq!(ta.text(), "hello?!");
+    }
 }
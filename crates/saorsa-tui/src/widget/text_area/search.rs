@@ -0,0 +1,212 @@
+//! Find and replace over a [`TextBuffer`].
+
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::error::{Result, SaorsaTuiError};
+use crate::text_buffer::TextBuffer;
+
+/// How a search query is interpreted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression. Replacements may then
+    /// refer to capture groups as `$1` or `${name}`.
+    pub regex: bool,
+    /// Match case exactly.
+    pub case_sensitive: bool,
+}
+
+impl SearchOptions {
+    /// Plain-text, case-insensitive search.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interpret the query as a regular expression.
+    #[must_use]
+    pub fn regex(mut self, regex: bool) -> Self {
+        self.regex = regex;
+        self
+    }
+
+    /// Match case exactly.
+    #[must_use]
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Compile `query` for widgets that search line by line; `None` for
+    /// an empty query.
+    pub(crate) fn compile(self, query: &str) -> Result<Option<Regex>> {
+        compile(query, self)
+            .map_err(|e| SaorsaTuiError::Widget(format!("invalid search pattern: {e}")))
+    }
+}
+
+/// A compiled query and the matches it found.
+pub(super) struct Search {
+    pub query: String,
+    pub options: SearchOptions,
+    regex: Option<Regex>,
+    /// Why the query could not be compiled, if it could not.
+    pub error: Option<String>,
+    /// Matches as character ranges into the buffer, in order.
+    pub matches: Vec<Range<usize>>,
+    /// Index into `matches` of the current match.
+    pub current: Option<usize>,
+}
+
+impl Search {
+    /// Compile a query. An invalid regex leaves the search without matches
+    /// and records the error.
+    pub fn new(query: &str, options: SearchOptions) -> Self {
+        let (regex, error) = match compile(query, options) {
+            Ok(regex) => (regex, None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        Self {
+            query: query.to_string(),
+            options,
+            regex,
+            error,
+            matches: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Find every match in `buffer`, keeping the current match on the
+    /// first one starting at or after `from`.
+    pub fn refresh(&mut self, buffer: &TextBuffer, from: usize) {
+        self.matches = match &self.regex {
+            Some(regex) => find_all(regex, buffer),
+            None => Vec::new(),
+        };
+        self.current = self.index_from(from);
+    }
+
+    /// The first match starting at or after `from`, wrapping around.
+    pub fn index_from(&self, from: usize) -> Option<usize> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(
+            self.matches
+                .iter()
+                .position(|m| m.start >= from)
+                .unwrap_or(0),
+        )
+    }
+
+    /// The last match ending at or before `to`, wrapping around.
+    pub fn index_before(&self, to: usize) -> Option<usize> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(
+            self.matches
+                .iter()
+                .rposition(|m| m.end <= to)
+                .unwrap_or(self.matches.len() - 1),
+        )
+    }
+
+    /// The text replacing `matched`: the replacement with capture groups
+    /// expanded for regex searches, or the replacement itself.
+    pub fn expand(&self, matched: &str, replacement: &str) -> String {
+        match &self.regex {
+            Some(regex) if self.options.regex => match regex.captures(matched) {
+                Some(caps) => {
+                    let mut out = String::new();
+                    caps.expand(replacement, &mut out);
+                    out
+                }
+                None => replacement.to_string(),
+            },
+            _ => replacement.to_string(),
+        }
+    }
+
+    /// The compile error as a widget error, if the query was invalid.
+    pub fn check(&self) -> Result<()> {
+        match &self.error {
+            Some(e) => Err(SaorsaTuiError::Widget(format!(
+                "invalid search pattern: {e}"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Compile a query; an empty query compiles to nothing.
+fn compile(
+    query: &str,
+    options: SearchOptions,
+) -> std::result::Result<Option<Regex>, regex::Error> {
+    if query.is_empty() {
+        return Ok(None);
+    }
+    let pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+        .map(Some)
+}
+
+/// All non-empty matches of `regex` in `buffer` as character ranges.
+fn find_all(regex: &Regex, buffer: &TextBuffer) -> Vec<Range<usize>> {
+    let text = buffer.to_string();
+    regex
+        .find_iter(&text)
+        .filter(|m| !m.is_empty())
+        .map(|m| buffer.byte_to_char(m.start())..buffer.byte_to_char(m.end()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_search_is_case_insensitive_by_default() {
+        let buffer = TextBuffer::from_text("Foo foo\nföo FOO");
+        let mut search = Search::new("foo", SearchOptions::new());
+        search.refresh(&buffer, 4);
+        assert_eq!(search.matches, vec![0..3, 4..7, 12..15]);
+        assert_eq!(search.current, Some(1));
+
+        let mut exact = Search::new("foo", SearchOptions::new().case_sensitive(true));
+        exact.refresh(&buffer, 0);
+        assert_eq!(exact.matches, vec![4..7]);
+    }
+
+    #[test]
+    fn regex_replacement_expands_groups() {
+        let buffer = TextBuffer::from_text("let a = 1;\nlet b = 2;");
+        let mut search = Search::new(r"let (\w) = (\d)", SearchOptions::new().regex(true));
+        search.refresh(&buffer, 0);
+        assert_eq!(search.matches.len(), 2);
+        assert_eq!(
+            search.expand("let a = 1", "const $1: u8 = $2"),
+            "const a: u8 = 1"
+        );
+        assert_eq!(search.index_before(11), Some(0));
+        assert_eq!(search.index_before(0), Some(1));
+    }
+
+    #[test]
+    fn invalid_regex_reports_an_error() {
+        let mut search = Search::new("(", SearchOptions::new().regex(true));
+        search.refresh(&TextBuffer::from_text("("), 0);
+        assert!(search.matches.is_empty());
+        assert!(search.check().is_err());
+        // The same text as a literal is fine.
+        assert!(Search::new("(", SearchOptions::new()).check().is_ok());
+    }
+}
@@ -31,6 +31,7 @@ pub struct Toast {
     position: ToastPosition,
     style: Style,
     width: u16,
+    opacity: f32,
 }
 
 impl Toast {
@@ -41,6 +42,7 @@ impl Toast {
             position: ToastPosition::TopRight,
             style: Style::default(),
             width: 30,
+            opacity: 1.0,
         }
     }
 
@@ -65,6 +67,14 @@ impl Toast {
         self
     }
 
+    /// Set the toast opacity in `0.0..=1.0` so the content beneath shows
+    /// through its background.
+    #[must_use]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
     /// Render the toast to lines ready for the compositor.
     ///
     /// Produces a single-line notification padded to the toast width.
@@ -104,6 +114,7 @@ impl Toast {
             size: Size::new(self.width, height),
             z_offset: 0,
             dim_background: false,
+            opacity: self.opacity,
         }
     }
 }
@@ -128,6 +128,7 @@ impl Tooltip {
             size: self.size(),
             z_offset: 0,
             dim_background: false,
+            opacity: 1.0,
         }
     }
 
@@ -169,11 +169,35 @@ impl<T> Tree<T> {
         &self.roots
     }
 
+    /// Get mutable access to the root nodes, for example to reload
+    /// children. The selection is kept by index; follow with
+    /// [`Tree::select_where`] to keep it on the same data.
+    pub fn roots_mut(&mut self) -> &mut [TreeNode<T>] {
+        &mut self.roots
+    }
+
     /// Get the selected visible node index.
     pub fn selected(&self) -> usize {
         self.selected
     }
 
+    /// Select the first visible node whose data matches `pred`.
+    ///
+    /// Returns `false`, keeping the selection within the visible nodes,
+    /// when none matches.
+    pub fn select_where(&mut self, pred: impl Fn(&T) -> bool) -> bool {
+        let visible = self.build_visible();
+        let found = visible.iter().position(|v| {
+            self.node_at_path(&v.path)
+                .is_some_and(|node| pred(&node.data))
+        });
+        match found {
+            Some(index) => self.selected = index,
+            None => self.selected = self.selected.min(visible.len().saturating_sub(1)),
+        }
+        found.is_some()
+    }
+
     /// Get the scroll offset.
     pub fn scroll_offset(&self) -> usize {
         self.scroll_offset
@@ -353,7 +377,12 @@ impl<T> Widget for Tree<T> {
         let count = visible.len();
 
         let max_offset = count.saturating_sub(height.max(1));
-        let scroll = self.scroll_offset.min(max_offset);
+        // Keep the selection in view, whatever the area's height.
+        let scroll = self
+            .scroll_offset
+            .min(self.selected)
+            .max((self.selected + 1).saturating_sub(height))
+            .min(max_offset);
         let visible_end = (scroll + height).min(count);
 
         for (row, vis_idx) in (scroll..visible_end).enumerate() {
@@ -423,6 +452,7 @@ impl<T> Widget for Tree<T> {
                         }
                         let remaining = width.saturating_sub(col as usize);
                         let truncated = truncate_to_display_width(&segment.text, remaining);
+                        let segment_style = style.merge(&segment.style);
                         for ch in truncated.chars() {
                             let char_w =
                                 UnicodeWidthStr::width(ch.encode_utf8(&mut [0; 4]) as &str);
@@ -432,7 +462,7 @@ impl<T> Widget for Tree<T> {
                             buf.set(
                                 inner.position.x + col,
                                 y,
-                                Cell::new(ch.to_string(), style.clone()),
+                                Cell::new(ch.to_string(), segment_style.clone()),
                             );
                             col += char_w as u16;
                         }
@@ -575,14 +605,8 @@ mod tests {
         let mut tree = make_test_tree();
         tree.toggle_selected(); // expand root
 
-        let down = Event::Key(KeyEvent {
-            code: KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
-        let up = Event::Key(KeyEvent {
-            code: KeyCode::Up,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = Event::Key(KeyEvent::new(KeyCode::Down, crate::event::Modifiers::NONE));
+        let up = Event::Key(KeyEvent::new(KeyCode::Up, crate::event::Modifiers::NONE));
 
         assert_eq!(tree.selected(), 0);
         tree.handle_event(&down);
@@ -596,10 +620,7 @@ mod tests {
     #[test]
     fn right_key_expands() {
         let mut tree = make_test_tree();
-        let right = Event::Key(KeyEvent {
-            code: KeyCode::Right,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let right = Event::Key(KeyEvent::new(KeyCode::Right, crate::event::Modifiers::NONE));
 
         tree.handle_event(&right); // expand root
         assert_eq!(tree.visible_count(), 4);
@@ -609,10 +630,7 @@ mod tests {
     fn left_key_collapses() {
         let mut tree = make_test_tree();
         tree.toggle_selected(); // expand root
-        let left = Event::Key(KeyEvent {
-            code: KeyCode::Left,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let left = Event::Key(KeyEvent::new(KeyCode::Left, crate::event::Modifiers::NONE));
 
         tree.handle_event(&left); // collapse root
         assert_eq!(tree.visible_count(), 1);
@@ -621,10 +639,7 @@ mod tests {
     #[test]
     fn enter_toggles() {
         let mut tree = make_test_tree();
-        let enter = Event::Key(KeyEvent {
-            code: KeyCode::Enter,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let enter = Event::Key(KeyEvent::new(KeyCode::Enter, crate::event::Modifiers::NONE));
 
         tree.handle_event(&enter); // expand
         assert_eq!(tree.visible_count(), 4);
@@ -632,6 +647,17 @@ mod tests {
         assert_eq!(tree.visible_count(), 1);
     }
 
+    #[test]
+    fn key_release_is_ignored() {
+        let mut tree = make_test_tree();
+        let release = Event::Key(
+            KeyEvent::plain(KeyCode::Enter).with_kind(crate::event::KeyEventKind::Release),
+        );
+
+        assert_eq!(tree.dispatch_event(&release), EventResult::Ignored);
+        assert_eq!(tree.visible_count(), 1);
+    }
+
     #[test]
     fn lazy_load_on_expand() {
         use std::cell::RefCell;
@@ -705,10 +731,7 @@ mod tests {
 
         // Expand all levels
         tree.expand_selected(); // L0
-        let down = Event::Key(KeyEvent {
-            code: KeyCode::Down,
-            modifiers: crate::event::Modifiers::NONE,
-        });
+        let down = Event::Key(KeyEvent::new(KeyCode::Down, crate::event::Modifiers::NONE));
         tree.handle_event(&down); // select L1
         tree.expand_selected(); // L1
         tree.handle_event(&down); // select L2
@@ -750,4 +773,36 @@ mod tests {
 
         assert_eq!(buf.get(0, 0).map(|c| c.grapheme.as_str()), Some("\u{250c}"));
     }
+
+    #[test]
+    fn select_where_finds_visible_nodes() {
+        let mut tree = make_test_tree();
+        assert!(!tree.select_where(|d| d == "leaf2"));
+        tree.toggle_selected();
+        assert!(tree.select_where(|d| d == "leaf2"));
+        assert_eq!(tree.selected(), 3);
+        assert_eq!(tree.selected_node().map(|n| n.data.as_str()), Some("leaf2"));
+    }
+
+    #[test]
+    fn render_keeps_selection_in_view_and_segment_styles() {
+        let mut tree =
+            Tree::new(vec![TreeNode::branch("root".to_string()).with_children(
+                (0..10).map(|i| TreeNode::new(format!("n{i}"))).collect(),
+            )])
+            .with_render_fn(|data: &String, _, _, _| {
+                vec![Segment::styled(data, Style::new().bold(true))]
+            });
+        tree.toggle_selected();
+        tree.select_where(|d| d == "n9");
+
+        let mut buf = ScreenBuffer::new(Size::new(20, 3));
+        tree.render(Rect::new(0, 0, 20, 3), &mut buf);
+        // Indent, indicator and a space come before the label.
+        assert_eq!(buf.get(4, 2).map(|c| c.grapheme.as_str()), Some("n"));
+        assert!(
+            buf.get(4, 2)
+                .is_some_and(|c| c.style.bold && c.style.reverse)
+        );
+    }
 }
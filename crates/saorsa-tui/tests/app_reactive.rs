@@ -0,0 +1,221 @@
+#![allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+#![allow(missing_docs)]
+
+use std::cell::Cell as StdCell;
+use std::rc::Rc;
+
+use saorsa_tui::app::{App, Dom, Leaf, NodeRef, StyledLeaf};
+use saorsa_tui::reactive::{Signal, batch};
+use saorsa_tui::terminal::TestBackend;
+use saorsa_tui::widget::{Container, Label};
+
+/// An app with a column root and two labels.
+fn app_with_labels(backend: &TestBackend) -> (App, NodeRef, NodeRef, NodeRef) {
+    let mut dom = Dom::new();
+    let root = dom.create(
+        "Root",
+        Box::new(Leaf::new(saorsa_tui::StaticWidget::new(vec![]))),
+    );
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    let a = dom.create("Label", Box::new(StyledLeaf::new(Label::new("a"))));
+    let b = dom.create("Label", Box::new(StyledLeaf::new(Label::new("b"))));
+
+    let mut app = App::from_tcss_string(
+        backend,
+        dom,
+        "#root { display: flex; flex-direction: column; } Label { height: 1; }",
+    )
+    .unwrap();
+    app.mount(root, a).unwrap();
+    app.mount(root, b).unwrap();
+    (app, root, a, b)
+}
+
+fn label_text(app: &mut App, node: NodeRef) -> String {
+    app.dom_mut()
+        .downcast_widget_mut::<Label>(node)
+        .unwrap()
+        .text()
+        .to_string()
+}
+
+#[test]
+fn bind_text_updates_label_and_marks_only_that_node() {
+    let mut backend = TestBackend::new(20, 4);
+    let (mut app, _, a, b) = app_with_labels(&backend);
+    let title = Signal::new(String::from("hello"));
+    app.bind_text(a, &title).unwrap();
+    assert_eq!(label_text(&mut app, a), "hello");
+    app.render_frame(&mut backend).unwrap();
+    assert!(!app.is_node_dirty(a));
+
+    title.set("WORLD".into());
+    assert!(app.apply_bindings().unwrap());
+    assert_eq!(label_text(&mut app, a), "WORLD");
+    assert!(app.is_node_dirty(a));
+    assert!(!app.is_node_dirty(b));
+
+    backend.clear_buffer();
+    assert!(app.render_if_needed(&mut backend).unwrap());
+    assert!(String::from_utf8_lossy(backend.buffer()).contains("WORLD"));
+    assert!(!app.is_node_dirty(a));
+}
+
+#[test]
+fn signal_changes_trigger_render_if_needed() {
+    let mut backend = TestBackend::new(20, 4);
+    let (mut app, _, a, _) = app_with_labels(&backend);
+    let count = Signal::new(0_u32);
+    app.bind_widget(a, &count, |label: &mut Label, n: &u32| {
+        label.set_text(format!("count {n}"));
+    })
+    .unwrap();
+    app.render_frame(&mut backend).unwrap();
+    assert!(!app.render_if_needed(&mut backend).unwrap());
+
+    batch(|| {
+        count.set(1);
+        count.set(2);
+    });
+    assert!(app.render_if_needed(&mut backend).unwrap());
+    assert_eq!(label_text(&mut app, a), "count 2");
+}
+
+#[test]
+fn bind_class_toggles_class() {
+    let backend = TestBackend::new(20, 4);
+    let (mut app, _, a, b) = app_with_labels(&backend);
+    let active = Signal::new(false);
+    app.bind_class(b, "active", &active).unwrap();
+    assert!(app.query(".active").unwrap().is_empty());
+
+    active.set(true);
+    app.apply_bindings().unwrap();
+    assert_eq!(app.query(".active").unwrap(), vec![b]);
+    assert!(app.is_node_dirty(b));
+    assert!(!app.is_node_dirty(a));
+
+    active.set(false);
+    app.apply_bindings().unwrap();
+    assert!(app.query(".active").unwrap().is_empty());
+}
+
+#[test]
+fn bind_widget_rejects_wrong_widget_type() {
+    let backend = TestBackend::new(20, 4);
+    let (mut app, root, _, _) = app_with_labels(&backend);
+    let text = Signal::new(String::new());
+    assert!(app.bind_text(root, &text).is_err());
+    assert_eq!(app.binding_count(root), 0);
+}
+
+#[test]
+fn remove_subtree_disposes_bindings() {
+    let backend = TestBackend::new(20, 4);
+    let (mut app, _, a, _) = app_with_labels(&backend);
+    let calls = Rc::new(StdCell::new(0));
+    let text = Signal::new(String::from("x"));
+    let counter = Rc::clone(&calls);
+    app.bind_widget(a, &text, move |label: &mut Label, t: &String| {
+        counter.set(counter.get() + 1);
+        label.set_text(t.clone());
+    })
+    .unwrap();
+    assert_eq!(app.binding_count(a), 1);
+    assert_eq!(calls.get(), 1);
+    assert_eq!(Rc::strong_count(&calls), 2);
+
+    app.remove_subtree(a).unwrap();
+    assert_eq!(app.binding_count(a), 0);
+    // The binding (and the closure it owned) is gone.
+    assert_eq!(Rc::strong_count(&calls), 1);
+    text.set("y".into());
+    assert!(!app.apply_bindings().unwrap());
+    assert_eq!(calls.get(), 1);
+}
+
+#[derive(Clone)]
+struct Item {
+    id: u32,
+    name: &'static str,
+}
+
+fn item(id: u32, name: &'static str) -> Item {
+    Item { id, name }
+}
+
+fn list_app(
+    backend: &TestBackend,
+    items: &Signal<Vec<Item>>,
+) -> (App, NodeRef, Rc<StdCell<usize>>) {
+    let mut dom = Dom::new();
+    let root = dom.create("List", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    let mut app = App::from_tcss_string(
+        backend,
+        dom,
+        "List { display: flex; flex-direction: column; } Label { height: 1; }",
+    )
+    .unwrap();
+
+    let created = Rc::new(StdCell::new(0));
+    let counter = Rc::clone(&created);
+    app.bind_list(
+        root,
+        items,
+        |item: &Item| item.id,
+        move |app: &mut App, item: &Item| {
+            counter.set(counter.get() + 1);
+            Ok(app
+                .dom_mut()
+                .create("Label", Box::new(StyledLeaf::new(Label::new(item.name)))))
+        },
+    )
+    .unwrap();
+    (app, root, created)
+}
+
+fn texts(app: &mut App) -> Vec<String> {
+    let labels = app.query("Label").unwrap();
+    labels
+        .into_iter()
+        .map(|node| label_text(app, node))
+        .collect()
+}
+
+#[test]
+fn bind_list_mounts_initial_items() {
+    let backend = TestBackend::new(20, 6);
+    let items = Signal::new(vec![item(1, "one"), item(2, "two")]);
+    let (mut app, _, created) = list_app(&backend, &items);
+    assert_eq!(texts(&mut app), vec!["one", "two"]);
+    assert_eq!(created.get(), 2);
+}
+
+#[test]
+fn bind_list_keyed_diff_reuses_reorders_and_removes() {
+    let mut backend = TestBackend::new(20, 6);
+    let items = Signal::new(vec![item(1, "one"), item(2, "two"), item(3, "three")]);
+    let (mut app, _, created) = list_app(&backend, &items);
+    let before = app.query("Label").unwrap();
+
+    items.set(vec![item(3, "three"), item(4, "four"), item(1, "one")]);
+    app.render_frame(&mut backend).unwrap();
+
+    assert_eq!(texts(&mut app), vec!["three", "four", "one"]);
+    // Only the new key created a node; surviving keys kept theirs.
+    assert_eq!(created.get(), 4);
+    let after = app.query("Label").unwrap();
+    assert_eq!(after[0], before[2]);
+    assert_eq!(after[2], before[0]);
+    assert!(!after.contains(&before[1]));
+
+    // Layout follows the new order.
+    assert_eq!(app.rect_of(after[0]).unwrap().position.y, 0);
+    assert_eq!(app.rect_of(after[2]).unwrap().position.y, 2);
+
+    items.set(Vec::new());
+    app.apply_bindings().unwrap();
+    assert!(app.query("Label").unwrap().is_empty());
+}
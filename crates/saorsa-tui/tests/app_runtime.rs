@@ -3,13 +3,19 @@
 
 use std::cell::Cell as StdCell;
 use std::rc::Rc;
+use std::time::Duration;
 
-use saorsa_tui::app::{App, Dom, Leaf, StyledLeaf};
+use saorsa_tui::Color;
+use saorsa_tui::app::{App, Dom, Interactive, Leaf, StyledInteractive, StyledLeaf};
 use saorsa_tui::buffer::ScreenBuffer;
-use saorsa_tui::event::{Event, KeyCode, KeyEvent, Modifiers};
+use saorsa_tui::color::NamedColor;
+use saorsa_tui::event::{Event, KeyCode, KeyEvent, Modifiers, MouseEvent, MouseEventKind};
 use saorsa_tui::geometry::Rect;
-use saorsa_tui::terminal::TestBackend;
-use saorsa_tui::widget::{Container, Label};
+use saorsa_tui::highlight::{HighlightSpan, Highlighter, SyntaxTheme, TextEdit};
+use saorsa_tui::style::Style;
+use saorsa_tui::tcss::ThemeVariant;
+use saorsa_tui::terminal::{MockQuerier, TerminalQuerier, TestBackend};
+use saorsa_tui::widget::{Container, EventResult, Label, TextArea};
 
 struct HookWidget {
     mounted: Rc<StdCell<usize>>,
@@ -206,3 +212,991 @@ fn actions_keybinding_dispatches_app_action() {
     // Should be safe to render after actions.
     app.render_if_needed(&mut backend).unwrap();
 }
+
+#[test]
+fn actions_are_listed_and_run_by_name() {
+    let backend = TestBackend::new(20, 5);
+    let mut dom = Dom::new();
+    let root = dom.create(
+        "Root",
+        Box::new(Leaf::new(saorsa_tui::StaticWidget::new(vec![]))),
+    );
+    dom.set_root(root);
+    let mut app = make_app(&backend, dom, "");
+
+    let count = Rc::new(StdCell::new(0));
+    let count2 = count.clone();
+    app.register_action(
+        "save",
+        Box::new(move |_app: &mut App| {
+            count2.set(count2.get() + 1);
+            Ok(saorsa_tui::widget::EventResult::Consumed)
+        }),
+    );
+    app.register_action(
+        "quit",
+        Box::new(|_app: &mut App| Ok(saorsa_tui::widget::EventResult::Consumed)),
+    );
+    app.describe_action("save", "Save the document");
+    app.bind_key(KeyEvent::new(KeyCode::Char('s'), Modifiers::CTRL), "save");
+    app.bind_key(KeyEvent::plain(KeyCode::F(2)), "save");
+    app.bind_key(KeyEvent::plain(KeyCode::F(3)), "missing");
+
+    let actions = app.actions();
+    let names: Vec<&str> = actions.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["quit", "save"]);
+    assert_eq!(actions[0].description, None);
+    assert!(actions[0].keys.is_empty());
+    assert_eq!(actions[1].description.as_deref(), Some("Save the document"));
+    let keys: Vec<String> = actions[1].keys.iter().map(ToString::to_string).collect();
+    assert_eq!(keys, ["Ctrl+S", "F2"]);
+
+    let res = app.run_action("save").unwrap();
+    assert_eq!(res, saorsa_tui::widget::EventResult::Consumed);
+    assert_eq!(count.get(), 1);
+    let res = app.run_action("missing").unwrap();
+    assert_eq!(res, saorsa_tui::widget::EventResult::Ignored);
+    // The action is still registered after running.
+    app.handle_event(&Event::Key(KeyEvent::plain(KeyCode::F(2))))
+        .unwrap();
+    assert_eq!(count.get(), 2);
+}
+
+fn mouse(kind: MouseEventKind, x: u16, y: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        x,
+        y,
+        modifiers: Modifiers::NONE,
+    })
+}
+
+#[test]
+fn mouse_drag_selects_and_copies_via_osc52() {
+    let mut backend = TestBackend::new(20, 3);
+    let mut dom = Dom::new();
+
+    let root = dom.create(
+        "Root",
+        Box::new(Leaf::new(saorsa_tui::StaticWidget::new(vec![]))),
+    );
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    let code = dom.create("Label", Box::new(StyledLeaf::new(Label::new("let x = 1;"))));
+
+    let mut app = make_app(
+        &backend,
+        dom,
+        "#root { display: flex; flex-direction: column; } Label { height: 1; }",
+    );
+    app.mount(root, code).unwrap();
+    app.render_frame(&mut backend).unwrap();
+
+    app.handle_event(&mouse(MouseEventKind::Press, 0, 0))
+        .unwrap();
+    let res = app
+        .handle_event(&mouse(MouseEventKind::Drag, 4, 0))
+        .unwrap();
+    assert_eq!(res, saorsa_tui::widget::EventResult::Consumed);
+    app.handle_event(&mouse(MouseEventKind::Release, 4, 0))
+        .unwrap();
+    assert_eq!(app.clipboard().text().as_deref(), Some("let x"));
+
+    backend.clear_buffer();
+    assert!(app.render_if_needed(&mut backend).unwrap());
+    let out = String::from_utf8_lossy(backend.buffer()).into_owned();
+    assert!(out.contains("\x1b[7m"));
+    assert!(out.ends_with("\x1b]52;c;bGV0IHg=\x07"));
+
+    // A key press clears the highlight.
+    app.handle_event(&Event::Key(KeyEvent::plain(KeyCode::Escape)))
+        .unwrap();
+    assert!(app.selection().is_none());
+}
+
+#[test]
+fn click_without_drag_does_not_copy() {
+    let mut backend = TestBackend::new(20, 3);
+    let mut dom = Dom::new();
+    let root = dom.create("Label", Box::new(StyledLeaf::new(Label::new("text"))));
+    dom.set_root(root);
+
+    let mut app = make_app(&backend, dom, "");
+    app.render_frame(&mut backend).unwrap();
+
+    app.handle_event(&mouse(MouseEventKind::Press, 1, 0))
+        .unwrap();
+    app.handle_event(&mouse(MouseEventKind::Release, 1, 0))
+        .unwrap();
+    assert!(app.selection().is_none());
+    assert!(app.clipboard().text().is_none());
+}
+
+#[test]
+fn text_area_drag_selects_text_instead_of_the_screen() {
+    let mut backend = TestBackend::new(20, 3);
+    let mut dom = Dom::new();
+    let clipboard = saorsa_tui::Clipboard::new();
+    let editor = TextArea::from_text("fn main() {}").with_clipboard(clipboard.clone());
+    let root = dom.create("TextArea", Box::new(StyledInteractive::new(editor)));
+    dom.set_root(root);
+    dom.set_focusable(root, true);
+
+    let mut app = make_app(&backend, dom, "");
+    app.render_frame(&mut backend).unwrap();
+
+    app.handle_event(&mouse(MouseEventKind::Press, 3, 0))
+        .unwrap();
+    app.handle_event(&mouse(MouseEventKind::Drag, 7, 0))
+        .unwrap();
+    app.handle_event(&mouse(MouseEventKind::Release, 7, 0))
+        .unwrap();
+    assert!(app.selection().is_none());
+    assert!(app.clipboard().text().is_none());
+
+    app.handle_event(&Event::Key(KeyEvent::new(
+        KeyCode::Char('c'),
+        Modifiers::CTRL,
+    )))
+    .unwrap();
+    assert_eq!(clipboard.text().as_deref(), Some("main"));
+}
+
+#[test]
+fn key_release_does_not_fire_bindings() {
+    let backend = TestBackend::new(20, 5);
+    let mut dom = Dom::new();
+    let root = dom.create(
+        "Root",
+        Box::new(Leaf::new(saorsa_tui::StaticWidget::new(vec![]))),
+    );
+    dom.set_root(root);
+
+    let mut app = make_app(&backend, dom, "");
+    let count = Rc::new(StdCell::new(0));
+    let count2 = count.clone();
+    app.register_action(
+        "send",
+        Box::new(move |_app: &mut App| {
+            count2.set(count2.get() + 1);
+            Ok(saorsa_tui::widget::EventResult::Consumed)
+        }),
+    );
+    app.bind_key(KeyEvent::new(KeyCode::Enter, Modifiers::SHIFT), "send");
+
+    let press = KeyEvent::new(KeyCode::Enter, Modifiers::SHIFT);
+    let release = press
+        .clone()
+        .with_kind(saorsa_tui::event::KeyEventKind::Release);
+    app.handle_event(&Event::Key(press)).unwrap();
+    let res = app.handle_event(&Event::Key(release)).unwrap();
+    assert_eq!(res, saorsa_tui::widget::EventResult::Ignored);
+    // Plain Enter is a different binding.
+    app.handle_event(&Event::Key(KeyEvent::plain(KeyCode::Enter)))
+        .unwrap();
+    assert_eq!(count.get(), 1);
+}
+
+#[test]
+fn nested_rects_are_absolute() {
+    let mut backend = TestBackend::new(20, 6);
+    let mut dom = Dom::new();
+    let root = dom.create(
+        "Root",
+        Box::new(Leaf::new(saorsa_tui::StaticWidget::new(vec![]))),
+    );
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    let panel = dom.create("Panel", Box::new(StyledLeaf::new(Container::new())));
+    let label = dom.create("Label", Box::new(StyledLeaf::new(Label::new("inner"))));
+
+    let mut app = make_app(
+        &backend,
+        dom,
+        r#"
+        #root { display: flex; flex-direction: column; }
+        Panel { margin-top: 2; padding-left: 3; height: 3; }
+        Label { height: 1; }
+        "#,
+    );
+    app.mount(root, panel).unwrap();
+    app.mount(panel, label).unwrap();
+    app.render_frame(&mut backend).unwrap();
+
+    assert_eq!(app.rect_of(panel).unwrap().position.y, 2);
+    let inner = app.rect_of(label).unwrap();
+    assert_eq!((inner.position.x, inner.position.y), (3, 2));
+}
+
+/// A 20x6 screen with a 4-row scrollable list of ten focusable rows.
+fn scrolling_app(
+    backend: &TestBackend,
+    extra_css: &str,
+) -> (App, saorsa_tui::app::NodeRef, Vec<saorsa_tui::app::NodeRef>) {
+    let mut dom = Dom::new();
+    let root = dom.create(
+        "Root",
+        Box::new(Leaf::new(saorsa_tui::StaticWidget::new(vec![]))),
+    );
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    let list = dom.create("List", Box::new(StyledLeaf::new(Container::new())));
+    let rows: Vec<_> = (0..10)
+        .map(|i| {
+            let row = dom.create(
+                "Label",
+                Box::new(StyledLeaf::new(Label::new(format!("row{i}")))),
+            );
+            dom.set_focusable(row, true);
+            row
+        })
+        .collect();
+
+    let css = format!(
+        r#"
+        #root {{ display: flex; flex-direction: column; }}
+        List {{ display: flex; flex-direction: column; height: 4; overflow-y: scroll; }}
+        Label {{ height: 1; flex-shrink: 0; }}
+        {extra_css}
+        "#
+    );
+    let mut app = make_app(backend, dom, &css);
+    app.mount(root, list).unwrap();
+    for &row in &rows {
+        app.mount(list, row).unwrap();
+    }
+    (app, list, rows)
+}
+
+fn screen_text(backend: &TestBackend) -> String {
+    String::from_utf8_lossy(backend.buffer()).into_owned()
+}
+
+#[test]
+fn scroll_container_clips_children_and_draws_scrollbar() {
+    let mut backend = TestBackend::new(20, 6);
+    let (mut app, list, _) = scrolling_app(&backend, "");
+    app.render_frame(&mut backend).unwrap();
+
+    let state = app.scroll_state(list).unwrap();
+    assert_eq!(state.content_height, 10);
+    assert_eq!(state.viewport_height, 4);
+    assert_eq!(state.viewport_width, 19);
+
+    let out = screen_text(&backend);
+    assert!(out.contains("row3"));
+    assert!(!out.contains("row4"));
+    assert!(out.contains('█'));
+
+    app.scroll_to(list, 0, 6);
+    backend.clear_buffer();
+    app.render_frame(&mut backend).unwrap();
+    // Only the row digits change: row6 at the top, row9 at the bottom.
+    let out = screen_text(&backend);
+    assert!(out.contains("\x1b[1;4H6"));
+    assert!(out.contains("\x1b[4;4H9"));
+}
+
+#[test]
+fn wheel_and_page_keys_scroll_nearest_container() {
+    let mut backend = TestBackend::new(20, 6);
+    let (mut app, list, rows) = scrolling_app(&backend, "");
+    app.render_frame(&mut backend).unwrap();
+
+    let res = app
+        .handle_event(&mouse(MouseEventKind::ScrollDown, 2, 1))
+        .unwrap();
+    assert_eq!(res, saorsa_tui::widget::EventResult::Consumed);
+    assert_eq!(app.scroll_state(list).unwrap().offset_y, 3);
+
+    // Hit testing follows the scrolled content.
+    app.handle_event(&mouse(MouseEventKind::Press, 2, 0))
+        .unwrap();
+    assert_eq!(app.dom().focus().focused(), Some(rows[3].0));
+
+    app.handle_event(&Event::Key(KeyEvent::plain(KeyCode::PageDown)))
+        .unwrap();
+    assert_eq!(app.scroll_state(list).unwrap().offset_y, 6);
+    app.handle_event(&Event::Key(KeyEvent::plain(KeyCode::PageUp)))
+        .unwrap();
+    assert_eq!(app.scroll_state(list).unwrap().offset_y, 3);
+
+    // Wheel outside any scroll container is not consumed.
+    let res = app
+        .handle_event(&mouse(MouseEventKind::ScrollDown, 2, 5))
+        .unwrap();
+    assert_eq!(res, saorsa_tui::widget::EventResult::Ignored);
+}
+
+#[test]
+fn focus_scrolls_into_view() {
+    let mut backend = TestBackend::new(20, 6);
+    let (mut app, list, rows) = scrolling_app(&backend, "");
+    app.render_frame(&mut backend).unwrap();
+
+    for _ in 0..6 {
+        app.handle_event(&Event::Key(KeyEvent::plain(KeyCode::Tab)))
+            .unwrap();
+    }
+    assert_eq!(app.dom().focus().focused(), Some(rows[6].0));
+    app.render_frame(&mut backend).unwrap();
+    assert_eq!(app.scroll_state(list).unwrap().offset_y, 3);
+
+    app.scroll_into_view(rows[1]);
+    app.render_frame(&mut backend).unwrap();
+    assert_eq!(app.scroll_state(list).unwrap().offset_y, 1);
+}
+
+#[test]
+fn scrollbar_size_zero_hides_scrollbar() {
+    let mut backend = TestBackend::new(20, 6);
+    let (mut app, list, _) = scrolling_app(&backend, "List { scrollbar-size: 0; }");
+    app.render_frame(&mut backend).unwrap();
+    assert_eq!(app.scroll_state(list).unwrap().viewport_width, 20);
+    assert!(!screen_text(&backend).contains('█'));
+}
+
+fn timer_app(backend: &TestBackend) -> App {
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    make_app(backend, dom, "")
+}
+
+#[test]
+fn timers_fire_in_due_order_on_advance() {
+    let backend = TestBackend::new(10, 2);
+    let mut app = timer_app(&backend);
+    let log = Rc::new(std::cell::RefCell::new(Vec::new()));
+    for (name, ms) in [("b", 20), ("a", 10), ("c", 20)] {
+        let log = Rc::clone(&log);
+        app.set_timer(
+            Duration::from_millis(ms),
+            Box::new(move |app| {
+                log.borrow_mut().push((name, app.clock().as_millis()));
+                Ok(EventResult::Ignored)
+            }),
+        );
+    }
+    assert_eq!(app.next_timer_in(), Some(Duration::from_millis(10)));
+    assert_eq!(app.advance_clock(Duration::from_millis(5)).unwrap(), 0);
+    assert_eq!(app.advance_clock(Duration::from_millis(20)).unwrap(), 3);
+    assert_eq!(*log.borrow(), vec![("a", 10), ("b", 20), ("c", 20)]);
+    assert_eq!(app.clock(), Duration::from_millis(25));
+    assert_eq!(app.next_timer_in(), None);
+}
+
+#[test]
+fn interval_repeats_until_cancelled() {
+    let backend = TestBackend::new(10, 2);
+    let mut app = timer_app(&backend);
+    let count = Rc::new(StdCell::new(0));
+    let counter = Rc::clone(&count);
+    let id = app.set_interval(
+        Duration::from_millis(10),
+        Box::new(move |_| {
+            counter.set(counter.get() + 1);
+            Ok(EventResult::Ignored)
+        }),
+    );
+    assert_eq!(app.advance_clock(Duration::from_millis(35)).unwrap(), 3);
+    assert!(app.cancel_timer(id));
+    assert!(!app.cancel_timer(id));
+    assert_eq!(app.advance_clock(Duration::from_millis(100)).unwrap(), 0);
+    assert_eq!(count.get(), 3);
+}
+
+#[test]
+fn interval_can_cancel_itself() {
+    let backend = TestBackend::new(10, 2);
+    let mut app = timer_app(&backend);
+    let id = Rc::new(StdCell::new(0));
+    let own = Rc::clone(&id);
+    id.set(app.set_interval(
+        Duration::from_millis(10),
+        Box::new(move |app| {
+            app.cancel_timer(own.get());
+            Ok(EventResult::Consumed)
+        }),
+    ));
+    assert_eq!(app.advance_clock(Duration::from_millis(50)).unwrap(), 1);
+    assert!(app.is_dirty());
+}
+
+/// A querier whose background color the test can change while the app
+/// owns it.
+struct SharedQuerier(Rc<std::cell::RefCell<MockQuerier>>);
+
+impl TerminalQuerier for SharedQuerier {
+    fn query_color_support(&mut self) -> Option<saorsa_tui::terminal::ColorSupport> {
+        None
+    }
+
+    fn query_synchronized_output(&mut self) -> Option<bool> {
+        None
+    }
+
+    fn query_kitty_keyboard(&mut self) -> Option<bool> {
+        None
+    }
+
+    fn query_background_color(&mut self) -> Option<Color> {
+        self.0.borrow_mut().query_background_color()
+    }
+}
+
+fn themed_app(backend: &TestBackend, tcss: &str) -> App {
+    let mut dom = Dom::new();
+    let root = dom.create("Label", Box::new(StyledLeaf::new(Label::new("themed"))));
+    dom.set_root(root);
+    make_app(backend, dom, tcss)
+}
+
+const DARK_BG: Color = Color::Rgb {
+    r: 20,
+    g: 20,
+    b: 30,
+};
+const LIGHT_BG: Color = Color::Rgb {
+    r: 250,
+    g: 250,
+    b: 245,
+};
+
+#[test]
+fn auto_theme_picks_builtin_variant_and_redetects_on_focus() {
+    let mut backend = TestBackend::new(10, 1);
+    let mut app = themed_app(&backend, "Label { color: $fg; }");
+    let querier = Rc::new(std::cell::RefCell::new(
+        MockQuerier::new().with_background_color(LIGHT_BG),
+    ));
+    let variant = app.enable_auto_theme(Box::new(SharedQuerier(Rc::clone(&querier))), None);
+    assert_eq!(variant, Some(ThemeVariant::Light));
+    assert_eq!(app.active_theme(), Some("light"));
+    app.render_frame(&mut backend).unwrap();
+    let light_fg = app.screen().get(0, 0).unwrap().style.fg.clone();
+    assert!(light_fg.is_some());
+
+    // The terminal switched to dark mode while unfocused.
+    // The terminal is queried before the next frame, not while the event
+    // is dispatched.
+    querier.borrow_mut().set_background_color(Some(DARK_BG));
+    app.handle_event(&Event::FocusGained).unwrap();
+    assert_eq!(app.theme_variant(), Some(ThemeVariant::Light));
+    assert!(app.is_dirty());
+    app.render_frame(&mut backend).unwrap();
+    assert_eq!(app.theme_variant(), Some(ThemeVariant::Dark));
+    assert_eq!(app.active_theme(), Some("dark"));
+    assert_ne!(app.screen().get(0, 0).unwrap().style.fg, light_fg);
+
+    app.disable_auto_theme();
+    querier.borrow_mut().set_background_color(Some(LIGHT_BG));
+    app.handle_event(&Event::FocusGained).unwrap();
+    app.render_frame(&mut backend).unwrap();
+    assert_eq!(app.active_theme(), Some("dark"));
+}
+
+#[test]
+fn auto_theme_uses_family_variants() {
+    let backend = TestBackend::new(10, 1);
+    let mut app = themed_app(
+        &backend,
+        ".brand-dark { $fg: #ffffff; } .brand-light { $fg: #000000; }",
+    );
+    let querier = MockQuerier::new().with_background_color(DARK_BG);
+    app.enable_auto_theme(Box::new(querier), Some("brand"));
+    assert_eq!(app.active_theme(), Some("brand-dark"));
+
+    let querier = MockQuerier::new().with_background_color(LIGHT_BG);
+    app.enable_auto_theme(Box::new(querier), Some("catppuccin"));
+    assert_eq!(app.active_theme(), Some("catppuccin-latte"));
+
+    // Nord has no light variant: fall back to the built-in light theme.
+    let querier = MockQuerier::new().with_background_color(LIGHT_BG);
+    app.enable_auto_theme(Box::new(querier), Some("nord"));
+    assert_eq!(app.active_theme(), Some("light"));
+}
+
+#[test]
+fn auto_theme_without_answer_keeps_theme() {
+    let backend = TestBackend::new(10, 1);
+    let mut app = themed_app(&backend, "");
+    app.set_active_theme(Some("dracula"));
+    assert_eq!(
+        app.enable_auto_theme(Box::new(MockQuerier::new()), None),
+        None
+    );
+    assert_eq!(app.active_theme(), Some("dracula"));
+    assert_eq!(app.theme_variant(), None);
+}
+
+fn layered_app(tcss: &str) -> saorsa_tui::Pilot {
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    for id in ["ghost", "glass"] {
+        let label = dom.create("Label", Box::new(StyledLeaf::new(Label::new(id))));
+        dom.set_css_id(label, id);
+        dom.append_child(root, label);
+    }
+    saorsa_tui::Pilot::from_tcss(10, 3, dom, tcss).unwrap()
+}
+
+const LAYERED_TCSS: &str = "
+#root { display: flex; flex-direction: column; background: rgb(0, 0, 200); }
+Label { height: 1; color: rgb(255, 255, 255); }
+#ghost { background: rgb(200, 0, 0); opacity: 0.5; }
+#glass { background: transparent; }
+";
+
+#[test]
+fn opacity_blends_node_over_parent() {
+    let pilot = layered_app(LAYERED_TCSS);
+    assert_eq!(pilot.line(0), "ghost");
+    let style = pilot.style_at(0, 0).unwrap();
+    assert_eq!(
+        style.fg,
+        Some(Color::Rgb {
+            r: 128,
+            g: 128,
+            b: 228
+        })
+    );
+    assert_eq!(
+        style.bg,
+        Some(Color::Rgb {
+            r: 100,
+            g: 0,
+            b: 100
+        })
+    );
+    assert!(!style.dim);
+}
+
+#[test]
+fn transparent_background_keeps_parent_background() {
+    let pilot = layered_app(LAYERED_TCSS);
+    assert_eq!(pilot.line(1), "glass");
+    let blue = Some(Color::Rgb { r: 0, g: 0, b: 200 });
+    assert_eq!(pilot.style_at(0, 1).unwrap().bg, blue);
+    assert_eq!(pilot.style_at(8, 1).unwrap().bg, blue);
+    assert_eq!(
+        pilot.style_at(0, 1).unwrap().fg,
+        Some(Color::Rgb {
+            r: 255,
+            g: 255,
+            b: 255
+        })
+    );
+}
+
+#[test]
+fn opaque_nodes_paint_over_parent() {
+    let pilot = layered_app(
+        "#root { display: flex; flex-direction: column; background: rgb(0, 0, 200); }
+         Label { height: 1; }",
+    );
+    assert_eq!(pilot.style_at(0, 1).unwrap().bg, None);
+}
+
+/// A column of `rows` labels with ids `row{i}`.
+fn rows_app(rows: usize) -> (saorsa_tui::Pilot, Vec<saorsa_tui::app::NodeRef>) {
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    let labels: Vec<_> = (0..rows)
+        .map(|i| {
+            let label = dom.create(
+                "Label",
+                Box::new(StyledLeaf::new(Label::new(format!("row {i}")))),
+            );
+            dom.set_css_id(label, format!("row{i}"));
+            dom.append_child(root, label);
+            label
+        })
+        .collect();
+    let tcss = "
+        #root { display: flex; flex-direction: column; }
+        Label { height: 1; }
+        .hot { color: red; }
+        .tall { height: 2; }
+        Label[kind=error] { color: blue; }
+    ";
+    (
+        saorsa_tui::Pilot::from_tcss(12, rows as u16 + 2, dom, tcss).unwrap(),
+        labels,
+    )
+}
+
+fn screen_cells(pilot: &saorsa_tui::Pilot) -> Vec<saorsa_tui::Cell> {
+    let screen = pilot.app().screen();
+    (0..screen.height())
+        .flat_map(|y| screen.get_row(y).unwrap().to_vec())
+        .collect()
+}
+
+/// The current frame must equal a full repaint of the same state.
+fn assert_matches_full_repaint(pilot: &mut saorsa_tui::Pilot) {
+    let partial = screen_cells(pilot);
+    pilot.app_mut().request_render();
+    pilot.wait_for_idle().unwrap();
+    assert!(pilot.app().last_frame_stats().full_repaint);
+    assert_eq!(partial, screen_cells(pilot));
+}
+
+#[test]
+fn binding_change_repaints_only_its_node() {
+    let (mut pilot, labels) = rows_app(20);
+    let text = saorsa_tui::reactive::Signal::new("row 3".to_string());
+    pilot.app_mut().bind_text(labels[3], &text).unwrap();
+    pilot.wait_for_idle().unwrap();
+
+    text.set("changed".into());
+    pilot.app_mut().apply_bindings().unwrap();
+    assert!(pilot.app().is_node_dirty(labels[3]));
+    pilot.wait_for_idle().unwrap();
+
+    let stats = pilot.app().last_frame_stats();
+    assert!(!stats.full_repaint);
+    assert!(!stats.layout);
+    assert_eq!(stats.restyled, 0);
+    // The label and the root behind it.
+    assert_eq!(stats.painted, 2);
+    assert_eq!(pilot.line(3), "changed");
+    assert_eq!(pilot.line(4), "row 4");
+    assert_matches_full_repaint(&mut pilot);
+}
+
+#[test]
+fn mark_node_dirty_repaints_direct_changes() {
+    let (mut pilot, labels) = rows_app(5);
+    pilot
+        .app_mut()
+        .dom_mut()
+        .downcast_widget_mut::<Label>(labels[1])
+        .unwrap()
+        .set_text("direct");
+    pilot.app_mut().mark_node_dirty(labels[1]);
+    assert!(pilot.app().dirty_flags(labels[1]).paint);
+    pilot.wait_for_idle().unwrap();
+
+    assert!(!pilot.app().last_frame_stats().full_repaint);
+    assert_eq!(pilot.line(1), "direct");
+    assert!(pilot.app().dirty_flags(labels[1]).is_clean());
+    assert_matches_full_repaint(&mut pilot);
+}
+
+#[test]
+fn style_change_restyles_without_relayout() {
+    let (mut pilot, labels) = rows_app(10);
+    pilot.app_mut().dom_mut().add_class(labels[2], "hot");
+    pilot.app_mut().mark_style_dirty(labels[2]);
+    assert!(pilot.app().dirty_flags(labels[2]).style);
+    pilot.wait_for_idle().unwrap();
+
+    let stats = pilot.app().last_frame_stats();
+    assert!(!stats.layout);
+    assert!(!stats.full_repaint);
+    // The node and its later siblings may depend on its classes.
+    assert!(stats.restyled < 10);
+    assert_eq!(
+        pilot.style_at(0, 2).unwrap().fg,
+        Some(Color::Named(saorsa_tui::color::NamedColor::Red))
+    );
+    assert_matches_full_repaint(&mut pilot);
+}
+
+#[test]
+fn attribute_change_restyles_matching_nodes() {
+    let (mut pilot, labels) = rows_app(4);
+    pilot.wait_for_idle().unwrap();
+    pilot
+        .app_mut()
+        .dom_mut()
+        .set_attribute(labels[1], "kind", "error");
+    assert!(pilot.app().is_dirty());
+    pilot.wait_for_idle().unwrap();
+    assert_eq!(
+        pilot.style_at(0, 1).unwrap().fg,
+        Some(Color::Named(saorsa_tui::color::NamedColor::Blue))
+    );
+
+    pilot
+        .app_mut()
+        .dom_mut()
+        .set_attribute(labels[1], "kind", "ok");
+    pilot.wait_for_idle().unwrap();
+    assert_eq!(pilot.style_at(0, 1).unwrap().fg, None);
+    assert_matches_full_repaint(&mut pilot);
+}
+
+#[test]
+fn has_change_deep_in_tree_restyles_dependents() {
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    let panel = dom.create("Panel", Box::new(StyledLeaf::new(Container::new())));
+    dom.append_child(root, panel);
+    let row = dom.create("Row", Box::new(StyledLeaf::new(Container::new())));
+    dom.append_child(panel, row);
+    let item = dom.create("Item", Box::new(StyledLeaf::new(Label::new("item"))));
+    dom.append_child(row, item);
+    let label = dom.create("Label", Box::new(StyledLeaf::new(Label::new("label"))));
+    dom.append_child(panel, label);
+    let after = dom.create("Box", Box::new(StyledLeaf::new(Label::new("after"))));
+    dom.append_child(root, after);
+    let tcss = "
+        Root, Panel, Row { display: flex; flex-direction: column; }
+        Panel { height: 2; }
+        Row, Item, Label, Box { height: 1; }
+        Panel:has(.error) Label { color: red; }
+        Panel:has(.error) + Box { color: blue; }
+    ";
+    let mut pilot = saorsa_tui::Pilot::from_tcss(12, 4, dom, tcss).unwrap();
+    pilot.wait_for_idle().unwrap();
+    assert_eq!(pilot.style_at(0, 1).unwrap().fg, None);
+    assert_eq!(pilot.style_at(0, 2).unwrap().fg, None);
+
+    pilot.app_mut().dom_mut().add_class(item, "error");
+    pilot.wait_for_idle().unwrap();
+    assert_eq!(
+        pilot.style_at(0, 1).unwrap().fg,
+        Some(Color::Named(NamedColor::Red))
+    );
+    assert_eq!(
+        pilot.style_at(0, 2).unwrap().fg,
+        Some(Color::Named(NamedColor::Blue))
+    );
+
+    pilot.app_mut().dom_mut().remove_class(item, "error");
+    pilot.wait_for_idle().unwrap();
+    assert_eq!(pilot.style_at(0, 1).unwrap().fg, None);
+    assert_eq!(pilot.style_at(0, 2).unwrap().fg, None);
+    assert_matches_full_repaint(&mut pilot);
+}
+
+#[test]
+fn layout_change_repaints_moved_nodes() {
+    let (mut pilot, labels) = rows_app(6);
+    pilot.app_mut().dom_mut().add_class(labels[1], "tall");
+    pilot.app_mut().mark_style_dirty(labels[1]);
+    pilot.wait_for_idle().unwrap();
+
+    let stats = pilot.app().last_frame_stats();
+    assert!(stats.layout);
+    assert_eq!(stats.relaid_out, 1);
+    assert!(!stats.full_repaint);
+    assert_eq!(pilot.line(3), "row 2");
+    assert_matches_full_repaint(&mut pilot);
+
+    pilot.app_mut().remove_subtree(labels[0]).unwrap();
+    pilot.wait_for_idle().unwrap();
+    assert_eq!(pilot.line(0), "row 1");
+    assert_matches_full_repaint(&mut pilot);
+}
+
+#[test]
+fn focus_change_repaints_both_nodes() {
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    for _ in 0..3 {
+        let label = dom.create("Label", Box::new(StyledLeaf::new(Label::new("item"))));
+        dom.set_focusable(label, true);
+        dom.append_child(root, label);
+    }
+    let mut pilot = saorsa_tui::Pilot::from_tcss(
+        10,
+        4,
+        dom,
+        "Root { display: flex; flex-direction: column; }
+         Label { height: 1; }
+         Label:focus { text-style: reverse; }",
+    )
+    .unwrap();
+    assert!(pilot.style_at(0, 0).unwrap().reverse);
+    pilot.press(KeyCode::Tab).unwrap();
+    pilot.wait_for_idle().unwrap();
+    assert!(!pilot.app().last_frame_stats().full_repaint);
+    assert!(!pilot.style_at(0, 0).unwrap().reverse);
+    assert!(pilot.style_at(0, 1).unwrap().reverse);
+    assert_matches_full_repaint(&mut pilot);
+}
+
+/// Highlights a leading `fn` with the theme's keyword style.
+struct ThemedKeyword(Style);
+
+impl Highlighter for ThemedKeyword {
+    fn highlight_line(&self, _line_idx: usize, text: &str) -> Vec<HighlightSpan> {
+        if !text.starts_with("fn") {
+            return Vec::new();
+        }
+        vec![HighlightSpan {
+            start_col: 0,
+            end_col: 2,
+            style: self.0.clone(),
+        }]
+    }
+
+    fn on_edit(&mut self, _edit: &TextEdit<'_>) {}
+
+    fn set_theme(&mut self, theme: &SyntaxTheme) {
+        self.0 = theme.style_for("keyword").cloned().unwrap_or_default();
+    }
+}
+
+#[test]
+fn theme_variables_restyle_syntax_highlighting() {
+    let mut dom = Dom::new();
+    let editor = TextArea::from_text("fn main() {}")
+        .with_highlighter(Box::new(ThemedKeyword(Style::default())));
+    let root = dom.create("TextArea", Box::new(StyledInteractive::new(editor)));
+    dom.set_root(root);
+    let mut pilot = saorsa_tui::Pilot::from_tcss(
+        20,
+        2,
+        dom,
+        ":root { $syntax-keyword: green; }
+         .alt { $syntax-keyword: red; }",
+    )
+    .unwrap();
+    // Column 0 holds the cursor.
+    let fg = |pilot: &saorsa_tui::Pilot| pilot.style_at(1, 0).and_then(|s| s.fg.clone());
+    assert_eq!(fg(&pilot), Some(Color::Named(NamedColor::Green)));
+
+    pilot.app_mut().set_active_theme(Some("alt"));
+    pilot.wait_for_idle().unwrap();
+    assert_eq!(fg(&pilot), Some(Color::Named(NamedColor::Red)));
+}
+
+#[test]
+fn text_area_edit_mode_is_a_pseudo_class() {
+    let mut dom = Dom::new();
+    let editor = TextArea::from_text("abc").with_keymap(saorsa_tui::KeymapKind::Vim);
+    let root = dom.create("TextArea", Box::new(StyledInteractive::new(editor)));
+    dom.set_root(root);
+    dom.set_focusable(root, true);
+    let mut pilot = saorsa_tui::Pilot::from_tcss(
+        20,
+        2,
+        dom,
+        "TextArea:vim-normal { color: green; }
+         TextArea:vim-insert { color: red; }",
+    )
+    .unwrap();
+    let fg = |pilot: &saorsa_tui::Pilot| pilot.style_at(2, 0).and_then(|s| s.fg.clone());
+    assert_eq!(fg(&pilot), Some(Color::Named(NamedColor::Green)));
+
+    pilot.press(KeyCode::Char('i')).unwrap();
+    assert_eq!(fg(&pilot), Some(Color::Named(NamedColor::Red)));
+
+    pilot.press(KeyCode::Escape).unwrap();
+    assert_eq!(fg(&pilot), Some(Color::Named(NamedColor::Green)));
+}
+
+struct Player {
+    label: Label,
+    playing: bool,
+}
+
+impl saorsa_tui::widget::Widget for Player {
+    fn render(&self, area: Rect, buf: &mut ScreenBuffer) {
+        self.label.render(area, buf);
+    }
+}
+
+impl saorsa_tui::widget::InteractiveWidget for Player {
+    fn handle_event(&mut self, event: &Event) -> EventResult {
+        match event {
+            Event::Key(key) if key.code == KeyCode::Char('p') => {
+                self.playing = !self.playing;
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn pseudo_classes(&self) -> Vec<&'static str> {
+        if self.playing {
+            vec!["playing"]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn declared_pseudo_classes(&self) -> &'static [&'static str] {
+        &["playing"]
+    }
+}
+
+impl saorsa_tui::tcss::ApplyComputedStyle for Player {
+    fn apply_computed_style(&mut self, computed: &saorsa_tui::tcss::ComputedStyle) {
+        self.label.apply_computed_style(computed);
+    }
+}
+
+#[test]
+fn declared_widget_states_are_pseudo_classes() {
+    let tcss = "Player:playing { color: red; } Label, Label:playing { color: blue; }";
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    let player = Player {
+        label: Label::new("play"),
+        playing: false,
+    };
+    let player = dom.create("Player", Box::new(StyledInteractive::new(player)));
+    dom.set_focusable(player, true);
+    dom.append_child(root, player);
+    let label = dom.create("Label", Box::new(StyledLeaf::new(Label::new("label"))));
+    dom.append_child(root, label);
+    let tcss_layout = format!(
+        "Root {{ display: flex; flex-direction: column; }} Player, Label {{ height: 1; }} {tcss}"
+    );
+    let mut pilot = saorsa_tui::Pilot::from_tcss(10, 2, dom, &tcss_layout).unwrap();
+    let fg = |pilot: &saorsa_tui::Pilot, y| pilot.style_at(0, y).and_then(|s| s.fg.clone());
+    assert_eq!(fg(&pilot, 0), None);
+    assert_eq!(fg(&pilot, 1), Some(Color::Named(NamedColor::Blue)));
+
+    pilot.press(KeyCode::Char('p')).unwrap();
+    assert_eq!(fg(&pilot, 0), Some(Color::Named(NamedColor::Red)));
+
+    // An app without players does not know the state and drops the rule.
+    let mut dom = Dom::new();
+    let root = dom.create("Label", Box::new(StyledLeaf::new(Label::new("label"))));
+    dom.set_root(root);
+    let pilot = saorsa_tui::Pilot::from_tcss(10, 1, dom, tcss).unwrap();
+    assert_eq!(fg(&pilot, 0), None);
+}
+
+#[test]
+fn tab_reaches_a_focused_terminal_view() {
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    let term = dom.create(
+        "TerminalView",
+        Box::new(Interactive::new(saorsa_tui::TerminalView::new(10, 2))),
+    );
+    dom.set_focusable(term, true);
+    dom.append_child(root, term);
+    let label = dom.create("Label", Box::new(StyledLeaf::new(Label::new("next"))));
+    dom.set_focusable(label, true);
+    dom.append_child(root, label);
+    let mut pilot = saorsa_tui::Pilot::from_tcss(10, 4, dom, "").unwrap();
+    assert_eq!(pilot.focused(), Some(term));
+
+    pilot.press(KeyCode::Tab).unwrap();
+    pilot.press_with(KeyCode::Tab, Modifiers::SHIFT).unwrap();
+    assert_eq!(pilot.focused(), Some(term));
+    let input = pilot
+        .app_mut()
+        .dom_mut()
+        .downcast_widget_mut::<saorsa_tui::TerminalView>(term)
+        .unwrap()
+        .take_input();
+    assert_eq!(input, b"\t\x1b[Z");
+}
@@ -0,0 +1,90 @@
+#![allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+#![allow(missing_docs)]
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use saorsa_tui::app::{App, Dom, StyledLeaf};
+use saorsa_tui::terminal::{RecordingTerminal, TestBackend};
+use saorsa_tui::widget::{Container, Label};
+use saorsa_tui::{ExportOptions, Pilot};
+
+const TCSS: &str = "
+#root { display: flex; flex-direction: column; }
+Label { height: 1; }
+#title { color: #ffaa00; background: blue; text-style: bold underline; }
+";
+
+fn dom() -> (Dom, saorsa_tui::app::NodeRef) {
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    let title = dom.create("Label", Box::new(StyledLeaf::new(Label::new("Report <1>"))));
+    dom.set_css_id(title, "title");
+    let body = dom.create(
+        "Label",
+        Box::new(StyledLeaf::new(Label::new("世界 & more"))),
+    );
+    dom.append_child(root, title);
+    dom.append_child(root, body);
+    (dom, body)
+}
+
+#[test]
+fn app_svg_snapshot() {
+    let (dom, _) = dom();
+    let pilot = Pilot::from_tcss(14, 2, dom, TCSS).unwrap();
+    let svg = pilot.app().export_svg(
+        &ExportOptions::new()
+            .font_family("monospace")
+            .title("report"),
+    );
+    insta::assert_snapshot!(svg);
+}
+
+#[test]
+fn app_html_snapshot() {
+    let (dom, _) = dom();
+    let pilot = Pilot::from_tcss(14, 2, dom, TCSS).unwrap();
+    let html = pilot
+        .app()
+        .export_html(&ExportOptions::new().font_family("monospace"));
+    insta::assert_snapshot!(html);
+}
+
+#[test]
+fn recording_terminal_captures_app_session() {
+    let (dom, body) = dom();
+    let millis = Arc::new(AtomicU64::new(0));
+    let clock = Arc::clone(&millis);
+    let mut term = RecordingTerminal::new(TestBackend::new(14, 2), Vec::new())
+        .with_timestamp(Some(1_700_000_000))
+        .with_clock(Box::new(move || {
+            Duration::from_millis(clock.load(Ordering::SeqCst))
+        }));
+    let mut app = App::from_tcss_string(term.inner(), dom, TCSS).unwrap();
+    app.render_frame(&mut term).unwrap();
+
+    millis.store(2_000, Ordering::SeqCst);
+    app.dom_mut()
+        .downcast_widget_mut::<Label>(body)
+        .unwrap()
+        .set_text("done");
+    app.request_render();
+    app.render_if_needed(&mut term).unwrap();
+
+    let (backend, cast) = term.finish().unwrap();
+    let cast = String::from_utf8(cast).unwrap();
+    let lines: Vec<&str> = cast.lines().collect();
+    assert_eq!(
+        lines[0],
+        "{\"version\": 2, \"width\": 14, \"height\": 2, \"timestamp\": 1700000000}"
+    );
+    assert!(lines[1].starts_with("[0.000000, \"o\", \""));
+    assert!(lines[1].contains("Report <1>"));
+    assert!(lines.last().unwrap().starts_with("[2.000000, \"o\", \""));
+    assert!(lines.last().unwrap().contains("done"));
+    assert!(String::from_utf8_lossy(backend.buffer()).contains("done"));
+}
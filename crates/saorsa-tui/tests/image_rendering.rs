@@ -0,0 +1,170 @@
+//! Byte-level tests for inline image output through the render pipeline.
+
+use saorsa_tui::cell::Cell;
+use saorsa_tui::geometry::Rect;
+use saorsa_tui::graphics::{ImageData, ImageProtocol, kitty, sixel};
+use saorsa_tui::render_context::RenderContext;
+use saorsa_tui::style::Style;
+use saorsa_tui::terminal::{MultiplexerKind, TestBackend};
+use saorsa_tui::widget::{Image, Widget};
+
+fn pixel_image() -> ImageData {
+    match ImageData::from_rgba(1, 1, vec![255, 0, 0, 255]) {
+        Ok(img) => img,
+        Err(_) => unreachable!("valid buffer"),
+    }
+}
+
+fn context(backend: &TestBackend) -> RenderContext {
+    match RenderContext::new(backend) {
+        Ok(ctx) => ctx,
+        Err(_) => unreachable!("test backend has a size"),
+    }
+}
+
+/// Render one frame with `draw` and return the bytes written.
+fn frame(
+    ctx: &mut RenderContext,
+    backend: &mut TestBackend,
+    draw: impl FnOnce(&mut RenderContext),
+) -> String {
+    backend.clear_buffer();
+    ctx.begin_frame();
+    draw(ctx);
+    assert!(ctx.end_frame(backend).is_ok());
+    String::from_utf8_lossy(backend.buffer()).into_owned()
+}
+
+#[test]
+fn kitty_image_is_sent_once() {
+    let mut backend = TestBackend::new(20, 10);
+    let mut ctx = context(&backend);
+    let image = Image::new(pixel_image())
+        .with_protocol(ImageProtocol::Kitty)
+        .with_preserve_aspect(false);
+    let area = Rect::new(2, 1, 4, 2);
+
+    let out = frame(&mut ctx, &mut backend, |c| {
+        image.render(area, c.buffer_mut())
+    });
+    let expected = format!(
+        "{}\x1b[2;3H{}",
+        kitty::transmit(image.image(), image.id()).concat(),
+        kitty::place(image.id(), 4, 2)
+    );
+    assert_eq!(out, expected);
+
+    let out = frame(&mut ctx, &mut backend, |c| {
+        image.render(area, c.buffer_mut())
+    });
+    assert_eq!(out, "");
+}
+
+#[test]
+fn kitty_image_moves_and_clears() {
+    let mut backend = TestBackend::new(20, 10);
+    let mut ctx = context(&backend);
+    let image = Image::new(pixel_image())
+        .with_protocol(ImageProtocol::Kitty)
+        .with_preserve_aspect(false);
+
+    frame(&mut ctx, &mut backend, |c| {
+        image.render(Rect::new(0, 0, 2, 1), c.buffer_mut());
+    });
+    // Moving only places the stored image again.
+    let out = frame(&mut ctx, &mut backend, |c| {
+        image.render(Rect::new(0, 3, 2, 1), c.buffer_mut());
+    });
+    assert_eq!(out, format!("\x1b[4;1H{}", kitty::place(image.id(), 2, 1)));
+
+    let out = frame(&mut ctx, &mut backend, |_| {});
+    assert_eq!(out, kitty::delete(image.id()));
+}
+
+#[test]
+fn kitty_image_scrolls_without_retransmitting() {
+    let mut backend = TestBackend::new(20, 10);
+    let mut ctx = context(&backend);
+    let image = Image::new(pixel_image())
+        .with_protocol(ImageProtocol::Kitty)
+        .with_preserve_aspect(false);
+
+    let out = frame(&mut ctx, &mut backend, |c| {
+        image.render(Rect::new(0, 4, 3, 2), c.buffer_mut());
+    });
+    assert!(out.contains("a=t,"));
+    for row in (0..4).rev() {
+        let out = frame(&mut ctx, &mut backend, |c| {
+            image.render(Rect::new(0, row, 3, 2), c.buffer_mut());
+        });
+        assert!(!out.contains("a=t,"), "row {row} retransmitted");
+        assert!(out.ends_with(&kitty::place(image.id(), 3, 2)));
+    }
+
+    // Hidden behind other content, the placement goes but the data stays.
+    let out = frame(&mut ctx, &mut backend, |c| {
+        image.render(Rect::new(0, 0, 3, 2), c.buffer_mut());
+        c.buffer_mut().set(1, 1, Cell::new("X", Style::default()));
+    });
+    assert!(out.contains(&kitty::delete_placement(image.id())));
+    let out = frame(&mut ctx, &mut backend, |c| {
+        image.render(Rect::new(0, 0, 3, 2), c.buffer_mut());
+    });
+    assert!(!out.contains("a=t,"));
+    assert!(out.contains(&kitty::place(image.id(), 3, 2)));
+}
+
+#[test]
+fn tmux_passthrough_wraps_each_chunk() {
+    let mut backend = TestBackend::new(20, 10).with_multiplexer(MultiplexerKind::Tmux);
+    let mut ctx = context(&backend);
+    let image = Image::new(pixel_image())
+        .with_protocol(ImageProtocol::Kitty)
+        .with_preserve_aspect(false);
+
+    let out = frame(&mut ctx, &mut backend, |c| {
+        image.render(Rect::new(0, 0, 1, 1), c.buffer_mut());
+    });
+    let wrap = |inner: &str| format!("\x1bPtmux;\x1b{}\x1b\\", inner.replace('\x1b', "\x1b\x1b"));
+    let transmit: String = kitty::transmit(image.image(), image.id())
+        .iter()
+        .map(|chunk| wrap(chunk))
+        .collect();
+    let place = wrap(&kitty::place(image.id(), 1, 1));
+    assert_eq!(out, format!("{transmit}\x1b[1;1H{place}"));
+}
+
+#[test]
+fn sixel_removal_repaints_cells() {
+    let mut backend = TestBackend::new(10, 4);
+    let mut ctx = context(&backend);
+    let image = Image::new(pixel_image())
+        .with_protocol(ImageProtocol::Sixel)
+        .with_preserve_aspect(false);
+
+    let out = frame(&mut ctx, &mut backend, |c| {
+        image.render(Rect::new(0, 0, 1, 1), c.buffer_mut());
+    });
+    let scaled = image.image().resized(10, 20);
+    assert_eq!(out, format!("\x1b[1;1H{}", sixel::encode(&scaled)));
+
+    // The image disappears; its cell is blank in both frames, so only the
+    // forced repaint clears the pixels.
+    let out = frame(&mut ctx, &mut backend, |_| {});
+    assert_eq!(out, "\x1b[1;1H ");
+}
+
+#[test]
+fn overlapping_content_hides_image() {
+    let mut backend = TestBackend::new(10, 4);
+    let mut ctx = context(&backend);
+    let image = Image::new(pixel_image())
+        .with_protocol(ImageProtocol::Kitty)
+        .with_preserve_aspect(false);
+
+    let out = frame(&mut ctx, &mut backend, |c| {
+        image.render(Rect::new(0, 0, 3, 2), c.buffer_mut());
+        c.buffer_mut().set(1, 1, Cell::new("X", Style::default()));
+    });
+    assert_eq!(out, "\x1b[2;2HX");
+}
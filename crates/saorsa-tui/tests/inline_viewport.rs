@@ -0,0 +1,148 @@
+//! Byte-level tests for inline (non-alternate-screen) rendering.
+
+use saorsa_tui::buffer::ScreenBuffer;
+use saorsa_tui::cell::Cell;
+use saorsa_tui::geometry::{Position, Size};
+use saorsa_tui::render_context::RenderContext;
+use saorsa_tui::style::Style;
+use saorsa_tui::terminal::TestBackend;
+
+fn inline(backend: &mut TestBackend, height: u16) -> RenderContext {
+    match RenderContext::new_inline(backend, height) {
+        Ok(ctx) => ctx,
+        Err(_) => unreachable!("test backend has a size"),
+    }
+}
+
+fn write_text(buf: &mut ScreenBuffer, x: u16, y: u16, text: &str) {
+    for (i, ch) in text.chars().enumerate() {
+        buf.set(x + i as u16, y, Cell::new(ch.to_string(), Style::default()));
+    }
+}
+
+/// Render one frame with `text` at the viewport origin and return the bytes written.
+fn frame(ctx: &mut RenderContext, backend: &mut TestBackend, text: &str) -> String {
+    backend.clear_buffer();
+    ctx.begin_frame();
+    write_text(ctx.buffer_mut(), 0, 0, text);
+    assert!(ctx.end_frame(backend).is_ok());
+    String::from_utf8_lossy(backend.buffer()).into_owned()
+}
+
+#[test]
+fn viewport_starts_at_cursor_line() {
+    let mut backend = TestBackend::new(20, 10).with_cursor_position(Some(Position::new(0, 2)));
+    let mut ctx = inline(&mut backend, 3);
+    assert!(backend.buffer().is_empty());
+    assert_eq!(ctx.inline_top(), Some(2));
+    assert_eq!(ctx.size(), Size::new(20, 3));
+
+    assert_eq!(frame(&mut ctx, &mut backend, "hi"), "\x1b[3;1Hhi");
+    // Only the diff is written, offset to the viewport's rows.
+    assert_eq!(frame(&mut ctx, &mut backend, "ho"), "\x1b[3;2Ho");
+}
+
+#[test]
+fn viewport_scrolls_screen_when_cursor_is_low() {
+    let mut backend = TestBackend::new(20, 10).with_cursor_position(Some(Position::new(0, 8)));
+    let ctx = inline(&mut backend, 4);
+    assert_eq!(backend.buffer(), b"\x1b[10;1H\n\n");
+    assert_eq!(ctx.inline_top(), Some(6));
+}
+
+#[test]
+fn unknown_cursor_reserves_at_bottom() {
+    let mut backend = TestBackend::new(20, 10).with_cursor_position(None);
+    let ctx = inline(&mut backend, 3);
+    assert_eq!(backend.buffer(), b"\x1b[10;1H\n\n");
+    assert_eq!(ctx.inline_top(), Some(7));
+}
+
+#[test]
+fn insert_before_moves_viewport_down() {
+    let mut backend = TestBackend::new(10, 10);
+    let mut ctx = inline(&mut backend, 2);
+    frame(&mut ctx, &mut backend, "live");
+
+    backend.clear_buffer();
+    let res = ctx.insert_before(&mut backend, 1, |buf| write_text(buf, 0, 0, "done"));
+    assert!(res.is_ok());
+    assert_eq!(
+        String::from_utf8_lossy(backend.buffer()),
+        "\x1b[1;1H\x1b[2K\x1b[1;1Hdone"
+    );
+    assert_eq!(ctx.inline_top(), Some(1));
+
+    // The viewport is cleared and fully redrawn at its new rows.
+    assert_eq!(
+        frame(&mut ctx, &mut backend, "live"),
+        "\x1b[2;1H\x1b[2K\x1b[3;1H\x1b[2K\x1b[2;1Hlive"
+    );
+}
+
+#[test]
+fn insert_before_scrolls_at_bottom() {
+    let mut backend = TestBackend::new(10, 10).with_cursor_position(Some(Position::new(0, 8)));
+    let mut ctx = inline(&mut backend, 2);
+    assert_eq!(ctx.inline_top(), Some(8));
+
+    backend.clear_buffer();
+    let res = ctx.insert_before(&mut backend, 2, |buf| {
+        write_text(buf, 0, 0, "a");
+        write_text(buf, 0, 1, "b");
+    });
+    assert!(res.is_ok());
+    assert_eq!(
+        String::from_utf8_lossy(backend.buffer()),
+        "\x1b[10;1H\n\n\x1b[7;1H\x1b[2K\x1b[8;1H\x1b[2K\x1b[7;1Ha\x1b[8;1Hb"
+    );
+    assert_eq!(ctx.inline_top(), Some(8));
+}
+
+#[test]
+fn leave_inline_keeps_or_clears_output() {
+    let mut backend = TestBackend::new(10, 10).with_cursor_position(Some(Position::new(0, 3)));
+    let mut ctx = inline(&mut backend, 2);
+    backend.clear_buffer();
+    assert!(ctx.leave_inline(&mut backend, false).is_ok());
+    assert_eq!(backend.buffer(), b"\x1b[0m\x1b[6;1H");
+
+    backend.clear_buffer();
+    assert!(ctx.leave_inline(&mut backend, true).is_ok());
+    assert_eq!(
+        backend.buffer(),
+        b"\x1b[0m\x1b[4;1H\x1b[2K\x1b[5;1H\x1b[2K\x1b[4;1H"
+    );
+}
+
+#[test]
+fn leave_inline_at_bottom_scrolls_once() {
+    let mut backend = TestBackend::new(10, 4).with_cursor_position(Some(Position::new(0, 3)));
+    let mut ctx = inline(&mut backend, 2);
+    backend.clear_buffer();
+    assert!(ctx.leave_inline(&mut backend, false).is_ok());
+    assert_eq!(backend.buffer(), b"\x1b[0m\x1b[4;1H\n");
+}
+
+#[test]
+fn full_screen_context_rejects_insert_before() {
+    let mut backend = TestBackend::new(10, 4);
+    let mut ctx = match RenderContext::new(&backend) {
+        Ok(ctx) => ctx,
+        Err(_) => unreachable!("test backend has a size"),
+    };
+    assert!(!ctx.is_inline());
+    assert!(ctx.insert_before(&mut backend, 1, |_| {}).is_err());
+    assert!(ctx.leave_inline(&mut backend, true).is_ok());
+    assert!(backend.buffer().is_empty());
+}
+
+#[test]
+fn resize_keeps_viewport_on_screen() {
+    let mut backend = TestBackend::new(10, 10).with_cursor_position(Some(Position::new(0, 7)));
+    let mut ctx = inline(&mut backend, 3);
+    assert_eq!(ctx.inline_top(), Some(7));
+    ctx.handle_resize(Size::new(8, 6));
+    assert_eq!(ctx.inline_top(), Some(3));
+    assert_eq!(ctx.size(), Size::new(8, 3));
+}
@@ -0,0 +1,148 @@
+#![allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+#![allow(missing_docs)]
+
+use std::time::Duration;
+
+use saorsa_tui::app::{Dom, Interactive, NodeRef, StyledLeaf};
+use saorsa_tui::color::NamedColor;
+use saorsa_tui::event::{KeyCode, Modifiers};
+use saorsa_tui::widget::{Container, EventResult, Label, TextArea};
+use saorsa_tui::{Color, Pilot, Style};
+
+const TCSS: &str = "
+#root { display: flex; flex-direction: column; }
+Label { height: 1; }
+#status { color: red; text-style: bold; }
+TextArea { height: 2; }
+";
+
+/// A title, a status line and two text areas stacked in a column.
+fn form() -> (Pilot, NodeRef) {
+    let mut dom = Dom::new();
+    let root = dom.create("Root", Box::new(StyledLeaf::new(Container::new())));
+    dom.set_root(root);
+    dom.set_css_id(root, "root");
+    let title = dom.create("Label", Box::new(StyledLeaf::new(Label::new("Sign up"))));
+    let status = dom.create("Label", Box::new(StyledLeaf::new(Label::new("ready"))));
+    dom.set_css_id(status, "status");
+    let name = dom.create("TextArea", Box::new(Interactive::new(TextArea::new())));
+    dom.set_css_id(name, "name");
+    dom.set_focusable(name, true);
+    let email = dom.create("TextArea", Box::new(Interactive::new(TextArea::new())));
+    dom.set_css_id(email, "email");
+    dom.set_focusable(email, true);
+    for child in [title, status, name, email] {
+        dom.append_child(root, child);
+    }
+    (Pilot::from_tcss(20, 6, dom, TCSS).unwrap(), status)
+}
+
+fn set_status(pilot: &mut Pilot, status: NodeRef, text: String) {
+    pilot
+        .app_mut()
+        .dom_mut()
+        .downcast_widget_mut::<Label>(status)
+        .unwrap()
+        .set_text(text);
+}
+
+#[test]
+fn typing_goes_to_focused_text_area() {
+    let (mut pilot, _) = form();
+    assert!(pilot.is_focused("#name").unwrap());
+    pilot.type_text("Ada").unwrap();
+    assert_eq!(pilot.line(2), "Ada");
+    assert_eq!(pilot.find_text("Ada").map(|p| (p.x, p.y)), Some((0, 2)));
+}
+
+#[test]
+fn tab_and_click_move_focus() {
+    let (mut pilot, _) = form();
+    pilot.press(KeyCode::Tab).unwrap();
+    assert!(pilot.is_focused("#email").unwrap());
+    pilot.type_text("a@b").unwrap();
+    assert_eq!(pilot.line(4), "a@b");
+
+    pilot.click("#name").unwrap();
+    assert!(pilot.is_focused("#name").unwrap());
+    assert_eq!(pilot.focused(), Some(pilot.node("#name").unwrap()));
+}
+
+#[test]
+fn click_reports_unknown_selector() {
+    let (mut pilot, _) = form();
+    assert!(pilot.click("#missing").is_err());
+}
+
+#[test]
+fn keys_with_modifiers_and_paste_reach_widget() {
+    let (mut pilot, _) = form();
+    pilot.paste("hello").unwrap();
+    pilot
+        .press_with(KeyCode::Left, Modifiers::CTRL | Modifiers::SHIFT)
+        .unwrap();
+    pilot.press_keys(&[KeyCode::Char('X')]).unwrap();
+    assert!(pilot.contains_text("X"));
+}
+
+#[test]
+fn resize_relayouts() {
+    let (mut pilot, _) = form();
+    pilot.resize(10, 4).unwrap();
+    assert_eq!(pilot.app().screen().width(), 10);
+    assert_eq!(pilot.screen_text().split('\n').count(), 4);
+    assert_eq!(pilot.line(0), "Sign up");
+}
+
+#[test]
+fn advance_fires_interval_timers() {
+    let (mut pilot, status) = form();
+    let mut ticks = 0;
+    pilot.app_mut().set_interval(
+        Duration::from_millis(100),
+        Box::new(move |app| {
+            ticks += 1;
+            app.dom_mut()
+                .downcast_widget_mut::<Label>(status)
+                .unwrap()
+                .set_text(format!("tick {ticks}"));
+            Ok(EventResult::Consumed)
+        }),
+    );
+    assert_eq!(pilot.advance(Duration::from_millis(50)).unwrap(), 0);
+    assert_eq!(pilot.line(1), "ready");
+    assert_eq!(pilot.advance(Duration::from_millis(260)).unwrap(), 3);
+    assert_eq!(pilot.line(1), "tick 3");
+}
+
+#[test]
+fn wait_for_idle_renders_direct_changes() {
+    let (mut pilot, status) = form();
+    set_status(&mut pilot, status, "saved".into());
+    pilot.app_mut().request_render();
+    pilot.wait_for_idle().unwrap();
+    assert_eq!(pilot.line(1), "saved");
+    assert!(!pilot.app().is_dirty());
+}
+
+#[test]
+fn style_at_reads_rendered_style() {
+    let (pilot, _) = form();
+    let style = pilot.style_at(0, 1).unwrap();
+    assert_eq!(style.fg, Some(Color::Named(NamedColor::Red)));
+    assert!(style.bold);
+    assert_eq!(pilot.style_at(0, 0), Some(&Style::default()));
+}
+
+#[test]
+fn form_text_snapshot() {
+    let (mut pilot, _) = form();
+    pilot.type_text("Ada\tada@example.com").unwrap();
+    insta::assert_snapshot!(pilot.screen_text());
+}
+
+#[test]
+fn form_styled_snapshot() {
+    let (pilot, _) = form();
+    insta::assert_snapshot!(pilot.styled_snapshot());
+}
@@ -8,7 +8,7 @@ use saorsa_tui::geometry::{Rect, Size};
 use saorsa_tui::segment::Segment;
 use saorsa_tui::style::Style;
 use saorsa_tui::widget::{
-    Column, DataTable, DiffView, RichLog, SelectList, Tree, TreeNode, Widget,
+    Column, DataTable, DiffMode, DiffView, RichLog, SelectList, Tree, TreeNode, Widget,
 };
 
 /// Render a widget to a text grid for snapshot testing.
@@ -176,10 +176,7 @@ fn snapshot_tree_nested() {
         .with_render_fn(|data: &String, _depth, _expanded, _is_leaf| vec![Segment::new(data)]);
 
     tree.expand_selected();
-    let down = Event::Key(KeyEvent {
-        code: KeyCode::Down,
-        modifiers: Modifiers::NONE,
-    });
+    let down = Event::Key(KeyEvent::new(KeyCode::Down, Modifiers::NONE));
     tree.handle_event(&down);
     tree.expand_selected();
 
@@ -215,3 +212,41 @@ fn snapshot_diffview_deletions() {
     let rendered = render_widget_to_text(&diff, 40, 5);
     insta::assert_snapshot!("diffview_deletions", rendered);
 }
+
+#[test]
+fn snapshot_diffview_patch_side_by_side() {
+    let patch = "diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs\n+++ b/src/main.rs\n\
+        @@ -3,3 +3,3 @@ fn main() {\n     let x = 1;\n-    println!(\"{x}\");\n+    println!(\"x = {x}\");\n }\n\
+        @@ -20,2 +20,3 @@\n fn helper() {}\n+fn added() {}\n \n";
+    let diff = match DiffView::from_patch(patch) {
+        Ok(diff) => diff
+            .with_mode(DiffMode::SideBySide)
+            .with_hunk_headers(true)
+            .with_line_numbers(true),
+        Err(e) => unreachable!("patch should parse: {e}"),
+    };
+    let rendered = render_widget_to_text(&diff, 60, 12);
+    insta::assert_snapshot!("diffview_patch_side_by_side", rendered);
+}
+
+#[test]
+fn snapshot_diffview_hunk_headers_and_line_numbers() {
+    let old = "line1\nline2\nline3\n";
+    let new = "line1\nmodified\nline3\n";
+    let diff = DiffView::new(old, new)
+        .with_hunk_headers(true)
+        .with_line_numbers(true);
+    let rendered = render_widget_to_text(&diff, 40, 5);
+    insta::assert_snapshot!("diffview_hunk_headers_and_line_numbers", rendered);
+}
+
+#[test]
+fn snapshot_diffview_folded_context() {
+    let old: String = (1..=12).map(|i| format!("line{i}\n")).collect();
+    let new = old.replace("line2\n", "changed\n");
+    let diff = DiffView::new(&old, &new)
+        .with_context_lines(1)
+        .with_context_collapsed(true);
+    let rendered = render_widget_to_text(&diff, 40, 6);
+    insta::assert_snapshot!("diffview_folded_context", rendered);
+}
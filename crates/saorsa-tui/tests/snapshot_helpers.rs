@@ -21,13 +21,13 @@ pub fn render_to_text(lines: &[Vec<saorsa_tui::Segment>], width: u16, height: u1
         }
 
         // Pad to width
-        let current_len = row_text.len();
+        let current_len = saorsa_tui::text::string_display_width(&row_text) as usize;
         if current_len < width {
             for _ in 0..(width - current_len) {
                 row_text.push(' ');
             }
         } else if current_len > width {
-            row_text.truncate(width);
+            row_text = saorsa_tui::text::truncate_to_display_width(&row_text, width).to_string();
         }
 
         result.push_str(&row_text);
@@ -137,3 +137,14 @@ fn snapshot_markdown_link() {
     let rendered = render_to_text(&lines, 40, 3);
     insta::assert_snapshot!("markdown_link", rendered);
 }
+
+#[test]
+fn snapshot_markdown_gfm() {
+    let mut md = MarkdownRenderer::new();
+    md.push_str(
+        "## Status\n\n| Task | Owner | Done |\n|:-----|:-----:|-----:|\n| Parser | ana | 90% |\n| Renderer | bo | 40% |\n\n- [x] ~~tables~~\n- [ ] footnotes[^1]\n\n> quoted\n> > nested\n\n[^1]: Later.",
+    );
+    let lines = md.render_to_lines(40);
+    let rendered = render_to_text(&lines, 40, 18);
+    insta::assert_snapshot!("markdown_gfm", rendered);
+}
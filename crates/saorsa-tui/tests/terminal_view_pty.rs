@@ -0,0 +1,118 @@
+//! Integration tests running processes in a `TerminalView`.
+#![cfg(all(feature = "pty", unix))]
+
+use std::time::{Duration, Instant};
+
+use saorsa_tui::TerminalView;
+use saorsa_tui::app::{Dom, Interactive};
+use saorsa_tui::buffer::ScreenBuffer;
+use saorsa_tui::event::{Event, KeyCode, KeyEvent};
+use saorsa_tui::geometry::{Rect, Size};
+use saorsa_tui::widget::terminal_view::CommandBuilder;
+use saorsa_tui::widget::{InteractiveWidget, Widget};
+
+fn sh(script: &str) -> CommandBuilder {
+    let mut command = CommandBuilder::new("sh");
+    command.args(["-c", script]);
+    command
+}
+
+fn spawn(view: &mut TerminalView, command: CommandBuilder) {
+    if let Err(e) = view.spawn(command) {
+        unreachable!("spawning should succeed: {e}");
+    }
+}
+
+/// Wait until the screen satisfies `done`, up to a few seconds.
+fn wait_for(view: &TerminalView, done: impl Fn(&str) -> bool) -> String {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let text = view.screen_text();
+        if done(&text) || Instant::now() > deadline {
+            return text;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn runs_a_command_and_reports_its_exit() {
+    let mut view = TerminalView::new(20, 4);
+    spawn(&mut view, sh("printf 'hello\\n\\033[1;32mgreen'; exit 3"));
+    let text = wait_for(&view, |t| t.contains("green"));
+    assert_eq!(text, "hello\ngreen\n\n");
+    assert!(view.take_output_ready());
+    assert!(view.cell(0, 1).is_some_and(|c| c.style.bold));
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while view.is_running() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert_eq!(view.exit_code(), Some(3));
+}
+
+#[test]
+fn forwards_input_and_follows_resizes() {
+    let mut view = TerminalView::new(20, 4);
+    spawn(&mut view, sh("read line; stty size; echo \"got $line\""));
+    for c in "abc".chars() {
+        view.handle_event(&Event::Key(KeyEvent::plain(KeyCode::Char(c))));
+    }
+    // Rendering into a smaller area resizes the pseudo-terminal before the
+    // command asks for its size.
+    let mut buf = ScreenBuffer::new(Size::new(30, 6));
+    view.render(Rect::new(0, 0, 30, 6), &mut buf);
+    view.handle_event(&Event::Key(KeyEvent::plain(KeyCode::Enter)));
+
+    let text = wait_for(&view, |t| t.contains("got abc"));
+    assert!(text.contains("6 30"), "screen was {text:?}");
+    assert!(text.contains("got abc"), "screen was {text:?}");
+}
+
+#[test]
+fn kill_stops_the_process() {
+    let mut view = TerminalView::new(20, 4);
+    spawn(&mut view, sh("sleep 30"));
+    assert!(view.is_running());
+    assert!(view.process_id().is_some());
+    view.kill();
+    assert!(!view.is_running());
+}
+
+#[test]
+fn app_repaints_when_output_arrives() {
+    let mut dom = Dom::new();
+    let root = dom.create(
+        "TerminalView",
+        Box::new(Interactive::new(TerminalView::new(20, 4))),
+    );
+    dom.set_root(root);
+    let mut pilot = match saorsa_tui::Pilot::from_tcss(20, 4, dom, "") {
+        Ok(pilot) => pilot,
+        Err(e) => unreachable!("pilot should start: {e}"),
+    };
+    let Some(view) = pilot
+        .app_mut()
+        .dom_mut()
+        .downcast_widget_mut::<TerminalView>(root)
+    else {
+        unreachable!("root is a TerminalView");
+    };
+    spawn(view, sh("sleep 0.2; echo ready; sleep 30"));
+    assert!(!pilot.contains_text("ready"));
+
+    // No input events: the app notices the output by itself.
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !pilot.contains_text("ready") && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+        if pilot.app().is_dirty() {
+            assert!(pilot.wait_for_idle().is_ok());
+        }
+    }
+    assert!(
+        pilot.contains_text("ready"),
+        "screen was {:?}",
+        pilot.screen_text()
+    );
+    assert!(!pilot.app().is_dirty());
+}
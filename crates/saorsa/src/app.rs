@@ -5,6 +5,20 @@ use crate::autocomplete::{Autocomplete, Suggestion};
 /// Maximum number of suggestions visible in the dropdown.
 const MAX_VISIBLE_SUGGESTIONS: usize = 8;
 
+/// Maximum number of input undo steps kept.
+const MAX_INPUT_UNDO: usize = 100;
+
+/// Undo history for keymap edits of the input line.
+#[derive(Debug, Default)]
+struct InputHistory {
+    /// Earlier `(input, cursor)` states, most recent last.
+    undo: Vec<(String, usize)>,
+    /// States undone, most recent last.
+    redo: Vec<(String, usize)>,
+    /// Nesting depth of the open change, if any.
+    depth: usize,
+}
+
 /// Active overlay mode for the application.
 ///
 /// Overlays capture input while visible. The main input field is inactive
@@ -18,6 +32,8 @@ pub enum OverlayMode {
     ModelSelector,
     /// Settings screen overlay (/settings --ui).
     Settings,
+    /// Command palette overlay (Ctrl+O).
+    CommandPalette,
 }
 
 /// Current status of the application.
@@ -99,6 +115,10 @@ pub struct AppState {
     autocomplete_suggestions: Vec<Suggestion>,
     /// Selected index in the autocomplete dropdown.
     autocomplete_index: usize,
+    /// Keymap for editing the input line.
+    pub keymap: saorsa_tui::Keymap,
+    /// Undo history for keymap edits of the input line.
+    input_history: InputHistory,
 }
 
 impl AppState {
@@ -123,6 +143,8 @@ impl AppState {
             pending_stream_text: String::new(),
             autocomplete_suggestions: Vec::new(),
             autocomplete_index: 0,
+            keymap: saorsa_tui::Keymap::default(),
+            input_history: InputHistory::default(),
         }
     }
 
@@ -249,9 +271,69 @@ impl AppState {
     pub fn take_input(&mut self) -> String {
         self.cursor = 0;
         self.dirty = true;
+        self.input_history = InputHistory::default();
         std::mem::take(&mut self.input)
     }
 
+    /// Start an input change that undoes as one step. Calls nest.
+    pub fn begin_input_change(&mut self) {
+        if self.input_history.depth == 0 {
+            self.push_input_undo();
+        }
+        self.input_history.depth += 1;
+    }
+
+    /// End a change started with [`begin_input_change`](Self::begin_input_change).
+    pub fn end_input_change(&mut self) {
+        let history = &mut self.input_history;
+        history.depth = history.depth.saturating_sub(1);
+        // A change that left the input as it was is not an undo step.
+        if history.depth == 0
+            && history
+                .undo
+                .last()
+                .is_some_and(|(text, _)| *text == self.input)
+        {
+            history.undo.pop();
+        }
+    }
+
+    /// Record the input before an edit made outside any open change.
+    pub fn record_input(&mut self) {
+        if self.input_history.depth == 0 {
+            self.push_input_undo();
+        }
+    }
+
+    fn push_input_undo(&mut self) {
+        let history = &mut self.input_history;
+        history.undo.push((self.input.clone(), self.cursor));
+        if history.undo.len() > MAX_INPUT_UNDO {
+            history.undo.remove(0);
+        }
+        history.redo.clear();
+    }
+
+    /// Restore the input as it was before the last recorded edit.
+    pub fn undo_input(&mut self) {
+        if let Some((text, cursor)) = self.input_history.undo.pop() {
+            let current = (std::mem::replace(&mut self.input, text), self.cursor);
+            self.input_history.redo.push(current);
+            self.cursor = cursor;
+            self.dirty = true;
+        }
+    }
+
+    /// Reapply the last undone input edit.
+    pub fn redo_input(&mut self) {
+        if let Some((text, cursor)) = self.input_history.redo.pop() {
+            let current = (std::mem::replace(&mut self.input, text), self.cursor);
+            self.input_history.undo.push(current);
+            self.cursor = cursor;
+            self.dirty = true;
+        }
+    }
+
     /// Cycle to the next model in the enabled list.
     ///
     /// Returns `None` if fewer than two models are available.
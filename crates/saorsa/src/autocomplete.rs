@@ -54,6 +54,11 @@ impl Autocomplete {
         self.file_paths = paths;
     }
 
+    /// The slash commands with their descriptions.
+    pub fn commands(&self) -> &[(&'static str, &'static str)] {
+        &self.commands
+    }
+
     /// Get suggestions for a prefix.
     pub fn suggest(&self, text: &str) -> Vec<Suggestion> {
         if let Some(stripped) = text.strip_prefix('@') {
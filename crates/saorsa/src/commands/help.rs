@@ -24,7 +24,9 @@ Available commands:
   /login             Configure API keys
   /logout            Remove API keys
 
-Aliases: /h, /?, /m, /think, /keys, /config, /bm, /tools"
+Aliases: /h, /?, /m, /think, /keys, /config, /bm, /tools
+
+Press Ctrl+O to search commands, models and settings."
         .to_string())
 }
 
@@ -10,6 +10,7 @@ Keyboard shortcuts:
   Ctrl+P             Next model
   Shift+Ctrl+P       Previous model
   Ctrl+L             Open model selector
+  Ctrl+O             Open command palette
   PageUp             Scroll up
   PageDown           Scroll down
   Escape             Clear input / close overlay
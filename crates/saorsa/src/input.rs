@@ -1,6 +1,11 @@
 //! Input event handling for the chat application.
 
-use saorsa_tui::event::{Event, KeyCode, Modifiers, MouseEventKind};
+use std::ops::Range;
+
+use saorsa_tui::event::{Event, KeyCode, KeyEvent, Modifiers, MouseEventKind};
+use saorsa_tui::text_buffer::TextBuffer;
+use saorsa_tui::widget::EventResult;
+use saorsa_tui::{KeymapKind, TextEditor};
 
 use crate::app::AppState;
 
@@ -11,7 +16,7 @@ const MOUSE_SCROLL_LINES: usize = 3;
 const PAGE_SCROLL_LINES: usize = 10;
 
 /// Result of handling an input event.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum InputAction {
     /// No action needed.
     None,
@@ -23,7 +28,7 @@ pub enum InputAction {
     Redraw,
     /// Cycle to the next model (Ctrl+P).
     CycleModel,
-    /// Cycle to the previous model (Shift+Ctrl+P).
+    /// Cycle to the previous model (Ctrl+Shift+P).
     CycleModelBackward,
     /// Scroll message history up by the given number of lines.
     ScrollUp(usize),
@@ -31,15 +36,81 @@ pub enum InputAction {
     ScrollDown(usize),
     /// Open the model selector overlay (Ctrl+L).
     OpenModelSelector,
+    /// Open the command palette overlay (Ctrl+O).
+    OpenCommandPalette,
+    /// Switch to the named model (chosen in the command palette).
+    SwitchModel(String),
     /// Tab-complete the current input.
     TabComplete,
     /// Accept the currently selected autocomplete suggestion.
     AutocompleteAccept,
 }
 
+/// A keyboard shortcut run by [`handle_event`].
+#[derive(Clone, Debug)]
+pub struct Shortcut {
+    /// Action name, under which the UI runtime registers the shortcut.
+    pub name: &'static str,
+    /// What the shortcut does.
+    pub description: &'static str,
+    /// The key chord.
+    pub key: KeyEvent,
+    /// The action it results in.
+    pub action: InputAction,
+}
+
+/// The keyboard shortcuts [`handle_event`] runs. Quitting works at any
+/// time; the others only while idle.
+pub fn shortcuts() -> Vec<Shortcut> {
+    let ctrl = |c| KeyEvent::new(KeyCode::Char(c), Modifiers::CTRL);
+    let shortcut = |name, description, key, action| Shortcut {
+        name,
+        description,
+        key,
+        action,
+    };
+    vec![
+        shortcut(
+            "next-model",
+            "Switch to the next model",
+            ctrl('p'),
+            InputAction::CycleModel,
+        ),
+        shortcut(
+            "previous-model",
+            "Switch to the previous model",
+            KeyEvent::new(KeyCode::Char('p'), Modifiers::CTRL | Modifiers::SHIFT),
+            InputAction::CycleModelBackward,
+        ),
+        shortcut(
+            "model-selector",
+            "Open the model selector",
+            ctrl('l'),
+            InputAction::OpenModelSelector,
+        ),
+        shortcut(
+            "command-palette",
+            "Open the command palette",
+            ctrl('o'),
+            InputAction::OpenCommandPalette,
+        ),
+        shortcut("quit", "Quit", ctrl('c'), InputAction::Quit),
+    ]
+}
+
+/// The action of the shortcut bound to `code` with exactly `modifiers`.
+fn shortcut_action(code: &KeyCode, modifiers: Modifiers) -> Option<InputAction> {
+    shortcuts()
+        .into_iter()
+        .find(|s| s.key.code == *code && s.key.modifiers == modifiers)
+        .map(|s| s.action)
+}
+
 /// Handle an input event and return the resulting action.
 pub fn handle_event(state: &mut AppState, event: &Event) -> InputAction {
     match event {
+        // Key releases (Kitty keyboard protocol) must not repeat actions.
+        Event::Key(key) if key.is_release() => InputAction::None,
         Event::Key(key) => handle_key(state, key.code.clone(), key.modifiers),
         Event::Mouse(mouse) => handle_mouse(mouse.kind),
         Event::Resize(_, _) => InputAction::Redraw,
@@ -58,8 +129,10 @@ fn handle_mouse(kind: MouseEventKind) -> InputAction {
 
 /// Handle a key event.
 fn handle_key(state: &mut AppState, code: KeyCode, modifiers: Modifiers) -> InputAction {
+    let shortcut = shortcut_action(&code, modifiers);
+
     // Ctrl-C always quits.
-    if code == KeyCode::Char('c') && modifiers.contains(Modifiers::CTRL) {
+    if shortcut == Some(InputAction::Quit) {
         state.should_quit = true;
         return InputAction::Quit;
     }
@@ -107,22 +180,31 @@ fn handle_key(state: &mut AppState, code: KeyCode, modifiers: Modifiers) -> Inpu
         }
     }
 
-    // Ctrl+P: cycle model forward.
-    if code == KeyCode::Char('p') && modifiers.contains(Modifiers::CTRL) {
-        if modifiers.contains(Modifiers::SHIFT) {
-            return InputAction::CycleModelBackward;
-        }
-        return InputAction::CycleModel;
+    // Model switching, the model selector and the command palette.
+    if let Some(action) = shortcut {
+        return action;
     }
 
-    // Ctrl+L: open model selector overlay.
-    if code == KeyCode::Char('l') && modifiers.contains(Modifiers::CTRL) {
-        return InputAction::OpenModelSelector;
+    // Vim and Emacs keymaps edit the input line; Enter and Tab keep their
+    // meaning, and Escape never clears the input.
+    if state.keymap.kind() != KeymapKind::Standard && !matches!(code, KeyCode::Enter | KeyCode::Tab)
+    {
+        let key = KeyEvent::new(code.clone(), modifiers);
+        let mut keymap = std::mem::take(&mut state.keymap);
+        let result = keymap.handle_key(&mut Prompt::new(state), &key);
+        state.keymap = keymap;
+        if result == EventResult::Consumed || code == KeyCode::Escape {
+            state.mark_dirty();
+            return InputAction::Redraw;
+        }
     }
 
     match code {
         KeyCode::Tab if modifiers == Modifiers::NONE => InputAction::TabComplete,
         KeyCode::Enter => {
+            let mut keymap = std::mem::take(&mut state.keymap);
+            keymap.finish(&mut Prompt::new(state));
+            state.keymap = keymap;
             let text = state.take_input();
             if text.is_empty() {
                 InputAction::None
@@ -165,6 +247,110 @@ fn handle_key(state: &mut AppState, code: KeyCode, modifiers: Modifiers) -> Inpu
     }
 }
 
+/// The input line as a [`TextEditor`], for modal keymaps.
+struct Prompt<'a> {
+    state: &'a mut AppState,
+    /// The input as a buffer, rebuilt after each edit.
+    buffer: TextBuffer,
+}
+
+impl<'a> Prompt<'a> {
+    fn new(state: &'a mut AppState) -> Self {
+        let buffer = TextBuffer::from_text(&state.input);
+        Self { state, buffer }
+    }
+
+    /// The byte offset of a character index into the input.
+    fn byte_of(&self, idx: usize) -> usize {
+        self.state
+            .input
+            .char_indices()
+            .nth(idx)
+            .map_or(self.state.input.len(), |(i, _)| i)
+    }
+
+    fn sync(&mut self) {
+        self.buffer = TextBuffer::from_text(&self.state.input);
+        self.state.mark_dirty();
+    }
+}
+
+impl TextEditor for Prompt<'_> {
+    fn buffer(&self) -> &TextBuffer {
+        &self.buffer
+    }
+
+    fn cursor(&self) -> usize {
+        let cursor = self.state.cursor.min(self.state.input.len());
+        self.state.input[..cursor].chars().count()
+    }
+
+    fn set_cursor(&mut self, idx: usize) {
+        self.state.cursor = self.byte_of(idx);
+        self.state.mark_dirty();
+    }
+
+    fn set_selection(&mut self, _range: Option<Range<usize>>) {
+        // The input line does not show a selection.
+    }
+
+    fn replace(&mut self, range: Range<usize>, text: &str) {
+        let (start, end) = (self.byte_of(range.start), self.byte_of(range.end));
+        self.state.record_input();
+        self.state.input.replace_range(start..end, text);
+        self.state.cursor = start + text.len();
+        self.sync();
+    }
+
+    fn begin_change(&mut self) {
+        self.state.begin_input_change();
+    }
+
+    fn end_change(&mut self) {
+        self.state.end_input_change();
+    }
+
+    fn undo(&mut self) {
+        self.state.undo_input();
+        self.sync();
+    }
+
+    fn redo(&mut self) {
+        self.state.redo_input();
+        self.sync();
+    }
+
+    fn insert_key(&mut self, key: &KeyEvent) -> EventResult {
+        let state = &mut *self.state;
+        match key.code {
+            KeyCode::Char(c)
+                if !key.modifiers.contains(Modifiers::CTRL)
+                    && !key.modifiers.contains(Modifiers::ALT) =>
+            {
+                state.record_input();
+                state.insert_char(c);
+            }
+            KeyCode::Backspace if state.cursor > 0 => {
+                state.record_input();
+                state.delete_char_before();
+            }
+            KeyCode::Delete if state.cursor < state.input.len() => {
+                state.record_input();
+                state.cursor_right();
+                state.delete_char_before();
+            }
+            KeyCode::Backspace | KeyCode::Delete => {}
+            KeyCode::Left => state.cursor_left(),
+            KeyCode::Right => state.cursor_right(),
+            KeyCode::Home => state.cursor = 0,
+            KeyCode::End => state.cursor = state.input.len(),
+            _ => return EventResult::Ignored,
+        }
+        self.sync();
+        EventResult::Consumed
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,17 +358,11 @@ mod tests {
     use saorsa_tui::event::KeyEvent;
 
     fn key_event(code: KeyCode) -> Event {
-        Event::Key(KeyEvent {
-            code,
-            modifiers: Modifiers::NONE,
-        })
+        Event::Key(KeyEvent::new(code, Modifiers::NONE))
     }
 
     fn ctrl_key(c: char) -> Event {
-        Event::Key(KeyEvent {
-            code: KeyCode::Char(c),
-            modifiers: Modifiers::CTRL,
-        })
+        Event::Key(KeyEvent::new(KeyCode::Char(c), Modifiers::CTRL))
     }
 
     #[test]
@@ -289,10 +469,10 @@ mod tests {
     }
 
     fn shift_ctrl_key(c: char) -> Event {
-        Event::Key(KeyEvent {
-            code: KeyCode::Char(c),
-            modifiers: Modifiers::CTRL | Modifiers::SHIFT,
-        })
+        Event::Key(KeyEvent::new(
+            KeyCode::Char(c),
+            Modifiers::CTRL | Modifiers::SHIFT,
+        ))
     }
 
     #[test]
@@ -389,6 +569,14 @@ mod tests {
         assert_eq!(action, InputAction::OpenModelSelector);
     }
 
+    #[test]
+    fn ctrl_o_opens_command_palette() {
+        let mut state = AppState::new("test");
+        let action = handle_event(&mut state, &ctrl_key('o'));
+        assert_eq!(action, InputAction::OpenCommandPalette);
+        assert!(state.input.is_empty());
+    }
+
     #[test]
     fn ctrl_l_blocked_while_thinking() {
         let mut state = AppState::new("test");
@@ -482,4 +670,65 @@ mod tests {
         assert_eq!(action, InputAction::Redraw);
         assert_eq!(state.input, "/hel");
     }
+
+    #[test]
+    fn key_release_is_ignored() {
+        let mut state = AppState::new("test");
+        let release =
+            KeyEvent::plain(KeyCode::Char('h')).with_kind(saorsa_tui::event::KeyEventKind::Release);
+        let action = handle_event(&mut state, &Event::Key(release));
+        assert_eq!(action, InputAction::None);
+        assert!(state.input.is_empty());
+    }
+
+    fn type_keys(state: &mut AppState, keys: &str) {
+        for ch in keys.chars() {
+            let code = match ch {
+                '\x1b' => KeyCode::Escape,
+                c => KeyCode::Char(c),
+            };
+            handle_event(state, &key_event(code));
+        }
+    }
+
+    #[test]
+    fn vim_keymap_edits_the_input() {
+        let mut state = AppState::new("test");
+        state.keymap = saorsa_tui::Keymap::new(KeymapKind::Vim);
+        type_keys(&mut state, "ihello world\x1b");
+        assert_eq!(state.input, "hello world");
+        assert_eq!(state.keymap.status(), "NORMAL");
+
+        // Escape in normal mode keeps the input.
+        type_keys(&mut state, "\x1b0dw");
+        assert_eq!(state.input, "world");
+        type_keys(&mut state, "u");
+        assert_eq!(state.input, "hello world");
+        type_keys(&mut state, "u");
+        assert_eq!(state.input, "");
+
+        type_keys(&mut state, "ihi");
+        let action = handle_event(&mut state, &key_event(KeyCode::Enter));
+        assert_eq!(action, InputAction::Submit("hi".into()));
+        assert_eq!(state.keymap.status(), "NORMAL");
+    }
+
+    #[test]
+    fn emacs_keymap_kills_and_yanks() {
+        let mut state = AppState::new("test");
+        state.keymap = saorsa_tui::Keymap::new(KeymapKind::Emacs);
+        type_keys(&mut state, "héllo world");
+        handle_event(&mut state, &ctrl_key('a'));
+        handle_event(&mut state, &ctrl_key('k'));
+        assert_eq!(state.input, "");
+        handle_event(&mut state, &ctrl_key('y'));
+        assert_eq!(state.input, "héllo world");
+        assert_eq!(state.cursor, state.input.len());
+        let action = handle_event(
+            &mut state,
+            &Event::Key(KeyEvent::new(KeyCode::Char('b'), Modifiers::ALT)),
+        );
+        assert_eq!(action, InputAction::Redraw);
+        assert_eq!(state.cursor, "héllo ".len());
+    }
 }
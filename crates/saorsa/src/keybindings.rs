@@ -23,6 +23,7 @@ impl KeybindingMap {
         bindings.insert("cancel".to_string(), "Escape".to_string());
         bindings.insert("new_chat".to_string(), "Ctrl+N".to_string());
         bindings.insert("model_selector".to_string(), "Ctrl+L".to_string());
+        bindings.insert("command_palette".to_string(), "Ctrl+O".to_string());
         bindings.insert("settings".to_string(), "Ctrl+,".to_string());
         bindings.insert("queue".to_string(), "Ctrl+Q".to_string());
         bindings.insert("save".to_string(), "Ctrl+S".to_string());
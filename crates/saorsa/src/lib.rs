@@ -11,6 +11,7 @@ pub mod commands;
 pub mod input;
 pub mod keybindings;
 pub mod operating_mode;
+pub mod palette;
 pub mod render_throttle;
 pub mod tui_runtime;
 pub mod ui;
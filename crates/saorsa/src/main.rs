@@ -8,8 +8,8 @@ use crossterm::event::EventStream;
 use futures::StreamExt;
 
 use saorsa_agent::{
-    AgentConfig, AgentEvent, AgentLoop, AuthConfig, EventReceiver, Message, SessionStorage,
-    Settings, TurnEndReason, default_tools, ensure_config_dir, event_channel,
+    AgentConfig, AgentEvent, AgentLoop, AuthConfig, EditingMode, EventReceiver, Message,
+    SessionStorage, Settings, TurnEndReason, default_tools, ensure_config_dir, event_channel,
     find_last_active_session, find_session_by_prefix, restore_session,
 };
 
@@ -17,13 +17,14 @@ use saorsa_agent::{
 use saorsa_agent::ToolRegistry;
 use saorsa_ai::{ProviderConfig, ProviderKind, ProviderRegistry, determine_provider};
 use saorsa_tui::Terminal;
-use saorsa_tui::event::{KeyCode, Modifiers};
+use saorsa_tui::event::{KeyCode, Modifiers, MouseEventKind};
 use saorsa_tui::terminal::CrosstermBackend;
 
 use saorsa::app::{AppState, AppStatus, OverlayMode};
 use saorsa::cli::Cli;
 use saorsa::commands::{self, CommandResult};
 use saorsa::input::{InputAction, handle_event};
+use saorsa::palette;
 use saorsa::render_throttle::RenderThrottle;
 use saorsa::tui_runtime::SaorsaUi;
 
@@ -462,6 +463,11 @@ async fn run_interactive(
 
     // Populate enabled_models from settings.
     state.enabled_models = settings.enabled_models.clone();
+    state.keymap = saorsa_tui::Keymap::new(match settings.editing_mode {
+        EditingMode::Standard => saorsa_tui::KeymapKind::Standard,
+        EditingMode::Vim => saorsa_tui::KeymapKind::Vim,
+        EditingMode::Emacs => saorsa_tui::KeymapKind::Emacs,
+    });
 
     // Set model_index to the position of the current model in the list, or 0.
     state.model_index = state
@@ -645,10 +651,44 @@ async fn run_interactive(
                     tracing::warn!(error = %e, "UI runtime event handling failed");
                 }
 
+                // Mouse drag selection copies transcript text (e.g. code
+                // blocks) to the system clipboard; typing clears it.
+                match &event {
+                    saorsa_tui::event::Event::Mouse(m)
+                        if matches!(
+                            m.kind,
+                            MouseEventKind::Press | MouseEventKind::Drag | MouseEventKind::Release
+                        ) =>
+                    {
+                        if let Err(e) = ui.handle_event(&event) {
+                            tracing::warn!(error = %e, "UI runtime event handling failed");
+                        }
+                    }
+                    saorsa_tui::event::Event::Key(k) if !k.is_release() => ui.clear_selection(),
+                    _ => {}
+                }
+
                 if let saorsa_tui::event::Event::Resize(w, h) = event {
                     ui.handle_resize(w, h);
                 }
-                let action = handle_event(&mut state, &event);
+                // The open command palette takes input; Ctrl+C still quits.
+                let quit = matches!(
+                    &event,
+                    saorsa_tui::event::Event::Key(k)
+                        if k.code == KeyCode::Char('c') && k.modifiers.contains(Modifiers::CTRL)
+                );
+                let action = if state.overlay_mode == OverlayMode::CommandPalette && !quit {
+                    let executed = ui.handle_palette_event(&event).unwrap_or_else(|e| {
+                        tracing::warn!(error = %e, "Command palette action failed");
+                        None
+                    });
+                    if !ui.is_palette_open() {
+                        state.overlay_mode = OverlayMode::None;
+                    }
+                    executed.unwrap_or(InputAction::Redraw)
+                } else {
+                    handle_event(&mut state, &event)
+                };
 
                 match action {
                     InputAction::Quit => break,
@@ -722,41 +762,7 @@ async fn run_interactive(
                     InputAction::CycleModel => {
                         if let Some(new_model) = state.cycle_model_forward() {
                             let new_model = new_model.to_string();
-                            match &mut provider {
-                                InteractiveProvider::Http { provider_kind, api_key } => {
-                                    let new_kind = determine_provider(&new_model)
-                                        .unwrap_or(ProviderKind::OpenAiCompatible);
-                                    // Re-resolve API key for the new provider.
-                                    // Load auth config fresh in case it changed.
-                                    if let Ok(config_dir) =
-                                        ensure_config_dir().map_err(|e| anyhow::anyhow!("{e}"))
-                                    {
-                                        let auth_config = saorsa_agent::config::auth::load(
-                                            &config_dir.join("auth.json"),
-                                        )
-                                        .ok()
-                                        .unwrap_or_default();
-                                        if let Ok(key) = resolve_api_key(None, &auth_config, new_kind)
-                                        {
-                                            *api_key = key;
-                                        }
-                                    }
-                                    *provider_kind = new_kind;
-                                    model = new_model.clone();
-                                    state.model = new_model.clone();
-                                    state.add_system_message(format!(
-                                        "Switched to {} ({})",
-                                        new_model,
-                                        new_kind.display_name(),
-                                    ));
-                                }
-                                #[cfg(feature = "mistralrs")]
-                                InteractiveProvider::Mistralrs { .. } => {
-                                    state.add_system_message(
-                                        "Model switching is currently disabled in mistralrs mode.",
-                                    );
-                                }
-                            }
+                            switch_model(&mut provider, &mut model, &mut state, new_model);
                         } else {
                             state.add_system_message(
                                 "No other models configured. Add models to ~/.saorsa/settings.json"
@@ -773,39 +779,7 @@ async fn run_interactive(
                     InputAction::CycleModelBackward => {
                         if let Some(new_model) = state.cycle_model_backward() {
                             let new_model = new_model.to_string();
-                            match &mut provider {
-                                InteractiveProvider::Http { provider_kind, api_key } => {
-                                    let new_kind = determine_provider(&new_model)
-                                        .unwrap_or(ProviderKind::OpenAiCompatible);
-                                    if let Ok(config_dir) =
-                                        ensure_config_dir().map_err(|e| anyhow::anyhow!("{e}"))
-                                    {
-                                        let auth_config = saorsa_agent::config::auth::load(
-                                            &config_dir.join("auth.json"),
-                                        )
-                                        .ok()
-                                        .unwrap_or_default();
-                                        if let Ok(key) = resolve_api_key(None, &auth_config, new_kind)
-                                        {
-                                            *api_key = key;
-                                        }
-                                    }
-                                    *provider_kind = new_kind;
-                                    model = new_model.clone();
-                                    state.model = new_model.clone();
-                                    state.add_system_message(format!(
-                                        "Switched to {} ({})",
-                                        new_model,
-                                        new_kind.display_name(),
-                                    ));
-                                }
-                                #[cfg(feature = "mistralrs")]
-                                InteractiveProvider::Mistralrs { .. } => {
-                                    state.add_system_message(
-                                        "Model switching is currently disabled in mistralrs mode.",
-                                    );
-                                }
-                            }
+                            switch_model(&mut provider, &mut model, &mut state, new_model);
                         } else {
                             state.add_system_message(
                                 "No other models configured. Add models to ~/.saorsa/settings.json"
@@ -819,6 +793,21 @@ async fn run_interactive(
                             throttle.mark_rendered();
                         }
                     }
+                    InputAction::SwitchModel(new_model) => {
+                        if let Some(index) =
+                            state.enabled_models.iter().position(|m| *m == new_model)
+                        {
+                            state.model_index = index;
+                        }
+                        switch_model(&mut provider, &mut model, &mut state, new_model);
+                        let _ = ui.sync_from_state(&state);
+                        if throttle.should_render()
+                            && let Ok(rendered) = ui.render_if_needed(&mut backend)
+                            && rendered
+                        {
+                            throttle.mark_rendered();
+                        }
+                    }
                     InputAction::TabComplete => {
                         // Dismiss any visible autocomplete dropdown first.
                         state.dismiss_autocomplete();
@@ -858,6 +847,15 @@ async fn run_interactive(
                         let _ = ui.render_frame(&mut backend);
                         throttle.mark_rendered();
                     }
+                    InputAction::OpenCommandPalette => {
+                        state.dismiss_autocomplete();
+                        state.overlay_mode = OverlayMode::CommandPalette;
+                        ui.open_palette(palette::entries(&state, &autocomplete));
+                        state.mark_dirty();
+                        let _ = ui.sync_from_state(&state);
+                        let _ = ui.render_frame(&mut backend);
+                        throttle.mark_rendered();
+                    }
                     InputAction::ScrollUp(lines) => {
                         state.scroll_up(lines);
                         ui.scroll_messages_up(lines);
@@ -900,6 +898,45 @@ async fn run_interactive(
     Ok(())
 }
 
+/// Switch the provider and model used for new interactions to `new_model`.
+fn switch_model(
+    provider: &mut InteractiveProvider,
+    model: &mut String,
+    state: &mut AppState,
+    new_model: String,
+) {
+    match provider {
+        InteractiveProvider::Http {
+            provider_kind,
+            api_key,
+        } => {
+            let new_kind = determine_provider(&new_model).unwrap_or(ProviderKind::OpenAiCompatible);
+            // Re-resolve API key for the new provider.
+            // Load auth config fresh in case it changed.
+            if let Ok(config_dir) = ensure_config_dir().map_err(|e| anyhow::anyhow!("{e}")) {
+                let auth_config = saorsa_agent::config::auth::load(&config_dir.join("auth.json"))
+                    .ok()
+                    .unwrap_or_default();
+                if let Ok(key) = resolve_api_key(None, &auth_config, new_kind) {
+                    *api_key = key;
+                }
+            }
+            *provider_kind = new_kind;
+            *model = new_model.clone();
+            state.model = new_model.clone();
+            state.add_system_message(format!(
+                "Switched to {} ({})",
+                new_model,
+                new_kind.display_name(),
+            ));
+        }
+        #[cfg(feature = "mistralrs")]
+        InteractiveProvider::Mistralrs { .. } => {
+            state.add_system_message("Model switching is currently disabled in mistralrs mode.");
+        }
+    }
+}
+
 /// Start an agent interaction and return the event receiver and join handle.
 ///
 /// The agent runs in a background tokio task. Events arrive via the returned
@@ -0,0 +1,140 @@
+//! Command palette entries for slash commands, models and settings.
+//!
+//! [`entries`] lists what the palette (Ctrl+O) offers besides the
+//! keyboard shortcuts, which the UI runtime lists from its registered
+//! actions; [`action_for`] turns the id of the executed entry into the
+//! [`InputAction`] the event loop runs, so choosing an entry behaves like
+//! typing the command.
+
+use saorsa_agent::ThinkingLevel;
+use saorsa_tui::widget::PaletteEntry;
+
+use crate::app::AppState;
+use crate::autocomplete::Autocomplete;
+use crate::input::InputAction;
+
+/// Id prefix of entries that submit a slash command.
+const COMMAND: &str = "command:";
+/// Id prefix of entries that change a setting through `/config`.
+const SETTING: &str = "setting:";
+/// Id prefix of entries that switch model.
+const MODEL: &str = "model:";
+/// The palette entries for the current state: slash commands, enabled
+/// models and settings.
+pub fn entries(state: &AppState, autocomplete: &Autocomplete) -> Vec<PaletteEntry> {
+    let mut entries: Vec<PaletteEntry> = autocomplete
+        .commands()
+        .iter()
+        .map(|(name, description)| {
+            PaletteEntry::new(format!("{COMMAND}{name}"), *name).with_description(*description)
+        })
+        .collect();
+
+    entries.extend(state.enabled_models.iter().map(|name| {
+        let description = if *name == state.model {
+            "Current model"
+        } else {
+            "Switch to this model"
+        };
+        PaletteEntry::new(format!("{MODEL}{name}"), format!("Model: {name}"))
+            .with_description(description)
+    }));
+
+    let levels = [
+        ThinkingLevel::Off,
+        ThinkingLevel::Low,
+        ThinkingLevel::Medium,
+        ThinkingLevel::High,
+    ];
+    entries.extend(levels.into_iter().map(|level| {
+        let description = if level == state.thinking_level {
+            "Current extended-thinking level"
+        } else {
+            "Set the extended-thinking level"
+        };
+        PaletteEntry::new(
+            format!("{SETTING}/config thinking {level}"),
+            format!("Thinking: {level}"),
+        )
+        .with_description(description)
+    }));
+    let compact = if state.compact_mode { "on" } else { "off" };
+    entries.push(
+        PaletteEntry::new(format!("{SETTING}/config compact"), "Toggle compact mode")
+            .with_description(format!("Compact display is {compact}")),
+    );
+    entries.push(
+        PaletteEntry::new(format!("{SETTING}/config reset"), "Reset settings")
+            .with_description("Restore the default thinking level and display mode"),
+    );
+    entries
+}
+
+/// The action to run for the palette entry with `id`.
+pub fn action_for(id: &str) -> InputAction {
+    if let Some(command) = id
+        .strip_prefix(COMMAND)
+        .or_else(|| id.strip_prefix(SETTING))
+    {
+        return InputAction::Submit(command.to_string());
+    }
+    match id.strip_prefix(MODEL) {
+        Some(model) => InputAction::SwitchModel(model.to_string()),
+        None => InputAction::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> AppState {
+        let mut state = AppState::new("gpt-4o");
+        state.enabled_models = vec!["gpt-4o".into(), "claude-sonnet-4".into()];
+        state
+    }
+
+    #[test]
+    fn lists_commands_models_and_settings() {
+        let entries = entries(&state(), &Autocomplete::new());
+        let find = |id: &str| entries.iter().find(|e| e.id == id);
+
+        let help = find("command:/help");
+        assert_eq!(
+            help.and_then(|e| e.description.as_deref()),
+            Some("Show available commands")
+        );
+        let current = find("model:gpt-4o");
+        assert_eq!(
+            current.and_then(|e| e.description.as_deref()),
+            Some("Current model")
+        );
+        assert!(find("model:claude-sonnet-4").is_some());
+        assert!(find("setting:/config thinking high").is_some());
+        assert!(find("setting:/config compact").is_some());
+    }
+
+    #[test]
+    fn every_entry_has_an_action() {
+        for entry in entries(&state(), &Autocomplete::new()) {
+            assert_ne!(action_for(&entry.id), InputAction::None, "{}", entry.id);
+        }
+    }
+
+    #[test]
+    fn entries_map_to_input_actions() {
+        assert_eq!(
+            action_for("command:/clear"),
+            InputAction::Submit("/clear".into())
+        );
+        assert_eq!(
+            action_for("setting:/config thinking low"),
+            InputAction::Submit("/config thinking low".into())
+        );
+        assert_eq!(
+            action_for("model:claude-sonnet-4"),
+            InputAction::SwitchModel("claude-sonnet-4".into())
+        );
+        assert_eq!(action_for("unknown"), InputAction::None);
+    }
+}
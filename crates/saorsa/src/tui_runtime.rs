@@ -3,6 +3,7 @@
 use anyhow::Context;
 
 use saorsa_tui::Color;
+use saorsa_tui::ansi::parse_ansi;
 use saorsa_tui::app::{App, Dom, Leaf, StyledInteractive, StyledLeaf};
 use saorsa_tui::color::NamedColor;
 use saorsa_tui::event::{Event, KeyCode, KeyEvent, Modifiers};
@@ -10,15 +11,23 @@ use saorsa_tui::segment::Segment;
 use saorsa_tui::style::Style;
 use saorsa_tui::terminal::Terminal;
 use saorsa_tui::widget::EventResult;
-use saorsa_tui::widget::{BorderStyle, Container, Label, RichLog};
+use saorsa_tui::widget::{
+    BorderStyle, CommandPalette, Container, InteractiveWidget, Label, PaletteEntry, RichLog,
+};
 
 use crate::app::{AppState, AppStatus, ChatRole};
+use crate::input::{self, InputAction};
+use crate::palette;
+
+/// Id prefix of palette entries for actions registered with the runtime.
+const ACTION: &str = "action:";
 
 /// Retained UI wrapper for the `saorsa` application.
 pub struct SaorsaUi {
     app: App,
     header: saorsa_tui::app::NodeRef,
     messages: saorsa_tui::app::NodeRef,
+    palette: saorsa_tui::app::NodeRef,
     input_box: saorsa_tui::app::NodeRef,
     input_text: saorsa_tui::app::NodeRef,
     last_sig: UiSignature,
@@ -41,6 +50,7 @@ impl SaorsaUi {
             root: saorsa_tui::app::NodeRef,
             header: saorsa_tui::app::NodeRef,
             messages: saorsa_tui::app::NodeRef,
+            palette: saorsa_tui::app::NodeRef,
             input_box: saorsa_tui::app::NodeRef,
             input_text: saorsa_tui::app::NodeRef,
         }
@@ -70,6 +80,13 @@ impl SaorsaUi {
             dom.set_css_id(messages, "messages");
             dom.set_focusable(messages, true);
 
+            // Command palette, hidden until opened (Ctrl+O).
+            let palette = dom.create(
+                "CommandPalette",
+                Box::new(StyledInteractive::new(CommandPalette::new(Vec::new()))),
+            );
+            dom.set_css_id(palette, "palette");
+
             // Input box + input text label.
             let input_box = dom.create(
                 "Container",
@@ -89,6 +106,7 @@ impl SaorsaUi {
                 root,
                 header,
                 messages,
+                palette,
                 input_box,
                 input_text,
             }
@@ -103,13 +121,14 @@ impl SaorsaUi {
             root,
             header,
             messages,
+            palette,
             input_box,
             input_text,
         } = build_dom();
 
-        let (mut app, root, header, messages, input_box, input_text) =
+        let (mut app, root, header, messages, palette, input_box, input_text) =
             match App::from_tcss_file(terminal, dom, &tcss_path) {
-                Ok(app) => (app, root, header, messages, input_box, input_text),
+                Ok(app) => (app, root, header, messages, palette, input_box, input_text),
                 Err(e) => {
                     // Fall back to embedded string if file watch is unavailable.
                     let BuiltDom {
@@ -117,6 +136,7 @@ impl SaorsaUi {
                         root,
                         header,
                         messages,
+                        palette,
                         input_box,
                         input_text,
                     } = build_dom();
@@ -126,7 +146,7 @@ impl SaorsaUi {
                             "Failed to initialize saorsa-tui App runtime (file={:?}, err={e})",
                             tcss_path
                         ))?;
-                    (app, root, header, messages, input_box, input_text)
+                    (app, root, header, messages, palette, input_box, input_text)
                 }
             };
 
@@ -135,6 +155,8 @@ impl SaorsaUi {
             .map_err(|e| anyhow::anyhow!("{e}"))?;
         app.mount(root, messages)
             .map_err(|e| anyhow::anyhow!("{e}"))?;
+        app.mount(root, palette)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
         app.mount(root, input_box)
             .map_err(|e| anyhow::anyhow!("{e}"))?;
         app.mount(input_box, input_text)
@@ -148,15 +170,25 @@ impl SaorsaUi {
                 Ok(EventResult::Consumed)
             }),
         );
+        app.describe_action("force_render", "Redraw the whole screen");
         app.bind_key(
             KeyEvent::new(KeyCode::Char('r'), Modifiers::CTRL),
             "force_render",
         );
 
+        // The input loop runs the keyboard shortcuts; registering them lets
+        // the command palette list them with their keys.
+        for shortcut in input::shortcuts() {
+            app.register_action(shortcut.name, Box::new(|_| Ok(EventResult::Ignored)));
+            app.describe_action(shortcut.name, shortcut.description);
+            app.bind_key(shortcut.key, shortcut.name);
+        }
+
         Ok(Self {
             app,
             header,
             messages,
+            palette,
             input_box,
             input_text,
             last_sig: UiSignature::default(),
@@ -177,6 +209,82 @@ impl SaorsaUi {
         Ok(())
     }
 
+    /// Remove any mouse selection highlight.
+    pub fn clear_selection(&mut self) {
+        self.app.clear_selection();
+    }
+
+    /// Open the command palette listing `entries` followed by the actions
+    /// registered with the runtime.
+    pub fn open_palette(&mut self, mut entries: Vec<PaletteEntry>) {
+        entries.extend(self.app.actions().iter().map(|action| PaletteEntry {
+            id: format!("{ACTION}{}", action.name),
+            ..PaletteEntry::from(action)
+        }));
+        if let Some(palette) = self
+            .app
+            .dom_mut()
+            .downcast_widget_mut::<CommandPalette>(self.palette)
+        {
+            palette.set_entries(entries);
+            palette.open();
+        }
+        self.set_palette_visible(true);
+    }
+
+    /// Whether the command palette is open.
+    pub fn is_palette_open(&self) -> bool {
+        self.app.dom().has_class(self.palette, "open")
+    }
+
+    /// Give an event to the open command palette.
+    ///
+    /// Returns the action for the entry the user executed, if any.
+    /// Runtime actions other than keyboard shortcuts are run here and
+    /// not returned.
+    pub fn handle_palette_event(&mut self, event: &Event) -> anyhow::Result<Option<InputAction>> {
+        let Some(palette) = self
+            .app
+            .dom_mut()
+            .downcast_widget_mut::<CommandPalette>(self.palette)
+        else {
+            return Ok(None);
+        };
+        palette.dispatch_event(event);
+        let executed = palette.take_executed();
+        let open = palette.is_open();
+        self.app.mark_node_dirty(self.palette);
+        if !open {
+            self.set_palette_visible(false);
+        }
+        let Some(entry) = executed else {
+            return Ok(None);
+        };
+        let Some(name) = entry.id.strip_prefix(ACTION) else {
+            return Ok(Some(palette::action_for(&entry.id)));
+        };
+        if let Some(shortcut) = input::shortcuts().into_iter().find(|s| s.name == name) {
+            return Ok(Some(shortcut.action));
+        }
+        self.app
+            .run_action(name)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok(None)
+    }
+
+    /// Show or hide the palette node (`#palette.open` in the stylesheet).
+    fn set_palette_visible(&mut self, visible: bool) {
+        if visible == self.is_palette_open() {
+            return;
+        }
+        if visible {
+            self.app.dom_mut().add_class(self.palette, "open");
+        } else {
+            self.app.dom_mut().remove_class(self.palette, "open");
+        }
+        self.app.mark_style_dirty(self.palette);
+    }
+
     /// Poll for stylesheet reload events (if the runtime was created from a file).
     pub fn poll_stylesheet_reload(&mut self) -> anyhow::Result<()> {
         let _ = self
@@ -251,14 +359,21 @@ impl SaorsaUi {
                         ("  ", Style::default().fg(Color::Named(NamedColor::Cyan)))
                     }
                     ChatRole::Tool { name } => {
+                        // Tool output keeps its ANSI colours over the tool
+                        // style; later lines are indented under the name.
                         let prefix = format!("  [{name}] ");
+                        let indent = " ".repeat(prefix.chars().count());
                         let style = Style::default()
                             .fg(Color::Named(NamedColor::Yellow))
                             .dim(true);
-                        log.push(vec![
-                            Segment::styled(prefix, style.clone()),
-                            Segment::styled(msg.content.clone(), style),
-                        ]);
+                        for (i, line) in parse_ansi(&msg.content).into_iter().enumerate() {
+                            let lead = if i == 0 { &prefix } else { &indent };
+                            let mut entry = vec![Segment::styled(lead.clone(), style.clone())];
+                            entry.extend(line.into_iter().map(|segment| {
+                                Segment::styled(segment.text, style.merge(&segment.style))
+                            }));
+                            log.push(entry);
+                        }
                         continue;
                     }
                     ChatRole::System => (
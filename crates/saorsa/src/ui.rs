@@ -89,7 +89,7 @@ fn render_messages(state: &AppState, buf: &mut ScreenBuffer, area: Rect) {
                     .fg(Color::Named(saorsa_tui::color::NamedColor::Yellow))
                     .dim(true);
                 // Render tool prefix inline.
-                let label = Label::new(format!("{prefix_str}{}", msg.content)).style(style);
+                let label = Label::from_ansi(&format!("{prefix_str}{}", msg.content)).style(style);
                 label.render(row_area, buf);
                 continue;
             }
@@ -136,13 +136,15 @@ fn render_messages(state: &AppState, buf: &mut ScreenBuffer, area: Rect) {
 
 /// Render the input area with a border.
 fn render_input(state: &AppState, buf: &mut ScreenBuffer, area: Rect) {
-    let container = Container::new()
-        .border(BorderStyle::Rounded)
-        .title(if state.is_idle() {
-            "Type a message"
-        } else {
-            "Waiting..."
-        });
+    let title = if !state.is_idle() {
+        "Waiting...".to_string()
+    } else {
+        match state.keymap.status().as_str() {
+            "" => "Type a message".to_string(),
+            status => format!("Type a message [{status}]"),
+        }
+    };
+    let container = Container::new().border(BorderStyle::Rounded).title(title);
     container.render(area, buf);
 
     // Render the input text inside the container.
@@ -312,6 +314,26 @@ mod tests {
         // Should not panic even with minimal space.
     }
 
+    #[test]
+    fn render_tool_output_with_ansi_colours() {
+        let mut state = AppState::new("test-model");
+        state.add_tool_message("bash", "\x1b[31mfailed\x1b[0m");
+        let mut buf = ScreenBuffer::new(Size::new(40, 10));
+        render(&state, &mut buf);
+        let row =
+            (0..buf.height()).find(|&y| buf.get(2, y).map(|c| c.grapheme.as_str()) == Some("["));
+        let Some(y) = row else {
+            unreachable!("tool message should be rendered");
+        };
+        // "  [bash] " is nine columns wide.
+        assert_eq!(buf.get(9, y).map(|c| c.grapheme.as_str()), Some("f"));
+        assert_eq!(
+            buf.get(9, y).and_then(|c| c.style.fg.clone()),
+            Some(Color::Named(saorsa_tui::color::NamedColor::Red))
+        );
+        assert_eq!(buf.get(15, y).map(|c| c.grapheme.as_str()), Some(" "));
+    }
+
     #[test]
     fn render_many_messages() {
         let mut state = AppState::new("test-model");
@@ -151,8 +151,7 @@ impl Widget for MessageQueue {
         );
 
         // Render messages
-        let mut y = area.position.y + 2;
-        for (idx, msg) in self.messages.iter().enumerate() {
+        for (y, (idx, msg)) in (area.position.y + 2..).zip(self.messages.iter().enumerate()) {
             if y >= area.position.y + area.size.height {
                 break;
             }
@@ -174,8 +173,6 @@ impl Widget for MessageQueue {
                 msg.text.clone()
             };
             self.write_text(buffer, text_start, y, &truncated, style);
-
-            y += 1;
         }
 
         // Render help text
@@ -202,14 +199,17 @@ impl InteractiveWidget for MessageQueue {
             Event::Key(KeyEvent {
                 code: KeyCode::Char('q'),
                 modifiers: Modifiers::NONE,
+                ..
             })
             | Event::Key(KeyEvent {
                 code: KeyCode::Char('Q'),
                 modifiers: Modifiers::SHIFT,
+                ..
             })
             | Event::Key(KeyEvent {
                 code: KeyCode::Escape,
                 modifiers: Modifiers::NONE,
+                ..
             }) => {
                 self.hide();
                 EventResult::Consumed
@@ -217,10 +217,12 @@ impl InteractiveWidget for MessageQueue {
             Event::Key(KeyEvent {
                 code: KeyCode::Char('d'),
                 modifiers: Modifiers::NONE,
+                ..
             })
             | Event::Key(KeyEvent {
                 code: KeyCode::Char('D'),
                 modifiers: Modifiers::SHIFT,
+                ..
             }) => {
                 self.remove_selected();
                 EventResult::Consumed
@@ -228,10 +230,12 @@ impl InteractiveWidget for MessageQueue {
             Event::Key(KeyEvent {
                 code: KeyCode::Char('x'),
                 modifiers: Modifiers::NONE,
+                ..
             })
             | Event::Key(KeyEvent {
                 code: KeyCode::Char('X'),
                 modifiers: Modifiers::SHIFT,
+                ..
             }) => {
                 self.clear();
                 EventResult::Consumed
@@ -239,6 +243,7 @@ impl InteractiveWidget for MessageQueue {
             Event::Key(KeyEvent {
                 code: KeyCode::Up,
                 modifiers: Modifiers::NONE,
+                ..
             }) => {
                 self.move_up();
                 EventResult::Consumed
@@ -246,6 +251,7 @@ impl InteractiveWidget for MessageQueue {
             Event::Key(KeyEvent {
                 code: KeyCode::Down,
                 modifiers: Modifiers::NONE,
+                ..
             }) => {
                 self.move_down();
                 EventResult::Consumed
@@ -253,6 +259,7 @@ impl InteractiveWidget for MessageQueue {
             Event::Key(KeyEvent {
                 code: KeyCode::Enter,
                 modifiers: Modifiers::NONE,
+                ..
             }) => {
                 // Send all - handled by application
                 EventResult::Consumed
@@ -343,10 +350,7 @@ mod tests {
         queue.show();
         queue.add_message("Test".to_string());
 
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Char('d'),
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('d'), Modifiers::NONE));
         let result = queue.handle_event(&event);
         assert!(matches!(result, EventResult::Consumed));
         assert!(queue.is_empty());
@@ -359,10 +363,7 @@ mod tests {
         queue.add_message("One".to_string());
         queue.add_message("Two".to_string());
 
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Char('x'),
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('x'), Modifiers::NONE));
         let result = queue.handle_event(&event);
         assert!(matches!(result, EventResult::Consumed));
         assert!(queue.is_empty());
@@ -373,10 +374,7 @@ mod tests {
         let mut queue = MessageQueue::new();
         queue.show();
 
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Escape,
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Escape, Modifiers::NONE));
         let result = queue.handle_event(&event);
         assert!(matches!(result, EventResult::Consumed));
         assert!(!queue.is_visible());
@@ -385,10 +383,7 @@ mod tests {
     #[test]
     fn handle_event_when_hidden() {
         let mut queue = MessageQueue::new();
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Enter,
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Enter, Modifiers::NONE));
         let result = queue.handle_event(&event);
         assert!(matches!(result, EventResult::Ignored));
     }
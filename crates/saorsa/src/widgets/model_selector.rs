@@ -315,6 +315,7 @@ impl InteractiveWidget for ModelSelector {
             Event::Key(KeyEvent {
                 code: KeyCode::Char('f'),
                 modifiers: Modifiers::NONE,
+                ..
             }) => {
                 // Toggle favorite for current selection
                 let model_name = if let Some(list) = &self.select_list {
@@ -331,6 +332,7 @@ impl InteractiveWidget for ModelSelector {
             Event::Key(KeyEvent {
                 code: KeyCode::Char('l'),
                 modifiers: Modifiers::CTRL,
+                ..
             }) => {
                 // Cycle favorites
                 if let Some(next) = self.cycle_favorites() {
@@ -342,6 +344,7 @@ impl InteractiveWidget for ModelSelector {
             Event::Key(KeyEvent {
                 code: KeyCode::Escape,
                 modifiers: Modifiers::NONE,
+                ..
             }) => {
                 self.hide();
                 EventResult::Consumed
@@ -349,6 +352,7 @@ impl InteractiveWidget for ModelSelector {
             Event::Key(KeyEvent {
                 code: KeyCode::Enter,
                 modifiers: Modifiers::NONE,
+                ..
             }) => {
                 // Confirm selection
                 if let Some(list) = &self.select_list
@@ -508,10 +512,7 @@ mod tests {
         selector.show();
         assert!(selector.is_visible());
 
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Escape,
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Escape, Modifiers::NONE));
         let result = selector.handle_event(&event);
         assert!(matches!(result, EventResult::Consumed));
         assert!(!selector.is_visible());
@@ -520,10 +521,7 @@ mod tests {
     #[test]
     fn handle_event_when_hidden() {
         let mut selector = ModelSelector::new(sample_models());
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Enter,
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Enter, Modifiers::NONE));
         let result = selector.handle_event(&event);
         assert!(matches!(result, EventResult::Ignored));
     }
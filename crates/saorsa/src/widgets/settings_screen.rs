@@ -388,10 +388,12 @@ impl InteractiveWidget for SettingsScreen {
             Event::Key(KeyEvent {
                 code: KeyCode::Char('s'),
                 modifiers: Modifiers::NONE,
+                ..
             })
             | Event::Key(KeyEvent {
                 code: KeyCode::Char('S'),
                 modifiers: Modifiers::SHIFT,
+                ..
             }) => {
                 self.save();
                 EventResult::Consumed
@@ -399,10 +401,12 @@ impl InteractiveWidget for SettingsScreen {
             Event::Key(KeyEvent {
                 code: KeyCode::Char('c'),
                 modifiers: Modifiers::NONE,
+                ..
             })
             | Event::Key(KeyEvent {
                 code: KeyCode::Char('C'),
                 modifiers: Modifiers::SHIFT,
+                ..
             }) => {
                 self.cancel();
                 EventResult::Consumed
@@ -410,6 +414,7 @@ impl InteractiveWidget for SettingsScreen {
             Event::Key(KeyEvent {
                 code: KeyCode::Escape,
                 modifiers: Modifiers::NONE,
+                ..
             }) => {
                 self.cancel();
                 EventResult::Consumed
@@ -417,6 +422,7 @@ impl InteractiveWidget for SettingsScreen {
             Event::Key(KeyEvent {
                 code: KeyCode::Tab,
                 modifiers: Modifiers::NONE,
+                ..
             }) => {
                 // Cycle tabs
                 let next = match self.current_tab {
@@ -431,6 +437,7 @@ impl InteractiveWidget for SettingsScreen {
             Event::Key(KeyEvent {
                 code: KeyCode::Tab,
                 modifiers: Modifiers::SHIFT,
+                ..
             }) => {
                 // Cycle tabs backwards (Shift+Tab)
                 let prev = match self.current_tab {
@@ -566,10 +573,7 @@ mod tests {
         let mut screen = SettingsScreen::new();
         screen.show();
 
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Tab,
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Tab, Modifiers::NONE));
 
         assert_eq!(screen.current_tab, SettingsTab::General);
         screen.handle_event(&event);
@@ -588,10 +592,7 @@ mod tests {
         screen.show();
         screen.settings_mut().thinking_mode = true;
 
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Char('s'),
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('s'), Modifiers::NONE));
         let result = screen.handle_event(&event);
 
         assert!(matches!(result, EventResult::Consumed));
@@ -605,10 +606,7 @@ mod tests {
         screen.show();
         screen.settings_mut().thinking_mode = true;
 
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Char('c'),
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('c'), Modifiers::NONE));
         let result = screen.handle_event(&event);
 
         assert!(matches!(result, EventResult::Consumed));
@@ -622,10 +620,7 @@ mod tests {
         let mut screen = SettingsScreen::new();
         screen.show();
 
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Escape,
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Escape, Modifiers::NONE));
         let result = screen.handle_event(&event);
 
         assert!(matches!(result, EventResult::Consumed));
@@ -635,10 +630,7 @@ mod tests {
     #[test]
     fn handle_event_when_hidden() {
         let mut screen = SettingsScreen::new();
-        let event = Event::Key(KeyEvent {
-            code: KeyCode::Char('s'),
-            modifiers: Modifiers::NONE,
-        });
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('s'), Modifiers::NONE));
         let result = screen.handle_event(&event);
         assert!(matches!(result, EventResult::Ignored));
     }
@@ -1,8 +1,9 @@
 #![allow(missing_docs, clippy::expect_used)]
 
 use saorsa::app::AppState;
+use saorsa::input::InputAction;
 use saorsa::tui_runtime::SaorsaUi;
-use saorsa_tui::event::{Event, KeyCode, KeyEvent, Modifiers};
+use saorsa_tui::event::{Event, KeyCode, KeyEvent, KeyEventKind, Modifiers};
 use saorsa_tui::terminal::TestBackend;
 
 #[test]
@@ -38,3 +39,90 @@ fn ctrl_r_forces_render() {
         .expect("render_if_needed should succeed");
     assert!(!rendered);
 }
+
+#[test]
+fn command_palette_opens_filters_and_executes() {
+    let mut backend = TestBackend::new(60, 20);
+    let mut ui = SaorsaUi::new(&backend).expect("SaorsaUi::new should succeed");
+    let state = AppState::new("test-model");
+    ui.sync_from_state(&state)
+        .expect("sync_from_state should succeed");
+    ui.render_frame(&mut backend)
+        .expect("initial render should succeed");
+    assert!(!ui.is_palette_open());
+
+    let autocomplete = saorsa::autocomplete::Autocomplete::new();
+    ui.open_palette(saorsa::palette::entries(&state, &autocomplete));
+    assert!(ui.is_palette_open());
+    backend.clear_buffer();
+    ui.render_frame(&mut backend)
+        .expect("palette render should succeed");
+    let screen = String::from_utf8_lossy(backend.buffer()).into_owned();
+    assert!(screen.contains("Type a command"), "screen was {screen:?}");
+
+    let key = |code| Event::Key(KeyEvent::plain(code));
+    for c in "clear".chars() {
+        let executed = ui
+            .handle_palette_event(&key(KeyCode::Char(c)))
+            .expect("palette event should succeed");
+        assert_eq!(executed, None);
+    }
+    let executed = ui
+        .handle_palette_event(&key(KeyCode::Enter))
+        .expect("palette event should succeed");
+    assert_eq!(executed, Some(InputAction::Submit("/clear".into())));
+    assert!(!ui.is_palette_open());
+
+    // Keyboard shortcuts are listed with their bound keys and return their
+    // input action.
+    ui.open_palette(Vec::new());
+    for c in "previous".chars() {
+        ui.handle_palette_event(&key(KeyCode::Char(c)))
+            .expect("palette event should succeed");
+    }
+    backend.clear_buffer();
+    ui.render_frame(&mut backend)
+        .expect("palette render should succeed");
+    let screen = String::from_utf8_lossy(backend.buffer()).into_owned();
+    assert!(screen.contains("Ctrl+Shift+P"), "screen was {screen:?}");
+    let executed = ui
+        .handle_palette_event(&key(KeyCode::Enter))
+        .expect("palette event should succeed");
+    assert_eq!(executed, Some(InputAction::CycleModelBackward));
+
+    // Runtime actions are listed with their descriptions and run directly.
+    ui.open_palette(Vec::new());
+    for c in "force".chars() {
+        ui.handle_palette_event(&key(KeyCode::Char(c)))
+            .expect("palette event should succeed");
+    }
+    backend.clear_buffer();
+    ui.render_frame(&mut backend)
+        .expect("palette render should succeed");
+    let screen = String::from_utf8_lossy(backend.buffer()).into_owned();
+    assert!(
+        screen.contains("Redraw the whole screen"),
+        "screen was {screen:?}"
+    );
+    let executed = ui
+        .handle_palette_event(&key(KeyCode::Enter))
+        .expect("palette event should succeed");
+    assert_eq!(executed, None);
+    assert!(!ui.is_palette_open());
+}
+
+#[test]
+fn command_palette_ignores_key_releases() {
+    let backend = TestBackend::new(60, 20);
+    let mut ui = SaorsaUi::new(&backend).expect("SaorsaUi::new should succeed");
+    ui.open_palette(Vec::new());
+
+    let release = KeyEvent::plain(KeyCode::Escape).with_kind(KeyEventKind::Release);
+    ui.handle_palette_event(&Event::Key(release))
+        .expect("palette event should succeed");
+    assert!(ui.is_palette_open());
+
+    ui.handle_palette_event(&Event::Key(KeyEvent::plain(KeyCode::Escape)))
+        .expect("palette event should succeed");
+    assert!(!ui.is_palette_open());
+}